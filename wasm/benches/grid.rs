@@ -0,0 +1,62 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use prisoners_dilemma_2d::domain::agent::Position;
+use prisoners_dilemma_2d::domain::grid::{Grid, GridService};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+// 半分のセルが埋まった size × size のグリッド
+fn half_filled_grid(size: usize) -> Grid {
+    let mut grid = Grid::new(size, size);
+    let mut rng = ChaCha12Rng::seed_from_u64(1);
+    GridService::initialize_random_agents(&mut grid, size * size / 2, &mut rng).unwrap();
+    grid
+}
+
+fn bench_grid_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid");
+    for size in [100, 300] {
+        let grid = half_filled_grid(size);
+        let positions: Vec<Position> = grid.agents().values().map(|a| a.position).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("get_neighbors_all", size),
+            &grid,
+            |b, grid| {
+                b.iter(|| {
+                    positions
+                        .iter()
+                        .map(|position| grid.get_neighbors(position).len())
+                        .sum::<usize>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_agent_at_position", size),
+            &grid,
+            |b, grid| {
+                b.iter(|| {
+                    positions
+                        .iter()
+                        .filter(|position| {
+                            grid.get_agent_at_position(black_box(position)).is_some()
+                        })
+                        .count()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_empty_positions", size),
+            &grid,
+            |b, grid| b.iter(|| grid.get_empty_positions().len()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("agents_in_position_order", size),
+            &grid,
+            |b, grid| b.iter(|| grid.agents_in_position_order().len()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_grid_queries);
+criterion_main!(benches);
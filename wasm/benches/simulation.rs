@@ -0,0 +1,109 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use prisoners_dilemma_2d::application::evolution::EvolutionService;
+use prisoners_dilemma_2d::application::simulation::{SimulationConfig, SimulationService};
+use prisoners_dilemma_2d::domain::agent::Agent;
+use prisoners_dilemma_2d::infrastructure::persistence::{
+    ExportOptions, PersistenceFormat, PersistenceService, SerializationService,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 約半分のセルが埋まるグリッドに agent_count 体を置いたシミュレーション
+fn half_filled_simulation(agent_count: usize) -> SimulationService {
+    let size = ((agent_count * 2) as f64).sqrt().ceil() as usize;
+    let config = SimulationConfig::default().with_seed(1);
+    SimulationService::with_config(size, size, agent_count, config).unwrap()
+}
+
+// 1 ステップ対戦を済ませて、スコアと履歴が入った個体
+fn played_agents(agent_count: usize) -> HashMap<Uuid, Agent> {
+    let mut simulation = half_filled_simulation(agent_count);
+    simulation.execute_battles();
+    simulation
+        .get_agents()
+        .into_iter()
+        .map(|agent| (agent.id, agent))
+        .collect()
+}
+
+fn bench_battles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("battles");
+    group.sample_size(10);
+    for agent_count in [10_000, 100_000] {
+        let mut simulation = half_filled_simulation(agent_count);
+        group.throughput(Throughput::Elements(agent_count as u64));
+        group.bench_function(BenchmarkId::new("execute_battles", agent_count), |b| {
+            b.iter(|| simulation.execute_battles())
+        });
+    }
+    group.finish();
+}
+
+fn bench_evolution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evolution");
+    group.sample_size(10);
+    let evolution_service = EvolutionService::new();
+    let config = SimulationConfig::default();
+    for agent_count in [10_000, 100_000] {
+        let agents = played_agents(agent_count);
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+        group.throughput(Throughput::Elements(agent_count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("evolve_generation", agent_count),
+            &agents,
+            |b, agents| {
+                b.iter(|| {
+                    evolution_service
+                        .evolve_with_config(agents, &config, &mut rng)
+                        .len()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+    group.sample_size(10);
+    let agent_count = 100_000;
+    let agents: Vec<Agent> = played_agents(agent_count).into_values().collect();
+    let options = ExportOptions::default();
+    let binary = SerializationService::agents_to_binary(&agents).unwrap();
+    group.throughput(Throughput::Elements(agent_count as u64));
+
+    group.bench_function(BenchmarkId::new("agents_to_csv", agent_count), |b| {
+        b.iter(|| SerializationService::agents_to_csv(&agents, &options).len())
+    });
+    group.bench_function(BenchmarkId::new("agents_to_json", agent_count), |b| {
+        b.iter(|| {
+            PersistenceService::export_agents(&agents, PersistenceFormat::Json)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function(BenchmarkId::new("agents_to_binary", agent_count), |b| {
+        b.iter(|| {
+            SerializationService::agents_to_binary(&agents)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function(BenchmarkId::new("agents_from_binary", agent_count), |b| {
+        b.iter_batched(
+            || binary.clone(),
+            |bytes| {
+                SerializationService::agents_from_binary(&bytes)
+                    .unwrap()
+                    .len()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_battles, bench_evolution, bench_serialization);
+criterion_main!(benches);
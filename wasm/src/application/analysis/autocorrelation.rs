@@ -0,0 +1,69 @@
+use crate::application::simulation::SimulationStatistics;
+
+// 世代履歴から平均協力率の時系列を取り出す
+pub fn cooperation_series(generation_history: &[SimulationStatistics]) -> Vec<f64> {
+    generation_history
+        .iter()
+        .map(|stats| stats.average_cooperation_rate)
+        .collect()
+}
+
+// ラグ 0..=max_lag の標本自己相関（ラグ 0 は 1.0、分散 0 の系列は全て 0.0）
+pub fn cooperation_autocorrelation(series: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = series.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let variance: f64 = series.iter().map(|x| (x - mean).powi(2)).sum();
+
+    (0..=max_lag.min(n - 1))
+        .map(|lag| {
+            if variance == 0.0 {
+                return 0.0;
+            }
+            let covariance: f64 = series
+                .iter()
+                .zip(&series[lag..])
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum();
+            covariance / variance
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_sinusoid_autocorrelation_shows_period() {
+        // Arrange: 周期 10 の正弦波
+        let period = 10;
+        let series: Vec<f64> = (0..200)
+            .map(|t| 0.5 + 0.3 * (2.0 * PI * t as f64 / period as f64).sin())
+            .collect();
+
+        // Act
+        let autocorrelation = cooperation_autocorrelation(&series, 20);
+
+        // Assert: 半周期で負のピーク、1 周期で正のピーク
+        assert_eq!(autocorrelation.len(), 21);
+        assert!((autocorrelation[0] - 1.0).abs() < 1e-12);
+        assert!(autocorrelation[period / 2] < -0.9);
+        assert!(autocorrelation[period] > 0.9);
+        assert!(autocorrelation[period] > autocorrelation[period - 1]);
+        assert!(autocorrelation[period] > autocorrelation[period + 1]);
+    }
+
+    #[test]
+    fn test_constant_series_has_zero_autocorrelation() {
+        // Arrange & Act
+        let autocorrelation = cooperation_autocorrelation(&[0.4; 5], 10);
+
+        // Assert: ラグは系列長 - 1 で打ち切られる
+        assert_eq!(autocorrelation, vec![0.0; 5]);
+    }
+}
@@ -0,0 +1,520 @@
+use super::Summary;
+use crate::application::simulation::{SimulationResult, SimulationStatistics};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// 比べる指標
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMetric {
+    Cooperation,
+    // 設定の適応度の平均（SimulationStatistics::average_fitness）
+    Fitness,
+    // 戦略分布の正規化シャノンエントロピー
+    Diversity,
+}
+
+impl ComparisonMetric {
+    pub const ALL: [ComparisonMetric; 3] = [
+        ComparisonMetric::Cooperation,
+        ComparisonMetric::Fitness,
+        ComparisonMetric::Diversity,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComparisonMetric::Cooperation => "cooperation",
+            ComparisonMetric::Fitness => "fitness",
+            ComparisonMetric::Diversity => "diversity",
+        }
+    }
+
+    pub fn value(&self, stats: &SimulationStatistics) -> f64 {
+        match self {
+            ComparisonMetric::Cooperation => stats.average_cooperation_rate,
+            ComparisonMetric::Fitness => stats.average_fitness,
+            ComparisonMetric::Diversity => stats.strategy_diversity(),
+        }
+    }
+}
+
+// ある世代の 1 指標の値と差（candidate - baseline）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub candidate: f64,
+    pub delta: f64,
+}
+
+impl MetricDelta {
+    fn new(baseline: f64, candidate: f64) -> Self {
+        Self {
+            baseline,
+            candidate,
+            delta: candidate - baseline,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationDelta {
+    pub generation: u32,
+    pub cooperation: MetricDelta,
+    pub fitness: MetricDelta,
+    pub diversity: MetricDelta,
+}
+
+impl GenerationDelta {
+    pub fn metric(&self, metric: ComparisonMetric) -> &MetricDelta {
+        match metric {
+            ComparisonMetric::Cooperation => &self.cooperation,
+            ComparisonMetric::Fitness => &self.fitness,
+            ComparisonMetric::Diversity => &self.diversity,
+        }
+    }
+}
+
+// 両側検定の結果。Welch の t 検定は t 分布、Mann-Whitney の U 検定は同順位と連続性を補正した正規近似
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignificanceTests {
+    pub welch_t: f64,
+    pub welch_df: f64,
+    pub welch_p_value: f64,
+    // candidate の U（candidate が baseline を上回る組の数、同値は 0.5）
+    pub mann_whitney_u: f64,
+    pub mann_whitney_p_value: f64,
+}
+
+// 最終分布での 1 指標の比較
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric: ComparisonMetric,
+    pub baseline: Summary,
+    pub candidate: Summary,
+    pub mean_difference: f64,
+    // 平均の差をプールした標準偏差で割った値。両方ともばらつきがなければ 0
+    pub cohens_d: f64,
+    // P(candidate > baseline) - P(candidate < baseline)（-1〜1）
+    pub cliffs_delta: f64,
+    // 検定を有効にし、両方の標本が 2 件以上あるときだけ
+    pub tests: Option<SignificanceTests>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultComparison {
+    // 最終分布に使った末尾の世代数
+    pub window: usize,
+    // 両方の履歴にある世代だけを世代番号順に並べる
+    pub generations: Vec<GenerationDelta>,
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl ResultComparison {
+    pub fn metric(&self, metric: ComparisonMetric) -> Option<&MetricComparison> {
+        self.metrics
+            .iter()
+            .find(|comparison| comparison.metric == metric)
+    }
+}
+
+// 2 つの実行結果（baseline と candidate）を世代番号でそろえて比べる。
+// 最終分布は各履歴の末尾 window 世代の値。世代間の値は自己相関があるので p 値は目安として使う
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultComparisonService {
+    window: usize,
+    significance_tests: bool,
+}
+
+impl Default for ResultComparisonService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultComparisonService {
+    pub const DEFAULT_WINDOW: usize = 10;
+
+    pub fn new() -> Self {
+        Self {
+            window: Self::DEFAULT_WINDOW,
+            significance_tests: false,
+        }
+    }
+
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    pub fn with_significance_tests(mut self, enabled: bool) -> Self {
+        self.significance_tests = enabled;
+        self
+    }
+
+    pub fn compare(
+        &self,
+        baseline: &SimulationResult,
+        candidate: &SimulationResult,
+    ) -> ResultComparison {
+        let baseline_by_generation: BTreeMap<u32, &SimulationStatistics> = baseline
+            .generation_history
+            .iter()
+            .map(|stats| (stats.generation, stats))
+            .collect();
+        let candidate_by_generation: BTreeMap<u32, &SimulationStatistics> = candidate
+            .generation_history
+            .iter()
+            .map(|stats| (stats.generation, stats))
+            .collect();
+
+        let generations = baseline_by_generation
+            .iter()
+            .filter_map(|(generation, before)| {
+                let after = candidate_by_generation.get(generation)?;
+                let delta = |metric: ComparisonMetric| {
+                    MetricDelta::new(metric.value(before), metric.value(after))
+                };
+                Some(GenerationDelta {
+                    generation: *generation,
+                    cooperation: delta(ComparisonMetric::Cooperation),
+                    fitness: delta(ComparisonMetric::Fitness),
+                    diversity: delta(ComparisonMetric::Diversity),
+                })
+            })
+            .collect();
+
+        let metrics = ComparisonMetric::ALL
+            .iter()
+            .map(|metric| {
+                self.compare_metric(
+                    *metric,
+                    &self.final_values(baseline, *metric),
+                    &self.final_values(candidate, *metric),
+                )
+            })
+            .collect();
+
+        ResultComparison {
+            window: self.window,
+            generations,
+            metrics,
+        }
+    }
+
+    fn final_values(&self, result: &SimulationResult, metric: ComparisonMetric) -> Vec<f64> {
+        let history = &result.generation_history;
+        history[history.len().saturating_sub(self.window)..]
+            .iter()
+            .map(|stats| metric.value(stats))
+            .collect()
+    }
+
+    fn compare_metric(
+        &self,
+        metric: ComparisonMetric,
+        baseline: &[f64],
+        candidate: &[f64],
+    ) -> MetricComparison {
+        let before = Summary::of(baseline);
+        let after = Summary::of(candidate);
+        let mean_difference = after.mean - before.mean;
+
+        let pooled_df = (before.count + after.count).saturating_sub(2);
+        let pooled_std_dev = if pooled_df == 0 {
+            0.0
+        } else {
+            (((before.count.saturating_sub(1)) as f64 * before.std_dev.powi(2)
+                + (after.count.saturating_sub(1)) as f64 * after.std_dev.powi(2))
+                / pooled_df as f64)
+                .sqrt()
+        };
+        let cohens_d = if pooled_std_dev > 0.0 {
+            mean_difference / pooled_std_dev
+        } else {
+            0.0
+        };
+
+        let u = mann_whitney_u(baseline, candidate);
+        let pairs = (baseline.len() * candidate.len()) as f64;
+        let cliffs_delta = if pairs > 0.0 {
+            2.0 * u / pairs - 1.0
+        } else {
+            0.0
+        };
+
+        let tests =
+            (self.significance_tests && baseline.len() >= 2 && candidate.len() >= 2).then(|| {
+                let (welch_t, welch_df, welch_p_value) = welch_t_test(&before, &after);
+                SignificanceTests {
+                    welch_t,
+                    welch_df,
+                    welch_p_value,
+                    mann_whitney_u: u,
+                    mann_whitney_p_value: mann_whitney_p_value(baseline, candidate, u),
+                }
+            });
+
+        MetricComparison {
+            metric,
+            baseline: before,
+            candidate: after,
+            mean_difference,
+            cohens_d,
+            cliffs_delta,
+            tests,
+        }
+    }
+}
+
+// (t, 自由度, 両側 p 値)。両方ともばらつきがなければ平均が同じかどうかだけで決める
+fn welch_t_test(baseline: &Summary, candidate: &Summary) -> (f64, f64, f64) {
+    let baseline_var = baseline.std_dev.powi(2) / baseline.count as f64;
+    let candidate_var = candidate.std_dev.powi(2) / candidate.count as f64;
+    let difference = candidate.mean - baseline.mean;
+    let standard_error = (baseline_var + candidate_var).sqrt();
+    if standard_error == 0.0 {
+        let df = (baseline.count + candidate.count - 2) as f64;
+        return if difference == 0.0 {
+            (0.0, df, 1.0)
+        } else {
+            (f64::INFINITY.copysign(difference), df, 0.0)
+        };
+    }
+
+    let t = difference / standard_error;
+    let df = (baseline_var + candidate_var).powi(2)
+        / (baseline_var.powi(2) / (baseline.count - 1) as f64
+            + candidate_var.powi(2) / (candidate.count - 1) as f64);
+    let p = regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t));
+    (t, df, p.clamp(0.0, 1.0))
+}
+
+// candidate の U。両方を合わせた順位（同順位は平均）から求める
+fn mann_whitney_u(baseline: &[f64], candidate: &[f64]) -> f64 {
+    let ranks = average_ranks(baseline, candidate);
+    let candidate_rank_sum: f64 = ranks[baseline.len()..].iter().sum();
+    let n = candidate.len() as f64;
+    candidate_rank_sum - n * (n + 1.0) / 2.0
+}
+
+fn mann_whitney_p_value(baseline: &[f64], candidate: &[f64], u: f64) -> f64 {
+    let n1 = baseline.len() as f64;
+    let n2 = candidate.len() as f64;
+    let n = n1 + n2;
+
+    let mut values: Vec<f64> = baseline.iter().chain(candidate).copied().collect();
+    values.sort_by(f64::total_cmp);
+    let tie_correction: f64 = values
+        .chunk_by(|a, b| a == b)
+        .map(|ties| {
+            let t = ties.len() as f64;
+            t * t * t - t
+        })
+        .sum();
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return 1.0;
+    }
+
+    let z = ((u - n1 * n2 / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+    erfc(z / std::f64::consts::SQRT_2).clamp(0.0, 1.0)
+}
+
+// baseline, candidate の順に連結した値の順位（1 始まり、同順位は平均）
+fn average_ranks(baseline: &[f64], candidate: &[f64]) -> Vec<f64> {
+    let values: Vec<f64> = baseline.iter().chain(candidate).copied().collect();
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for index in &order[start..end] {
+            ranks[*index] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+// 正則化不完全ベータ関数 I_x(a, b)（連分数展開）
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+// Lentz 法
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-14;
+    let guard = |value: f64| if value.abs() < TINY { TINY } else { value };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..=300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / guard(1.0 + even * d);
+        c = guard(1.0 + even / c);
+        h *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / guard(1.0 + odd * d);
+        c = guard(1.0 + odd / c);
+        let step = d * c;
+        h *= step;
+        if (step - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+// Lanczos 近似（g = 7）
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, coefficient)| {
+            sum + coefficient / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// 相補誤差関数（チェビシェフ近似、相対誤差 1.2e-7 未満）
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let tail = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        tail
+    } else {
+        2.0 - tail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::StrategyType;
+    use std::collections::HashMap;
+
+    fn history(cooperation: &[f64], generations: impl Iterator<Item = u32>) -> SimulationResult {
+        SimulationResult::new(
+            generations
+                .zip(cooperation)
+                .map(|(generation, rate)| SimulationStatistics {
+                    generation,
+                    total_agents: 4,
+                    strategy_counts: HashMap::from([
+                        (StrategyType::AllCooperate, 2),
+                        (StrategyType::AllDefect, 2),
+                    ]),
+                    average_cooperation_rate: *rate,
+                    average_fitness: 10.0 * rate,
+                    ..SimulationStatistics::new()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_compare_aligns_generations_and_reports_effect_sizes() {
+        // Arrange: candidate は 1 世代目から始まり、協力率が 0.2 ずつ高い
+        let baseline = history(&[0.1, 0.2, 0.3, 0.4, 0.5], 0..);
+        let candidate = history(&[0.4, 0.5, 0.6, 0.7], 1..);
+        let service = ResultComparisonService::new()
+            .with_window(4)
+            .with_significance_tests(true);
+
+        // Act
+        let comparison = service.compare(&baseline, &candidate);
+
+        // Assert: 世代 1〜4 だけがそろう。戦略分布は同じなので多様性の差は 0
+        assert_eq!(comparison.generations.len(), 4);
+        assert_eq!(comparison.generations[0].generation, 1);
+        assert!((comparison.generations[0].cooperation.delta - 0.2).abs() < 1e-12);
+        assert!((comparison.generations[3].fitness.delta - 2.0).abs() < 1e-12);
+        assert_eq!(comparison.generations[2].diversity.delta, 0.0);
+
+        let cooperation = comparison.metric(ComparisonMetric::Cooperation).unwrap();
+        assert_eq!(cooperation.baseline.count, 4);
+        assert!((cooperation.mean_difference - 0.2).abs() < 1e-12);
+        assert!(cooperation.cohens_d > 1.0);
+        assert!(cooperation.tests.is_some());
+        let diversity = comparison.metric(ComparisonMetric::Diversity).unwrap();
+        assert_eq!(diversity.cohens_d, 0.0);
+        assert_eq!(diversity.tests.unwrap().welch_p_value, 1.0);
+    }
+
+    #[test]
+    fn test_welch_and_mann_whitney_match_reference_values() {
+        // Arrange
+        let baseline = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let candidate = [3.0, 4.0, 5.0, 6.0, 7.0];
+        let service = ResultComparisonService::new().with_significance_tests(true);
+
+        // Act
+        let comparison =
+            service.compare_metric(ComparisonMetric::Cooperation, &baseline, &candidate);
+
+        // Assert: t = 2、自由度 8 の両側 p = 0.0805。U = 20.5、同順位補正後の z = 7.5 / √22.5
+        let tests = comparison.tests.unwrap();
+        assert!((tests.welch_t - 2.0).abs() < 1e-12);
+        assert!((tests.welch_df - 8.0).abs() < 1e-12);
+        assert!((tests.welch_p_value - 0.080_516).abs() < 1e-5);
+        assert_eq!(tests.mann_whitney_u, 20.5);
+        assert!((tests.mann_whitney_p_value - 0.113_846).abs() < 1e-5);
+        assert!((comparison.cohens_d - 2.0 / 2.5f64.sqrt()).abs() < 1e-12);
+        assert!((comparison.cliffs_delta - 0.64).abs() < 1e-12);
+        assert!(ResultComparisonService::new()
+            .compare_metric(ComparisonMetric::Cooperation, &baseline, &candidate)
+            .tests
+            .is_none());
+    }
+}
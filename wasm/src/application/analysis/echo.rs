@@ -0,0 +1,177 @@
+use crate::domain::agent::{Action, Agent};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PairPattern {
+    MutualCooperation,
+    MutualDefection,
+    // CD と DC が交互に続くエコー（ノイズ下の TFT 同士で起きやすい）
+    Echo,
+    Mixed,
+}
+
+// ペアの対戦履歴 (自分, 相手) を古い順に受け取り分類する
+pub fn classify_pair_history(rounds: &[(Action, Action)]) -> PairPattern {
+    use Action::{Cooperate, Defect};
+
+    if rounds.is_empty() {
+        return PairPattern::Mixed;
+    }
+    if rounds.iter().all(|round| *round == (Cooperate, Cooperate)) {
+        return PairPattern::MutualCooperation;
+    }
+    if rounds.iter().all(|round| *round == (Defect, Defect)) {
+        return PairPattern::MutualDefection;
+    }
+
+    let alternating = rounds.len() >= 2
+        && rounds.iter().all(|(mine, theirs)| mine != theirs)
+        && rounds.windows(2).all(|pair| pair[1].0 == pair[0].1);
+    if alternating {
+        PairPattern::Echo
+    } else {
+        PairPattern::Mixed
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PairPatternSummary {
+    pub total_pairs: usize,
+    pub mutual_cooperation: usize,
+    pub mutual_defection: usize,
+    pub echo: usize,
+    pub mixed: usize,
+}
+
+impl PairPatternSummary {
+    pub fn record(&mut self, pattern: PairPattern) {
+        self.total_pairs += 1;
+        match pattern {
+            PairPattern::MutualCooperation => self.mutual_cooperation += 1,
+            PairPattern::MutualDefection => self.mutual_defection += 1,
+            PairPattern::Echo => self.echo += 1,
+            PairPattern::Mixed => self.mixed += 1,
+        }
+    }
+
+    pub fn echo_fraction(&self) -> f64 {
+        self.fraction(self.echo)
+    }
+
+    pub fn mutual_cooperation_fraction(&self) -> f64 {
+        self.fraction(self.mutual_cooperation)
+    }
+
+    pub fn mutual_defection_fraction(&self) -> f64 {
+        self.fraction(self.mutual_defection)
+    }
+
+    fn fraction(&self, count: usize) -> f64 {
+        if self.total_pairs == 0 {
+            0.0
+        } else {
+            count as f64 / self.total_pairs as f64
+        }
+    }
+}
+
+// 各エージェントの対戦履歴から、直近 window 回分でペアごとのパターンを集計する
+// 同じペアは片方の履歴だけを数える
+pub fn analyze_pair_patterns(agents: &[Agent], window: usize) -> PairPatternSummary {
+    let mut summary = PairPatternSummary::default();
+    let mut seen: HashSet<(Uuid, Uuid)> = HashSet::new();
+
+    for agent in agents {
+        for opponent_id in agent.history.opponent_ids() {
+            let key = if agent.id < opponent_id {
+                (agent.id, opponent_id)
+            } else {
+                (opponent_id, agent.id)
+            };
+            if !seen.insert(key) {
+                continue;
+            }
+
+            let rounds = agent.history.rounds_against(&opponent_id);
+            let start = rounds.len().saturating_sub(window.max(1));
+            summary.record(classify_pair_history(&rounds[start..]));
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+    use Action::{Cooperate, Defect};
+
+    #[test]
+    fn test_alternating_cd_dc_is_classified_as_echo() {
+        // Arrange: 片方の裏切りにもう片方が報復し続ける交互パターン
+        let rounds = [
+            (Cooperate, Defect),
+            (Defect, Cooperate),
+            (Cooperate, Defect),
+            (Defect, Cooperate),
+        ];
+
+        // Act
+        let pattern = classify_pair_history(&rounds);
+
+        // Assert
+        assert_eq!(pattern, PairPattern::Echo);
+    }
+
+    #[test]
+    fn test_non_alternating_histories_are_not_echo() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            classify_pair_history(&[(Cooperate, Cooperate), (Cooperate, Cooperate)]),
+            PairPattern::MutualCooperation
+        );
+        assert_eq!(
+            classify_pair_history(&[(Defect, Defect), (Defect, Defect)]),
+            PairPattern::MutualDefection
+        );
+        assert_eq!(
+            classify_pair_history(&[(Cooperate, Defect), (Cooperate, Defect)]),
+            PairPattern::Mixed
+        );
+    }
+
+    #[test]
+    fn test_pair_patterns_count_each_pair_once() {
+        // Arrange
+        let mut a = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Explorer,
+        );
+        let mut b = Agent::new(
+            Position::new(1, 0),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Explorer,
+        );
+        for (a_action, b_action) in [
+            (Cooperate, Defect),
+            (Defect, Cooperate),
+            (Cooperate, Defect),
+        ] {
+            a.add_game_result(b.id, a_action, b_action, 0);
+            b.add_game_result(a.id, b_action, a_action, 0);
+        }
+
+        // Act
+        let summary = analyze_pair_patterns(&[a, b], 10);
+
+        // Assert
+        assert_eq!(summary.total_pairs, 1);
+        assert_eq!(summary.echo, 1);
+        assert_eq!(summary.echo_fraction(), 1.0);
+    }
+}
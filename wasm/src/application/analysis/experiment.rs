@@ -0,0 +1,336 @@
+use super::{ReplicateRunner, Summary};
+use crate::application::simulation::SimulationConfig;
+use crate::domain::game::{GameType, PublicGoodsGame};
+use serde::{Deserialize, Serialize};
+
+// 掃引できる設定値
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepParameter {
+    MutationRate,
+    ActionNoise,
+    // 利得表の各値（整数に丸める）。掃引中は T > R > P > S を満たさない表も許可する
+    Temptation,
+    Reward,
+    Punishment,
+    Sucker,
+    // 囚人のジレンマの設定に適用すると既定の公共財ゲームに切り替える
+    PublicGoodsMultiplier,
+}
+
+impl SweepParameter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweepParameter::MutationRate => "mutation_rate",
+            SweepParameter::ActionNoise => "action_noise",
+            SweepParameter::Temptation => "temptation",
+            SweepParameter::Reward => "reward",
+            SweepParameter::Punishment => "punishment",
+            SweepParameter::Sucker => "sucker",
+            SweepParameter::PublicGoodsMultiplier => "public_goods_multiplier",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            SweepParameter::MutationRate,
+            SweepParameter::ActionNoise,
+            SweepParameter::Temptation,
+            SweepParameter::Reward,
+            SweepParameter::Punishment,
+            SweepParameter::Sucker,
+            SweepParameter::PublicGoodsMultiplier,
+        ]
+        .into_iter()
+        .find(|parameter| parameter.name() == name)
+    }
+
+    pub fn apply(&self, config: SimulationConfig, value: f64) -> SimulationConfig {
+        let mut payoffs = config.payoff_matrix;
+        let payoff = value.round() as i32;
+        match self {
+            SweepParameter::MutationRate => config.with_mutation_rate(value),
+            SweepParameter::ActionNoise => config.with_action_noise(value),
+            SweepParameter::PublicGoodsMultiplier => {
+                let game = match config.game_type {
+                    GameType::PublicGoods(game) => game,
+                    GameType::PrisonersDilemma => PublicGoodsGame::default(),
+                };
+                config.with_game_type(GameType::PublicGoods(PublicGoodsGame {
+                    multiplier: value,
+                    ..game
+                }))
+            }
+            SweepParameter::Temptation
+            | SweepParameter::Reward
+            | SweepParameter::Punishment
+            | SweepParameter::Sucker => {
+                match self {
+                    SweepParameter::Temptation => payoffs.temptation = payoff,
+                    SweepParameter::Reward => payoffs.reward = payoff,
+                    SweepParameter::Punishment => payoffs.punishment = payoff,
+                    _ => payoffs.sucker = payoff,
+                }
+                config
+                    .with_payoff_matrix(payoffs)
+                    .with_non_dilemma_payoffs(true)
+            }
+        }
+    }
+}
+
+// start から end までを steps 点で等間隔に取る（両端を含む。steps が 1 なら start のみ）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParameterRange {
+    pub parameter: SweepParameter,
+    pub start: f64,
+    pub end: f64,
+    pub steps: usize,
+}
+
+impl ParameterRange {
+    pub fn new(parameter: SweepParameter, start: f64, end: f64, steps: usize) -> Self {
+        Self {
+            parameter,
+            start,
+            end,
+            steps: steps.max(1),
+        }
+    }
+
+    // "mutation_rate=0.01:0.3:10"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, range) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("Expected PARAM=START:END:STEPS, got {spec}"))?;
+        let parameter = SweepParameter::from_name(name)
+            .ok_or_else(|| format!("Unknown sweep parameter: {name}"))?;
+        let parts: Vec<&str> = range.split(':').collect();
+        let [start, end, steps] = parts[..] else {
+            return Err(format!("Expected START:END:STEPS, got {range}"));
+        };
+        let number = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number in sweep {spec}: {value}"))
+        };
+        let steps = steps
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid step count in sweep {spec}: {steps}"))?;
+        Ok(Self::new(parameter, number(start)?, number(end)?, steps))
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        if self.steps <= 1 {
+            return vec![self.start];
+        }
+        let step = (self.end - self.start) / (self.steps - 1) as f64;
+        (0..self.steps)
+            .map(|index| self.start + step * index as f64)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExperimentSettings {
+    pub width: usize,
+    pub height: usize,
+    pub agent_count: usize,
+    pub generations: u32,
+    pub replicates: usize,
+    // 反復 r はシード base_seed + r で走らせる（全ての組み合わせで同じシード列を使う）
+    pub base_seed: u64,
+    pub config: SimulationConfig,
+    pub ranges: Vec<ParameterRange>,
+}
+
+impl Default for ExperimentSettings {
+    fn default() -> Self {
+        Self {
+            width: 30,
+            height: 30,
+            agent_count: 300,
+            generations: 20,
+            replicates: 5,
+            base_seed: 0,
+            config: SimulationConfig::default(),
+            ranges: Vec::new(),
+        }
+    }
+}
+
+// 1 つのパラメータの組み合わせについての反復実行の集計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentRow {
+    pub parameters: Vec<(SweepParameter, f64)>,
+    // 最終世代（世代交代直前）の平均協力率と平均スコア
+    pub cooperation: Summary,
+    pub score: Summary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExperimentProgress {
+    pub completed_runs: usize,
+    pub total_runs: usize,
+}
+
+pub struct ExperimentService;
+
+impl ExperimentService {
+    // 範囲の直積（最初の範囲が最も外側のループ）
+    pub fn combinations(ranges: &[ParameterRange]) -> Vec<Vec<(SweepParameter, f64)>> {
+        ranges.iter().fold(vec![Vec::new()], |combinations, range| {
+            combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    range.values().into_iter().map(move |value| {
+                        let mut next = combination.clone();
+                        next.push((range.parameter, value));
+                        next
+                    })
+                })
+                .collect()
+        })
+    }
+
+    pub fn run(settings: &ExperimentSettings) -> Result<Vec<ExperimentRow>, String> {
+        Self::run_with(settings, |_| true)
+    }
+
+    // 1 回の実行ごとに on_progress を呼ぶ。false を返すと中断し、集計が済んだ組み合わせだけを返す
+    pub fn run_with<F>(
+        settings: &ExperimentSettings,
+        mut on_progress: F,
+    ) -> Result<Vec<ExperimentRow>, String>
+    where
+        F: FnMut(ExperimentProgress) -> bool,
+    {
+        let combinations = Self::combinations(&settings.ranges);
+        let replicates = settings.replicates.max(1);
+        let total_runs = combinations.len() * replicates;
+
+        let mut rows = Vec::with_capacity(combinations.len());
+        for (index, parameters) in combinations.into_iter().enumerate() {
+            let config = parameters
+                .iter()
+                .fold(settings.config.clone(), |config, (parameter, value)| {
+                    parameter.apply(config, *value)
+                });
+            let runner = ReplicateRunner::new(
+                settings.width,
+                settings.height,
+                settings.agent_count,
+                config,
+            )
+            .with_replicates(replicates)
+            .with_base_seed(settings.base_seed);
+
+            let mut cancelled = false;
+            let result = runner.run_with(settings.generations, |completed, _| {
+                cancelled = !on_progress(ExperimentProgress {
+                    completed_runs: index * replicates + completed,
+                    total_runs,
+                });
+                !cancelled
+            })?;
+            if cancelled {
+                break;
+            }
+
+            let last = result.final_generation().cloned();
+            rows.push(ExperimentRow {
+                parameters,
+                cooperation: last.as_ref().map(|g| g.cooperation).unwrap_or_default(),
+                score: last.map(|g| g.fitness).unwrap_or_default(),
+            });
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range_and_enumerate_combinations() {
+        // Arrange
+        let mutation = ParameterRange::parse("mutation_rate=0.0:0.2:3").unwrap();
+        let temptation = ParameterRange::parse("temptation=4:6:2").unwrap();
+
+        // Act
+        let combinations = ExperimentService::combinations(&[mutation, temptation]);
+
+        // Assert
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(
+            combinations[3],
+            vec![
+                (SweepParameter::MutationRate, 0.1),
+                (SweepParameter::Temptation, 6.0)
+            ]
+        );
+        assert!(ParameterRange::parse("unknown=0:1:2").is_err());
+        assert!(ParameterRange::parse("reward=0:1").is_err());
+    }
+
+    #[test]
+    fn test_run_aggregates_replicates_per_combination() {
+        // Arrange
+        let settings = ExperimentSettings {
+            width: 8,
+            height: 8,
+            agent_count: 30,
+            generations: 2,
+            replicates: 3,
+            ranges: vec![ParameterRange::new(
+                SweepParameter::ActionNoise,
+                0.0,
+                0.5,
+                2,
+            )],
+            ..ExperimentSettings::default()
+        };
+        let mut progress = Vec::new();
+
+        // Act
+        let rows = ExperimentService::run_with(&settings, |p| {
+            progress.push(p.completed_runs);
+            true
+        })
+        .unwrap();
+
+        // Assert
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.cooperation.count == 3
+            && row.cooperation.ci_low <= row.cooperation.mean
+            && row.cooperation.mean <= row.cooperation.ci_high));
+        assert_eq!(progress, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_cancelled_run_returns_finished_combinations_only() {
+        // Arrange
+        let settings = ExperimentSettings {
+            width: 6,
+            height: 6,
+            agent_count: 10,
+            generations: 1,
+            replicates: 2,
+            ranges: vec![ParameterRange::new(
+                SweepParameter::MutationRate,
+                0.0,
+                0.1,
+                3,
+            )],
+            ..ExperimentSettings::default()
+        };
+
+        // Act: 3 回目の実行で止める
+        let rows = ExperimentService::run_with(&settings, |p| p.completed_runs < 3).unwrap();
+
+        // Assert
+        assert_eq!(rows.len(), 1);
+    }
+}
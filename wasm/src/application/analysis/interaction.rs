@@ -0,0 +1,143 @@
+use crate::domain::agent::{Action, StrategyType};
+use crate::domain::grid::Grid;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionNode {
+    pub id: Uuid,
+    pub x: usize,
+    pub y: usize,
+    pub strategy: StrategyType,
+    pub cooperation_rate: f64,
+    pub score: i32,
+}
+
+// source の対戦履歴に残っている target との対戦の集計（向きのある辺。逆向きの辺は target の履歴から作る）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+    pub games: usize,
+    // source が協力した割合
+    pub cooperation_rate: f64,
+    pub mutual_cooperation_rate: f64,
+    // source の平均利得
+    pub average_payoff: f64,
+}
+
+// 誰が誰と対戦したかのグラフ。ノードは盤面の個体（位置順）、辺は残っている対戦記録から作る
+// 盤面にいない相手との記録は辺にしない
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InteractionNetwork {
+    pub nodes: Vec<InteractionNode>,
+    // source の位置順、同じ source の中では target の位置順
+    pub edges: Vec<InteractionEdge>,
+}
+
+impl InteractionNetwork {
+    pub fn from_grid(grid: &Grid) -> Self {
+        let agents = grid.agents_in_position_order();
+        let index: HashMap<Uuid, usize> = agents
+            .iter()
+            .enumerate()
+            .map(|(i, agent)| (agent.id, i))
+            .collect();
+
+        let nodes = agents
+            .iter()
+            .map(|agent| InteractionNode {
+                id: agent.id,
+                x: agent.position.x,
+                y: agent.position.y,
+                strategy: agent.strategy,
+                cooperation_rate: agent.cooperation_rate(),
+                score: agent.score,
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for agent in &agents {
+            // target の番号 → (対戦数, 協力数, 相互協力数, 利得の合計)
+            let mut tallies: HashMap<usize, (usize, usize, usize, i64)> = HashMap::new();
+            for record in agent.history.records() {
+                let Some(&target) = index.get(&record.opponent_id) else {
+                    continue;
+                };
+                let cooperated = record.my_action == Action::Cooperate;
+                let tally = tallies.entry(target).or_default();
+                tally.0 += 1;
+                tally.1 += usize::from(cooperated);
+                tally.2 += usize::from(cooperated && record.opponent_action == Action::Cooperate);
+                tally.3 += record.payoff as i64;
+            }
+            let mut tallies: Vec<_> = tallies.into_iter().collect();
+            tallies.sort_by_key(|&(target, _)| target);
+            edges.extend(tallies.into_iter().map(
+                |(target, (games, cooperations, mutual_cooperations, payoff))| InteractionEdge {
+                    source: agent.id,
+                    target: agents[target].id,
+                    games,
+                    cooperation_rate: cooperations as f64 / games as f64,
+                    mutual_cooperation_rate: mutual_cooperations as f64 / games as f64,
+                    average_payoff: payoff as f64 / games as f64,
+                },
+            ));
+        }
+
+        Self { nodes, edges }
+    }
+
+    pub fn edge(&self, source: &Uuid, target: &Uuid) -> Option<&InteractionEdge> {
+        self.edges
+            .iter()
+            .find(|edge| edge.source == *source && edge.target == *target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Agent, MovementStrategy, Position};
+
+    #[test]
+    fn test_edges_follow_battle_records_between_agents_on_the_grid() {
+        // Arrange: a は b と 2 戦、盤面にいない相手と 1 戦。b の履歴は 1 戦だけ残っている
+        let mut grid = Grid::new(3, 1);
+        let mut a = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        let mut b = Agent::new(
+            Position::new(2, 0),
+            StrategyType::AllDefect,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        a.add_game_result(b.id, Action::Cooperate, Action::Defect, 0);
+        a.add_game_result(b.id, Action::Defect, Action::Defect, 1);
+        a.add_game_result(Uuid::new_v4(), Action::Cooperate, Action::Cooperate, 3);
+        b.add_game_result(a.id, Action::Defect, Action::Defect, 1);
+        let (a_id, b_id) = (a.id, b.id);
+        grid.add_agent(a).unwrap();
+        grid.add_agent(b).unwrap();
+
+        // Act
+        let network = InteractionNetwork::from_grid(&grid);
+
+        // Assert
+        assert_eq!(network.nodes.len(), 2);
+        assert_eq!(network.nodes[0].id, a_id);
+        assert_eq!(network.edges.len(), 2);
+        let forward = network.edge(&a_id, &b_id).unwrap();
+        assert_eq!(forward.games, 2);
+        assert_eq!(forward.cooperation_rate, 0.5);
+        assert_eq!(forward.average_payoff, 0.5);
+        let backward = network.edge(&b_id, &a_id).unwrap();
+        assert_eq!(backward.games, 1);
+        assert_eq!(backward.cooperation_rate, 0.0);
+    }
+}
@@ -0,0 +1,25 @@
+pub mod autocorrelation;
+pub mod basin;
+pub mod comparison;
+pub mod echo;
+pub mod ess;
+pub mod experiment;
+pub mod interaction;
+pub mod network;
+pub mod replicate;
+pub mod replicator;
+pub mod spatial;
+pub mod summary;
+
+pub use autocorrelation::*;
+pub use basin::*;
+pub use comparison::*;
+pub use echo::*;
+pub use ess::*;
+pub use experiment::*;
+pub use interaction::*;
+pub use network::*;
+pub use replicate::*;
+pub use replicator::*;
+pub use spatial::*;
+pub use summary::*;
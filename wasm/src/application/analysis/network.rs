@@ -0,0 +1,100 @@
+use crate::domain::grid::{edge_correlation, Grid};
+use serde::{Deserialize, Serialize};
+
+// 1 世代分のネットワーク構造の指標
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkMetrics {
+    pub generation: u32,
+    pub edge_count: usize,
+    pub mean_degree: f64,
+    // 添字が次数、値がその次数のノード数
+    pub degree_distribution: Vec<usize>,
+    // 辺の両端の次数の相関（正なら次数の大きいノード同士がつながりやすい）
+    pub degree_assortativity: f64,
+    // 両端に個体がいる辺での協力率の相関（正なら協力者同士がつながっている）
+    pub cooperation_assortativity: f64,
+    // 前の世代の区切りからつなぎ替えた辺の数
+    pub rewired_links: usize,
+}
+
+impl NetworkMetrics {
+    pub fn heap_bytes(&self) -> usize {
+        self.degree_distribution.capacity() * std::mem::size_of::<usize>()
+    }
+}
+
+pub struct NetworkAnalysisService;
+
+impl NetworkAnalysisService {
+    // ネットワークがなければ None
+    pub fn analyze(grid: &Grid, generation: u32, rewired_links: usize) -> Option<NetworkMetrics> {
+        let network = grid.network()?;
+        let node_count = network.node_count();
+        let edges = network.edges();
+
+        let cooperation_pairs: Vec<(f64, f64)> = edges
+            .iter()
+            .filter_map(|&(a, b)| {
+                let rate = |node: usize| {
+                    grid.get_agent_at_position(&grid.position_of_node(node))
+                        .map(|agent| agent.cooperation_rate())
+                };
+                Some((rate(a)?, rate(b)?))
+            })
+            .collect();
+
+        Some(NetworkMetrics {
+            generation,
+            edge_count: edges.len(),
+            mean_degree: if node_count == 0 {
+                0.0
+            } else {
+                2.0 * edges.len() as f64 / node_count as f64
+            },
+            degree_distribution: network.degree_distribution(),
+            degree_assortativity: network.degree_assortativity(),
+            cooperation_assortativity: edge_correlation(&cooperation_pairs),
+            rewired_links,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, Agent, MovementStrategy, Position, StrategyType};
+    use crate::domain::grid::NetworkWorld;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_cooperators_linked_to_cooperators_are_assortative() {
+        // Arrange: 4x1 の道 0-1-2-3 で、左の 2 体が協力者、右の 2 体が裏切り者
+        let network = NetworkWorld::from_edges(4, &[(0, 1), (1, 2), (2, 3)]).unwrap();
+        let mut grid = Grid::new(4, 1).with_network(Some(network));
+        for x in 0..4 {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                StrategyType::TitForTat,
+                0.0,
+                MovementStrategy::Settler,
+            );
+            let action = if x < 2 {
+                Action::Cooperate
+            } else {
+                Action::Defect
+            };
+            agent.add_game_result(Uuid::new_v4(), action, Action::Cooperate, 0);
+            grid.add_agent(agent).unwrap();
+        }
+
+        // Act
+        let metrics = NetworkAnalysisService::analyze(&grid, 1, 0).unwrap();
+
+        // Assert: 両端が 1 の辺 1 本、0 の辺 1 本、混ざった辺 1 本で相関は 1/3
+        assert_eq!(metrics.edge_count, 3);
+        assert_eq!(metrics.mean_degree, 1.5);
+        assert_eq!(metrics.degree_distribution, vec![0, 2, 2]);
+        assert!((metrics.cooperation_assortativity - 1.0 / 3.0).abs() < 1e-9);
+        assert!(NetworkAnalysisService::analyze(&Grid::new(4, 1), 1, 0).is_none());
+    }
+}
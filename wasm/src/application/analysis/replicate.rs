@@ -0,0 +1,179 @@
+use super::Summary;
+use crate::application::simulation::{SimulationConfig, SimulationService, SimulationStatistics};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// 1 世代分の反復間の集計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationSummary {
+    pub generation: u32,
+    pub cooperation: Summary,
+    // 適応度は世代交代直前の平均スコア
+    pub fitness: Summary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregatedResult {
+    // 実際に完了した反復の数（中断した場合は指定より少ない）
+    pub replicates: usize,
+    pub seeds: Vec<u64>,
+    pub generations: Vec<GenerationSummary>,
+}
+
+impl AggregatedResult {
+    // 世代ごとの統計履歴を世代番号でそろえて集計する（履歴の長さが反復ごとに違ってもよい）
+    pub fn from_histories(seeds: Vec<u64>, histories: &[Vec<SimulationStatistics>]) -> Self {
+        let mut by_generation: BTreeMap<u32, (Vec<f64>, Vec<f64>)> = BTreeMap::new();
+        for stats in histories.iter().flatten() {
+            let (cooperation, fitness) = by_generation.entry(stats.generation).or_default();
+            cooperation.push(stats.average_cooperation_rate);
+            fitness.push(stats.average_score);
+        }
+
+        Self {
+            replicates: histories.len(),
+            seeds,
+            generations: by_generation
+                .into_iter()
+                .map(|(generation, (cooperation, fitness))| GenerationSummary {
+                    generation,
+                    cooperation: Summary::of(&cooperation),
+                    fitness: Summary::of(&fitness),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn final_generation(&self) -> Option<&GenerationSummary> {
+        self.generations.last()
+    }
+}
+
+// 同じ設定をシードだけ変えて K 回実行する。反復 k のシードは base_seed + k
+#[derive(Debug, Clone)]
+pub struct ReplicateRunner {
+    width: usize,
+    height: usize,
+    agent_count: usize,
+    config: SimulationConfig,
+    replicates: usize,
+    base_seed: u64,
+}
+
+impl ReplicateRunner {
+    pub const DEFAULT_REPLICATES: usize = 10;
+
+    // base_seed は設定のシード（なければ 0）から始める
+    pub fn new(width: usize, height: usize, agent_count: usize, config: SimulationConfig) -> Self {
+        Self {
+            width,
+            height,
+            agent_count,
+            base_seed: config.seed.unwrap_or(0),
+            config,
+            replicates: Self::DEFAULT_REPLICATES,
+        }
+    }
+
+    pub fn with_replicates(mut self, replicates: usize) -> Self {
+        self.replicates = replicates.max(1);
+        self
+    }
+
+    pub fn with_base_seed(mut self, seed: u64) -> Self {
+        self.base_seed = seed;
+        self
+    }
+
+    pub fn seeds(&self) -> Vec<u64> {
+        (0..self.replicates as u64)
+            .map(|replicate| self.base_seed.wrapping_add(replicate))
+            .collect()
+    }
+
+    pub fn run(&self, generations: u32) -> Result<AggregatedResult, String> {
+        self.run_with(generations, |_, _| true)
+    }
+
+    // 1 回の実行が終わるごとに on_replicate(完了数, 総数) を呼ぶ。false を返すと残りを実行せずに集計する
+    pub fn run_with<F>(
+        &self,
+        generations: u32,
+        mut on_replicate: F,
+    ) -> Result<AggregatedResult, String>
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        let seeds = self.seeds();
+        let mut histories = Vec::with_capacity(seeds.len());
+        for &seed in &seeds {
+            let mut simulation = SimulationService::with_config(
+                self.width,
+                self.height,
+                self.agent_count,
+                self.config.clone().with_seed(seed),
+            )?;
+            simulation.run_generations(generations);
+            histories.push(
+                simulation
+                    .get_generation_history()
+                    .iter()
+                    .cloned()
+                    .collect(),
+            );
+
+            if !on_replicate(histories.len(), seeds.len()) {
+                break;
+            }
+        }
+
+        let completed = histories.len();
+        Ok(AggregatedResult::from_histories(
+            seeds.into_iter().take(completed).collect(),
+            &histories,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregates_each_generation_across_replicates() {
+        // Arrange
+        let runner = ReplicateRunner::new(8, 8, 30, SimulationConfig::default().with_seed(7))
+            .with_replicates(3);
+
+        // Act
+        let result = runner.run(4).unwrap();
+
+        // Assert
+        assert_eq!(result.replicates, 3);
+        assert_eq!(result.seeds, vec![7, 8, 9]);
+        assert_eq!(result.generations.len(), 4);
+        let last = result.final_generation().unwrap();
+        assert_eq!(last.cooperation.count, 3);
+        assert!(last.cooperation.ci_low <= last.cooperation.mean);
+        assert!(last.fitness.mean <= last.fitness.ci_high);
+    }
+
+    #[test]
+    fn test_from_histories_aligns_by_generation_number() {
+        // Arrange: 2 本目の履歴は 1 世代分しかない
+        let stats = |generation, rate| SimulationStatistics {
+            generation,
+            average_cooperation_rate: rate,
+            ..SimulationStatistics::new()
+        };
+        let histories = vec![vec![stats(1, 0.2), stats(2, 0.4)], vec![stats(1, 0.6)]];
+
+        // Act
+        let result = AggregatedResult::from_histories(vec![0, 1], &histories);
+
+        // Assert
+        assert_eq!(result.generations[0].cooperation.count, 2);
+        assert!((result.generations[0].cooperation.mean - 0.4).abs() < 1e-12);
+        assert_eq!(result.generations[1].cooperation.count, 1);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::domain::agent::StrategyType;
+use std::collections::HashMap;
+
+// 完全混合集団でのレプリケータ方程式をオイラー法で積分する
+// 戻り値の各要素は StrategyType::ALL の順に並んだ戦略割合（先頭は初期状態）
+pub fn replicator_dynamics(
+    payoff_matrix: &HashMap<(StrategyType, StrategyType), f64>,
+    initial_fractions: &HashMap<StrategyType, f64>,
+    steps: usize,
+    dt: f64,
+) -> Vec<Vec<f64>> {
+    let strategies = StrategyType::ALL;
+    let mut fractions: Vec<f64> = strategies
+        .iter()
+        .map(|s| initial_fractions.get(s).copied().unwrap_or(0.0).max(0.0))
+        .collect();
+    normalize(&mut fractions);
+
+    let mut trajectory = Vec::with_capacity(steps + 1);
+    trajectory.push(fractions.clone());
+
+    for _ in 0..steps {
+        let fitness: Vec<f64> = strategies
+            .iter()
+            .map(|strategy| {
+                strategies
+                    .iter()
+                    .zip(&fractions)
+                    .map(|(opponent, fraction)| {
+                        payoff_matrix
+                            .get(&(*strategy, *opponent))
+                            .copied()
+                            .unwrap_or(0.0)
+                            * fraction
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        let average_fitness: f64 = fitness.iter().zip(&fractions).map(|(f, x)| f * x).sum();
+
+        for (fraction, strategy_fitness) in fractions.iter_mut().zip(&fitness) {
+            *fraction =
+                (*fraction + dt * *fraction * (strategy_fitness - average_fitness)).max(0.0);
+        }
+        normalize(&mut fractions);
+
+        trajectory.push(fractions.clone());
+    }
+
+    trajectory
+}
+
+fn normalize(fractions: &mut [f64]) {
+    let total: f64 = fractions.iter().sum();
+    if total > 0.0 {
+        for fraction in fractions.iter_mut() {
+            *fraction /= total;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::game::{GameService, PayoffMatrix};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    fn index_of(strategy: StrategyType) -> usize {
+        StrategyType::ALL
+            .iter()
+            .position(|s| *s == strategy)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dominant_strategy_increases_monotonically_toward_one() {
+        // Arrange: AllCooperate と AllDefect のみの集団では裏切りが支配戦略
+        let payoff_matrix = GameService::strategy_payoff_matrix(
+            &PayoffMatrix::default(),
+            10,
+            &mut ChaCha12Rng::seed_from_u64(1),
+        );
+        let initial_fractions = HashMap::from([
+            (StrategyType::AllCooperate, 0.9),
+            (StrategyType::AllDefect, 0.1),
+        ]);
+        let defect = index_of(StrategyType::AllDefect);
+
+        // Act
+        let trajectory = replicator_dynamics(&payoff_matrix, &initial_fractions, 500, 0.1);
+
+        // Assert
+        assert_eq!(trajectory.len(), 501);
+        for window in trajectory.windows(2) {
+            assert!(window[1][defect] >= window[0][defect] - 1e-12);
+        }
+        assert!(trajectory.last().unwrap()[defect] > 0.99);
+    }
+
+    #[test]
+    fn test_fractions_remain_normalized_and_absent_strategies_stay_absent() {
+        // Arrange
+        let payoff_matrix = GameService::strategy_payoff_matrix(
+            &PayoffMatrix::default(),
+            10,
+            &mut ChaCha12Rng::seed_from_u64(1),
+        );
+        let initial_fractions = HashMap::from([
+            (StrategyType::TitForTat, 2.0),
+            (StrategyType::AllDefect, 2.0),
+        ]);
+
+        // Act
+        let trajectory = replicator_dynamics(&payoff_matrix, &initial_fractions, 50, 0.05);
+
+        // Assert
+        for fractions in &trajectory {
+            assert!((fractions.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert_eq!(fractions[index_of(StrategyType::Pavlov)], 0.0);
+        }
+    }
+}
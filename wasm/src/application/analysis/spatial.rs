@@ -0,0 +1,197 @@
+use crate::domain::agent::Position;
+use crate::domain::grid::Grid;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// 1 世代分の空間構造の指標
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpatialMetrics {
+    pub generation: u32,
+    pub cooperator_count: usize,
+    pub cluster_count: usize,
+    pub largest_cluster: usize,
+    pub mean_cluster_size: f64,
+    // (クラスタの大きさ, その大きさのクラスタ数) を大きさの昇順で
+    pub cluster_size_distribution: Vec<(usize, usize)>,
+    // 協力率の空間自己相関（近傍を重み 1 とした Moran's I）
+    pub morans_i: f64,
+}
+
+impl SpatialMetrics {
+    pub fn heap_bytes(&self) -> usize {
+        self.cluster_size_distribution.capacity() * std::mem::size_of::<(usize, usize)>()
+    }
+}
+
+pub struct SpatialAnalysisService;
+
+impl SpatialAnalysisService {
+    // 協力率がこの値以上のエージェントを協力者とみなす
+    pub const COOPERATOR_THRESHOLD: f64 = 0.5;
+
+    pub fn analyze(grid: &Grid, generation: u32) -> SpatialMetrics {
+        let clusters = Self::cooperator_clusters(grid, Self::COOPERATOR_THRESHOLD);
+        let cooperator_count: usize = clusters.iter().map(Vec::len).sum();
+
+        let mut distribution: HashMap<usize, usize> = HashMap::new();
+        for cluster in &clusters {
+            *distribution.entry(cluster.len()).or_insert(0) += 1;
+        }
+        let mut cluster_size_distribution: Vec<(usize, usize)> = distribution.into_iter().collect();
+        cluster_size_distribution.sort_unstable();
+
+        SpatialMetrics {
+            generation,
+            cooperator_count,
+            cluster_count: clusters.len(),
+            largest_cluster: clusters.iter().map(Vec::len).max().unwrap_or(0),
+            mean_cluster_size: if clusters.is_empty() {
+                0.0
+            } else {
+                cooperator_count as f64 / clusters.len() as f64
+            },
+            cluster_size_distribution,
+            morans_i: Self::morans_i(grid),
+        }
+    }
+
+    // 近傍でつながった協力者の集まりを塗りつぶしで求める（位置順に走査するので結果は決定的）
+    pub fn cooperator_clusters(grid: &Grid, threshold: f64) -> Vec<Vec<Position>> {
+        let cooperators: HashSet<Position> = grid
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|agent| agent.cooperation_rate() >= threshold)
+            .map(|agent| agent.position)
+            .collect();
+
+        let mut visited = HashSet::new();
+        let mut clusters = Vec::new();
+        for agent in grid.agents_in_position_order() {
+            let start = agent.position;
+            if !cooperators.contains(&start) || !visited.insert(start) {
+                continue;
+            }
+
+            let mut cluster = Vec::new();
+            let mut queue = VecDeque::from([start]);
+            while let Some(position) = queue.pop_front() {
+                cluster.push(position);
+                for neighbor in grid.neighbor_positions(&position) {
+                    if cooperators.contains(&neighbor) && visited.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            clusters.push(cluster);
+        }
+        clusters
+    }
+
+    // I = (N / W) * Σ w_ij (x_i - x̄)(x_j - x̄) / Σ (x_i - x̄)²
+    // 隣り合うエージェント同士を w_ij = 1 とする。分散か重みが 0 なら 0.0
+    pub fn morans_i(grid: &Grid) -> f64 {
+        let agents = grid.agents_in_position_order();
+        let n = agents.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mean = agents
+            .iter()
+            .map(|agent| agent.cooperation_rate())
+            .sum::<f64>()
+            / n as f64;
+        let variance: f64 = agents
+            .iter()
+            .map(|agent| (agent.cooperation_rate() - mean).powi(2))
+            .sum();
+
+        let mut weight_total = 0.0;
+        let mut covariance = 0.0;
+        for agent in &agents {
+            let deviation = agent.cooperation_rate() - mean;
+            for neighbor in grid.get_neighbors(&agent.position) {
+                weight_total += 1.0;
+                covariance += deviation * (neighbor.cooperation_rate() - mean);
+            }
+        }
+
+        if variance == 0.0 || weight_total == 0.0 {
+            return 0.0;
+        }
+        (n as f64 / weight_total) * covariance / variance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, Agent, MovementStrategy, StrategyType};
+    use crate::domain::grid::NeighborhoodType;
+    use uuid::Uuid;
+
+    fn agent_with_rate(x: usize, y: usize, cooperative: bool) -> Agent {
+        let mut agent = Agent::new(
+            Position::new(x, y),
+            StrategyType::TitForTat,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        let action = if cooperative {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        };
+        agent.add_game_result(Uuid::new_v4(), action, Action::Cooperate, 0);
+        agent
+    }
+
+    // 左半分が協力者、右半分が裏切り者の 4x4 グリッド
+    fn split_grid() -> Grid {
+        let mut grid = Grid::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                grid.add_agent(agent_with_rate(x, y, x < 2)).unwrap();
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_separate_cooperator_blocks_form_separate_clusters() {
+        // Arrange: 角に 3 体の塊と 1 体の孤立した協力者
+        let mut grid = Grid::new(6, 6);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (5, 5)] {
+            grid.add_agent(agent_with_rate(x, y, true)).unwrap();
+        }
+        grid.add_agent(agent_with_rate(3, 3, false)).unwrap();
+
+        // Act
+        let metrics = SpatialAnalysisService::analyze(&grid, 2);
+
+        // Assert
+        assert_eq!(metrics.cooperator_count, 4);
+        assert_eq!(metrics.cluster_count, 2);
+        assert_eq!(metrics.largest_cluster, 3);
+        assert_eq!(metrics.cluster_size_distribution, vec![(1, 1), (3, 1)]);
+        assert_eq!(metrics.mean_cluster_size, 2.0);
+    }
+
+    #[test]
+    fn test_morans_i_is_positive_for_segregated_and_negative_for_checkerboard() {
+        // Arrange
+        let segregated = split_grid();
+        let mut checkerboard = Grid::new(4, 4).with_neighborhood(NeighborhoodType::VonNeumann);
+        for y in 0..4 {
+            for x in 0..4 {
+                checkerboard
+                    .add_agent(agent_with_rate(x, y, (x + y) % 2 == 0))
+                    .unwrap();
+            }
+        }
+
+        // Act & Assert: 分離していれば正、市松模様（4 近傍）なら -1
+        assert!(SpatialAnalysisService::morans_i(&segregated) > 0.3);
+        assert!((SpatialAnalysisService::morans_i(&checkerboard) + 1.0).abs() < 1e-12);
+    }
+}
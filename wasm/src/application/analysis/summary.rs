@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+// 両側 95% の t 分布の臨界値（自由度 1〜30）。それより大きい自由度は正規近似の 1.96
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+// 反復実行の結果の要約（標本標準偏差と平均の 95% 信頼区間）
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+impl Summary {
+    // 1 件だけなら区間は平均の 1 点、空なら全て 0
+    pub fn of(values: &[f64]) -> Self {
+        let count = values.len();
+        if count == 0 {
+            return Self::default();
+        }
+        let mean = values.iter().sum::<f64>() / count as f64;
+        if count == 1 {
+            return Self {
+                count,
+                mean,
+                std_dev: 0.0,
+                ci_low: mean,
+                ci_high: mean,
+            };
+        }
+
+        let variance = values
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / (count - 1) as f64;
+        let std_dev = variance.sqrt();
+        let half_width = Self::t_critical(count - 1) * std_dev / (count as f64).sqrt();
+        Self {
+            count,
+            mean,
+            std_dev,
+            ci_low: mean - half_width,
+            ci_high: mean + half_width,
+        }
+    }
+
+    fn t_critical(degrees_of_freedom: usize) -> f64 {
+        T_CRITICAL_95
+            .get(degrees_of_freedom.wrapping_sub(1))
+            .copied()
+            .unwrap_or(1.96)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_uses_sample_std_dev_and_t_interval() {
+        // Arrange & Act
+        let summary = Summary::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+
+        // Assert: 標本分散 32/7、自由度 7 の t = 2.365
+        let std_dev = (32.0f64 / 7.0).sqrt();
+        assert_eq!(summary.count, 8);
+        assert!((summary.mean - 5.0).abs() < 1e-12);
+        assert!((summary.std_dev - std_dev).abs() < 1e-12);
+        assert!((summary.ci_high - (5.0 + 2.365 * std_dev / 8f64.sqrt())).abs() < 1e-12);
+        assert_eq!(Summary::of(&[3.0]).ci_low, 3.0);
+    }
+}
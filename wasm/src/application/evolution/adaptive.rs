@@ -0,0 +1,195 @@
+use crate::application::simulation::SimulationStatistics;
+use crate::domain::agent::{Agent, StrategyType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+// 世代交代ごとに実効の突然変異率を決め直す。条件を満たす間だけ基本率を boost 倍にし（上限 1.0）、外れたら基本率に戻す
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AdaptiveMutation {
+    // 戦略の多様性（正規化シャノンエントロピー 0〜1）が threshold を下回ったとき
+    Diversity { threshold: f64, boost: f64 },
+    // 平均スコアが直近 generations 世代、それ以前の最高値を超えなかったとき
+    Stagnation { generations: usize, boost: f64 },
+}
+
+impl AdaptiveMutation {
+    // "diversity:THRESHOLD:BOOST" / "stagnation:GENERATIONS:BOOST"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected diversity:T:B or stagnation:N:B, got {spec}");
+        let parts: Vec<&str> = spec.split(':').collect();
+        let [kind, value, boost] = parts[..] else {
+            return Err(invalid());
+        };
+        let boost: f64 = boost.parse().map_err(|_| invalid())?;
+        match kind {
+            "diversity" => Ok(AdaptiveMutation::Diversity {
+                threshold: value.parse().map_err(|_| invalid())?,
+                boost,
+            }),
+            "stagnation" => Ok(AdaptiveMutation::Stagnation {
+                generations: value.parse().map_err(|_| invalid())?,
+                boost,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    // history は世代交代直前の統計の履歴（最後が今の世代）
+    pub fn effective_rate(
+        &self,
+        base_rate: f64,
+        agents: &HashMap<Uuid, Agent>,
+        history: &VecDeque<SimulationStatistics>,
+    ) -> f64 {
+        let (triggered, boost) = match *self {
+            AdaptiveMutation::Diversity { threshold, boost } => {
+                (Self::strategy_diversity(agents) < threshold, boost)
+            }
+            AdaptiveMutation::Stagnation { generations, boost } => {
+                (Self::is_stagnant(history, generations), boost)
+            }
+        };
+
+        if triggered {
+            (base_rate * boost).clamp(0.0, 1.0)
+        } else {
+            base_rate
+        }
+    }
+
+    // 戦略分布のシャノンエントロピーを組み込み戦略数の対数で割った値
+    pub fn strategy_diversity(agents: &HashMap<Uuid, Agent>) -> f64 {
+        if agents.is_empty() {
+            return 0.0;
+        }
+        let mut counts: HashMap<StrategyType, usize> = HashMap::new();
+        for agent in agents.values() {
+            *counts.entry(agent.strategy).or_insert(0) += 1;
+        }
+
+        let total = agents.len() as f64;
+        let entropy: f64 = counts
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.ln()
+            })
+            .sum();
+        (entropy / (StrategyType::ALL.len() as f64).ln()).clamp(0.0, 1.0)
+    }
+
+    // 比べる世代が足りない間は停滞とみなさない
+    fn is_stagnant(history: &VecDeque<SimulationStatistics>, generations: usize) -> bool {
+        if generations == 0 || history.len() <= generations {
+            return false;
+        }
+        let split = history.len() - generations;
+        let best_before = history
+            .range(..split)
+            .map(|stats| stats.average_score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        history
+            .range(split..)
+            .all(|stats| stats.average_score <= best_before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position};
+
+    fn population(strategies: &[StrategyType]) -> HashMap<Uuid, Agent> {
+        strategies
+            .iter()
+            .enumerate()
+            .map(|(i, strategy)| {
+                let agent = Agent::new(
+                    Position::new(i, 0),
+                    *strategy,
+                    0.5,
+                    MovementStrategy::Settler,
+                );
+                (agent.id, agent)
+            })
+            .collect()
+    }
+
+    fn scores(values: &[f64]) -> VecDeque<SimulationStatistics> {
+        values
+            .iter()
+            .map(|score| SimulationStatistics {
+                average_score: *score,
+                ..SimulationStatistics::new()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diversity_trigger_boosts_monoculture_only() {
+        // Arrange
+        let adaptive = AdaptiveMutation::Diversity {
+            threshold: 0.5,
+            boost: 4.0,
+        };
+        let monoculture = population(&[StrategyType::AllDefect; 8]);
+        let diverse = population(&StrategyType::ALL);
+
+        // Act & Assert
+        assert_eq!(
+            adaptive.effective_rate(0.05, &monoculture, &VecDeque::new()),
+            0.2
+        );
+        assert_eq!(
+            adaptive.effective_rate(0.05, &diverse, &VecDeque::new()),
+            0.05
+        );
+        assert!((AdaptiveMutation::strategy_diversity(&diverse) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_reads_both_triggers() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            AdaptiveMutation::parse("diversity:0.4:3").unwrap(),
+            AdaptiveMutation::Diversity {
+                threshold: 0.4,
+                boost: 3.0
+            }
+        );
+        assert_eq!(
+            AdaptiveMutation::parse("stagnation:5:2.5").unwrap(),
+            AdaptiveMutation::Stagnation {
+                generations: 5,
+                boost: 2.5
+            }
+        );
+        assert!(AdaptiveMutation::parse("stagnation:0.5:2").is_err());
+        assert!(AdaptiveMutation::parse("diversity:0.4").is_err());
+    }
+
+    #[test]
+    fn test_stagnation_trigger_compares_recent_best_with_earlier_best() {
+        // Arrange
+        let adaptive = AdaptiveMutation::Stagnation {
+            generations: 2,
+            boost: 2.0,
+        };
+        let agents = HashMap::new();
+
+        // Act & Assert
+        assert_eq!(
+            adaptive.effective_rate(0.1, &agents, &scores(&[10.0, 9.0, 10.0])),
+            0.2
+        );
+        assert_eq!(
+            adaptive.effective_rate(0.1, &agents, &scores(&[10.0, 9.0, 11.0])),
+            0.1
+        );
+        assert_eq!(
+            adaptive.effective_rate(0.1, &agents, &scores(&[10.0, 9.0])),
+            0.1
+        );
+    }
+}
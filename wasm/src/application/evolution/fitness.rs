@@ -0,0 +1,264 @@
+use crate::domain::agent::Agent;
+use serde::{Deserialize, Serialize};
+
+// 親選択で使う適応度を算出する
+// 並列ビルドでは個体ごとの評価を複数スレッドから呼ぶ
+pub trait FitnessEvaluator: Sync {
+    fn evaluate(&self, agent: &Agent) -> f64;
+}
+
+// 従来どおりスコアのみを適応度とする
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScoreFitness;
+
+impl FitnessEvaluator for ScoreFitness {
+    fn evaluate(&self, agent: &Agent) -> f64 {
+        agent.score as f64
+    }
+}
+
+// 対戦数で正規化したスコアを適応度とする
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizedScoreFitness;
+
+impl FitnessEvaluator for NormalizedScoreFitness {
+    fn evaluate(&self, agent: &Agent) -> f64 {
+        agent.normalized_score
+    }
+}
+
+// スコアと形質の重み付き和（協力率を報酬、裏切り率を攻撃性として罰する）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeightedFitness {
+    pub score_weight: f64,
+    pub cooperation_weight: f64,
+    pub aggression_penalty: f64,
+    pub mobility_weight: f64,
+}
+
+impl Default for WeightedFitness {
+    fn default() -> Self {
+        Self {
+            score_weight: 1.0,
+            cooperation_weight: 0.0,
+            aggression_penalty: 0.0,
+            mobility_weight: 0.0,
+        }
+    }
+}
+
+impl WeightedFitness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_score_weight(mut self, weight: f64) -> Self {
+        self.score_weight = weight;
+        self
+    }
+
+    pub fn with_cooperation_weight(mut self, weight: f64) -> Self {
+        self.cooperation_weight = weight;
+        self
+    }
+
+    pub fn with_aggression_penalty(mut self, penalty: f64) -> Self {
+        self.aggression_penalty = penalty;
+        self
+    }
+
+    pub fn with_mobility_weight(mut self, weight: f64) -> Self {
+        self.mobility_weight = weight;
+        self
+    }
+}
+
+impl FitnessEvaluator for WeightedFitness {
+    fn evaluate(&self, agent: &Agent) -> f64 {
+        let cooperation = agent.cooperation_rate();
+        let aggression = 1.0 - cooperation;
+
+        self.score_weight * agent.score as f64 + self.cooperation_weight * cooperation
+            - self.aggression_penalty * aggression
+            + self.mobility_weight * agent.mobility
+    }
+}
+
+// 設定で選ぶ適応度。親選択・エリートの選抜・統計の平均適応度で共通に使う
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FitnessFunction {
+    // スコアそのもの
+    #[default]
+    RawScore,
+    // 対戦数で割ったスコア
+    ScorePerBattle,
+    // スコア + energy_weight × エネルギー（エネルギー型の繁殖と組み合わせる）
+    EnergyWeighted {
+        energy_weight: f64,
+    },
+    // スコア × (1 - rate)^年齢。長く生きて積み上げたスコアを割り引く
+    AgeDiscounted {
+        rate: f64,
+    },
+    // スコアと形質の重み付き和
+    Weighted(WeightedFitness),
+}
+
+impl FitnessFunction {
+    // "raw" / "per_battle" / "energy:W" / "age:RATE" / "weighted:SCORE,COOPERATION,AGGRESSION,MOBILITY"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Unknown fitness function: {spec}");
+        let number = |value: &str| value.trim().parse::<f64>().map_err(|_| invalid());
+        let (name, args) = spec.split_once(':').unwrap_or((spec, ""));
+        let fitness = match (name, args) {
+            ("raw", "") => FitnessFunction::RawScore,
+            ("per_battle", "") => FitnessFunction::ScorePerBattle,
+            ("energy", weight) => FitnessFunction::EnergyWeighted {
+                energy_weight: number(weight)?,
+            },
+            ("age", rate) => FitnessFunction::AgeDiscounted {
+                rate: number(rate)?,
+            },
+            ("weighted", weights) => {
+                let weights: Vec<f64> = weights.split(',').map(number).collect::<Result<_, _>>()?;
+                let [score, cooperation, aggression, mobility] = weights[..] else {
+                    return Err(invalid());
+                };
+                FitnessFunction::Weighted(
+                    WeightedFitness::new()
+                        .with_score_weight(score)
+                        .with_cooperation_weight(cooperation)
+                        .with_aggression_penalty(aggression)
+                        .with_mobility_weight(mobility),
+                )
+            }
+            _ => return Err(invalid()),
+        };
+        fitness.validate()?;
+        Ok(fitness)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            FitnessFunction::EnergyWeighted { energy_weight } if !energy_weight.is_finite() => {
+                Err(format!("energy weight must be finite, got {energy_weight}"))
+            }
+            FitnessFunction::AgeDiscounted { rate } if !(0.0..=1.0).contains(&rate) => Err(
+                format!("age discount rate must be between 0 and 1, got {rate}"),
+            ),
+            FitnessFunction::Weighted(weights)
+                if ![
+                    weights.score_weight,
+                    weights.cooperation_weight,
+                    weights.aggression_penalty,
+                    weights.mobility_weight,
+                ]
+                .iter()
+                .all(|weight| weight.is_finite()) =>
+            {
+                Err("weights must be finite".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FitnessEvaluator for FitnessFunction {
+    fn evaluate(&self, agent: &Agent) -> f64 {
+        match self {
+            FitnessFunction::RawScore => ScoreFitness.evaluate(agent),
+            FitnessFunction::ScorePerBattle => NormalizedScoreFitness.evaluate(agent),
+            FitnessFunction::EnergyWeighted { energy_weight } => {
+                agent.score as f64 + energy_weight * agent.energy
+            }
+            FitnessFunction::AgeDiscounted { rate } => {
+                agent.score as f64 * (1.0 - rate).powf(f64::from(agent.age))
+            }
+            FitnessFunction::Weighted(weights) => weights.evaluate(agent),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, MovementStrategy, Position, StrategyType};
+    use uuid::Uuid;
+
+    fn agent_with_games(strategy: StrategyType, action: Action, games: usize, score: i32) -> Agent {
+        let mut agent = Agent::new(
+            Position::new(0, 0),
+            strategy,
+            0.5,
+            MovementStrategy::Explorer,
+        );
+        let opponent = Uuid::new_v4();
+        for _ in 0..games {
+            agent.add_game_result(opponent, action, Action::Cooperate, 0);
+        }
+        agent.score = score;
+        agent
+    }
+
+    #[test]
+    fn test_high_cooperation_weight_ranks_cooperators_above_higher_scorers() {
+        // Arrange: 裏切り者の方がスコアは高い
+        let cooperator = agent_with_games(StrategyType::AllCooperate, Action::Cooperate, 10, 30);
+        let defector = agent_with_games(StrategyType::AllDefect, Action::Defect, 10, 50);
+        let fitness = WeightedFitness::new()
+            .with_cooperation_weight(100.0)
+            .with_aggression_penalty(10.0);
+
+        // Act
+        let cooperator_fitness = fitness.evaluate(&cooperator);
+        let defector_fitness = fitness.evaluate(&defector);
+
+        // Assert
+        assert!(ScoreFitness.evaluate(&defector) > ScoreFitness.evaluate(&cooperator));
+        assert!(cooperator_fitness > defector_fitness);
+    }
+
+    #[test]
+    fn test_fitness_functions_rank_by_energy_and_age() {
+        // Arrange: young はスコアが低いがエネルギーが多く、若い
+        let mut young = agent_with_games(StrategyType::AllCooperate, Action::Cooperate, 2, 10);
+        young.energy = 20.0;
+        let mut old = agent_with_games(StrategyType::AllCooperate, Action::Cooperate, 2, 16);
+        old.age = 10;
+
+        // Act
+        let energy = FitnessFunction::parse("energy:0.5").unwrap();
+        let age = FitnessFunction::parse("age:0.1").unwrap();
+
+        // Assert
+        assert!(
+            FitnessFunction::RawScore.evaluate(&old) > FitnessFunction::RawScore.evaluate(&young)
+        );
+        assert!(energy.evaluate(&young) > energy.evaluate(&old));
+        assert!(age.evaluate(&young) > age.evaluate(&old));
+        assert_eq!(
+            FitnessFunction::ScorePerBattle.evaluate(&young),
+            young.normalized_score
+        );
+        assert_eq!(
+            FitnessFunction::parse("weighted:1,0,0,0")
+                .unwrap()
+                .evaluate(&old),
+            16.0
+        );
+        assert!(FitnessFunction::parse("age:1.5").is_err());
+        assert!(FitnessFunction::parse("weighted:1,2").is_err());
+    }
+
+    #[test]
+    fn test_default_weights_match_score_fitness() {
+        // Arrange
+        let agent = agent_with_games(StrategyType::TitForTat, Action::Defect, 4, 12);
+
+        // Act & Assert
+        assert_eq!(
+            WeightedFitness::default().evaluate(&agent),
+            ScoreFitness.evaluate(&agent)
+        );
+    }
+}
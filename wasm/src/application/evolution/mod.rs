@@ -1,5 +1,17 @@
+pub mod adaptive;
+pub mod fitness;
+pub mod niching;
+pub mod operators;
+pub mod pareto;
 pub mod selection;
 pub mod service;
+pub mod strategy;
 
+pub use adaptive::*;
+pub use fitness::*;
+pub use niching::*;
+pub use operators::*;
+pub use pareto::*;
 pub use selection::*;
 pub use service::*;
+pub use strategy::*;
@@ -0,0 +1,193 @@
+use super::FitnessEvaluator;
+use crate::domain::agent::{Agent, Genotype};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 適応度共有（Goldberg & Richardson）。形質の距離が radius 未満の個体同士で適応度を分け合い、
+// 似た個体が多いほど親に選ばれにくくして、異なる戦略が共存できるようにする
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FitnessSharing {
+    // 共有半径（Genotype::distance は 0〜1）
+    pub radius: f64,
+    // 共有関数の形。1 なら距離に比例して線形に減る
+    pub alpha: f64,
+}
+
+impl FitnessSharing {
+    pub fn new(radius: f64) -> Self {
+        Self { radius, alpha: 1.0 }
+    }
+
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    // "RADIUS" / "RADIUS:ALPHA"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected RADIUS or RADIUS:ALPHA, got {spec}");
+        let (radius, alpha) = match spec.split_once(':') {
+            Some((radius, alpha)) => (radius, Some(alpha)),
+            None => (spec, None),
+        };
+        let sharing = Self::new(radius.parse().map_err(|_| invalid())?);
+        match alpha {
+            Some(alpha) => Ok(sharing.with_alpha(alpha.parse().map_err(|_| invalid())?)),
+            None => Ok(sharing),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            return Err(format!("radius must be positive, got {}", self.radius));
+        }
+        if !(self.alpha > 0.0 && self.alpha.is_finite()) {
+            return Err(format!("alpha must be positive, got {}", self.alpha));
+        }
+        Ok(())
+    }
+
+    // 共有関数 sh(d) = 1 - (d / radius)^alpha（radius 以上は 0）
+    pub fn share(&self, distance: f64) -> f64 {
+        if distance >= self.radius {
+            0.0
+        } else {
+            1.0 - (distance / self.radius).powf(self.alpha)
+        }
+    }
+
+    // 個体ごとの適応度を、最小値を 1 にそろえてからニッチ数（自分を含む共有関数の和）で割る（常に正）
+    pub fn shared_fitness(
+        &self,
+        agents: &HashMap<Uuid, Agent>,
+        fitness: &dyn FitnessEvaluator,
+    ) -> SharedFitness {
+        let genotypes: Vec<(Uuid, Genotype, f64)> = agents
+            .values()
+            .map(|agent| (agent.id, Genotype::of(agent), fitness.evaluate(agent)))
+            .collect();
+        let min_fitness = genotypes
+            .iter()
+            .map(|(_, _, value)| *value)
+            .fold(f64::INFINITY, f64::min);
+
+        let values = genotypes
+            .iter()
+            .map(|(id, genotype, value)| {
+                let niche_count: f64 = genotypes
+                    .iter()
+                    .map(|(_, other, _)| self.share(genotype.distance(other)))
+                    .sum();
+                (*id, (value - min_fitness + 1.0) / niche_count)
+            })
+            .collect();
+        SharedFitness { values }
+    }
+
+    // 適応度の高い順に、まだどのニッチにも入っていない個体を中心にして radius 以内の個体をまとめる
+    // （同点は位置順）。中心の数をニッチの数とする
+    pub fn count_niches(&self, agents: &[&Agent], fitness: &dyn FitnessEvaluator) -> usize {
+        let mut ranked: Vec<(f64, &Agent)> = agents
+            .iter()
+            .map(|agent| (fitness.evaluate(agent), *agent))
+            .collect();
+        ranked.sort_by(|(fitness_a, a), (fitness_b, b)| {
+            fitness_b
+                .total_cmp(fitness_a)
+                .then((a.position.y, a.position.x).cmp(&(b.position.y, b.position.x)))
+        });
+
+        let mut centers: Vec<Genotype> = Vec::new();
+        for (_, agent) in ranked {
+            let genotype = Genotype::of(agent);
+            if centers
+                .iter()
+                .all(|center| center.distance(&genotype) >= self.radius)
+            {
+                centers.push(genotype);
+            }
+        }
+        centers.len()
+    }
+}
+
+// 世代交代の直前に計算した共有後の適応度（その世代の個体 ID で引く）
+pub struct SharedFitness {
+    values: HashMap<Uuid, f64>,
+}
+
+impl SharedFitness {
+    // 条件付き戦略の共有後の値を (1 - penalty_rate) 倍にする（スコアのルーレットに掛ける割引と同じ）
+    pub fn with_complexity_penalty(
+        mut self,
+        agents: &HashMap<Uuid, Agent>,
+        penalty_rate: f32,
+    ) -> Self {
+        let multiplier = 1.0 - f64::from(penalty_rate);
+        for (id, value) in self.values.iter_mut() {
+            if agents
+                .get(id)
+                .is_some_and(|agent| agent.strategy.is_conditional())
+            {
+                *value *= multiplier;
+            }
+        }
+        self
+    }
+}
+
+impl FitnessEvaluator for SharedFitness {
+    fn evaluate(&self, agent: &Agent) -> f64 {
+        self.values.get(&agent.id).copied().unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::FitnessFunction;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+
+    #[test]
+    fn test_crowded_genotypes_share_their_fitness() {
+        // Arrange: 同じスコア 10 の TFT が 3 体と AllD が 1 体、スコア 0 の協力者が 1 体
+        let agent = |x: usize, strategy: StrategyType, score: i32| {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                strategy,
+                0.5,
+                MovementStrategy::Settler,
+            );
+            agent.score = score;
+            agent
+        };
+        let crowd: Vec<Agent> = vec![
+            agent(0, StrategyType::TitForTat, 10),
+            agent(1, StrategyType::TitForTat, 10),
+            agent(2, StrategyType::TitForTat, 10),
+            agent(3, StrategyType::AllDefect, 10),
+            agent(4, StrategyType::AllCooperate, 0),
+        ];
+        let agents: HashMap<Uuid, Agent> = crowd.iter().map(|a| (a.id, a.clone())).collect();
+        let sharing = FitnessSharing::new(0.1);
+
+        // Act
+        let shared = sharing.shared_fitness(&agents, &FitnessFunction::RawScore);
+        let refs: Vec<&Agent> = crowd.iter().collect();
+        let niches = sharing.count_niches(&refs, &FitnessFunction::RawScore);
+
+        // Assert: 戦略が違えば距離は 1/6 で半径の外。TFT は 3 体で 11 を分け合う
+        assert!((shared.evaluate(&crowd[0]) - 11.0 / 3.0).abs() < 1e-12);
+        let penalized = shared.with_complexity_penalty(&agents, 0.5);
+        assert!((penalized.evaluate(&crowd[0]) - 11.0 / 6.0).abs() < 1e-12);
+        assert_eq!(penalized.evaluate(&crowd[3]), 11.0);
+        assert_eq!(penalized.evaluate(&crowd[4]), 1.0);
+        assert_eq!(niches, 3);
+        assert_eq!(
+            FitnessSharing::parse("0.2:2"),
+            Ok(FitnessSharing::new(0.2).with_alpha(2.0))
+        );
+        assert!(FitnessSharing::new(0.0).validate().is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::domain::agent::{Agent, MemoryGenome};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+// memory-N の行動表に対する遺伝的操作
+pub struct MemoryGenomeOperators;
+
+impl MemoryGenomeOperators {
+    // 確率表を変異させるときのガウス摂動の標準偏差
+    pub const PROBABILITY_MUTATION_SD: f64 = 0.1;
+
+    // 遺伝子座ごとの一様交叉。記憶長が違う親同士ではどちらかの表をそのまま継承する
+    pub fn crossover<R: Rng + ?Sized>(
+        parent1: &MemoryGenome,
+        parent2: &MemoryGenome,
+        rng: &mut R,
+    ) -> MemoryGenome {
+        if parent1.memory() != parent2.memory() {
+            return if rng.gen_bool(0.5) {
+                parent1.clone()
+            } else {
+                parent2.clone()
+            };
+        }
+
+        let mut child = parent1.clone();
+        let other = std::iter::once(parent2.initial()).chain(parent2.table().iter().copied());
+        for (entry, value) in child.entries_mut().zip(other) {
+            if rng.gen_bool(0.5) {
+                *entry = value;
+            }
+        }
+        child
+    }
+
+    // 各遺伝子座が mutation_rate の確率で変異する
+    // 決定的な表はビット反転、確率を含む表は [0, 1] に収まるガウス摂動
+    pub fn mutate<R: Rng + ?Sized>(genome: &mut MemoryGenome, mutation_rate: f64, rng: &mut R) {
+        let mutation_rate = mutation_rate.clamp(0.0, 1.0);
+        let deterministic = genome.is_deterministic();
+        let noise =
+            Normal::new(0.0, Self::PROBABILITY_MUTATION_SD).expect("valid standard deviation");
+
+        for entry in genome.entries_mut() {
+            if !rng.gen_bool(mutation_rate) {
+                continue;
+            }
+            *entry = if deterministic {
+                1.0 - *entry
+            } else {
+                (*entry + noise.sample(rng)).clamp(0.0, 1.0)
+            };
+        }
+    }
+}
+
+// 連続値の形質（移動性・内集団びいきの強さ・攻撃性、いずれも 0〜1）の交叉
+// 平均は子を両親の間に閉じ込めるので、SBX・BLX-α で両親の外側も探索できるようにする
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CrossoverMethod {
+    // 両親の平均（乱数を引かない）
+    #[default]
+    Average,
+    // 模擬二進交叉（SBX）。distribution_index が大きいほど子が親の近くに生まれる
+    Sbx {
+        distribution_index: f64,
+    },
+    // BLX-α。両親の区間を両側に幅の alpha 倍ずつ広げた範囲から一様に選ぶ
+    Blend {
+        alpha: f64,
+    },
+}
+
+impl CrossoverMethod {
+    pub const DEFAULT_DISTRIBUTION_INDEX: f64 = 15.0;
+    pub const DEFAULT_ALPHA: f64 = 0.5;
+
+    // "average" / "sbx" / "sbx:ETA" / "blx" / "blx:ALPHA"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected average, sbx[:ETA] or blx[:ALPHA], got {spec}");
+        let (kind, value) = match spec.split_once(':') {
+            Some((kind, value)) => (kind, Some(value.parse::<f64>().map_err(|_| invalid())?)),
+            None => (spec, None),
+        };
+        match (kind, value) {
+            ("average", None) => Ok(CrossoverMethod::Average),
+            ("sbx", value) => Ok(CrossoverMethod::Sbx {
+                distribution_index: value.unwrap_or(Self::DEFAULT_DISTRIBUTION_INDEX),
+            }),
+            ("blx", value) => Ok(CrossoverMethod::Blend {
+                alpha: value.unwrap_or(Self::DEFAULT_ALPHA),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            CrossoverMethod::Average => Ok(()),
+            CrossoverMethod::Sbx { distribution_index } => {
+                if distribution_index >= 0.0 && distribution_index.is_finite() {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "distribution index must be non-negative, got {distribution_index}"
+                    ))
+                }
+            }
+            CrossoverMethod::Blend { alpha } => {
+                if alpha >= 0.0 && alpha.is_finite() {
+                    Ok(())
+                } else {
+                    Err(format!("alpha must be non-negative, got {alpha}"))
+                }
+            }
+        }
+    }
+
+    // 両親の値 a・b から子の値を 1 つ作る（[0, 1] に収める）
+    pub fn combine<R: Rng + ?Sized>(&self, a: f64, b: f64, rng: &mut R) -> f64 {
+        let child = match *self {
+            CrossoverMethod::Average => (a + b) / 2.0,
+            CrossoverMethod::Sbx { distribution_index } => {
+                let u: f64 = rng.gen();
+                let exponent = 1.0 / (distribution_index + 1.0);
+                let beta = if u <= 0.5 {
+                    (2.0 * u).powf(exponent)
+                } else {
+                    (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+                };
+                // SBX が作る 2 つの子のどちらかを選ぶ
+                let (near, far) = if rng.gen_bool(0.5) { (a, b) } else { (b, a) };
+                0.5 * ((1.0 + beta) * near + (1.0 - beta) * far)
+            }
+            CrossoverMethod::Blend { alpha } => {
+                let (low, high) = (a.min(b), a.max(b));
+                let spread = (high - low) * alpha;
+                if high - low == 0.0 {
+                    low
+                } else {
+                    rng.gen_range(low - spread..=high + spread)
+                }
+            }
+        };
+        child.clamp(0.0, 1.0)
+    }
+
+    // 子の連続値の形質を両親から組み直す（Agent::crossover の平均を置き換える）
+    pub fn recombine_traits<R: Rng + ?Sized>(
+        &self,
+        child: &mut Agent,
+        parent1: &Agent,
+        parent2: &Agent,
+        rng: &mut R,
+    ) {
+        child.mobility = self.combine(parent1.mobility, parent2.mobility, rng);
+        child.in_group_bias = self.combine(parent1.in_group_bias, parent2.in_group_bias, rng);
+        child.aggression = self.combine(parent1.aggression, parent2.aggression, rng);
+    }
+}
+
+// 重みに比例して個体の番号を引く選択操作
+pub struct SelectionOperators;
+
+impl SelectionOperators {
+    // 確率的普遍抽出：1 つの乱数で等間隔に count 本の針を置く。各個体が選ばれる回数は
+    // 期待値の切り捨てか切り上げになり、ルーレットを count 回回すより分散が小さい
+    pub fn stochastic_universal<R: Rng + ?Sized>(
+        weights: &[f64],
+        count: usize,
+        rng: &mut R,
+    ) -> Vec<usize> {
+        if weights.is_empty() || count == 0 {
+            return Vec::new();
+        }
+        let total: f64 = weights.iter().sum();
+        if !total.is_finite() || total <= 0.0 {
+            return (0..count)
+                .map(|_| rng.gen_range(0..weights.len()))
+                .collect();
+        }
+
+        let spacing = total / count as f64;
+        let start = rng.gen_range(0.0..spacing);
+        let mut selected = Vec::with_capacity(count);
+        let mut index = 0;
+        let mut cumulative = weights[0];
+        for pointer in (0..count).map(|i| start + i as f64 * spacing) {
+            while cumulative <= pointer && index + 1 < weights.len() {
+                index += 1;
+                cumulative += weights[index];
+            }
+            selected.push(index);
+        }
+        selected
+    }
+
+    // 重みに比例して 1 個体を引く
+    pub fn roulette<R: Rng + ?Sized>(weights: &[f64], rng: &mut R) -> usize {
+        let total: f64 = weights.iter().sum();
+        if !total.is_finite() || total <= 0.0 {
+            return rng.gen_range(0..weights.len());
+        }
+        let mut random_value = rng.gen_range(0.0..total);
+        weights
+            .iter()
+            .position(|weight| {
+                random_value -= weight;
+                random_value <= 0.0
+            })
+            .unwrap_or(weights.len() - 1)
+    }
+
+    // ボルツマン選択の重み exp(f / T)。最大値を引いてから指数を取り、桁あふれを防ぐ
+    pub fn boltzmann_weights(fitness: &[f64], temperature: f64) -> Vec<f64> {
+        let max_fitness = fitness.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        fitness
+            .iter()
+            .map(|f| ((f - max_fitness) / temperature).exp())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::StrategyType;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_crossover_takes_each_locus_from_a_parent() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let all_cooperate = MemoryGenome::from_strategy(StrategyType::AllCooperate, 2).unwrap();
+        let all_defect = MemoryGenome::from_strategy(StrategyType::AllDefect, 2).unwrap();
+
+        // Act
+        let child = MemoryGenomeOperators::crossover(&all_cooperate, &all_defect, &mut rng);
+
+        // Assert: 17 遺伝子座が両親から混ざって受け継がれる
+        assert_eq!(child.memory(), 2);
+        assert!(child.is_deterministic());
+        let cooperations = child.table().iter().filter(|&&p| p == 1.0).count();
+        assert!(cooperations > 0 && cooperations < 16);
+    }
+
+    #[test]
+    fn test_mutate_flips_bits_of_deterministic_tables() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let mut genome = MemoryGenome::from_strategy(StrategyType::TitForTat, 1).unwrap();
+
+        // Act: 変異率 1.0 ならすべて反転する
+        MemoryGenomeOperators::mutate(&mut genome, 1.0, &mut rng);
+
+        // Assert
+        assert_eq!(genome.to_bits().as_deref(), Some("0|0101"));
+    }
+
+    #[test]
+    fn test_mutate_keeps_probabilities_in_range() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let mut genome = MemoryGenome::from_strategy(StrategyType::Extortion, 1).unwrap();
+
+        // Act
+        for _ in 0..100 {
+            MemoryGenomeOperators::mutate(&mut genome, 1.0, &mut rng);
+        }
+
+        // Assert
+        assert!(genome.table().iter().all(|p| (0.0..=1.0).contains(p)));
+        assert!(MemoryGenome::new(1, genome.initial(), genome.table().to_vec()).is_ok());
+    }
+
+    #[test]
+    fn test_sbx_and_blend_explore_beyond_the_parents() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(5);
+        let sbx = CrossoverMethod::parse("sbx:2").unwrap();
+        let blend = CrossoverMethod::parse("blx:0.5").unwrap();
+
+        // Act
+        let sbx_children: Vec<f64> = (0..200).map(|_| sbx.combine(0.4, 0.6, &mut rng)).collect();
+        let blend_children: Vec<f64> = (0..200)
+            .map(|_| blend.combine(0.4, 0.6, &mut rng))
+            .collect();
+
+        // Assert: BLX-0.5 は [0.3, 0.7] に収まり、どちらも両親の区間の外にも子を作る
+        let outside = |children: &[f64]| children.iter().any(|c| !(0.4..=0.6).contains(c));
+        assert!(outside(&sbx_children) && outside(&blend_children));
+        assert!(blend_children.iter().all(|c| (0.3..=0.7).contains(c)));
+        assert!(sbx_children.iter().all(|c| (0.0..=1.0).contains(c)));
+        assert_eq!(blend.combine(0.25, 0.25, &mut rng), 0.25);
+        assert_eq!(CrossoverMethod::Average.combine(0.2, 0.6, &mut rng), 0.4);
+        assert_eq!(
+            CrossoverMethod::parse("sbx"),
+            Ok(CrossoverMethod::Sbx {
+                distribution_index: 15.0
+            })
+        );
+        assert!(CrossoverMethod::parse("average:1").is_err());
+        assert!(CrossoverMethod::Blend { alpha: -1.0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_stochastic_universal_sampling_hits_expected_counts() {
+        for seed in 0..20 {
+            // Arrange: 期待回数はちょうど 1, 1, 2, 4 回
+            let mut rng = ChaCha12Rng::seed_from_u64(seed);
+            let weights = [1.0, 1.0, 2.0, 4.0];
+
+            // Act
+            let selected = SelectionOperators::stochastic_universal(&weights, 8, &mut rng);
+
+            // Assert
+            let counts: Vec<usize> = (0..weights.len())
+                .map(|i| selected.iter().filter(|&&index| index == i).count())
+                .collect();
+            assert_eq!(counts, vec![1, 1, 2, 4]);
+        }
+    }
+
+    #[test]
+    fn test_boltzmann_weights_sharpen_as_the_temperature_drops() {
+        // Arrange
+        let fitness = [0.0, 10.0, 1000.0];
+
+        // Act
+        let hot = SelectionOperators::boltzmann_weights(&fitness, 1e6);
+        let cold = SelectionOperators::boltzmann_weights(&fitness, 1.0);
+
+        // Assert: 高温ではほぼ一様、低温では最良の個体だけが重みを持つ（桁あふれもしない）
+        assert!(hot.iter().all(|weight| *weight > 0.99));
+        assert_eq!(cold, vec![0.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::domain::agent::Agent;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 多目的選択で最大化する目的（いずれも大きいほど良い）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    Score,
+    Cooperation,
+    // 生まれてから経過したステップ数
+    Longevity,
+    // この世代に対戦した相手の数（周囲への影響の広さ）
+    SpatialInfluence,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 4] = [
+        Objective::Score,
+        Objective::Cooperation,
+        Objective::Longevity,
+        Objective::SpatialInfluence,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Objective::Score => "score",
+            Objective::Cooperation => "cooperation",
+            Objective::Longevity => "longevity",
+            Objective::SpatialInfluence => "spatial_influence",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|objective| objective.name() == name)
+    }
+
+    pub fn evaluate(&self, agent: &Agent) -> f64 {
+        match self {
+            Objective::Score => agent.score as f64,
+            Objective::Cooperation => agent.cooperation_rate(),
+            Objective::Longevity => agent.age as f64,
+            Objective::SpatialInfluence => agent.history.opponent_ids().len() as f64,
+        }
+    }
+}
+
+// 1 世代の Pareto 前線（どの目的でも他に劣らないエージェント）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParetoFront {
+    pub generation: u32,
+    pub objectives: Vec<Objective>,
+    pub members: Vec<ParetoMember>,
+}
+
+impl ParetoFront {
+    pub fn heap_bytes(&self) -> usize {
+        self.objectives.capacity() * std::mem::size_of::<Objective>()
+            + self.members.capacity() * std::mem::size_of::<ParetoMember>()
+            + self
+                .members
+                .iter()
+                .map(|member| member.values.capacity() * std::mem::size_of::<f64>())
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParetoMember {
+    pub id: Uuid,
+    // objectives と同じ並び
+    pub values: Vec<f64>,
+}
+
+// 非優越ソートのランクと混雑度による 2 者トーナメントで親を選ぶ（NSGA-II の選択）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiObjective {
+    pub objectives: Vec<Objective>,
+}
+
+impl MultiObjective {
+    pub fn new(objectives: Vec<Objective>) -> Self {
+        Self { objectives }
+    }
+
+    // "score,cooperation"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let objectives = spec
+            .split(',')
+            .map(|name| {
+                Objective::from_name(name.trim())
+                    .ok_or_else(|| format!("Unknown objective: {}", name.trim()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self::new(objectives))
+    }
+
+    pub fn values(&self, agent: &Agent) -> Vec<f64> {
+        self.objectives
+            .iter()
+            .map(|objective| objective.evaluate(agent))
+            .collect()
+    }
+
+    pub fn pareto_front(&self, agents: &HashMap<Uuid, Agent>, generation: u32) -> ParetoFront {
+        let ordered = Self::ordered(agents);
+        let values: Vec<Vec<f64>> = ordered.iter().map(|agent| self.values(agent)).collect();
+        let ranks = Self::pareto_ranks(&values);
+
+        ParetoFront {
+            generation,
+            objectives: self.objectives.clone(),
+            members: ordered
+                .iter()
+                .zip(values)
+                .zip(ranks)
+                .filter(|(_, rank)| *rank == 0)
+                .map(|((agent, values), _)| ParetoMember {
+                    id: agent.id,
+                    values,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn select_parents<R: Rng + ?Sized>(
+        &self,
+        agents: &HashMap<Uuid, Agent>,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        let ordered = Self::ordered(agents);
+        if ordered.is_empty() {
+            return Vec::new();
+        }
+        let values: Vec<Vec<f64>> = ordered.iter().map(|agent| self.values(agent)).collect();
+        let ranks = Self::pareto_ranks(&values);
+        let crowding = Self::crowding_distances(&values, &ranks);
+
+        (0..ordered.len())
+            .map(|_| {
+                let a = rng.gen_range(0..ordered.len());
+                let b = rng.gen_range(0..ordered.len());
+                let a_wins =
+                    ranks[a] < ranks[b] || (ranks[a] == ranks[b] && crowding[a] >= crowding[b]);
+                ordered[if a_wins { a } else { b }].clone()
+            })
+            .collect()
+    }
+
+    // 0 が Pareto 前線、1 は前線を除いた残りの前線…
+    pub fn pareto_ranks(values: &[Vec<f64>]) -> Vec<usize> {
+        let n = values.len();
+        let mut dominated_by_count = vec![0usize; n];
+        let mut dominates: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if Self::dominates(&values[i], &values[j]) {
+                    dominates[i].push(j);
+                    dominated_by_count[j] += 1;
+                } else if Self::dominates(&values[j], &values[i]) {
+                    dominates[j].push(i);
+                    dominated_by_count[i] += 1;
+                }
+            }
+        }
+
+        let mut ranks = vec![0; n];
+        let mut front: Vec<usize> = (0..n).filter(|i| dominated_by_count[*i] == 0).collect();
+        let mut rank = 0;
+        while !front.is_empty() {
+            let mut next = Vec::new();
+            for &i in &front {
+                ranks[i] = rank;
+                for &j in &dominates[i] {
+                    dominated_by_count[j] -= 1;
+                    if dominated_by_count[j] == 0 {
+                        next.push(j);
+                    }
+                }
+            }
+            front = next;
+            rank += 1;
+        }
+        ranks
+    }
+
+    // 同じランクの中で、目的空間で両隣との間隔が広いほど大きい（両端は無限大）
+    pub fn crowding_distances(values: &[Vec<f64>], ranks: &[usize]) -> Vec<f64> {
+        let mut distances = vec![0.0; values.len()];
+        let objective_count = values.first().map_or(0, Vec::len);
+        let columns: Vec<Vec<f64>> = (0..objective_count)
+            .map(|m| values.iter().map(|value| value[m]).collect())
+            .collect();
+        let max_rank = ranks.iter().copied().max().unwrap_or(0);
+
+        for rank in 0..=max_rank {
+            let mut front: Vec<usize> = (0..values.len()).filter(|i| ranks[*i] == rank).collect();
+            for column in &columns {
+                front.sort_by(|a, b| column[*a].total_cmp(&column[*b]));
+                let (Some(&first), Some(&last)) = (front.first(), front.last()) else {
+                    continue;
+                };
+                distances[first] = f64::INFINITY;
+                distances[last] = f64::INFINITY;
+                let span = column[last] - column[first];
+                if span <= 0.0 {
+                    continue;
+                }
+                for window in front.windows(3) {
+                    distances[window[1]] += (column[window[2]] - column[window[0]]) / span;
+                }
+            }
+        }
+        distances
+    }
+
+    // すべての目的で a >= b かつ少なくとも 1 つで a > b
+    fn dominates(a: &[f64], b: &[f64]) -> bool {
+        a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+    }
+
+    fn ordered(agents: &HashMap<Uuid, Agent>) -> Vec<&Agent> {
+        let mut ordered: Vec<&Agent> = agents.values().collect();
+        ordered.sort_by_key(|agent| (agent.position.y, agent.position.x));
+        ordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+
+    #[test]
+    fn test_pareto_ranks_peel_successive_fronts() {
+        // Arrange: (1,3) と (3,1) と (2,2) は互いに優越せず、(1,1) はすべてに劣る
+        let values = vec![
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![2.0, 2.0],
+            vec![1.0, 1.0],
+            vec![0.0, 0.0],
+        ];
+
+        // Act
+        let ranks = MultiObjective::pareto_ranks(&values);
+        let crowding = MultiObjective::crowding_distances(&values, &ranks);
+
+        // Assert
+        assert_eq!(ranks, vec![0, 0, 0, 1, 2]);
+        assert!(crowding[0].is_infinite() && crowding[1].is_infinite());
+        assert!((crowding[2] - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pareto_front_lists_non_dominated_agents_with_values() {
+        // Arrange
+        let agents: HashMap<Uuid, Agent> = [(10, 0), (5, 4), (3, 2)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (score, age))| {
+                let mut agent = Agent::new(
+                    Position::new(i, 0),
+                    StrategyType::TitForTat,
+                    0.5,
+                    MovementStrategy::Settler,
+                );
+                agent.score = score;
+                agent.age = age;
+                (agent.id, agent)
+            })
+            .collect();
+        let multi = MultiObjective::parse("score, longevity").unwrap();
+
+        // Act
+        let front = multi.pareto_front(&agents, 4);
+
+        // Assert
+        let values: Vec<Vec<f64>> = front.members.iter().map(|m| m.values.clone()).collect();
+        assert_eq!(values, vec![vec![10.0, 0.0], vec![5.0, 4.0]]);
+        assert!(MultiObjective::parse("score,height").is_err());
+    }
+}
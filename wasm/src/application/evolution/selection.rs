@@ -1,16 +1,165 @@
-use crate::domain::agent::{Agent, StrategyType};
+use super::{FitnessEvaluator, SelectionOperators, SharedFitness};
+use crate::domain::agent::Agent;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+// 重み付き適応度・多目的選択を使わないときの親選択の方式（CLI・設定ファイル共通の定義）
+// SUS とボルツマン選択は適応度関数（SimulationConfig::fitness()）の値に掛かる
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionMethod {
+    #[default]
+    Roulette,
+    // 戦略の複雑さに応じてスコアを割り引く
+    ComplexityPenalty,
+    // 対戦数で割ったスコアで選ぶ
+    Normalized,
+    // 確率的普遍抽出（SUS）。等間隔の針で一度に全員分を選ぶ
+    StochasticUniversal,
+    // 温度つきのボルツマン選択。初期温度と焼きなましの予定を持つ
+    Boltzmann(BoltzmannSchedule),
+}
+
+impl SelectionMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SelectionMethod::Roulette => "roulette",
+            SelectionMethod::ComplexityPenalty => "complexity-penalty",
+            SelectionMethod::Normalized => "normalized",
+            SelectionMethod::StochasticUniversal => "sus",
+            SelectionMethod::Boltzmann(_) => "boltzmann",
+        }
+    }
+
+    // 名前のほか "boltzmann:T:RATE[:MIN]" で焼きなましの予定を指定できる
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "roulette" => Ok(SelectionMethod::Roulette),
+            "complexity-penalty" => Ok(SelectionMethod::ComplexityPenalty),
+            "normalized" => Ok(SelectionMethod::Normalized),
+            "sus" => Ok(SelectionMethod::StochasticUniversal),
+            "boltzmann" => Ok(SelectionMethod::Boltzmann(BoltzmannSchedule::default())),
+            _ => match value.strip_prefix("boltzmann:") {
+                Some(schedule) => {
+                    BoltzmannSchedule::parse(schedule).map(SelectionMethod::Boltzmann)
+                }
+                None => Err(format!("Unknown selection method: {value}")),
+            },
+        }
+    }
+
+    // SUS・ボルツマン選択は適応度の値から抽出し、それ以外はスコアのルーレットを回す
+    pub fn samples_fitness(&self) -> bool {
+        matches!(
+            self,
+            SelectionMethod::StochasticUniversal | SelectionMethod::Boltzmann(_)
+        )
+    }
+
+    // generation 世代目の温度で固定した方式（世代交代ごとにこれを選択に渡す）
+    pub fn at_generation(&self, generation: u32) -> Self {
+        match self {
+            SelectionMethod::Boltzmann(schedule) => SelectionMethod::Boltzmann(
+                BoltzmannSchedule::constant(schedule.temperature_at(generation)),
+            ),
+            other => *other,
+        }
+    }
+}
+
+// ボルツマン選択の焼きなまし。温度は世代ごとに cooling_rate 倍になり、min_temperature で止まる
+// 温度が高いほど一様な選択に近く、低いほど適応度の高い個体に集中する
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoltzmannSchedule {
+    pub initial_temperature: f64,
+    pub cooling_rate: f64,
+    pub min_temperature: f64,
+}
+
+impl Default for BoltzmannSchedule {
+    fn default() -> Self {
+        Self {
+            initial_temperature: 10.0,
+            cooling_rate: 0.95,
+            min_temperature: 0.1,
+        }
+    }
+}
+
+impl BoltzmannSchedule {
+    pub fn new(initial_temperature: f64, cooling_rate: f64, min_temperature: f64) -> Self {
+        Self {
+            initial_temperature,
+            cooling_rate,
+            min_temperature,
+        }
+    }
+
+    // 焼きなまさない一定の温度
+    pub fn constant(temperature: f64) -> Self {
+        Self::new(temperature, 1.0, temperature)
+    }
+
+    // "T:RATE" / "T:RATE:MIN"（MIN を省くと既定の 0.1 と T の小さい方）
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected T:RATE or T:RATE:MIN, got {spec}");
+        let values = spec
+            .split(':')
+            .map(|value| value.parse::<f64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<f64>, String>>()?;
+        match values[..] {
+            [temperature, rate] => Ok(Self::new(
+                temperature,
+                rate,
+                Self::default().min_temperature.min(temperature),
+            )),
+            [temperature, rate, min] => Ok(Self::new(temperature, rate, min)),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn temperature_at(&self, generation: u32) -> f64 {
+        let cooled = self.initial_temperature * self.cooling_rate.powf(f64::from(generation));
+        cooled.max(self.min_temperature)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_temperature > 0.0 && self.min_temperature.is_finite()) {
+            return Err(format!(
+                "min temperature must be positive, got {}",
+                self.min_temperature
+            ));
+        }
+        if !(self.initial_temperature >= self.min_temperature
+            && self.initial_temperature.is_finite())
+        {
+            return Err(format!(
+                "initial temperature {} must be at least the min temperature {}",
+                self.initial_temperature, self.min_temperature
+            ));
+        }
+        if !(self.cooling_rate > 0.0 && self.cooling_rate <= 1.0) {
+            return Err(format!(
+                "cooling rate must be in (0, 1], got {}",
+                self.cooling_rate
+            ));
+        }
+        Ok(())
+    }
+}
+
 pub struct RouletteSelection;
 
 impl RouletteSelection {
-    pub fn select_parents(agents: &HashMap<Uuid, Agent>) -> Vec<Agent> {
-        let mut rng = rand::thread_rng();
+    pub fn select_parents<R: Rng + ?Sized>(
+        agents: &HashMap<Uuid, Agent>,
+        rng: &mut R,
+    ) -> Vec<Agent> {
         let mut selected = Vec::new();
 
-        let agents_vec: Vec<&Agent> = agents.values().collect();
+        let agents_vec = Self::ordered(agents);
         if agents_vec.is_empty() {
             return selected;
         }
@@ -53,14 +202,14 @@ impl RouletteSelection {
         selected
     }
 
-    pub fn select_parents_with_penalty(
+    pub fn select_parents_with_penalty<R: Rng + ?Sized>(
         agents: &HashMap<Uuid, Agent>,
         penalty_rate: f32,
+        rng: &mut R,
     ) -> Vec<Agent> {
-        let mut rng = rand::thread_rng();
         let mut selected = Vec::new();
 
-        let agents_vec: Vec<&Agent> = agents.values().collect();
+        let agents_vec = Self::ordered(agents);
         if agents_vec.is_empty() {
             return selected;
         }
@@ -73,11 +222,10 @@ impl RouletteSelection {
             .iter()
             .map(|a| {
                 let base_score = (a.score - min_score + 1) as f32;
-                match a.strategy {
-                    StrategyType::TitForTat | StrategyType::Pavlov => {
-                        base_score * penalty_multiplier
-                    }
-                    _ => base_score,
+                if a.strategy.is_conditional() {
+                    base_score * penalty_multiplier
+                } else {
+                    base_score
                 }
             })
             .collect();
@@ -115,4 +263,195 @@ impl RouletteSelection {
 
         selected
     }
+
+    // 任意の適応度評価でルーレット選択する（最小値を 1 にそろえて全員に機会を残す）
+    pub fn select_parents_by_fitness<R: Rng + ?Sized>(
+        agents: &HashMap<Uuid, Agent>,
+        evaluator: &dyn FitnessEvaluator,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        let mut selected = Vec::new();
+
+        let agents_vec = Self::ordered(agents);
+        if agents_vec.is_empty() {
+            return selected;
+        }
+
+        let fitness = Self::evaluate_all(&agents_vec, evaluator);
+        let min_fitness = fitness.iter().copied().fold(f64::INFINITY, f64::min);
+        let adjusted_fitness: Vec<f64> = fitness.iter().map(|f| f - min_fitness + 1.0).collect();
+
+        let total_fitness: f64 = adjusted_fitness.iter().sum();
+
+        if !total_fitness.is_finite() || total_fitness <= 0.0 {
+            for _ in 0..agents_vec.len() {
+                let index = rng.gen_range(0..agents_vec.len());
+                selected.push(agents_vec[index].clone());
+            }
+            return selected;
+        }
+
+        for _ in 0..agents_vec.len() {
+            let mut random_value = rng.gen_range(0.0..total_fitness);
+            let index = adjusted_fitness
+                .iter()
+                .position(|f| {
+                    random_value -= f;
+                    random_value <= 0.0
+                })
+                .unwrap_or(agents_vec.len() - 1);
+            selected.push(agents_vec[index].clone());
+        }
+
+        selected
+    }
+
+    // ルーレット以外の抽出法で親を選ぶ。SUS は適応度の最小値を 1 にそろえた重み、
+    // ボルツマン選択は exp(適応度 / 温度) の重みを使う（温度は schedule の初期値）
+    pub fn select_parents_by_sampling<R: Rng + ?Sized>(
+        agents: &HashMap<Uuid, Agent>,
+        evaluator: &dyn FitnessEvaluator,
+        method: &SelectionMethod,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        let agents_vec = Self::ordered(agents);
+        if agents_vec.is_empty() {
+            return Vec::new();
+        }
+
+        let fitness = || Self::evaluate_all(&agents_vec, evaluator);
+        let indices = match method {
+            SelectionMethod::StochasticUniversal => {
+                let fitness = fitness();
+                let min_fitness = fitness.iter().copied().fold(f64::INFINITY, f64::min);
+                let weights: Vec<f64> = fitness.iter().map(|f| f - min_fitness + 1.0).collect();
+                SelectionOperators::stochastic_universal(&weights, agents_vec.len(), rng)
+            }
+            SelectionMethod::Boltzmann(schedule) => {
+                let weights =
+                    SelectionOperators::boltzmann_weights(&fitness(), schedule.temperature_at(0));
+                (0..agents_vec.len())
+                    .map(|_| SelectionOperators::roulette(&weights, rng))
+                    .collect()
+            }
+            _ => return Self::select_parents_by_fitness(agents, evaluator, rng),
+        };
+
+        indices
+            .into_iter()
+            .map(|index| agents_vec[index].clone())
+            .collect()
+    }
+
+    // 適応度共有の値（正の値）をずらさずに重みにする。ずらすとニッチ数で割った差が薄まるため
+    pub fn select_parents_by_shared_fitness<R: Rng + ?Sized>(
+        agents: &HashMap<Uuid, Agent>,
+        shared: &SharedFitness,
+        method: &SelectionMethod,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        let agents_vec = Self::ordered(agents);
+        if agents_vec.is_empty() {
+            return Vec::new();
+        }
+
+        let values = Self::evaluate_all(&agents_vec, shared);
+        let indices: Vec<usize> = match method {
+            SelectionMethod::StochasticUniversal => {
+                SelectionOperators::stochastic_universal(&values, agents_vec.len(), rng)
+            }
+            SelectionMethod::Boltzmann(schedule) => {
+                let weights =
+                    SelectionOperators::boltzmann_weights(&values, schedule.temperature_at(0));
+                (0..agents_vec.len())
+                    .map(|_| SelectionOperators::roulette(&weights, rng))
+                    .collect()
+            }
+            _ => (0..agents_vec.len())
+                .map(|_| SelectionOperators::roulette(&values, rng))
+                .collect(),
+        };
+
+        indices
+            .into_iter()
+            .map(|index| agents_vec[index].clone())
+            .collect()
+    }
+
+    // 並列ビルドでは個体ごとの評価を rayon で分ける（結果は個体の順のまま）
+    #[cfg(feature = "parallel")]
+    fn evaluate_all(agents: &[&Agent], evaluator: &dyn FitnessEvaluator) -> Vec<f64> {
+        use rayon::prelude::*;
+        agents
+            .par_iter()
+            .map(|agent| evaluator.evaluate(agent))
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn evaluate_all(agents: &[&Agent], evaluator: &dyn FitnessEvaluator) -> Vec<f64> {
+        agents
+            .iter()
+            .map(|agent| evaluator.evaluate(agent))
+            .collect()
+    }
+
+    // HashMap の走査順に依存せず同じ乱数列で同じ親が選ばれるよう位置順に並べる
+    fn ordered(agents: &HashMap<Uuid, Agent>) -> Vec<&Agent> {
+        let mut agents_vec: Vec<&Agent> = agents.values().collect();
+        agents_vec.sort_by_key(|agent| (agent.position.y, agent.position.x));
+        agents_vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::simulation::SimulationConfig;
+
+    #[test]
+    fn test_selection_method_round_trips_through_config() {
+        for method in [
+            SelectionMethod::Roulette,
+            SelectionMethod::ComplexityPenalty,
+            SelectionMethod::Normalized,
+            SelectionMethod::StochasticUniversal,
+            SelectionMethod::Boltzmann(BoltzmannSchedule::default()),
+        ] {
+            // Arrange & Act
+            let config = SimulationConfig::default().with_selection(method);
+
+            // Assert
+            assert_eq!(config.selection_method(), method);
+            assert_eq!(SelectionMethod::parse(method.name()), Ok(method));
+        }
+        assert!(SelectionMethod::parse("tournament").is_err());
+    }
+
+    #[test]
+    fn test_boltzmann_schedule_cools_down_to_its_floor() {
+        // Arrange
+        let schedule = BoltzmannSchedule::parse("10:0.5:1").unwrap();
+        let method = SelectionMethod::Boltzmann(schedule);
+
+        // Act
+        let temperatures: Vec<f64> = [0, 1, 2, 3, 10]
+            .iter()
+            .map(|&generation| schedule.temperature_at(generation))
+            .collect();
+
+        // Assert
+        assert_eq!(temperatures, vec![10.0, 5.0, 2.5, 1.25, 1.0]);
+        assert_eq!(
+            method.at_generation(2),
+            SelectionMethod::Boltzmann(BoltzmannSchedule::constant(2.5))
+        );
+        assert_eq!(
+            SelectionMethod::parse("boltzmann:2:0.9"),
+            Ok(SelectionMethod::Boltzmann(BoltzmannSchedule::new(
+                2.0, 0.9, 0.1
+            )))
+        );
+        assert!(BoltzmannSchedule::new(1.0, 1.5, 0.1).validate().is_err());
+    }
 }
@@ -1,8 +1,12 @@
-use super::RouletteSelection;
-use crate::application::simulation::SimulationConfig;
-use crate::domain::agent::{Agent, Position};
+use super::{
+    CrossoverMethod, FitnessEvaluator, FitnessFunction, MemoryGenomeOperators, RouletteSelection,
+};
+use crate::application::simulation::{PerformanceMetrics, SimulationConfig, Stopwatch};
+use crate::domain::agent::{Agent, Genotype, Position};
+use crate::domain::grid::Region;
+use rand::seq::SliceRandom;
 use rand::Rng;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 pub struct EvolutionService;
@@ -18,38 +22,146 @@ impl EvolutionService {
         Self
     }
 
-    pub fn evolve(&self, current_agents: &HashMap<Uuid, Agent>) -> Vec<Agent> {
-        self.evolve_with_config(current_agents, &SimulationConfig::default())
+    pub fn evolve<R: Rng + ?Sized>(
+        &self,
+        current_agents: &HashMap<Uuid, Agent>,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        self.evolve_with_config(current_agents, &SimulationConfig::default(), rng)
     }
 
-    pub fn evolve_with_config(
+    pub fn evolve_with_config<R: Rng + ?Sized>(
         &self,
         current_agents: &HashMap<Uuid, Agent>,
         config: &SimulationConfig,
+        rng: &mut R,
     ) -> Vec<Agent> {
+        self.evolve_for_grid(current_agents, config, 100, 100, rng)
+    }
+
+    pub fn evolve_for_grid<R: Rng + ?Sized>(
+        &self,
+        current_agents: &HashMap<Uuid, Agent>,
+        config: &SimulationConfig,
+        width: usize,
+        height: usize,
+        rng: &mut R,
+    ) -> Vec<Agent> {
+        self.evolve_with_diagnostics(
+            current_agents,
+            config,
+            width,
+            height,
+            current_agents.len(),
+            rng,
+        )
+        .agents
+    }
+
+    pub fn evolve_with_diagnostics<R: Rng + ?Sized>(
+        &self,
+        current_agents: &HashMap<Uuid, Agent>,
+        config: &SimulationConfig,
+        width: usize,
+        height: usize,
+        target: usize,
+        rng: &mut R,
+    ) -> EvolutionOutcome {
+        self.evolve_in_region(
+            current_agents,
+            config,
+            Region::new(0, 0, width, height),
+            target,
+            rng,
+        )
+    }
+
+    // 次世代の target 体を region 内の重複しない位置に配置する
+    pub fn evolve_in_region<R: Rng + ?Sized>(
+        &self,
+        current_agents: &HashMap<Uuid, Agent>,
+        config: &SimulationConfig,
+        region: Region,
+        target: usize,
+        rng: &mut R,
+    ) -> EvolutionOutcome {
+        let mut outcome = EvolutionOutcome::default();
         if current_agents.is_empty() {
-            return Vec::new();
+            return outcome;
         }
 
-        let parents = if config.strategy_complexity_penalty_enabled {
-            RouletteSelection::select_parents_with_penalty(
+        // 複雑さペナルティはスコアそのものをルーレットに掛けるときだけ効く
+        // 適応度共有は設定した適応度・抽出法・ペナルティの上に重ねる（多目的選択とは validate で併用を拒む）
+        let selection = Stopwatch::start();
+        let fitness = config.fitness();
+        let penalty_rate = (config.strategy_complexity_penalty_enabled
+            && fitness == FitnessFunction::RawScore
+            && !config.selection.samples_fitness())
+        .then_some(config.strategy_complexity_penalty_rate);
+        let parents = if let Some(multi) = &config.multi_objective {
+            multi.select_parents(current_agents, rng)
+        } else if let Some(sharing) = &config.fitness_sharing {
+            let mut shared = sharing.shared_fitness(current_agents, &fitness);
+            if let Some(rate) = penalty_rate {
+                shared = shared.with_complexity_penalty(current_agents, rate);
+            }
+            RouletteSelection::select_parents_by_shared_fitness(
+                current_agents,
+                &shared,
+                &config.selection,
+                rng,
+            )
+        } else if config.selection.samples_fitness() {
+            RouletteSelection::select_parents_by_sampling(
                 current_agents,
-                config.strategy_complexity_penalty_rate,
+                &fitness,
+                &config.selection,
+                rng,
             )
+        } else if fitness != FitnessFunction::RawScore {
+            RouletteSelection::select_parents_by_fitness(current_agents, &fitness, rng)
+        } else if let Some(rate) = penalty_rate {
+            RouletteSelection::select_parents_with_penalty(current_agents, rate, rng)
         } else {
-            RouletteSelection::select_parents(current_agents)
+            RouletteSelection::select_parents(current_agents, rng)
         };
+        outcome.timings.selection_ms += selection.elapsed_ms();
 
-        let mut new_agents = Vec::new();
-        let mut rng = rand::thread_rng();
+        // 領域の収容数（壁を除くセル数）を超える目標数は収容数に切り詰める
+        let mut open_positions = region.positions();
+        if !config.obstacles.is_empty() {
+            let obstacles: HashSet<Position> = config.obstacles.iter().copied().collect();
+            open_positions.retain(|position| !obstacles.contains(position));
+        }
+        let capacity = open_positions.len();
+        let mut agent_count = target;
+        if agent_count > capacity {
+            outcome.diagnostics.push(format!(
+                "Target population {agent_count} exceeds grid capacity {capacity} ({}x{}); capping at {capacity}",
+                region.width, region.height
+            ));
+            agent_count = capacity;
+        }
 
-        let agent_count = current_agents.len();
-        let grid_positions = self.generate_positions(agent_count);
+        let grid_positions = self.generate_positions(agent_count, open_positions, rng);
 
-        for (_i, position) in grid_positions.iter().enumerate().take(agent_count) {
+        // エリートは最初の位置から順に、変異させずに置く
+        let selection = Stopwatch::start();
+        let elites = Self::fittest(
+            current_agents,
+            config.evolution_strategy.elite_count(),
+            &fitness,
+        );
+        outcome.timings.selection_ms += selection.elapsed_ms();
+        for (index, position) in grid_positions.into_iter().enumerate() {
+            if let Some(elite) = elites.get(index) {
+                outcome
+                    .agents
+                    .push(Genotype::of(elite).spawn(position).with_random_id(rng));
+                continue;
+            }
             if parents.len() < 2 {
-                let agent = Agent::random(*position);
-                new_agents.push(agent);
+                outcome.agents.push(Agent::random(position, rng));
                 continue;
             }
 
@@ -59,46 +171,452 @@ impl EvolutionService {
             let parent1 = &parents[parent1_idx];
             let parent2 = &parents[parent2_idx];
 
-            let mut child = Agent::crossover(parent1, parent2, *position);
-            child.mutate();
+            outcome.agents.push(Self::breed_timed(
+                parent1,
+                parent2,
+                position,
+                config.mutation_rate,
+                config,
+                &mut outcome.timings,
+                rng,
+            ));
+        }
+
+        outcome
+    }
 
-            new_agents.push(child);
+    // 適応度の上位 count 体（同点は位置順）
+    pub fn fittest(
+        agents: &HashMap<Uuid, Agent>,
+        count: usize,
+        fitness: &FitnessFunction,
+    ) -> Vec<Agent> {
+        if count == 0 {
+            return Vec::new();
         }
+        let mut ranked: Vec<(f64, &Agent)> = agents
+            .values()
+            .map(|agent| (fitness.evaluate(agent), agent))
+            .collect();
+        ranked.sort_by(|(fitness_a, a), (fitness_b, b)| {
+            fitness_b
+                .total_cmp(fitness_a)
+                .then((a.position.y, a.position.x).cmp(&(b.position.y, b.position.x)))
+        });
+        ranked
+            .into_iter()
+            .take(count)
+            .map(|(_, agent)| agent.clone())
+            .collect()
+    }
 
-        new_agents
+    // 交叉・突然変異で子を作る。形質（戦略・移動性）と行動表の両方を親から受け継ぐ
+    // 世代交代・エネルギー型の繁殖・死亡出生更新のすべてがこの経路を通る
+    // config にタグや罰があれば、タグ・内集団びいきの強さ・攻撃性も突然変異させる
+    // 子の位置が突然変異率を持つ区域にあれば、mutation_rate の代わりにその率を使う
+    pub fn breed<R: Rng + ?Sized>(
+        parent1: &Agent,
+        parent2: &Agent,
+        position: Position,
+        mutation_rate: f64,
+        config: &SimulationConfig,
+        rng: &mut R,
+    ) -> Agent {
+        let mut timings = PerformanceMetrics::default();
+        Self::breed_timed(
+            parent1,
+            parent2,
+            position,
+            mutation_rate,
+            config,
+            &mut timings,
+            rng,
+        )
     }
 
-    fn generate_positions(&self, count: usize) -> Vec<Position> {
-        let mut positions = Vec::new();
-        let mut rng = rand::thread_rng();
-        let grid_size = 100;
-        let max_positions = grid_size * grid_size;
-
-        // If we need more positions than available, allow overlapping
-        if count > max_positions {
-            for _ in 0..count {
-                let x = rng.gen_range(0..grid_size);
-                let y = rng.gen_range(0..grid_size);
-                positions.push(Position::new(x, y));
+    // breed と同じ。交叉と突然変異にかかった時間を timings に足す
+    fn breed_timed<R: Rng + ?Sized>(
+        parent1: &Agent,
+        parent2: &Agent,
+        position: Position,
+        mutation_rate: f64,
+        config: &SimulationConfig,
+        timings: &mut PerformanceMetrics,
+        rng: &mut R,
+    ) -> Agent {
+        let crossover = Stopwatch::start();
+        let mutation_rate = config
+            .zones
+            .as_ref()
+            .and_then(|zones| zones.mutation_rate_at(&position))
+            .unwrap_or(mutation_rate);
+        let mut child = Agent::crossover(parent1, parent2, position, rng);
+        if config.crossover_method != CrossoverMethod::Average {
+            config
+                .crossover_method
+                .recombine_traits(&mut child, parent1, parent2, rng);
+        }
+        timings.crossover_ms += crossover.elapsed_ms();
+
+        let mutation = Stopwatch::start();
+        child.mutate_with_rate(mutation_rate, rng);
+        Self::inherit_memory_genome(&mut child, parent1, parent2, mutation_rate, rng);
+        if let Some(tags) = &config.tags {
+            tags.mutate(&mut child, rng);
+        }
+        if let Some(punishment) = &config.punishment {
+            punishment.mutate(&mut child, mutation_rate, rng);
+        }
+        timings.mutation_ms += mutation.elapsed_ms();
+        child
+    }
+
+    // 行動表を持つ親がいれば子も表を受け継ぎ、strategy は表から決め直す
+    fn inherit_memory_genome<R: Rng + ?Sized>(
+        child: &mut Agent,
+        parent1: &Agent,
+        parent2: &Agent,
+        mutation_rate: f64,
+        rng: &mut R,
+    ) {
+        let mut genome = match (&parent1.memory_genome, &parent2.memory_genome) {
+            (Some(genome1), Some(genome2)) => {
+                MemoryGenomeOperators::crossover(genome1, genome2, rng)
             }
-        } else {
-            // Try to avoid overlapping positions
-            let mut attempts = 0;
-            for _ in 0..count {
-                loop {
-                    let x = rng.gen_range(0..grid_size);
-                    let y = rng.gen_range(0..grid_size);
-                    let position = Position::new(x, y);
-
-                    if !positions.contains(&position) || attempts > max_positions * 2 {
-                        positions.push(position);
-                        break;
-                    }
-                    attempts += 1;
+            (Some(genome), None) | (None, Some(genome)) => genome.clone(),
+            (None, None) => return,
+        };
+        MemoryGenomeOperators::mutate(&mut genome, mutation_rate, rng);
+        child.strategy = genome.classify();
+        child.memory_genome = Some(genome);
+    }
+
+    // 候補の位置から重複せずに count 個選ぶ（count は候補の数以下であること）
+    fn generate_positions<R: Rng + ?Sized>(
+        &self,
+        count: usize,
+        mut positions: Vec<Position>,
+        rng: &mut R,
+    ) -> Vec<Position> {
+        positions.shuffle(rng);
+        positions.truncate(count);
+        positions
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EvolutionOutcome {
+    pub agents: Vec<Agent>,
+    pub diagnostics: Vec<String>,
+    // 選択・交叉・突然変異にかかった時間（ほかの項目は 0）
+    pub timings: PerformanceMetrics,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::{
+        BoltzmannSchedule, EvolutionStrategyType, FitnessSharing, SelectionMethod,
+    };
+    use crate::domain::agent::{MemoryGenome, MovementStrategy, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use std::collections::HashSet;
+
+    fn population(count: usize, width: usize) -> HashMap<Uuid, Agent> {
+        let mut rng = rand::thread_rng();
+        (0..count)
+            .map(|i| {
+                let agent = Agent::random(Position::new(i % width, i / width), &mut rng);
+                (agent.id, agent)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evolve_caps_target_at_grid_capacity_with_diagnostic() {
+        // Arrange: 盤面には 90 体しかいないが、目標は盤面に収まらない 150 体
+        let agents = population(90, 10);
+        let service = EvolutionService::new();
+
+        // Act
+        let outcome = service.evolve_with_diagnostics(
+            &agents,
+            &SimulationConfig::default(),
+            10,
+            10,
+            150,
+            &mut rand::thread_rng(),
+        );
+
+        // Assert
+        assert_eq!(outcome.agents.len(), 100);
+        assert_eq!(outcome.diagnostics.len(), 1);
+        assert!(outcome.diagnostics[0].contains("capacity 100"));
+    }
+
+    #[test]
+    fn test_configured_fitness_function_decides_parents() {
+        // Arrange: 協力者はスコア 0 でエネルギーが多く、裏切り者はスコア 50 でエネルギーがない
+        let agents: HashMap<Uuid, Agent> = (0..20)
+            .map(|i| {
+                let position = Position::new(i % 5, i / 5);
+                let mut agent = if i % 2 == 0 {
+                    Agent::new(
+                        position,
+                        StrategyType::AllCooperate,
+                        0.0,
+                        MovementStrategy::Settler,
+                    )
+                } else {
+                    Agent::new(
+                        position,
+                        StrategyType::AllDefect,
+                        0.0,
+                        MovementStrategy::Settler,
+                    )
+                };
+                if agent.strategy == StrategyType::AllCooperate {
+                    agent.energy = 1000.0;
+                } else {
+                    agent.score = 50;
                 }
-            }
+                (agent.id, agent)
+            })
+            .collect();
+        let config = SimulationConfig::default().with_mutation_rate(0.0);
+        let energy_config = config
+            .clone()
+            .with_fitness_function(FitnessFunction::EnergyWeighted { energy_weight: 1.0 });
+        let service = EvolutionService::new();
+        let mut rng = rand::thread_rng();
+        let cooperators = |children: Vec<Agent>| {
+            children
+                .iter()
+                .filter(|agent| agent.strategy == StrategyType::AllCooperate)
+                .count()
+        };
+
+        // Act
+        let by_score = cooperators(service.evolve_for_grid(&agents, &config, 5, 4, &mut rng));
+        let by_energy =
+            cooperators(service.evolve_for_grid(&agents, &energy_config, 5, 4, &mut rng));
+
+        // Assert: 調整後の適応度の比はスコアで 51:1、エネルギーで 1:951 なので、子の大半が一方に偏る
+        assert!(by_score <= 5);
+        assert!(by_energy >= 15);
+    }
+
+    #[test]
+    fn test_boltzmann_temperature_controls_selection_pressure() {
+        // Arrange: スコア 100 の裏切り者が 1 体、スコア 0 の協力者が 19 体
+        let agents: HashMap<Uuid, Agent> = (0..20)
+            .map(|i| {
+                let strategy = if i == 0 {
+                    StrategyType::AllDefect
+                } else {
+                    StrategyType::AllCooperate
+                };
+                let mut agent = Agent::new(
+                    Position::new(i % 5, i / 5),
+                    strategy,
+                    0.0,
+                    MovementStrategy::Settler,
+                );
+                agent.score = if i == 0 { 100 } else { 0 };
+                (agent.id, agent)
+            })
+            .collect();
+        let config = SimulationConfig::default().with_mutation_rate(0.0);
+        let cold = config
+            .clone()
+            .with_selection(SelectionMethod::Boltzmann(BoltzmannSchedule::constant(1.0)));
+        let hot =
+            config.with_selection(SelectionMethod::Boltzmann(BoltzmannSchedule::constant(1e6)));
+        let service = EvolutionService::new();
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+        let defectors = |children: Vec<Agent>| {
+            children
+                .iter()
+                .filter(|agent| agent.strategy == StrategyType::AllDefect)
+                .count()
+        };
+
+        // Act
+        let cold_defectors = defectors(service.evolve_for_grid(&agents, &cold, 5, 4, &mut rng));
+        let hot_defectors = defectors(service.evolve_for_grid(&agents, &hot, 5, 4, &mut rng));
+
+        // Assert: 低温では最良の個体だけが親になり、高温ではほぼ一様に選ばれる
+        assert_eq!(cold_defectors, 20);
+        assert!(hot_defectors <= 8);
+    }
+
+    #[test]
+    fn test_fitness_sharing_lets_a_rare_strategy_win_more_parents() {
+        // Arrange: 同じスコアの TitForTat 18 体と AllDefect 2 体
+        let agents: HashMap<Uuid, Agent> = (0..20)
+            .map(|i| {
+                let strategy = if i < 2 {
+                    StrategyType::AllDefect
+                } else {
+                    StrategyType::TitForTat
+                };
+                let mut agent = Agent::new(
+                    Position::new(i % 5, i / 5),
+                    strategy,
+                    0.5,
+                    MovementStrategy::Settler,
+                );
+                agent.score = 10;
+                (agent.id, agent)
+            })
+            .collect();
+        let config = SimulationConfig::default().with_mutation_rate(0.0);
+        let shared = config
+            .clone()
+            .with_fitness_sharing(FitnessSharing::new(0.1));
+        let service = EvolutionService::new();
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let defectors = |children: Vec<Agent>| {
+            children
+                .iter()
+                .filter(|agent| agent.strategy == StrategyType::AllDefect)
+                .count()
+        };
+
+        // Act: 10 世代分の子を数える
+        let (mut plain_count, mut shared_count) = (0, 0);
+        for _ in 0..10 {
+            plain_count += defectors(service.evolve_for_grid(&agents, &config, 5, 4, &mut rng));
+            shared_count += defectors(service.evolve_for_grid(&agents, &shared, 5, 4, &mut rng));
         }
 
-        positions
+        // Assert: 共有なしでは約 1 割、共有ありでは 2 つのニッチが適応度を半分ずつ持つので約 5 割
+        assert!(plain_count < 50);
+        assert!(shared_count > 70);
+    }
+
+    #[test]
+    fn test_elitist_strategy_carries_the_best_genotype_over_unmutated() {
+        // Arrange: 全員が必ず変異する設定で、最高スコアの個体だけ移動性を 0.123456 にしておく
+        let mut agents = population(20, 5);
+        let best_id = *agents.keys().next().unwrap();
+        let best = agents.get_mut(&best_id).unwrap();
+        best.score = 100;
+        best.mobility = 0.123456;
+        let elite = Genotype::of(best);
+        let classic = SimulationConfig::default().with_mutation_rate(1.0);
+        let elitist = classic
+            .clone()
+            .with_evolution_strategy(EvolutionStrategyType::Elitist { elite_count: 1 });
+        let service = EvolutionService::new();
+        let mut rng = rand::thread_rng();
+
+        // Act
+        let with_elite = service.evolve_for_grid(&agents, &elitist, 5, 4, &mut rng);
+        let without = service.evolve_for_grid(&agents, &classic, 5, 4, &mut rng);
+
+        // Assert
+        let carries =
+            |children: &[Agent]| children.iter().any(|child| Genotype::of(child) == elite);
+        assert!(carries(&with_elite));
+        assert!(!carries(&without));
+        assert!(with_elite.iter().all(|child| child.score == 0));
+    }
+
+    #[test]
+    fn test_evolve_fills_target_with_unique_positions() {
+        // Arrange
+        let agents = population(100, 10);
+        let service = EvolutionService::new();
+
+        // Act
+        let outcome = service.evolve_with_diagnostics(
+            &agents,
+            &SimulationConfig::default(),
+            10,
+            10,
+            100,
+            &mut rand::thread_rng(),
+        );
+
+        // Assert
+        let positions: HashSet<Position> = outcome.agents.iter().map(|a| a.position).collect();
+        assert_eq!(outcome.agents.len(), 100);
+        assert_eq!(positions.len(), 100);
+        assert!(outcome.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_breed_keeps_memory_genome_and_traits() {
+        // Arrange
+        let mut rng = rand::thread_rng();
+        let genome = MemoryGenome::from_strategy(StrategyType::Pavlov, 2).unwrap();
+        let parent = Agent::new(
+            Position::new(0, 0),
+            StrategyType::Pavlov,
+            0.3,
+            MovementStrategy::Settler,
+        )
+        .with_memory_genome(genome.clone());
+
+        // Act: 突然変異なし
+        let child = EvolutionService::breed(
+            &parent,
+            &parent,
+            Position::new(1, 0),
+            0.0,
+            &SimulationConfig::default(),
+            &mut rng,
+        );
+
+        // Assert
+        assert_eq!(child.memory_genome, Some(genome));
+        assert_eq!(child.strategy, StrategyType::Pavlov);
+        assert_eq!(child.mobility, 0.3);
+        assert_eq!(child.position, Position::new(1, 0));
+    }
+
+    #[test]
+    fn test_breed_uses_the_configured_crossover_for_continuous_traits() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(11);
+        let parent = |mobility: f64, aggression: f64| {
+            Agent::new(
+                Position::new(0, 0),
+                StrategyType::TitForTat,
+                mobility,
+                MovementStrategy::Settler,
+            )
+            .with_aggression(aggression)
+        };
+        let (parent1, parent2) = (parent(0.2, 0.0), parent(0.8, 1.0));
+        let config = SimulationConfig::default()
+            .with_crossover_method(CrossoverMethod::Blend { alpha: 0.0 });
+
+        // Act
+        let children: Vec<Agent> = (0..20)
+            .map(|_| {
+                EvolutionService::breed(
+                    &parent1,
+                    &parent2,
+                    Position::new(1, 0),
+                    0.0,
+                    &config,
+                    &mut rng,
+                )
+            })
+            .collect();
+
+        // Assert: BLX-0 は両親の区間から一様に選ぶので、平均（0.5）に固まらない
+        assert!(children
+            .iter()
+            .all(|child| (0.2..=0.8).contains(&child.mobility)));
+        assert!(children
+            .iter()
+            .any(|child| (child.mobility - 0.5).abs() > 0.05));
+        assert!(children
+            .iter()
+            .any(|child| (child.aggression - 0.5).abs() > 0.05));
     }
 }
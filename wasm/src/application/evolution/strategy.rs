@@ -0,0 +1,160 @@
+use super::{AdaptiveMutation, MultiObjective, Objective};
+use crate::application::simulation::{ReproductionModel, SimulationConfig, SteadyStateConfig};
+use crate::domain::grid::DemeStructure;
+use serde::{Deserialize, Serialize};
+
+// 進化の方式。SimulationConfig に組み込む設定の組み合わせを名前で選ぶ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EvolutionStrategyType {
+    // 世代ごとに全個体をルーレット選択の子で置き換える
+    #[default]
+    Classic,
+    // Classic に加え、適応度の上位 elite_count 体を変異させずに次世代へ残す
+    Elitist {
+        elite_count: usize,
+    },
+    // 1 ステップごとに一部の個体だけが戦略を更新する（模倣）
+    SteadyState,
+    // 盤面を columns x rows の島に分け、島ごとに世代交代して定期的に上位個体を移住させる
+    IslandModel {
+        columns: usize,
+        rows: usize,
+    },
+    // 戦略の多様性が落ちたときに突然変異率を上げる
+    Adaptive,
+    // スコアと協力率のパレート選択
+    MultiObjective,
+}
+
+impl EvolutionStrategyType {
+    pub const DEFAULT_ELITE_COUNT: usize = 2;
+
+    // "classic" / "elitist" / "elitist:N" / "steady_state" / "island_model" / "island_model:CxR"
+    // "adaptive" / "multi_objective"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "classic" => Some(EvolutionStrategyType::Classic),
+            "elitist" => Some(EvolutionStrategyType::Elitist {
+                elite_count: Self::DEFAULT_ELITE_COUNT,
+            }),
+            "steady_state" => Some(EvolutionStrategyType::SteadyState),
+            "island_model" => Some(EvolutionStrategyType::IslandModel {
+                columns: 2,
+                rows: 2,
+            }),
+            "adaptive" => Some(EvolutionStrategyType::Adaptive),
+            "multi_objective" => Some(EvolutionStrategyType::MultiObjective),
+            _ => {
+                if let Some(count) = name.strip_prefix("elitist:") {
+                    let elite_count = count.parse().ok()?;
+                    return Some(EvolutionStrategyType::Elitist { elite_count });
+                }
+                let (columns, rows) = name.strip_prefix("island_model:")?.split_once('x')?;
+                Some(EvolutionStrategyType::IslandModel {
+                    columns: columns.parse().ok()?,
+                    rows: rows.parse().ok()?,
+                })
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EvolutionStrategyType::Classic => "classic",
+            EvolutionStrategyType::Elitist { .. } => "elitist",
+            EvolutionStrategyType::SteadyState => "steady_state",
+            EvolutionStrategyType::IslandModel { .. } => "island_model",
+            EvolutionStrategyType::Adaptive => "adaptive",
+            EvolutionStrategyType::MultiObjective => "multi_objective",
+        }
+    }
+
+    // 世代交代で変異させずに残す個体数
+    pub fn elite_count(&self) -> usize {
+        match self {
+            EvolutionStrategyType::Elitist { elite_count } => *elite_count,
+            _ => 0,
+        }
+    }
+}
+
+pub struct EvolutionStrategyFactory;
+
+impl EvolutionStrategyFactory {
+    // 島モデルの既定の定期移住（10 世代ごとに各島の上位 2 体）
+    const ISLAND_MIGRATION_INTERVAL: u32 = 10;
+    const ISLAND_MIGRANTS: usize = 2;
+
+    // config.evolution_strategy に必要な設定を組み込んだ設定を作る
+    // 利用者が個別に設定した項目（繁殖モデル・島・適応的突然変異・目的）はそのまま使う
+    pub fn build(config: &SimulationConfig) -> SimulationConfig {
+        let mut config = config.clone();
+        match config.evolution_strategy {
+            EvolutionStrategyType::Classic | EvolutionStrategyType::Elitist { .. } => {}
+            EvolutionStrategyType::SteadyState => {
+                if config.reproduction == ReproductionModel::Generational {
+                    config.reproduction =
+                        ReproductionModel::SteadyState(SteadyStateConfig::default());
+                }
+            }
+            EvolutionStrategyType::IslandModel { columns, rows } => {
+                config.deme_structure.get_or_insert_with(|| {
+                    DemeStructure::new(columns, rows).with_scheduled_migration(
+                        Self::ISLAND_MIGRATION_INTERVAL,
+                        Self::ISLAND_MIGRANTS,
+                    )
+                });
+            }
+            EvolutionStrategyType::Adaptive => {
+                config
+                    .adaptive_mutation
+                    .get_or_insert(AdaptiveMutation::Diversity {
+                        threshold: 0.5,
+                        boost: 3.0,
+                    });
+            }
+            EvolutionStrategyType::MultiObjective => {
+                config.multi_objective.get_or_insert_with(|| {
+                    MultiObjective::new(vec![Objective::Score, Objective::Cooperation])
+                });
+            }
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_fills_in_only_what_the_strategy_needs() {
+        // Arrange
+        let islands = SimulationConfig::default()
+            .with_evolution_strategy(EvolutionStrategyType::from_name("island_model:3x1").unwrap());
+        let custom_islands = islands
+            .clone()
+            .with_deme_structure(DemeStructure::new(1, 2));
+        let steady =
+            SimulationConfig::default().with_evolution_strategy(EvolutionStrategyType::SteadyState);
+
+        // Act
+        let built = EvolutionStrategyFactory::build(&islands);
+        let kept = EvolutionStrategyFactory::build(&custom_islands);
+        let steady = EvolutionStrategyFactory::build(&steady);
+
+        // Assert
+        let demes = built.deme_structure.unwrap();
+        assert_eq!((demes.columns, demes.rows, demes.migrants), (3, 1, 2));
+        assert_eq!(kept.deme_structure, Some(DemeStructure::new(1, 2)));
+        assert!(matches!(
+            steady.reproduction,
+            ReproductionModel::SteadyState(_)
+        ));
+        assert_eq!(
+            EvolutionStrategyType::from_name("elitist:4").map(|kind| kind.elite_count()),
+            Some(4)
+        );
+        assert_eq!(EvolutionStrategyType::from_name("island_model:3"), None);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod analysis;
 pub mod evolution;
 pub mod simulation;
@@ -0,0 +1,522 @@
+use crate::domain::agent::{Action, Agent, GameHistory, GameRecord, StrategyType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+// 個体の対戦履歴をどこまで残すか。既定は 1 体あたり直近 10 戦（従来どおり）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleHistoryRetention {
+    PerAgent(usize),
+    // 全個体の合計の上限。個体数で等分した件数を 1 体あたりの上限にする
+    // 個体数より少なくても 1 体 1 件は残す（直前の手を参照する戦略のため）
+    Total(usize),
+    // 直近 N ステップの対戦だけを残す（件数の上限はなし）
+    LastSteps(u32),
+}
+
+impl Default for BattleHistoryRetention {
+    fn default() -> Self {
+        BattleHistoryRetention::PerAgent(GameHistory::DEFAULT_MAX_HISTORY)
+    }
+}
+
+impl BattleHistoryRetention {
+    // "per_agent:N" / "total:N" / "last_steps:N"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected per_agent:N, total:N or last_steps:N, got {spec}");
+        let (kind, value) = spec.split_once(':').ok_or_else(invalid)?;
+        match kind {
+            "per_agent" => value.parse().map(Self::PerAgent).map_err(|_| invalid()),
+            "total" => value.parse().map(Self::Total).map_err(|_| invalid()),
+            "last_steps" => value.parse().map(Self::LastSteps).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+
+    // 方針に合わせて各個体の履歴を切り詰め、前回以降に捨てた記録の件数を返す
+    // （対戦中に 1 体あたりの上限で押し出された分も含む）
+    pub fn compact(&self, agents: &mut HashMap<Uuid, Agent>) -> u64 {
+        let per_agent_total = match self {
+            BattleHistoryRetention::Total(total) => Some((total / agents.len().max(1)).max(1)),
+            _ => None,
+        };
+        agents
+            .values_mut()
+            .map(|agent| {
+                match self {
+                    BattleHistoryRetention::PerAgent(max) => agent.history.set_max_history(*max),
+                    BattleHistoryRetention::Total(_) => agent
+                        .history
+                        .set_max_history(per_agent_total.unwrap_or_default()),
+                    BattleHistoryRetention::LastSteps(steps) => {
+                        agent.history.set_max_history(usize::MAX);
+                        agent.history.retain_since(agent.age.saturating_sub(*steps));
+                    }
+                }
+                agent.history.take_evicted()
+            })
+            .sum()
+    }
+}
+
+// 対戦履歴が使っている件数と、これまでに捨てた件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BattleHistoryUsage {
+    pub retained_records: usize,
+    pub evicted_records: u64,
+}
+
+// 個体の対戦履歴の照会条件。round の範囲と相手で絞り込んでから、古い順に offset 件飛ばして limit 件返す
+// aggregate では対戦ごとの記録の代わりに相手ごとの集計を（初出順で）ページに切る
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BattleHistoryQuery {
+    pub opponent: Option<Uuid>,
+    pub from_round: Option<u64>,
+    pub to_round: Option<u64>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub aggregate: bool,
+}
+
+// 相手ごとの集計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpponentSummary {
+    pub opponent_id: Uuid,
+    pub games: usize,
+    // 双方が協力した対戦の割合
+    pub mutual_cooperation_rate: f64,
+    pub average_payoff: f64,
+}
+
+// total はページに切る前の件数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum BattleHistoryPage {
+    Records {
+        total: usize,
+        records: Vec<GameRecord>,
+    },
+    Summaries {
+        total: usize,
+        summaries: Vec<OpponentSummary>,
+    },
+}
+
+impl BattleHistoryQuery {
+    pub fn run(&self, history: &GameHistory) -> BattleHistoryPage {
+        let matching: Vec<&GameRecord> = history
+            .records()
+            .filter(|record| self.matches(record))
+            .collect();
+
+        if self.aggregate {
+            let summaries = Self::summarize(&matching);
+            BattleHistoryPage::Summaries {
+                total: summaries.len(),
+                summaries: self.page(summaries),
+            }
+        } else {
+            BattleHistoryPage::Records {
+                total: matching.len(),
+                records: self.page(matching.into_iter().cloned().collect()),
+            }
+        }
+    }
+
+    fn matches(&self, record: &GameRecord) -> bool {
+        self.opponent
+            .is_none_or(|opponent| record.opponent_id == opponent)
+            && self.from_round.is_none_or(|from| record.round >= from)
+            && self.to_round.is_none_or(|to| record.round <= to)
+    }
+
+    fn page<T>(&self, items: Vec<T>) -> Vec<T> {
+        let items = items.into_iter().skip(self.offset);
+        match self.limit {
+            Some(limit) => items.take(limit).collect(),
+            None => items.collect(),
+        }
+    }
+
+    fn summarize(records: &[&GameRecord]) -> Vec<OpponentSummary> {
+        let mut summaries: Vec<OpponentSummary> = Vec::new();
+        let mut mutual_cooperations: Vec<usize> = Vec::new();
+        let mut payoffs: Vec<i64> = Vec::new();
+
+        for record in records {
+            let index = match summaries
+                .iter()
+                .position(|summary| summary.opponent_id == record.opponent_id)
+            {
+                Some(index) => index,
+                None => {
+                    summaries.push(OpponentSummary {
+                        opponent_id: record.opponent_id,
+                        games: 0,
+                        mutual_cooperation_rate: 0.0,
+                        average_payoff: 0.0,
+                    });
+                    mutual_cooperations.push(0);
+                    payoffs.push(0);
+                    summaries.len() - 1
+                }
+            };
+            summaries[index].games += 1;
+            if record.my_action == Action::Cooperate && record.opponent_action == Action::Cooperate
+            {
+                mutual_cooperations[index] += 1;
+            }
+            payoffs[index] += record.payoff as i64;
+        }
+
+        for (index, summary) in summaries.iter_mut().enumerate() {
+            let games = summary.games as f64;
+            summary.mutual_cooperation_rate = mutual_cooperations[index] as f64 / games;
+            summary.average_payoff = payoffs[index] as f64 / games;
+        }
+        summaries
+    }
+}
+
+// 利得ごとの記録数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffCount {
+    pub payoff: i32,
+    pub count: usize,
+}
+
+// 戦略の組（記録の持ち主の戦略と相手の戦略）ごとの集計。相手が盤面にいなければ opponent_strategy は None
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyPairStats {
+    pub strategy: StrategyType,
+    pub opponent_strategy: Option<StrategyType>,
+    pub records: usize,
+    // 持ち主が協力した割合
+    pub cooperation_rate: f64,
+    pub mutual_cooperation_rate: f64,
+    pub average_payoff: f64,
+}
+
+// 盤面の全個体に残っている対戦記録の集計。記録は持ち主ごとにあるので、両者が残っている対戦は 2 件と数える
+// total_games は相手の記録と重ならないよう、相手も盤面にいる対戦は ID の小さい側の記録だけを数えたもの
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalBattleStats {
+    pub total_records: usize,
+    pub total_games: usize,
+    pub cooperation_frequency: f64,
+    pub mutual_cooperation_rate: f64,
+    pub mutual_defection_rate: f64,
+    pub average_payoff: f64,
+    // 利得の小さい順
+    pub payoff_distribution: Vec<PayoffCount>,
+    // 戦略 ID の順（相手がいないものは各戦略の最後）
+    pub strategy_pairs: Vec<StrategyPairStats>,
+}
+
+impl GlobalBattleStats {
+    pub fn from_agents(agents: &HashMap<Uuid, Agent>) -> Self {
+        #[derive(Default)]
+        struct Tally {
+            records: usize,
+            cooperations: usize,
+            mutual_cooperations: usize,
+            payoff: i64,
+        }
+
+        let mut stats = GlobalBattleStats::default();
+        let mut total = Tally::default();
+        let mut mutual_defections = 0;
+        let mut payoffs: BTreeMap<i32, usize> = BTreeMap::new();
+        let mut pairs: HashMap<(StrategyType, Option<StrategyType>), Tally> = HashMap::new();
+
+        for agent in agents.values() {
+            for record in agent.history.records() {
+                let opponent = agents.get(&record.opponent_id);
+                if opponent.is_none_or(|_| agent.id < record.opponent_id) {
+                    stats.total_games += 1;
+                }
+                let cooperated = record.my_action == Action::Cooperate;
+                let mutual = cooperated && record.opponent_action == Action::Cooperate;
+                if !cooperated && record.opponent_action == Action::Defect {
+                    mutual_defections += 1;
+                }
+                *payoffs.entry(record.payoff).or_default() += 1;
+                let key = (agent.strategy, opponent.map(|opponent| opponent.strategy));
+                for tally in [&mut total, pairs.entry(key).or_default()] {
+                    tally.records += 1;
+                    tally.cooperations += usize::from(cooperated);
+                    tally.mutual_cooperations += usize::from(mutual);
+                    tally.payoff += record.payoff as i64;
+                }
+            }
+        }
+
+        if total.records == 0 {
+            return stats;
+        }
+        let rate = |count: usize, records: usize| count as f64 / records as f64;
+        stats.total_records = total.records;
+        stats.cooperation_frequency = rate(total.cooperations, total.records);
+        stats.mutual_cooperation_rate = rate(total.mutual_cooperations, total.records);
+        stats.mutual_defection_rate = rate(mutual_defections, total.records);
+        stats.average_payoff = total.payoff as f64 / total.records as f64;
+        stats.payoff_distribution = payoffs
+            .into_iter()
+            .map(|(payoff, count)| PayoffCount { payoff, count })
+            .collect();
+        stats.strategy_pairs = pairs
+            .into_iter()
+            .map(|((strategy, opponent_strategy), tally)| StrategyPairStats {
+                strategy,
+                opponent_strategy,
+                records: tally.records,
+                cooperation_rate: rate(tally.cooperations, tally.records),
+                mutual_cooperation_rate: rate(tally.mutual_cooperations, tally.records),
+                average_payoff: tally.payoff as f64 / tally.records as f64,
+            })
+            .collect();
+        stats.strategy_pairs.sort_by_key(|pair| {
+            (
+                pair.strategy.id(),
+                pair.opponent_strategy.map_or(u16::MAX, |s| s.id() as u16),
+            )
+        });
+        stats
+    }
+
+    // strategy から見た opponent_strategy との集計
+    pub fn pair(
+        &self,
+        strategy: StrategyType,
+        opponent_strategy: StrategyType,
+    ) -> Option<&StrategyPairStats> {
+        self.strategy_pairs.iter().find(|pair| {
+            pair.strategy == strategy && pair.opponent_strategy == Some(opponent_strategy)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position};
+
+    fn history(rounds: &[(Uuid, Action, Action, i32)]) -> GameHistory {
+        let mut history = GameHistory::new();
+        for &(opponent, my_action, opponent_action, payoff) in rounds {
+            history.add_game(opponent, my_action, opponent_action, payoff);
+        }
+        history
+    }
+
+    #[test]
+    fn test_query_filters_rounds_and_pages_records() {
+        // Arrange
+        let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let history = history(&[
+            (a, Action::Cooperate, Action::Cooperate, 3),
+            (b, Action::Cooperate, Action::Defect, 0),
+            (a, Action::Defect, Action::Cooperate, 5),
+            (b, Action::Defect, Action::Defect, 1),
+        ]);
+        let query = BattleHistoryQuery {
+            from_round: Some(1),
+            offset: 1,
+            limit: Some(1),
+            ..BattleHistoryQuery::default()
+        };
+
+        // Act
+        let page = query.run(&history);
+
+        // Assert: round 1〜3 の 3 件のうち 2 件目
+        let BattleHistoryPage::Records { total, records } = page else {
+            panic!("expected records");
+        };
+        assert_eq!(total, 3);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].round, 2);
+        assert_eq!(records[0].opponent_id, a);
+    }
+
+    #[test]
+    fn test_retention_policies_evict_and_count_records() {
+        // Arrange
+        let opponent = Uuid::from_u128(1);
+        let mut agents = HashMap::new();
+        for x in 0..2 {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                StrategyType::TitForTat,
+                0.0,
+                MovementStrategy::Settler,
+            );
+            for _ in 0..4 {
+                agent.add_game_result(opponent, Action::Cooperate, Action::Cooperate, 3);
+                agent.age += 1;
+            }
+            agents.insert(agent.id, agent);
+        }
+        let retained = |agents: &HashMap<Uuid, Agent>| -> Vec<usize> {
+            agents.values().map(|agent| agent.history.len()).collect()
+        };
+
+        // Act & Assert: 直近 3 ステップ → 合計 4 件 → 1 体 1 件
+        assert_eq!(BattleHistoryRetention::LastSteps(3).compact(&mut agents), 2);
+        assert_eq!(retained(&agents), vec![3, 3]);
+        assert_eq!(BattleHistoryRetention::Total(4).compact(&mut agents), 2);
+        assert_eq!(retained(&agents), vec![2, 2]);
+        assert_eq!(BattleHistoryRetention::PerAgent(1).compact(&mut agents), 2);
+        assert_eq!(retained(&agents), vec![1, 1]);
+        assert_eq!(
+            BattleHistoryRetention::parse("last_steps:5"),
+            Ok(BattleHistoryRetention::LastSteps(5))
+        );
+        assert!(BattleHistoryRetention::parse("forever").is_err());
+    }
+
+    #[test]
+    fn test_total_below_population_keeps_last_record_per_agent() {
+        // Arrange: 3 体に合計 2 件の上限
+        let opponent = Uuid::from_u128(1);
+        let mut agents = HashMap::new();
+        for x in 0..3 {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                StrategyType::TitForTat,
+                0.0,
+                MovementStrategy::Settler,
+            );
+            agent.add_game_result(opponent, Action::Cooperate, Action::Cooperate, 3);
+            agent.add_game_result(opponent, Action::Cooperate, Action::Defect, 0);
+            agents.insert(agent.id, agent);
+        }
+
+        // Act
+        let evicted = BattleHistoryRetention::Total(2).compact(&mut agents);
+        for agent in agents.values_mut() {
+            agent.add_game_result(opponent, Action::Defect, Action::Cooperate, 5);
+        }
+        let evicted_after_battle = BattleHistoryRetention::Total(2).compact(&mut agents);
+
+        // Assert: 上限は 1 体 1 件に切り上がり、直前の相手の手が残る
+        assert_eq!(evicted, 3);
+        assert_eq!(evicted_after_battle, 3);
+        for agent in agents.values() {
+            assert_eq!(agent.history.len(), 1);
+            let last = agent.history.records().last().unwrap();
+            assert_eq!(last.opponent_action, Action::Cooperate);
+            assert_eq!(last.my_action, Action::Defect);
+        }
+    }
+
+    #[test]
+    fn test_zero_cap_history_keeps_nothing_and_counts_no_phantom_evictions() {
+        // Arrange
+        let mut history = GameHistory::new();
+        history.set_max_history(0);
+
+        // Act
+        for _ in 0..3 {
+            history.add_game(Uuid::from_u128(1), Action::Cooperate, Action::Cooperate, 3);
+        }
+
+        // Assert
+        assert_eq!(history.len(), 0);
+        assert_eq!(history.take_evicted(), 0);
+    }
+
+    #[test]
+    fn test_aggregate_summarizes_per_opponent() {
+        // Arrange
+        let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let history = history(&[
+            (a, Action::Cooperate, Action::Cooperate, 3),
+            (b, Action::Cooperate, Action::Defect, 0),
+            (a, Action::Defect, Action::Cooperate, 5),
+        ]);
+        let query = BattleHistoryQuery {
+            aggregate: true,
+            ..BattleHistoryQuery::default()
+        };
+
+        // Act
+        let page = query.run(&history);
+
+        // Assert
+        let BattleHistoryPage::Summaries { total, summaries } = page else {
+            panic!("expected summaries");
+        };
+        assert_eq!(total, 2);
+        assert_eq!(summaries[0].opponent_id, a);
+        assert_eq!(summaries[0].games, 2);
+        assert_eq!(summaries[0].mutual_cooperation_rate, 0.5);
+        assert_eq!(summaries[0].average_payoff, 4.0);
+        assert_eq!(summaries[1].mutual_cooperation_rate, 0.0);
+    }
+
+    #[test]
+    fn test_global_stats_tally_strategy_pairs_and_payoffs() {
+        // Arrange: TitForTat と AllDefect が 2 回対戦し、もう 1 戦は盤面にいない相手と
+        let mut agents = HashMap::new();
+        let mut tft = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        let mut defector = Agent::new(
+            Position::new(1, 0),
+            StrategyType::AllDefect,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        for tft_action in [Action::Cooperate, Action::Defect] {
+            tft.add_game_result(defector.id, tft_action, Action::Defect, 0);
+            defector.add_game_result(tft.id, Action::Defect, tft_action, 5);
+        }
+        tft.add_game_result(Uuid::new_v4(), Action::Cooperate, Action::Cooperate, 3);
+        agents.insert(tft.id, tft);
+        agents.insert(defector.id, defector);
+
+        // Act
+        let stats = GlobalBattleStats::from_agents(&agents);
+
+        // Assert: 記録は 5 件、対戦は 3 回
+        assert_eq!(stats.total_records, 5);
+        assert_eq!(stats.total_games, 3);
+        assert_eq!(stats.cooperation_frequency, 0.4);
+        assert_eq!(stats.mutual_cooperation_rate, 0.2);
+        assert_eq!(
+            stats.payoff_distribution,
+            vec![
+                PayoffCount {
+                    payoff: 0,
+                    count: 2
+                },
+                PayoffCount {
+                    payoff: 3,
+                    count: 1
+                },
+                PayoffCount {
+                    payoff: 5,
+                    count: 2
+                },
+            ]
+        );
+        let tft_vs_defector = stats
+            .pair(StrategyType::TitForTat, StrategyType::AllDefect)
+            .unwrap();
+        assert_eq!(tft_vs_defector.records, 2);
+        assert_eq!(tft_vs_defector.cooperation_rate, 0.5);
+        assert_eq!(tft_vs_defector.mutual_cooperation_rate, 0.0);
+        assert_eq!(stats.strategy_pairs.len(), 3);
+        assert_eq!(stats.strategy_pairs[2].strategy, StrategyType::TitForTat);
+        assert_eq!(stats.strategy_pairs[2].opponent_strategy, None);
+        assert_eq!(
+            GlobalBattleStats::from_agents(&HashMap::new()),
+            GlobalBattleStats::default()
+        );
+    }
+}
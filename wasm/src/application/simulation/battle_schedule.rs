@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 対戦の組を、同じエージェントを 2 回含まないバッチに分ける（貪欲な辺彩色）
+// バッチ内の対戦は互いに独立なので並列に実行できる。バッチ数は最大でも 2 × 近傍数 − 1
+pub fn conflict_free_batches(pairs: &[(Uuid, Uuid)]) -> Vec<Vec<(Uuid, Uuid)>> {
+    let mut batches: Vec<Vec<(Uuid, Uuid)>> = Vec::new();
+    let mut used: HashMap<Uuid, Vec<usize>> = HashMap::with_capacity(pairs.len());
+
+    for &(id1, id2) in pairs {
+        let index = (0..)
+            .find(|index| {
+                !used
+                    .get(&id1)
+                    .is_some_and(|batches| batches.contains(index))
+                    && !used
+                        .get(&id2)
+                        .is_some_and(|batches| batches.contains(index))
+            })
+            .unwrap_or_default();
+
+        if index == batches.len() {
+            batches.push(Vec::new());
+        }
+        batches[index].push((id1, id2));
+        used.entry(id1).or_default().push(index);
+        used.entry(id2).or_default().push(index);
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_batches_cover_all_pairs_without_shared_agents() {
+        // Arrange: 4×4 の格子で右と下の隣接ペアを作る
+        let ids: Vec<Uuid> = (0..16).map(|_| Uuid::new_v4()).collect();
+        let mut pairs = Vec::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                if x + 1 < 4 {
+                    pairs.push((ids[y * 4 + x], ids[y * 4 + x + 1]));
+                }
+                if y + 1 < 4 {
+                    pairs.push((ids[y * 4 + x], ids[(y + 1) * 4 + x]));
+                }
+            }
+        }
+
+        // Act
+        let batches = conflict_free_batches(&pairs);
+
+        // Assert: すべての組がちょうど 1 回現れ、各バッチで同じエージェントが重複しない
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), pairs.len());
+        for batch in &batches {
+            let mut seen = HashSet::new();
+            for (id1, id2) in batch {
+                assert!(seen.insert(*id1));
+                assert!(seen.insert(*id2));
+            }
+        }
+        // 近傍数 4 なのでバッチ数は 2×4−1 以下
+        assert!(batches.len() <= 7);
+    }
+}
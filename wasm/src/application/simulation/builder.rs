@@ -0,0 +1,203 @@
+use super::{ConfigViolation, SimulationConfig, SimulationService};
+use crate::domain::agent::Agent;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 世界の大きさ・個体数・設定を名前付きで組み立てる（位置引数の取り違えを防ぐ）
+// SimulationConfig::builder().world(50, 50).population(100).configure(|c| c.with_seed(1)).build()
+#[derive(Debug, Clone)]
+pub struct SimulationBuilder {
+    width: usize,
+    height: usize,
+    agent_count: usize,
+    turns_per_generation: Option<u32>,
+    // 設定するとランダムな初期集団の代わりにこの個体を置く
+    agents: Option<HashMap<Uuid, Agent>>,
+    config: SimulationConfig,
+}
+
+impl Default for SimulationBuilder {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            height: 100,
+            agent_count: 1000,
+            turns_per_generation: None,
+            agents: None,
+            config: SimulationConfig::default(),
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn builder() -> SimulationBuilder {
+        SimulationBuilder::default()
+    }
+}
+
+impl SimulationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn world(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn population(mut self, agent_count: usize) -> Self {
+        self.agent_count = agent_count;
+        self
+    }
+
+    // 書き出した個体で始める（個体数は個体の数になる）。盤面の外の個体は空きセルへ移す
+    pub fn with_agents(mut self, agents: HashMap<Uuid, Agent>) -> Self {
+        self.agent_count = agents.len();
+        self.agents = Some(agents);
+        self
+    }
+
+    pub fn turns_per_generation(mut self, turns: u32) -> Self {
+        self.turns_per_generation = Some(turns);
+        self
+    }
+
+    pub fn config(mut self, config: SimulationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // SimulationConfig の with_* をそのまま使う
+    pub fn configure(mut self, update: impl FnOnce(SimulationConfig) -> SimulationConfig) -> Self {
+        self.config = update(self.config);
+        self
+    }
+
+    pub fn seed(self, seed: u64) -> Self {
+        self.configure(|config| config.with_seed(seed))
+    }
+
+    pub fn mutation_rate(self, rate: f64) -> Self {
+        self.configure(|config| config.with_mutation_rate(rate))
+    }
+
+    pub fn get_config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    pub fn validate(&self) -> Vec<ConfigViolation> {
+        let mut violations = self
+            .config
+            .validate_for(self.width, self.height, self.agent_count);
+        if self.turns_per_generation == Some(0) {
+            violations.push(ConfigViolation::new(
+                "turns_per_generation",
+                "must be at least 1",
+            ));
+        }
+        violations
+    }
+
+    pub fn build(self) -> Result<SimulationService, String> {
+        let violations = self.validate();
+        if !violations.is_empty() {
+            return Err(format!(
+                "Invalid configuration: {}",
+                ConfigViolation::join(&violations)
+            ));
+        }
+        let mut simulation = match self.agents {
+            Some(agents) => {
+                SimulationService::with_agents(self.width, self.height, agents, self.config)?
+            }
+            None => SimulationService::with_config(
+                self.width,
+                self.height,
+                self.agent_count,
+                self.config,
+            )?,
+        };
+        if let Some(turns) = self.turns_per_generation {
+            simulation.set_turns_per_generation(turns);
+        }
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_creates_configured_simulation() {
+        // Arrange & Act
+        let simulation = SimulationConfig::builder()
+            .world(20, 10)
+            .population(30)
+            .turns_per_generation(5)
+            .seed(9)
+            .configure(|config| config.with_action_noise(0.1))
+            .build()
+            .unwrap();
+
+        // Assert
+        assert_eq!(simulation.get_grid_size(), (20, 10));
+        assert_eq!(simulation.get_agent_count(), 30);
+        assert_eq!(simulation.get_turns_per_generation(), 5);
+        assert_eq!(simulation.get_config().seed, Some(9));
+        assert_eq!(simulation.get_config().action_noise, 0.1);
+    }
+
+    #[test]
+    fn test_builder_seeds_the_population_from_exported_agents() {
+        // Arrange: 20x20 で育った集団を 10x10 に移植する
+        let source = SimulationConfig::builder()
+            .world(20, 20)
+            .population(30)
+            .seed(3)
+            .build()
+            .unwrap();
+        let agents: HashMap<Uuid, Agent> = source
+            .get_agents()
+            .into_iter()
+            .map(|agent| (agent.id, agent))
+            .collect();
+
+        // Act
+        let transplanted = SimulationConfig::builder()
+            .world(10, 10)
+            .seed(3)
+            .with_agents(agents.clone())
+            .build()
+            .unwrap();
+
+        // Assert: 個体はそのまま（盤面の外にいた個体だけ位置が変わる）
+        assert_eq!(transplanted.get_agent_count(), 30);
+        for agent in transplanted.get_agents() {
+            assert_eq!(agent.strategy, agents[&agent.id].strategy);
+            assert!(agent.position.x < 10 && agent.position.y < 10);
+        }
+        let moved = agents
+            .values()
+            .filter(|agent| agent.position.x >= 10 || agent.position.y >= 10)
+            .count();
+        assert_eq!(transplanted.get_diagnostics().len(), usize::from(moved > 0));
+    }
+
+    #[test]
+    fn test_builder_reports_violations_before_building() {
+        // Arrange
+        let builder = SimulationConfig::builder()
+            .world(5, 5)
+            .population(0)
+            .turns_per_generation(0);
+
+        // Act
+        let fields: Vec<String> = builder.validate().into_iter().map(|v| v.field).collect();
+
+        // Assert
+        assert_eq!(fields, vec!["agent_count", "turns_per_generation"]);
+        assert!(builder.build().is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// 長い実行をステップ境界で打ち切るためのフラグ（複製は同じフラグを共有する）
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    // 要求を消費する（打ち切った実行の後、次の実行に持ち越さない）
+    pub fn take(&self) -> bool {
+        self.cancelled.swap(false, Ordering::SeqCst)
+    }
+}
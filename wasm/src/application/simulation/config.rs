@@ -1,7 +1,96 @@
+use super::{
+    BattleHistoryRetention, EvolutionTracker, ExtinctionPolicy, InitialPopulationSpec,
+    LifecycleConfig, ReproductionModel, TrackingTarget,
+};
+use crate::application::evolution::{
+    AdaptiveMutation, CrossoverMethod, EvolutionStrategyType, FitnessFunction, FitnessSharing,
+    MultiObjective, SelectionMethod, WeightedFitness,
+};
+use crate::domain::agent::{Position, TagConfig};
+use crate::domain::game::{
+    GameType, PayoffMatrix, PayoffSchedule, PunishmentConfig, ReputationConfig,
+};
+use crate::domain::grid::{
+    DemeStructure, MigrationModel, MovementPolicyKind, NeighborhoodType, NetworkConfig,
+    PlacementPattern, ResourceConfig, ZoneMap,
+};
+use serde::{Deserialize, Serialize};
+
+// 項目追加後も古いスナップショットを読めるよう、欠けた項目は既定値で補う
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimulationConfig {
     pub strategy_complexity_penalty_enabled: bool,
     pub strategy_complexity_penalty_rate: f32,
     pub torus_field_enabled: bool,
+    pub neighborhood: NeighborhoodType,
+    // 設定するとセルをグラフのノードとみなし、近傍（neighborhood・torus）の代わりにグラフの隣接で対戦する
+    pub network: Option<NetworkConfig>,
+    // ネットワーク上で、直前に裏切った相手との辺をこの確率で切り、隣接ノードの隣接ノードと結び直す
+    pub rewiring_probability: f64,
+    // 設定すると個体が遺伝するタグを持ち、同じタグの相手をひいきするようになる
+    pub tags: Option<TagConfig>,
+    // 設定すると対戦の行動を観察して公開の評判を更新し、戦略が相手の評判を参照できるようになる（間接互恵）
+    pub reputation: Option<ReputationConfig>,
+    // 設定すると囚人のジレンマの対戦後に、裏切られた側が攻撃性に応じてコストを払って相手を罰する
+    pub punishment: Option<PunishmentConfig>,
+    pub mutation_rate: f64,
+    // 世代交代ごとに多様性やスコアの停滞から突然変異率を引き上げる（None なら常に mutation_rate）
+    pub adaptive_mutation: Option<AdaptiveMutation>,
+    pub action_noise: f64,
+    pub deme_structure: Option<DemeStructure>,
+    pub weighted_fitness: Option<WeightedFitness>,
+    // 設定すると weighted_fitness・score_normalization_enabled より優先して適応度を決める
+    pub fitness_function: Option<FitnessFunction>,
+    // 設定すると親選択を Pareto ランクによるトーナメントに切り替え、世代ごとに Pareto 前線を記録する
+    pub multi_objective: Option<MultiObjective>,
+    // 設定すると親選択の前に、形質の似た個体同士で適応度を分け合う（ニッチの共存）
+    pub fitness_sharing: Option<FitnessSharing>,
+    // 親選択の方式。ペナルティ・正規化は下のフラグにも反映され、SUS・ボルツマン選択はここだけで決まる
+    pub selection: SelectionMethod,
+    // 連続値の形質（移動性・内集団びいき・攻撃性）の交叉
+    pub crossover_method: CrossoverMethod,
+    pub score_normalization_enabled: bool,
+    pub seed: Option<u64>,
+    pub initial_population: Option<InitialPopulationSpec>,
+    pub placement: PlacementPattern,
+    // Some(N) なら各エージェントが memory-N の行動表で行動し、表ごと進化する
+    pub memory_genome: Option<usize>,
+    pub game_type: GameType,
+    pub payoff_matrix: PayoffMatrix,
+    // T > R > P > S を満たさない利得表を警告付きで許可する
+    pub allow_non_dilemma_payoffs: bool,
+    // 設定すると指定した世代で利得表を切り替えたり、線形に変えたりする（環境の変化）
+    pub payoff_schedule: Option<PayoffSchedule>,
+    // セルごとの資源レイヤー（None なら資源による利得の増減なし）
+    pub resources: Option<ResourceConfig>,
+    // 設定すると区域ごとに利得表・突然変異率を変える（区域外は payoff_matrix・mutation_rate）
+    pub zones: Option<ZoneMap>,
+    // 通れない壁のセル。個体は置かれず、移動先にも近傍にもならない
+    pub obstacles: Vec<Position>,
+    // 設定すると全個体が共通の移動方針で動く（None なら個体ごとの movement_strategy に従う）
+    pub movement_policy: Option<MovementPolicyKind>,
+    // 対戦の後、戦略の更新の前に行う移住（Helbing & Yu の成功駆動の移住など）
+    pub migration_model: MigrationModel,
+    pub reproduction: ReproductionModel,
+    // 進化の方式。構築時に EvolutionStrategyFactory が必要な設定を組み込む
+    pub evolution_strategy: EvolutionStrategyType,
+    // Some(ビン数) なら世代ごとに形質のヒストグラムを記録する
+    pub histogram_bins: Option<usize>,
+    // 世代ごとに協力者クラスタと Moran's I を記録する
+    pub spatial_metrics_enabled: bool,
+    // 各履歴（世代・ステップ・ヒストグラム・空間指標）に残す最大件数。None なら無制限
+    pub history_capacity: Option<usize>,
+    // 世代ごとの記録（適応度・多様性・処理時間、EvolutionTracker）に残す世代数
+    pub evolution_history_size: usize,
+    // 各個体の対戦履歴の保持方針（長時間の実行でメモリを抑える）
+    pub battle_history_retention: BattleHistoryRetention,
+    // 加齢による死亡、年齢に応じた死亡確率、スコア・エネルギーの下限による死亡（None なら誰も死なない）
+    pub lifecycle: Option<LifecycleConfig>,
+    // 設定すると選んだ個体のスコア・エネルギー・位置・戦略を世代ごとに記録する
+    pub agent_tracking: Option<TrackingTarget>,
+    // 個体が全滅したときに止める・置き直す・そのまま進めるのどれにするか
+    pub extinction_policy: ExtinctionPolicy,
 }
 
 impl Default for SimulationConfig {
@@ -10,6 +99,46 @@ impl Default for SimulationConfig {
             strategy_complexity_penalty_enabled: false,
             strategy_complexity_penalty_rate: 0.15, // 15% penalty by default
             torus_field_enabled: false,             // Default to bounded field
+            neighborhood: NeighborhoodType::Moore,  // Square lattice, 8 neighbors
+            network: None,                          // Spatial neighbors only
+            rewiring_probability: 0.0,              // Static links
+            tags: None,                             // No tag-based favoritism
+            reputation: None,                       // Private histories only
+            punishment: None,                       // No post-game punishment
+            mutation_rate: 0.05,                    // 5% mutation by default
+            adaptive_mutation: None,                // Fixed mutation rate
+            action_noise: 0.0,                      // Noiseless battles
+            deme_structure: None,                   // Single well-connected population
+            weighted_fitness: None,                 // Score-only fitness
+            fitness_function: None,                 // Derived from the flags above
+            multi_objective: None,                  // Single-objective selection
+            fitness_sharing: None,                  // No niching
+            selection: SelectionMethod::Roulette,   // Fitness-proportional spins
+            crossover_method: CrossoverMethod::Average, // Midpoint of the parents' traits
+            score_normalization_enabled: false,     // Total score drives selection
+            seed: None,                             // Fresh entropy per run
+            initial_population: None,               // Every trait random
+            placement: PlacementPattern::Uniform,   // Uniformly random cells
+            memory_genome: None,                    // Built-in strategies only
+            game_type: GameType::PrisonersDilemma,  // Pairwise games
+            payoff_matrix: PayoffMatrix::default(), // T=5, R=3, P=1, S=0
+            allow_non_dilemma_payoffs: false,       // Enforce T > R > P > S
+            payoff_schedule: None,                  // Fixed payoffs
+            resources: None,                        // No environment layer
+            zones: None,                            // Homogeneous parameters
+            obstacles: Vec::new(),                  // Every cell is open
+            movement_policy: None,                  // Per-agent movement strategies
+            migration_model: MigrationModel::None,  // No pre-update migration
+            reproduction: ReproductionModel::Generational, // Synchronous GA
+            evolution_strategy: EvolutionStrategyType::Classic, // Plain roulette GA
+            histogram_bins: None,                   // Summary statistics only
+            spatial_metrics_enabled: false,         // No cluster analysis
+            history_capacity: None,                 // Keep every generation
+            evolution_history_size: EvolutionTracker::DEFAULT_CAPACITY, // Last 1000 generation records
+            battle_history_retention: BattleHistoryRetention::default(), // Last 10 games per agent
+            lifecycle: None,                                            // Agents never die of age
+            agent_tracking: None, // No individual trajectories
+            extinction_policy: ExtinctionPolicy::Continue, // Keep stepping an empty grid
         }
     }
 }
@@ -33,4 +162,233 @@ impl SimulationConfig {
         self.torus_field_enabled = enabled;
         self
     }
+
+    pub fn with_neighborhood(mut self, neighborhood: NeighborhoodType) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn with_rewiring_probability(mut self, probability: f64) -> Self {
+        self.rewiring_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: TagConfig) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    pub fn with_reputation(mut self, reputation: ReputationConfig) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    pub fn with_punishment(mut self, punishment: PunishmentConfig) -> Self {
+        self.punishment = Some(punishment);
+        self
+    }
+
+    pub fn with_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_action_noise(mut self, noise: f64) -> Self {
+        self.action_noise = noise.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_deme_structure(mut self, demes: DemeStructure) -> Self {
+        self.deme_structure = Some(demes);
+        self
+    }
+
+    pub fn with_weighted_fitness(mut self, fitness: WeightedFitness) -> Self {
+        self.weighted_fitness = Some(fitness);
+        self
+    }
+
+    pub fn with_fitness_function(mut self, fitness: FitnessFunction) -> Self {
+        self.fitness_function = Some(fitness);
+        self
+    }
+
+    pub fn with_fitness_sharing(mut self, sharing: FitnessSharing) -> Self {
+        self.fitness_sharing = Some(sharing);
+        self
+    }
+
+    pub fn with_crossover_method(mut self, method: CrossoverMethod) -> Self {
+        self.crossover_method = method;
+        self
+    }
+
+    pub fn with_score_normalization(mut self, enabled: bool) -> Self {
+        self.score_normalization_enabled = enabled;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_initial_population(mut self, spec: InitialPopulationSpec) -> Self {
+        self.initial_population = Some(spec);
+        self
+    }
+
+    pub fn with_placement(mut self, placement: PlacementPattern) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn with_memory_genomes(mut self, memory: usize) -> Self {
+        self.memory_genome = Some(memory);
+        self
+    }
+
+    pub fn with_game_type(mut self, game_type: GameType) -> Self {
+        self.game_type = game_type;
+        self
+    }
+
+    pub fn with_payoff_matrix(mut self, payoffs: PayoffMatrix) -> Self {
+        self.payoff_matrix = payoffs;
+        self
+    }
+
+    pub fn with_non_dilemma_payoffs(mut self, allowed: bool) -> Self {
+        self.allow_non_dilemma_payoffs = allowed;
+        self
+    }
+
+    pub fn with_payoff_schedule(mut self, schedule: PayoffSchedule) -> Self {
+        self.payoff_schedule = Some(schedule);
+        self
+    }
+
+    pub fn with_resources(mut self, resources: ResourceConfig) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    pub fn with_zones(mut self, zones: ZoneMap) -> Self {
+        self.zones = Some(zones);
+        self
+    }
+
+    pub fn with_obstacles(mut self, obstacles: Vec<Position>) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_movement_policy(mut self, policy: MovementPolicyKind) -> Self {
+        self.movement_policy = Some(policy);
+        self
+    }
+
+    pub fn with_migration_model(mut self, model: MigrationModel) -> Self {
+        self.migration_model = model;
+        self
+    }
+
+    pub fn with_evolution_strategy(mut self, strategy: EvolutionStrategyType) -> Self {
+        self.evolution_strategy = strategy;
+        self
+    }
+
+    pub fn with_reproduction(mut self, reproduction: ReproductionModel) -> Self {
+        self.reproduction = reproduction;
+        self
+    }
+
+    pub fn with_histograms(mut self, bins: usize) -> Self {
+        self.histogram_bins = Some(bins.max(1));
+        self
+    }
+
+    pub fn with_spatial_metrics(mut self, enabled: bool) -> Self {
+        self.spatial_metrics_enabled = enabled;
+        self
+    }
+
+    pub fn with_history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = Some(capacity.max(1));
+        self
+    }
+
+    pub fn with_evolution_history_size(mut self, size: usize) -> Self {
+        self.evolution_history_size = size;
+        self
+    }
+
+    pub fn with_multi_objective(mut self, multi: MultiObjective) -> Self {
+        self.multi_objective = Some(multi);
+        self
+    }
+
+    pub fn with_adaptive_mutation(mut self, adaptive: AdaptiveMutation) -> Self {
+        self.adaptive_mutation = Some(adaptive);
+        self
+    }
+
+    pub fn with_battle_history_retention(mut self, retention: BattleHistoryRetention) -> Self {
+        self.battle_history_retention = retention;
+        self
+    }
+
+    pub fn with_lifecycle(mut self, lifecycle: LifecycleConfig) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    pub fn with_agent_tracking(mut self, target: TrackingTarget) -> Self {
+        self.agent_tracking = Some(target);
+        self
+    }
+
+    pub fn with_extinction_policy(mut self, policy: ExtinctionPolicy) -> Self {
+        self.extinction_policy = policy;
+        self
+    }
+
+    // 方式を記録し、複雑さペナルティとスコア正規化のフラグもそれに合わせて切り替える
+    pub fn with_selection(mut self, method: SelectionMethod) -> Self {
+        self.selection = method;
+        self.with_strategy_complexity_penalty(method == SelectionMethod::ComplexityPenalty)
+            .with_score_normalization(method == SelectionMethod::Normalized)
+    }
+
+    // 実際に使う適応度。fitness_function、weighted_fitness、スコア正規化の順に優先する
+    pub fn fitness(&self) -> FitnessFunction {
+        if let Some(fitness) = self.fitness_function {
+            fitness
+        } else if let Some(weights) = self.weighted_fitness {
+            FitnessFunction::Weighted(weights)
+        } else if self.score_normalization_enabled {
+            FitnessFunction::ScorePerBattle
+        } else {
+            FitnessFunction::RawScore
+        }
+    }
+
+    // SUS・ボルツマン選択はそのまま返し、それ以外はフラグから求める
+    // 両方のフラグが立っている場合は複雑さペナルティを優先して報告する
+    pub fn selection_method(&self) -> SelectionMethod {
+        if self.selection.samples_fitness() {
+            self.selection
+        } else if self.strategy_complexity_penalty_enabled {
+            SelectionMethod::ComplexityPenalty
+        } else if self.score_normalization_enabled {
+            SelectionMethod::Normalized
+        } else {
+            SelectionMethod::Roulette
+        }
+    }
 }
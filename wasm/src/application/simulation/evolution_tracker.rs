@@ -0,0 +1,209 @@
+use super::{DiversityMetrics, FitnessMetrics, PerformanceMetrics, PerformanceReport};
+use serde::{Deserialize, Serialize};
+
+// 1 世代分の記録（適応度と多様性は世代交代の直前、時間は世代交代まで含む）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    pub generation: u32,
+    pub population: usize,
+    pub average_cooperation_rate: f64,
+    pub fitness: FitnessMetrics,
+    pub diversity: DiversityMetrics,
+    pub performance: PerformanceMetrics,
+    // 世代交代の後のメモリ使用量（MemoryUsage::memory_usage_mb）
+    #[serde(default)]
+    pub memory_usage_mb: f64,
+}
+
+// 記録中の世代の要約（記録がなければ全て 0）
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct EvolutionSummary {
+    pub generations_recorded: usize,
+    pub first_generation: u32,
+    pub last_generation: u32,
+    // 個体の適応度の最高値と、それが出た世代
+    pub best_fitness: f64,
+    pub best_generation: u32,
+    // 最後と最初の記録の平均適応度の差
+    pub fitness_improvement: f64,
+    pub final_fitness: FitnessMetrics,
+    pub final_diversity: DiversityMetrics,
+    pub average_genetic_diversity: f64,
+    pub average_generation_ms: f64,
+    pub total_ms: f64,
+    #[serde(default)]
+    pub peak_memory_usage_mb: f64,
+}
+
+// 世代ごとの記録を、新しいものから最大 capacity 世代分だけ世代順に持つ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvolutionTracker {
+    capacity: usize,
+    records: Vec<GenerationRecord>,
+}
+
+impl Default for EvolutionTracker {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl EvolutionTracker {
+    pub const DEFAULT_CAPACITY: usize = 1000;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // 縮めた場合はすぐに古い記録から捨てる
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    pub fn record(&mut self, record: GenerationRecord) {
+        self.records.push(record);
+        self.trim();
+    }
+
+    // 世代順
+    pub fn records(&self) -> &[GenerationRecord] {
+        &self.records
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    pub fn summary(&self) -> EvolutionSummary {
+        let (Some(first), Some(last)) = (self.records.first(), self.records.last()) else {
+            return EvolutionSummary::default();
+        };
+        let best = self.records.iter().fold(first, |best, record| {
+            if record.fitness.max > best.fitness.max {
+                record
+            } else {
+                best
+            }
+        });
+        let n = self.records.len() as f64;
+        let total_ms: f64 = self
+            .records
+            .iter()
+            .map(|record| record.performance.total_ms)
+            .sum();
+
+        EvolutionSummary {
+            generations_recorded: self.records.len(),
+            first_generation: first.generation,
+            last_generation: last.generation,
+            best_fitness: best.fitness.max,
+            best_generation: best.generation,
+            fitness_improvement: last.fitness.mean - first.fitness.mean,
+            final_fitness: last.fitness,
+            final_diversity: last.diversity,
+            average_genetic_diversity: self
+                .records
+                .iter()
+                .map(|record| record.diversity.genetic_diversity)
+                .sum::<f64>()
+                / n,
+            average_generation_ms: total_ms / n,
+            total_ms,
+            peak_memory_usage_mb: self
+                .records
+                .iter()
+                .map(|record| record.memory_usage_mb)
+                .fold(0.0, f64::max),
+        }
+    }
+
+    pub fn performance_report(&self) -> PerformanceReport {
+        let Some(last) = self.records.last() else {
+            return PerformanceReport::default();
+        };
+        let mut total = PerformanceMetrics::default();
+        for record in &self.records {
+            total.add(&record.performance);
+        }
+        PerformanceReport {
+            generations: self.records.len(),
+            average: total.scaled(1.0 / self.records.len() as f64),
+            total,
+            latest: last.performance,
+        }
+    }
+
+    fn trim(&mut self) {
+        let excess = self.records.len().saturating_sub(self.capacity);
+        self.records.drain(..excess);
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.records.capacity() * std::mem::size_of::<GenerationRecord>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(generation: u32, mean: f64, max: f64) -> GenerationRecord {
+        GenerationRecord {
+            generation,
+            population: 10,
+            average_cooperation_rate: 0.5,
+            fitness: FitnessMetrics {
+                mean,
+                max,
+                ..FitnessMetrics::default()
+            },
+            diversity: DiversityMetrics {
+                genetic_diversity: 0.1 * generation as f64,
+                ..DiversityMetrics::default()
+            },
+            performance: PerformanceMetrics {
+                total_ms: 2.0,
+                ..PerformanceMetrics::default()
+            },
+            memory_usage_mb: generation as f64,
+        }
+    }
+
+    #[test]
+    fn test_tracker_keeps_the_latest_records_and_summarizes_them() {
+        // Arrange
+        let mut tracker = EvolutionTracker::new(3);
+
+        // Act
+        for (generation, mean, max) in [(0, 1.0, 5.0), (1, 2.0, 9.0), (2, 3.0, 12.0), (3, 4.0, 8.0)]
+        {
+            tracker.record(record(generation, mean, max));
+        }
+        let summary = tracker.summary();
+
+        // Assert: 世代 0 は容量を超えて捨てられる
+        assert_eq!(tracker.records().len(), 3);
+        assert_eq!(summary.first_generation, 1);
+        assert_eq!(summary.last_generation, 3);
+        assert_eq!((summary.best_fitness, summary.best_generation), (12.0, 2));
+        assert_eq!(summary.fitness_improvement, 2.0);
+        assert!((summary.average_genetic_diversity - 0.2).abs() < 1e-12);
+        assert_eq!(summary.total_ms, 6.0);
+        assert_eq!(summary.peak_memory_usage_mb, 3.0);
+
+        tracker.set_capacity(1);
+        assert_eq!(tracker.records()[0].generation, 3);
+        assert_eq!(
+            EvolutionTracker::default().summary(),
+            EvolutionSummary::default()
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+// 個体が全滅したときの扱い。既定の Continue は空の盤面のままステップを進める（従来どおり）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtinctionPolicy {
+    // 以降の step() は何もしない（reset で再開）
+    Stop,
+    // 初期配置と同じ規則で N 体を置き直す
+    ReseedRandom(usize),
+    // 全滅の直前にスコア上位だった個体の遺伝子型から N 体を置き直す
+    ReseedFromElite(usize),
+    #[default]
+    Continue,
+}
+
+impl ExtinctionPolicy {
+    // "stop" / "continue" / "reseed_random:N" / "reseed_elite:N"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Expected stop, continue, reseed_random:N or reseed_elite:N, got {spec}");
+        match spec.split_once(':') {
+            None => match spec {
+                "stop" => Ok(ExtinctionPolicy::Stop),
+                "continue" => Ok(ExtinctionPolicy::Continue),
+                _ => Err(invalid()),
+            },
+            Some(("reseed_random", n)) => n
+                .parse()
+                .map(ExtinctionPolicy::ReseedRandom)
+                .map_err(|_| invalid()),
+            Some(("reseed_elite", n)) => n
+                .parse()
+                .map(ExtinctionPolicy::ReseedFromElite)
+                .map_err(|_| invalid()),
+            Some(_) => Err(invalid()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExtinctionPolicy::Stop => "stop",
+            ExtinctionPolicy::ReseedRandom(_) => "reseed_random",
+            ExtinctionPolicy::ReseedFromElite(_) => "reseed_elite",
+            ExtinctionPolicy::Continue => "continue",
+        }
+    }
+
+    // 置き直す個体数（置き直さない方針では None）
+    pub fn reseed_count(&self) -> Option<usize> {
+        match *self {
+            ExtinctionPolicy::ReseedRandom(n) | ExtinctionPolicy::ReseedFromElite(n) => Some(n),
+            ExtinctionPolicy::Stop | ExtinctionPolicy::Continue => None,
+        }
+    }
+}
+
+// 全滅が起きたステップと、そのとき取った行動。reseeded は実際に置けた個体数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtinctionEvent {
+    pub generation: u32,
+    pub turn: u32,
+    pub action: ExtinctionPolicy,
+    pub reseeded: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reads_every_policy() {
+        // Act & Assert
+        assert_eq!(ExtinctionPolicy::parse("stop"), Ok(ExtinctionPolicy::Stop));
+        assert_eq!(
+            ExtinctionPolicy::parse("continue"),
+            Ok(ExtinctionPolicy::Continue)
+        );
+        assert_eq!(
+            ExtinctionPolicy::parse("reseed_random:20"),
+            Ok(ExtinctionPolicy::ReseedRandom(20))
+        );
+        assert_eq!(
+            ExtinctionPolicy::parse("reseed_elite:5"),
+            Ok(ExtinctionPolicy::ReseedFromElite(5))
+        );
+        assert_eq!(ExtinctionPolicy::ReseedFromElite(5).reseed_count(), Some(5));
+        assert!(ExtinctionPolicy::parse("reseed_random:many").is_err());
+        assert!(ExtinctionPolicy::parse("restart").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::domain::agent::{Agent, MovementStrategy, Position, StrategyType};
+use serde::{Deserialize, Serialize};
+
+// 再生用に量子化したエージェント状態（移動性は 0-255 に丸める）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameAgent {
+    pub x: u16,
+    pub y: u16,
+    pub strategy: u8,
+    pub movement_strategy: u8,
+    pub mobility: u8,
+}
+
+impl FrameAgent {
+    pub fn from_agent(agent: &Agent) -> Self {
+        Self {
+            x: agent.position.x as u16,
+            y: agent.position.y as u16,
+            strategy: agent.strategy.id(),
+            movement_strategy: agent.movement_strategy.id(),
+            mobility: (agent.mobility.clamp(0.0, 1.0) * 255.0).round() as u8,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        Position::new(self.x as usize, self.y as usize)
+    }
+
+    pub fn to_agent(&self) -> Result<Agent, String> {
+        let strategy = StrategyType::from_id(self.strategy)
+            .ok_or_else(|| format!("Unknown strategy id {}", self.strategy))?;
+        let movement_strategy = MovementStrategy::from_id(self.movement_strategy)
+            .ok_or_else(|| format!("Unknown movement strategy id {}", self.movement_strategy))?;
+
+        Ok(Agent::new(
+            self.position(),
+            strategy,
+            self.mobility as f64 / 255.0,
+            movement_strategy,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub generation: u32,
+    pub turn: u32,
+    pub agents: Vec<FrameAgent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameSequence {
+    pub width: usize,
+    pub height: usize,
+    pub frames: Vec<Frame>,
+}
+
+impl FrameSequence {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Frame> {
+        self.frames.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
@@ -0,0 +1,158 @@
+use crate::domain::agent::{Agent, StrategyType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// [min, max] を等幅に区切った度数分布。max ちょうどの値は最後のビンに入れる
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn new(values: impl IntoIterator<Item = f64>, bins: usize, min: f64, max: f64) -> Self {
+        let bins = bins.max(1);
+        let mut counts = vec![0; bins];
+        let width = (max - min) / bins as f64;
+
+        for value in values {
+            let index = if width > 0.0 {
+                ((value - min) / width)
+                    .floor()
+                    .clamp(0.0, (bins - 1) as f64) as usize
+            } else {
+                0
+            };
+            counts[index] += 1;
+        }
+
+        Self { min, max, counts }
+    }
+
+    // 範囲をデータの最小値・最大値から決める
+    pub fn from_values(values: &[f64], bins: usize) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if values.is_empty() {
+            return Self::new([], bins, 0.0, 0.0);
+        }
+        Self::new(values.iter().copied(), bins, min, max)
+    }
+
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.counts.capacity() * std::mem::size_of::<usize>()
+    }
+}
+
+// 世代ごとの形質の分布（フロントエンドで分布の推移を描画する用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetailedStatistics {
+    pub generation: u32,
+    pub cooperation_rate: Histogram,
+    pub mobility: Histogram,
+    pub score: Histogram,
+    // (戦略名, 個体数) を戦略 ID 順に並べたもの（0 体の組み込み戦略も含む）
+    pub strategy_counts: Vec<(String, usize)>,
+}
+
+impl DetailedStatistics {
+    pub const DEFAULT_BINS: usize = 20;
+
+    pub fn calculate(agents: &HashMap<Uuid, Agent>, generation: u32, bins: usize) -> Self {
+        let mut counts: HashMap<StrategyType, usize> = StrategyType::ALL
+            .iter()
+            .map(|strategy| (*strategy, 0))
+            .collect();
+        for agent in agents.values() {
+            *counts.entry(agent.strategy).or_insert(0) += 1;
+        }
+        let mut strategy_counts: Vec<(StrategyType, usize)> = counts.into_iter().collect();
+        strategy_counts.sort_by_key(|(strategy, _)| strategy.id());
+
+        let scores: Vec<f64> = agents.values().map(|agent| agent.score as f64).collect();
+
+        Self {
+            generation,
+            cooperation_rate: Histogram::new(
+                agents.values().map(|agent| agent.cooperation_rate()),
+                bins,
+                0.0,
+                1.0,
+            ),
+            mobility: Histogram::new(agents.values().map(|agent| agent.mobility), bins, 0.0, 1.0),
+            score: Histogram::from_values(&scores, bins),
+            strategy_counts: strategy_counts
+                .into_iter()
+                .map(|(strategy, count)| (strategy.name(), count))
+                .collect(),
+        }
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.cooperation_rate.heap_bytes()
+            + self.mobility.heap_bytes()
+            + self.score.heap_bytes()
+            + self.strategy_counts.capacity() * std::mem::size_of::<(String, usize)>()
+            + self
+                .strategy_counts
+                .iter()
+                .map(|(name, _)| name.capacity())
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position};
+
+    #[test]
+    fn test_histogram_puts_boundary_values_in_edge_bins() {
+        // Arrange & Act
+        let histogram = Histogram::new([0.0, 0.05, 0.1, 0.55, 1.0], 10, 0.0, 1.0);
+
+        // Assert
+        assert_eq!(histogram.counts, vec![2, 1, 0, 0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(histogram.total(), 5);
+        assert!((histogram.bin_width() - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_detailed_statistics_counts_every_builtin_strategy() {
+        // Arrange
+        let agents: HashMap<Uuid, Agent> = [0.1, 0.9, 0.95]
+            .into_iter()
+            .enumerate()
+            .map(|(i, mobility)| {
+                let agent = Agent::new(
+                    Position::new(i, 0),
+                    StrategyType::TitForTat,
+                    mobility,
+                    MovementStrategy::Settler,
+                );
+                (agent.id, agent)
+            })
+            .collect();
+
+        // Act
+        let stats = DetailedStatistics::calculate(&agents, 3, 2);
+
+        // Assert
+        assert_eq!(stats.mobility.counts, vec![1, 2]);
+        assert_eq!(stats.strategy_counts.len(), StrategyType::ALL.len());
+        assert!(stats
+            .strategy_counts
+            .contains(&(StrategyType::TitForTat.name(), 3)));
+        assert_eq!(stats.score.total(), 3);
+    }
+}
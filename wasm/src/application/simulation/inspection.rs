@@ -0,0 +1,35 @@
+use crate::domain::agent::{Action, Agent, Position, StrategyType};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// インスペクタで選んだセルから見た近傍の個体と、中心の個体との直近の対戦
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeighborView {
+    pub id: Uuid,
+    pub position: Position,
+    pub strategy: StrategyType,
+    pub score: i32,
+    pub cooperation_rate: f64,
+    // 中心の個体の対戦履歴に残っている (中心の行動, 相手の行動)。古い順
+    pub rounds: Vec<(Action, Action)>,
+    // 直近の対戦で中心の個体が得た利得
+    pub last_payoff: Option<i32>,
+}
+
+impl NeighborView {
+    // 中心が空きセルなら対戦の記録は空
+    pub fn between(focal: Option<&Agent>, neighbor: &Agent) -> Self {
+        let history = focal.map(|focal| &focal.history);
+        Self {
+            id: neighbor.id,
+            position: neighbor.position,
+            strategy: neighbor.strategy,
+            score: neighbor.score,
+            cooperation_rate: neighbor.cooperation_rate(),
+            rounds: history
+                .map(|history| history.rounds_against(&neighbor.id))
+                .unwrap_or_default(),
+            last_payoff: history.and_then(|history| history.get_last_payoff(&neighbor.id)),
+        }
+    }
+}
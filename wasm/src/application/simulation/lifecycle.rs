@@ -0,0 +1,132 @@
+use crate::domain::agent::Agent;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 年齢に応じた毎ステップの死亡確率
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MortalityCurve {
+    // 年齢によらず一定
+    Constant(f64),
+    // Gompertz 型: base * exp(growth * age)（1.0 で頭打ち）
+    Gompertz { base: f64, growth: f64 },
+}
+
+impl MortalityCurve {
+    // "constant:P" / "gompertz:BASE:GROWTH"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected constant:P or gompertz:BASE:GROWTH, got {spec}");
+        let parts: Vec<&str> = spec.split(':').collect();
+        let number = |value: &str| value.parse::<f64>().map_err(|_| invalid());
+        match parts[..] {
+            ["constant", p] => Ok(MortalityCurve::Constant(number(p)?)),
+            ["gompertz", base, growth] => Ok(MortalityCurve::Gompertz {
+                base: number(base)?,
+                growth: number(growth)?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn probability(&self, age: u32) -> f64 {
+        match *self {
+            MortalityCurve::Constant(p) => p,
+            MortalityCurve::Gompertz { base, growth } => base * (growth * age as f64).exp(),
+        }
+        .clamp(0.0, 1.0)
+    }
+}
+
+// 加齢と死亡の規則。どれも未設定なら誰も死なない
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifecycleConfig {
+    // この年齢（ステップ数）に達したら死亡
+    pub max_age: Option<u32>,
+    pub mortality: Option<MortalityCurve>,
+    // 累積スコアがこれを下回ったら死亡
+    pub min_score: Option<i32>,
+    // エネルギーがこれを下回ったら死亡（エネルギーモデルの餓死とは別に判定する）
+    pub min_energy: Option<f64>,
+}
+
+impl LifecycleConfig {
+    pub fn with_max_age(mut self, max_age: u32) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn with_mortality(mut self, mortality: MortalityCurve) -> Self {
+        self.mortality = Some(mortality);
+        self
+    }
+
+    pub fn with_min_score(mut self, min_score: i32) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    pub fn with_min_energy(mut self, min_energy: f64) -> Self {
+        self.min_energy = Some(min_energy);
+        self
+    }
+
+    // 決定的な条件を先に調べ、死亡確率の乱数は曲線を設定したときだけ引く
+    pub fn should_die<R: Rng + ?Sized>(&self, agent: &Agent, rng: &mut R) -> bool {
+        if self.max_age.is_some_and(|max_age| agent.age >= max_age)
+            || self
+                .min_score
+                .is_some_and(|min_score| agent.score < min_score)
+            || self
+                .min_energy
+                .is_some_and(|min_energy| agent.energy < min_energy)
+        {
+            return true;
+        }
+        self.mortality
+            .is_some_and(|mortality| rng.gen_bool(mortality.probability(agent.age)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_thresholds_and_curve_decide_death() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let mut agent = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        agent.age = 5;
+        agent.score = 3;
+        let gompertz = MortalityCurve::Gompertz {
+            base: 0.01,
+            growth: 1.0,
+        };
+
+        // Act & Assert
+        assert!(LifecycleConfig::default()
+            .with_max_age(5)
+            .should_die(&agent, &mut rng));
+        assert!(!LifecycleConfig::default()
+            .with_max_age(6)
+            .should_die(&agent, &mut rng));
+        assert!(LifecycleConfig::default()
+            .with_min_score(4)
+            .should_die(&agent, &mut rng));
+        assert!(!LifecycleConfig::default()
+            .with_mortality(MortalityCurve::Constant(0.0))
+            .should_die(&agent, &mut rng));
+        assert_eq!(gompertz.probability(10), 1.0);
+        assert!((gompertz.probability(0) - 0.01).abs() < 1e-12);
+        assert_eq!(MortalityCurve::parse("gompertz:0.01:1"), Ok(gompertz));
+        assert!(MortalityCurve::parse("linear:1").is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use crate::application::evolution::FitnessEvaluator;
+use crate::domain::agent::{Action, Agent, Genotype, StrategyType};
+use crate::domain::grid::Grid;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// 世代交代直前の適応度の分布（標準偏差は母標準偏差）
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct FitnessMetrics {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DiversityMetrics {
+    // 戦略分布の正規化シャノンエントロピー（0〜1）
+    pub strategy_entropy: f64,
+    // 1 体以上いる戦略の種類の数
+    pub strategy_richness: usize,
+    // 遺伝子型の平均ペア距離（Genotype::distance、0〜1）
+    pub genetic_diversity: f64,
+    // 移動性・ひいき・攻撃性の分散の平均を最大値 0.25 で割った値（0〜1）
+    #[serde(default)]
+    pub phenotypic_diversity: f64,
+    // 近傍に違う戦略がいる割合の平均を、無作為に混ざった場合の期待値（1 - Σp²）で割った値
+    // 0 なら戦略ごとに完全に固まり、1 前後なら無作為、1 より大きければ互い違いに並ぶ
+    #[serde(default)]
+    pub spatial_diversity: f64,
+    // 対戦履歴に残る実際の手の組（CC・CD・DC・DD）の正規化エントロピー（0〜1）
+    #[serde(default)]
+    pub behavioral_diversity: f64,
+}
+
+// 1 世代にかかった時間（ミリ秒）。total_ms = steps_ms + stats_ms + evolution_ms
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    // 世代内の各ステップの合計（対戦・移動に、資源・エネルギー・加齢などの処理を加えたもの）
+    pub steps_ms: f64,
+    // そのうち対戦（適応度の評価）
+    #[serde(default)]
+    pub battles_ms: f64,
+    // そのうち移住と移動
+    #[serde(default)]
+    pub movement_ms: f64,
+    // 統計・履歴・世代ごとの記録の計算
+    #[serde(default)]
+    pub stats_ms: f64,
+    // 世代交代
+    pub evolution_ms: f64,
+    // そのうち親とエリートの選択、子の交叉、子の突然変異
+    #[serde(default)]
+    pub selection_ms: f64,
+    #[serde(default)]
+    pub crossover_ms: f64,
+    #[serde(default)]
+    pub mutation_ms: f64,
+    pub total_ms: f64,
+}
+
+impl PerformanceMetrics {
+    pub fn add(&mut self, other: &PerformanceMetrics) {
+        self.steps_ms += other.steps_ms;
+        self.battles_ms += other.battles_ms;
+        self.movement_ms += other.movement_ms;
+        self.stats_ms += other.stats_ms;
+        self.evolution_ms += other.evolution_ms;
+        self.selection_ms += other.selection_ms;
+        self.crossover_ms += other.crossover_ms;
+        self.mutation_ms += other.mutation_ms;
+        self.total_ms += other.total_ms;
+    }
+
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            steps_ms: self.steps_ms * factor,
+            battles_ms: self.battles_ms * factor,
+            movement_ms: self.movement_ms * factor,
+            stats_ms: self.stats_ms * factor,
+            evolution_ms: self.evolution_ms * factor,
+            selection_ms: self.selection_ms * factor,
+            crossover_ms: self.crossover_ms * factor,
+            mutation_ms: self.mutation_ms * factor,
+            total_ms: self.total_ms * factor,
+        }
+    }
+}
+
+// 記録中の世代の処理時間の合計・1 世代あたりの平均・直近の世代
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub generations: usize,
+    pub total: PerformanceMetrics,
+    pub average: PerformanceMetrics,
+    pub latest: PerformanceMetrics,
+}
+
+// メモリ使用量（MB）。estimated_mb は盤面の個体と残している履歴の概算で、
+// linear_memory_mb は wasm32 でだけ取れる線形メモリ全体の大きさ
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub estimated_mb: f64,
+    pub linear_memory_mb: Option<f64>,
+}
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+impl MemoryUsage {
+    pub fn from_estimated_bytes(bytes: usize) -> Self {
+        Self {
+            estimated_mb: bytes as f64 / BYTES_PER_MB,
+            linear_memory_mb: linear_memory_mb(),
+        }
+    }
+
+    // 線形メモリの大きさが取れればそれを、なければ概算を返す
+    pub fn memory_usage_mb(&self) -> f64 {
+        self.linear_memory_mb.unwrap_or(self.estimated_mb)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn linear_memory_mb() -> Option<f64> {
+    const PAGE_BYTES: usize = 65536;
+    Some((core::arch::wasm32::memory_size(0) * PAGE_BYTES) as f64 / BYTES_PER_MB)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn linear_memory_mb() -> Option<f64> {
+    None
+}
+
+// 経過時間の計測。wasm32 では std::time::Instant が使えないので performance.now()（なければ Date.now()）を使う
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    #[cfg(target_arch = "wasm32")]
+    started_ms: f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    started: std::time::Instant,
+}
+
+// Window と Worker のどちらでも使えるよう、グローバルの performance.now を一度だけ引いておく
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    use wasm_bindgen::{JsCast, JsValue};
+    thread_local! {
+        static PERFORMANCE_NOW: Option<(JsValue, js_sys::Function)> = {
+            js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+                .ok()
+                .filter(JsValue::is_object)
+                .and_then(|performance| {
+                    let now = js_sys::Reflect::get(&performance, &JsValue::from_str("now"))
+                        .ok()?
+                        .dyn_into::<js_sys::Function>()
+                        .ok()?;
+                    Some((performance, now))
+                })
+        };
+    }
+    PERFORMANCE_NOW
+        .with(|clock| {
+            let (performance, now) = clock.as_ref()?;
+            now.call0(performance).ok()?.as_f64()
+        })
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+impl Stopwatch {
+    #[cfg(target_arch = "wasm32")]
+    pub fn start() -> Self {
+        Self {
+            started_ms: now_ms(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start() -> Self {
+        Self {
+            started: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn elapsed_ms(&self) -> f64 {
+        (now_ms() - self.started_ms).max(0.0)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.started.elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+pub struct MetricsCalculator;
+
+impl MetricsCalculator {
+    // 遺伝的多様性はこの数までの個体（位置順に等間隔で選ぶ）の全ペアで求める
+    pub const GENETIC_SAMPLE_SIZE: usize = 200;
+
+    // agents は位置順（合計が走査順で揺れないように）
+    pub fn fitness(agents: &[&Agent], fitness: &dyn FitnessEvaluator) -> FitnessMetrics {
+        if agents.is_empty() {
+            return FitnessMetrics::default();
+        }
+        let values: Vec<f64> = agents.iter().map(|agent| fitness.evaluate(agent)).collect();
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        FitnessMetrics {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    pub fn diversity(grid: &Grid) -> DiversityMetrics {
+        let agents = grid.agents_in_position_order();
+        let mut counts: HashMap<StrategyType, usize> = HashMap::new();
+        for agent in &agents {
+            *counts.entry(agent.strategy).or_insert(0) += 1;
+        }
+        DiversityMetrics {
+            strategy_entropy: Self::normalized_entropy(
+                counts.values().copied(),
+                StrategyType::ALL.len(),
+            ),
+            strategy_richness: counts.len(),
+            genetic_diversity: Self::genetic_diversity(&agents),
+            phenotypic_diversity: Self::phenotypic_diversity(&agents),
+            spatial_diversity: Self::spatial_diversity(grid, &agents, &counts),
+            behavioral_diversity: Self::behavioral_diversity(&agents),
+        }
+    }
+
+    // 度数のシャノンエントロピーを ln(categories) で割った値（0〜1）
+    pub fn normalized_entropy(counts: impl Iterator<Item = usize>, categories: usize) -> f64 {
+        if categories < 2 {
+            return 0.0;
+        }
+        (Self::shannon_entropy(counts) / (categories as f64).ln()).clamp(0.0, 1.0)
+    }
+
+    // 度数のシャノンエントロピー -Σ p ln p（ナット、度数が全て 0 なら 0）
+    pub fn shannon_entropy(counts: impl Iterator<Item = usize>) -> f64 {
+        // HashMap の走査順で和の丸めが変わらないよう、度数を並べてから足す
+        let mut counts: Vec<usize> = counts.filter(|count| *count > 0).collect();
+        counts.sort_unstable();
+        let total: usize = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        counts
+            .iter()
+            .map(|count| {
+                let p = *count as f64 / total as f64;
+                -p * p.ln()
+            })
+            .sum()
+    }
+
+    // ジニ・シンプソン指数 1 - Σ p²（無作為に選んだ 2 体が違う種類である確率、度数が全て 0 なら 0）
+    pub fn simpson_index(counts: impl Iterator<Item = usize>) -> f64 {
+        let mut counts: Vec<usize> = counts.collect();
+        counts.sort_unstable();
+        let total: usize = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        1.0 - counts
+            .iter()
+            .map(|count| (*count as f64 / total as f64).powi(2))
+            .sum::<f64>()
+    }
+
+    fn genetic_diversity(agents: &[&Agent]) -> f64 {
+        let stride = agents.len().div_ceil(Self::GENETIC_SAMPLE_SIZE).max(1);
+        let genotypes: Vec<Genotype> = agents
+            .iter()
+            .step_by(stride)
+            .map(|agent| Genotype::of(agent))
+            .collect();
+        if genotypes.len() < 2 {
+            return 0.0;
+        }
+
+        let mut total = 0.0;
+        for (i, genotype) in genotypes.iter().enumerate() {
+            for other in &genotypes[i + 1..] {
+                total += genotype.distance(other);
+            }
+        }
+        let pairs = genotypes.len() * (genotypes.len() - 1) / 2;
+        total / pairs as f64
+    }
+
+    fn phenotypic_diversity(agents: &[&Agent]) -> f64 {
+        if agents.len() < 2 {
+            return 0.0;
+        }
+        let traits: [fn(&Agent) -> f64; 3] = [
+            |agent| agent.mobility,
+            |agent| agent.in_group_bias,
+            |agent| agent.aggression,
+        ];
+        let n = agents.len() as f64;
+        let variance_sum: f64 = traits
+            .iter()
+            .map(|value| {
+                let mean = agents.iter().map(|agent| value(agent)).sum::<f64>() / n;
+                agents
+                    .iter()
+                    .map(|agent| (value(agent) - mean).powi(2))
+                    .sum::<f64>()
+                    / n
+            })
+            .sum();
+        (variance_sum / traits.len() as f64 / 0.25).clamp(0.0, 1.0)
+    }
+
+    fn spatial_diversity(
+        grid: &Grid,
+        agents: &[&Agent],
+        counts: &HashMap<StrategyType, usize>,
+    ) -> f64 {
+        let total = agents.len() as f64;
+        let expected = 1.0
+            - counts
+                .values()
+                .map(|count| (*count as f64 / total).powi(2))
+                .sum::<f64>();
+        if agents.is_empty() || expected <= 0.0 {
+            return 0.0;
+        }
+
+        let mut mixed_sum = 0.0;
+        let mut counted = 0;
+        for agent in agents {
+            let neighbors = grid.get_neighbors(&agent.position);
+            if neighbors.is_empty() {
+                continue;
+            }
+            let different = neighbors
+                .iter()
+                .filter(|neighbor| neighbor.strategy != agent.strategy)
+                .count();
+            mixed_sum += different as f64 / neighbors.len() as f64;
+            counted += 1;
+        }
+        if counted == 0 {
+            return 0.0;
+        }
+        mixed_sum / counted as f64 / expected
+    }
+
+    fn behavioral_diversity(agents: &[&Agent]) -> f64 {
+        let mut counts = [0usize; 4];
+        for agent in agents {
+            for record in agent.history.records() {
+                let index = match (record.my_action, record.opponent_action) {
+                    (Action::Cooperate, Action::Cooperate) => 0,
+                    (Action::Cooperate, Action::Defect) => 1,
+                    (Action::Defect, Action::Cooperate) => 2,
+                    (Action::Defect, Action::Defect) => 3,
+                };
+                counts[index] += 1;
+            }
+        }
+        Self::normalized_entropy(counts.into_iter(), counts.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::FitnessFunction;
+    use crate::domain::agent::{MovementStrategy, Position};
+
+    #[test]
+    fn test_fitness_and_diversity_of_a_mixed_population() {
+        // Arrange: 3x1 の盤面に同じ TFT が 2 体（スコア 2 と 6）と AllD が 1 体（スコア 10）。両端だけが 1 戦ずつしている
+        let agent = |x: usize, strategy: StrategyType, score: i32| {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                strategy,
+                0.5,
+                MovementStrategy::Settler,
+            );
+            agent.score = score;
+            agent
+        };
+        let mut agents = [
+            agent(0, StrategyType::TitForTat, 2),
+            agent(1, StrategyType::TitForTat, 6),
+            agent(2, StrategyType::AllDefect, 10),
+        ];
+        let opponent = agents[1].id;
+        agents[0]
+            .history
+            .add_game(opponent, Action::Cooperate, Action::Cooperate, 3);
+        agents[2]
+            .history
+            .add_game(opponent, Action::Defect, Action::Cooperate, 5);
+        let refs: Vec<&Agent> = agents.iter().collect();
+        let mut grid = Grid::new(3, 1);
+        for agent in &agents {
+            grid.add_agent(agent.clone()).unwrap();
+        }
+
+        // Act
+        let fitness = MetricsCalculator::fitness(&refs, &FitnessFunction::RawScore);
+        let diversity = MetricsCalculator::diversity(&grid);
+
+        // Assert: 戦略だけが違う組は距離 1/6、3 組の平均は 1/9
+        assert_eq!((fitness.min, fitness.max, fitness.mean), (2.0, 10.0, 6.0));
+        assert!((fitness.std_dev - (32.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(diversity.strategy_richness, 2);
+        assert!((diversity.genetic_diversity - 1.0 / 9.0).abs() < 1e-12);
+        let expected_entropy = -(2.0f64 / 3.0 * (2.0f64 / 3.0).ln()
+            + 1.0 / 3.0 * (1.0f64 / 3.0).ln())
+            / (StrategyType::ALL.len() as f64).ln();
+        assert!((diversity.strategy_entropy - expected_entropy).abs() < 1e-12);
+        // 違う戦略の近傍の割合は 0・1/2・1 で平均 1/2、無作為なら 4/9
+        assert!((diversity.spatial_diversity - 1.125).abs() < 1e-12);
+        // 手の組は CC と DC が 1 回ずつ
+        assert!((diversity.behavioral_diversity - 0.5).abs() < 1e-12);
+        assert_eq!(diversity.phenotypic_diversity, 0.0);
+        grid.get_agent_mut(&opponent).unwrap().mobility = 0.0;
+        grid.get_agent_mut(&agents[2].id).unwrap().mobility = 1.0;
+        // 移動性 0.5・0・1 の分散 1/6 を 3 形質で平均して 0.25 で割る
+        let phenotypic = MetricsCalculator::diversity(&grid).phenotypic_diversity;
+        assert!((phenotypic - 2.0 / 9.0).abs() < 1e-12);
+        assert_eq!(
+            MetricsCalculator::fitness(&[], &FitnessFunction::RawScore),
+            FitnessMetrics::default()
+        );
+    }
+}
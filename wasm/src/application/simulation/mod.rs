@@ -1,7 +1,41 @@
+pub mod battle_history;
+pub mod battle_schedule;
+pub mod builder;
+pub mod cancellation;
 pub mod config;
+pub mod evolution_tracker;
+pub mod extinction;
+pub mod frame;
+pub mod histogram;
+pub mod inspection;
+pub mod lifecycle;
+pub mod metrics;
+pub mod population;
+pub mod reproduction;
+pub mod result;
 pub mod service;
 pub mod statistics;
+pub mod steady_state;
+pub mod tracker;
+pub mod validation;
 
+pub use battle_history::*;
+pub use battle_schedule::*;
+pub use builder::*;
+pub use cancellation::*;
 pub use config::*;
+pub use evolution_tracker::*;
+pub use extinction::*;
+pub use frame::*;
+pub use histogram::*;
+pub use inspection::*;
+pub use lifecycle::*;
+pub use metrics::*;
+pub use population::*;
+pub use reproduction::*;
+pub use result::*;
 pub use service::*;
 pub use statistics::*;
+pub use steady_state::*;
+pub use tracker::*;
+pub use validation::*;
@@ -0,0 +1,193 @@
+use crate::domain::agent::{Agent, MovementStrategy, Position, StrategyType};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 初期集団の一部を占める系統。指定しない形質はランダムに決める
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archetype {
+    pub proportion: f64,
+    pub strategy: Option<StrategyType>,
+    pub mobility: Option<f64>,
+    pub movement_strategy: Option<MovementStrategy>,
+}
+
+impl Archetype {
+    pub fn strategy(strategy: StrategyType, proportion: f64) -> Self {
+        Self {
+            strategy: Some(strategy),
+            ..Self::random(proportion)
+        }
+    }
+
+    // すべての形質がランダムな系統
+    pub fn random(proportion: f64) -> Self {
+        Self {
+            proportion,
+            strategy: None,
+            mobility: None,
+            movement_strategy: None,
+        }
+    }
+
+    pub fn with_mobility(mut self, mobility: f64) -> Self {
+        self.mobility = Some(mobility.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn with_movement_strategy(mut self, movement_strategy: MovementStrategy) -> Self {
+        self.movement_strategy = Some(movement_strategy);
+        self
+    }
+
+    pub fn spawn<R: Rng + ?Sized>(&self, position: Position, rng: &mut R) -> Agent {
+        let mut agent = Agent::random(position, rng);
+        if let Some(strategy) = self.strategy {
+            agent.strategy = strategy;
+        }
+        if let Some(mobility) = self.mobility {
+            agent.mobility = mobility;
+        }
+        if let Some(movement_strategy) = self.movement_strategy {
+            agent.movement_strategy = movement_strategy;
+        }
+        agent
+    }
+}
+
+// 系統ごとの割合で初期集団を作る（侵入実験用：例 AllDefect 90% + TitForTat 10%）
+// 割合は合計で正規化するので、合計が 1 でなくてもよい
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InitialPopulationSpec {
+    pub archetypes: Vec<Archetype>,
+}
+
+impl InitialPopulationSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_archetype(mut self, archetype: Archetype) -> Self {
+        self.archetypes.push(archetype);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.archetypes.is_empty() {
+            return Err("Initial population needs at least one archetype".to_string());
+        }
+        if let Some(archetype) = self
+            .archetypes
+            .iter()
+            .find(|archetype| !(archetype.proportion.is_finite() && archetype.proportion >= 0.0))
+        {
+            return Err(format!(
+                "Archetype proportion must be non-negative, got {}",
+                archetype.proportion
+            ));
+        }
+        if self.total_proportion() <= 0.0 {
+            return Err("Archetype proportions must not all be zero".to_string());
+        }
+        Ok(())
+    }
+
+    // 最大剰余方式で agent_count を各系統の人数に割り振る
+    pub fn counts(&self, agent_count: usize) -> Vec<usize> {
+        let total = self.total_proportion();
+        let quotas: Vec<f64> = self
+            .archetypes
+            .iter()
+            .map(|archetype| archetype.proportion / total * agent_count as f64)
+            .collect();
+        let mut counts: Vec<usize> = quotas.iter().map(|quota| quota.floor() as usize).collect();
+
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+        by_remainder.sort_by(|&a, &b| {
+            let remainder = |i: usize| quotas[i] - quotas[i].floor();
+            remainder(b).total_cmp(&remainder(a))
+        });
+        let missing = agent_count.saturating_sub(counts.iter().sum());
+        for &index in by_remainder.iter().take(missing) {
+            counts[index] += 1;
+        }
+
+        counts
+    }
+
+    // 各エージェントの系統を配置順に並べる（系統の偏りが出ないようシャッフルする）
+    pub fn assign<R: Rng + ?Sized>(&self, agent_count: usize, rng: &mut R) -> Vec<&Archetype> {
+        let mut assignment = self.grouped(agent_count);
+        assignment.shuffle(rng);
+        assignment
+    }
+
+    // 系統ごとにまとめて並べる（配置パターンの埋める順と組み合わせると系統が空間的に固まる）
+    pub fn grouped(&self, agent_count: usize) -> Vec<&Archetype> {
+        self.counts(agent_count)
+            .into_iter()
+            .zip(&self.archetypes)
+            .flat_map(|(count, archetype)| std::iter::repeat_n(archetype, count))
+            .collect()
+    }
+
+    fn total_proportion(&self) -> f64 {
+        self.archetypes
+            .iter()
+            .map(|archetype| archetype.proportion)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_counts_follow_proportions_and_sum_to_agent_count() {
+        // Arrange: 30% AllDefect, 30% TitForTat, 40% ランダム
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 0.3))
+            .with_archetype(Archetype::strategy(StrategyType::TitForTat, 0.3))
+            .with_archetype(Archetype::random(0.4));
+
+        // Act
+        let counts = spec.counts(101);
+
+        // Assert: 端数は剰余の大きい系統に配られる
+        assert_eq!(counts, vec![30, 30, 41]);
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_spawn_applies_fixed_traits() {
+        // Arrange
+        let archetype = Archetype::strategy(StrategyType::Pavlov, 1.0)
+            .with_mobility(0.2)
+            .with_movement_strategy(MovementStrategy::Settler);
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+
+        // Act
+        let agent = archetype.spawn(Position::new(1, 1), &mut rng);
+
+        // Assert
+        assert_eq!(agent.strategy, StrategyType::Pavlov);
+        assert_eq!(agent.mobility, 0.2);
+        assert_eq!(agent.movement_strategy, MovementStrategy::Settler);
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_and_zero_specs() {
+        // Arrange
+        let empty = InitialPopulationSpec::new();
+        let zero = InitialPopulationSpec::new().with_archetype(Archetype::random(0.0));
+        let negative = InitialPopulationSpec::new().with_archetype(Archetype::random(-1.0));
+
+        // Act & Assert
+        assert!(empty.validate().is_err());
+        assert!(zero.validate().is_err());
+        assert!(negative.validate().is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use super::{SimulationConfig, SteadyStateConfig};
+use crate::application::evolution::EvolutionService;
+use crate::domain::agent::Position;
+use crate::domain::grid::Grid;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 世代交代の方法
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ReproductionModel {
+    // turns_per_generation ごとに遺伝的アルゴリズムで全員を入れ替える
+    #[default]
+    Generational,
+    // エネルギーが尽きたら死亡し、閾値を超えたら空いた近傍セルに子を産む
+    // 世代数は turns_per_generation ごとの区切りとしてだけ数える
+    Energy(EnergyConfig),
+    // 毎ステップ、ランダムに選んだエージェントを近傍との比較で 1 体ずつ更新する（進化グラフ理論の標準的な更新）
+    // 世代数は turns_per_generation ごとの区切りとしてだけ数え、統計はステップごとにも記録する
+    SteadyState(SteadyStateConfig),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnergyConfig {
+    pub initial_energy: f64,
+    // 利得 1 点あたりに得るエネルギー
+    pub payoff_to_energy: f64,
+    // 毎ステップの基礎代謝
+    pub step_cost: f64,
+    // 別のセルへ移動したステップに追加で払う
+    pub move_cost: f64,
+    // これ以上のエネルギーで繁殖し、親と子で半分ずつ分ける
+    pub reproduction_threshold: f64,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self {
+            initial_energy: 10.0,
+            payoff_to_energy: 0.25,
+            step_cost: 2.0,
+            move_cost: 1.0,
+            reproduction_threshold: 20.0,
+        }
+    }
+}
+
+impl EnergyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            self.initial_energy,
+            self.payoff_to_energy,
+            self.step_cost,
+            self.move_cost,
+            self.reproduction_threshold,
+        ];
+        if values
+            .iter()
+            .any(|value| !value.is_finite() || *value < 0.0)
+        {
+            return Err("Energy parameters must be finite and non-negative".to_string());
+        }
+        if self.initial_energy <= 0.0 || self.reproduction_threshold <= 0.0 {
+            return Err("Initial energy and reproduction threshold must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+// 1 ステップ分のエネルギー収支・死亡・繁殖
+pub struct EnergyLifecycle;
+
+impl EnergyLifecycle {
+    // 対戦前のスコアと位置（ステップ開始時に記録したもの）
+    pub fn snapshot(grid: &Grid) -> HashMap<Uuid, (i32, Position)> {
+        grid.agents()
+            .values()
+            .map(|agent| (agent.id, (agent.score, agent.position)))
+            .collect()
+    }
+
+    // このステップの利得をエネルギーに換え、代謝と移動のコストを引く
+    pub fn settle(grid: &mut Grid, config: &EnergyConfig, before: &HashMap<Uuid, (i32, Position)>) {
+        for agent in grid.agents_mut().values_mut() {
+            let Some(&(score, position)) = before.get(&agent.id) else {
+                continue;
+            };
+            let moved = agent.position != position;
+            agent.energy += (agent.score - score) as f64 * config.payoff_to_energy
+                - config.step_cost
+                - if moved { config.move_cost } else { 0.0 };
+        }
+    }
+
+    // エネルギーが尽きたエージェントを取り除き、死亡数を返す
+    pub fn remove_starved(grid: &mut Grid) -> usize {
+        let starved: Vec<Uuid> = grid
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|agent| agent.energy <= 0.0)
+            .map(|agent| agent.id)
+            .collect();
+        for id in &starved {
+            grid.remove_agent(id);
+        }
+        starved.len()
+    }
+
+    // 閾値を超えたエージェントが空いた近傍セルに子を産む（突然変異あり）。出生数を返す
+    pub fn reproduce<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        config: &EnergyConfig,
+        mutation_rate: f64,
+        simulation: &SimulationConfig,
+        rng: &mut R,
+    ) -> usize {
+        let parents: Vec<Uuid> = grid
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|agent| agent.energy >= config.reproduction_threshold)
+            .map(|agent| agent.id)
+            .collect();
+
+        let mut births = 0;
+        for id in parents {
+            let Some(parent) = grid.get_agent(&id) else {
+                continue;
+            };
+            let empty = grid.get_empty_neighbors(&parent.position);
+            if empty.is_empty() {
+                continue;
+            }
+            let position = empty[rng.gen_range(0..empty.len())];
+
+            let mut child =
+                EvolutionService::breed(parent, parent, position, mutation_rate, simulation, rng);
+            child.energy = parent.energy / 2.0;
+
+            if let Some(parent) = grid.get_agent_mut(&id) {
+                parent.energy /= 2.0;
+            }
+            if grid.add_agent(child).is_ok() {
+                births += 1;
+            }
+        }
+        births
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Agent, MovementStrategy, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    fn agent_with_energy(x: usize, y: usize, energy: f64) -> Agent {
+        let mut agent = Agent::new(
+            Position::new(x, y),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        agent.energy = energy;
+        agent
+    }
+
+    #[test]
+    fn test_settle_converts_payoffs_and_charges_movement() {
+        // Arrange
+        let mut grid = Grid::new(5, 5);
+        grid.add_agent(agent_with_energy(0, 0, 10.0)).unwrap();
+        let before = EnergyLifecycle::snapshot(&grid);
+        let id = *grid.agents().keys().next().unwrap();
+        grid.get_agent_mut(&id).unwrap().score += 8;
+        grid.move_agent(&id, Position::new(1, 0)).unwrap();
+
+        // Act
+        EnergyLifecycle::settle(&mut grid, &EnergyConfig::default(), &before);
+
+        // Assert: 10 + 8 * 0.25 - 2 - 1
+        assert_eq!(grid.get_agent(&id).unwrap().energy, 9.0);
+    }
+
+    #[test]
+    fn test_starved_agents_die_and_rich_agents_reproduce() {
+        // Arrange
+        let mut grid = Grid::new(5, 5);
+        grid.add_agent(agent_with_energy(0, 0, 0.0)).unwrap();
+        grid.add_agent(agent_with_energy(3, 3, 30.0)).unwrap();
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+
+        // Act
+        let deaths = EnergyLifecycle::remove_starved(&mut grid);
+        let births = EnergyLifecycle::reproduce(
+            &mut grid,
+            &EnergyConfig::default(),
+            0.0,
+            &SimulationConfig::default(),
+            &mut rng,
+        );
+
+        // Assert: 子は親の隣に生まれ、エネルギーを半分ずつ分ける
+        assert_eq!((deaths, births), (1, 1));
+        assert_eq!(grid.agent_count(), 2);
+        assert!(grid.agents().values().all(|agent| agent.energy == 15.0
+            && agent.strategy == StrategyType::TitForTat
+            && agent.position.x >= 2));
+    }
+}
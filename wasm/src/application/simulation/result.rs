@@ -0,0 +1,273 @@
+use super::{ExtinctionEvent, IslandStatistics, SimulationStatistics, TagStatistics};
+use crate::domain::agent::StrategyType;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub generation_history: Vec<SimulationStatistics>,
+    // 全滅が起きたステップと、そのとき取った行動
+    #[serde(default)]
+    pub extinction_events: Vec<ExtinctionEvent>,
+}
+
+impl SimulationResult {
+    pub fn new(generation_history: Vec<SimulationStatistics>) -> Self {
+        Self {
+            generation_history,
+            extinction_events: Vec::new(),
+        }
+    }
+
+    pub fn with_extinction_events(mut self, events: Vec<ExtinctionEvent>) -> Self {
+        self.extinction_events = events;
+        self
+    }
+
+    pub fn final_statistics(&self) -> Option<&SimulationStatistics> {
+        self.generation_history.last()
+    }
+
+    // 世代ごとの (世代, 個体数)。戦略の盛衰をプロットする用
+    pub fn strategy_series(&self, strategy: StrategyType) -> Vec<(u32, usize)> {
+        self.generation_history
+            .iter()
+            .map(|stats| {
+                (
+                    stats.generation,
+                    *stats.strategy_counts.get(&strategy).unwrap_or(&0),
+                )
+            })
+            .collect()
+    }
+
+    // 直近 k 世代の統計を平均する（個体数・出生数・死亡数・罰の回数は四捨五入）
+    // 島ごと・タグごとの集計は、その島・タグが記録されている世代だけで番号ごとに平均する
+    pub fn tail_average(&self, k: usize) -> SimulationStatistics {
+        let start = self.generation_history.len().saturating_sub(k);
+        let tail = &self.generation_history[start..];
+        if tail.is_empty() {
+            return SimulationStatistics::new();
+        }
+
+        let n = tail.len() as f64;
+        let mean =
+            |value: fn(&SimulationStatistics) -> f64| tail.iter().map(value).sum::<f64>() / n;
+
+        let mut strategy_totals = HashMap::new();
+        let mut movement_strategy_totals = HashMap::new();
+        for stats in tail {
+            for (strategy, count) in &stats.strategy_counts {
+                *strategy_totals.entry(*strategy).or_insert(0) += count;
+            }
+            for (movement_strategy, count) in &stats.movement_strategy_counts {
+                *movement_strategy_totals
+                    .entry(movement_strategy.clone())
+                    .or_insert(0) += count;
+            }
+        }
+
+        SimulationStatistics {
+            generation: tail[tail.len() - 1].generation,
+            total_agents: mean(|s| s.total_agents as f64).round() as usize,
+            strategy_counts: strategy_totals
+                .into_iter()
+                .map(|(strategy, total)| (strategy, (total as f64 / n).round() as usize))
+                .collect(),
+            movement_strategy_counts: movement_strategy_totals
+                .into_iter()
+                .map(|(strategy, total)| (strategy, (total as f64 / n).round() as usize))
+                .collect(),
+            average_cooperation_rate: mean(|s| s.average_cooperation_rate),
+            average_mobility: mean(|s| s.average_mobility),
+            average_score: mean(|s| s.average_score),
+            average_fitness: mean(|s| s.average_fitness),
+            mutation_rate: mean(|s| s.mutation_rate),
+            island_statistics: Self::average_islands(tail),
+            tag_statistics: Self::average_tags(tail),
+            births: mean(|s| s.births as f64).round() as usize,
+            deaths: mean(|s| s.deaths as f64).round() as usize,
+            punishments: mean(|s| s.punishments as f64).round() as usize,
+            punishment_frequency: mean(|s| s.punishment_frequency),
+            niche_count: mean(|s| s.niche_count as f64).round() as usize,
+            shannon_index: mean(|s| s.shannon_index),
+            simpson_index: mean(|s| s.simpson_index),
+        }
+    }
+
+    // 島の番号順
+    fn average_islands(tail: &[SimulationStatistics]) -> Vec<IslandStatistics> {
+        // 島の番号 → (記録された世代数, 個体数・協力率・スコアの合計)
+        let mut totals: BTreeMap<usize, (f64, f64, f64, f64)> = BTreeMap::new();
+        for island in tail.iter().flat_map(|stats| &stats.island_statistics) {
+            let total = totals.entry(island.island).or_default();
+            total.0 += 1.0;
+            total.1 += island.total_agents as f64;
+            total.2 += island.average_cooperation_rate;
+            total.3 += island.average_score;
+        }
+        totals
+            .into_iter()
+            .map(
+                |(island, (n, agents, cooperation, score))| IslandStatistics {
+                    island,
+                    total_agents: (agents / n).round() as usize,
+                    average_cooperation_rate: cooperation / n,
+                    average_score: score / n,
+                },
+            )
+            .collect()
+    }
+
+    // タグの番号順
+    fn average_tags(tail: &[SimulationStatistics]) -> Vec<TagStatistics> {
+        // タグ → (記録された世代数, 個体数・協力率・内集団びいきの強さの合計)
+        let mut totals: BTreeMap<u8, (f64, f64, f64, f64)> = BTreeMap::new();
+        for tag in tail.iter().flat_map(|stats| &stats.tag_statistics) {
+            let total = totals.entry(tag.tag).or_default();
+            total.0 += 1.0;
+            total.1 += tag.total_agents as f64;
+            total.2 += tag.average_cooperation_rate;
+            total.3 += tag.average_in_group_bias;
+        }
+        totals
+            .into_iter()
+            .map(|(tag, (n, agents, cooperation, bias))| TagStatistics {
+                tag,
+                total_agents: (agents / n).round() as usize,
+                average_cooperation_rate: cooperation / n,
+                average_in_group_bias: bias / n,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(
+        generation: u32,
+        cooperation: f64,
+        score: f64,
+        defectors: usize,
+    ) -> SimulationStatistics {
+        SimulationStatistics {
+            generation,
+            total_agents: 10,
+            strategy_counts: HashMap::from([(StrategyType::AllDefect, defectors)]),
+            average_cooperation_rate: cooperation,
+            average_score: score,
+            ..SimulationStatistics::new()
+        }
+    }
+
+    #[test]
+    fn test_tail_average_over_last_three_of_five_generations() {
+        // Arrange
+        let result = SimulationResult::new(vec![
+            stats(0, 0.9, 100.0, 0),
+            stats(1, 0.8, 90.0, 1),
+            stats(2, 0.6, 60.0, 2),
+            stats(3, 0.4, 30.0, 4),
+            stats(4, 0.2, 30.0, 6),
+        ]);
+
+        // Act
+        let average = result.tail_average(3);
+
+        // Assert
+        assert_eq!(average.generation, 4);
+        assert_eq!(average.total_agents, 10);
+        assert!((average.average_cooperation_rate - 0.4).abs() < 1e-12);
+        assert!((average.average_score - 40.0).abs() < 1e-12);
+        assert_eq!(average.strategy_counts[&StrategyType::AllDefect], 4);
+    }
+
+    #[test]
+    fn test_tail_average_with_k_larger_than_history_uses_all_generations() {
+        // Arrange
+        let result = SimulationResult::new(vec![stats(0, 0.2, 10.0, 0), stats(1, 0.4, 20.0, 0)]);
+
+        // Act
+        let average = result.tail_average(10);
+
+        // Assert
+        assert!((average.average_cooperation_rate - 0.3).abs() < 1e-12);
+        assert!((average.average_score - 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tail_average_covers_event_counts_and_island_and_tag_statistics() {
+        // Arrange
+        let record = |generation: u32, births: usize, frequency: f64, cooperation: f64| {
+            SimulationStatistics {
+                births,
+                deaths: births + 2,
+                punishments: births / 2,
+                punishment_frequency: frequency,
+                island_statistics: vec![IslandStatistics {
+                    island: 0,
+                    total_agents: births,
+                    average_cooperation_rate: cooperation,
+                    average_score: 10.0,
+                }],
+                tag_statistics: vec![TagStatistics {
+                    tag: 1,
+                    total_agents: 5,
+                    average_cooperation_rate: cooperation,
+                    average_in_group_bias: frequency,
+                }],
+                ..stats(generation, cooperation, 0.0, 0)
+            }
+        };
+        let result = SimulationResult::new(vec![
+            record(0, 100, 0.9, 0.9),
+            record(1, 4, 0.2, 0.2),
+            record(2, 8, 0.4, 0.6),
+        ]);
+
+        // Act
+        let average = result.tail_average(2);
+
+        // Assert
+        assert_eq!(average.births, 6);
+        assert_eq!(average.deaths, 8);
+        assert_eq!(average.punishments, 3);
+        assert!((average.punishment_frequency - 0.3).abs() < 1e-12);
+        assert_eq!(average.island_statistics.len(), 1);
+        assert_eq!(average.island_statistics[0].total_agents, 6);
+        assert!((average.island_statistics[0].average_cooperation_rate - 0.4).abs() < 1e-12);
+        assert_eq!(average.tag_statistics.len(), 1);
+        assert_eq!(average.tag_statistics[0].tag, 1);
+        assert!((average.tag_statistics[0].average_in_group_bias - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_strategy_series_and_census_per_generation() {
+        // Arrange
+        let result = SimulationResult::new(vec![stats(0, 0.9, 100.0, 1), stats(1, 0.5, 50.0, 3)]);
+
+        // Act
+        let series = result.strategy_series(StrategyType::AllDefect);
+        let census = result.generation_history[1].strategy_census();
+
+        // Assert: 0 体の組み込み戦略も国勢調査に含まれる
+        assert_eq!(series, vec![(0, 1), (1, 3)]);
+        assert_eq!(census.len(), StrategyType::ALL.len());
+        assert_eq!(census[1], (StrategyType::AllDefect, 3));
+        assert_eq!(census[0], (StrategyType::AllCooperate, 0));
+    }
+
+    #[test]
+    fn test_tail_average_of_empty_history() {
+        // Arrange
+        let result = SimulationResult::default();
+
+        // Act
+        let average = result.tail_average(3);
+
+        // Assert
+        assert_eq!(average.total_agents, 0);
+    }
+}
@@ -1,9 +1,57 @@
-use super::{SimulationConfig, SimulationStatistics};
+use super::{
+    AgentTracker, BattleHistoryPage, BattleHistoryQuery, BattleHistoryRetention,
+    BattleHistoryUsage, CancellationToken, ConfigViolation, DetailedStatistics, EnergyLifecycle,
+    EvolutionSummary, EvolutionTracker, ExtinctionEvent, ExtinctionPolicy, Frame, FrameAgent,
+    FrameSequence, GenerationRecord, GlobalBattleStats, InitialPopulationSpec, IslandStatistics,
+    LifecycleConfig, MemoryUsage, MetricsCalculator, NeighborView, PerformanceMetrics,
+    PerformanceReport, ReproductionModel, SimulationConfig, SimulationResult, SimulationStatistics,
+    SteadyStateConfig, SteadyStateUpdate, Stopwatch, TagStatistics, TrackingTarget,
+    TrajectoryPoint, UpdateRule,
+};
+use crate::application::analysis::{
+    InteractionNetwork, NetworkAnalysisService, NetworkMetrics, SpatialAnalysisService,
+    SpatialMetrics,
+};
+use crate::application::evolution::{
+    AdaptiveMutation, CrossoverMethod, EvolutionService, EvolutionStrategyFactory,
+    FitnessEvaluator, FitnessFunction, FitnessSharing, MultiObjective, ParetoFront,
+    SelectionMethod, WeightedFitness,
+};
 use crate::domain::{
-    agent::Agent,
-    game::GameService,
-    grid::{Grid, GridService},
+    agent::{
+        Action, Agent, GameHistory, Genotype, MemoryGenome, Position, StrategyRegistry,
+        StrategyType, TagConfig,
+    },
+    game::{
+        GameService, GameType, PayoffMatrix, PayoffSchedule, PublicGoodsGame, PunishmentConfig,
+        ReputationBoard, ReputationConfig,
+    },
+    grid::{
+        DemeStructure, Grid, GridService, HeatmapField, MigrationModel, MovementPolicyKind,
+        NeighborhoodType, NetworkConfig, NetworkWorld, PlacementPattern, Region, ResourceConfig,
+        ZoneMap,
+    },
+    invariants::{InvariantViolation, WorldInvariants},
+};
+use crate::infrastructure::persistence::{
+    ExportOptions, SerializationService, SimulationSnapshot, SNAPSHOT_VERSION,
 };
+use crate::infrastructure::render::{SvgOptions, SvgRenderer};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use std::collections::{vec_deque, HashMap, VecDeque};
+use uuid::Uuid;
+
+// 構築時に初期集団の形質をどこまで指定したか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Founders {
+    // 全形質ランダム（行動表はランダムな決定的な表）
+    Random,
+    // 戦略を指定（行動表は戦略を展開した表、タグ・攻撃性は設定に従ってランダム）
+    Strategies,
+    // 遺伝子型を指定（タグ・攻撃性・行動表は遺伝子型のものを使い、行動表がなければ戦略を展開する）
+    Genotypes,
+}
 
 pub struct SimulationService {
     grid: Grid,
@@ -11,20 +59,53 @@ pub struct SimulationService {
     turn: u32,
     turns_per_generation: u32,
     config: SimulationConfig,
+    // 実効の突然変異率。adaptive_mutation がなければ常に config.mutation_rate
+    mutation_rate: f64,
+    // 世代交代で作る子の数。構築時・reset 時の個体数で、途中で個体が減っても変わらない
+    population_target: usize,
+    // 古いものから捨て、DIAGNOSTICS_CAPACITY 件までしか残さない
+    diagnostics: Vec<String>,
+    generation_history: VecDeque<SimulationStatistics>,
+    // 定常状態更新でのみ、ステップごとの統計を残す
+    step_history: VecDeque<SimulationStatistics>,
+    // histogram_bins が設定されているときだけ世代ごとに記録する
+    detailed_history: VecDeque<DetailedStatistics>,
+    // spatial_metrics_enabled のときだけ世代ごとに記録する
+    spatial_history: VecDeque<SpatialMetrics>,
+    // multi_objective のときだけ世代ごとに記録する
+    pareto_history: VecDeque<ParetoFront>,
+    // ネットワーク上で実行しているときだけ世代ごとに記録する
+    network_history: VecDeque<NetworkMetrics>,
+    // 保持方針で捨てた対戦記録の累計
+    evicted_battle_records: u64,
+    tracker: Option<AgentTracker>,
+    evolution_tracker: EvolutionTracker,
+    // 今の世代の処理時間の途中経過（世代交代で記録して 0 に戻す）
+    generation_timings: PerformanceMetrics,
+    // 世代交代で親の世代から回収した対戦履歴のバッファ（子に使い回す）
+    history_pool: Vec<GameHistory>,
+    // 前の世代の区切りからの出生数・死亡数
+    births: usize,
+    deaths: usize,
+    // 前の世代の区切りからつなぎ替えた辺の数
+    rewired_links: usize,
+    // 直前の世代交代で親の世代に数えたニッチの数（適応度共有を設定していなければ 0）
+    niche_count: usize,
+    // 公開の評判。reputation を設定したときだけ囚人のジレンマの対戦ごとに更新する
+    reputation_board: ReputationBoard,
+    // 前の世代の区切りからの罰の回数と、罰の機会（相手に裏切られた回数）
+    punishments: usize,
+    punishment_opportunities: usize,
+    extinction_events: Vec<ExtinctionEvent>,
+    // 全滅時の方針が Stop で止まっているあいだ true
+    stopped: bool,
+    rng: ChaCha12Rng,
+    cancellation: CancellationToken,
 }
 
 impl SimulationService {
     pub fn new(width: usize, height: usize, agent_count: usize) -> Result<Self, String> {
-        let mut grid = Grid::new(width, height);
-        GridService::initialize_random_agents(&mut grid, agent_count)?;
-
-        Ok(Self {
-            grid,
-            generation: 0,
-            turn: 0,
-            turns_per_generation: 100,
-            config: SimulationConfig::default(),
-        })
+        Self::with_config(width, height, agent_count, SimulationConfig::default())
     }
 
     pub fn with_config(
@@ -33,16 +114,430 @@ impl SimulationService {
         agent_count: usize,
         config: SimulationConfig,
     ) -> Result<Self, String> {
-        let mut grid = Grid::new(width, height).with_torus_mode(config.torus_field_enabled);
-        GridService::initialize_random_agents(&mut grid, agent_count)?;
+        let mut simulation = Self::prepare(width, height, agent_count, config)?;
+        simulation.populate(agent_count)?;
+
+        Ok(simulation)
+    }
+
+    // 進化戦略を組み立て、agent_count 体で設定を検証した空の盤面を作る（どの構築方法もここを通る）
+    fn prepare(
+        width: usize,
+        height: usize,
+        agent_count: usize,
+        config: SimulationConfig,
+    ) -> Result<Self, String> {
+        let config = EvolutionStrategyFactory::build(&config);
+        let grid = Self::build_grid(width, height, &config);
+        let mut simulation = Self::from_grid(grid, config);
+        simulation.validate_population(agent_count)?;
+        Ok(simulation)
+    }
+
+    fn validate_population(&mut self, agent_count: usize) -> Result<(), String> {
+        let (width, height) = self.get_grid_size();
+        let violations = self.config.validate_for(width, height, agent_count);
+        if !violations.is_empty() {
+            return Err(format!(
+                "Invalid configuration: {}",
+                ConfigViolation::join(&violations)
+            ));
+        }
+        self.population_target = agent_count;
+        if let Some(warning) = Self::check_payoffs(
+            &self.config.payoff_matrix,
+            self.config.allow_non_dilemma_payoffs,
+        )? {
+            self.diagnostics.push(warning);
+        }
+        Ok(())
+    }
+
+    // 初期集団の指定があれば系統ごとの割合で、なければ全形質ランダムで配置する
+    // Uniform 以外の配置パターンでは系統を埋める順にまとめて置く（中心の塊・円弧・ブロックごと）
+    fn populate(&mut self, agent_count: usize) -> Result<(), String> {
+        self.place_initial_agents(agent_count)?;
+        let founders = match self.config.initial_population {
+            Some(_) => Founders::Strategies,
+            None => Founders::Random,
+        };
+        self.initialize_traits(founders)
+    }
+
+    // 置いた個体に設定由来の形質（行動表・タグ・攻撃性・初期エネルギー）を与える
+    fn initialize_traits(&mut self, founders: Founders) -> Result<(), String> {
+        self.assign_memory_genomes(founders != Founders::Random)?;
+        if founders != Founders::Genotypes {
+            self.assign_tags();
+            self.assign_aggression();
+        }
+        self.assign_initial_energy()
+    }
+
+    // punishment が設定されていれば全員にランダムな攻撃性を与える
+    fn assign_aggression(&mut self) {
+        let Some(punishment) = self.config.punishment else {
+            return;
+        };
+        let ids: Vec<Uuid> = self
+            .grid
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| agent.id)
+            .collect();
+        for id in ids {
+            if let Some(agent) = self.grid.get_agent_mut(&id) {
+                punishment.assign(agent, &mut self.rng);
+            }
+        }
+    }
+
+    // tags が設定されていれば全員にランダムなタグと内集団びいきの強さを与える
+    fn assign_tags(&mut self) {
+        let Some(tags) = self.config.tags else {
+            return;
+        };
+        let ids: Vec<Uuid> = self
+            .grid
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| agent.id)
+            .collect();
+        for id in ids {
+            if let Some(agent) = self.grid.get_agent_mut(&id) {
+                tags.assign(agent, &mut self.rng);
+            }
+        }
+    }
+
+    fn assign_initial_energy(&mut self) -> Result<(), String> {
+        if let ReproductionModel::Energy(energy) = self.config.reproduction {
+            energy.validate()?;
+            for agent in self.grid.agents_mut().values_mut() {
+                agent.energy = energy.initial_energy;
+            }
+        }
+        Ok(())
+    }
+
+    // memory_genome が設定されていれば、行動表を持たない全員に持たせる
+    // seeded なら各自の戦略を展開した表、それ以外はランダムな決定的な表
+    fn assign_memory_genomes(&mut self, seeded: bool) -> Result<(), String> {
+        let Some(memory) = self.config.memory_genome else {
+            return Ok(());
+        };
+        let ids: Vec<Uuid> = self
+            .grid
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| agent.id)
+            .collect();
+        for id in ids {
+            let Some(agent) = self.grid.get_agent_mut(&id) else {
+                continue;
+            };
+            if agent.memory_genome.is_some() {
+                continue;
+            }
+            let expanded = seeded
+                .then(|| MemoryGenome::from_strategy(agent.strategy, memory))
+                .flatten();
+            let genome = match expanded {
+                Some(genome) => genome,
+                None => MemoryGenome::random_deterministic(memory, &mut self.rng)?,
+            };
+            agent.strategy = genome.classify();
+            agent.memory_genome = Some(genome);
+        }
+        Ok(())
+    }
+
+    fn place_initial_agents(&mut self, agent_count: usize) -> Result<(), String> {
+        let spec = self.config.initial_population.clone();
+        if let Some(spec) = &spec {
+            spec.validate()?;
+        }
+
+        if self.config.placement == PlacementPattern::Uniform {
+            let Some(spec) = spec else {
+                return GridService::initialize_random_agents(
+                    &mut self.grid,
+                    agent_count,
+                    &mut self.rng,
+                );
+            };
+            let assignment = spec.assign(agent_count, &mut self.rng);
+            return GridService::place_agents(
+                &mut self.grid,
+                agent_count,
+                &mut self.rng,
+                |position, index, rng| assignment[index].spawn(position, rng),
+            );
+        }
+
+        let positions = self
+            .config
+            .placement
+            .positions(&self.grid, agent_count, &mut self.rng)?;
+        let assignment = spec.as_ref().map(|spec| spec.grouped(agent_count));
+        for (index, position) in positions.into_iter().enumerate() {
+            let agent = match &assignment {
+                Some(assignment) => assignment[index].spawn(position, &mut self.rng),
+                None => Agent::random(position, &mut self.rng),
+            };
+            self.grid.add_agent(agent)?;
+        }
+        Ok(())
+    }
+
+    pub fn with_strategies(
+        width: usize,
+        height: usize,
+        strategies: &[StrategyType],
+        config: SimulationConfig,
+    ) -> Result<Self, String> {
+        let mut simulation = Self::prepare(width, height, strategies.len(), config)?;
+        GridService::initialize_with_strategies(
+            &mut simulation.grid,
+            strategies,
+            &mut simulation.rng,
+        )?;
+        simulation.initialize_traits(Founders::Strategies)?;
+
+        Ok(simulation)
+    }
+
+    pub fn with_genotypes(
+        width: usize,
+        height: usize,
+        agent_count: usize,
+        genotypes: &[Genotype],
+        config: SimulationConfig,
+    ) -> Result<Self, String> {
+        let mut simulation = Self::prepare(width, height, agent_count, config)?;
+        GridService::initialize_from_genotypes(&mut simulation.grid, agent_count, genotypes)?;
+        simulation.initialize_traits(Founders::Genotypes)?;
+
+        Ok(simulation)
+    }
+
+    // 書き出した個体（JSON・バイナリ）から集団を作る（移植実験や保存した集団の続きの進化）
+    // 個体はスコア・履歴・形質を保ったまま元の位置に置き、置けない個体は空きセルへ移して診断に残す
+    pub fn with_agents(
+        width: usize,
+        height: usize,
+        agents: HashMap<Uuid, Agent>,
+        config: SimulationConfig,
+    ) -> Result<Self, String> {
+        let mut simulation = Self::prepare(width, height, agents.len(), config)?;
+        let relocated = GridService::place_imported_agents(
+            &mut simulation.grid,
+            agents.into_values().collect(),
+            &mut simulation.rng,
+        )?;
+        if relocated > 0 {
+            simulation.diagnostics.push(format!(
+                "{relocated} imported agents were outside the {width}x{height} grid or on a taken cell and were moved to empty cells"
+            ));
+        }
+        // エネルギー型の繁殖では、エネルギーを持たない個体に初期エネルギーを与える
+        if let ReproductionModel::Energy(energy) = simulation.config.reproduction {
+            for agent in simulation.grid.agents_mut().values_mut() {
+                if agent.energy <= 0.0 {
+                    agent.energy = energy.initial_energy;
+                }
+            }
+        }
+        Ok(simulation)
+    }
+
+    pub fn from_frame(sequence: &FrameSequence, index: usize) -> Result<Self, String> {
+        Self::from_frame_with_config(sequence, index, SimulationConfig::default())
+    }
+
+    // 記録済みフレームからグリッドを復元する（履歴・スコアは引き継がない近似的な復元）
+    // フレームに残らない形質（行動表・タグ・攻撃性・エネルギー）は設定に従って配り直す
+    pub fn from_frame_with_config(
+        sequence: &FrameSequence,
+        index: usize,
+        config: SimulationConfig,
+    ) -> Result<Self, String> {
+        let frame = sequence.get(index).ok_or_else(|| {
+            format!(
+                "Frame index {index} is out of range ({} frames recorded)",
+                sequence.len()
+            )
+        })?;
+
+        let mut simulation =
+            Self::prepare(sequence.width, sequence.height, frame.agents.len(), config)?;
+        for frame_agent in &frame.agents {
+            simulation.grid.add_agent(frame_agent.to_agent()?)?;
+        }
+        simulation.initialize_traits(Founders::Strategies)?;
+        simulation.generation = frame.generation;
+        simulation.turn = frame.turn;
+        Ok(simulation)
+    }
 
-        Ok(Self {
+    fn build_grid(width: usize, height: usize, config: &SimulationConfig) -> Grid {
+        Grid::new(width, height)
+            .with_torus_mode(config.torus_field_enabled)
+            .with_neighborhood(config.neighborhood)
+            .with_demes(config.deme_structure)
+            .with_resources(config.resources)
+            .with_zones(config.zones.clone())
+            .with_obstacles(&config.obstacles)
+            .with_network(
+                config
+                    .network
+                    .as_ref()
+                    .map(|network| network.build(width * height)),
+            )
+    }
+
+    fn from_grid(grid: Grid, config: SimulationConfig) -> Self {
+        let population_target = grid.agent_count();
+        Self {
             grid,
             generation: 0,
             turn: 0,
             turns_per_generation: 100,
+            population_target,
+            diagnostics: Vec::new(),
+            generation_history: VecDeque::new(),
+            step_history: VecDeque::new(),
+            detailed_history: VecDeque::new(),
+            spatial_history: VecDeque::new(),
+            pareto_history: VecDeque::new(),
+            network_history: VecDeque::new(),
+            evicted_battle_records: 0,
+            tracker: config.agent_tracking.clone().map(AgentTracker::new),
+            evolution_tracker: EvolutionTracker::new(config.evolution_history_size),
+            generation_timings: PerformanceMetrics::default(),
+            history_pool: Vec::new(),
+            births: 0,
+            deaths: 0,
+            rewired_links: 0,
+            niche_count: 0,
+            reputation_board: ReputationBoard::new(),
+            punishments: 0,
+            punishment_opportunities: 0,
+            extinction_events: Vec::new(),
+            stopped: false,
+            rng: Self::build_rng(&config),
+            cancellation: CancellationToken::new(),
+            mutation_rate: config.mutation_rate,
             config,
-        })
+        }
+    }
+
+    // シード指定時は同じ設定で同じ世代履歴を再現できる
+    fn build_rng(config: &SimulationConfig) -> ChaCha12Rng {
+        match config.seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_entropy(),
+        }
+    }
+
+    pub fn save_snapshot(&self) -> SimulationSnapshot {
+        let (width, height) = self.get_grid_size();
+        SimulationSnapshot {
+            schema_version: SNAPSHOT_VERSION,
+            width,
+            height,
+            generation: self.generation,
+            turn: self.turn,
+            turns_per_generation: self.turns_per_generation,
+            config: self.config.clone(),
+            agents: self
+                .grid
+                .agents_in_position_order()
+                .into_iter()
+                .cloned()
+                .collect(),
+            generation_history: self.generation_history.iter().cloned().collect(),
+            diagnostics: self.diagnostics.clone(),
+            rng: self.rng.clone(),
+            network: self.grid.network().cloned(),
+            reputation_board: self.reputation_board.clone(),
+            evicted_battle_records: self.evicted_battle_records,
+            mutation_rate: Some(self.mutation_rate),
+            population_target: Some(self.population_target),
+            step_history: self.step_history.iter().cloned().collect(),
+            detailed_history: self.detailed_history.iter().cloned().collect(),
+            spatial_history: self.spatial_history.iter().cloned().collect(),
+            pareto_history: self.pareto_history.iter().cloned().collect(),
+            network_history: self.network_history.iter().cloned().collect(),
+            tracker: self.tracker.clone(),
+            evolution_tracker: Some(self.evolution_tracker.clone()),
+            births: self.births,
+            deaths: self.deaths,
+            punishments: self.punishments,
+            punishment_opportunities: self.punishment_opportunities,
+            rewired_links: self.rewired_links,
+            niche_count: self.niche_count,
+            extinction_events: self.extinction_events.clone(),
+            stopped: self.stopped,
+            resources: self.grid.resources().cloned(),
+        }
+    }
+
+    // 保存時点から同じ乱数列で続きを実行できる状態に戻す
+    pub fn restore_snapshot(snapshot: SimulationSnapshot) -> Result<Self, String> {
+        let mut grid = Self::build_grid(snapshot.width, snapshot.height, &snapshot.config);
+        if let Some(network) = snapshot.network {
+            if network.node_count() != snapshot.width * snapshot.height {
+                return Err(format!(
+                    "Network has {} nodes but the grid has {} cells",
+                    network.node_count(),
+                    snapshot.width * snapshot.height
+                ));
+            }
+            grid.set_network(Some(network));
+        }
+        if let Some(resources) = snapshot.resources {
+            grid.restore_resources(resources)?;
+        }
+        for agent in snapshot.agents {
+            grid.add_agent(agent)?;
+        }
+
+        let mut simulation = Self::from_grid(grid, snapshot.config);
+        simulation.generation = snapshot.generation;
+        simulation.turn = snapshot.turn;
+        simulation.turns_per_generation = snapshot.turns_per_generation;
+        simulation.generation_history = snapshot.generation_history.into();
+        simulation.diagnostics = snapshot.diagnostics;
+        simulation.rng = snapshot.rng;
+        simulation.reputation_board = snapshot.reputation_board;
+        simulation.evicted_battle_records = snapshot.evicted_battle_records;
+        if let Some(rate) = snapshot.mutation_rate {
+            simulation.mutation_rate = rate;
+        }
+        if let Some(target) = snapshot.population_target {
+            simulation.population_target = target;
+        }
+        simulation.step_history = snapshot.step_history.into();
+        simulation.detailed_history = snapshot.detailed_history.into();
+        simulation.spatial_history = snapshot.spatial_history.into();
+        simulation.pareto_history = snapshot.pareto_history.into();
+        simulation.network_history = snapshot.network_history.into();
+        if let Some(tracker) = snapshot.tracker {
+            simulation.tracker = Some(tracker);
+        }
+        if let Some(tracker) = snapshot.evolution_tracker {
+            simulation.evolution_tracker = tracker;
+        }
+        simulation.births = snapshot.births;
+        simulation.deaths = snapshot.deaths;
+        simulation.punishments = snapshot.punishments;
+        simulation.punishment_opportunities = snapshot.punishment_opportunities;
+        simulation.rewired_links = snapshot.rewired_links;
+        simulation.niche_count = snapshot.niche_count;
+        simulation.extinction_events = snapshot.extinction_events;
+        simulation.stopped = snapshot.stopped;
+        Ok(simulation)
     }
 
     pub fn set_strategy_complexity_penalty(&mut self, enabled: bool) {
@@ -53,121 +548,3547 @@ impl SimulationService {
         self.config.strategy_complexity_penalty_rate = rate.clamp(0.0, 1.0);
     }
 
-    pub fn set_torus_field(&mut self, enabled: bool) {
-        self.config.torus_field_enabled = enabled;
-        self.grid.set_torus_mode(enabled);
+    pub fn set_mutation_rate(&mut self, rate: f64) {
+        self.config.mutation_rate = rate.clamp(0.0, 1.0);
+        self.mutation_rate = self.config.mutation_rate;
     }
 
-    pub fn step(&mut self) -> SimulationStatistics {
-        self.process_games();
-        GridService::process_movements(&mut self.grid, self.config.torus_field_enabled);
+    pub fn set_adaptive_mutation(&mut self, adaptive: Option<AdaptiveMutation>) {
+        self.config.adaptive_mutation = adaptive;
+        if adaptive.is_none() {
+            self.mutation_rate = self.config.mutation_rate;
+        }
+    }
 
-        self.turn += 1;
+    pub fn set_weighted_fitness(&mut self, fitness: Option<WeightedFitness>) {
+        self.config.weighted_fitness = fitness;
+    }
 
-        if self.turn >= self.turns_per_generation {
-            self.next_generation();
+    // None に戻すと weighted_fitness・スコア正規化の設定から決める
+    pub fn set_fitness_function(&mut self, fitness: Option<FitnessFunction>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.fitness_function = fitness;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
         }
+        self.config = config;
+        Ok(())
+    }
 
-        self.get_statistics()
+    // 次の世代交代から反映する
+    pub fn set_selection(&mut self, method: SelectionMethod) -> Result<(), String> {
+        let config = self.config.clone().with_selection(method);
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
     }
 
-    pub fn get_statistics(&self) -> SimulationStatistics {
-        SimulationStatistics::calculate(self.grid.agents(), self.generation)
+    // 次の世代交代から反映する。None にすると適応度を分け合わない
+    pub fn set_fitness_sharing(&mut self, sharing: Option<FitnessSharing>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.fitness_sharing = sharing;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
     }
 
-    pub fn get_agents(&self) -> Vec<Agent> {
-        self.grid.agents().values().cloned().collect()
+    // 次の世代交代・繁殖から反映する
+    pub fn set_crossover_method(&mut self, method: CrossoverMethod) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.crossover_method = method;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
     }
 
-    pub fn get_grid_size(&self) -> (usize, usize) {
-        (self.grid.width(), self.grid.height())
+    pub fn set_score_normalization(&mut self, enabled: bool) {
+        self.config.score_normalization_enabled = enabled;
     }
 
-    pub fn get_generation(&self) -> u32 {
-        self.generation
+    // 次の reset から使われる
+    pub fn set_initial_population(&mut self, spec: Option<InitialPopulationSpec>) {
+        self.config.initial_population = spec;
     }
 
-    pub fn get_turn(&self) -> u32 {
-        self.turn
+    // 次の reset から使われる
+    pub fn set_placement(&mut self, placement: PlacementPattern) {
+        self.config.placement = placement;
     }
 
-    pub fn reset(&mut self, agent_count: usize) -> Result<(), String> {
-        self.grid.clear();
-        GridService::initialize_random_agents(&mut self.grid, agent_count)?;
-        self.generation = 0;
-        self.turn = 0;
+    pub fn set_memory_genome(&mut self, memory: Option<usize>) {
+        self.config.memory_genome = memory;
+    }
+
+    // すぐに反映する。None にすると公開の評判を捨てる
+    pub fn set_reputation(&mut self, reputation: Option<ReputationConfig>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.reputation = reputation;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        if reputation.is_none() {
+            self.reputation_board.clear();
+        }
         Ok(())
     }
 
-    fn process_games(&mut self) {
-        let mut games_to_play = Vec::new();
+    // すぐに反映する。初期の攻撃性は次の reset で配られる
+    pub fn set_punishment(&mut self, punishment: Option<PunishmentConfig>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.punishment = punishment;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
+    }
 
-        // Collect all agent data first to avoid borrowing conflicts
-        let agent_data: Vec<(uuid::Uuid, crate::domain::agent::position::Position)> = self
-            .grid
-            .agents()
+    pub fn get_reputation_board(&self) -> &ReputationBoard {
+        &self.reputation_board
+    }
+
+    // 次の reset から使われる（いまの個体のタグはそのまま）
+    pub fn set_tags(&mut self, tags: Option<TagConfig>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.tags = tags;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    // 囚人のジレンマの大小関係を満たさない利得表は allow_non_dilemma のときだけ警告付きで受け付ける
+    pub fn set_payoff_matrix(
+        &mut self,
+        payoffs: PayoffMatrix,
+        allow_non_dilemma: bool,
+    ) -> Result<(), String> {
+        if let Some(warning) = Self::check_payoffs(&payoffs, allow_non_dilemma)? {
+            self.diagnostics.push(warning);
+        }
+        self.config.payoff_matrix = payoffs;
+        self.config.allow_non_dilemma_payoffs = allow_non_dilemma;
+        Ok(())
+    }
+
+    // すぐに反映する。キーフレームは世代で数えるので、途中で設定すると今の世代に応じた利得表から始まる
+    pub fn set_payoff_schedule(&mut self, schedule: Option<PayoffSchedule>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.payoff_schedule = schedule;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    // 今の世代で使われる利得表（予定がなければ payoff_matrix）
+    pub fn current_payoffs(&self) -> PayoffMatrix {
+        match &self.config.payoff_schedule {
+            Some(schedule) => schedule.payoffs_at(self.generation, &self.config.payoff_matrix),
+            None => self.config.payoff_matrix,
+        }
+    }
+
+    // 予定のキーフレームに達した世代の始めに、切り替わった利得表を診断情報に残す
+    fn report_payoff_keyframe(&mut self) {
+        let Some(schedule) = &self.config.payoff_schedule else {
+            return;
+        };
+        if !schedule
+            .keyframes
             .iter()
-            .map(|(id, agent)| (*id, agent.position))
-            .collect();
+            .any(|keyframe| keyframe.generation == self.generation)
+        {
+            return;
+        }
+        let payoffs = self.current_payoffs();
+        self.diagnostics.push(format!(
+            "Generation {}: payoff schedule set T={}, R={}, P={}, S={}",
+            self.generation, payoffs.temptation, payoffs.reward, payoffs.punishment, payoffs.sucker
+        ));
+    }
 
-        // Find games to play without borrowing the grid
-        for (id1, pos1) in agent_data.iter() {
-            let neighbor_positions = pos1.neighbors_with_mode(
-                self.grid.width(),
-                self.grid.height(),
-                self.config.torus_field_enabled,
-            );
+    // 名前付きの利得表（雪かき・タカハト・鹿狩りなど）は意図的なものとして受け付ける
+    pub fn set_game_preset(&mut self, name: &str) -> Result<(), String> {
+        let payoffs = PayoffMatrix::preset(name).ok_or_else(|| {
+            format!(
+                "Unknown game preset: {name} (expected one of {})",
+                PayoffMatrix::PRESET_NAMES.join(", ")
+            )
+        })?;
+        self.set_payoff_matrix(payoffs, payoffs.validate_prisoners_dilemma().is_err())
+    }
 
-            for neighbor_pos in neighbor_positions {
-                if let Some(neighbor_agent) = self.grid.get_agent_at_position(&neighbor_pos) {
-                    let neighbor_id = neighbor_agent.id;
-                    if *id1 < neighbor_id {
-                        games_to_play.push((*id1, neighbor_id));
-                    }
-                }
+    fn check_payoffs(
+        payoffs: &PayoffMatrix,
+        allow_non_dilemma: bool,
+    ) -> Result<Option<String>, String> {
+        match payoffs.validate_prisoners_dilemma() {
+            Ok(()) => Ok(None),
+            Err(e) if allow_non_dilemma => {
+                Ok(Some(format!("{e}; running a different social dilemma")))
             }
+            Err(e) => Err(format!("{e} (allow non-dilemma payoffs to use it anyway)")),
+        }
+    }
+
+    // 資源量は満タンから始め直す
+    pub fn set_resources(&mut self, resources: Option<ResourceConfig>) {
+        self.config.resources = resources;
+        self.grid.set_resources(resources);
+    }
+
+    // すぐに反映する（区域の利得表は次の対戦から、突然変異率は次に生まれる子から）
+    pub fn set_zones(&mut self, zones: Option<ZoneMap>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.zones = zones.clone();
+        let mut violations = config.validate();
+        let (width, height) = self.get_grid_size();
+        if let Some(Err(message)) = zones.as_ref().map(|zones| zones.validate(width, height)) {
+            violations.push(ConfigViolation::new("zones", message));
         }
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        self.grid.set_zones(zones);
+        Ok(())
+    }
 
-        // Play games with proper borrowing
-        for (id1, id2) in games_to_play {
-            // Get immutable references first, then clone - with safe error handling
-            let (agent1_data, agent2_data) = {
-                let agent1 = match self.grid.get_agent(&id1) {
-                    Some(agent) => agent.clone(),
-                    None => continue, // Skip this game if agent not found
-                };
-                let agent2 = match self.grid.get_agent(&id2) {
-                    Some(agent) => agent.clone(),
-                    None => continue, // Skip this game if agent not found
-                };
-                (agent1, agent2)
-            };
+    // 壁を置く・取り除く（すぐに反映）。壁を置いたセルにいた個体は死亡として取り除く
+    pub fn set_obstacle(&mut self, position: Position, blocked: bool) -> Result<(), String> {
+        if self.grid.set_obstacle(&position, blocked)?.is_some() {
+            self.deaths += 1;
+        }
+        self.config.obstacles = self.grid.obstacle_positions();
+        Ok(())
+    }
 
-            let mut agent1 = agent1_data;
-            let mut agent2 = agent2_data;
+    pub fn get_obstacles(&self) -> &[Position] {
+        &self.config.obstacles
+    }
 
-            GameService::play_game(&mut agent1, &mut agent2);
+    // 次のステップから反映する。None に戻すと個体ごとの movement_strategy に従う
+    pub fn set_movement_policy(
+        &mut self,
+        policy: Option<MovementPolicyKind>,
+    ) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.movement_policy = policy;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
+    }
 
-            // Update agents separately to avoid double mutable borrow
-            if let Some(agent) = self.grid.get_agent_mut(&id1) {
-                *agent = agent1;
-            }
-            if let Some(agent) = self.grid.get_agent_mut(&id2) {
-                *agent = agent2;
+    // 次のステップから反映する
+    pub fn set_migration_model(&mut self, model: MigrationModel) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.migration_model = model;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    // 対戦の後、戦略の更新の前に移住させる
+    fn migrate_agents(&mut self) {
+        if let MigrationModel::SuccessDriven(range) = self.config.migration_model {
+            let payoffs = self.current_payoffs();
+            GridService::process_success_driven_migration(
+                &mut self.grid,
+                range,
+                &payoffs,
+                &mut self.rng,
+            );
+        }
+    }
+
+    fn move_agents(&mut self) {
+        match self.config.movement_policy {
+            Some(kind) => {
+                let policy = kind.policy(self.current_payoffs());
+                GridService::process_movements_with(&mut self.grid, policy.as_ref(), &mut self.rng);
             }
+            None => GridService::process_movements(&mut self.grid, &mut self.rng),
         }
     }
 
-    fn next_generation(&mut self) {
-        let evolution_service = crate::application::evolution::EvolutionService::new();
-        let new_agents = evolution_service.evolve_with_config(self.grid.agents(), &self.config);
+    pub fn get_heatmap(&self, field: HeatmapField) -> Vec<f32> {
+        field.render(&self.grid)
+    }
 
-        self.grid.clear();
-        for agent in new_agents {
-            let _ = self.grid.add_agent(agent);
+    pub fn get_resource_levels(&self) -> Option<&[f64]> {
+        self.grid.resources().map(|resources| resources.levels())
+    }
+
+    // エネルギー型に切り替えると今いるエージェントは初期エネルギーから始める
+    pub fn set_reproduction(&mut self, reproduction: ReproductionModel) -> Result<(), String> {
+        self.config.reproduction = reproduction;
+        self.assign_initial_energy()
+    }
+
+    // 検査に通らない規則は反映しない
+    pub fn set_lifecycle(&mut self, lifecycle: Option<LifecycleConfig>) -> Result<(), String> {
+        let config = SimulationConfig {
+            lifecycle,
+            ..self.config.clone()
+        };
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
         }
+        self.config.lifecycle = lifecycle;
+        Ok(())
+    }
 
-        self.generation += 1;
-        self.turn = 0;
+    pub fn set_game_type(&mut self, game_type: GameType) {
+        self.config.game_type = game_type;
+    }
+
+    pub fn set_neighborhood(&mut self, neighborhood: NeighborhoodType) {
+        self.config.neighborhood = neighborhood;
+        self.grid.set_neighborhood(neighborhood);
+    }
+
+    // グラフを作り直す（今いる個体はそのセル＝ノードのまま）。検査に通らない設定は反映しない
+    pub fn set_network(&mut self, network: Option<NetworkConfig>) -> Result<(), String> {
+        let (width, height) = self.get_grid_size();
+        if let Some(network) = &network {
+            network.validate(width * height)?;
+        }
+        self.grid.set_network(
+            network
+                .as_ref()
+                .map(|network| network.build(width * height)),
+        );
+        self.config.network = network;
+        Ok(())
+    }
+
+    pub fn get_network(&self) -> Option<&NetworkWorld> {
+        self.grid.network()
+    }
+
+    // 検査に通らない確率（ネットワークがないときの正の確率を含む）は反映しない
+    pub fn set_rewiring_probability(&mut self, probability: f64) -> Result<(), String> {
+        let config = SimulationConfig {
+            rewiring_probability: probability,
+            ..self.config.clone()
+        };
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config.rewiring_probability = probability;
+        Ok(())
+    }
+
+    pub fn set_action_noise(&mut self, noise: f64) {
+        self.config.action_noise = noise.clamp(0.0, 1.0);
+    }
+
+    pub fn set_torus_field(&mut self, enabled: bool) {
+        self.config.torus_field_enabled = enabled;
+        self.grid.set_torus_mode(enabled);
+    }
+
+    pub fn set_deme_structure(&mut self, demes: Option<DemeStructure>) {
+        self.config.deme_structure = demes;
+        self.grid.set_demes(demes);
+    }
+
+    pub fn step(&mut self) -> SimulationStatistics {
+        if self.stopped {
+            return self.get_statistics();
+        }
+        let stopwatch = Stopwatch::start();
+        let reproduction = self.config.reproduction;
+        // 全滅したときに置き直せるよう、ステップ前の上位個体の遺伝子型を控えておく
+        let populated = self.grid.agent_count() > 0;
+        let elite: Vec<Genotype> = match self.config.extinction_policy {
+            ExtinctionPolicy::ReseedFromElite(n) if populated => {
+                EvolutionService::fittest(self.grid.agents(), n, &self.config.fitness())
+                    .iter()
+                    .map(Genotype::of)
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let energy = match reproduction {
+            ReproductionModel::Energy(energy) => Some(energy),
+            _ => None,
+        };
+        let before = energy.map(|_| EnergyLifecycle::snapshot(&self.grid));
+        let scores_before: Option<HashMap<Uuid, i32>> =
+            matches!(reproduction, ReproductionModel::SteadyState(_)).then(|| {
+                self.grid
+                    .agents()
+                    .values()
+                    .map(|agent| (agent.id, agent.score))
+                    .collect()
+            });
+
+        let battles = Stopwatch::start();
+        self.execute_battles();
+        self.generation_timings.battles_ms += battles.elapsed_ms();
+        self.rewire_links();
+        self.decay_reputations();
+        if let Some(resources) = self.grid.resources_mut() {
+            resources.regenerate();
+        }
+        let migration = Stopwatch::start();
+        self.migrate_agents();
+        self.generation_timings.movement_ms += migration.elapsed_ms();
+        if let (ReproductionModel::SteadyState(steady), Some(scores_before)) =
+            (reproduction, scores_before)
+        {
+            self.update_steady_state(&steady, &scores_before);
+        }
+        let movement = Stopwatch::start();
+        self.move_agents();
+        GridService::process_migrations(&mut self.grid, &mut self.rng);
+        self.generation_timings.movement_ms += movement.elapsed_ms();
+        if let (Some(energy), Some(before)) = (energy, before) {
+            EnergyLifecycle::settle(&mut self.grid, &energy, &before);
+            self.deaths += EnergyLifecycle::remove_starved(&mut self.grid);
+        }
+        self.age_agents();
+        self.compact_battle_history();
+        if let Some(energy) = energy {
+            self.births += EnergyLifecycle::reproduce(
+                &mut self.grid,
+                &energy,
+                self.mutation_rate,
+                &self.config,
+                &mut self.rng,
+            );
+        }
+
+        self.turn += 1;
+        self.generation_timings.steps_ms += stopwatch.elapsed_ms();
+
+        let stats_timer = Stopwatch::start();
+        let capacity = self.config.history_capacity;
+        if matches!(reproduction, ReproductionModel::SteadyState(_)) {
+            let stats = self.get_statistics();
+            push_bounded(&mut self.step_history, stats, capacity);
+        }
+
+        if self.turn >= self.turns_per_generation {
+            // 世代交代前の統計を履歴に残す
+            let stats = self.get_statistics();
+            push_bounded(&mut self.generation_history, stats, capacity);
+            self.births = 0;
+            self.deaths = 0;
+            self.punishments = 0;
+            self.punishment_opportunities = 0;
+            if let Some(bins) = self.config.histogram_bins {
+                let detailed = self.get_detailed_statistics(bins);
+                push_bounded(&mut self.detailed_history, detailed, capacity);
+            }
+            if self.config.spatial_metrics_enabled {
+                let metrics = self.get_spatial_metrics();
+                push_bounded(&mut self.spatial_history, metrics, capacity);
+            }
+            if let Some(front) = self.get_pareto_front() {
+                push_bounded(&mut self.pareto_history, front, capacity);
+            }
+            if let Some(metrics) = self.get_network_metrics() {
+                push_bounded(&mut self.network_history, metrics, capacity);
+            }
+            self.rewired_links = 0;
+            if let Some(tracker) = &mut self.tracker {
+                tracker.record(self.grid.agents(), self.generation, capacity);
+            }
+            let mut record = self.generation_record();
+            self.generation_timings.stats_ms += stats_timer.elapsed_ms();
+
+            let evolution = Stopwatch::start();
+            self.adapt_mutation_rate();
+            match reproduction {
+                ReproductionModel::Generational => self.next_generation(),
+                // 世代交代型以外では個体は入れ替えず、世代は区切りとしてだけ進める
+                _ => {
+                    self.generation += 1;
+                    self.turn = 0;
+                }
+            }
+            let mut timings = std::mem::take(&mut self.generation_timings);
+            timings.evolution_ms += evolution.elapsed_ms();
+            timings.total_ms = timings.steps_ms + timings.stats_ms + timings.evolution_ms;
+            record.performance = timings;
+            record.memory_usage_mb = self.get_memory_usage().memory_usage_mb();
+            self.evolution_tracker.record(record);
+            self.report_payoff_keyframe();
+        } else {
+            self.generation_timings.stats_ms += stats_timer.elapsed_ms();
+        }
+        if populated && self.grid.agent_count() == 0 {
+            self.handle_extinction(&elite);
+        }
+        #[cfg(feature = "invariant-checks")]
+        {
+            let violations = self.validate_world();
+            debug_assert!(
+                violations.is_empty(),
+                "Generation {} turn {}: {}",
+                self.generation,
+                self.turn,
+                InvariantViolation::join(&violations)
+            );
+        }
+
+        let stats_timer = Stopwatch::start();
+        let stats = self.get_statistics();
+        self.generation_timings.stats_ms += stats_timer.elapsed_ms();
+        stats
+    }
+
+    // 全滅したステップの終わりに方針どおりに止める・置き直す・進めるを選び、イベントとして残す
+    fn handle_extinction(&mut self, elite: &[Genotype]) {
+        let action = self.config.extinction_policy;
+        let reseeded = match action {
+            ExtinctionPolicy::Stop => {
+                self.stopped = true;
+                0
+            }
+            ExtinctionPolicy::Continue => 0,
+            ExtinctionPolicy::ReseedRandom(n) => self.reseed(n, &[]),
+            ExtinctionPolicy::ReseedFromElite(n) => self.reseed(n, elite),
+        };
+        self.births += reseeded;
+        self.diagnostics.push(format!(
+            "Generation {}: population went extinct ({}, reseeded {reseeded})",
+            self.generation,
+            action.name()
+        ));
+        self.extinction_events.push(ExtinctionEvent {
+            generation: self.generation,
+            turn: self.turn,
+            action,
+            reseeded,
+        });
+    }
+
+    // 空の盤面に最大 count 体を置き、置けた数を返す。elite が空なら初期配置と同じ規則を使う
+    fn reseed(&mut self, count: usize, elite: &[Genotype]) -> usize {
+        let (width, height) = self.get_grid_size();
+        let count = count.min(width * height);
+        let placed = if elite.is_empty() {
+            let seeded = self.config.initial_population.is_some();
+            self.place_initial_agents(count)
+                .and_then(|()| self.assign_memory_genomes(seeded))
+        } else {
+            GridService::place_agents(
+                &mut self.grid,
+                count,
+                &mut self.rng,
+                |position, index, rng| {
+                    elite[index % elite.len()]
+                        .spawn(position)
+                        .with_random_id(rng)
+                },
+            )
+        };
+        if let Err(e) = placed.and_then(|()| self.assign_initial_energy()) {
+            self.diagnostics.push(format!(
+                "Generation {}: failed to reseed population: {e}",
+                self.generation
+            ));
+        }
+        self.grid.agent_count()
+    }
+
+    // 全滅時の方針（すぐに反映）
+    pub fn set_extinction_policy(&mut self, policy: ExtinctionPolicy) -> Result<(), String> {
+        let config = SimulationConfig {
+            extinction_policy: policy,
+            ..self.config.clone()
+        };
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config.extinction_policy = policy;
+        Ok(())
+    }
+
+    pub fn get_extinction_events(&self) -> &[ExtinctionEvent] {
+        &self.extinction_events
+    }
+
+    // 全滅時の方針 Stop で止まっているか
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    // 実行中に別のハンドルから cancel() されるとステップ境界で打ち切る
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    pub fn request_cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    pub fn run_generations(&mut self, generations: u32) -> SimulationStatistics {
+        let target_generation = self.generation + generations;
+        while self.generation < target_generation && !self.stopped {
+            if self.cancellation.take() {
+                break;
+            }
+            self.step();
+        }
+
+        self.get_statistics()
+    }
+
+    // 各世代の終了ごとに世代交代直前の統計を渡し、false が返れば打ち切る
+    // 戻り値は完了した世代数
+    pub fn run_generations_with<F>(&mut self, generations: u32, mut on_generation: F) -> u32
+    where
+        F: FnMut(&SimulationStatistics) -> bool,
+    {
+        for completed in 1..=generations {
+            let target_generation = self.generation + 1;
+            while self.generation < target_generation {
+                if self.cancellation.take() || self.stopped {
+                    return completed - 1;
+                }
+                self.step();
+            }
+
+            let stats = self
+                .generation_history
+                .back()
+                .cloned()
+                .unwrap_or_else(|| self.get_statistics());
+            if !on_generation(&stats) {
+                return completed;
+            }
+        }
+
+        generations
+    }
+
+    pub fn get_statistics(&self) -> SimulationStatistics {
+        SimulationStatistics {
+            island_statistics: IslandStatistics::calculate(&self.grid),
+            tag_statistics: self
+                .config
+                .tags
+                .map(|tags| TagStatistics::calculate(&self.grid, tags.tag_count))
+                .unwrap_or_default(),
+            mutation_rate: self.mutation_rate,
+            births: self.births,
+            punishments: self.punishments,
+            punishment_frequency: if self.punishment_opportunities == 0 {
+                0.0
+            } else {
+                self.punishments as f64 / self.punishment_opportunities as f64
+            },
+            deaths: self.deaths,
+            average_fitness: self.average_fitness(),
+            niche_count: self.niche_count,
+            ..SimulationStatistics::calculate(self.grid.agents(), self.generation)
+        }
+    }
+
+    // 設定の適応度の平均（位置順に合計する）
+    fn average_fitness(&self) -> f64 {
+        let agents = self.grid.agents_in_position_order();
+        if agents.is_empty() {
+            return 0.0;
+        }
+        let fitness = self.config.fitness();
+        agents
+            .iter()
+            .map(|agent| fitness.evaluate(agent))
+            .sum::<f64>()
+            / agents.len() as f64
+    }
+
+    // 適応度共有を設定していなければ 0。全員の組を比べるので世代交代で一度だけ数える
+    fn count_niches(&self) -> usize {
+        self.config.fitness_sharing.map_or(0, |sharing| {
+            sharing.count_niches(
+                &self.grid.agents_in_position_order(),
+                &self.config.fitness(),
+            )
+        })
+    }
+
+    pub fn get_detailed_statistics(&self, bins: usize) -> DetailedStatistics {
+        DetailedStatistics::calculate(self.grid.agents(), self.generation, bins)
+    }
+
+    pub fn get_spatial_metrics(&self) -> SpatialMetrics {
+        SpatialAnalysisService::analyze(&self.grid, self.generation)
+    }
+
+    // ネットワークがなければ None
+    pub fn get_network_metrics(&self) -> Option<NetworkMetrics> {
+        NetworkAnalysisService::analyze(&self.grid, self.generation, self.rewired_links)
+    }
+
+    pub fn get_network_history(&self) -> &VecDeque<NetworkMetrics> {
+        &self.network_history
+    }
+
+    pub fn get_agents(&self) -> Vec<Agent> {
+        self.grid.agents().values().cloned().collect()
+    }
+
+    // 個体を複製せずに CSV（SerializationService::agents_to_csv と同じ列）を writer に書く
+    pub fn write_agents_csv<W: std::io::Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        options: &ExportOptions,
+    ) -> std::io::Result<()> {
+        SerializationService::write_agents_csv(writer, self.grid.agents().values(), options)
+    }
+
+    pub fn get_agent_count(&self) -> usize {
+        self.grid.agent_count()
+    }
+
+    // 描画用。位置順 (y, x) で並ぶので毎回同じ順序になる
+    pub fn agents_in_position_order(&self) -> Vec<&Agent> {
+        self.grid.agents_in_position_order()
+    }
+
+    // 実行中に個体を置く（侵入者の投入など）。ID は乱数列から振るのでシード指定時も再現できる
+    // エネルギーモデルでは初期エネルギーを与える
+    pub fn add_agent(&mut self, position: Position, genotype: &Genotype) -> Result<Uuid, String> {
+        let genotype = self.express(genotype.clone())?;
+        let mut agent = genotype.spawn(position).with_random_id(&mut self.rng);
+        if let ReproductionModel::Energy(energy) = self.config.reproduction {
+            agent.energy = energy.initial_energy;
+        }
+
+        let id = agent.id;
+        self.grid.add_agent(agent)?;
+        Ok(id)
+    }
+
+    // 生きている個体の遺伝的形質だけを書き換える（位置・スコア・対戦履歴・年齢はそのまま）
+    pub fn update_agent_genotype(&mut self, id: &Uuid, genotype: Genotype) -> Result<(), String> {
+        let genotype = self.express(genotype)?;
+        let agent = self
+            .grid
+            .get_agent_mut(id)
+            .ok_or_else(|| format!("Agent not found: {id}"))?;
+        agent.strategy = genotype.strategy;
+        agent.movement_strategy = genotype.movement_strategy;
+        agent.mobility = genotype.mobility;
+        agent.memory_genome = genotype.memory_genome;
+        Ok(())
+    }
+
+    // memory-N の行動表が有効なのに表を持たない遺伝子型は、戦略から表を展開する
+    fn express(&self, genotype: Genotype) -> Result<Genotype, String> {
+        let (Some(memory), None) = (self.config.memory_genome, &genotype.memory_genome) else {
+            return Ok(genotype);
+        };
+        let genome = MemoryGenome::from_strategy(genotype.strategy, memory).ok_or_else(|| {
+            format!(
+                "Strategy {} cannot be expressed as a memory-{memory} genome",
+                genotype.strategy.name()
+            )
+        })?;
+        Ok(genotype.with_memory_genome(genome))
+    }
+
+    pub fn get_agent(&self, id: &Uuid) -> Option<&Agent> {
+        self.grid.get_agent(id)
+    }
+
+    pub fn get_battle_history(
+        &self,
+        id: &Uuid,
+        query: &BattleHistoryQuery,
+    ) -> Result<BattleHistoryPage, String> {
+        self.grid
+            .get_agent(id)
+            .map(|agent| query.run(&agent.history))
+            .ok_or_else(|| format!("Agent not found: {id}"))
+    }
+
+    // 保持方針に合わせて各個体の対戦履歴を切り詰め、今回捨てた件数を返す（毎ステップ自動で呼ばれる）
+    pub fn compact_battle_history(&mut self) -> u64 {
+        let evicted = self
+            .config
+            .battle_history_retention
+            .compact(self.grid.agents_mut());
+        self.evicted_battle_records += evicted;
+        evicted
+    }
+
+    pub fn set_battle_history_retention(
+        &mut self,
+        retention: BattleHistoryRetention,
+    ) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.battle_history_retention = retention;
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.config = config;
+        self.compact_battle_history();
+        Ok(())
+    }
+
+    // 盤面の全個体に残っている対戦記録の集計（協力の頻度・利得の分布・戦略の組ごとの協力率）
+    pub fn get_global_battle_stats(&self) -> GlobalBattleStats {
+        GlobalBattleStats::from_agents(self.grid.agents())
+    }
+
+    // 残っている対戦記録から作る「誰が誰と対戦したか」のグラフ
+    pub fn get_interaction_network(&self) -> InteractionNetwork {
+        InteractionNetwork::from_grid(&self.grid)
+    }
+
+    pub fn get_battle_history_usage(&self) -> BattleHistoryUsage {
+        BattleHistoryUsage {
+            retained_records: self
+                .grid
+                .agents()
+                .values()
+                .map(|agent| agent.history.len())
+                .sum(),
+            evicted_records: self.evicted_battle_records,
+        }
+    }
+
+    // radius 0 なら対戦に使う近傍（デーム境界も考慮）、1 以上ならその距離以内の全個体
+    pub fn get_neighbors_of(
+        &self,
+        position: &Position,
+        radius: u32,
+    ) -> Result<Vec<NeighborView>, String> {
+        let (width, height) = self.get_grid_size();
+        if position.x >= width || position.y >= height {
+            return Err(format!(
+                "Position ({}, {}) is out of bounds for grid {width}x{height}",
+                position.x, position.y
+            ));
+        }
+
+        let focal = self.grid.get_agent_at_position(position);
+        let neighbors = match radius {
+            0 => self.grid.get_neighbors(position),
+            radius => self.grid.agents_within(position, radius),
+        };
+        Ok(neighbors
+            .into_iter()
+            .map(|neighbor| NeighborView::between(focal, neighbor))
+            .collect())
+    }
+
+    // 取り除いた個体を返す。統計は現在の盤面から計算するので、記録済みの履歴はそのまま残り
+    // 世代交代は残った個体だけで行う
+    pub fn remove_agent(&mut self, id: &Uuid) -> Option<Agent> {
+        self.grid.remove_agent(id)
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn get_grid_size(&self) -> (usize, usize) {
+        (self.grid.width(), self.grid.height())
+    }
+
+    pub fn get_generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn get_turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn set_turns_per_generation(&mut self, turns: u32) {
+        self.turns_per_generation = turns.max(1);
+    }
+
+    pub fn get_config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    pub fn get_turns_per_generation(&self) -> u32 {
+        self.turns_per_generation
+    }
+
+    pub fn capture_frame(&self) -> Frame {
+        Frame {
+            generation: self.generation,
+            turn: self.turn,
+            agents: self
+                .grid
+                .agents()
+                .values()
+                .map(FrameAgent::from_agent)
+                .collect(),
+        }
+    }
+
+    pub fn get_generation_history(&self) -> &VecDeque<SimulationStatistics> {
+        &self.generation_history
+    }
+
+    // from..=to 世代の統計（履歴は世代順なので二分探索で範囲を決める）
+    pub fn get_history(
+        &self,
+        from_generation: u32,
+        to_generation: u32,
+    ) -> vec_deque::Iter<'_, SimulationStatistics> {
+        let history = &self.generation_history;
+        let start = history.partition_point(|stats| stats.generation < from_generation);
+        let end = history.partition_point(|stats| stats.generation <= to_generation);
+        history.range(start..end.max(start))
+    }
+
+    // None で無制限。縮めた場合は次に記録したときに古い世代から捨てる
+    pub fn set_history_capacity(&mut self, capacity: Option<usize>) {
+        self.config.history_capacity = capacity.map(|capacity| capacity.max(1));
+    }
+
+    pub fn get_step_history(&self) -> &VecDeque<SimulationStatistics> {
+        &self.step_history
+    }
+
+    pub fn get_detailed_history(&self) -> &VecDeque<DetailedStatistics> {
+        &self.detailed_history
+    }
+
+    // multi_objective が設定されていなければ None
+    pub fn get_pareto_front(&self) -> Option<ParetoFront> {
+        self.config
+            .multi_objective
+            .as_ref()
+            .map(|multi| multi.pareto_front(self.grid.agents(), self.generation))
+    }
+
+    pub fn get_pareto_history(&self) -> &VecDeque<ParetoFront> {
+        &self.pareto_history
+    }
+
+    pub fn set_multi_objective(&mut self, multi: Option<MultiObjective>) {
+        self.config.multi_objective = multi;
+    }
+
+    // 追跡を切り替えると記録済みの軌跡は捨てる
+    pub fn set_agent_tracking(&mut self, target: Option<TrackingTarget>) {
+        self.tracker = target.clone().map(AgentTracker::new);
+        self.config.agent_tracking = target;
+    }
+
+    // 追跡していない個体なら None
+    pub fn get_agent_trajectory(&self, id: &Uuid) -> Option<&[TrajectoryPoint]> {
+        self.tracker.as_ref()?.trajectory(id)
+    }
+
+    pub fn get_agent_tracker(&self) -> Option<&AgentTracker> {
+        self.tracker.as_ref()
+    }
+
+    pub fn get_evolution_records(&self) -> &[GenerationRecord] {
+        self.evolution_tracker.records()
+    }
+
+    pub fn get_evolution_summary(&self) -> EvolutionSummary {
+        self.evolution_tracker.summary()
+    }
+
+    // 記録中の世代の段階ごとの処理時間（対戦・移動・統計・世代交代とその内訳）
+    pub fn get_performance_report(&self) -> PerformanceReport {
+        self.evolution_tracker.performance_report()
+    }
+
+    // 個体の形質の範囲・占有配列と位置の一致・個体数の上限・利得表の対称性を確かめる（空なら問題なし）
+    pub fn validate_world(&self) -> Vec<InvariantViolation> {
+        WorldInvariants::check_world(&self.grid, &self.current_payoffs())
+    }
+
+    // 盤面の個体（対戦履歴・行動表のバッファを含む）と、残している履歴が確保しているヒープ領域の概算
+    // （wasm32 では線形メモリの大きさも返す）
+    pub fn get_memory_usage(&self) -> MemoryUsage {
+        let bytes = self.grid.heap_bytes()
+            + history_bytes(&self.generation_history, SimulationStatistics::heap_bytes)
+            + history_bytes(&self.step_history, SimulationStatistics::heap_bytes)
+            + history_bytes(&self.detailed_history, DetailedStatistics::heap_bytes)
+            + history_bytes(&self.spatial_history, SpatialMetrics::heap_bytes)
+            + history_bytes(&self.pareto_history, ParetoFront::heap_bytes)
+            + history_bytes(&self.network_history, NetworkMetrics::heap_bytes)
+            + self.history_pool.capacity() * std::mem::size_of::<GameHistory>()
+            + self.evolution_tracker.heap_bytes();
+        MemoryUsage::from_estimated_bytes(bytes)
+    }
+
+    // 世代ごとの記録を 1 行 1 世代の CSV で返す
+    pub fn export_metrics_csv(&self) -> String {
+        SerializationService::generation_records_to_csv(
+            self.evolution_tracker.records(),
+            &ExportOptions::default(),
+        )
+    }
+
+    // 現在のグリッドを SVG で返す
+    pub fn export_svg(&self, options: &SvgOptions) -> String {
+        SvgRenderer::render(&self.grid, options)
+    }
+
+    // 縮めた場合はすぐに古い記録から捨てる
+    pub fn set_evolution_history_size(&mut self, size: usize) -> Result<(), String> {
+        let config = SimulationConfig {
+            evolution_history_size: size,
+            ..self.config.clone()
+        };
+        let violations = config.validate();
+        if !violations.is_empty() {
+            return Err(ConfigViolation::join(&violations));
+        }
+        self.evolution_tracker.set_capacity(size);
+        self.config.evolution_history_size = size;
+        Ok(())
+    }
+
+    pub fn get_spatial_history(&self) -> &VecDeque<SpatialMetrics> {
+        &self.spatial_history
+    }
+
+    pub fn set_spatial_metrics(&mut self, enabled: bool) {
+        self.config.spatial_metrics_enabled = enabled;
+    }
+
+    // None で記録を止める（既存の履歴は残す）
+    pub fn set_histogram_bins(&mut self, bins: Option<usize>) {
+        self.config.histogram_bins = bins.map(|bins| bins.max(1));
+    }
+
+    pub fn to_result(&self) -> SimulationResult {
+        SimulationResult::new(self.generation_history.iter().cloned().collect())
+            .with_extinction_events(self.extinction_events.clone())
+    }
+
+    pub fn get_diagnostics(&self) -> &[String] {
+        &self.diagnostics
+    }
+
+    pub fn clear_diagnostics(&mut self) {
+        self.diagnostics.clear();
+    }
+
+    pub fn reset(&mut self, agent_count: usize) -> Result<(), String> {
+        // 登録簿は他のシミュレーションと共有しているので、この盤面の個体が使っている戦略だけをリセットする
+        let strategies: Vec<StrategyType> = self
+            .grid
+            .agents()
+            .values()
+            .map(|agent| agent.strategy)
+            .collect();
+        StrategyRegistry::reset(strategies);
+        self.grid.clear();
+        self.grid.set_resources(self.config.resources);
+        // つなぎ替えたネットワークも設定どおりに作り直す
+        let (width, height) = self.get_grid_size();
+        let network = self.config.network.as_ref();
+        self.grid
+            .set_network(network.map(|network| network.build(width * height)));
+        self.diagnostics.clear();
+        self.generation_history.clear();
+        self.step_history.clear();
+        self.detailed_history.clear();
+        self.spatial_history.clear();
+        self.pareto_history.clear();
+        self.network_history.clear();
+        self.evicted_battle_records = 0;
+        self.births = 0;
+        self.deaths = 0;
+        self.punishments = 0;
+        self.punishment_opportunities = 0;
+        self.rewired_links = 0;
+        self.niche_count = 0;
+        self.reputation_board.clear();
+        self.extinction_events.clear();
+        self.stopped = false;
+        if let Some(tracker) = &mut self.tracker {
+            tracker.clear();
+        }
+        self.evolution_tracker.clear();
+        self.generation_timings = PerformanceMetrics::default();
+        self.rng = Self::build_rng(&self.config);
+        self.validate_population(agent_count)?;
+        self.populate(agent_count)?;
+        self.generation = 0;
+        self.turn = 0;
+        Ok(())
+    }
+
+    // 評判を共有するときだけ、対戦前の両者の公開の評判を返す
+    fn reputations_of(
+        board: &ReputationBoard,
+        reputation: Option<&ReputationConfig>,
+        id1: &Uuid,
+        id2: &Uuid,
+    ) -> (Option<f64>, Option<f64>) {
+        match reputation {
+            Some(_) => (Some(board.get(id1)), Some(board.get(id2))),
+            None => (None, None),
+        }
+    }
+
+    fn observe_reputation(&mut self, first: (Uuid, Action), second: (Uuid, Action)) {
+        if let Some(reputation) = &self.config.reputation {
+            self.reputation_board
+                .observe_game(reputation, first, second, &mut self.rng);
+        }
+    }
+
+    // punishment が設定されていれば対戦後の罰を与え、罰の回数と機会を数える
+    // 資源による利得の増減は罰には掛からない
+    fn punish_pair(&mut self, id1: Uuid, id2: Uuid, actions: (Action, Action)) {
+        let Some(punishment) = self.config.punishment else {
+            return;
+        };
+        let (Some(mut agent1), Some(mut agent2)) = (
+            self.grid.get_agent(&id1).cloned(),
+            self.grid.get_agent(&id2).cloned(),
+        ) else {
+            return;
+        };
+        let (punished1, punished2) =
+            punishment.punish(&mut agent1, &mut agent2, actions, &mut self.rng);
+        self.punishment_opportunities +=
+            usize::from(actions.0 == Action::Defect) + usize::from(actions.1 == Action::Defect);
+        self.punishments += usize::from(punished1) + usize::from(punished2);
+        for agent in [agent1, agent2] {
+            if let Some(current) = self.grid.get_agent_mut(&agent.id) {
+                *current = agent;
+            }
+        }
+    }
+
+    // ステップの終わりに評判を減衰させ、いなくなった個体の評判を捨てる
+    fn decay_reputations(&mut self) {
+        let Some(reputation) = self.config.reputation else {
+            return;
+        };
+        let grid = &self.grid;
+        self.reputation_board
+            .retain(|id| grid.get_agent(id).is_some());
+        self.reputation_board.decay(reputation.decay);
+    }
+
+    // 直前の対戦で裏切った隣接個体との辺を rewiring_probability で切り、隣接ノードの隣接ノードと結び直す
+    // 位置順に集めてから順に引くので、シード指定時は同じようにつなぎ替わる
+    fn rewire_links(&mut self) {
+        use rand::Rng;
+
+        let probability = self.config.rewiring_probability;
+        if probability <= 0.0 || self.grid.network().is_none() {
+            return;
+        }
+        let grid = &self.grid;
+        let defections: Vec<(usize, usize)> = grid
+            .agents_in_position_order()
+            .into_iter()
+            .flat_map(|agent| {
+                grid.get_neighbors(&agent.position)
+                    .into_iter()
+                    .filter(|neighbor| {
+                        agent.history.get_last_opponent_action(&neighbor.id) == Some(Action::Defect)
+                    })
+                    .filter_map(|neighbor| {
+                        Some((
+                            grid.node_of(&agent.position)?,
+                            grid.node_of(&neighbor.position)?,
+                        ))
+                    })
+            })
+            .collect();
+
+        for (node, defector) in defections {
+            if self.rng.gen::<f64>() >= probability {
+                continue;
+            }
+            if let Some(network) = self.grid.network_mut() {
+                if network.rewire(node, defector, &mut self.rng).is_some() {
+                    self.rewired_links += 1;
+                }
+            }
+        }
+    }
+
+    // 隣接するエージェントの組（デーム設定時は同じデーム内のみ）
+    pub fn game_pairs(&self) -> Vec<(Uuid, Uuid)> {
+        let mut pairs = Vec::new();
+
+        // 走査順と組の向きを位置で決め、シード指定時に対戦順が再現されるようにする
+        for agent in self.grid.agents_in_position_order() {
+            let key = (agent.position.y, agent.position.x);
+            for neighbor_pos in self.grid.neighbor_positions(&agent.position) {
+                if let Some(neighbor_agent) = self.grid.get_agent_at_position(&neighbor_pos) {
+                    if key < (neighbor_agent.position.y, neighbor_agent.position.x) {
+                        pairs.push((agent.id, neighbor_agent.id));
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    // 今の配置のまま 1 ステップ分の対戦だけを行う（移動・加齢・世代交代はしない）
+    pub fn execute_battles(&mut self) {
+        match self.config.game_type {
+            GameType::PrisonersDilemma => self.process_games(),
+            GameType::PublicGoods(game) => self.process_public_goods(&game),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn process_games(&mut self) {
+        let games_to_play = self.game_pairs();
+        let payoffs = self.current_payoffs();
+        // エージェントごとの (このステップの獲得スコア, 対戦数)
+        let mut step_gains: HashMap<Uuid, (i32, u32)> = HashMap::new();
+
+        // Play games with proper borrowing
+        for (id1, id2) in games_to_play {
+            // Get immutable references first, then clone - with safe error handling
+            let (agent1_data, agent2_data) = {
+                let agent1 = match self.grid.get_agent(&id1) {
+                    Some(agent) => agent.clone(),
+                    None => continue, // Skip this game if agent not found
+                };
+                let agent2 = match self.grid.get_agent(&id2) {
+                    Some(agent) => agent.clone(),
+                    None => continue, // Skip this game if agent not found
+                };
+                (agent1, agent2)
+            };
+
+            let mut agent1 = agent1_data;
+            let mut agent2 = agent2_data;
+
+            let (score1, score2) = (agent1.score, agent2.score);
+            let payoffs1 = self.grid.payoffs_at(&agent1.position, &payoffs);
+            let payoffs2 = self.grid.payoffs_at(&agent2.position, &payoffs);
+            let (action1, action2) = GameService::play_game_with_reputation(
+                &mut agent1,
+                &mut agent2,
+                (&payoffs1, &payoffs2),
+                self.config.action_noise,
+                Self::reputations_of(
+                    &self.reputation_board,
+                    self.config.reputation.as_ref(),
+                    &id1,
+                    &id2,
+                ),
+                &mut self.rng,
+            );
+            self.observe_reputation((id1, action1), (id2, action2));
+            Self::scale_by_resources(&mut self.grid, &mut agent1, score1);
+            Self::scale_by_resources(&mut self.grid, &mut agent2, score2);
+
+            if self.config.score_normalization_enabled {
+                for (id, gain) in [(id1, agent1.score - score1), (id2, agent2.score - score2)] {
+                    let entry = step_gains.entry(id).or_insert((0, 0));
+                    entry.0 += gain;
+                    entry.1 += 1;
+                }
+            }
+
+            // Update agents separately to avoid double mutable borrow
+            if let Some(agent) = self.grid.get_agent_mut(&id1) {
+                *agent = agent1;
+            }
+            if let Some(agent) = self.grid.get_agent_mut(&id2) {
+                *agent = agent2;
+            }
+            self.punish_pair(id1, id2, (action1, action2));
+        }
+
+        self.apply_normalized_gains(step_gains);
+    }
+
+    // 対戦を同じエージェントを含まないバッチに分け、バッチ内を rayon で並列実行する
+    // wasm32 でスレッドプールを用意していなければ rayon が呼び出し元のスレッドだけで実行する
+    // 対戦ごとのシードを逐次に引くので、同じシードなら結果は再現される（対戦順が変わるため逐次版とは一致しない）
+    // ノイズがなくても確率的な戦略やタグの内集団びいきが乱数を使うので、シードは常に対戦ごとに変える
+    #[cfg(feature = "parallel")]
+    fn process_games(&mut self) {
+        use super::conflict_free_batches;
+        use rand::Rng;
+        use rayon::prelude::*;
+
+        let noise = self.config.action_noise;
+        let payoffs = self.current_payoffs();
+        let reputation = self.config.reputation;
+        let mut step_gains: HashMap<Uuid, (i32, u32)> = HashMap::new();
+
+        for batch in conflict_free_batches(&self.game_pairs()) {
+            let seeds: Vec<u64> = batch.iter().map(|_| self.rng.gen()).collect();
+
+            let grid = &self.grid;
+            let board = &self.reputation_board;
+            let results: Vec<(Agent, Agent, (Action, Action))> = batch
+                .par_iter()
+                .zip(seeds.par_iter())
+                .filter_map(|((id1, id2), seed)| {
+                    let mut agent1 = grid.get_agent(id1)?.clone();
+                    let mut agent2 = grid.get_agent(id2)?.clone();
+                    let mut rng = ChaCha12Rng::seed_from_u64(*seed);
+                    let reputations = Self::reputations_of(board, reputation.as_ref(), id1, id2);
+                    let payoffs1 = grid.payoffs_at(&agent1.position, &payoffs);
+                    let payoffs2 = grid.payoffs_at(&agent2.position, &payoffs);
+                    let actions = GameService::play_game_with_reputation(
+                        &mut agent1,
+                        &mut agent2,
+                        (&payoffs1, &payoffs2),
+                        noise,
+                        reputations,
+                        &mut rng,
+                    );
+                    Some((agent1, agent2, actions))
+                })
+                .collect();
+
+            // 評判の観察と対戦後の罰はバッチの対戦順に逐次行う
+            let games: Vec<(Uuid, Uuid, (Action, Action))> = results
+                .iter()
+                .map(|(agent1, agent2, actions)| (agent1.id, agent2.id, *actions))
+                .collect();
+            for &(id1, id2, (action1, action2)) in &games {
+                self.observe_reputation((id1, action1), (id2, action2));
+            }
+            for mut agent in results
+                .into_iter()
+                .flat_map(|(agent1, agent2, _)| [agent1, agent2])
+            {
+                let Some(score) = self.grid.get_agent(&agent.id).map(|current| current.score)
+                else {
+                    continue;
+                };
+                Self::scale_by_resources(&mut self.grid, &mut agent, score);
+                if self.config.score_normalization_enabled {
+                    let entry = step_gains.entry(agent.id).or_insert((0, 0));
+                    entry.0 += agent.score - score;
+                    entry.1 += 1;
+                }
+                if let Some(current) = self.grid.get_agent_mut(&agent.id) {
+                    *current = agent;
+                }
+            }
+            for (id1, id2, actions) in games {
+                self.punish_pair(id1, id2, actions);
+            }
+        }
+
+        self.apply_normalized_gains(step_gains);
+    }
+
+    // 各エージェントを中心に、自分と近傍のエージェントで公共財ゲームを 1 回ずつ行う
+    // （各エージェントは自分のグループと近傍が中心のグループに参加する）
+    fn process_public_goods(&mut self, game: &PublicGoodsGame) {
+        let groups: Vec<Vec<Uuid>> = self
+            .grid
+            .agents_in_position_order()
+            .into_iter()
+            .map(|focal| {
+                std::iter::once(focal.id)
+                    .chain(
+                        self.grid
+                            .get_neighbors(&focal.position)
+                            .into_iter()
+                            .map(|neighbor| neighbor.id),
+                    )
+                    .collect()
+            })
+            .filter(|group: &Vec<Uuid>| group.len() > 1)
+            .collect();
+        let mut step_gains: HashMap<Uuid, (i32, u32)> = HashMap::new();
+
+        for ids in groups {
+            let mut group: Vec<Agent> = ids
+                .iter()
+                .filter_map(|id| self.grid.get_agent(id).cloned())
+                .collect();
+            let scores: Vec<i32> = group.iter().map(|agent| agent.score).collect();
+
+            GameService::play_public_goods(
+                &mut group,
+                game,
+                self.config.action_noise,
+                &mut self.rng,
+            );
+
+            for (mut agent, score) in group.into_iter().zip(scores) {
+                Self::scale_by_resources(&mut self.grid, &mut agent, score);
+                if self.config.score_normalization_enabled {
+                    let entry = step_gains.entry(agent.id).or_insert((0, 0));
+                    entry.0 += agent.score - score;
+                    entry.1 += 1;
+                }
+                if let Some(current) = self.grid.get_agent_mut(&agent.id) {
+                    *current = agent;
+                }
+            }
+        }
+
+        self.apply_normalized_gains(step_gains);
+    }
+
+    // このステップの利得（対戦前のスコアとの差）で 1 体ずつ戦略を更新する
+    fn update_steady_state(
+        &mut self,
+        steady: &SteadyStateConfig,
+        scores_before: &HashMap<Uuid, i32>,
+    ) {
+        let payoffs: HashMap<Uuid, i32> = self
+            .grid
+            .agents()
+            .values()
+            .map(|agent| {
+                let before = scores_before.get(&agent.id).copied().unwrap_or(agent.score);
+                (agent.id, agent.score - before)
+            })
+            .collect();
+        let changes = SteadyStateUpdate::apply(
+            &mut self.grid,
+            steady,
+            &payoffs,
+            self.mutation_rate,
+            &self.config,
+            &mut self.rng,
+        );
+        // 死亡・出生更新では 1 回の更新が 1 体の死と 1 体の誕生
+        if steady.rule == UpdateRule::DeathBirth {
+            self.births += changes;
+            self.deaths += changes;
+        }
+    }
+
+    // 年齢を 1 つ進め、寿命の規則に当たった個体を（位置順に判定して）取り除く
+    fn age_agents(&mut self) {
+        for agent in self.grid.agents_mut().values_mut() {
+            agent.age += 1;
+        }
+        let Some(lifecycle) = self.config.lifecycle else {
+            return;
+        };
+        let dead: Vec<Uuid> = self
+            .grid
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|agent| lifecycle.should_die(agent, &mut self.rng))
+            .map(|agent| agent.id)
+            .collect();
+        for id in &dead {
+            self.grid.remove_agent(id);
+        }
+        self.deaths += dead.len();
+    }
+
+    // 資源レイヤーがあれば、1 回のゲームで得た利得を自分のセルから取れた資源の割合で縮める
+    fn scale_by_resources(grid: &mut Grid, agent: &mut Agent, score_before: i32) {
+        if let Some(resources) = grid.resources_mut() {
+            let share = resources.harvest(&agent.position);
+            let gain = agent.score - score_before;
+            agent.score = score_before + (gain as f64 * share).round() as i32;
+        }
+    }
+
+    fn apply_normalized_gains(&mut self, step_gains: HashMap<Uuid, (i32, u32)>) {
+        for (id, (gain, battles)) in step_gains {
+            if let Some(agent) = self.grid.get_agent_mut(&id) {
+                agent.normalized_score += gain as f64 / battles as f64;
+            }
+        }
+    }
+
+    fn next_generation(&mut self) {
+        let evolution_service = EvolutionService::new();
+        let (width, height) = self.get_grid_size();
+
+        // デーム設定時は各デーム内で独立に選択・配置する
+        let regions = match self.grid.demes() {
+            Some(demes) => demes.regions(width, height),
+            None => vec![Region::new(0, 0, width, height)],
+        };
+        let migrants = self
+            .grid
+            .demes()
+            .filter(|demes| demes.migration_due(self.generation + 1))
+            .map(|demes| demes.migrants);
+
+        // ボルツマン選択はこの世代まで冷ました温度で選ぶ
+        let config = SimulationConfig {
+            mutation_rate: self.mutation_rate,
+            selection: self.config.selection.at_generation(self.generation),
+            ..self.config.clone()
+        };
+
+        self.niche_count = self.count_niches();
+
+        let populations: Vec<usize> = regions
+            .iter()
+            .map(|region| {
+                self.grid
+                    .agents()
+                    .values()
+                    .filter(|agent| region.contains(&agent.position))
+                    .count()
+            })
+            .collect();
+        let targets = Self::region_targets(self.population_target, &populations);
+        let mut islands = Vec::new();
+        let mut emigrants = Vec::new();
+        let whole_grid = self.grid.demes().is_none();
+        for (region, target) in regions.into_iter().zip(targets) {
+            // デームがなければ盤面全体が 1 つの区域なので、個体を複製せずにそのまま渡す
+            let cloned: HashMap<Uuid, Agent>;
+            let region_agents = if whole_grid {
+                self.grid.agents()
+            } else {
+                cloned = self
+                    .grid
+                    .agents()
+                    .iter()
+                    .filter(|(_, agent)| region.contains(&agent.position))
+                    .map(|(id, agent)| (*id, agent.clone()))
+                    .collect();
+                &cloned
+            };
+
+            let outcome = evolution_service.evolve_in_region(
+                region_agents,
+                &config,
+                region,
+                target,
+                &mut self.rng,
+            );
+            self.generation_timings.add(&outcome.timings);
+            self.diagnostics.extend(outcome.diagnostics);
+            islands.push(outcome.agents);
+            if let Some(count) = migrants {
+                emigrants.push(EvolutionService::fittest(
+                    region_agents,
+                    count,
+                    &config.fitness(),
+                ));
+            }
+        }
+        self.exchange_migrants(&mut islands, emigrants);
+
+        // 世代交代では親の世代が全員入れ替わる
+        // 親の対戦履歴のバッファは捨てずに子に引き継ぎ、世代ごとの確保をなくす
+        self.deaths += self.grid.agent_count();
+        self.history_pool
+            .extend(self.grid.drain_agents().map(|agent| agent.history));
+        for mut agent in islands.into_iter().flatten() {
+            if let Some(buffer) = self.history_pool.pop() {
+                agent.history.reuse_buffer(buffer);
+            }
+            match self.grid.add_agent(agent) {
+                Ok(()) => self.births += 1,
+                Err(e) => self.diagnostics.push(format!(
+                    "Generation {}: failed to place agent: {e}",
+                    self.generation + 1
+                )),
+            }
+        }
+
+        self.history_pool.clear();
+        let excess = self.diagnostics.len().saturating_sub(DIAGNOSTICS_CAPACITY);
+        self.diagnostics.drain(..excess);
+
+        self.generation += 1;
+        self.turn = 0;
+    }
+
+    // 目標の個体数を区域ごとの今の個体数に比例して分ける（端数は前の区域から 1 体ずつ足す）
+    // 誰も死んでいなければ各区域の個体数がそのまま目標になる
+    fn region_targets(target: usize, populations: &[usize]) -> Vec<usize> {
+        let total: usize = populations.iter().sum();
+        if total == 0 {
+            return vec![0; populations.len()];
+        }
+        let mut targets: Vec<usize> = populations
+            .iter()
+            .map(|population| target * population / total)
+            .collect();
+        let remainder = target - targets.iter().sum::<usize>();
+        for share in targets.iter_mut().take(remainder) {
+            *share += 1;
+        }
+        targets
+    }
+
+    // 世代交代の直前の適応度と多様性（処理時間は世代交代の後に埋める）
+    fn generation_record(&self) -> GenerationRecord {
+        let agents = self.grid.agents_in_position_order();
+        let fitness = self.config.fitness();
+        GenerationRecord {
+            generation: self.generation,
+            population: agents.len(),
+            average_cooperation_rate: if agents.is_empty() {
+                0.0
+            } else {
+                agents
+                    .iter()
+                    .map(|agent| agent.cooperation_rate())
+                    .sum::<f64>()
+                    / agents.len() as f64
+            },
+            fitness: MetricsCalculator::fitness(&agents, &fitness),
+            diversity: MetricsCalculator::diversity(&self.grid),
+            performance: PerformanceMetrics::default(),
+            memory_usage_mb: 0.0,
+        }
+    }
+
+    // 次の世代を作る前に、今の世代の多様性とスコアの推移から実効の突然変異率を決める
+    fn adapt_mutation_rate(&mut self) {
+        if let Some(adaptive) = self.config.adaptive_mutation {
+            self.mutation_rate = adaptive.effective_rate(
+                self.config.mutation_rate,
+                self.grid.agents(),
+                &self.generation_history,
+            );
+        }
+    }
+
+    // スコアの高い順（同点は位置順）に count 体
+    // 島 i の移住者を島 (i + 1) mod K の子の代わりに置く（環状の移住経路）
+    // 移住者は遺伝子型（タグ・内集団びいき・攻撃性・行動表を含む）だけを持ち込み、位置は置き換えた子のものを使う
+    fn exchange_migrants(&mut self, islands: &mut [Vec<Agent>], emigrants: Vec<Vec<Agent>>) {
+        let island_count = islands.len();
+        for (source, migrants) in emigrants.into_iter().enumerate() {
+            let destination = (source + 1) % island_count;
+            for (slot, migrant) in islands[destination].iter_mut().zip(migrants) {
+                *slot = Genotype::of(&migrant)
+                    .spawn(slot.position)
+                    .with_random_id(&mut self.rng);
+            }
+        }
+    }
+}
+
+// 世代交代のたびに溜まる診断メッセージの上限
+const DIAGNOSTICS_CAPACITY: usize = 100;
+
+// 確保済みの枠と、各記録が持つヒープ領域の合計
+fn history_bytes<T>(history: &VecDeque<T>, heap_bytes: impl Fn(&T) -> usize) -> usize {
+    history.capacity() * std::mem::size_of::<T>() + history.iter().map(heap_bytes).sum::<usize>()
+}
+
+// 容量を超えたら古い記録から捨てる（VecDeque なので先頭を捨てても残りは動かない）
+fn push_bounded<T>(history: &mut VecDeque<T>, record: T, capacity: Option<usize>) {
+    history.push_back(record);
+    if let Some(capacity) = capacity {
+        while history.len() > capacity {
+            history.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::{FitnessEvaluator, NormalizedScoreFitness, Objective};
+    use crate::application::simulation::{
+        Archetype, EnergyConfig, InitialPopulationSpec, UpdateRule,
+    };
+    use crate::domain::agent::{GameRecord, MovementStrategy};
+    use crate::domain::game::ReputationRule;
+    use crate::domain::grid::{NetworkTopology, Zone};
+
+    fn deme_populations(simulation: &SimulationService) -> Vec<usize> {
+        let grid = &simulation.grid;
+        let demes = grid.demes().unwrap();
+        let mut counts = vec![0; demes.deme_count()];
+        for agent in grid.agents().values() {
+            counts[grid.deme_of(&agent.position).unwrap()] += 1;
+        }
+        counts
+    }
+
+    fn sorted_positions(agents: impl Iterator<Item = Position>) -> Vec<(usize, usize)> {
+        let mut positions: Vec<(usize, usize)> = agents.map(|p| (p.x, p.y)).collect();
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn test_score_normalization_removes_neighbor_count_bias() {
+        // Arrange: 同じ裏切り者が、協力者 1 体と 3 体にそれぞれ囲まれている
+        let mut grid = Grid::new(10, 10);
+        let defector = |x, y| {
+            Agent::new(
+                Position::new(x, y),
+                StrategyType::AllDefect,
+                0.0,
+                MovementStrategy::Settler,
+            )
+        };
+        let cooperator = |x, y| {
+            Agent::new(
+                Position::new(x, y),
+                StrategyType::AllCooperate,
+                0.0,
+                MovementStrategy::Settler,
+            )
+        };
+        let sparse = defector(1, 1);
+        let dense = defector(7, 7);
+        let (sparse_id, dense_id) = (sparse.id, dense.id);
+        for agent in [
+            sparse,
+            cooperator(0, 0),
+            dense,
+            cooperator(6, 6),
+            cooperator(7, 6),
+            cooperator(8, 6),
+        ] {
+            grid.add_agent(agent).unwrap();
+        }
+        let mut simulation = SimulationService::from_grid(
+            grid,
+            SimulationConfig::default().with_score_normalization(true),
+        );
+
+        // Act
+        simulation.process_games();
+
+        // Assert: 総スコアは対戦数に比例するが、正規化スコアは等しい
+        let sparse = simulation.grid.get_agent(&sparse_id).unwrap();
+        let dense = simulation.grid.get_agent(&dense_id).unwrap();
+        assert_eq!(sparse.score, 5);
+        assert_eq!(dense.score, 15);
+        assert_eq!(sparse.normalized_score, dense.normalized_score);
+        assert_eq!(NormalizedScoreFitness.evaluate(sparse), 5.0);
+    }
+
+    #[test]
+    fn test_run_generations_with_reports_each_generation_and_stops_on_false() {
+        // Arrange
+        let mut simulation = SimulationService::new(6, 6, 10).unwrap();
+        let mut reported = Vec::new();
+
+        // Act
+        let completed = simulation.run_generations_with(5, |stats| {
+            reported.push(stats.generation);
+            reported.len() < 2
+        });
+
+        // Assert
+        assert_eq!(completed, 2);
+        assert_eq!(reported, vec![0, 1]);
+        assert_eq!(simulation.get_generation(), 2);
+    }
+
+    #[test]
+    fn test_cancellation_stops_run_at_step_boundary() {
+        // Arrange
+        let mut simulation = SimulationService::new(6, 6, 10).unwrap();
+        let token = simulation.cancellation_token();
+
+        // Act: 1 世代目の報告中に別ハンドルから中断を要求する
+        let completed = simulation.run_generations_with(5, |_| {
+            token.cancel();
+            true
+        });
+
+        // Assert: 要求は消費され、次の実行は通常どおり進む
+        assert_eq!(completed, 1);
+        assert_eq!(simulation.get_generation(), 1);
+        assert!(!token.is_cancelled());
+        simulation.run_generations(1);
+        assert_eq!(simulation.get_generation(), 2);
+    }
+
+    #[test]
+    fn test_hex_neighborhood_plays_six_neighbor_pairs() {
+        // Arrange: 3x3 を埋め尽くした盤面
+        let genotype = Genotype::new(StrategyType::TitForTat, MovementStrategy::Settler, 0.0);
+        let build = |neighborhood| {
+            SimulationService::with_genotypes(
+                3,
+                3,
+                9,
+                std::slice::from_ref(&genotype),
+                SimulationConfig::default().with_neighborhood(neighborhood),
+            )
+            .unwrap()
+        };
+
+        // Act
+        let hex_pairs = build(NeighborhoodType::Hex).game_pairs();
+        let moore_pairs = build(NeighborhoodType::Moore).game_pairs();
+
+        // Assert: 六角格子では片方の対角線だけが隣接になる
+        assert_eq!(hex_pairs.len(), 16);
+        assert_eq!(moore_pairs.len(), 20);
+    }
+
+    #[test]
+    fn test_network_replaces_grid_neighbors_for_games() {
+        // Arrange: 4x4 を埋め尽くし、離れたセル同士だけを辺で結ぶ
+        let genotype = Genotype::new(StrategyType::TitForTat, MovementStrategy::Settler, 0.0);
+        let network = NetworkConfig::new(NetworkTopology::EdgeList(vec![(0, 15), (5, 10), (0, 5)]));
+        let mut simulation = SimulationService::with_genotypes(
+            4,
+            4,
+            16,
+            std::slice::from_ref(&genotype),
+            SimulationConfig::default().with_network(network),
+        )
+        .unwrap();
+
+        // Act
+        let pairs: Vec<((usize, usize), (usize, usize))> = simulation
+            .game_pairs()
+            .iter()
+            .map(|(a, b)| {
+                let a = simulation.get_agent(a).unwrap().position;
+                let b = simulation.get_agent(b).unwrap().position;
+                ((a.x, a.y), (b.x, b.y))
+            })
+            .collect();
+        let invalid = simulation.set_network(Some(NetworkConfig::new(NetworkTopology::EdgeList(
+            vec![(0, 16)],
+        ))));
+
+        // Assert: 対戦はグラフの辺だけで、範囲外のノードを含む辺リストは反映しない
+        assert_eq!(
+            pairs,
+            vec![((0, 0), (1, 1)), ((0, 0), (3, 3)), ((1, 1), (2, 2))]
+        );
+        assert!(invalid.is_err());
+        assert_eq!(simulation.get_network().unwrap().edge_count(), 3);
+        simulation.set_network(None).unwrap();
+        assert_eq!(simulation.game_pairs().len(), 42);
+    }
+
+    #[test]
+    fn test_rewiring_cuts_links_to_defectors_and_snapshot_keeps_new_links() {
+        // Arrange: 裏切り者だけが並んだ次数 4 の環
+        let genotype = Genotype::new(StrategyType::AllDefect, MovementStrategy::Settler, 0.0);
+        let ring = NetworkConfig::new(NetworkTopology::WattsStrogatz {
+            degree: 4,
+            rewire_probability: 0.0,
+        });
+        let config = SimulationConfig::default()
+            .with_seed(5)
+            .with_network(ring.clone())
+            .with_rewiring_probability(1.0);
+        let mut simulation =
+            SimulationService::with_genotypes(5, 4, 20, std::slice::from_ref(&genotype), config)
+                .unwrap();
+        simulation.set_turns_per_generation(2);
+
+        // Act
+        simulation.step();
+        let rewired = simulation.get_network_metrics().unwrap().rewired_links;
+        simulation.step();
+        let restored = SimulationService::restore_snapshot(simulation.save_snapshot()).unwrap();
+
+        // Assert: 辺の数は保たれ、世代ごとの記録と保存にもつなぎ替え後の辺が残る
+        assert!(rewired > 0);
+        let network = simulation.get_network().unwrap();
+        assert_eq!(network.edge_count(), 40);
+        assert_ne!(network, &ring.build(20));
+        assert_eq!(simulation.get_network_history().len(), 1);
+        assert_eq!(simulation.get_network_history()[0].edge_count, 40);
+        assert_eq!(restored.get_network(), Some(network));
+        assert!(SimulationConfig::default()
+            .with_rewiring_probability(0.5)
+            .validate()
+            .iter()
+            .any(|violation| violation.field == "rewiring_probability"));
+    }
+
+    #[test]
+    fn test_in_group_favoritism_drives_cooperation_within_tags() {
+        // Arrange: 全員が常に裏切る戦略だが、同じタグを持ち 0.8 の強さでひいきする集団
+        let genotype =
+            Genotype::new(StrategyType::AllDefect, MovementStrategy::Settler, 0.0).with_tag(0, 0.8);
+        let config = SimulationConfig::default()
+            .with_seed(3)
+            .with_tags(TagConfig::new(1).with_mutation_rate(0.0));
+        let mut simulation =
+            SimulationService::with_genotypes(6, 6, 36, std::slice::from_ref(&genotype), config)
+                .unwrap();
+
+        // Act
+        simulation.step();
+        let stats = simulation.get_statistics();
+
+        // Assert: 同じタグの相手には in_group_bias の確率で協力するので、協力率はびいきの強さに近い
+        assert_eq!(stats.tag_statistics.len(), 1);
+        let tag = &stats.tag_statistics[0];
+        assert_eq!(tag.total_agents, 36);
+        assert!((tag.average_in_group_bias - 0.8).abs() < 1e-9);
+        assert!((tag.average_cooperation_rate - 0.8).abs() < 0.1);
+        assert!(SimulationConfig::default()
+            .with_tags(TagConfig {
+                tag_count: 0,
+                mutation_rate: 0.0
+            })
+            .validate()
+            .iter()
+            .any(|violation| violation.field == "tags.tag_count"));
+    }
+
+    #[test]
+    fn test_reputation_board_lets_discriminators_refuse_known_defectors() {
+        // Arrange: 評判で判断する個体と常に裏切る個体が交互に並ぶ
+        let genotypes = [
+            Genotype::new(
+                StrategyType::ReputationBased,
+                MovementStrategy::Settler,
+                0.0,
+            ),
+            Genotype::new(StrategyType::AllDefect, MovementStrategy::Settler, 0.0),
+        ];
+        let config = SimulationConfig::default()
+            .with_seed(4)
+            .with_reputation(ReputationConfig::new(ReputationRule::Standing));
+        let mut simulation =
+            SimulationService::with_genotypes(6, 5, 30, &genotypes, config).unwrap();
+
+        // Act
+        for _ in 0..3 {
+            simulation.step();
+        }
+        let restored = SimulationService::restore_snapshot(simulation.save_snapshot()).unwrap();
+
+        // Assert: 裏切り者だけ評判が悪く、識別者は裏切り者を裏切っても評判を落とさない
+        let board = simulation.get_reputation_board();
+        for agent in simulation.agents_in_position_order() {
+            let expected_good = agent.strategy == StrategyType::ReputationBased;
+            assert_eq!(board.is_good(&agent.id), expected_good);
+            if expected_good {
+                for neighbor in simulation.grid.get_neighbors(&agent.position) {
+                    let expected = if neighbor.strategy == StrategyType::AllDefect {
+                        Action::Defect
+                    } else {
+                        Action::Cooperate
+                    };
+                    assert_eq!(
+                        agent.history.get_last_my_action(&neighbor.id),
+                        Some(expected)
+                    );
+                }
+            }
+        }
+        assert_eq!(restored.get_reputation_board(), board);
+    }
+
+    #[test]
+    fn test_aggressive_cooperators_punish_defecting_neighbors() {
+        // Arrange: 必ず罰する協力者と、罰さない裏切り者が列ごとに交互に並ぶ
+        let genotypes = [
+            Genotype::new(StrategyType::AllCooperate, MovementStrategy::Settler, 0.0)
+                .with_aggression(1.0),
+            Genotype::new(StrategyType::AllDefect, MovementStrategy::Settler, 0.0),
+        ];
+        let config = SimulationConfig::default()
+            .with_seed(6)
+            .with_punishment(PunishmentConfig::new(1, 4));
+        let mut simulation =
+            SimulationService::with_genotypes(6, 4, 24, &genotypes, config).unwrap();
+
+        // Act
+        let stats = simulation.step();
+
+        // Assert: 協力者は裏切られるたびに罰し、罰は対戦記録に残る（裏切り者同士は罰さない）
+        assert!(stats.punishments > 0);
+        assert!(stats.punishment_frequency > 0.0 && stats.punishment_frequency < 1.0);
+        for agent in simulation.agents_in_position_order() {
+            for record in agent.history.records() {
+                let punisher = simulation.get_agent(&record.opponent_id).unwrap();
+                let expected = agent.strategy == StrategyType::AllDefect
+                    && punisher.strategy == StrategyType::AllCooperate;
+                assert_eq!(record.punished_by_opponent, expected);
+            }
+        }
+        assert!(SimulationConfig::default()
+            .with_punishment(PunishmentConfig::new(-1, 4))
+            .validate()
+            .iter()
+            .any(|violation| violation.field == "punishment"));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_games_in_one_batch_draw_their_own_random_numbers() {
+        // Arrange: 離れた 2 組の「寛容なしっぺ返し 対 全裏切り」。2 つの対戦は同じバッチに入る
+        let mut agents = HashMap::new();
+        for (x, strategy) in [
+            (0, StrategyType::GenerousTitForTat),
+            (1, StrategyType::AllDefect),
+            (4, StrategyType::GenerousTitForTat),
+            (5, StrategyType::AllDefect),
+        ] {
+            let agent = Agent::new(
+                Position::new(x, 1),
+                strategy,
+                0.0,
+                MovementStrategy::Settler,
+            );
+            agents.insert(agent.id, agent);
+        }
+        let config = SimulationConfig::default()
+            .with_seed(11)
+            .with_torus_field(false);
+        let mut simulation = SimulationService::with_agents(8, 3, agents, config).unwrap();
+
+        // Act
+        for _ in 0..20 {
+            simulation.execute_battles();
+        }
+
+        // Assert: ノイズなしでも、確率的な戦略の手は対戦ごとに別の乱数で決まる
+        let moves: Vec<Vec<Action>> = simulation
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|agent| agent.strategy == StrategyType::GenerousTitForTat)
+            .map(|agent| {
+                agent
+                    .history
+                    .records()
+                    .map(|record| record.my_action)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(moves.len(), 2);
+        assert!(moves[0].len() > 1);
+        assert_ne!(moves[0], moves[1]);
+    }
+
+    #[test]
+    fn test_set_payoff_matrix_validates_and_applies_to_next_games() {
+        // Arrange: 協力者だけの集団
+        let genotypes = [Genotype::new(
+            StrategyType::AllCooperate,
+            MovementStrategy::Settler,
+            0.0,
+        )];
+        let config = SimulationConfig::default()
+            .with_seed(3)
+            .with_mutation_rate(0.0);
+        let mut simulation =
+            SimulationService::with_genotypes(6, 4, 24, &genotypes, config).unwrap();
+        // 雪崩ゲーム（T > R > S > P）は囚人のジレンマではない
+        let snowdrift = PayoffMatrix::new(5, 3, 0, 1);
+
+        // Act
+        let rejected = simulation.set_payoff_matrix(snowdrift, false);
+        let accepted = simulation.set_payoff_matrix(PayoffMatrix::new(11, 10, 1, 0), false);
+        simulation.step();
+
+        // Assert: 拒否した表は反映されず、受け付けた表で次の対戦が行われる
+        assert!(rejected.is_err());
+        assert!(accepted.is_ok());
+        assert_eq!(simulation.get_config().payoff_matrix.reward, 10);
+        let agents = simulation.agents_in_position_order();
+        assert!(agents.iter().any(|agent| agent.score > 0));
+        for agent in agents {
+            assert_eq!(agent.score % 10, 0);
+        }
+        assert!(simulation.set_payoff_matrix(snowdrift, true).is_ok());
+        assert_eq!(simulation.current_payoffs(), snowdrift);
+        assert!(simulation
+            .get_diagnostics()
+            .last()
+            .is_some_and(|line| line.ends_with("running a different social dilemma")));
+    }
+
+    #[test]
+    fn test_payoff_schedule_switches_payoffs_at_keyframe_generation() {
+        // Arrange: 協力者だけの集団で、1 世代目から R=10 に切り替える
+        let genotypes = [Genotype::new(
+            StrategyType::AllCooperate,
+            MovementStrategy::Settler,
+            0.0,
+        )];
+        let schedule = PayoffSchedule::new().with_change(1, PayoffMatrix::new(11, 10, 1, 0));
+        let config = SimulationConfig::default()
+            .with_seed(7)
+            .with_mutation_rate(0.0)
+            .with_payoff_schedule(schedule);
+        let mut simulation =
+            SimulationService::with_genotypes(6, 4, 24, &genotypes, config).unwrap();
+        simulation.set_turns_per_generation(1);
+
+        // Act
+        let before = simulation.current_payoffs();
+        simulation.step();
+        simulation.set_turns_per_generation(2);
+        simulation.step();
+
+        // Assert: 世代 0 は基本の利得表、世代 1 からは予定の利得表で対戦する
+        assert_eq!(before, PayoffMatrix::prisoners_dilemma());
+        assert_eq!(simulation.current_payoffs().reward, 10);
+        let agents = simulation.agents_in_position_order();
+        assert!(agents.iter().any(|agent| agent.score > 0));
+        for agent in agents {
+            assert_eq!(agent.score % 10, 0);
+        }
+        assert!(simulation
+            .get_diagnostics()
+            .iter()
+            .any(|line| line.starts_with("Generation 1: payoff schedule")));
+        assert!(simulation
+            .set_payoff_schedule(Some(PayoffSchedule::new()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_zones_change_payoffs_and_mutation_rate_by_region() {
+        // Arrange: 右半分だけ R=10 で、子が必ず突然変異する区域。全体の突然変異率は 0
+        let genotypes = [Genotype::new(
+            StrategyType::AllCooperate,
+            MovementStrategy::Settler,
+            0.0,
+        )];
+        let rich = Zone::new("rich", Region::new(3, 0, 3, 4))
+            .with_payoffs(PayoffMatrix::new(11, 10, 1, 0))
+            .with_mutation_rate(1.0);
+        let config = SimulationConfig::default()
+            .with_seed(8)
+            .with_mutation_rate(0.0)
+            .with_zones(ZoneMap::new(vec![rich]));
+        let mut simulation =
+            SimulationService::with_genotypes(6, 4, 24, &genotypes, config).unwrap();
+        simulation.set_turns_per_generation(2);
+
+        // Act
+        simulation.step();
+        let scores: Vec<(usize, i32)> = simulation
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| (agent.position.x, agent.score))
+            .collect();
+        simulation.set_turns_per_generation(1);
+        simulation.step();
+
+        // Assert: 利得は自分のいるセルの区域の表で決まり、突然変異は区域の中で生まれた子だけに起こる
+        assert!(scores.iter().all(|&(x, score)| if x >= 3 {
+            score > 0 && score % 10 == 0
+        } else {
+            score > 0 && score % 3 == 0
+        }));
+        let agents = simulation.agents_in_position_order();
+        let mutated =
+            |agent: &&Agent| agent.strategy != StrategyType::AllCooperate || agent.mobility > 0.0;
+        assert!(agents
+            .iter()
+            .filter(|agent| agent.position.x < 3)
+            .all(|agent| !mutated(agent)));
+        assert!(agents
+            .iter()
+            .filter(|agent| agent.position.x >= 3)
+            .any(mutated));
+        let map = simulation.get_heatmap(HeatmapField::Zone);
+        assert_eq!(map[3], 0.0);
+        assert!(map[2].is_nan());
+        assert!(simulation
+            .set_zones(Some(ZoneMap::new(vec![Zone::new(
+                "outside",
+                Region::new(5, 0, 2, 1)
+            )])))
+            .is_err());
+    }
+
+    #[test]
+    fn test_obstacle_wall_separates_battles_and_offspring_avoid_it() {
+        // Arrange: 7x3 の真ん中の列を壁にし、残りのセルを埋める
+        let wall: Vec<Position> = (0..3).map(|y| Position::new(3, y)).collect();
+        let config = SimulationConfig::default()
+            .with_seed(9)
+            .with_obstacles(wall.clone());
+        let mut simulation = SimulationService::with_config(7, 3, 18, config).unwrap();
+        simulation.set_turns_per_generation(1);
+
+        // Act
+        simulation.run_generations(2);
+        simulation.set_turns_per_generation(2);
+        simulation.step();
+
+        // Assert: 子は壁に置かれず、対戦は壁の同じ側の相手とだけ行う
+        let agents = simulation.agents_in_position_order();
+        assert_eq!(agents.len(), 18);
+        for agent in &agents {
+            assert_ne!(agent.position.x, 3);
+            for record in agent.history.records() {
+                let opponent = simulation.get_agent(&record.opponent_id).unwrap();
+                assert_eq!(opponent.position.x < 3, agent.position.x < 3);
+            }
+        }
+        assert!(SimulationService::with_config(
+            7,
+            3,
+            19,
+            SimulationConfig::default().with_obstacles(wall)
+        )
+        .is_err());
+        simulation.set_obstacle(Position::new(0, 0), true).unwrap();
+        assert_eq!(simulation.get_agent_count(), 17);
+        assert_eq!(simulation.get_obstacles().len(), 4);
+    }
+
+    #[test]
+    fn test_generation_caps_configured_target_at_capacity_and_bounds_diagnostics() {
+        // Arrange: 5x5 を埋めた 25 体の目標のまま、個体のいたセルを壁にして収容数を 24 に減らす
+        let config = SimulationConfig::default().with_seed(5);
+        let mut simulation = SimulationService::with_config(5, 5, 25, config).unwrap();
+        simulation.set_turns_per_generation(1);
+        simulation.set_obstacle(Position::new(2, 2), true).unwrap();
+
+        // Act
+        simulation.run_generations(1);
+        let capped = simulation.get_diagnostics().to_vec();
+        simulation.run_generations(DIAGNOSTICS_CAPACITY as u32 + 10);
+
+        // Assert: 収容数まで埋め、切り詰めたことを診断に残す。診断は上限を超えて溜まらない
+        assert_eq!(simulation.get_agent_count(), 24);
+        assert_eq!(capped.len(), 1);
+        assert!(capped[0].contains("Target population 25 exceeds grid capacity 24"));
+        assert_eq!(simulation.get_diagnostics().len(), DIAGNOSTICS_CAPACITY);
+    }
+
+    #[test]
+    fn test_generation_restores_configured_population_after_deaths() {
+        // Arrange: 壁で 1 体を失ったあと壁を取り除く
+        let config = SimulationConfig::default().with_seed(6);
+        let mut simulation = SimulationService::with_config(6, 6, 20, config).unwrap();
+        simulation.set_turns_per_generation(1);
+        let occupied = simulation.agents_in_position_order()[0].position;
+        simulation.set_obstacle(occupied, true).unwrap();
+        simulation.set_obstacle(occupied, false).unwrap();
+        assert_eq!(simulation.get_agent_count(), 19);
+
+        // Act
+        simulation.run_generations(1);
+
+        // Assert: 子の数は今の個体数ではなく構築時の目標に従う
+        assert_eq!(simulation.get_agent_count(), 20);
+        assert!(simulation.get_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_movement_policy_replaces_per_agent_movement() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(4)
+            .with_movement_policy(MovementPolicyKind::Stay);
+        let mut simulation = SimulationService::with_config(10, 10, 30, config).unwrap();
+        simulation.set_turns_per_generation(10);
+        let positions = |simulation: &SimulationService| -> Vec<Position> {
+            simulation
+                .agents_in_position_order()
+                .iter()
+                .map(|agent| agent.position)
+                .collect()
+        };
+        let before = positions(&simulation);
+
+        // Act
+        simulation.step();
+        let after_stay = positions(&simulation);
+        simulation
+            .set_movement_policy(Some(MovementPolicyKind::RandomWalk))
+            .unwrap();
+        simulation.step();
+
+        // Assert
+        assert_eq!(after_stay, before);
+        assert_ne!(positions(&simulation), before);
+        assert!(simulation
+            .set_movement_policy(Some(MovementPolicyKind::SuccessDriven { radius: 0 }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_success_driven_migration_gathers_isolated_cooperators() {
+        // Arrange: 協力者だけがまばらにいる。孤立した個体の利得は 0 なので、協力者の隣へ移る
+        let spec = InitialPopulationSpec::new().with_archetype(
+            Archetype::strategy(StrategyType::AllCooperate, 1.0).with_mobility(0.0),
+        );
+        let config = SimulationConfig::default()
+            .with_seed(3)
+            .with_initial_population(spec)
+            .with_migration_model(MigrationModel::SuccessDriven(5));
+        let mut simulation = SimulationService::with_config(12, 12, 15, config).unwrap();
+        simulation.set_turns_per_generation(10);
+        let isolated = |simulation: &SimulationService| {
+            simulation
+                .agents_in_position_order()
+                .iter()
+                .filter(|agent| simulation.grid.get_neighbors(&agent.position).is_empty())
+                .count()
+        };
+        let before = isolated(&simulation);
+
+        // Act
+        simulation.step();
+
+        // Assert
+        assert!(isolated(&simulation) < before);
+        assert!(simulation
+            .set_migration_model(MigrationModel::SuccessDriven(0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_fitness_function_resolves_from_config_and_feeds_statistics() {
+        // Arrange
+        let weights = WeightedFitness::new().with_cooperation_weight(10.0);
+        let config = SimulationConfig::default().with_seed(2);
+        let mut simulation = SimulationService::with_config(10, 10, 40, config.clone()).unwrap();
+        simulation.set_turns_per_generation(10);
+
+        // Act
+        let raw = simulation.step();
+        simulation
+            .set_fitness_function(Some(FitnessFunction::ScorePerBattle))
+            .unwrap();
+        let per_battle = simulation.get_statistics();
+
+        // Assert
+        assert_eq!(config.fitness(), FitnessFunction::RawScore);
+        assert_eq!(
+            config.clone().with_weighted_fitness(weights).fitness(),
+            FitnessFunction::Weighted(weights)
+        );
+        assert_eq!(
+            config
+                .with_weighted_fitness(weights)
+                .with_fitness_function(FitnessFunction::ScorePerBattle)
+                .fitness(),
+            FitnessFunction::ScorePerBattle
+        );
+        assert_eq!(raw.average_fitness, raw.average_score);
+        assert!(per_battle.average_fitness < per_battle.average_score);
+        assert!(simulation
+            .set_fitness_function(Some(FitnessFunction::AgeDiscounted { rate: 2.0 }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_fitness_sharing_reports_niches_in_statistics() {
+        // Arrange
+        let config = SimulationConfig::default().with_seed(4);
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+
+        // Act: ニッチは世代交代のときに数え、次の世代交代まで同じ値を返す
+        let without = simulation.run_generations(1);
+        simulation
+            .set_fitness_sharing(Some(FitnessSharing::new(0.2)))
+            .unwrap();
+        let before_generation = simulation.get_statistics();
+        let with = simulation.run_generations(1);
+        let wider = {
+            simulation
+                .set_fitness_sharing(Some(FitnessSharing::new(1.0)))
+                .unwrap();
+            simulation.run_generations(1)
+        };
+
+        // Assert: 半径を広げるほどニッチはまとまる（距離は 1 未満なので半径 1 なら 1 つ）
+        assert_eq!(without.niche_count, 0);
+        assert_eq!(before_generation.niche_count, 0);
+        assert!(with.niche_count > 1 && with.niche_count <= 40);
+        assert_eq!(wider.niche_count, 1);
+        assert!(simulation
+            .set_fitness_sharing(Some(FitnessSharing::new(-0.1)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_initial_population_spec_seeds_invasion_setup() {
+        // Arrange: AllDefect の集団に TitForTat を 10% 混ぜる
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 0.9))
+            .with_archetype(Archetype::strategy(StrategyType::TitForTat, 0.1).with_mobility(0.0));
+        let config = SimulationConfig::default()
+            .with_seed(1)
+            .with_initial_population(spec);
+
+        // Act
+        let mut simulation = SimulationService::with_config(10, 10, 50, config).unwrap();
+        let count = |simulation: &SimulationService, strategy| {
+            simulation
+                .get_agents()
+                .iter()
+                .filter(|agent| agent.strategy == strategy)
+                .count()
+        };
+
+        // Assert: reset でも同じ指定で初期化される
+        assert_eq!(count(&simulation, StrategyType::AllDefect), 45);
+        assert_eq!(count(&simulation, StrategyType::TitForTat), 5);
+        assert!(simulation
+            .get_agents()
+            .iter()
+            .filter(|agent| agent.strategy == StrategyType::TitForTat)
+            .all(|agent| agent.mobility == 0.0));
+        simulation.reset(20).unwrap();
+        assert_eq!(count(&simulation, StrategyType::AllDefect), 18);
+        assert_eq!(count(&simulation, StrategyType::TitForTat), 2);
+    }
+
+    #[test]
+    fn test_center_blob_places_first_archetype_at_center() {
+        // Arrange: 中心の塊に TitForTat、その周りを AllDefect が囲む
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::TitForTat, 0.1))
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 0.9));
+        let config = SimulationConfig::default()
+            .with_seed(2)
+            .with_initial_population(spec)
+            .with_placement(PlacementPattern::CenterBlob);
+
+        // Act
+        let simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+
+        // Assert
+        let center = simulation
+            .grid
+            .get_agent_at_position(&Position::new(5, 5))
+            .unwrap();
+        assert_eq!(center.strategy, StrategyType::TitForTat);
+        assert!(simulation.grid.is_position_free(&Position::new(0, 0)));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_generation_history() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(42)
+            .with_deme_structure(DemeStructure::new(2, 1).with_migration_rate(0.1));
+        let run = |config: SimulationConfig| {
+            let mut simulation = SimulationService::with_config(12, 12, 60, config).unwrap();
+            simulation.run_generations(3);
+            simulation.to_result()
+        };
+
+        // Act
+        let first = run(config.clone());
+        let second = run(config.clone());
+        let other = run(config.with_seed(7));
+
+        // Assert
+        let summary = |result: &SimulationResult| -> Vec<(Vec<usize>, f64, f64, f64)> {
+            result
+                .generation_history
+                .iter()
+                .map(|stats| {
+                    (
+                        StrategyType::ALL
+                            .iter()
+                            .map(|s| stats.strategy_counts.get(s).copied().unwrap_or(0))
+                            .collect(),
+                        stats.average_cooperation_rate,
+                        stats.average_mobility,
+                        stats.average_score,
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(first.generation_history.len(), 3);
+        assert_eq!(summary(&first), summary(&second));
+        assert_ne!(summary(&first), summary(&other));
+    }
+
+    #[test]
+    fn test_from_frame_reconstructs_recorded_positions() {
+        // Arrange
+        let mut simulation = SimulationService::new(12, 12, 40).unwrap();
+        let mut sequence = FrameSequence::new(12, 12);
+        for _ in 0..3 {
+            simulation.step();
+            sequence.push(simulation.capture_frame());
+        }
+
+        // Act
+        let restored = SimulationService::from_frame(&sequence, 1).unwrap();
+
+        // Assert
+        let frame = &sequence.frames[1];
+        assert_eq!(restored.get_turn(), frame.turn);
+        assert_eq!(restored.get_generation(), frame.generation);
+        assert_eq!(
+            sorted_positions(restored.get_agents().iter().map(|a| a.position)),
+            sorted_positions(frame.agents.iter().map(FrameAgent::position))
+        );
+        for agent in restored.get_agents() {
+            let original = frame
+                .agents
+                .iter()
+                .find(|f| f.position() == agent.position)
+                .unwrap();
+            assert_eq!(agent.strategy.id(), original.strategy);
+            assert_eq!(agent.movement_strategy.id(), original.movement_strategy);
+        }
+    }
+
+    #[test]
+    fn test_from_frame_rejects_out_of_range_index() {
+        // Arrange
+        let sequence = FrameSequence::new(5, 5);
+
+        // Act
+        let result = SimulationService::from_frame(&sequence, 0);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_demes_without_migration_keep_agents_and_battles_inside() {
+        // Arrange
+        let config = SimulationConfig::default().with_deme_structure(DemeStructure::new(2, 2));
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+        let initial_demes: HashMap<Uuid, usize> = simulation
+            .grid
+            .agents()
+            .values()
+            .map(|agent| (agent.id, simulation.grid.deme_of(&agent.position).unwrap()))
+            .collect();
+
+        for _ in 0..50 {
+            // Assert: 対戦は同じデーム内のみ
+            for (id1, id2) in simulation.game_pairs() {
+                let agent1 = simulation.grid.get_agent(&id1).unwrap();
+                let agent2 = simulation.grid.get_agent(&id2).unwrap();
+                assert!(simulation
+                    .grid
+                    .same_deme(&agent1.position, &agent2.position));
+            }
+
+            // Act
+            simulation.step();
+
+            // Assert: どのエージェントもデーム境界を越えない
+            for agent in simulation.grid.agents().values() {
+                assert_eq!(
+                    simulation.grid.deme_of(&agent.position),
+                    Some(initial_demes[&agent.id])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_demes_without_migration_preserve_deme_populations_across_generations() {
+        // Arrange
+        let config = SimulationConfig::default().with_deme_structure(DemeStructure::new(2, 1));
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+        let before = deme_populations(&simulation);
+
+        // Act
+        simulation.run_generations(1);
+
+        // Assert
+        assert_eq!(deme_populations(&simulation), before);
+    }
+
+    #[test]
+    fn test_demes_with_full_migration_move_agents_between_demes() {
+        // Arrange
+        let demes = DemeStructure::new(2, 1).with_migration_rate(1.0);
+        let config = SimulationConfig::default().with_deme_structure(demes);
+        let mut simulation = SimulationService::with_config(10, 10, 10, config).unwrap();
+        let initial_demes: HashMap<Uuid, usize> = simulation
+            .grid
+            .agents()
+            .values()
+            .map(|agent| (agent.id, simulation.grid.deme_of(&agent.position).unwrap()))
+            .collect();
+
+        // Act
+        simulation.step();
+
+        // Assert: 空きセルが十分あるため全員が別デームへ移住する
+        for agent in simulation.grid.agents().values() {
+            assert_ne!(
+                simulation.grid.deme_of(&agent.position),
+                Some(initial_demes[&agent.id])
+            );
+        }
+    }
+
+    // 島 0 を全員 AllDefect、島 1 を全員 AllCooperate にする
+    fn split_islands(demes: DemeStructure) -> SimulationService {
+        let config = SimulationConfig::default()
+            .with_seed(11)
+            .with_mutation_rate(0.0)
+            .with_deme_structure(demes);
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+        let ids: Vec<Uuid> = simulation.grid.agents().keys().copied().collect();
+        for id in ids {
+            let island = simulation
+                .grid
+                .deme_of(&simulation.grid.agents()[&id].position)
+                .unwrap();
+            simulation.grid.get_agent_mut(&id).unwrap().strategy = if island == 0 {
+                StrategyType::AllDefect
+            } else {
+                StrategyType::AllCooperate
+            };
+        }
+        simulation
+    }
+
+    fn island_strategies(simulation: &SimulationService, island: usize) -> Vec<StrategyType> {
+        simulation
+            .grid
+            .agents()
+            .values()
+            .filter(|agent| simulation.grid.deme_of(&agent.position) == Some(island))
+            .map(|agent| agent.strategy)
+            .collect()
+    }
+
+    #[test]
+    fn test_scheduled_migration_sends_top_agents_to_next_island() {
+        // Arrange
+        let mut isolated = split_islands(DemeStructure::new(2, 1));
+        let mut connected = split_islands(DemeStructure::new(2, 1).with_scheduled_migration(1, 2));
+
+        // Act
+        isolated.run_generations(1);
+        connected.run_generations(1);
+
+        // Assert: 島ごとの個体数は変わらず、移住した 2 体だけ戦略が混ざる
+        assert!(island_strategies(&isolated, 1)
+            .iter()
+            .all(|strategy| *strategy == StrategyType::AllCooperate));
+        let defectors = island_strategies(&connected, 1)
+            .iter()
+            .filter(|strategy| **strategy == StrategyType::AllDefect)
+            .count();
+        assert_eq!(defectors, 2);
+        assert_eq!(deme_populations(&connected), deme_populations(&isolated));
+    }
+
+    #[test]
+    fn test_statistics_report_each_island() {
+        // Arrange
+        let simulation = split_islands(DemeStructure::new(2, 1));
+
+        // Act
+        let stats = simulation.get_statistics();
+
+        // Assert
+        assert_eq!(stats.island_statistics.len(), 2);
+        assert_eq!(
+            stats
+                .island_statistics
+                .iter()
+                .map(|island| island.total_agents)
+                .sum::<usize>(),
+            stats.total_agents
+        );
+        assert!(
+            SimulationService::with_config(5, 5, 5, SimulationConfig::default())
+                .unwrap()
+                .get_statistics()
+                .island_statistics
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_adaptive_mutation_rate_is_reported_per_generation() {
+        // Arrange: 閾値 1.0 なら完全に均等でない限り毎世代引き上げる
+        let config = SimulationConfig::default()
+            .with_seed(3)
+            .with_mutation_rate(0.05)
+            .with_adaptive_mutation(AdaptiveMutation::Diversity {
+                threshold: 1.0,
+                boost: 3.0,
+            });
+        let mut simulation = SimulationService::with_config(10, 10, 30, config).unwrap();
+
+        // Act
+        simulation.run_generations(2);
+
+        // Assert: 初期集団は基本率、2 世代目以降は引き上げた率で生まれる
+        let history = simulation.get_generation_history();
+        assert_eq!(history[0].mutation_rate, 0.05);
+        assert!((history[1].mutation_rate - 0.15).abs() < 1e-12);
+        assert!((simulation.get_statistics().mutation_rate - 0.15).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_multi_objective_records_pareto_front_each_generation() {
+        // Arrange
+        let multi = MultiObjective::new(vec![Objective::Score, Objective::Cooperation]);
+        let config = SimulationConfig::default()
+            .with_seed(4)
+            .with_multi_objective(multi.clone());
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+
+        // Act
+        simulation.run_generations(2);
+
+        // Assert: 前線のどのメンバーも他のエージェントに優越されない
+        let history = simulation.get_pareto_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].generation, 1);
+        assert!(!history[1].members.is_empty());
+        let front = simulation.get_pareto_front().unwrap();
+        for member in &front.members {
+            assert!(simulation.grid.agents().values().all(|agent| {
+                let other = multi.values(agent);
+                !(other.iter().zip(&member.values).all(|(o, m)| o >= m) && other != member.values)
+            }));
+        }
+    }
+
+    #[test]
+    fn test_memory_genomes_are_inherited_across_generations() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(5)
+            .with_memory_genomes(2);
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+
+        // Act
+        simulation.run_generations(2);
+
+        // Assert: 全員が memory-two の表を持ち、strategy は表から分類した値
+        assert!(simulation.grid.agent_count() > 0);
+        for agent in simulation.grid.agents().values() {
+            let genome = agent.memory_genome.as_ref().unwrap();
+            assert_eq!(genome.memory(), 2);
+            assert_eq!(agent.strategy, genome.classify());
+        }
+    }
+
+    #[test]
+    fn test_invalid_memory_genome_length_is_rejected() {
+        // Arrange
+        let config = SimulationConfig::default().with_memory_genomes(3);
+
+        // Act
+        let result = SimulationService::with_config(10, 10, 10, config);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_public_goods_cooperators_lose_against_defectors() {
+        // Arrange: 増幅率がグループ人数より小さいと拠出者は損をする
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllCooperate, 0.5))
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 0.5));
+        let config = SimulationConfig::default()
+            .with_seed(9)
+            .with_initial_population(spec)
+            .with_game_type(GameType::PublicGoods(PublicGoodsGame::default()));
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+
+        // Act
+        simulation.process_public_goods(&PublicGoodsGame::default());
+
+        // Assert
+        let average = |strategy: StrategyType| {
+            let scores: Vec<i32> = simulation
+                .grid
+                .agents()
+                .values()
+                .filter(|agent| agent.strategy == strategy)
+                .map(|agent| agent.score)
+                .collect();
+            scores.iter().sum::<i32>() as f64 / scores.len() as f64
+        };
+        assert!(average(StrategyType::AllDefect) > average(StrategyType::AllCooperate));
+    }
+
+    #[test]
+    fn test_non_dilemma_payoffs_require_override() {
+        // Arrange
+        let strict = SimulationConfig::default().with_payoff_matrix(PayoffMatrix::new(5, 3, 3, 0));
+        let allowed = strict.clone().with_non_dilemma_payoffs(true);
+
+        // Act
+        let rejected = SimulationService::with_config(10, 10, 10, strict);
+        let accepted = SimulationService::with_config(10, 10, 10, allowed).unwrap();
+
+        // Assert: 上書きした場合は警告が診断情報に残る
+        assert!(rejected.is_err());
+        assert_eq!(accepted.get_diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn test_game_preset_switches_payoffs() {
+        // Arrange
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 1.0));
+        let config = SimulationConfig::default()
+            .with_seed(4)
+            .with_initial_population(spec);
+        let mut simulation = SimulationService::with_config(10, 10, 50, config).unwrap();
+
+        // Act: タカ・ハトではタカ同士の対戦は -1
+        simulation.set_game_preset("hawk_dove").unwrap();
+        simulation.process_games();
+
+        // Assert
+        assert!(simulation.set_game_preset("chicken").is_err());
+        assert!(simulation
+            .grid
+            .agents()
+            .values()
+            .all(|agent| agent.score <= 0));
+        assert!(simulation
+            .grid
+            .agents()
+            .values()
+            .any(|agent| agent.score < 0));
+    }
+
+    #[test]
+    fn test_depleted_resources_shrink_payoffs() {
+        // Arrange: 回復しない資源を 1 回の対戦で使い切る
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllCooperate, 1.0));
+        let resources = ResourceConfig::new(0.1, 0.0, 0.1).unwrap();
+        let config = SimulationConfig::default()
+            .with_seed(6)
+            .with_initial_population(spec)
+            .with_resources(resources);
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+
+        // Act
+        simulation.step();
+
+        // Assert: 各エージェントは最初の 1 回分（R = 3）しか得られない
+        assert!(simulation
+            .grid
+            .agents()
+            .values()
+            .all(|agent| agent.score <= 3));
+        assert!(simulation
+            .get_resource_levels()
+            .unwrap()
+            .iter()
+            .all(|&level| level == 0.0 || level == 0.1));
+    }
+
+    #[test]
+    fn test_energy_model_starves_defectors_and_keeps_generation_count() {
+        // Arrange: 裏切り同士の利得（P = 1）では代謝をまかなえない
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 1.0));
+        let config = SimulationConfig::default()
+            .with_seed(8)
+            .with_initial_population(spec)
+            .with_reproduction(ReproductionModel::Energy(EnergyConfig::default()));
+        let mut simulation = SimulationService::with_config(10, 10, 50, config).unwrap();
+        simulation.turns_per_generation = 5;
+
+        // Act
+        for _ in 0..5 {
+            simulation.step();
+        }
+
+        // Assert: 初期エネルギー 10 に対し 1 ステップの収支は最大 8 * 0.25 - 2 = 0 以下
+        assert_eq!(simulation.get_generation(), 1);
+        assert!(simulation.get_agent_count() < 50);
+        assert!(simulation
+            .grid
+            .agents()
+            .values()
+            .all(|agent| agent.energy <= 10.0));
+    }
+
+    #[test]
+    fn test_every_constructor_starts_energy_model_agents_with_initial_energy() {
+        // Arrange: どの構築方法でも、エネルギー型の個体は初期エネルギー 10 から始める
+        let energy = || {
+            SimulationConfig::default()
+                .with_seed(4)
+                .with_reproduction(ReproductionModel::Energy(EnergyConfig::default()))
+        };
+        let genotypes = [Genotype::new(
+            StrategyType::TitForTat,
+            MovementStrategy::Settler,
+            0.0,
+        )];
+        let mut recorded = SimulationService::new(8, 8, 20).unwrap();
+        let mut sequence = FrameSequence::new(8, 8);
+        sequence.push(recorded.capture_frame());
+        recorded.step();
+        let agents: HashMap<Uuid, Agent> = recorded
+            .get_agents()
+            .into_iter()
+            .map(|agent| (agent.id, agent))
+            .collect();
+
+        // Act
+        let simulations = [
+            SimulationService::with_strategies(
+                8,
+                8,
+                &[StrategyType::TitForTat, StrategyType::AllCooperate],
+                energy(),
+            ),
+            SimulationService::with_genotypes(8, 8, 20, &genotypes, energy()),
+            SimulationService::with_agents(8, 8, agents, energy()),
+            SimulationService::from_frame_with_config(&sequence, 0, energy()),
+        ]
+        .map(Result::unwrap);
+
+        // Assert: 最初のステップで飢え死にしない
+        for mut simulation in simulations {
+            let population = simulation.get_agent_count();
+            assert!(simulation
+                .get_agents()
+                .iter()
+                .all(|agent| agent.energy == 10.0));
+            simulation.step();
+            assert_eq!(simulation.get_agent_count(), population);
+        }
+    }
+
+    #[test]
+    fn test_constructors_validate_configuration() {
+        // Arrange: タグの種類数 0 は不正
+        let config = SimulationConfig::default().with_tags(TagConfig {
+            tag_count: 0,
+            mutation_rate: 0.0,
+        });
+
+        // Act
+        let by_strategies =
+            SimulationService::with_strategies(4, 4, &[StrategyType::TitForTat], config.clone());
+        let by_genotypes = SimulationService::with_genotypes(
+            4,
+            4,
+            2,
+            &[Genotype::new(
+                StrategyType::TitForTat,
+                MovementStrategy::Settler,
+                0.0,
+            )],
+            config,
+        );
+
+        // Assert
+        assert!(by_strategies.is_err());
+        assert!(by_genotypes.is_err());
+    }
+
+    #[test]
+    fn test_steady_state_updates_without_replacing_population() {
+        // Arrange
+        let config = SimulationConfig::default().with_seed(12).with_reproduction(
+            ReproductionModel::SteadyState(SteadyStateConfig {
+                rule: UpdateRule::DeathBirth,
+                updates_per_step: 5,
+            }),
+        );
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+        simulation.turns_per_generation = 10;
+
+        // Act
+        for _ in 0..10 {
+            simulation.step();
+        }
+
+        // Assert: 個体数は変わらず、統計はステップごとに残る
+        assert_eq!(simulation.get_agent_count(), 60);
+        assert_eq!(simulation.get_step_history().len(), 10);
+        assert_eq!(simulation.get_generation_history().len(), 1);
+        assert_eq!(simulation.get_generation(), 1);
+    }
+
+    #[test]
+    fn test_histograms_recorded_each_generation_when_enabled() {
+        // Arrange
+        let config = SimulationConfig::default().with_seed(4).with_histograms(20);
+        let mut simulation = SimulationService::with_config(10, 10, 50, config).unwrap();
+        simulation.turns_per_generation = 2;
+
+        // Act
+        for _ in 0..6 {
+            simulation.step();
+        }
+
+        // Assert: 3 世代分、各 20 ビンで全個体を数える
+        let history = simulation.get_detailed_history();
+        assert_eq!(history.len(), 3);
+        assert!(history
+            .iter()
+            .all(|stats| stats.cooperation_rate.counts.len() == 20
+                && stats.mobility.total() == stats.score.total()));
+        assert_eq!(history[2].generation, 2);
+
+        simulation.reset(50).unwrap();
+        assert!(simulation.get_detailed_history().is_empty());
+    }
+
+    #[test]
+    fn test_spatial_metrics_recorded_each_generation_when_enabled() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(8)
+            .with_spatial_metrics(true);
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+        simulation.turns_per_generation = 3;
+
+        // Act
+        for _ in 0..6 {
+            simulation.step();
+        }
+
+        // Assert: クラスタの合計は協力者数と一致する
+        let history = simulation.get_spatial_history();
+        assert_eq!(history.len(), 2);
+        for metrics in history {
+            let clustered: usize = metrics
+                .cluster_size_distribution
+                .iter()
+                .map(|(size, count)| size * count)
+                .sum();
+            assert_eq!(clustered, metrics.cooperator_count);
+            assert!(metrics.morans_i.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_history_capacity_keeps_latest_generations() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(6)
+            .with_history_capacity(3);
+        let mut simulation = SimulationService::with_config(10, 10, 30, config).unwrap();
+        simulation.turns_per_generation = 1;
+
+        // Act
+        simulation.run_generations(5);
+
+        // Assert: 古い 2 世代は捨てられ、範囲指定は残っている世代だけを返す
+        let generations: Vec<u32> = simulation
+            .get_generation_history()
+            .iter()
+            .map(|stats| stats.generation)
+            .collect();
+        assert_eq!(generations, vec![2, 3, 4]);
+        assert_eq!(simulation.get_history(0, 3).len(), 2);
+        assert_eq!(
+            simulation
+                .get_history(4, 10)
+                .next()
+                .map(|stats| stats.generation),
+            Some(4)
+        );
+        assert_eq!(simulation.get_history(6, 1).len(), 0);
+    }
+
+    #[test]
+    fn test_migrants_carry_their_whole_genotype() {
+        // Arrange: 島 0 の移住者はタグ・内集団びいき・攻撃性・行動表を持つ
+        let mut simulation =
+            SimulationService::with_config(4, 1, 2, SimulationConfig::default().with_seed(1))
+                .unwrap();
+        let child = |x: usize| {
+            Agent::new(
+                Position::new(x, 0),
+                StrategyType::AllCooperate,
+                0.5,
+                MovementStrategy::Settler,
+            )
+        };
+        let genotype = Genotype::new(StrategyType::AllDefect, MovementStrategy::Seeker, 0.3)
+            .with_memory_genome(MemoryGenome::from_strategy(StrategyType::TitForTat, 1).unwrap())
+            .with_tag(3, 0.7)
+            .with_aggression(0.9);
+        let migrant = genotype.spawn(Position::new(0, 0));
+        let mut islands = vec![vec![child(0)], vec![child(2)]];
+
+        // Act
+        simulation.exchange_migrants(&mut islands, vec![vec![migrant.clone()], Vec::new()]);
+
+        // Assert: 島 1 の子が移住者の遺伝子型で置き換わり、位置は子のもの
+        let immigrant = &islands[1][0];
+        assert_eq!(Genotype::of(immigrant), genotype);
+        assert_eq!(immigrant.position, Position::new(2, 0));
+        assert_ne!(immigrant.id, migrant.id);
+        assert_eq!(islands[0][0].strategy, StrategyType::AllCooperate);
+    }
+
+    #[test]
+    fn test_reset_only_resets_custom_strategies_of_its_own_agents() {
+        // Arrange: 記録した回数を数える戦略を、片方のシミュレーションだけが使う
+        use crate::domain::agent::{DecisionContext, DecisionStrategy};
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        struct CountingForResetTest(AtomicU32);
+        impl DecisionStrategy for CountingForResetTest {
+            fn name(&self) -> &str {
+                "CountingForResetTest"
+            }
+            fn decide(&self, _context: &DecisionContext) -> Action {
+                Action::Cooperate
+            }
+            fn record(&self, _: &DecisionContext, _: Action, _: Action, _: i32) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            fn reset(&self) {
+                self.0.store(0, Ordering::SeqCst);
+            }
+        }
+        let counting = Arc::new(CountingForResetTest(AtomicU32::new(0)));
+        let custom = StrategyRegistry::register(counting.clone()).unwrap();
+        let config = SimulationConfig::default().with_seed(4);
+        let mut user =
+            SimulationService::with_strategies(4, 4, &[custom; 8], config.clone()).unwrap();
+        let mut other = SimulationService::with_config(4, 4, 8, config).unwrap();
+        user.step();
+        let recorded = counting.0.load(Ordering::SeqCst);
+
+        // Act
+        other.reset(8).unwrap();
+        let after_other_reset = counting.0.load(Ordering::SeqCst);
+        user.reset(8).unwrap();
+
+        // Assert: 他のシミュレーションのリセットでは状態が消えない
+        assert!(recorded > 0);
+        assert_eq!(after_other_reset, recorded);
+        assert_eq!(counting.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_push_bounded_drops_oldest_records_first() {
+        // Arrange
+        let mut history = VecDeque::new();
+
+        // Act
+        for record in 0..5 {
+            push_bounded(&mut history, record, Some(3));
+        }
+        push_bounded(&mut history, 5, None);
+
+        // Assert
+        assert_eq!(history, VecDeque::from([2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_evolution_tracker_records_each_generation_within_its_size() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(6)
+            .with_evolution_history_size(4);
+        let mut simulation = SimulationService::with_config(10, 10, 30, config).unwrap();
+        simulation.turns_per_generation = 2;
+
+        // Act
+        simulation.run_generations(6);
+        let summary = simulation.get_evolution_summary();
+        let csv = simulation.export_metrics_csv();
+
+        // Assert: 世代交代の直前の集団を記録し、古い 2 世代は捨てられる
+        let records = simulation.get_evolution_records();
+        let generations: Vec<u32> = records.iter().map(|record| record.generation).collect();
+        assert_eq!(generations, vec![2, 3, 4, 5]);
+        let last = &records[3];
+        let stats = &simulation.get_generation_history()[5];
+        assert_eq!(last.population, stats.total_agents);
+        assert!((last.fitness.mean - stats.average_fitness).abs() < 1e-9);
+        assert!(last.fitness.min <= last.fitness.mean && last.fitness.mean <= last.fitness.max);
+        let timings = last.performance;
+        assert!(timings.battles_ms + timings.movement_ms <= timings.steps_ms);
+        assert!(
+            timings.selection_ms + timings.crossover_ms + timings.mutation_ms
+                <= timings.evolution_ms
+        );
+        assert_eq!(
+            timings.total_ms,
+            timings.steps_ms + timings.stats_ms + timings.evolution_ms
+        );
+        let report = simulation.get_performance_report();
+        assert_eq!(report.generations, 4);
+        assert_eq!(report.latest, timings);
+        assert!((report.average.total_ms * 4.0 - report.total.total_ms).abs() < 1e-9);
+        assert_eq!(summary.generations_recorded, 4);
+        assert_eq!(summary.last_generation, 5);
+        assert_eq!(csv.lines().count(), 5);
+        assert!(simulation.set_evolution_history_size(0).is_err());
+        simulation.set_evolution_history_size(1).unwrap();
+        assert_eq!(simulation.get_evolution_records()[0].generation, 5);
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn prop_world_invariants_hold_across_generations(
+            seed in proptest::prelude::any::<u64>(),
+            agent_count in 1usize..=64,
+            mutation_rate in 0.0f64..=1.0,
+        ) {
+            // Arrange
+            let config = SimulationConfig::default()
+                .with_seed(seed)
+                .with_mutation_rate(mutation_rate);
+            let mut simulation =
+                SimulationService::with_config(8, 8, agent_count, config).unwrap();
+            simulation.turns_per_generation = 3;
+
+            // Act
+            simulation.run_generations(3);
+
+            // Assert: 世代交代と移動の後も形質・占有・個体数の上限が崩れない
+            let violations = simulation.validate_world();
+            proptest::prop_assert!(
+                violations.is_empty(),
+                "{}",
+                InvariantViolation::join(&violations)
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_generation_reuses_history_buffers_and_reports_memory() {
+        // Arrange
+        let config = SimulationConfig::default().with_seed(8);
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+        simulation.turns_per_generation = 1;
+
+        // Act
+        simulation.step();
+        let usage = simulation.get_memory_usage();
+
+        // Assert: 子の履歴は空だが、親の対戦で確保したバッファを引き継いでいる
+        assert_eq!(simulation.get_generation(), 1);
+        let agents = simulation.grid.agents();
+        assert!(!agents.is_empty());
+        assert!(agents.values().all(|agent| agent.history.is_empty()));
+        assert!(agents
+            .values()
+            .any(|agent| agent.history.buffer_capacity() > 0));
+        // 概算には引き継いだ履歴のバッファの容量（記録の数ではない）と世代の統計が入る
+        let buffers: usize = agents
+            .values()
+            .map(|agent| agent.history.buffer_capacity() * std::mem::size_of::<GameRecord>())
+            .sum();
+        let statistics =
+            simulation.generation_history.capacity() * std::mem::size_of::<SimulationStatistics>();
+        assert!(usage.estimated_mb * 1024.0 * 1024.0 >= (buffers + statistics) as f64);
+        assert_eq!(usage.linear_memory_mb, None);
+        assert_eq!(usage.memory_usage_mb(), usage.estimated_mb);
+        let record = &simulation.get_evolution_records()[0];
+        assert!(record.memory_usage_mb > 0.0);
+        assert_eq!(
+            simulation.get_evolution_summary().peak_memory_usage_mb,
+            record.memory_usage_mb
+        );
+    }
+
+    #[test]
+    fn test_fermi_rule_spreads_fitter_strategy() {
+        // Arrange: 低温のフェルミ則では協力者の集団に囲まれた裏切りが有利
+        let spec = InitialPopulationSpec::new()
+            .with_archetype(Archetype::strategy(StrategyType::AllCooperate, 0.5))
+            .with_archetype(Archetype::strategy(StrategyType::AllDefect, 0.5));
+        let config = SimulationConfig::default()
+            .with_seed(21)
+            .with_mutation_rate(0.0)
+            .with_initial_population(spec)
+            .with_reproduction(ReproductionModel::SteadyState(SteadyStateConfig {
+                rule: UpdateRule::Fermi { temperature: 0.1 },
+                updates_per_step: 20,
+            }));
+        let mut simulation = SimulationService::with_config(10, 10, 80, config).unwrap();
+        let count = |simulation: &SimulationService| {
+            simulation
+                .get_statistics()
+                .strategy_counts
+                .get(&StrategyType::AllDefect)
+                .copied()
+                .unwrap_or(0)
+        };
+        let initial_defectors = count(&simulation);
+
+        // Act
+        for _ in 0..20 {
+            simulation.step();
+        }
+
+        // Assert
+        assert!(count(&simulation) > initial_defectors);
+    }
+
+    #[test]
+    fn test_add_and_remove_agent_mid_run_update_statistics() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(5)
+            .with_memory_genomes(1)
+            .with_reproduction(ReproductionModel::Energy(EnergyConfig::default()));
+        let mut simulation = SimulationService::with_config(10, 10, 20, config).unwrap();
+        simulation.step();
+        let population = simulation.get_agent_count();
+        let empty = simulation.grid.get_empty_positions()[0];
+        let occupied = simulation.get_agents()[0].position;
+        let invader = Genotype::new(StrategyType::AllDefect, MovementStrategy::Settler, 0.0);
+
+        // Act
+        let id = simulation.add_agent(empty, &invader).unwrap();
+        let duplicate = simulation.add_agent(occupied, &invader);
+        let outside = simulation.add_agent(Position::new(10, 0), &invader);
+
+        // Assert: 行動表とエネルギーは設定に合わせて与える
+        let agent = simulation.grid.get_agent(&id).unwrap();
+        assert_eq!(agent.strategy, StrategyType::AllDefect);
+        assert!(agent.memory_genome.is_some());
+        assert_eq!(agent.energy, EnergyConfig::default().initial_energy);
+        assert!(duplicate.is_err());
+        assert!(outside.is_err());
+        assert_eq!(simulation.get_statistics().total_agents, population + 1);
+
+        // Act
+        let removed = simulation.remove_agent(&id);
+
+        // Assert
+        assert_eq!(removed.map(|agent| agent.id), Some(id));
+        assert!(simulation.remove_agent(&id).is_none());
+        assert_eq!(simulation.get_statistics().total_agents, population);
+        assert!(simulation.grid.is_position_free(&empty));
+    }
+
+    #[test]
+    fn test_update_agent_genotype_keeps_score_and_position() {
+        // Arrange
+        let mut simulation =
+            SimulationService::with_config(8, 8, 10, SimulationConfig::default().with_seed(9))
+                .unwrap();
+        simulation.step();
+        let before = simulation.get_agents()[0].clone();
+        let genotype = Genotype::of(&before)
+            .with_strategy(StrategyType::Pavlov)
+            .with_movement(MovementStrategy::Explorer, 0.9)
+            .unwrap();
+
+        // Act
+        simulation
+            .update_agent_genotype(&before.id, genotype.clone())
+            .unwrap();
+        let missing = simulation.update_agent_genotype(&Uuid::nil(), genotype.clone());
+
+        // Assert
+        let after = simulation.get_agent(&before.id).unwrap();
+        assert_eq!(Genotype::of(after), genotype);
+        assert_eq!(after.score, before.score);
+        assert_eq!(after.position, before.position);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_battle_history_retention_caps_records_and_counts_evictions() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(6)
+            .with_battle_history_retention(BattleHistoryRetention::LastSteps(1));
+        let mut simulation = SimulationService::with_config(10, 10, 60, config).unwrap();
+        simulation.set_turns_per_generation(100);
+
+        // Act
+        for _ in 0..3 {
+            simulation.step();
+        }
+        let recent = simulation.get_battle_history_usage();
+        simulation
+            .set_battle_history_retention(BattleHistoryRetention::Total(120))
+            .unwrap();
+        let capped = simulation.get_battle_history_usage();
+
+        // Assert: 直近 1 ステップ分（1 体あたり近傍の数まで）しか残らない
+        assert!(recent.evicted_records > 0);
+        assert!(simulation
+            .get_agents()
+            .iter()
+            .all(|agent| agent.history.len() <= 8));
+        assert!(capped.retained_records <= 120);
+        assert_eq!(
+            capped.evicted_records - recent.evicted_records,
+            (recent.retained_records - capped.retained_records) as u64
+        );
+        assert!(simulation
+            .set_battle_history_retention(BattleHistoryRetention::PerAgent(0))
+            .is_err());
+        assert_eq!(
+            simulation.get_config().battle_history_retention,
+            BattleHistoryRetention::Total(120)
+        );
+    }
+
+    #[test]
+    fn test_agent_tracking_records_top_scorers_each_generation() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(8)
+            .with_agent_tracking(TrackingTarget::TopK(2));
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+        simulation.set_turns_per_generation(5);
+
+        // Act
+        simulation.run_generations(3);
+        let tracker = simulation.get_agent_tracker().unwrap();
+        let points: usize = tracker.trajectories().values().map(Vec::len).sum();
+        let (id, trajectory) = tracker.trajectories().iter().next().unwrap();
+
+        // Assert: 世代ごとに 2 体ずつ記録する
+        assert_eq!(points, 6);
+        assert_eq!(
+            simulation.get_agent_trajectory(id),
+            Some(trajectory.as_slice())
+        );
+        simulation.reset(40).unwrap();
+        assert!(simulation
+            .get_agent_tracker()
+            .unwrap()
+            .trajectories()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle_max_age_removes_agents_and_counts_deaths() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(9)
+            .with_lifecycle(LifecycleConfig::default().with_max_age(4));
+        let mut simulation = SimulationService::with_config(10, 10, 40, config).unwrap();
+        simulation.set_turns_per_generation(100);
+
+        // Act
+        for _ in 0..3 {
+            simulation.step();
+        }
+        let before = simulation.get_statistics();
+        simulation.step();
+        let after = simulation.get_statistics();
+
+        // Assert: 4 ステップ目の加齢で全員が寿命に達する
+        assert_eq!(before.total_agents, 40);
+        assert_eq!(before.deaths, 0);
+        assert_eq!(after.total_agents, 0);
+        assert_eq!(after.deaths, 40);
+        assert!(simulation
+            .set_lifecycle(Some(LifecycleConfig::default().with_max_age(0)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_extinction_policy_reseeds_or_stops_and_records_event() {
+        // Arrange: 2 ステップ目の加齢で全員が死ぬ
+        let config = |policy| {
+            SimulationConfig::default()
+                .with_seed(10)
+                .with_lifecycle(LifecycleConfig::default().with_max_age(2))
+                .with_extinction_policy(policy)
+        };
+        let mut reseeded = SimulationService::with_config(
+            10,
+            10,
+            30,
+            config(ExtinctionPolicy::ReseedFromElite(5)),
+        )
+        .unwrap();
+        let mut stopped =
+            SimulationService::with_config(10, 10, 30, config(ExtinctionPolicy::Stop)).unwrap();
+
+        // Act: 全滅直前のスコア上位 5 体を控えておく
+        reseeded.step();
+        stopped.step();
+        let elite: Vec<StrategyType> =
+            EvolutionService::fittest(reseeded.grid.agents(), 5, &FitnessFunction::RawScore)
+                .iter()
+                .map(|agent| agent.strategy)
+                .collect();
+        reseeded.step();
+        stopped.step();
+        stopped.run_generations(1);
+
+        // Assert
+        let events = reseeded.get_extinction_events();
+        assert_eq!(reseeded.get_agent_count(), 5);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].turn, 2);
+        assert_eq!(events[0].reseeded, 5);
+        assert_eq!(reseeded.to_result().extinction_events, events);
+        assert!(reseeded
+            .get_agents()
+            .iter()
+            .all(|agent| elite.contains(&agent.strategy)));
+        assert!(stopped.is_stopped());
+        assert_eq!(stopped.get_turn(), 2);
+        assert_eq!(
+            stopped.get_extinction_events()[0].action,
+            ExtinctionPolicy::Stop
+        );
+        stopped.reset(30).unwrap();
+        assert!(!stopped.is_stopped());
+    }
+
+    #[test]
+    fn test_get_neighbors_of_reports_rounds_with_focal_agent() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(4)
+            .with_mutation_rate(0.0);
+        let mut simulation = SimulationService::with_config(6, 6, 30, config).unwrap();
+        // 移動させずに対戦だけを 1 回行う
+        simulation.process_games();
+        let focal = simulation.agents_in_position_order()[0].clone();
+
+        // Act
+        let adjacent = simulation.get_neighbors_of(&focal.position, 0).unwrap();
+        let wide = simulation.get_neighbors_of(&focal.position, 2).unwrap();
+        let outside = simulation.get_neighbors_of(&Position::new(6, 0), 1);
+
+        // Assert: 隣接する相手とは 1 回ずつ対戦している
+        assert!(!adjacent.is_empty());
+        for neighbor in &adjacent {
+            assert_eq!(neighbor.rounds.len(), 1);
+            assert!(neighbor.last_payoff.is_some());
+        }
+        assert!(wide.len() >= adjacent.len());
+        assert!(wide.iter().all(|neighbor| neighbor.id != focal.id));
+        assert!(outside.is_err());
     }
 }
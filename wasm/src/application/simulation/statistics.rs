@@ -1,7 +1,87 @@
+use super::MetricsCalculator;
 use crate::domain::agent::{Agent, StrategyType};
+use crate::domain::grid::Grid;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// 島（デーム）ごとの集計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IslandStatistics {
+    pub island: usize,
+    pub total_agents: usize,
+    pub average_cooperation_rate: f64,
+    pub average_score: f64,
+}
+
+impl IslandStatistics {
+    // デームが設定されていなければ空
+    pub fn calculate(grid: &Grid) -> Vec<Self> {
+        let Some(demes) = grid.demes() else {
+            return Vec::new();
+        };
+        let mut islands: Vec<Self> = (0..demes.deme_count())
+            .map(|island| Self {
+                island,
+                total_agents: 0,
+                average_cooperation_rate: 0.0,
+                average_score: 0.0,
+            })
+            .collect();
+
+        for agent in grid.agents_in_position_order() {
+            if let Some(island) = grid
+                .deme_of(&agent.position)
+                .and_then(|index| islands.get_mut(index))
+            {
+                island.total_agents += 1;
+                island.average_cooperation_rate += agent.cooperation_rate();
+                island.average_score += agent.score as f64;
+            }
+        }
+        for island in islands.iter_mut().filter(|island| island.total_agents > 0) {
+            island.average_cooperation_rate /= island.total_agents as f64;
+            island.average_score /= island.total_agents as f64;
+        }
+        islands
+    }
+}
+
+// タグごとの集計（タグを設定したときだけ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagStatistics {
+    pub tag: u8,
+    pub total_agents: usize,
+    pub average_cooperation_rate: f64,
+    pub average_in_group_bias: f64,
+}
+
+impl TagStatistics {
+    // タグの番号順。個体のいないタグも 0 件として並べる
+    pub fn calculate(grid: &Grid, tag_count: u8) -> Vec<Self> {
+        let mut tags: Vec<Self> = (0..tag_count)
+            .map(|tag| Self {
+                tag,
+                total_agents: 0,
+                average_cooperation_rate: 0.0,
+                average_in_group_bias: 0.0,
+            })
+            .collect();
+
+        for agent in grid.agents_in_position_order() {
+            if let Some(tag) = tags.get_mut(agent.tag as usize) {
+                tag.total_agents += 1;
+                tag.average_cooperation_rate += agent.cooperation_rate();
+                tag.average_in_group_bias += agent.in_group_bias;
+            }
+        }
+        for tag in tags.iter_mut().filter(|tag| tag.total_agents > 0) {
+            tag.average_cooperation_rate /= tag.total_agents as f64;
+            tag.average_in_group_bias /= tag.total_agents as f64;
+        }
+        tags
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationStatistics {
     pub generation: u32,
@@ -11,6 +91,36 @@ pub struct SimulationStatistics {
     pub average_cooperation_rate: f64,
     pub average_mobility: f64,
     pub average_score: f64,
+    // 設定の適応度（SimulationConfig::fitness）の平均。スコアそのものなら average_score と同じ
+    #[serde(default)]
+    pub average_fitness: f64,
+    // この世代を生んだ実効の突然変異率
+    #[serde(default)]
+    pub mutation_rate: f64,
+    // デーム（島）を設定したときだけ、島の番号順に並ぶ
+    #[serde(default)]
+    pub island_statistics: Vec<IslandStatistics>,
+    // タグを設定したときだけ、タグの番号順に並ぶ
+    #[serde(default)]
+    pub tag_statistics: Vec<TagStatistics>,
+    // 前の世代の区切りからの出生数・死亡数（世代交代型では世代交代で入れ替わった数を含む）
+    #[serde(default)]
+    pub births: usize,
+    #[serde(default)]
+    pub deaths: usize,
+    // 前の世代の区切りからの対戦後の罰の回数と、裏切られた回数に対する割合（罰を設定したときだけ）
+    #[serde(default)]
+    pub punishments: usize,
+    #[serde(default)]
+    pub punishment_frequency: f64,
+    // 適応度共有を設定したときだけ、直前の世代交代で親の世代を共有半径で数えたニッチの数
+    #[serde(default)]
+    pub niche_count: usize,
+    // 戦略の個体数のシャノンエントロピー（ナット）とジニ・シンプソン指数（1 - Σp²）
+    #[serde(default)]
+    pub shannon_index: f64,
+    #[serde(default)]
+    pub simpson_index: f64,
 }
 
 impl Default for SimulationStatistics {
@@ -29,6 +139,17 @@ impl SimulationStatistics {
             average_cooperation_rate: 0.0,
             average_mobility: 0.0,
             average_score: 0.0,
+            average_fitness: 0.0,
+            mutation_rate: 0.0,
+            island_statistics: Vec::new(),
+            tag_statistics: Vec::new(),
+            births: 0,
+            deaths: 0,
+            punishments: 0,
+            punishment_frequency: 0.0,
+            niche_count: 0,
+            shannon_index: 0.0,
+            simpson_index: 0.0,
         }
     }
 
@@ -45,7 +166,11 @@ impl SimulationStatistics {
         let mut total_mobility = 0.0;
         let mut total_score = 0.0;
 
-        for agent in agents.values() {
+        // 浮動小数点の合計が走査順で揺れないよう位置順に集計する
+        let mut ordered: Vec<&Agent> = agents.values().collect();
+        ordered.sort_by_key(|agent| (agent.position.y, agent.position.x));
+
+        for agent in ordered {
             *strategy_counts.entry(agent.strategy).or_insert(0) += 1;
             *movement_strategy_counts
                 .entry(agent.movement_strategy.to_string())
@@ -58,11 +183,22 @@ impl SimulationStatistics {
         Self {
             generation,
             total_agents,
-            strategy_counts,
             movement_strategy_counts,
             average_cooperation_rate: total_cooperation_rate / total_agents as f64,
             average_mobility: total_mobility / total_agents as f64,
             average_score: total_score / total_agents as f64,
+            average_fitness: total_score / total_agents as f64,
+            mutation_rate: 0.0,
+            island_statistics: Vec::new(),
+            tag_statistics: Vec::new(),
+            births: 0,
+            deaths: 0,
+            punishments: 0,
+            punishment_frequency: 0.0,
+            niche_count: 0,
+            shannon_index: MetricsCalculator::shannon_entropy(strategy_counts.values().copied()),
+            simpson_index: MetricsCalculator::simpson_index(strategy_counts.values().copied()),
+            strategy_counts,
         }
     }
 
@@ -75,6 +211,30 @@ impl SimulationStatistics {
         }
     }
 
+    // 戦略 ID 順の個体数。組み込み戦略は 0 体でも含め、利用者定義の戦略はいるものだけ並べる
+    pub fn strategy_census(&self) -> Vec<(StrategyType, usize)> {
+        let mut census: Vec<(StrategyType, usize)> = StrategyType::ALL
+            .iter()
+            .map(|strategy| (*strategy, *self.strategy_counts.get(strategy).unwrap_or(&0)))
+            .collect();
+        census.extend(
+            self.strategy_counts
+                .iter()
+                .filter(|(strategy, _)| matches!(strategy, StrategyType::Custom(_)))
+                .map(|(strategy, count)| (*strategy, *count)),
+        );
+        census.sort_by_key(|(strategy, _)| strategy.id());
+        census
+    }
+
+    // 戦略分布のシャノンエントロピーを組み込み戦略数の対数で割った値（0〜1）
+    pub fn strategy_diversity(&self) -> f64 {
+        MetricsCalculator::normalized_entropy(
+            self.strategy_counts.values().copied(),
+            StrategyType::ALL.len(),
+        )
+    }
+
     pub fn get_movement_strategy_percentage(&self, movement_strategy: &str) -> f64 {
         if self.total_agents == 0 {
             0.0
@@ -86,4 +246,56 @@ impl SimulationStatistics {
             *count as f64 / self.total_agents as f64 * 100.0
         }
     }
+
+    // 戦略別・移動戦略別の個体数と島・タグごとの集計が確保しているヒープ領域（HashMap は 1 枠 1 バイトの制御領域込み）
+    pub fn heap_bytes(&self) -> usize {
+        self.strategy_counts.capacity() * (std::mem::size_of::<(StrategyType, usize)>() + 1)
+            + self.movement_strategy_counts.capacity()
+                * (std::mem::size_of::<(String, usize)>() + 1)
+            + self
+                .movement_strategy_counts
+                .keys()
+                .map(String::capacity)
+                .sum::<usize>()
+            + self.island_statistics.capacity() * std::mem::size_of::<IslandStatistics>()
+            + self.tag_statistics.capacity() * std::mem::size_of::<TagStatistics>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position};
+
+    #[test]
+    fn test_calculate_reports_shannon_and_simpson_indices() {
+        // Arrange: TFT 2 体と AllD 2 体
+        let agents: HashMap<uuid::Uuid, Agent> = [
+            StrategyType::TitForTat,
+            StrategyType::TitForTat,
+            StrategyType::AllDefect,
+            StrategyType::AllDefect,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(x, strategy)| {
+            let agent = Agent::new(
+                Position::new(x, 0),
+                strategy,
+                0.5,
+                MovementStrategy::Settler,
+            );
+            (agent.id, agent)
+        })
+        .collect();
+
+        // Act
+        let stats = SimulationStatistics::calculate(&agents, 0);
+
+        // Assert: 半々なら ln 2 と 1/2、誰もいなければ 0
+        assert!((stats.shannon_index - 2.0f64.ln()).abs() < 1e-12);
+        assert!((stats.simpson_index - 0.5).abs() < 1e-12);
+        let empty = SimulationStatistics::calculate(&HashMap::new(), 0);
+        assert_eq!((empty.shannon_index, empty.simpson_index), (0.0, 0.0));
+    }
 }
@@ -0,0 +1,298 @@
+use super::SimulationConfig;
+use crate::application::evolution::EvolutionService;
+use crate::domain::agent::Agent;
+use crate::domain::grid::Grid;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 定常状態（非同期）更新で 1 体ずつ戦略を入れ替える規則
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum UpdateRule {
+    // 選ばれたエージェントがランダムな近傍 1 体と比べ、相手の利得が高ければ戦略を真似る
+    #[default]
+    Imitation,
+    // 選ばれたエージェントが死に、近傍が利得に比例した確率で子を産んで空いたセルを埋める
+    DeathBirth,
+    // フェルミ則（ペア比較）：ランダムな近傍 j の戦略を確率 1 / (1 + exp(-(f_j - f_i) / K)) で採用する
+    // K（温度）が小さいほど利得差に敏感になり、0 に近づくと Imitation と同じになる
+    Fermi {
+        temperature: f64,
+    },
+}
+
+impl UpdateRule {
+    pub const DEFAULT_FERMI_TEMPERATURE: f64 = 0.5;
+
+    // "imitation" / "death_birth" / "fermi" / "fermi:K"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "imitation" => Some(UpdateRule::Imitation),
+            "death_birth" => Some(UpdateRule::DeathBirth),
+            "fermi" => Some(UpdateRule::Fermi {
+                temperature: Self::DEFAULT_FERMI_TEMPERATURE,
+            }),
+            _ => name
+                .strip_prefix("fermi:")
+                .and_then(|temperature| temperature.parse::<f64>().ok())
+                .filter(|temperature| temperature.is_finite() && *temperature > 0.0)
+                .map(|temperature| UpdateRule::Fermi { temperature }),
+        }
+    }
+
+    // 近傍 j の戦略を採用する確率（f_i は自分、f_j は近傍の利得）
+    pub fn adoption_probability(&self, own_payoff: i32, neighbor_payoff: i32) -> f64 {
+        let difference = (neighbor_payoff - own_payoff) as f64;
+        match *self {
+            UpdateRule::Fermi { temperature } if temperature > 0.0 => {
+                1.0 / (1.0 + (-difference / temperature).exp())
+            }
+            _ => {
+                if difference > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SteadyStateConfig {
+    pub rule: UpdateRule,
+    // 1 ステップに更新するエージェント数
+    pub updates_per_step: usize,
+}
+
+impl Default for SteadyStateConfig {
+    fn default() -> Self {
+        Self {
+            rule: UpdateRule::Imitation,
+            updates_per_step: 1,
+        }
+    }
+}
+
+pub struct SteadyStateUpdate;
+
+impl SteadyStateUpdate {
+    // payoffs はこのステップで各エージェントが得た利得。戦略が変わった回数を返す
+    pub fn apply<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        config: &SteadyStateConfig,
+        payoffs: &HashMap<Uuid, i32>,
+        mutation_rate: f64,
+        simulation: &SimulationConfig,
+        rng: &mut R,
+    ) -> usize {
+        let mut changes = 0;
+        for _ in 0..config.updates_per_step {
+            let ids: Vec<Uuid> = grid
+                .agents_in_position_order()
+                .into_iter()
+                .map(|agent| agent.id)
+                .collect();
+            if ids.is_empty() {
+                break;
+            }
+            let focal = ids[rng.gen_range(0..ids.len())];
+
+            let changed = match config.rule {
+                UpdateRule::Imitation | UpdateRule::Fermi { .. } => {
+                    Self::imitate(grid, focal, &config.rule, payoffs, rng)
+                }
+                UpdateRule::DeathBirth => {
+                    Self::death_birth(grid, focal, payoffs, mutation_rate, simulation, rng)
+                }
+            };
+            if changed {
+                changes += 1;
+            }
+        }
+        changes
+    }
+
+    fn imitate<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        focal: Uuid,
+        rule: &UpdateRule,
+        payoffs: &HashMap<Uuid, i32>,
+        rng: &mut R,
+    ) -> bool {
+        let Some(agent) = grid.get_agent(&focal) else {
+            return false;
+        };
+        let neighbors = grid.get_neighbors(&agent.position);
+        if neighbors.is_empty() {
+            return false;
+        }
+        let model = neighbors[rng.gen_range(0..neighbors.len())];
+        let payoff = |agent: &Agent| payoffs.get(&agent.id).copied().unwrap_or(0);
+        let probability = rule.adoption_probability(payoff(agent), payoff(model));
+        if probability <= 0.0 || (probability < 1.0 && !rng.gen_bool(probability)) {
+            return false;
+        }
+
+        let (strategy, memory_genome) = (model.strategy, model.memory_genome.clone());
+        let Some(agent) = grid.get_agent_mut(&focal) else {
+            return false;
+        };
+        let changed = agent.strategy != strategy || agent.memory_genome != memory_genome;
+        agent.strategy = strategy;
+        agent.memory_genome = memory_genome;
+        changed
+    }
+
+    fn death_birth<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        focal: Uuid,
+        payoffs: &HashMap<Uuid, i32>,
+        mutation_rate: f64,
+        simulation: &SimulationConfig,
+        rng: &mut R,
+    ) -> bool {
+        let Some(agent) = grid.get_agent(&focal) else {
+            return false;
+        };
+        let position = agent.position;
+        let previous_strategy = agent.strategy;
+        let neighbors = grid.get_neighbors(&position);
+        if neighbors.is_empty() {
+            return false;
+        }
+
+        // ルーレット選択と同じく最小の利得を 1 にずらした重み
+        let payoff = |agent: &Agent| payoffs.get(&agent.id).copied().unwrap_or(0);
+        let min_payoff = neighbors
+            .iter()
+            .map(|agent| payoff(agent))
+            .min()
+            .unwrap_or(0);
+        let weights: Vec<i64> = neighbors
+            .iter()
+            .map(|agent| (payoff(agent) - min_payoff) as i64 + 1)
+            .collect();
+        let mut pick = rng.gen_range(0..weights.iter().sum::<i64>());
+        let parent = neighbors
+            .iter()
+            .zip(&weights)
+            .find(|(_, &weight)| {
+                pick -= weight;
+                pick < 0
+            })
+            .map(|(agent, _)| *agent)
+            .unwrap_or(neighbors[0]);
+
+        let child =
+            EvolutionService::breed(parent, parent, position, mutation_rate, simulation, rng);
+        let changed = child.strategy != previous_strategy;
+
+        grid.remove_agent(&focal);
+        grid.add_agent(child).is_ok() && changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    // 中央に裏切り 1 体、その左右に協力 2 体
+    fn line_grid() -> (Grid, HashMap<Uuid, i32>, Uuid) {
+        let mut grid = Grid::new(3, 1);
+        let mut payoffs = HashMap::new();
+        let mut center = Uuid::nil();
+        for (x, strategy, payoff) in [
+            (0, StrategyType::AllCooperate, 10),
+            (1, StrategyType::AllDefect, 2),
+            (2, StrategyType::AllCooperate, 10),
+        ] {
+            let agent = Agent::new(
+                Position::new(x, 0),
+                strategy,
+                0.0,
+                MovementStrategy::Settler,
+            );
+            payoffs.insert(agent.id, payoff);
+            if x == 1 {
+                center = agent.id;
+            }
+            grid.add_agent(agent).unwrap();
+        }
+        (grid, payoffs, center)
+    }
+
+    #[test]
+    fn test_imitation_copies_fitter_neighbor() {
+        // Arrange
+        let (mut grid, payoffs, center) = line_grid();
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let edge = grid.get_agent_at_position(&Position::new(0, 0)).unwrap().id;
+
+        // Act
+        let center_changed = SteadyStateUpdate::imitate(
+            &mut grid,
+            center,
+            &UpdateRule::Imitation,
+            &payoffs,
+            &mut rng,
+        );
+        let edge_changed =
+            SteadyStateUpdate::imitate(&mut grid, edge, &UpdateRule::Imitation, &payoffs, &mut rng);
+
+        // Assert: 利得の低い中央だけが近傍を真似る
+        assert!(center_changed);
+        assert!(!edge_changed);
+        assert_eq!(
+            grid.get_agent(&center).unwrap().strategy,
+            StrategyType::AllCooperate
+        );
+    }
+
+    #[test]
+    fn test_death_birth_replaces_focal_with_neighbor_offspring() {
+        // Arrange
+        let (mut grid, payoffs, center) = line_grid();
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+
+        // Act
+        let changed = SteadyStateUpdate::death_birth(
+            &mut grid,
+            center,
+            &payoffs,
+            0.0,
+            &SimulationConfig::default(),
+            &mut rng,
+        );
+
+        // Assert
+        assert!(changed);
+        assert!(grid.get_agent(&center).is_none());
+        let newborn = grid.get_agent_at_position(&Position::new(1, 0)).unwrap();
+        assert_eq!(newborn.strategy, StrategyType::AllCooperate);
+        assert_eq!(grid.agent_count(), 3);
+    }
+
+    #[test]
+    fn test_fermi_probability_depends_on_payoff_difference_and_temperature() {
+        // Arrange
+        let cold = UpdateRule::Fermi { temperature: 0.1 };
+        let hot = UpdateRule::Fermi { temperature: 100.0 };
+
+        // Act & Assert: 利得が等しければ 1/2、温度が高いほどランダムに近づく
+        assert!((cold.adoption_probability(3, 3) - 0.5).abs() < 1e-12);
+        assert!(cold.adoption_probability(0, 5) > 0.999);
+        assert!(cold.adoption_probability(5, 0) < 0.001);
+        assert!((hot.adoption_probability(5, 0) - 0.5).abs() < 0.02);
+        assert_eq!(
+            UpdateRule::from_name("fermi:0.2"),
+            Some(UpdateRule::Fermi { temperature: 0.2 })
+        );
+        assert_eq!(UpdateRule::from_name("fermi:0"), None);
+    }
+}
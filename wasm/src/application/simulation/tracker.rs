@@ -0,0 +1,171 @@
+use crate::domain::agent::{Agent, Position, StrategyType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+// 追跡する個体の選び方
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TrackingTarget {
+    Ids(Vec<Uuid>),
+    // 世代ごとにスコア上位 K 体（同点は ID 順）
+    TopK(usize),
+}
+
+impl TrackingTarget {
+    // "top:K" または "ids:ID,ID,..."
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected top:K or ids:ID,ID,..., got {spec}");
+        match spec.split_once(':').ok_or_else(invalid)? {
+            ("top", k) => k.parse().map(TrackingTarget::TopK).map_err(|_| invalid()),
+            ("ids", ids) => ids
+                .split(',')
+                .map(|id| Uuid::parse_str(id.trim()).map_err(|e| format!("Invalid agent id: {e}")))
+                .collect::<Result<_, _>>()
+                .map(TrackingTarget::Ids),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn select<'a>(&self, agents: &'a HashMap<Uuid, Agent>) -> Vec<&'a Agent> {
+        match self {
+            TrackingTarget::Ids(ids) => ids.iter().filter_map(|id| agents.get(id)).collect(),
+            TrackingTarget::TopK(k) => {
+                let mut ranked: Vec<&Agent> = agents.values().collect();
+                ranked.sort_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)));
+                ranked.truncate(*k);
+                ranked
+            }
+        }
+    }
+}
+
+// 1 世代分の個体の状態（世代交代の直前に記録する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub generation: u32,
+    pub position: Position,
+    pub strategy: StrategyType,
+    pub score: i32,
+    pub energy: f64,
+    pub cooperation_rate: f64,
+}
+
+impl TrajectoryPoint {
+    pub fn of(agent: &Agent, generation: u32) -> Self {
+        Self {
+            generation,
+            position: agent.position,
+            strategy: agent.strategy,
+            score: agent.score,
+            energy: agent.energy,
+            cooperation_rate: agent.cooperation_rate(),
+        }
+    }
+}
+
+// 選んだ個体の状態を世代ごとに記録する（個体 ID 順に保持）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentTracker {
+    target: TrackingTarget,
+    trajectories: BTreeMap<Uuid, Vec<TrajectoryPoint>>,
+}
+
+impl AgentTracker {
+    pub fn new(target: TrackingTarget) -> Self {
+        Self {
+            target,
+            trajectories: BTreeMap::new(),
+        }
+    }
+
+    pub fn target(&self) -> &TrackingTarget {
+        &self.target
+    }
+
+    // capacity を指定すると各個体の軌跡を直近 capacity 世代に抑える
+    pub fn record(
+        &mut self,
+        agents: &HashMap<Uuid, Agent>,
+        generation: u32,
+        capacity: Option<usize>,
+    ) {
+        for agent in self.target.select(agents) {
+            let trajectory = self.trajectories.entry(agent.id).or_default();
+            trajectory.push(TrajectoryPoint::of(agent, generation));
+            if let Some(capacity) = capacity {
+                let excess = trajectory.len().saturating_sub(capacity);
+                trajectory.drain(..excess);
+            }
+        }
+    }
+
+    pub fn trajectory(&self, id: &Uuid) -> Option<&[TrajectoryPoint]> {
+        self.trajectories.get(id).map(Vec::as_slice)
+    }
+
+    pub fn trajectories(&self) -> &BTreeMap<Uuid, Vec<TrajectoryPoint>> {
+        &self.trajectories
+    }
+
+    pub fn clear(&mut self) {
+        self.trajectories.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::MovementStrategy;
+
+    fn agents_with_scores(scores: &[i32]) -> HashMap<Uuid, Agent> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(x, &score)| {
+                let mut agent = Agent::new(
+                    Position::new(x, 0),
+                    StrategyType::TitForTat,
+                    0.0,
+                    MovementStrategy::Settler,
+                );
+                agent.score = score;
+                (agent.id, agent)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_top_k_tracks_highest_scorers_each_generation() {
+        // Arrange
+        let mut agents = agents_with_scores(&[5, 9, 1]);
+        let best = agents.values().find(|agent| agent.score == 9).unwrap().id;
+        let mut tracker = AgentTracker::new(TrackingTarget::TopK(1));
+
+        // Act
+        tracker.record(&agents, 0, Some(1));
+        agents.get_mut(&best).unwrap().score = 12;
+        tracker.record(&agents, 1, Some(1));
+
+        // Assert: 容量 1 なので最新の世代だけが残る
+        let trajectory = tracker.trajectory(&best).unwrap();
+        assert_eq!(tracker.trajectories().len(), 1);
+        assert_eq!(trajectory.len(), 1);
+        assert_eq!(trajectory[0].generation, 1);
+        assert_eq!(trajectory[0].score, 12);
+    }
+
+    #[test]
+    fn test_parse_reads_ids_and_top_k() {
+        // Arrange
+        let id = Uuid::from_u128(7);
+
+        // Act & Assert
+        assert_eq!(TrackingTarget::parse("top:3"), Ok(TrackingTarget::TopK(3)));
+        assert_eq!(
+            TrackingTarget::parse(&format!("ids:{id}")),
+            Ok(TrackingTarget::Ids(vec![id]))
+        );
+        assert!(TrackingTarget::parse("ids:nope").is_err());
+        assert!(TrackingTarget::parse("all").is_err());
+    }
+}
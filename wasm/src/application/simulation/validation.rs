@@ -0,0 +1,557 @@
+use super::{
+    BattleHistoryRetention, MortalityCurve, ReproductionModel, SimulationConfig, TrackingTarget,
+    UpdateRule,
+};
+use crate::application::evolution::{AdaptiveMutation, SelectionMethod};
+use crate::domain::agent::MemoryGenome;
+use crate::domain::game::GameType;
+use crate::domain::grid::{
+    MigrationModel, MovementPolicyKind, NeighborhoodType, PlacementPattern, ResourceConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+// 設定の問題 1 件（field は SimulationConfig のフィールド名、UI でフォームの欄に対応づける）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigViolation {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    // 全件を 1 つのエラーメッセージにまとめる
+    pub fn join(violations: &[ConfigViolation]) -> String {
+        violations
+            .iter()
+            .map(ConfigViolation::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl fmt::Display for ConfigViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn unit_interval(violations: &mut Vec<ConfigViolation>, field: &str, value: f64) {
+    if !(0.0..=1.0).contains(&value) {
+        violations.push(ConfigViolation::new(
+            field,
+            format!("must be between 0 and 1, got {value}"),
+        ));
+    }
+}
+
+fn positive_boost(violations: &mut Vec<ConfigViolation>, boost: f64) {
+    if boost <= 0.0 || !boost.is_finite() {
+        violations.push(ConfigViolation::new(
+            "adaptive_mutation.boost",
+            format!("must be a positive factor, got {boost}"),
+        ));
+    }
+}
+
+impl SimulationConfig {
+    // グリッドの大きさに依存しない検査。問題がなければ空
+    pub fn validate(&self) -> Vec<ConfigViolation> {
+        let mut violations = Vec::new();
+
+        unit_interval(
+            &mut violations,
+            "strategy_complexity_penalty_rate",
+            self.strategy_complexity_penalty_rate as f64,
+        );
+        unit_interval(&mut violations, "mutation_rate", self.mutation_rate);
+        unit_interval(&mut violations, "action_noise", self.action_noise);
+        unit_interval(
+            &mut violations,
+            "rewiring_probability",
+            self.rewiring_probability,
+        );
+        if self.rewiring_probability > 0.0 && self.network.is_none() {
+            violations.push(ConfigViolation::new(
+                "rewiring_probability",
+                "link rewiring requires a network",
+            ));
+        }
+
+        if let Some(tags) = &self.tags {
+            if tags.tag_count == 0 {
+                violations.push(ConfigViolation::new("tags.tag_count", "must be at least 1"));
+            }
+            unit_interval(&mut violations, "tags.mutation_rate", tags.mutation_rate);
+        }
+
+        if let Some(reputation) = &self.reputation {
+            unit_interval(
+                &mut violations,
+                "reputation.observation_probability",
+                reputation.observation_probability,
+            );
+            unit_interval(&mut violations, "reputation.decay", reputation.decay);
+        }
+
+        if let Some(punishment) = &self.punishment {
+            if punishment.cost < 0 || punishment.fine < 0 {
+                violations.push(ConfigViolation::new(
+                    "punishment",
+                    format!(
+                        "cost and fine must be non-negative, got {}:{}",
+                        punishment.cost, punishment.fine
+                    ),
+                ));
+            }
+        }
+
+        if let Some(demes) = &self.deme_structure {
+            if demes.columns == 0 || demes.rows == 0 {
+                violations.push(ConfigViolation::new(
+                    "deme_structure",
+                    "needs at least one column and one row",
+                ));
+            }
+            unit_interval(
+                &mut violations,
+                "deme_structure.migration_rate",
+                demes.migration_rate,
+            );
+            if demes.migration_interval > 0 && demes.migrants == 0 {
+                violations.push(ConfigViolation::new(
+                    "deme_structure.migrants",
+                    "must be at least 1 when migration_interval is set",
+                ));
+            }
+        }
+
+        if let Some(multi) = &self.multi_objective {
+            if multi.objectives.is_empty() {
+                violations.push(ConfigViolation::new(
+                    "multi_objective.objectives",
+                    "needs at least one objective",
+                ));
+            }
+        }
+
+        match self.adaptive_mutation {
+            Some(AdaptiveMutation::Diversity { threshold, boost }) => {
+                unit_interval(&mut violations, "adaptive_mutation.threshold", threshold);
+                positive_boost(&mut violations, boost);
+            }
+            Some(AdaptiveMutation::Stagnation { generations, boost }) => {
+                if generations == 0 {
+                    violations.push(ConfigViolation::new(
+                        "adaptive_mutation.generations",
+                        "must be at least 1",
+                    ));
+                }
+                positive_boost(&mut violations, boost);
+            }
+            None => {}
+        }
+
+        if let Some(fitness) = &self.weighted_fitness {
+            let weights = [
+                fitness.score_weight,
+                fitness.cooperation_weight,
+                fitness.aggression_penalty,
+                fitness.mobility_weight,
+            ];
+            if weights.iter().any(|weight| !weight.is_finite()) {
+                violations.push(ConfigViolation::new(
+                    "weighted_fitness",
+                    "weights must be finite",
+                ));
+            }
+        }
+
+        if let Some(fitness) = &self.fitness_function {
+            if let Err(message) = fitness.validate() {
+                violations.push(ConfigViolation::new("fitness_function", message));
+            }
+        }
+
+        if let Some(sharing) = &self.fitness_sharing {
+            if let Err(message) = sharing.validate() {
+                violations.push(ConfigViolation::new("fitness_sharing", message));
+            }
+            // Pareto ランクのトーナメントは適応度の値を使わないので、共有しても効かない
+            if self.multi_objective.is_some() {
+                violations.push(ConfigViolation::new(
+                    "fitness_sharing",
+                    "cannot be combined with multi_objective selection",
+                ));
+            }
+        }
+
+        if let Err(message) = self.crossover_method.validate() {
+            violations.push(ConfigViolation::new("crossover_method", message));
+        }
+
+        if let SelectionMethod::Boltzmann(schedule) = &self.selection {
+            if let Err(message) = schedule.validate() {
+                violations.push(ConfigViolation::new("selection", message));
+            }
+        }
+
+        if let Some(spec) = &self.initial_population {
+            if let Err(message) = spec.validate() {
+                violations.push(ConfigViolation::new("initial_population", message));
+            }
+        }
+
+        if let Some(memory) = self.memory_genome {
+            if !(1..=MemoryGenome::MAX_MEMORY).contains(&memory) {
+                violations.push(ConfigViolation::new(
+                    "memory_genome",
+                    format!(
+                        "memory must be between 1 and {}, got {memory}",
+                        MemoryGenome::MAX_MEMORY
+                    ),
+                ));
+            }
+        }
+
+        if let GameType::PublicGoods(game) = &self.game_type {
+            if !(game.multiplier.is_finite() && game.multiplier > 0.0) || game.contribution < 0.0 {
+                violations.push(ConfigViolation::new(
+                    "game_type",
+                    "public goods multiplier must be positive and contribution non-negative",
+                ));
+            }
+        }
+
+        if !self.allow_non_dilemma_payoffs {
+            if let Err(message) = self.payoff_matrix.validate_prisoners_dilemma() {
+                violations.push(ConfigViolation::new("payoff_matrix", message));
+            }
+        }
+
+        if let Some(zones) = &self.zones {
+            for zone in &zones.zones {
+                if let Some(rate) = zone.mutation_rate {
+                    unit_interval(&mut violations, "zones.mutation_rate", rate);
+                }
+                if let (false, Some(Err(message))) = (
+                    self.allow_non_dilemma_payoffs,
+                    zone.payoffs
+                        .map(|payoffs| payoffs.validate_prisoners_dilemma()),
+                ) {
+                    violations.push(ConfigViolation::new(
+                        "zones.payoffs",
+                        format!("zone {}: {message}", zone.name),
+                    ));
+                }
+            }
+        }
+
+        if let Some(schedule) = &self.payoff_schedule {
+            if let Err(message) = schedule.validate() {
+                violations.push(ConfigViolation::new("payoff_schedule", message));
+            }
+        }
+
+        if let Some(MovementPolicyKind::SuccessDriven { radius: 0 }) = self.movement_policy {
+            violations.push(ConfigViolation::new(
+                "movement_policy",
+                "success-driven radius must be at least 1",
+            ));
+        }
+
+        if self.migration_model == MigrationModel::SuccessDriven(0) {
+            violations.push(ConfigViolation::new(
+                "migration_model",
+                "success-driven migration range must be at least 1",
+            ));
+        }
+
+        if let Some(resources) = &self.resources {
+            if let Err(message) = ResourceConfig::new(
+                resources.capacity,
+                resources.regeneration_rate,
+                resources.consumption,
+            ) {
+                violations.push(ConfigViolation::new("resources", message));
+            }
+        }
+
+        match &self.reproduction {
+            ReproductionModel::Generational => {}
+            ReproductionModel::Energy(energy) => {
+                if let Err(message) = energy.validate() {
+                    violations.push(ConfigViolation::new("reproduction", message));
+                }
+            }
+            ReproductionModel::SteadyState(steady) => {
+                if steady.updates_per_step == 0 {
+                    violations.push(ConfigViolation::new(
+                        "reproduction.updates_per_step",
+                        "must be at least 1",
+                    ));
+                }
+                if let UpdateRule::Fermi { temperature } = steady.rule {
+                    if !(temperature.is_finite() && temperature > 0.0) {
+                        violations.push(ConfigViolation::new(
+                            "reproduction.rule",
+                            format!("Fermi temperature must be positive, got {temperature}"),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if self.histogram_bins == Some(0) {
+            violations.push(ConfigViolation::new("histogram_bins", "must be at least 1"));
+        }
+        if self.history_capacity == Some(0) {
+            violations.push(ConfigViolation::new(
+                "history_capacity",
+                "must be at least 1",
+            ));
+        }
+        if self.evolution_history_size == 0 {
+            violations.push(ConfigViolation::new(
+                "evolution_history_size",
+                "must be at least 1",
+            ));
+        }
+        let retention_limit = match self.battle_history_retention {
+            BattleHistoryRetention::PerAgent(limit) | BattleHistoryRetention::Total(limit) => limit,
+            BattleHistoryRetention::LastSteps(steps) => steps as usize,
+        };
+        if retention_limit == 0 {
+            violations.push(ConfigViolation::new(
+                "battle_history_retention",
+                "must keep at least 1 record or step",
+            ));
+        }
+        if let Some(lifecycle) = &self.lifecycle {
+            if lifecycle.max_age == Some(0) {
+                violations.push(ConfigViolation::new(
+                    "lifecycle.max_age",
+                    "must be at least 1",
+                ));
+            }
+            match lifecycle.mortality {
+                Some(MortalityCurve::Constant(p)) => {
+                    unit_interval(&mut violations, "lifecycle.mortality", p)
+                }
+                Some(MortalityCurve::Gompertz { base, growth }) => {
+                    unit_interval(&mut violations, "lifecycle.mortality.base", base);
+                    if !growth.is_finite() {
+                        violations.push(ConfigViolation::new(
+                            "lifecycle.mortality.growth",
+                            format!("must be finite, got {growth}"),
+                        ));
+                    }
+                }
+                None => {}
+            }
+            if lifecycle
+                .min_energy
+                .is_some_and(|min_energy| !min_energy.is_finite())
+            {
+                violations.push(ConfigViolation::new(
+                    "lifecycle.min_energy",
+                    "must be finite",
+                ));
+            }
+        }
+        match &self.agent_tracking {
+            Some(TrackingTarget::TopK(0)) => violations.push(ConfigViolation::new(
+                "agent_tracking",
+                "must track at least 1 agent",
+            )),
+            Some(TrackingTarget::Ids(ids)) if ids.is_empty() => violations.push(
+                ConfigViolation::new("agent_tracking", "must list at least 1 agent id"),
+            ),
+            _ => {}
+        }
+        if self.extinction_policy.reseed_count() == Some(0) {
+            violations.push(ConfigViolation::new(
+                "extinction_policy",
+                "must reseed at least 1 agent",
+            ));
+        }
+
+        violations
+    }
+
+    // validate に加えて、グリッドの大きさと初期個体数との整合を調べる
+    pub fn validate_for(
+        &self,
+        width: usize,
+        height: usize,
+        agent_count: usize,
+    ) -> Vec<ConfigViolation> {
+        let mut violations = Vec::new();
+
+        if width == 0 || height == 0 {
+            violations.push(ConfigViolation::new(
+                "grid",
+                format!("width and height must be positive, got {width}x{height}"),
+            ));
+        }
+        if agent_count == 0 {
+            violations.push(ConfigViolation::new(
+                "agent_count",
+                "population must not be empty",
+            ));
+        } else if agent_count > width * height {
+            violations.push(ConfigViolation::new(
+                "agent_count",
+                format!(
+                    "{agent_count} agents do not fit on a {width}x{height} grid ({} cells)",
+                    width * height
+                ),
+            ));
+        }
+
+        if let Some(outside) = self
+            .obstacles
+            .iter()
+            .find(|position| position.x >= width || position.y >= height)
+        {
+            violations.push(ConfigViolation::new(
+                "obstacles",
+                format!(
+                    "obstacle ({}, {}) lies outside the {width}x{height} grid",
+                    outside.x, outside.y
+                ),
+            ));
+        } else {
+            let open = width * height - self.obstacles.iter().collect::<HashSet<_>>().len();
+            if agent_count <= width * height && agent_count > open {
+                violations.push(ConfigViolation::new(
+                    "agent_count",
+                    format!(
+                        "{agent_count} agents do not fit in the {open} cells free of obstacles"
+                    ),
+                ));
+            }
+        }
+
+        if let NeighborhoodType::Radius(radius) = self.neighborhood {
+            if radius as usize >= width.max(height) {
+                violations.push(ConfigViolation::new(
+                    "neighborhood",
+                    format!("radius {radius} is larger than the {width}x{height} world"),
+                ));
+            }
+        }
+
+        if let Some(network) = &self.network {
+            if let Err(message) = network.validate(width * height) {
+                violations.push(ConfigViolation::new("network", message));
+            }
+        }
+
+        if let Some(demes) = &self.deme_structure {
+            if demes.columns > width || demes.rows > height {
+                violations.push(ConfigViolation::new(
+                    "deme_structure",
+                    format!(
+                        "{}x{} demes do not fit on a {width}x{height} grid",
+                        demes.columns, demes.rows
+                    ),
+                ));
+            }
+        }
+
+        if let Some(zones) = &self.zones {
+            if let Err(message) = zones.validate(width, height) {
+                violations.push(ConfigViolation::new("zones", message));
+            }
+        }
+
+        if let PlacementPattern::Clustered { block_size } = self.placement {
+            if block_size == 0 {
+                violations.push(ConfigViolation::new(
+                    "placement",
+                    "cluster block size must be at least 1",
+                ));
+            }
+        }
+
+        violations.extend(self.validate());
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::{FitnessSharing, MultiObjective, Objective};
+    use crate::domain::grid::DemeStructure;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        // Arrange & Act & Assert
+        assert!(SimulationConfig::default()
+            .validate_for(10, 10, 50)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation_by_field() {
+        // Arrange: builder はクランプするので直接書き換える
+        let config = SimulationConfig {
+            mutation_rate: 1.5,
+            neighborhood: NeighborhoodType::Radius(20),
+            memory_genome: Some(3),
+            deme_structure: Some(DemeStructure {
+                columns: 30,
+                rows: 1,
+                migration_rate: 0.0,
+                migration_interval: 0,
+                migrants: 0,
+            }),
+            ..SimulationConfig::default()
+        };
+
+        // Act
+        let violations = config.validate_for(10, 10, 0);
+
+        // Assert
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "agent_count",
+                "neighborhood",
+                "deme_structure",
+                "mutation_rate",
+                "memory_genome"
+            ]
+        );
+        assert!(
+            ConfigViolation::join(&violations).contains("mutation_rate: must be between 0 and 1")
+        );
+    }
+
+    #[test]
+    fn test_fitness_sharing_is_rejected_with_multi_objective_selection() {
+        // Arrange
+        let shared = SimulationConfig::default().with_fitness_sharing(FitnessSharing::new(0.2));
+        let pareto = shared
+            .clone()
+            .with_multi_objective(MultiObjective::new(vec![Objective::Score]));
+
+        // Act
+        let violations = pareto.validate();
+
+        // Assert
+        assert!(shared.validate().is_empty());
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["fitness_sharing"]);
+    }
+}
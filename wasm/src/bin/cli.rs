@@ -0,0 +1,1140 @@
+// WASM フロントエンドを使わずにバッチ実行するためのヘッドレス CLI
+use prisoners_dilemma_2d::application::analysis::{
+    ExperimentService, ExperimentSettings, InteractionNetwork, ParameterRange, ReplicateRunner,
+};
+use prisoners_dilemma_2d::application::evolution::{
+    AdaptiveMutation, CrossoverMethod, EvolutionStrategyType, FitnessFunction, FitnessSharing,
+    MultiObjective, SelectionMethod,
+};
+use prisoners_dilemma_2d::application::simulation::{
+    BattleHistoryRetention, DetailedStatistics, EvolutionTracker, ExtinctionPolicy,
+    InitialPopulationSpec, LifecycleConfig, ReproductionModel, SimulationConfig, SimulationService,
+    SimulationStatistics, TrackingTarget,
+};
+use prisoners_dilemma_2d::domain::agent::{Agent, Position, TagConfig};
+use prisoners_dilemma_2d::domain::game::{
+    GameType, PayoffMatrix, PayoffSchedule, PunishmentConfig, ReputationConfig,
+};
+use prisoners_dilemma_2d::domain::grid::{
+    DemeStructure, Grid, HeatmapField, MigrationModel, MovementPolicyKind, NeighborhoodType,
+    NetworkConfig, NetworkTopology, PlacementPattern, ResourceConfig, ZoneMap,
+};
+#[cfg(feature = "arrow")]
+use prisoners_dilemma_2d::infrastructure::persistence::{
+    ColumnarExporter, ColumnarFormat, ColumnarWriter,
+};
+use prisoners_dilemma_2d::infrastructure::persistence::{
+    ExportOptions, PersistenceFormat, PersistenceService, SerializationService,
+};
+use prisoners_dilemma_2d::infrastructure::render::{ColorMode, SvgOptions};
+#[cfg(feature = "render")]
+use prisoners_dilemma_2d::infrastructure::render::{GifRecorder, RenderOptions, RenderService};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "Usage: prisoners-dilemma [OPTIONS]
+
+Options:
+  --config <FILE>          JSON config file (flags override its values)
+  --width <N>              Grid width (default 50)
+  --height <N>             Grid height (default 50)
+  --agents <N>             Number of agents (default 500)
+  --generations <N>        Generations to run (default 100)
+  --mutation-rate <RATE>   Mutation rate 0.0-1.0 (default 0.05)
+  --adaptive-mutation <S>  diversity:T:B (boost the rate B-fold while strategy diversity < T) | stagnation:N:B (while the
+                           mean score has not beaten its earlier best for N generations)
+  --action-noise <RATE>    Probability of flipping each intended action (default 0.0)
+  --selection <METHOD>     roulette | complexity-penalty | normalized | sus | boltzmann[:T:RATE[:MIN]] (default roulette;
+                           Boltzmann selection starts at temperature T, cooled by RATE each generation down to MIN,
+                           default 10:0.95:0.1)
+  --crossover <SPEC>       average | sbx[:ETA] | blx[:ALPHA] crossover of mobility, in-group bias and aggression
+                           (default average; ETA defaults to 15, ALPHA to 0.5)
+  --sharing <R[:ALPHA]>    Fitness sharing: agents within trait distance R (0-1) split their fitness before selection;
+                           niche counts go to the niche_count statistic (ALPHA shapes the sharing curve, default 1;
+                           applies on top of --fitness/--selection, not allowed with --objectives)
+  --evolution-strategy <S> classic | elitist[:N] | steady_state | island_model[:CxR] | adaptive | multi_objective
+                           (fills in defaults for the reproduction model, islands, adaptive mutation or objectives
+                           unless set explicitly; default classic)
+  --fitness <SPEC>         raw | per_battle | energy:W | age:RATE | weighted:SCORE,COOPERATION,AGGRESSION,MOBILITY
+                           (parent selection, island/extinction elites and average_fitness; overrides --selection)
+  --objectives <LIST>      Pareto selection over score,cooperation,longevity,spatial_influence (overrides --selection)
+  --torus                  Wrap the grid edges
+  --neighborhood <KIND>    moore | von_neumann | radius:N | hex (default moore)
+  --network <SPEC>         Play on a graph over the cells instead of the grid neighborhood:
+                           erdos_renyi:P | watts_strogatz:K:B | barabasi_albert:M (built from --seed, default 0)
+  --network-edges <FILE>   Play on an edge list (one \"a b\" pair per line; node i is cell (i % width, i / width))
+  --rewiring <P>           Probability of cutting a link to a neighbor who defected and relinking to a neighbor's neighbor
+                           (requires --network or --network-edges; default 0.0)
+  --tags <N[:RATE]>        Give agents one of N inherited tags and a bias toward cooperating with the same tag
+                           (RATE: per-child tag mutation probability, default 0.01)
+  --reputation <SPEC>      Share a public reputation board for indirect reciprocity: image_scoring[:P[:D]] | standing[:P[:D]]
+                           (P: probability each action is observed, default 1.0; D: per-step decay, default 0.0)
+  --punishment <COST:FINE> After each game, a defected-on agent pays COST to fine the defector FINE with probability
+                           equal to its inherited aggression
+  --zones <FILE>           JSON array of rectangular zones with their own payoffs and/or mutation rate:
+                           [{\"name\", \"region\": {\"x\", \"y\", \"width\", \"height\"}, \"payoffs\", \"mutation_rate\"}]
+  --obstacles <FILE>       Wall cells as a text map: one line per grid row, '#' marks a wall (blocks placement,
+                           movement and neighbor interactions)
+  --movement-policy <NAME> Move every agent by one policy, with probability equal to its inherited mobility:
+                           stay | random_walk | gradient | flee_defectors | success_driven[:RADIUS] (default: each
+                           agent's own movement strategy)
+  --migration <MODEL>      none | success_driven[:RANGE]: before each update, every agent moves to the empty site within
+                           RANGE (default 5) where test games against its would-be neighbors pay most (Helbing & Yu)
+  --islands <CxR[:G:M]>    Split the grid into C x R islands; every G generations send each island's top M agents to the next
+  --placement <PATTERN>    uniform | clustered:N | ring | center_blob | checkerboard (default uniform)
+  --battle-history <SPEC>  per_agent:N | total:N | last_steps:N battle records to keep (default per_agent:10)
+  --on-extinction <SPEC>   stop | continue | reseed_random:N | reseed_elite:N when every agent dies (default continue)
+  --game <KIND>            pd | public_goods | public_goods:R (default pd)
+  --game-preset <NAME>     prisoners_dilemma | snowdrift | hawk_dove | stag_hunt
+  --payoffs <T,R,P,S>      Custom payoff matrix (must satisfy T > R > P > S unless overridden)
+  --allow-non-dilemma      Accept payoffs that break the prisoner's dilemma ordering
+  --payoff-schedule <SPEC> Change payoffs over generations: GEN=PAYOFFS switches at GEN, GEN~PAYOFFS ramps linearly
+                           to GEN; separate entries with ';' (PAYOFFS: preset name or T,R,P,S), e.g. \"500=snowdrift\"
+  --memory-genome <N>      Evolve memory-N lookup tables (1 or 2) instead of built-in strategies
+  --seed <N>               RNG seed for reproducible runs
+  --format <FORMAT>        csv | json | jsonl (default csv; jsonl writes one record per line)
+  --stats-out <FILE>       Write generation statistics (with --format jsonl, one line per generation as the run
+                           goes; - writes to stdout)
+  --agents-out <FILE>      Write final agents
+  --battle-log-out <FILE>  Write every agent's retained battle records before the last step of the final generation
+                           (one row per record; how many are kept follows --battle-history)
+  --interaction-out <FILE> Write who-played-whom as a graph at the same point as --battle-log-out (edge list for
+                           csv, nodes and edges for json, GraphML when FILE ends in .graphml)
+  --agents-in <FILE>       Start from agents exported as JSON or binary instead of a random population (ignores
+                           --agents; agents outside the grid or on a taken cell move to random empty cells)
+  --histograms-out <FILE>  Write per-generation trait histograms as JSON
+  --histogram-bins <N>     Bins per histogram (default 20)
+  --heatmap-out <FILE>     Write the final per-cell field as CSV (one line per grid row)
+  --heatmap-field <NAME>   cooperation | score | occupancy | energy | resources | zone | obstacle (default cooperation)
+  --spatial-out <FILE>     Write per-generation cooperator clusters and Moran's I
+  --network-out <FILE>     Write per-generation degree distribution, assortativity and rewired links (with a network)
+  --pareto-out <FILE>      Write each generation's Pareto front (requires --objectives)
+  --track <TARGET>         Record individual trajectories: top:K (top K scorers each generation) | ids:ID,ID,...
+  --trajectory-out <FILE>  Write the tracked agents' per-generation score, energy, position and strategy
+  --metrics-out <FILE>     Write per-generation fitness min/max/mean/sd, strategy and genetic diversity, and timings
+  --metrics-history <N>    Generations kept for --metrics-out (default 1000)
+  --render-dir <DIR>       Write the grid as generation_NNNN.png before each generation's replacement (needs the
+                           render feature: cargo run --features render)
+  --gif-out <FILE>         Write an animated GIF with one frame per generation (needs the render feature)
+  --render-color <MODE>    cooperation | strategy colors for --render-dir, --gif-out and --svg-out (default cooperation)
+  --render-cell <N>        Pixels per grid cell in rendered images (default 4)
+  --svg-out <FILE>         Write the final grid as SVG (works without the render feature)
+  --svg-clusters           Outline cooperator clusters in --svg-out
+  --arrow-agents <FILE>    Write every generation's agents (before replacement) as Arrow IPC, or Parquet for a
+                           .parquet path (needs the arrow feature: cargo run --features arrow)
+  --arrow-stats <FILE>     Write the statistics history as Arrow IPC or Parquet (needs the arrow feature)
+  --sweep <P=START:END:N>  Sweep a parameter over N evenly spaced values (repeatable; runs an experiment)
+                           P: mutation_rate | action_noise | temptation | reward | punishment | sucker | public_goods_multiplier
+  --replicates <N>         Runs per parameter combination in a sweep, or per --replicate-out run (default 5)
+  --sweep-out <FILE>       Write the sweep summary CSV (default stdout)
+  --replicate-out <FILE>   Repeat the run with seeds SEED, SEED+1, ... and write per-generation mean, sd and 95% CI
+  --quiet                  Suppress progress output
+  -h, --help               Show this message";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    Csv,
+    Json,
+    // 1 行 1 レコードの JSON。統計は実行中に世代ごとに書き足す
+    #[serde(rename = "jsonl")]
+    JsonLines,
+}
+
+impl OutputFormat {
+    // JSON Lines では JSON の配列を 1 要素 1 行に並べ直す
+    fn json(self, json: String) -> Result<String, String> {
+        match self {
+            OutputFormat::JsonLines => Ok(SerializationService::json_to_lines(&json)?),
+            OutputFormat::Csv | OutputFormat::Json => Ok(json),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct CliConfig {
+    width: usize,
+    height: usize,
+    agents: usize,
+    generations: u32,
+    mutation_rate: f64,
+    adaptive_mutation: Option<AdaptiveMutation>,
+    action_noise: f64,
+    selection: SelectionMethod,
+    crossover: CrossoverMethod,
+    sharing: Option<FitnessSharing>,
+    evolution_strategy: EvolutionStrategyType,
+    fitness: Option<FitnessFunction>,
+    objectives: Option<MultiObjective>,
+    torus: bool,
+    neighborhood: NeighborhoodType,
+    network: Option<NetworkTopology>,
+    rewiring: f64,
+    tags: Option<TagConfig>,
+    reputation: Option<ReputationConfig>,
+    punishment: Option<PunishmentConfig>,
+    zones: Option<ZoneMap>,
+    obstacles: Vec<Position>,
+    movement_policy: Option<MovementPolicyKind>,
+    migration: MigrationModel,
+    islands: Option<DemeStructure>,
+    placement: PlacementPattern,
+    battle_history: BattleHistoryRetention,
+    on_extinction: ExtinctionPolicy,
+    game: GameType,
+    payoffs: PayoffMatrix,
+    allow_non_dilemma: bool,
+    payoff_schedule: Option<PayoffSchedule>,
+    memory_genome: Option<usize>,
+    seed: Option<u64>,
+    // 設定ファイルでのみ指定できる（系統ごとの初期割合、セルごとの資源、繁殖モデル、寿命）
+    initial_population: Option<InitialPopulationSpec>,
+    resources: Option<ResourceConfig>,
+    reproduction: ReproductionModel,
+    lifecycle: Option<LifecycleConfig>,
+    format: OutputFormat,
+    stats_out: Option<String>,
+    agents_out: Option<String>,
+    battle_log_out: Option<String>,
+    interaction_out: Option<String>,
+    agents_in: Option<String>,
+    histograms_out: Option<String>,
+    histogram_bins: usize,
+    spatial_out: Option<String>,
+    network_out: Option<String>,
+    pareto_out: Option<String>,
+    track: Option<TrackingTarget>,
+    trajectory_out: Option<String>,
+    metrics_out: Option<String>,
+    metrics_history: usize,
+    render_dir: Option<String>,
+    gif_out: Option<String>,
+    render_color: String,
+    render_cell: usize,
+    svg_out: Option<String>,
+    svg_clusters: bool,
+    arrow_agents: Option<String>,
+    arrow_stats: Option<String>,
+    sweep: Vec<ParameterRange>,
+    replicates: usize,
+    sweep_out: Option<String>,
+    replicate_out: Option<String>,
+    heatmap_out: Option<String>,
+    heatmap_field: HeatmapField,
+    quiet: bool,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            width: 50,
+            height: 50,
+            agents: 500,
+            generations: 100,
+            mutation_rate: 0.05,
+            adaptive_mutation: None,
+            action_noise: 0.0,
+            selection: SelectionMethod::Roulette,
+            crossover: CrossoverMethod::Average,
+            sharing: None,
+            evolution_strategy: EvolutionStrategyType::Classic,
+            fitness: None,
+            objectives: None,
+            torus: false,
+            neighborhood: NeighborhoodType::Moore,
+            network: None,
+            rewiring: 0.0,
+            tags: None,
+            reputation: None,
+            punishment: None,
+            zones: None,
+            obstacles: Vec::new(),
+            movement_policy: None,
+            migration: MigrationModel::None,
+            islands: None,
+            placement: PlacementPattern::Uniform,
+            battle_history: BattleHistoryRetention::default(),
+            on_extinction: ExtinctionPolicy::Continue,
+            game: GameType::PrisonersDilemma,
+            payoffs: PayoffMatrix::default(),
+            allow_non_dilemma: false,
+            payoff_schedule: None,
+            memory_genome: None,
+            seed: None,
+            initial_population: None,
+            resources: None,
+            reproduction: ReproductionModel::Generational,
+            lifecycle: None,
+            format: OutputFormat::Csv,
+            stats_out: None,
+            agents_out: None,
+            battle_log_out: None,
+            interaction_out: None,
+            agents_in: None,
+            histograms_out: None,
+            histogram_bins: DetailedStatistics::DEFAULT_BINS,
+            spatial_out: None,
+            network_out: None,
+            pareto_out: None,
+            track: None,
+            trajectory_out: None,
+            metrics_out: None,
+            metrics_history: EvolutionTracker::DEFAULT_CAPACITY,
+            render_dir: None,
+            gif_out: None,
+            render_color: "cooperation".to_string(),
+            render_cell: 4,
+            svg_out: None,
+            svg_clusters: false,
+            arrow_agents: None,
+            arrow_stats: None,
+            sweep: Vec::new(),
+            replicates: 5,
+            sweep_out: None,
+            replicate_out: None,
+            heatmap_out: None,
+            heatmap_field: HeatmapField::Cooperation,
+            quiet: false,
+        }
+    }
+}
+
+impl CliConfig {
+    fn simulation_config(&self) -> SimulationConfig {
+        let config = SimulationConfig {
+            seed: self.seed,
+            initial_population: self.initial_population.clone(),
+            memory_genome: self.memory_genome,
+            resources: self.resources,
+            reproduction: self.reproduction,
+            lifecycle: self.lifecycle,
+            histogram_bins: self.histograms_out.as_ref().map(|_| self.histogram_bins),
+            spatial_metrics_enabled: self.spatial_out.is_some(),
+            deme_structure: self.islands,
+            network: self.network.clone().map(|topology| {
+                NetworkConfig::new(topology).with_seed(self.seed.unwrap_or_default())
+            }),
+            tags: self.tags,
+            reputation: self.reputation,
+            punishment: self.punishment,
+            zones: self.zones.clone(),
+            obstacles: self.obstacles.clone(),
+            movement_policy: self.movement_policy,
+            migration_model: self.migration,
+            payoff_schedule: self.payoff_schedule.clone(),
+            adaptive_mutation: self.adaptive_mutation,
+            fitness_function: self.fitness,
+            crossover_method: self.crossover,
+            fitness_sharing: self.sharing,
+            evolution_strategy: self.evolution_strategy,
+            multi_objective: self.objectives.clone(),
+            battle_history_retention: self.battle_history,
+            extinction_policy: self.on_extinction,
+            agent_tracking: self.track.clone(),
+            evolution_history_size: self.metrics_history,
+            ..SimulationConfig::default()
+        };
+        config
+            .with_mutation_rate(self.mutation_rate)
+            .with_action_noise(self.action_noise)
+            .with_torus_field(self.torus)
+            .with_neighborhood(self.neighborhood)
+            .with_rewiring_probability(self.rewiring)
+            .with_placement(self.placement)
+            .with_game_type(self.game)
+            .with_payoff_matrix(self.payoffs)
+            .with_non_dilemma_payoffs(self.allow_non_dilemma)
+            .with_selection(self.selection)
+    }
+}
+
+enum Command {
+    Run(Box<CliConfig>),
+    Help,
+}
+
+// --config を先に読み込み、残りのフラグで上書きする
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut config = match args.iter().position(|arg| arg == "--config") {
+        Some(index) => {
+            let path = args.get(index + 1).ok_or("--config requires a file path")?;
+            let text =
+                fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            serde_json::from_str(&text).map_err(|e| format!("Invalid config {path}: {e}"))?
+        }
+        None => CliConfig::default(),
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} requires a value"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--config" => {
+                value()?;
+            }
+            "--width" => config.width = parse_number(arg, &value()?)?,
+            "--height" => config.height = parse_number(arg, &value()?)?,
+            "--agents" => config.agents = parse_number(arg, &value()?)?,
+            "--generations" => config.generations = parse_number(arg, &value()?)?,
+            "--mutation-rate" => config.mutation_rate = parse_number(arg, &value()?)?,
+            "--battle-history" => config.battle_history = BattleHistoryRetention::parse(&value()?)?,
+            "--on-extinction" => config.on_extinction = ExtinctionPolicy::parse(&value()?)?,
+            "--adaptive-mutation" => {
+                config.adaptive_mutation = Some(AdaptiveMutation::parse(&value()?)?)
+            }
+            "--action-noise" => config.action_noise = parse_number(arg, &value()?)?,
+            "--selection" => config.selection = SelectionMethod::parse(&value()?)?,
+            "--crossover" => config.crossover = CrossoverMethod::parse(&value()?)?,
+            "--sharing" => config.sharing = Some(FitnessSharing::parse(&value()?)?),
+            "--evolution-strategy" => {
+                let name = value()?;
+                config.evolution_strategy = EvolutionStrategyType::from_name(&name)
+                    .ok_or_else(|| format!("Unknown evolution strategy: {name}"))?;
+            }
+            "--fitness" => config.fitness = Some(FitnessFunction::parse(&value()?)?),
+            "--objectives" => config.objectives = Some(MultiObjective::parse(&value()?)?),
+            "--torus" => config.torus = true,
+            "--neighborhood" => {
+                let name = value()?;
+                config.neighborhood = NeighborhoodType::from_name(&name)
+                    .ok_or_else(|| format!("Unknown neighborhood: {name}"))?;
+            }
+            "--network" => config.network = Some(NetworkTopology::parse(&value()?)?),
+            "--zones" => {
+                let path = value()?;
+                let text =
+                    fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+                config.zones = Some(
+                    serde_json::from_str(&text)
+                        .map_err(|e| format!("Invalid zones {path}: {e}"))?,
+                );
+            }
+            "--obstacles" => {
+                let path = value()?;
+                let text =
+                    fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+                config.obstacles = PersistenceService::import_obstacle_map(&text);
+            }
+            "--network-edges" => {
+                let path = value()?;
+                let text =
+                    fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+                let edges = PersistenceService::import_edge_list(&text)
+                    .map_err(|e| format!("Invalid edge list {path}: {}", String::from(e)))?;
+                config.network = Some(NetworkTopology::EdgeList(edges));
+            }
+            "--rewiring" => config.rewiring = parse_number(arg, &value()?)?,
+            "--tags" => config.tags = Some(TagConfig::parse(&value()?)?),
+            "--reputation" => config.reputation = Some(ReputationConfig::parse(&value()?)?),
+            "--punishment" => config.punishment = Some(PunishmentConfig::parse(&value()?)?),
+            "--movement-policy" => {
+                let name = value()?;
+                config.movement_policy = Some(
+                    MovementPolicyKind::from_name(&name)
+                        .ok_or_else(|| format!("Unknown movement policy: {name}"))?,
+                );
+            }
+            "--migration" => {
+                let name = value()?;
+                config.migration = MigrationModel::from_name(&name)
+                    .ok_or_else(|| format!("Unknown migration model: {name}"))?;
+            }
+            "--islands" => config.islands = Some(parse_islands(&value()?)?),
+            "--placement" => {
+                let name = value()?;
+                config.placement = PlacementPattern::from_name(&name)
+                    .ok_or_else(|| format!("Unknown placement pattern: {name}"))?;
+            }
+            "--game" => {
+                let name = value()?;
+                config.game = GameType::from_name(&name)
+                    .ok_or_else(|| format!("Unknown game type: {name}"))?;
+            }
+            "--game-preset" => {
+                let name = value()?;
+                config.payoffs = PayoffMatrix::preset(&name)
+                    .ok_or_else(|| format!("Unknown game preset: {name}"))?;
+                config.allow_non_dilemma |= config.payoffs.validate_prisoners_dilemma().is_err();
+            }
+            "--payoffs" => config.payoffs = parse_payoffs(&value()?)?,
+            "--allow-non-dilemma" => config.allow_non_dilemma = true,
+            "--payoff-schedule" => config.payoff_schedule = Some(PayoffSchedule::parse(&value()?)?),
+            "--memory-genome" => config.memory_genome = Some(parse_number(arg, &value()?)?),
+            "--seed" => config.seed = Some(parse_number(arg, &value()?)?),
+            "--format" => {
+                config.format = match value()?.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    "jsonl" => OutputFormat::JsonLines,
+                    other => return Err(format!("Unknown format: {other}")),
+                }
+            }
+            "--stats-out" => config.stats_out = Some(value()?),
+            "--agents-out" => config.agents_out = Some(value()?),
+            "--battle-log-out" => config.battle_log_out = Some(value()?),
+            "--interaction-out" => config.interaction_out = Some(value()?),
+            "--agents-in" => config.agents_in = Some(value()?),
+            "--histograms-out" => config.histograms_out = Some(value()?),
+            "--histogram-bins" => config.histogram_bins = parse_number(arg, &value()?)?,
+            "--spatial-out" => config.spatial_out = Some(value()?),
+            "--network-out" => config.network_out = Some(value()?),
+            "--pareto-out" => config.pareto_out = Some(value()?),
+            "--track" => config.track = Some(TrackingTarget::parse(&value()?)?),
+            "--trajectory-out" => config.trajectory_out = Some(value()?),
+            "--metrics-out" => config.metrics_out = Some(value()?),
+            "--metrics-history" => config.metrics_history = parse_number(arg, &value()?)?,
+            "--render-dir" => config.render_dir = Some(value()?),
+            "--gif-out" => config.gif_out = Some(value()?),
+            "--render-color" => config.render_color = value()?,
+            "--render-cell" => config.render_cell = parse_number(arg, &value()?)?,
+            "--svg-out" => config.svg_out = Some(value()?),
+            "--svg-clusters" => config.svg_clusters = true,
+            "--arrow-agents" => config.arrow_agents = Some(value()?),
+            "--arrow-stats" => config.arrow_stats = Some(value()?),
+            "--sweep" => config.sweep.push(ParameterRange::parse(&value()?)?),
+            "--replicates" => config.replicates = parse_number(arg, &value()?)?,
+            "--sweep-out" => config.sweep_out = Some(value()?),
+            "--replicate-out" => config.replicate_out = Some(value()?),
+            "--heatmap-out" => config.heatmap_out = Some(value()?),
+            "--heatmap-field" => {
+                let name = value()?;
+                config.heatmap_field = HeatmapField::from_name(&name)
+                    .ok_or_else(|| format!("Unknown heatmap field: {name}"))?;
+            }
+            "--quiet" => config.quiet = true,
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+
+    Ok(Command::Run(Box::new(config)))
+}
+
+// "T,R,P,S"
+fn parse_payoffs(value: &str) -> Result<PayoffMatrix, String> {
+    let values = value
+        .split(',')
+        .map(|part| parse_number("--payoffs", part.trim()))
+        .collect::<Result<Vec<i32>, String>>()?;
+    match values[..] {
+        [temptation, reward, punishment, sucker] => {
+            Ok(PayoffMatrix::new(temptation, reward, punishment, sucker))
+        }
+        _ => Err(format!("--payoffs expects T,R,P,S, got {value}")),
+    }
+}
+
+// "CxR" または "CxR:G:M"
+fn parse_islands(value: &str) -> Result<DemeStructure, String> {
+    let invalid = || format!("--islands expects CxR or CxR:G:M, got {value}");
+    let mut parts = value.split(':');
+    let (columns, rows) = parts
+        .next()
+        .and_then(|grid| grid.split_once('x'))
+        .ok_or_else(invalid)?;
+    let islands = DemeStructure::new(
+        parse_number("--islands", columns)?,
+        parse_number("--islands", rows)?,
+    );
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => Ok(islands),
+        (Some(interval), Some(migrants), None) => Ok(islands.with_scheduled_migration(
+            parse_number("--islands", interval)?,
+            parse_number("--islands", migrants)?,
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {flag}: {value}"))
+}
+
+fn run(config: &CliConfig) -> Result<(), String> {
+    if !config.sweep.is_empty() {
+        return run_experiment(config);
+    }
+    if let Some(path) = &config.replicate_out {
+        return run_replicates(config, path);
+    }
+
+    let mut simulation = match &config.agents_in {
+        Some(path) => {
+            let bytes = fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+            let imported = PersistenceService::import_auto(&bytes)
+                .map_err(|e| format!("Invalid agents {path}: {}", String::from(e)))?;
+            SimulationService::with_agents(
+                config.width,
+                config.height,
+                imported
+                    .agents
+                    .into_iter()
+                    .map(|agent| (agent.id, agent))
+                    .collect(),
+                config.simulation_config(),
+            )?
+        }
+        None => SimulationService::with_config(
+            config.width,
+            config.height,
+            config.agents,
+            config.simulation_config(),
+        )?,
+    };
+
+    let mut renderer = Renderer::new(config)?;
+    let mut columnar = Columnar::new(config)?;
+    let mut live_stats = LiveStats::new(config)?;
+    let mut battle_log_written = false;
+    let log_battles_requested = config.battle_log_out.is_some() || config.interaction_out.is_some();
+    for generation in 1..=config.generations {
+        // 世代交代で子の対戦履歴は空になるので、対戦記録は最後の世代の交代前に書く
+        let log_battles = log_battles_requested && generation == config.generations;
+        if renderer.is_some() || columnar.is_some() || log_battles {
+            // 協力率やスコアが新しい世代でリセットされる前に、最後のステップの手前で記録する
+            while simulation.get_turn() + 1 < simulation.get_turns_per_generation()
+                && !simulation.is_stopped()
+            {
+                simulation.step();
+            }
+        }
+        if let Some(renderer) = &mut renderer {
+            renderer.record(generation, simulation.get_grid())?;
+        }
+        if let Some(columnar) = &mut columnar {
+            columnar.record(generation, &simulation.get_agents())?;
+        }
+        if log_battles {
+            write_battle_log(config, simulation.get_grid())?;
+            battle_log_written = true;
+        }
+        simulation.run_generations(1);
+        if let Some(live_stats) = &mut live_stats {
+            live_stats.write(simulation.get_generation_history())?;
+        }
+        // 世代交代直前の統計（スコアがリセットされる前の値）を表示する
+        if let (false, Some(stats)) = (config.quiet, simulation.get_generation_history().back()) {
+            eprintln!(
+                "generation {generation}/{}: agents={} cooperation={:.3} score={:.2}",
+                config.generations,
+                stats.total_agents,
+                stats.average_cooperation_rate,
+                stats.average_score
+            );
+        }
+        for diagnostic in simulation.get_diagnostics() {
+            eprintln!("warning: {diagnostic}");
+        }
+        simulation.clear_diagnostics();
+        if simulation.is_stopped() {
+            eprintln!("stopped at generation {generation}: population went extinct");
+            break;
+        }
+    }
+
+    if let Some(renderer) = renderer {
+        renderer.finish()?;
+    }
+    if let Some(columnar) = columnar {
+        columnar.finish(simulation.get_generation_history())?;
+    }
+
+    let options = ExportOptions::default();
+    let history = simulation.get_generation_history();
+
+    if let Some(path) = &config.stats_out {
+        match config.format {
+            OutputFormat::Csv => stream_file(path, |writer| {
+                SerializationService::write_stats_history_csv(writer, history, &options)
+            })?,
+            OutputFormat::Json => {
+                let contents = SerializationService::stats_history_to_json(history, &options)?;
+                write_file(path, contents.as_bytes())?;
+            }
+            // 実行中に書き終えている
+            OutputFormat::JsonLines => {}
+        }
+    }
+
+    if let Some(path) = &config.agents_out {
+        match config.format {
+            OutputFormat::Csv => {
+                stream_file(path, |writer| simulation.write_agents_csv(writer, &options))?
+            }
+            OutputFormat::Json => {
+                let agents = simulation.get_agents();
+                let contents = PersistenceService::export_agents(&agents, PersistenceFormat::Json)?;
+                write_file(path, &contents)?;
+            }
+            OutputFormat::JsonLines => stream_file(path, |writer| {
+                SerializationService::write_agents_jsonl(writer, &simulation.get_agents())
+            })?,
+        }
+    }
+
+    // 途中で止まった場合は現在の個体の履歴を書く
+    if log_battles_requested && !battle_log_written {
+        write_battle_log(config, simulation.get_grid())?;
+    }
+
+    if let Some(path) = &config.histograms_out {
+        let contents = SerializationService::histograms_to_json(simulation.get_detailed_history())?;
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.heatmap_out {
+        let values = simulation.get_heatmap(config.heatmap_field);
+        let contents = SerializationService::heatmap_to_csv(&values, config.width, &options);
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.svg_out {
+        let color_mode = ColorMode::from_name(&config.render_color)
+            .ok_or_else(|| format!("Unknown render color: {}", config.render_color))?;
+        let options = SvgOptions::new()
+            .with_color_mode(color_mode)
+            .with_cluster_outlines(config.svg_clusters);
+        write_file(path, simulation.export_svg(&options).as_bytes())?;
+    }
+
+    if let Some(path) = &config.spatial_out {
+        let history = simulation.get_spatial_history();
+        let contents = match config.format {
+            OutputFormat::Csv => SerializationService::spatial_history_to_csv(history, &options),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                config
+                    .format
+                    .json(SerializationService::spatial_history_to_json(
+                        history, &options,
+                    )?)?
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.network_out {
+        let history = simulation.get_network_history();
+        let contents = match config.format {
+            OutputFormat::Csv => SerializationService::network_history_to_csv(history, &options),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                config
+                    .format
+                    .json(SerializationService::network_history_to_json(
+                        history, &options,
+                    )?)?
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.pareto_out {
+        let history = simulation.get_pareto_history();
+        let contents = match config.format {
+            OutputFormat::Csv => SerializationService::pareto_history_to_csv(history, &options),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                config
+                    .format
+                    .json(SerializationService::pareto_history_to_json(
+                        history, &options,
+                    )?)?
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.trajectory_out {
+        let tracker = simulation
+            .get_agent_tracker()
+            .ok_or("--trajectory-out requires --track")?;
+        let trajectories = tracker.trajectories();
+        let contents = match config.format {
+            OutputFormat::Csv => SerializationService::trajectories_to_csv(trajectories, &options),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                config
+                    .format
+                    .json(SerializationService::trajectories_to_json(
+                        trajectories,
+                        &options,
+                    )?)?
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    if let Some(path) = &config.metrics_out {
+        let records = simulation.get_evolution_records();
+        let contents = match config.format {
+            OutputFormat::Csv => SerializationService::generation_records_to_csv(records, &options),
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                config
+                    .format
+                    .json(SerializationService::generation_records_to_json(
+                        records, &options,
+                    )?)?
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+// 掃引の各組み合わせを反復実行し、組み合わせごとの要約を 1 行ずつ出力する
+fn run_experiment(config: &CliConfig) -> Result<(), String> {
+    let settings = ExperimentSettings {
+        width: config.width,
+        height: config.height,
+        agent_count: config.agents,
+        generations: config.generations,
+        replicates: config.replicates,
+        base_seed: config.seed.unwrap_or(0),
+        config: config.simulation_config(),
+        ranges: config.sweep.clone(),
+    };
+
+    let rows = ExperimentService::run_with(&settings, |progress| {
+        if !config.quiet {
+            eprintln!("run {}/{}", progress.completed_runs, progress.total_runs);
+        }
+        true
+    })?;
+
+    let contents = SerializationService::experiment_to_csv(&rows, &ExportOptions::default());
+    match &config.sweep_out {
+        Some(path) => write_file(path, contents.as_bytes()),
+        None => {
+            print!("{contents}");
+            Ok(())
+        }
+    }
+}
+
+// シードだけを変えて繰り返し、世代ごとの平均・標準偏差・95% 信頼区間を書き出す
+fn run_replicates(config: &CliConfig, path: &str) -> Result<(), String> {
+    let runner = ReplicateRunner::new(
+        config.width,
+        config.height,
+        config.agents,
+        config.simulation_config(),
+    )
+    .with_replicates(config.replicates);
+
+    let result = runner.run_with(config.generations, |completed, total| {
+        if !config.quiet {
+            eprintln!("replicate {completed}/{total}");
+        }
+        true
+    })?;
+
+    let options = ExportOptions::default();
+    let contents = match config.format {
+        OutputFormat::Csv => SerializationService::aggregated_to_csv(&result, &options),
+        OutputFormat::Json | OutputFormat::JsonLines => config
+            .format
+            .json(SerializationService::aggregated_to_json(&result, &options)?)?,
+    };
+    write_file(path, contents.as_bytes())
+}
+
+// --render-dir と --gif-out の書き出し先（どちらもなければ作らない）
+#[cfg(feature = "render")]
+struct Renderer {
+    options: RenderOptions,
+    dir: Option<String>,
+    gif: Option<(String, GifRecorder<std::io::BufWriter<fs::File>>)>,
+}
+
+#[cfg(feature = "render")]
+impl Renderer {
+    fn new(config: &CliConfig) -> Result<Option<Self>, String> {
+        if config.render_dir.is_none() && config.gif_out.is_none() {
+            return Ok(None);
+        }
+        let color_mode = ColorMode::from_name(&config.render_color)
+            .ok_or_else(|| format!("Unknown render color: {}", config.render_color))?;
+        let options = RenderOptions::new()
+            .with_color_mode(color_mode)
+            .with_cell_size(config.render_cell);
+        if let Some(dir) = &config.render_dir {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir}: {e}"))?;
+        }
+        let gif = match &config.gif_out {
+            Some(path) => {
+                let file =
+                    fs::File::create(path).map_err(|e| format!("Failed to write {path}: {e}"))?;
+                let recorder = GifRecorder::new(
+                    std::io::BufWriter::new(file),
+                    config.width,
+                    config.height,
+                    options,
+                )?;
+                Some((path.clone(), recorder))
+            }
+            None => None,
+        };
+        Ok(Some(Self {
+            options,
+            dir: config.render_dir.clone(),
+            gif,
+        }))
+    }
+
+    fn record(&mut self, generation: u32, grid: &Grid) -> Result<(), String> {
+        if let Some(dir) = &self.dir {
+            let png = RenderService::grid_to_png(grid, &self.options)?;
+            let path = std::path::Path::new(dir).join(format!("generation_{generation:04}.png"));
+            write_file(&path.to_string_lossy(), &png)?;
+        }
+        if let Some((_, recorder)) = &mut self.gif {
+            recorder.record(grid)?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if let Some((path, recorder)) = self.gif {
+            let mut writer = recorder.finish()?;
+            std::io::Write::flush(&mut writer)
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "render"))]
+enum Renderer {}
+
+#[cfg(not(feature = "render"))]
+impl Renderer {
+    fn new(config: &CliConfig) -> Result<Option<Self>, String> {
+        if config.render_dir.is_some() || config.gif_out.is_some() {
+            return Err(
+                "--render-dir and --gif-out need a build with the render feature (cargo run --features render)"
+                    .to_string(),
+            );
+        }
+        Ok(None)
+    }
+
+    fn record(&mut self, _generation: u32, _grid: &Grid) -> Result<(), String> {
+        match *self {}
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {}
+    }
+}
+
+// --format jsonl の --stats-out。世代ごとに 1 行書いてすぐ流すので、実行中の値を外部のダッシュボードへパイプできる
+struct LiveStats {
+    path: String,
+    writer: Box<dyn std::io::Write>,
+    last_generation: Option<u32>,
+}
+
+impl LiveStats {
+    fn new(config: &CliConfig) -> Result<Option<Self>, String> {
+        let (OutputFormat::JsonLines, Some(path)) = (config.format, &config.stats_out) else {
+            return Ok(None);
+        };
+        let writer: Box<dyn std::io::Write> = if path == "-" {
+            Box::new(std::io::stdout())
+        } else {
+            let file =
+                fs::File::create(path).map_err(|e| format!("Failed to write {path}: {e}"))?;
+            Box::new(std::io::BufWriter::new(file))
+        };
+        Ok(Some(Self {
+            path: path.clone(),
+            writer,
+            last_generation: None,
+        }))
+    }
+
+    // 新しい世代の統計があれば 1 行書く（絶滅で止まった後は同じ世代を繰り返さない）
+    fn write(&mut self, history: &VecDeque<SimulationStatistics>) -> Result<(), String> {
+        let Some(stats) = history.back() else {
+            return Ok(());
+        };
+        if self.last_generation == Some(stats.generation) {
+            return Ok(());
+        }
+        self.last_generation = Some(stats.generation);
+        SerializationService::write_stats_json_line(
+            &mut self.writer,
+            stats,
+            &ExportOptions::default(),
+        )
+        .and_then(|()| self.writer.flush())
+        .map_err(|e| format!("Failed to write {}: {e}", self.path))
+    }
+}
+
+// --arrow-agents と --arrow-stats の書き出し先（どちらもなければ作らない）
+#[cfg(feature = "arrow")]
+struct Columnar {
+    agents: Option<(String, ColumnarWriter<std::io::BufWriter<fs::File>>)>,
+    stats: Option<String>,
+}
+
+#[cfg(feature = "arrow")]
+impl Columnar {
+    fn new(config: &CliConfig) -> Result<Option<Self>, String> {
+        if config.arrow_agents.is_none() && config.arrow_stats.is_none() {
+            return Ok(None);
+        }
+        let agents = match &config.arrow_agents {
+            Some(path) => {
+                let file =
+                    fs::File::create(path).map_err(|e| format!("Failed to write {path}: {e}"))?;
+                let writer = ColumnarWriter::for_agents(
+                    std::io::BufWriter::new(file),
+                    ColumnarFormat::from_path(path),
+                )
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+                Some((path.clone(), writer))
+            }
+            None => None,
+        };
+        Ok(Some(Self {
+            agents,
+            stats: config.arrow_stats.clone(),
+        }))
+    }
+
+    fn record(&mut self, generation: u32, agents: &[Agent]) -> Result<(), String> {
+        if let Some((path, writer)) = &mut self.agents {
+            writer
+                .write_agents(generation, agents)
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+        }
+        Ok(())
+    }
+
+    fn finish(self, history: &VecDeque<SimulationStatistics>) -> Result<(), String> {
+        if let Some((path, writer)) = self.agents {
+            let mut writer = writer
+                .finish()
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+            std::io::Write::flush(&mut writer)
+                .map_err(|e| format!("Failed to write {path}: {e}"))?;
+        }
+        if let Some(path) = &self.stats {
+            let bytes = ColumnarExporter::stats_history_to_columnar(
+                history,
+                ColumnarFormat::from_path(path),
+            )
+            .map_err(|e| format!("Failed to write {path}: {e}"))?;
+            write_file(path, &bytes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "arrow"))]
+enum Columnar {}
+
+#[cfg(not(feature = "arrow"))]
+impl Columnar {
+    fn new(config: &CliConfig) -> Result<Option<Self>, String> {
+        if config.arrow_agents.is_some() || config.arrow_stats.is_some() {
+            return Err(
+                "--arrow-agents and --arrow-stats need a build with the arrow feature (cargo run --features arrow)"
+                    .to_string(),
+            );
+        }
+        Ok(None)
+    }
+
+    fn record(&mut self, _generation: u32, _agents: &[Agent]) -> Result<(), String> {
+        match *self {}
+    }
+
+    fn finish(self, _history: &VecDeque<SimulationStatistics>) -> Result<(), String> {
+        match self {}
+    }
+}
+
+fn write_file(path: &str, contents: &[u8]) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("Failed to write {path}: {e}"))
+}
+
+// --battle-log-out と --interaction-out（どちらも残っている対戦記録から作る）
+fn write_battle_log(config: &CliConfig, grid: &Grid) -> Result<(), String> {
+    let format = config.format;
+    if let Some(path) = &config.battle_log_out {
+        let agents = grid.agents_in_position_order();
+        match format {
+            OutputFormat::Csv => stream_file(path, |writer| {
+                SerializationService::write_battle_log_csv(writer, agents)
+            })?,
+            OutputFormat::Json | OutputFormat::JsonLines => {
+                let contents = format.json(SerializationService::battle_log_to_json(agents)?)?;
+                write_file(path, contents.as_bytes())?;
+            }
+        }
+    }
+
+    if let Some(path) = &config.interaction_out {
+        let network = InteractionNetwork::from_grid(grid);
+        let options = ExportOptions::default();
+        let contents = if path.ends_with(".graphml") {
+            SerializationService::interaction_network_to_graphml(&network, &options)
+        } else {
+            match format {
+                OutputFormat::Csv => {
+                    SerializationService::interaction_network_to_csv(&network, &options)
+                }
+                OutputFormat::Json | OutputFormat::JsonLines => format.json(
+                    SerializationService::interaction_network_to_json(&network, &options)?,
+                )?,
+            }
+        };
+        write_file(path, contents.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+// 大きな CSV を 1 つの文字列にせず、バッファ付きでファイルに書く
+fn stream_file(
+    path: &str,
+    write: impl FnOnce(&mut std::io::BufWriter<fs::File>) -> std::io::Result<()>,
+) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    let mut writer = std::io::BufWriter::new(file);
+    write(&mut writer)
+        .and_then(|()| std::io::Write::flush(&mut writer))
+        .map_err(|e| format!("Failed to write {path}: {e}"))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match parse_args(&args) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Run(config)) => match run(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {error}");
+                ExitCode::FAILURE
+            }
+        },
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use super::{Action, GameHistory, StrategyType};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+// 行動を決めるときに参照できる、同じ相手との対戦履歴
+// random は確率的な戦略が使う [0, 1) の一様乱数（is_stochastic でない戦略には 0.0 が渡る）
+pub struct DecisionContext<'a> {
+    pub history: &'a GameHistory,
+    pub opponent_id: &'a Uuid,
+    pub random: f64,
+    // 1 対 1 の対戦で相手と同じタグか（公共財ゲームなど相手が 1 人でないときは None）
+    pub same_tag: Option<bool>,
+    // 公開の評判（ReputationBoard）での相手の評判。評判を共有しないときは None
+    pub opponent_reputation: Option<f64>,
+}
+
+impl<'a> DecisionContext<'a> {
+    pub fn new(history: &'a GameHistory, opponent_id: &'a Uuid) -> Self {
+        Self {
+            history,
+            opponent_id,
+            random: 0.0,
+            same_tag: None,
+            opponent_reputation: None,
+        }
+    }
+
+    pub fn with_random(mut self, random: f64) -> Self {
+        self.random = random;
+        self
+    }
+
+    pub fn with_same_tag(mut self, same_tag: bool) -> Self {
+        self.same_tag = Some(same_tag);
+        self
+    }
+
+    pub fn with_opponent_reputation(mut self, reputation: Option<f64>) -> Self {
+        self.opponent_reputation = reputation;
+        self
+    }
+
+    pub fn last_opponent_action(&self) -> Option<Action> {
+        self.history.get_last_opponent_action(self.opponent_id)
+    }
+
+    pub fn last_my_action(&self) -> Option<Action> {
+        self.history.get_last_my_action(self.opponent_id)
+    }
+
+    pub fn last_payoff(&self) -> Option<i32> {
+        self.history.get_last_payoff(self.opponent_id)
+    }
+
+    // 古い順の (自分の行動, 相手の行動)。履歴の保持件数までしかさかのぼれない
+    pub fn rounds(&self) -> Vec<(Action, Action)> {
+        self.history.rounds_against(self.opponent_id)
+    }
+}
+
+// 戦略の拡張点。組み込み戦略もこのトレイトを実装している
+// record / reset は内部状態を持つ戦略のためのフックで、既定では何もしない
+pub trait DecisionStrategy: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn decide(&self, context: &DecisionContext) -> Action;
+
+    // true なら context.random を使う
+    fn is_stochastic(&self) -> bool {
+        false
+    }
+
+    // 1 ラウンドの結果（context はこのラウンドを記録する前の履歴）
+    fn record(
+        &self,
+        _context: &DecisionContext,
+        _my_action: Action,
+        _opponent_action: Action,
+        _payoff: i32,
+    ) {
+    }
+
+    // シミュレーションのリセット時に呼ばれる
+    fn reset(&self) {}
+}
+
+pub struct AllCooperateStrategy;
+pub struct AllDefectStrategy;
+pub struct TitForTatStrategy;
+pub struct PavlovStrategy;
+pub struct GrimTriggerStrategy;
+pub struct GenerousTitForTatStrategy;
+pub struct TitForTwoTatsStrategy;
+pub struct ExtortionStrategy;
+pub struct ReputationBasedStrategy;
+
+impl DecisionStrategy for AllCooperateStrategy {
+    fn name(&self) -> &str {
+        "AllCooperate"
+    }
+
+    fn decide(&self, _context: &DecisionContext) -> Action {
+        Action::Cooperate
+    }
+}
+
+impl DecisionStrategy for AllDefectStrategy {
+    fn name(&self) -> &str {
+        "AllDefect"
+    }
+
+    fn decide(&self, _context: &DecisionContext) -> Action {
+        Action::Defect
+    }
+}
+
+impl DecisionStrategy for TitForTatStrategy {
+    fn name(&self) -> &str {
+        "TitForTat"
+    }
+
+    // 初回は協力、その後は相手の前回行動をまねる
+    fn decide(&self, context: &DecisionContext) -> Action {
+        context.last_opponent_action().unwrap_or(Action::Cooperate)
+    }
+}
+
+impl DecisionStrategy for PavlovStrategy {
+    fn name(&self) -> &str {
+        "Pavlov"
+    }
+
+    // 前回 T か R を得た（相手が協力した）なら同じ行動、そうでなければ逆の行動
+    // 利得の値ではなく相手の行動で判定するので、利得表を変えても勝ち負けの境目は R のまま
+    fn decide(&self, context: &DecisionContext) -> Action {
+        match (context.last_my_action(), context.last_opponent_action()) {
+            (Some(action), Some(Action::Cooperate)) => action,
+            (Some(action), Some(Action::Defect)) => action.opposite(),
+            _ => Action::Cooperate, // 初回は協力
+        }
+    }
+}
+
+impl DecisionStrategy for GrimTriggerStrategy {
+    fn name(&self) -> &str {
+        "GrimTrigger"
+    }
+
+    // 相手が一度でも裏切ったら以後は裏切り続ける（覚えていられるのは履歴の保持件数まで）
+    fn decide(&self, context: &DecisionContext) -> Action {
+        if context
+            .rounds()
+            .iter()
+            .any(|&(_, opponent_action)| opponent_action == Action::Defect)
+        {
+            Action::Defect
+        } else {
+            Action::Cooperate
+        }
+    }
+}
+
+impl GenerousTitForTatStrategy {
+    // 利得 (T, R, P, S) = (5, 3, 1, 0) での寛容度 min(1 - (T-R)/(R-S), (R-P)/(T-P)) = 1/3
+    pub const GENEROSITY: f64 = 1.0 / 3.0;
+}
+
+impl DecisionStrategy for GenerousTitForTatStrategy {
+    fn name(&self) -> &str {
+        "GenerousTitForTat"
+    }
+
+    // しっぺ返しと同じだが、相手の裏切りを確率 GENEROSITY で許して協力する
+    fn decide(&self, context: &DecisionContext) -> Action {
+        match context.last_opponent_action() {
+            Some(Action::Defect) if context.random >= Self::GENEROSITY => Action::Defect,
+            _ => Action::Cooperate,
+        }
+    }
+
+    fn is_stochastic(&self) -> bool {
+        true
+    }
+}
+
+impl DecisionStrategy for TitForTwoTatsStrategy {
+    fn name(&self) -> &str {
+        "TitForTwoTats"
+    }
+
+    // 相手が 2 回続けて裏切ったときだけ裏切る
+    fn decide(&self, context: &DecisionContext) -> Action {
+        let rounds = context.rounds();
+        match rounds.as_slice() {
+            [.., (_, Action::Defect), (_, Action::Defect)] => Action::Defect,
+            _ => Action::Cooperate,
+        }
+    }
+}
+
+impl ExtortionStrategy {
+    // Press & Dyson の Extort-2：前回の (自分, 相手) が CC, CD, DC, DD のときの協力確率
+    pub const COOPERATION_PROBABILITIES: [f64; 4] = [8.0 / 9.0, 1.0 / 2.0, 1.0 / 3.0, 0.0];
+}
+
+impl DecisionStrategy for ExtortionStrategy {
+    fn name(&self) -> &str {
+        "Extortion"
+    }
+
+    // ゼロ行列式（ZD）戦略。自分の余剰利得が相手の 2 倍になるよう利得を支配する。初回は協力
+    fn decide(&self, context: &DecisionContext) -> Action {
+        let index = match (context.last_my_action(), context.last_opponent_action()) {
+            (Some(Action::Cooperate), Some(Action::Cooperate)) => 0,
+            (Some(Action::Cooperate), Some(Action::Defect)) => 1,
+            (Some(Action::Defect), Some(Action::Cooperate)) => 2,
+            (Some(Action::Defect), Some(Action::Defect)) => 3,
+            _ => return Action::Cooperate,
+        };
+
+        if context.random < Self::COOPERATION_PROBABILITIES[index] {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        }
+    }
+
+    fn is_stochastic(&self) -> bool {
+        true
+    }
+}
+
+impl DecisionStrategy for ReputationBasedStrategy {
+    fn name(&self) -> &str {
+        "ReputationBased"
+    }
+
+    // 間接互恵の識別者。公開の評判があればそれが負でない相手に協力する
+    // なければ自分が見た相手の協力回数と裏切り回数の差（初対面は協力）
+    fn decide(&self, context: &DecisionContext) -> Action {
+        let standing = context.opponent_reputation.unwrap_or_else(|| {
+            context
+                .rounds()
+                .iter()
+                .map(|(_, opponent)| match opponent {
+                    Action::Cooperate => 1.0,
+                    Action::Defect => -1.0,
+                })
+                .sum()
+        });
+        if standing >= 0.0 {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        }
+    }
+}
+
+// 利用者が追加した戦略。StrategyType::Custom(n) の n が登録順の番号になる
+static CUSTOM_STRATEGIES: RwLock<Vec<Arc<dyn DecisionStrategy>>> = RwLock::new(Vec::new());
+
+pub struct StrategyRegistry;
+
+impl StrategyRegistry {
+    pub const MAX_CUSTOM_STRATEGIES: usize =
+        (u8::MAX - StrategyType::CUSTOM_ID_OFFSET) as usize + 1;
+
+    // 同じ名前の戦略が登録済みならその戦略を置き換える
+    pub fn register(strategy: Arc<dyn DecisionStrategy>) -> Result<StrategyType, String> {
+        let name = strategy.name().to_string();
+        if StrategyType::ALL
+            .iter()
+            .any(|builtin| builtin.name() == name)
+        {
+            return Err(format!("'{name}' is a built-in strategy"));
+        }
+
+        let mut strategies = CUSTOM_STRATEGIES
+            .write()
+            .map_err(|_| "Strategy registry is poisoned".to_string())?;
+        if let Some(index) = strategies.iter().position(|s| s.name() == name) {
+            strategies[index] = strategy;
+            return Ok(StrategyType::Custom(index as u8));
+        }
+        if strategies.len() >= Self::MAX_CUSTOM_STRATEGIES {
+            return Err(format!(
+                "At most {} custom strategies can be registered",
+                Self::MAX_CUSTOM_STRATEGIES
+            ));
+        }
+
+        strategies.push(strategy);
+        Ok(StrategyType::Custom((strategies.len() - 1) as u8))
+    }
+
+    pub fn get(strategy: StrategyType) -> Option<Arc<dyn DecisionStrategy>> {
+        match strategy {
+            StrategyType::AllCooperate => Some(Arc::new(AllCooperateStrategy)),
+            StrategyType::AllDefect => Some(Arc::new(AllDefectStrategy)),
+            StrategyType::TitForTat => Some(Arc::new(TitForTatStrategy)),
+            StrategyType::Pavlov => Some(Arc::new(PavlovStrategy)),
+            StrategyType::GrimTrigger => Some(Arc::new(GrimTriggerStrategy)),
+            StrategyType::GenerousTitForTat => Some(Arc::new(GenerousTitForTatStrategy)),
+            StrategyType::TitForTwoTats => Some(Arc::new(TitForTwoTatsStrategy)),
+            StrategyType::Extortion => Some(Arc::new(ExtortionStrategy)),
+            StrategyType::ReputationBased => Some(Arc::new(ReputationBasedStrategy)),
+            StrategyType::Custom(index) => CUSTOM_STRATEGIES
+                .read()
+                .ok()
+                .and_then(|strategies| strategies.get(index as usize).cloned()),
+        }
+    }
+
+    pub fn find(name: &str) -> Option<StrategyType> {
+        StrategyType::ALL
+            .into_iter()
+            .chain(Self::custom_strategies())
+            .find(|strategy| strategy.name() == name)
+    }
+
+    pub fn custom_strategies() -> Vec<StrategyType> {
+        let count = CUSTOM_STRATEGIES
+            .read()
+            .map(|strategies| strategies.len())
+            .unwrap_or(0);
+        (0..count)
+            .map(|index| StrategyType::Custom(index as u8))
+            .collect()
+    }
+
+    // 渡された戦略のうち登録済みの Custom だけを、重複なく 1 回ずつリセットする
+    // 登録簿はプロセス全体で共有するので、呼び出し側は自分の使っている戦略だけを渡す
+    pub fn reset(strategies: impl IntoIterator<Item = StrategyType>) {
+        let mut indices: Vec<u8> = strategies
+            .into_iter()
+            .filter_map(|strategy| match strategy {
+                StrategyType::Custom(index) => Some(index),
+                _ => None,
+            })
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        if let Ok(registered) = CUSTOM_STRATEGIES.read() {
+            for index in indices {
+                if let Some(strategy) = registered.get(index as usize) {
+                    strategy.reset();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    // 相手が一度でも裏切ったら以後ずっと裏切る
+    struct GrimTriggerForTest;
+
+    impl DecisionStrategy for GrimTriggerForTest {
+        fn name(&self) -> &str {
+            "GrimTriggerForTest"
+        }
+
+        fn decide(&self, context: &DecisionContext) -> Action {
+            if context
+                .rounds()
+                .iter()
+                .any(|(_, opponent)| *opponent == Action::Defect)
+            {
+                Action::Defect
+            } else {
+                Action::Cooperate
+            }
+        }
+    }
+
+    struct CountingStrategy {
+        recorded: AtomicU32,
+    }
+
+    impl DecisionStrategy for CountingStrategy {
+        fn name(&self) -> &str {
+            "CountingStrategyForTest"
+        }
+
+        fn decide(&self, _context: &DecisionContext) -> Action {
+            Action::Cooperate
+        }
+
+        fn record(&self, _: &DecisionContext, _: Action, _: Action, _: i32) {
+            self.recorded.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn reset(&self) {
+            self.recorded.store(0, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_registered_strategy_decides_from_history() {
+        // Arrange
+        let strategy = StrategyRegistry::register(Arc::new(GrimTriggerForTest)).unwrap();
+        let opponent = Uuid::new_v4();
+        let mut history = GameHistory::new();
+        history.add_game(opponent, Action::Cooperate, Action::Defect, 0);
+        history.add_game(opponent, Action::Defect, Action::Cooperate, 5);
+
+        // Act
+        let action = strategy.decide(&DecisionContext::new(&history, &opponent));
+
+        // Assert: 2 ラウンド前の裏切りを覚えている
+        assert_eq!(action, Action::Defect);
+        assert_eq!(strategy.name(), "GrimTriggerForTest");
+        assert_eq!(StrategyRegistry::find("GrimTriggerForTest"), Some(strategy));
+        assert_eq!(StrategyType::from_id(strategy.id()), Some(strategy));
+    }
+
+    #[test]
+    fn test_record_and_reset_reach_custom_strategy() {
+        // Arrange
+        let counting = Arc::new(CountingStrategy {
+            recorded: AtomicU32::new(0),
+        });
+        let strategy = StrategyRegistry::register(counting.clone()).unwrap();
+        let opponent = Uuid::new_v4();
+        let history = GameHistory::new();
+        let context = DecisionContext::new(&history, &opponent);
+
+        // Act
+        strategy.record(&context, Action::Cooperate, Action::Cooperate, 3);
+        strategy.record(&context, Action::Cooperate, Action::Defect, 0);
+        let recorded = counting.recorded.load(Ordering::SeqCst);
+        StrategyRegistry::reset([StrategyType::TitForTat, strategy, strategy]);
+
+        // Assert
+        assert_eq!(recorded, 2);
+        assert_eq!(counting.recorded.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_builtin_names_cannot_be_registered() {
+        // Arrange
+        struct Impostor;
+        impl DecisionStrategy for Impostor {
+            fn name(&self) -> &str {
+                "TitForTat"
+            }
+            fn decide(&self, _context: &DecisionContext) -> Action {
+                Action::Defect
+            }
+        }
+
+        // Act & Assert
+        assert!(StrategyRegistry::register(Arc::new(Impostor)).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use super::{Action, MovementStrategy, Position, StrategyType};
+use super::{Action, DecisionContext, MemoryGenome, MovementStrategy, Position, StrategyType};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use uuid::Uuid;
@@ -11,6 +12,26 @@ pub struct Agent {
     pub mobility: f64, // 0.0 - 1.0
     pub movement_strategy: MovementStrategy,
     pub score: i32,
+    // ステップごとの獲得スコアを対戦数で割って累積したもの（密度バイアス補正用）
+    #[serde(default)]
+    pub normalized_score: f64,
+    // memory-N の行動表。ある場合は strategy の代わりにこれで行動を決める（strategy は最も近い組み込み戦略）
+    #[serde(default)]
+    pub memory_genome: Option<MemoryGenome>,
+    // エネルギー型の繁殖モデルでのみ使う（0 以下で死亡）
+    #[serde(default)]
+    pub energy: f64,
+    // 生まれてから経過したステップ数
+    #[serde(default)]
+    pub age: u32,
+    // 遺伝するタグと、同じタグの相手をひいきする強さ（0 なら常に戦略どおり）
+    #[serde(default)]
+    pub tag: u8,
+    #[serde(default)]
+    pub in_group_bias: f64,
+    // 裏切った相手を罰する確率（罰を設定したときだけ使う）
+    #[serde(default)]
+    pub aggression: f64,
     pub history: GameHistory,
 }
 
@@ -28,30 +49,86 @@ impl Agent {
             mobility: mobility.clamp(0.0, 1.0),
             movement_strategy,
             score: 0,
+            normalized_score: 0.0,
+            memory_genome: None,
+            energy: 0.0,
+            age: 0,
+            tag: 0,
+            in_group_bias: 0.0,
+            aggression: 0.0,
             history: GameHistory::new(),
         }
     }
 
-    pub fn random(position: Position) -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let movement_strategy = MovementStrategy::random();
+    pub fn random<R: Rng + ?Sized>(position: Position, rng: &mut R) -> Self {
+        let movement_strategy = MovementStrategy::random(rng);
+        let strategy = StrategyType::random(rng);
+        let mobility = movement_strategy.default_mobility() + rng.gen_range(-0.2..=0.2);
 
-        Self::new(
-            position,
-            StrategyType::random(),
-            movement_strategy.default_mobility() + rng.gen_range(-0.2..=0.2),
-            movement_strategy,
-        )
+        Self::new(position, strategy, mobility, movement_strategy).with_random_id(rng)
+    }
+
+    // シード付き乱数から ID を振り直す（同じシードで同じ ID 列になる）
+    pub fn with_random_id<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
+        self.id = uuid::Builder::from_random_bytes(rng.gen()).into_uuid();
+        self
+    }
+
+    pub fn with_memory_genome(mut self, genome: MemoryGenome) -> Self {
+        self.strategy = genome.classify();
+        self.memory_genome = Some(genome);
+        self
     }
 
-    pub fn decide_action(&self, opponent_id: &Uuid) -> Action {
-        let last_opponent_action = self.history.get_last_opponent_action(opponent_id);
-        let last_my_action = self.history.get_last_my_action(opponent_id);
-        let last_payoff = self.history.get_last_payoff(opponent_id);
+    pub fn with_tag(mut self, tag: u8, in_group_bias: f64) -> Self {
+        self.tag = tag;
+        self.in_group_bias = in_group_bias.clamp(0.0, 1.0);
+        self
+    }
 
-        self.strategy
-            .decide_action(last_opponent_action, last_my_action, last_payoff)
+    pub fn with_aggression(mut self, aggression: f64) -> Self {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self
+    }
+
+    // 確率的な戦略のときだけ乱数を引く（決定的な戦略では乱数列を消費しない）
+    pub fn decide_action<R: Rng + ?Sized>(&self, opponent_id: &Uuid, rng: &mut R) -> Action {
+        self.decide_with(DecisionContext::new(&self.history, opponent_id), rng)
+    }
+
+    // 1 対 1 の対戦用。in_group_bias の確率で、同じタグなら協力・違うタグなら裏切り、それ以外は戦略どおり
+    // びいきがなければ乱数を引かない。戦略には相手と同じタグかどうかと、公開の評判（あれば）も渡す
+    pub fn decide_action_toward<R: Rng + ?Sized>(
+        &self,
+        opponent: &Agent,
+        opponent_reputation: Option<f64>,
+        rng: &mut R,
+    ) -> Action {
+        let same_tag = self.tag == opponent.tag;
+        if self.in_group_bias > 0.0 && rng.gen::<f64>() < self.in_group_bias {
+            return if same_tag {
+                Action::Cooperate
+            } else {
+                Action::Defect
+            };
+        }
+        let context = DecisionContext::new(&self.history, &opponent.id)
+            .with_same_tag(same_tag)
+            .with_opponent_reputation(opponent_reputation);
+        self.decide_with(context, rng)
+    }
+
+    fn decide_with<R: Rng + ?Sized>(&self, mut context: DecisionContext, rng: &mut R) -> Action {
+        if let Some(genome) = &self.memory_genome {
+            if !genome.is_deterministic() {
+                context = context.with_random(rng.gen());
+            }
+            return genome.decide(&context);
+        }
+        if self.strategy.is_stochastic() {
+            context = context.with_random(rng.gen());
+        }
+        self.strategy.decide(&context)
     }
 
     pub fn add_game_result(
@@ -63,25 +140,23 @@ impl Agent {
     ) {
         self.score += payoff;
         self.history
-            .add_game(opponent_id, my_action, opponent_action, payoff);
+            .add_game_at(self.age, opponent_id, my_action, opponent_action, payoff);
     }
 
     pub fn cooperation_rate(&self) -> f64 {
         self.history.cooperation_rate()
     }
 
-    pub fn should_move(&self) -> bool {
-        self.should_move_with_neighbors(&[], &[])
+    pub fn should_move<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        self.should_move_with_neighbors(&[], &[], rng)
     }
 
-    pub fn should_move_with_neighbors(
+    pub fn should_move_with_neighbors<R: Rng + ?Sized>(
         &self,
         neighbor_agents: &[&Agent],
         neighbor_strategies: &[StrategyType],
+        rng: &mut R,
     ) -> bool {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
         let base_probability = self.mobility;
         let recent_performance = self.history.recent_performance();
 
@@ -152,19 +227,34 @@ impl Agent {
 
                 (different_strategy_count as f64 * base_probability * 0.3).min(0.9)
             }
+            MovementStrategy::Seeker => {
+                // 移動判定はGridServiceで周囲の協力度から決定的に行う
+                0.0
+            }
         };
 
         rng.gen::<f64>() < move_probability.clamp(0.0, 1.0)
     }
 
+    // 個体が別に確保しているヒープ領域の概算（対戦履歴のバッファと行動表）
+    pub fn heap_bytes(&self) -> usize {
+        self.history.buffer_capacity() * std::mem::size_of::<GameRecord>()
+            + self
+                .memory_genome
+                .as_ref()
+                .map_or(0, MemoryGenome::heap_bytes)
+    }
+
     pub fn move_to(&mut self, new_position: Position) {
         self.position = new_position;
     }
 
-    pub fn crossover(parent1: &Agent, parent2: &Agent, position: Position) -> Agent {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
+    pub fn crossover<R: Rng + ?Sized>(
+        parent1: &Agent,
+        parent2: &Agent,
+        position: Position,
+        rng: &mut R,
+    ) -> Agent {
         let strategy = if rng.gen_bool(0.5) {
             parent1.strategy
         } else {
@@ -173,6 +263,15 @@ impl Agent {
 
         let mobility = (parent1.mobility + parent2.mobility) / 2.0;
 
+        // タグは親のどちらかから（同じなら乱数を引かない）、ひいきの強さは平均
+        let tag = if parent1.tag == parent2.tag || rng.gen_bool(0.5) {
+            parent1.tag
+        } else {
+            parent2.tag
+        };
+        let in_group_bias = (parent1.in_group_bias + parent2.in_group_bias) / 2.0;
+        let aggression = (parent1.aggression + parent2.aggression) / 2.0;
+
         // 移動戦略の継承：75%で親から、25%で新規ランダム
         let movement_strategy = if rng.gen_bool(0.75) {
             if rng.gen_bool(0.5) {
@@ -181,21 +280,25 @@ impl Agent {
                 parent2.movement_strategy
             }
         } else {
-            MovementStrategy::random()
+            MovementStrategy::random(rng)
         };
 
         Agent::new(position, strategy, mobility, movement_strategy)
+            .with_tag(tag, in_group_bias)
+            .with_aggression(aggression)
+            .with_random_id(rng)
     }
 
-    pub fn mutate(&mut self) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+    pub fn mutate<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.mutate_with_rate(0.05, rng);
+    }
 
-        if rng.gen_bool(0.05) {
-            // 5%の確率で突然変異
+    pub fn mutate_with_rate<R: Rng + ?Sized>(&mut self, mutation_rate: f64, rng: &mut R) {
+        if rng.gen_bool(mutation_rate.clamp(0.0, 1.0)) {
+            // mutation_rate の確率で突然変異
             // 戦略の突然変異
             if rng.gen_bool(0.5) {
-                self.strategy = StrategyType::random();
+                self.strategy = StrategyType::random(rng);
             }
 
             // 移動性向の突然変異
@@ -205,7 +308,7 @@ impl Agent {
             // 移動戦略の突然変異
             if rng.gen_bool(0.3) {
                 // 30%の確率で移動戦略も変異
-                self.movement_strategy = MovementStrategy::random();
+                self.movement_strategy = MovementStrategy::random(rng);
             }
         }
     }
@@ -215,6 +318,12 @@ impl Agent {
 pub struct GameHistory {
     games: VecDeque<GameRecord>,
     max_history: usize,
+    // これまでに記録した対戦数。次の対戦の round になる
+    #[serde(default)]
+    recorded: u64,
+    // 上限や保持方針で捨てた記録のうち、まだ集計されていない件数（take_evicted で 0 に戻る）
+    #[serde(default)]
+    evicted: u64,
 }
 
 impl Default for GameHistory {
@@ -224,10 +333,14 @@ impl Default for GameHistory {
 }
 
 impl GameHistory {
+    pub const DEFAULT_MAX_HISTORY: usize = 10;
+
     pub fn new() -> Self {
         Self {
             games: VecDeque::new(),
-            max_history: 10,
+            max_history: Self::DEFAULT_MAX_HISTORY,
+            recorded: 0,
+            evicted: 0,
         }
     }
 
@@ -238,16 +351,56 @@ impl GameHistory {
         opponent_action: Action,
         payoff: i32,
     ) {
-        if self.games.len() >= self.max_history {
-            self.games.pop_front();
+        self.add_game_at(0, opponent_id, my_action, opponent_action, payoff);
+    }
+
+    // age は対戦したときの持ち主の年齢（ステップ数）。直近 N ステップだけを残す保持方針で使う
+    pub fn add_game_at(
+        &mut self,
+        age: u32,
+        opponent_id: Uuid,
+        my_action: Action,
+        opponent_action: Action,
+        payoff: i32,
+    ) {
+        // 上限 0 なら何も残さない（番号だけ進める）。捨てた件数は実際に取り除いた記録だけを数える
+        if self.max_history == 0 {
+            self.recorded += 1;
+            return;
+        }
+        if self.games.len() >= self.max_history && self.games.pop_front().is_some() {
+            self.evicted += 1;
         }
 
         self.games.push_back(GameRecord {
+            round: self.recorded,
+            age,
             opponent_id,
             my_action,
             opponent_action,
             payoff,
+            punished: false,
+            punished_by_opponent: false,
         });
+        self.recorded += 1;
+    }
+
+    // 対戦後の罰を、その相手との最新の記録に書き込む（記録が捨てられていれば何もしない）
+    pub fn mark_last_punishment(
+        &mut self,
+        opponent_id: &Uuid,
+        punished: bool,
+        punished_by_opponent: bool,
+    ) {
+        if let Some(game) = self
+            .games
+            .iter_mut()
+            .rev()
+            .find(|game| &game.opponent_id == opponent_id)
+        {
+            game.punished = punished;
+            game.punished_by_opponent = punished_by_opponent;
+        }
     }
 
     pub fn get_last_opponent_action(&self, opponent_id: &Uuid) -> Option<Action> {
@@ -274,6 +427,73 @@ impl GameHistory {
             .map(|game| game.payoff)
     }
 
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    // 上限を変え、超えた分は古い記録から捨てる
+    pub fn set_max_history(&mut self, max_history: usize) {
+        self.max_history = max_history;
+        while self.games.len() > max_history {
+            self.games.pop_front();
+            self.evicted += 1;
+        }
+    }
+
+    pub fn buffer_capacity(&self) -> usize {
+        self.games.capacity()
+    }
+
+    // 空の履歴に、捨てる個体の履歴のバッファを使い回す（容量だけを引き継ぎ、記録と数え上げは引き継がない）
+    pub fn reuse_buffer(&mut self, previous: GameHistory) {
+        let mut games = previous.games;
+        if self.games.is_empty() && games.capacity() > self.games.capacity() {
+            games.clear();
+            self.games = games;
+        }
+    }
+
+    // 年齢 min_age より前の対戦の記録を捨てる
+    pub fn retain_since(&mut self, min_age: u32) {
+        let before = self.games.len();
+        self.games.retain(|game| game.age >= min_age);
+        self.evicted += (before - self.games.len()) as u64;
+    }
+
+    // 前回の呼び出し以降に捨てた記録の件数
+    pub fn take_evicted(&mut self) -> u64 {
+        std::mem::take(&mut self.evicted)
+    }
+
+    // 記録に残っている対戦（古い順）
+    pub fn records(&self) -> impl DoubleEndedIterator<Item = &GameRecord> {
+        self.games.iter()
+    }
+
+    // 記録に残っている対戦相手（初出順、重複なし）
+    pub fn opponent_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = Vec::new();
+        for game in &self.games {
+            if !ids.contains(&game.opponent_id) {
+                ids.push(game.opponent_id);
+            }
+        }
+        ids
+    }
+
+    // 特定の相手との (自分の行動, 相手の行動) を古い順に返す
+    pub fn rounds_against(&self, opponent_id: &Uuid) -> Vec<(Action, Action)> {
+        self.games
+            .iter()
+            .filter(|game| &game.opponent_id == opponent_id)
+            .map(|game| (game.my_action, game.opponent_action))
+            .collect()
+    }
+
     pub fn cooperation_rate(&self) -> f64 {
         if self.games.is_empty() {
             0.5 // デフォルト値
@@ -298,10 +518,21 @@ impl GameHistory {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct GameRecord {
-    opponent_id: Uuid,
-    my_action: Action,
-    opponent_action: Action,
-    payoff: i32,
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    // その個体にとって何戦目か（0 始まり。古い記録が捨てられても番号は詰めない）
+    #[serde(default)]
+    pub round: u64,
+    // 対戦したときの持ち主の年齢（ステップ数）
+    #[serde(default)]
+    pub age: u32,
+    pub opponent_id: Uuid,
+    pub my_action: Action,
+    pub opponent_action: Action,
+    pub payoff: i32,
+    // 対戦後に持ち主が相手を罰したか・相手に罰されたか（payoff には含めない）
+    #[serde(default)]
+    pub punished: bool,
+    #[serde(default)]
+    pub punished_by_opponent: bool,
 }
@@ -0,0 +1,188 @@
+use super::{Agent, MemoryGenome, MovementStrategy, Position, StrategyType};
+use serde::{Deserialize, Serialize};
+
+// エージェントの遺伝的形質（位置・スコア・履歴を除いた部分）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Genotype {
+    pub strategy: StrategyType,
+    pub movement_strategy: MovementStrategy,
+    pub mobility: f64,
+    #[serde(default)]
+    pub memory_genome: Option<MemoryGenome>,
+    #[serde(default)]
+    pub tag: u8,
+    #[serde(default)]
+    pub in_group_bias: f64,
+    #[serde(default)]
+    pub aggression: f64,
+}
+
+impl Genotype {
+    pub fn new(strategy: StrategyType, movement_strategy: MovementStrategy, mobility: f64) -> Self {
+        Self {
+            strategy,
+            movement_strategy,
+            mobility: mobility.clamp(0.0, 1.0),
+            memory_genome: None,
+            tag: 0,
+            in_group_bias: 0.0,
+            aggression: 0.0,
+        }
+    }
+
+    pub fn of(agent: &Agent) -> Self {
+        Self {
+            memory_genome: agent.memory_genome.clone(),
+            tag: agent.tag,
+            in_group_bias: agent.in_group_bias,
+            aggression: agent.aggression,
+            ..Self::new(agent.strategy, agent.movement_strategy, agent.mobility)
+        }
+    }
+
+    // 外部からの編集用。new と違い範囲外の移動性は丸めずにエラーにする
+    pub fn with_movement(
+        mut self,
+        movement_strategy: MovementStrategy,
+        mobility: f64,
+    ) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&mobility) {
+            return Err(format!("Mobility must be within 0.0-1.0, got {mobility}"));
+        }
+        self.movement_strategy = movement_strategy;
+        self.mobility = mobility;
+        Ok(self)
+    }
+
+    // 組み込み戦略に差し替える（行動表は捨てる）
+    pub fn with_strategy(mut self, strategy: StrategyType) -> Self {
+        self.strategy = strategy;
+        self.memory_genome = None;
+        self
+    }
+
+    // 行動表に差し替え、戦略は表から分類し直す
+    pub fn with_memory_genome(mut self, genome: MemoryGenome) -> Self {
+        self.strategy = genome.classify();
+        self.memory_genome = Some(genome);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: u8, in_group_bias: f64) -> Self {
+        self.tag = tag;
+        self.in_group_bias = in_group_bias.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_aggression(mut self, aggression: f64) -> Self {
+        self.aggression = aggression.clamp(0.0, 1.0);
+        self
+    }
+
+    // 形質の距離（0〜1）。戦略・移動戦略・タグは一致しなければ 1、連続値の形質は差の絶対値で、
+    // その平均を取る。記憶長の等しい行動表同士は戦略の代わりに表の値の平均の差を使う
+    pub fn distance(&self, other: &Genotype) -> f64 {
+        let mismatch = |same: bool| if same { 0.0 } else { 1.0 };
+        let strategy = match (&self.memory_genome, &other.memory_genome) {
+            (Some(a), Some(b)) if a.memory() == b.memory() => {
+                let differences = std::iter::once((a.initial() - b.initial()).abs())
+                    .chain(a.table().iter().zip(b.table()).map(|(x, y)| (x - y).abs()));
+                differences.sum::<f64>() / (1 + a.table().len()) as f64
+            }
+            _ => mismatch(self.strategy == other.strategy),
+        };
+        let components = [
+            strategy,
+            mismatch(self.movement_strategy == other.movement_strategy),
+            (self.mobility - other.mobility).abs(),
+            mismatch(self.tag == other.tag),
+            (self.in_group_bias - other.in_group_bias).abs(),
+            (self.aggression - other.aggression).abs(),
+        ];
+        components.iter().sum::<f64>() / components.len() as f64
+    }
+
+    pub fn spawn(&self, position: Position) -> Agent {
+        let mut agent = Agent::new(
+            position,
+            self.strategy,
+            self.mobility,
+            self.movement_strategy,
+        )
+        .with_tag(self.tag, self.in_group_bias)
+        .with_aggression(self.aggression);
+        agent.memory_genome = self.memory_genome.clone();
+        agent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genotype_round_trip_keeps_memory_genome() {
+        // Arrange
+        let genome = MemoryGenome::from_strategy(StrategyType::TitForTat, 1).unwrap();
+        let agent = Agent::new(
+            Position::new(2, 3),
+            StrategyType::TitForTat,
+            0.4,
+            MovementStrategy::Explorer,
+        )
+        .with_memory_genome(genome.clone());
+
+        // Act
+        let spawned = Genotype::of(&agent).spawn(Position::new(0, 0));
+
+        // Assert
+        assert_eq!(spawned.memory_genome, Some(genome));
+        assert_eq!(Genotype::of(&spawned), Genotype::of(&agent));
+    }
+
+    #[test]
+    fn test_edits_validate_mobility_and_keep_strategy_consistent() {
+        // Arrange
+        let genotype = Genotype::new(StrategyType::AllCooperate, MovementStrategy::Settler, 0.0);
+        let genome = MemoryGenome::from_strategy(StrategyType::AllDefect, 1).unwrap();
+
+        // Act
+        let moved = genotype
+            .clone()
+            .with_movement(MovementStrategy::Explorer, 0.7);
+        let invalid = genotype
+            .clone()
+            .with_movement(MovementStrategy::Explorer, 1.5);
+        let evolved = genotype.with_memory_genome(genome);
+        let builtin = evolved.clone().with_strategy(StrategyType::TitForTat);
+
+        // Assert
+        assert_eq!(moved.unwrap().mobility, 0.7);
+        assert!(invalid.is_err());
+        assert_eq!(evolved.strategy, StrategyType::AllDefect);
+        assert_eq!(builtin.memory_genome, None);
+        assert_eq!(builtin.strategy, StrategyType::TitForTat);
+    }
+
+    #[test]
+    fn test_distance_averages_trait_differences() {
+        // Arrange
+        let base = Genotype::new(StrategyType::TitForTat, MovementStrategy::Settler, 0.2);
+        let other_strategy = base.clone().with_strategy(StrategyType::AllDefect);
+        let faster = Genotype::new(StrategyType::TitForTat, MovementStrategy::Settler, 0.8);
+        let cooperator = MemoryGenome::from_strategy(StrategyType::AllCooperate, 1).unwrap();
+        let defector = MemoryGenome::from_strategy(StrategyType::AllDefect, 1).unwrap();
+
+        // Act
+        let tables = base
+            .clone()
+            .with_memory_genome(cooperator)
+            .distance(&base.clone().with_memory_genome(defector));
+
+        // Assert: 6 成分の平均
+        assert_eq!(base.distance(&base), 0.0);
+        assert!((base.distance(&other_strategy) - 1.0 / 6.0).abs() < 1e-12);
+        assert!((base.distance(&faster) - 0.6 / 6.0).abs() < 1e-12);
+        assert!((tables - 1.0 / 6.0).abs() < 1e-12);
+    }
+}
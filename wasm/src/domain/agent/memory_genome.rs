@@ -0,0 +1,251 @@
+use super::{Action, DecisionContext, StrategyType};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 直近 memory ラウンドの (自分, 相手) の行動から協力確率を引く表（memory-N 戦略）
+// 状態番号は古いラウンドから順に CC=0, CD=1, DC=2, DD=3 を 4 進数で並べたもの
+// memory-one なら table = [CC, CD, DC, DD] の後の協力確率。0/1 だけなら決定的な戦略（ビット列）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryGenome {
+    memory: usize,
+    initial: f64, // 履歴が memory ラウンドに満たない間の協力確率
+    table: Vec<f64>,
+}
+
+impl MemoryGenome {
+    pub const MAX_MEMORY: usize = 2;
+
+    pub fn new(memory: usize, initial: f64, table: Vec<f64>) -> Result<Self, String> {
+        if !(1..=Self::MAX_MEMORY).contains(&memory) {
+            return Err(format!(
+                "Memory length must be between 1 and {}, got {memory}",
+                Self::MAX_MEMORY
+            ));
+        }
+        if table.len() != Self::state_count(memory) {
+            return Err(format!(
+                "Memory-{memory} table needs {} entries, got {}",
+                Self::state_count(memory),
+                table.len()
+            ));
+        }
+        if std::iter::once(&initial)
+            .chain(&table)
+            .any(|p| !(0.0..=1.0).contains(p))
+        {
+            return Err("Cooperation probabilities must be within 0.0-1.0".to_string());
+        }
+
+        Ok(Self {
+            memory,
+            initial,
+            table,
+        })
+    }
+
+    pub fn state_count(memory: usize) -> usize {
+        4usize.pow(memory as u32)
+    }
+
+    // すべての状態で協力か裏切りかを一様に選んだ決定的な表
+    pub fn random_deterministic<R: Rng + ?Sized>(
+        memory: usize,
+        rng: &mut R,
+    ) -> Result<Self, String> {
+        let mut bit = || if rng.gen_bool(0.5) { 1.0 } else { 0.0 };
+        let initial = bit();
+        let table = (0..Self::state_count(memory.clamp(1, Self::MAX_MEMORY)))
+            .map(|_| bit())
+            .collect();
+        Self::new(memory, initial, table)
+    }
+
+    // memory-one で表せる組み込み戦略を memory ラウンドの表に展開する（直前のラウンドだけを見る）
+    pub fn from_strategy(strategy: StrategyType, memory: usize) -> Option<Self> {
+        let (initial, last_round) = Self::memory_one(strategy)?;
+        let table = (0..Self::state_count(memory.clamp(1, Self::MAX_MEMORY)))
+            .map(|state| last_round[state % 4])
+            .collect();
+        Self::new(memory, initial, table).ok()
+    }
+
+    // (初手の協力確率, [CC, CD, DC, DD] の後の協力確率)
+    fn memory_one(strategy: StrategyType) -> Option<(f64, [f64; 4])> {
+        match strategy {
+            StrategyType::AllCooperate => Some((1.0, [1.0, 1.0, 1.0, 1.0])),
+            StrategyType::AllDefect => Some((0.0, [0.0, 0.0, 0.0, 0.0])),
+            StrategyType::TitForTat => Some((1.0, [1.0, 0.0, 1.0, 0.0])),
+            StrategyType::Pavlov => Some((1.0, [1.0, 0.0, 0.0, 1.0])),
+            StrategyType::GrimTrigger => Some((1.0, [1.0, 0.0, 0.0, 0.0])),
+            StrategyType::GenerousTitForTat => Some((1.0, [1.0, 1.0 / 3.0, 1.0, 1.0 / 3.0])),
+            StrategyType::Extortion => Some((1.0, [8.0 / 9.0, 1.0 / 2.0, 1.0 / 3.0, 0.0])),
+            StrategyType::TitForTwoTats
+            | StrategyType::ReputationBased
+            | StrategyType::Custom(_) => None,
+        }
+    }
+
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    pub fn initial(&self) -> f64 {
+        self.initial
+    }
+
+    pub fn table(&self) -> &[f64] {
+        &self.table
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        std::iter::once(&self.initial)
+            .chain(&self.table)
+            .all(|&p| p == 0.0 || p == 1.0)
+    }
+
+    // 決定的な表を "初手|表" のビット列で表す（例：しっぺ返しは "1|1010"）
+    pub fn to_bits(&self) -> Option<String> {
+        if !self.is_deterministic() {
+            return None;
+        }
+        let bit = |p: f64| if p == 1.0 { '1' } else { '0' };
+        Some(format!(
+            "{}|{}",
+            bit(self.initial),
+            self.table.iter().map(|&p| bit(p)).collect::<String>()
+        ))
+    }
+
+    pub fn decide(&self, context: &DecisionContext) -> Action {
+        let rounds = context.rounds();
+        let probability = if rounds.len() < self.memory {
+            self.initial
+        } else {
+            let state = rounds[rounds.len() - self.memory..]
+                .iter()
+                .fold(0, |state, &round| state * 4 + Self::round_code(round));
+            self.table[state]
+        };
+
+        if context.random < probability {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        }
+    }
+
+    // 統計・表示用に、直前ラウンドだけに縮約した表が最も近い組み込み戦略
+    pub fn classify(&self) -> StrategyType {
+        let mut projected = [0.0; 4];
+        for (state, &p) in self.table.iter().enumerate() {
+            projected[state % 4] += p;
+        }
+        let older_states = (self.table.len() / 4) as f64;
+        projected.iter_mut().for_each(|p| *p /= older_states);
+
+        let distance = |strategy: &StrategyType| {
+            Self::memory_one(*strategy).map_or(f64::INFINITY, |(initial, table)| {
+                (initial - self.initial).powi(2)
+                    + table
+                        .iter()
+                        .zip(&projected)
+                        .map(|(a, b)| (a - b).powi(2))
+                        .sum::<f64>()
+            })
+        };
+        StrategyType::ALL
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(StrategyType::AllCooperate)
+    }
+
+    pub(crate) fn entries_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+        std::iter::once(&mut self.initial).chain(self.table.iter_mut())
+    }
+
+    fn round_code((my_action, opponent_action): (Action, Action)) -> usize {
+        match (my_action, opponent_action) {
+            (Action::Cooperate, Action::Cooperate) => 0,
+            (Action::Cooperate, Action::Defect) => 1,
+            (Action::Defect, Action::Cooperate) => 2,
+            (Action::Defect, Action::Defect) => 3,
+        }
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.table.capacity() * std::mem::size_of::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::GameHistory;
+    use uuid::Uuid;
+
+    fn decide(genome: &MemoryGenome, rounds: &[(Action, Action)]) -> Action {
+        let opponent = Uuid::new_v4();
+        let mut history = GameHistory::new();
+        for &(my_action, opponent_action) in rounds {
+            history.add_game(opponent, my_action, opponent_action, 0);
+        }
+        genome.decide(&DecisionContext::new(&history, &opponent))
+    }
+
+    #[test]
+    fn test_memory_one_tit_for_tat_table() {
+        // Arrange
+        let genome = MemoryGenome::from_strategy(StrategyType::TitForTat, 1).unwrap();
+
+        // Act & Assert
+        assert_eq!(genome.to_bits().as_deref(), Some("1|1010"));
+        assert_eq!(decide(&genome, &[]), Action::Cooperate);
+        assert_eq!(
+            decide(&genome, &[(Action::Cooperate, Action::Defect)]),
+            Action::Defect
+        );
+        assert_eq!(genome.classify(), StrategyType::TitForTat);
+    }
+
+    #[test]
+    fn test_memory_two_uses_both_rounds() {
+        // Arrange: 相手が 2 回続けて裏切ったとき (CD, CD) = 状態 1*4+1 = 5 だけ裏切る
+        let mut table = vec![1.0; 16];
+        table[5] = 0.0;
+        let genome = MemoryGenome::new(2, 1.0, table).unwrap();
+
+        // Act & Assert
+        assert_eq!(
+            decide(&genome, &[(Action::Cooperate, Action::Defect)]),
+            Action::Cooperate // 1 ラウンドしかないので初手の確率
+        );
+        assert_eq!(
+            decide(
+                &genome,
+                &[
+                    (Action::Cooperate, Action::Cooperate),
+                    (Action::Cooperate, Action::Defect)
+                ]
+            ),
+            Action::Cooperate
+        );
+        assert_eq!(
+            decide(
+                &genome,
+                &[
+                    (Action::Cooperate, Action::Defect),
+                    (Action::Cooperate, Action::Defect)
+                ]
+            ),
+            Action::Defect
+        );
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_tables() {
+        // Arrange & Act & Assert
+        assert!(MemoryGenome::new(3, 1.0, vec![1.0; 64]).is_err());
+        assert!(MemoryGenome::new(1, 1.0, vec![1.0; 3]).is_err());
+        assert!(MemoryGenome::new(1, 1.5, vec![1.0; 4]).is_err());
+    }
+}
@@ -1,9 +1,17 @@
+pub mod decision;
 pub mod entity;
+pub mod genotype;
+pub mod memory_genome;
 pub mod movement_strategy;
 pub mod position;
 pub mod strategy;
+pub mod tag;
 
+pub use decision::*;
 pub use entity::*;
+pub use genotype::*;
+pub use memory_genome::*;
 pub use movement_strategy::*;
 pub use position::*;
 pub use strategy::*;
+pub use tag::*;
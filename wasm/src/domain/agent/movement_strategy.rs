@@ -12,6 +12,7 @@ pub enum MovementStrategy {
     Opportunist, // 機会主義：隣接の協力率を見て移動
     Social,      // 社会的：同じ戦略の仲間に近づく
     Antisocial,  // 反社会的：異なる戦略から離れる
+    Seeker,      // 探索的：協力的な隣人が最も多い空きセルへ決定的に移動
 }
 
 impl fmt::Display for MovementStrategy {
@@ -23,21 +24,48 @@ impl fmt::Display for MovementStrategy {
             MovementStrategy::Opportunist => "Opportunist",
             MovementStrategy::Social => "Social",
             MovementStrategy::Antisocial => "Antisocial",
+            MovementStrategy::Seeker => "Seeker",
         };
         write!(f, "{s}")
     }
 }
 
 impl MovementStrategy {
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
-        match rng.gen_range(0..6) {
+    pub const ALL: [MovementStrategy; 7] = [
+        MovementStrategy::Explorer,
+        MovementStrategy::Settler,
+        MovementStrategy::Adaptive,
+        MovementStrategy::Opportunist,
+        MovementStrategy::Social,
+        MovementStrategy::Antisocial,
+        MovementStrategy::Seeker,
+    ];
+
+    pub fn id(&self) -> u8 {
+        match self {
+            MovementStrategy::Explorer => 0,
+            MovementStrategy::Settler => 1,
+            MovementStrategy::Adaptive => 2,
+            MovementStrategy::Opportunist => 3,
+            MovementStrategy::Social => 4,
+            MovementStrategy::Antisocial => 5,
+            MovementStrategy::Seeker => 6,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        match rng.gen_range(0..7) {
             0 => MovementStrategy::Explorer,
             1 => MovementStrategy::Settler,
             2 => MovementStrategy::Adaptive,
             3 => MovementStrategy::Opportunist,
             4 => MovementStrategy::Social,
-            _ => MovementStrategy::Antisocial,
+            5 => MovementStrategy::Antisocial,
+            _ => MovementStrategy::Seeker,
         }
     }
 
@@ -49,8 +77,13 @@ impl MovementStrategy {
             MovementStrategy::Opportunist => 0.4, // やや低い基本移動性
             MovementStrategy::Social => 0.6,      // やや高い基本移動性
             MovementStrategy::Antisocial => 0.7,  // 高い基本移動性
+            MovementStrategy::Seeker => 0.3,      // 移動判定は周囲の状態で決まるため参考値
         }
     }
+
+    pub fn is_deterministic(&self) -> bool {
+        matches!(self, MovementStrategy::Seeker)
+    }
 }
 
 // wasm_bindgen methods for MovementStrategy
@@ -61,12 +94,12 @@ pub fn movement_strategy_to_string(strategy: MovementStrategy) -> String {
 
 #[wasm_bindgen]
 pub fn movement_strategy_random() -> MovementStrategy {
-    MovementStrategy::random()
+    MovementStrategy::random(&mut rand::thread_rng())
 }
 
 #[wasm_bindgen]
 pub fn movement_strategy_variant_count() -> u32 {
-    6
+    MovementStrategy::ALL.len() as u32
 }
 
 #[cfg(test)]
@@ -76,7 +109,7 @@ mod tests {
     #[test]
     fn test_movement_strategy_random_returns_valid_strategy() {
         // Arrange & Act
-        let strategy = MovementStrategy::random();
+        let strategy = MovementStrategy::random(&mut rand::thread_rng());
 
         // Assert
         assert!(matches!(
@@ -87,6 +120,7 @@ mod tests {
                 | MovementStrategy::Opportunist
                 | MovementStrategy::Social
                 | MovementStrategy::Antisocial
+                | MovementStrategy::Seeker
         ));
     }
 
@@ -99,6 +133,7 @@ mod tests {
         assert_eq!(MovementStrategy::Opportunist.default_mobility(), 0.4);
         assert_eq!(MovementStrategy::Social.default_mobility(), 0.6);
         assert_eq!(MovementStrategy::Antisocial.default_mobility(), 0.7);
+        assert_eq!(MovementStrategy::Seeker.default_mobility(), 0.3);
     }
 
     #[test]
@@ -110,6 +145,16 @@ mod tests {
         assert_eq!(MovementStrategy::Opportunist.to_string(), "Opportunist");
         assert_eq!(MovementStrategy::Social.to_string(), "Social");
         assert_eq!(MovementStrategy::Antisocial.to_string(), "Antisocial");
+        assert_eq!(MovementStrategy::Seeker.to_string(), "Seeker");
+    }
+
+    #[test]
+    fn test_movement_strategy_id_round_trip() {
+        // Arrange & Act & Assert
+        for strategy in MovementStrategy::ALL {
+            assert_eq!(MovementStrategy::from_id(strategy.id()), Some(strategy));
+        }
+        assert_eq!(MovementStrategy::from_id(7), None);
     }
 
     #[test]
@@ -132,7 +177,8 @@ mod tests {
                 | MovementStrategy::Opportunist
                 | MovementStrategy::Social
                 | MovementStrategy::Antisocial
+                | MovementStrategy::Seeker
         ));
-        assert_eq!(count_result, 6);
+        assert_eq!(count_result, 7);
     }
 }
@@ -53,13 +53,16 @@ impl Position {
         neighbors
     }
 
-    pub fn random_neighbor(&self, grid_width: usize, grid_height: usize) -> Option<Position> {
+    pub fn random_neighbor<R: rand::Rng + ?Sized>(
+        &self,
+        grid_width: usize,
+        grid_height: usize,
+        rng: &mut R,
+    ) -> Option<Position> {
         let neighbors = self.neighbors(grid_width, grid_height);
         if neighbors.is_empty() {
             None
         } else {
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
             Some(neighbors[rng.gen_range(0..neighbors.len())])
         }
     }
@@ -174,7 +177,7 @@ mod tests {
         let grid_height = 10;
 
         // Act
-        let neighbor = position.random_neighbor(grid_width, grid_height);
+        let neighbor = position.random_neighbor(grid_width, grid_height, &mut rand::thread_rng());
 
         // Assert: 返された隣接位置が有効な隣接位置に含まれる
         assert!(neighbor.is_some());
@@ -1,53 +1,191 @@
+use super::{
+    AllCooperateStrategy, AllDefectStrategy, DecisionContext, DecisionStrategy, ExtortionStrategy,
+    GameHistory, GenerousTitForTatStrategy, GrimTriggerStrategy, PavlovStrategy,
+    ReputationBasedStrategy, StrategyRegistry, TitForTatStrategy, TitForTwoTatsStrategy,
+};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+// 組み込み戦略と、StrategyRegistry に登録された利用者定義の戦略 Custom(n)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum StrategyType {
     AllCooperate,
     AllDefect,
     TitForTat,
     Pavlov,
+    GrimTrigger,
+    GenerousTitForTat,
+    TitForTwoTats,
+    Extortion,
+    ReputationBased,
+    Custom(u8),
 }
 
 impl StrategyType {
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
-        match rng.gen_range(0..4) {
-            0 => StrategyType::AllCooperate,
-            1 => StrategyType::AllDefect,
-            2 => StrategyType::TitForTat,
-            _ => StrategyType::Pavlov,
+    // 組み込み戦略のみ（ランダム生成・突然変異の対象）
+    pub const ALL: [StrategyType; 9] = [
+        StrategyType::AllCooperate,
+        StrategyType::AllDefect,
+        StrategyType::TitForTat,
+        StrategyType::Pavlov,
+        StrategyType::GrimTrigger,
+        StrategyType::GenerousTitForTat,
+        StrategyType::TitForTwoTats,
+        StrategyType::Extortion,
+        StrategyType::ReputationBased,
+    ];
+
+    // Custom(n) の id は CUSTOM_ID_OFFSET + n
+    pub const CUSTOM_ID_OFFSET: u8 = 64;
+
+    pub fn id(&self) -> u8 {
+        match self {
+            StrategyType::AllCooperate => 0,
+            StrategyType::AllDefect => 1,
+            StrategyType::TitForTat => 2,
+            StrategyType::Pavlov => 3,
+            StrategyType::GrimTrigger => 4,
+            StrategyType::GenerousTitForTat => 5,
+            StrategyType::TitForTwoTats => 6,
+            StrategyType::Extortion => 7,
+            StrategyType::ReputationBased => 8,
+            StrategyType::Custom(index) => Self::CUSTOM_ID_OFFSET.saturating_add(*index),
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id.checked_sub(Self::CUSTOM_ID_OFFSET) {
+            Some(index) => Some(StrategyType::Custom(index)),
+            None => Self::ALL.get(id as usize).copied(),
+        }
+    }
+
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::ALL[rng.gen_range(0..Self::ALL.len())]
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            StrategyType::AllCooperate => "常に協力",
+            StrategyType::AllDefect => "常に裏切り",
+            StrategyType::TitForTat => "しっぺ返し：初回は協力し、以後は相手の前回の行動をまねる",
+            StrategyType::Pavlov => "パブロフ：うまくいけば同じ行動、失敗したら行動を変える",
+            StrategyType::GrimTrigger => "トリガー：相手が一度でも裏切ったら以後は裏切り続ける",
+            StrategyType::GenerousTitForTat => "寛容なしっぺ返し：相手の裏切りを 1/3 の確率で許す",
+            StrategyType::TitForTwoTats => {
+                "二度目でしっぺ返し：相手が 2 回続けて裏切ったときだけ裏切る"
+            }
+            StrategyType::Extortion => {
+                "搾取（ZD 戦略 Extort-2）：相手の利得を支配し、自分の余剰を相手の 2 倍にする"
+            }
+            StrategyType::ReputationBased => {
+                "評判：相手の評判が悪くなければ協力する（公開の評判がなければ自分の対戦履歴で判断）"
+            }
+            StrategyType::Custom(_) => "利用者定義の戦略",
+        }
+    }
+
+    pub fn description_en(&self) -> &'static str {
+        match self {
+            StrategyType::AllCooperate => "Always cooperate",
+            StrategyType::AllDefect => "Always defect",
+            StrategyType::TitForTat => "Cooperate first, then copy the opponent's last move",
+            StrategyType::Pavlov => "Win-stay, lose-shift",
+            StrategyType::GrimTrigger => {
+                "Cooperate until the opponent defects once, then defect forever"
+            }
+            StrategyType::GenerousTitForTat => {
+                "Tit for Tat that forgives a defection with probability 1/3"
+            }
+            StrategyType::TitForTwoTats => "Defect only after two consecutive opponent defections",
+            StrategyType::Extortion => {
+                "Zero-determinant Extort-2: claims twice the opponent's surplus"
+            }
+            StrategyType::ReputationBased => {
+                "Cooperate with opponents in good standing on the reputation board (or in private history)"
+            }
+            StrategyType::Custom(_) => "User-defined strategy",
         }
     }
 
+    // 相手の行動に応じて振る舞いを変える戦略（複雑さペナルティの対象）
+    pub fn is_conditional(&self) -> bool {
+        !matches!(self, StrategyType::AllCooperate | StrategyType::AllDefect)
+    }
+
+    pub fn is_stochastic(&self) -> bool {
+        match self.builtin() {
+            Some(strategy) => strategy.is_stochastic(),
+            None => StrategyRegistry::get(*self).is_some_and(|strategy| strategy.is_stochastic()),
+        }
+    }
+
+    // 未登録の Custom は "Custom(n)"
+    pub fn name(&self) -> String {
+        match self.builtin() {
+            Some(strategy) => strategy.name().to_string(),
+            None => StrategyRegistry::get(*self)
+                .map(|strategy| strategy.name().to_string())
+                .unwrap_or_else(|| format!("{self:?}")),
+        }
+    }
+
+    // 未登録の Custom は常に協力する
+    pub fn decide(&self, context: &DecisionContext) -> Action {
+        match self.builtin() {
+            Some(strategy) => strategy.decide(context),
+            None => StrategyRegistry::get(*self)
+                .map(|strategy| strategy.decide(context))
+                .unwrap_or(Action::Cooperate),
+        }
+    }
+
+    // 組み込み戦略は状態を持たないので Custom のみ通知する
+    pub fn record(
+        &self,
+        context: &DecisionContext,
+        my_action: Action,
+        opponent_action: Action,
+        payoff: i32,
+    ) {
+        if let StrategyType::Custom(_) = self {
+            if let Some(strategy) = StrategyRegistry::get(*self) {
+                strategy.record(context, my_action, opponent_action, payoff);
+            }
+        }
+    }
+
+    // 直前の 1 ラウンドだけを与えて行動を決める（確率的な戦略は random = 0.0 で評価する）
     pub fn decide_action(
         &self,
         last_opponent_action: Option<Action>,
         last_my_action: Option<Action>,
         last_payoff: Option<i32>,
     ) -> Action {
+        let opponent_id = Uuid::nil();
+        let mut history = GameHistory::new();
+        if let (Some(opponent_action), Some(my_action), Some(payoff)) =
+            (last_opponent_action, last_my_action, last_payoff)
+        {
+            history.add_game(opponent_id, my_action, opponent_action, payoff);
+        }
+
+        self.decide(&DecisionContext::new(&history, &opponent_id))
+    }
+
+    fn builtin(&self) -> Option<&'static dyn DecisionStrategy> {
         match self {
-            StrategyType::AllCooperate => Action::Cooperate,
-            StrategyType::AllDefect => Action::Defect,
-            StrategyType::TitForTat => {
-                match last_opponent_action {
-                    Some(action) => action,
-                    None => Action::Cooperate, // 初回は協力
-                }
-            }
-            StrategyType::Pavlov => {
-                match (last_my_action, last_payoff) {
-                    (Some(action), Some(payoff)) => {
-                        if payoff >= 3 {
-                            // 勝利または相互協力
-                            action // 同じ行動を継続
-                        } else {
-                            action.opposite() // 逆の行動
-                        }
-                    }
-                    _ => Action::Cooperate, // 初回は協力
-                }
-            }
+            StrategyType::AllCooperate => Some(&AllCooperateStrategy),
+            StrategyType::AllDefect => Some(&AllDefectStrategy),
+            StrategyType::TitForTat => Some(&TitForTatStrategy),
+            StrategyType::Pavlov => Some(&PavlovStrategy),
+            StrategyType::GrimTrigger => Some(&GrimTriggerStrategy),
+            StrategyType::GenerousTitForTat => Some(&GenerousTitForTatStrategy),
+            StrategyType::TitForTwoTats => Some(&TitForTwoTatsStrategy),
+            StrategyType::Extortion => Some(&ExtortionStrategy),
+            StrategyType::ReputationBased => Some(&ReputationBasedStrategy),
+            StrategyType::Custom(_) => None,
         }
     }
 }
@@ -70,6 +208,10 @@ impl Action {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::agent::{Agent, MovementStrategy, Position};
+    use crate::domain::game::GameService;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
 
     #[test]
     fn test_action_opposite() {
@@ -129,7 +271,7 @@ mod tests {
         // Act & Assert: 初回は協力、その後はwin-stay-lose-shift
         assert_eq!(strategy.decide_action(None, None, None), Action::Cooperate);
 
-        // Win-stay: スコア3以上は同じ行動を継続
+        // Win-stay: 相手が協力した（T か R を得た）ら同じ行動を継続
         assert_eq!(
             strategy.decide_action(Some(Action::Cooperate), Some(Action::Cooperate), Some(3)),
             Action::Cooperate
@@ -139,7 +281,7 @@ mod tests {
             Action::Defect
         );
 
-        // Lose-shift: スコア2以下は反対の行動に変更
+        // Lose-shift: 相手が裏切った（P か S を得た）ら反対の行動に変更
         assert_eq!(
             strategy.decide_action(Some(Action::Defect), Some(Action::Cooperate), Some(0)),
             Action::Defect
@@ -150,18 +292,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strategy_type_id_round_trip() {
+        // Arrange & Act & Assert
+        for strategy in StrategyType::ALL {
+            assert_eq!(StrategyType::from_id(strategy.id()), Some(strategy));
+        }
+        assert_eq!(StrategyType::from_id(StrategyType::ALL.len() as u8), None);
+    }
+
     #[test]
     fn test_strategy_type_random_returns_valid_strategy() {
         // Arrange & Act
-        let strategy = StrategyType::random();
-
-        // Assert: 有効な戦略タイプの一つが返される
-        assert!(matches!(
-            strategy,
-            StrategyType::AllCooperate
-                | StrategyType::AllDefect
-                | StrategyType::TitForTat
-                | StrategyType::Pavlov
-        ));
+        let strategy = StrategyType::random(&mut rand::thread_rng());
+
+        // Assert: 組み込み戦略の一つが返される（Custom は返さない）
+        assert!(StrategyType::ALL.contains(&strategy));
+    }
+
+    fn history_of(rounds: &[(Action, Action)]) -> (GameHistory, Uuid) {
+        let opponent = Uuid::new_v4();
+        let mut history = GameHistory::new();
+        for &(my_action, opponent_action) in rounds {
+            history.add_game(opponent, my_action, opponent_action, 0);
+        }
+        (history, opponent)
+    }
+
+    #[test]
+    fn test_grim_trigger_never_forgives() {
+        // Arrange: 相手は 1 回裏切った後、協力に戻っている
+        let (history, opponent) = history_of(&[
+            (Action::Cooperate, Action::Defect),
+            (Action::Defect, Action::Cooperate),
+            (Action::Defect, Action::Cooperate),
+        ]);
+
+        // Act
+        let action = StrategyType::GrimTrigger.decide(&DecisionContext::new(&history, &opponent));
+
+        // Assert
+        assert_eq!(action, Action::Defect);
+        assert_eq!(
+            StrategyType::GrimTrigger.decide_action(None, None, None),
+            Action::Cooperate
+        );
+    }
+
+    #[test]
+    fn test_tit_for_two_tats_waits_for_second_defection() {
+        // Arrange
+        let (once, opponent_once) = history_of(&[
+            (Action::Cooperate, Action::Cooperate),
+            (Action::Cooperate, Action::Defect),
+        ]);
+        let (twice, opponent_twice) = history_of(&[
+            (Action::Cooperate, Action::Defect),
+            (Action::Cooperate, Action::Defect),
+        ]);
+        let strategy = StrategyType::TitForTwoTats;
+
+        // Act & Assert
+        assert_eq!(
+            strategy.decide(&DecisionContext::new(&once, &opponent_once)),
+            Action::Cooperate
+        );
+        assert_eq!(
+            strategy.decide(&DecisionContext::new(&twice, &opponent_twice)),
+            Action::Defect
+        );
+    }
+
+    #[test]
+    fn test_generous_tit_for_tat_forgives_below_generosity() {
+        // Arrange
+        let (history, opponent) = history_of(&[(Action::Cooperate, Action::Defect)]);
+        let strategy = StrategyType::GenerousTitForTat;
+        let context = |random| DecisionContext::new(&history, &opponent).with_random(random);
+
+        // Act & Assert: 乱数が寛容度未満なら許す
+        assert!(strategy.is_stochastic());
+        assert_eq!(strategy.decide(&context(0.2)), Action::Cooperate);
+        assert_eq!(strategy.decide(&context(0.5)), Action::Defect);
+    }
+
+    #[test]
+    fn test_extortion_follows_memory_one_probabilities() {
+        // Arrange: 前回 (自分, 相手) = (D, C) のときの協力確率は 1/3
+        let (history, opponent) = history_of(&[(Action::Defect, Action::Cooperate)]);
+        let (mutual_defection, opponent_dd) = history_of(&[(Action::Defect, Action::Defect)]);
+        let strategy = StrategyType::Extortion;
+
+        // Act & Assert
+        let decide = |random| {
+            strategy.decide(&DecisionContext::new(&history, &opponent).with_random(random))
+        };
+        assert_eq!(decide(0.3), Action::Cooperate);
+        assert_eq!(decide(0.4), Action::Defect);
+        assert_eq!(
+            strategy
+                .decide(&DecisionContext::new(&mutual_defection, &opponent_dd).with_random(0.0)),
+            Action::Defect
+        );
+    }
+
+    #[test]
+    fn test_extortion_claims_more_than_opponent_against_cooperator() {
+        // Arrange
+        let mut extortioner = Agent::new(
+            Position::new(0, 0),
+            StrategyType::Extortion,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        let mut cooperator = Agent::new(
+            Position::new(1, 0),
+            StrategyType::AllCooperate,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        let mut rng = ChaCha12Rng::seed_from_u64(5);
+
+        // Act
+        for _ in 0..500 {
+            GameService::play_game_with_noise(&mut extortioner, &mut cooperator, 0.0, &mut rng);
+        }
+
+        // Assert: 相互協力の利得 3 を超えて搾取する
+        assert!(extortioner.score > cooperator.score);
+        assert!(extortioner.score > 3 * 500);
     }
 }
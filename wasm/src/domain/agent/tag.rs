@@ -0,0 +1,113 @@
+use super::Agent;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 遺伝するタグ（緑ひげ効果）の設定。タグは 0..tag_count の値で、子は親のどちらかから受け継ぐ
+// 各個体の in_group_bias の確率で、同じタグの相手には協力し、違うタグの相手には裏切る（それ以外は戦略どおり）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TagConfig {
+    pub tag_count: u8,
+    // 子ごとにタグを引き直す確率（同じ確率で in_group_bias も ±0.1 の範囲でずれる）
+    pub mutation_rate: f64,
+}
+
+impl TagConfig {
+    pub fn new(tag_count: u8) -> Self {
+        Self {
+            tag_count: tag_count.max(1),
+            mutation_rate: 0.01,
+        }
+    }
+
+    pub fn with_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    // "N" または "N:RATE"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected N or N:RATE, got {spec}");
+        let (count, rate) = match spec.split_once(':') {
+            Some((count, rate)) => (count, Some(rate)),
+            None => (spec, None),
+        };
+        let tags = Self::new(count.parse().map_err(|_| invalid())?);
+        match rate {
+            Some(rate) => Ok(tags.with_mutation_rate(rate.parse().map_err(|_| invalid())?)),
+            None => Ok(tags),
+        }
+    }
+
+    // 初期集団用。タグと内集団びいきの強さを一様に引く
+    pub fn assign<R: Rng + ?Sized>(&self, agent: &mut Agent, rng: &mut R) {
+        agent.tag = rng.gen_range(0..self.tag_count.max(1));
+        agent.in_group_bias = rng.gen::<f64>();
+    }
+
+    pub fn mutate<R: Rng + ?Sized>(&self, agent: &mut Agent, rng: &mut R) {
+        let rate = self.mutation_rate.clamp(0.0, 1.0);
+        if rng.gen_bool(rate) {
+            agent.tag = rng.gen_range(0..self.tag_count.max(1));
+        }
+        if rng.gen_bool(rate) {
+            agent.in_group_bias = (agent.in_group_bias + rng.gen_range(-0.1..=0.1)).clamp(0.0, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, MovementStrategy, Position, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    fn tagged(tag: u8, in_group_bias: f64) -> Agent {
+        Agent::new(
+            Position::new(0, 0),
+            StrategyType::AllDefect,
+            0.0,
+            MovementStrategy::Settler,
+        )
+        .with_tag(tag, in_group_bias)
+    }
+
+    #[test]
+    fn test_full_bias_cooperates_only_with_same_tag() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let focal = tagged(1, 1.0);
+        let indifferent = tagged(1, 0.0);
+
+        // Act & Assert: びいきがなければ戦略（常に裏切り）どおり
+        assert_eq!(
+            focal.decide_action_toward(&tagged(1, 0.0), None, &mut rng),
+            Action::Cooperate
+        );
+        assert_eq!(
+            focal.decide_action_toward(&tagged(2, 0.0), None, &mut rng),
+            Action::Defect
+        );
+        assert_eq!(
+            indifferent.decide_action_toward(&tagged(1, 0.0), None, &mut rng),
+            Action::Defect
+        );
+    }
+
+    #[test]
+    fn test_mutation_keeps_tag_in_range_and_parse_reads_rate() {
+        // Arrange
+        let tags = TagConfig::parse("3:1.0").unwrap();
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+        let mut agent = tagged(0, 0.5);
+
+        // Act & Assert
+        for _ in 0..50 {
+            tags.mutate(&mut agent, &mut rng);
+            assert!(agent.tag < 3);
+            assert!((0.0..=1.0).contains(&agent.in_group_bias));
+        }
+        assert_eq!(TagConfig::parse("4"), Ok(TagConfig::new(4)));
+        assert!(TagConfig::parse("4:x").is_err());
+    }
+}
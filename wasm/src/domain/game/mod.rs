@@ -1,5 +1,13 @@
 pub mod payoff;
+pub mod payoff_schedule;
+pub mod public_goods;
+pub mod punishment;
+pub mod reputation;
 pub mod service;
 
 pub use payoff::*;
+pub use payoff_schedule::*;
+pub use public_goods::*;
+pub use punishment::*;
+pub use reputation::*;
 pub use service::*;
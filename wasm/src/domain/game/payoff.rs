@@ -1,16 +1,95 @@
 use crate::domain::agent::Action;
+use serde::{Deserialize, Serialize};
 
-pub struct PayoffMatrix;
+// 2 人ゲームの利得表（協力 = C, 裏切り = D）
+// reward = CC, temptation = DC, punishment = DD, sucker = CD のときの自分の利得
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffMatrix {
+    pub temptation: i32,
+    pub reward: i32,
+    pub punishment: i32,
+    pub sucker: i32,
+}
+
+impl Default for PayoffMatrix {
+    fn default() -> Self {
+        Self::prisoners_dilemma()
+    }
+}
 
 impl PayoffMatrix {
-    pub fn calculate(my_action: Action, opponent_action: Action) -> (i32, i32) {
+    pub const PRESET_NAMES: [&'static str; 4] =
+        ["prisoners_dilemma", "snowdrift", "hawk_dove", "stag_hunt"];
+
+    pub fn new(temptation: i32, reward: i32, punishment: i32, sucker: i32) -> Self {
+        Self {
+            temptation,
+            reward,
+            punishment,
+            sucker,
+        }
+    }
+
+    // T=5 > R=3 > P=1 > S=0
+    pub fn prisoners_dilemma() -> Self {
+        Self::new(5, 3, 1, 0)
+    }
+
+    // 雪かきゲーム：相手が裏切っても自分は協力した方がよい（T > R > S > P）
+    pub fn snowdrift() -> Self {
+        Self::new(5, 3, 0, 1)
+    }
+
+    // タカ・ハトゲーム（資源 V = 4、争いのコスト C = 6、協力 = ハト）
+    // ハト同士 V/2、タカ対ハト V と 0、タカ同士 (V - C)/2
+    pub fn hawk_dove() -> Self {
+        Self::new(4, 2, -1, 0)
+    }
+
+    // 鹿狩りゲーム：協調がもっとも得だが、裏切りの方が安全（R > T > P > S）
+    pub fn stag_hunt() -> Self {
+        Self::new(3, 5, 1, 0)
+    }
+
+    // "prisoners_dilemma"（"pd"） / "snowdrift" / "hawk_dove" / "stag_hunt"
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "prisoners_dilemma" | "pd" => Some(Self::prisoners_dilemma()),
+            "snowdrift" => Some(Self::snowdrift()),
+            "hawk_dove" => Some(Self::hawk_dove()),
+            "stag_hunt" => Some(Self::stag_hunt()),
+            _ => None,
+        }
+    }
+
+    // 囚人のジレンマの大小関係 T > R > P > S を満たすか
+    pub fn validate_prisoners_dilemma(&self) -> Result<(), String> {
+        if self.temptation > self.reward
+            && self.reward > self.punishment
+            && self.punishment > self.sucker
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Payoffs T={}, R={}, P={}, S={} do not satisfy the prisoner's dilemma ordering T > R > P > S",
+                self.temptation, self.reward, self.punishment, self.sucker
+            ))
+        }
+    }
+
+    pub fn payoffs(&self, my_action: Action, opponent_action: Action) -> (i32, i32) {
         match (my_action, opponent_action) {
-            (Action::Cooperate, Action::Cooperate) => (3, 3),
-            (Action::Cooperate, Action::Defect) => (0, 5),
-            (Action::Defect, Action::Cooperate) => (5, 0),
-            (Action::Defect, Action::Defect) => (1, 1),
+            (Action::Cooperate, Action::Cooperate) => (self.reward, self.reward),
+            (Action::Cooperate, Action::Defect) => (self.sucker, self.temptation),
+            (Action::Defect, Action::Cooperate) => (self.temptation, self.sucker),
+            (Action::Defect, Action::Defect) => (self.punishment, self.punishment),
         }
     }
+
+    // 標準の囚人のジレンマの利得
+    pub fn calculate(my_action: Action, opponent_action: Action) -> (i32, i32) {
+        Self::prisoners_dilemma().payoffs(my_action, opponent_action)
+    }
 }
 
 #[cfg(test)]
@@ -72,4 +151,34 @@ mod tests {
         assert_eq!(my_payoff, 1);
         assert_eq!(opponent_payoff, 1);
     }
+
+    #[test]
+    fn test_presets_follow_their_orderings() {
+        // Arrange & Act
+        let snowdrift = PayoffMatrix::snowdrift();
+        let hawk_dove = PayoffMatrix::hawk_dove();
+        let stag_hunt = PayoffMatrix::stag_hunt();
+
+        // Assert
+        assert!(snowdrift.sucker > snowdrift.punishment);
+        assert!(hawk_dove.temptation > hawk_dove.reward && hawk_dove.sucker > hawk_dove.punishment);
+        assert!(stag_hunt.reward > stag_hunt.temptation);
+        for name in PayoffMatrix::PRESET_NAMES {
+            assert!(PayoffMatrix::preset(name).is_some());
+        }
+    }
+
+    #[test]
+    fn test_validate_prisoners_dilemma_ordering() {
+        // Arrange & Act & Assert
+        assert!(PayoffMatrix::prisoners_dilemma()
+            .validate_prisoners_dilemma()
+            .is_ok());
+        assert!(PayoffMatrix::snowdrift()
+            .validate_prisoners_dilemma()
+            .is_err());
+        assert!(PayoffMatrix::new(5, 3, 3, 0)
+            .validate_prisoners_dilemma()
+            .is_err());
+    }
 }
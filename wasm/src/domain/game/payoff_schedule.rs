@@ -0,0 +1,174 @@
+use super::PayoffMatrix;
+use serde::{Deserialize, Serialize};
+
+// 直前のキーフレームからこのキーフレームへの移り方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PayoffTransition {
+    // generation に達した時点で切り替える
+    #[default]
+    Step,
+    // 直前のキーフレーム（なければ世代 0 の基本の利得表）から generation にかけて線形に近づける
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffKeyframe {
+    pub generation: u32,
+    pub payoffs: PayoffMatrix,
+    pub transition: PayoffTransition,
+}
+
+// 世代ごとの利得表の予定（環境の変化）。最初のキーフレームまでは設定の利得表がそのまま使われる
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffSchedule {
+    pub keyframes: Vec<PayoffKeyframe>,
+}
+
+impl PayoffSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // generation から payoffs に切り替える
+    pub fn with_change(self, generation: u32, payoffs: PayoffMatrix) -> Self {
+        self.with_keyframe(generation, payoffs, PayoffTransition::Step)
+    }
+
+    // generation までに payoffs へ線形に移る
+    pub fn with_ramp(self, generation: u32, payoffs: PayoffMatrix) -> Self {
+        self.with_keyframe(generation, payoffs, PayoffTransition::Linear)
+    }
+
+    fn with_keyframe(
+        mut self,
+        generation: u32,
+        payoffs: PayoffMatrix,
+        transition: PayoffTransition,
+    ) -> Self {
+        self.keyframes.push(PayoffKeyframe {
+            generation,
+            payoffs,
+            transition,
+        });
+        self
+    }
+
+    // キーフレームが世代の昇順に並び、同じ世代が重なっていないか
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keyframes.is_empty() {
+            return Err("must contain at least one keyframe".to_string());
+        }
+        match self
+            .keyframes
+            .windows(2)
+            .find(|pair| pair[0].generation >= pair[1].generation)
+        {
+            Some(pair) => Err(format!(
+                "keyframe generations must be strictly increasing, got {} then {}",
+                pair[0].generation, pair[1].generation
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // generation で使う利得表。base は最初のキーフレームより前の利得表
+    pub fn payoffs_at(&self, generation: u32, base: &PayoffMatrix) -> PayoffMatrix {
+        let mut previous = (0, *base);
+        for keyframe in &self.keyframes {
+            if generation >= keyframe.generation {
+                previous = (keyframe.generation, keyframe.payoffs);
+                continue;
+            }
+            if keyframe.transition == PayoffTransition::Linear {
+                let (start, from) = previous;
+                let t = f64::from(generation - start) / f64::from(keyframe.generation - start);
+                return Self::interpolate(&from, &keyframe.payoffs, t);
+            }
+            break;
+        }
+        previous.1
+    }
+
+    // 利得は整数なので各成分を四捨五入する
+    fn interpolate(from: &PayoffMatrix, to: &PayoffMatrix, t: f64) -> PayoffMatrix {
+        let lerp = |a: i32, b: i32| (f64::from(a) + f64::from(b - a) * t).round() as i32;
+        PayoffMatrix::new(
+            lerp(from.temptation, to.temptation),
+            lerp(from.reward, to.reward),
+            lerp(from.punishment, to.punishment),
+            lerp(from.sucker, to.sucker),
+        )
+    }
+
+    // "GEN=PAYOFFS" で切り替え、"GEN~PAYOFFS" で線形に移る。複数は ";" で区切る
+    // PAYOFFS はプリセット名（snowdrift など）か "T,R,P,S"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut schedule = Self::new();
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let invalid = || format!("Expected GEN=PAYOFFS or GEN~PAYOFFS, got {entry}");
+            let (generation, payoffs, transition) = match entry.split_once('=') {
+                Some((generation, payoffs)) => (generation, payoffs, PayoffTransition::Step),
+                None => {
+                    let (generation, payoffs) = entry.split_once('~').ok_or_else(invalid)?;
+                    (generation, payoffs, PayoffTransition::Linear)
+                }
+            };
+            let generation = generation.trim().parse().map_err(|_| invalid())?;
+            let payoffs = Self::parse_payoffs(payoffs.trim()).ok_or_else(invalid)?;
+            schedule = schedule.with_keyframe(generation, payoffs, transition);
+        }
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    fn parse_payoffs(spec: &str) -> Option<PayoffMatrix> {
+        if let Some(preset) = PayoffMatrix::preset(spec) {
+            return Some(preset);
+        }
+        let values: Vec<i32> = spec
+            .split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        match values[..] {
+            [t, r, p, s] => Some(PayoffMatrix::new(t, r, p, s)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp_then_flip_to_snowdrift() {
+        // Arrange: T を 5 から 9 まで 100 世代かけて上げ、200 世代目で雪かきゲームに切り替える
+        let base = PayoffMatrix::prisoners_dilemma();
+        let schedule = PayoffSchedule::parse("100~9,3,1,0; 200=snowdrift").unwrap();
+
+        // Act
+        let at = |generation| schedule.payoffs_at(generation, &base);
+
+        // Assert
+        assert_eq!(at(0), base);
+        assert_eq!(at(50).temptation, 7);
+        assert_eq!(at(150), PayoffMatrix::new(9, 3, 1, 0));
+        assert_eq!(at(200), PayoffMatrix::snowdrift());
+        assert_eq!(at(1000), PayoffMatrix::snowdrift());
+        assert_eq!(
+            schedule,
+            PayoffSchedule::new()
+                .with_ramp(100, PayoffMatrix::new(9, 3, 1, 0))
+                .with_change(200, PayoffMatrix::snowdrift())
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unordered_or_malformed_keyframes() {
+        // Act & Assert
+        assert!(PayoffSchedule::parse("200=snowdrift;100=pd").is_err());
+        assert!(PayoffSchedule::parse("100=1,2,3").is_err());
+        assert!(PayoffSchedule::parse("later=snowdrift").is_err());
+        assert!(PayoffSchedule::parse("").is_err());
+    }
+}
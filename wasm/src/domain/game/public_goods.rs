@@ -0,0 +1,132 @@
+use crate::domain::agent::Action;
+use serde::{Deserialize, Serialize};
+
+// 1 ステップの相互作用の種類
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum GameType {
+    // 隣接ペアごとの囚人のジレンマ
+    #[default]
+    PrisonersDilemma,
+    // 各エージェントを中心に自分と近傍全員で行う N 人公共財ゲーム
+    PublicGoods(PublicGoodsGame),
+}
+
+impl GameType {
+    // "pd" / "public_goods" / "public_goods:R"（R は増幅率）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pd" | "prisoners_dilemma" => Some(GameType::PrisonersDilemma),
+            "public_goods" => Some(GameType::PublicGoods(PublicGoodsGame::default())),
+            _ => name
+                .strip_prefix("public_goods:")
+                .and_then(|multiplier| multiplier.parse().ok())
+                .and_then(|multiplier| PublicGoodsGame::new(multiplier).ok())
+                .map(GameType::PublicGoods),
+        }
+    }
+}
+
+// 協力者は contribution を拠出し、拠出の合計を multiplier 倍してグループ全員で等分する
+// スコアは整数なので各自の利得は四捨五入する
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PublicGoodsGame {
+    pub multiplier: f64,
+    pub contribution: f64,
+}
+
+impl Default for PublicGoodsGame {
+    fn default() -> Self {
+        Self {
+            multiplier: 3.0,
+            contribution: 5.0,
+        }
+    }
+}
+
+impl PublicGoodsGame {
+    pub fn new(multiplier: f64) -> Result<Self, String> {
+        if !multiplier.is_finite() || multiplier < 0.0 {
+            return Err(format!(
+                "Public goods multiplier must be non-negative, got {multiplier}"
+            ));
+        }
+        Ok(Self {
+            multiplier,
+            ..Self::default()
+        })
+    }
+
+    pub fn with_contribution(mut self, contribution: f64) -> Self {
+        self.contribution = contribution.max(0.0);
+        self
+    }
+
+    // actions[i] に対応するメンバー i の利得
+    pub fn payoffs(&self, actions: &[Action]) -> Vec<i32> {
+        if actions.is_empty() {
+            return Vec::new();
+        }
+        let contributors = actions
+            .iter()
+            .filter(|&&action| action == Action::Cooperate)
+            .count();
+        let share =
+            self.multiplier * self.contribution * contributors as f64 / actions.len() as f64;
+
+        actions
+            .iter()
+            .map(|action| match action {
+                Action::Cooperate => (share - self.contribution).round() as i32,
+                Action::Defect => share.round() as i32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payoffs_split_the_multiplied_pot() {
+        // Arrange: 4 人中 2 人が拠出 → 2 * 5 * 3 / 4 = 7.5 ずつ
+        let game = PublicGoodsGame::default();
+        let actions = [
+            Action::Cooperate,
+            Action::Cooperate,
+            Action::Defect,
+            Action::Defect,
+        ];
+
+        // Act
+        let payoffs = game.payoffs(&actions);
+
+        // Assert
+        assert_eq!(payoffs, vec![3, 3, 8, 8]);
+    }
+
+    #[test]
+    fn test_full_cooperation_beats_full_defection() {
+        // Arrange
+        let game = PublicGoodsGame::new(3.0).unwrap();
+
+        // Act
+        let cooperators = game.payoffs(&[Action::Cooperate; 5]);
+        let defectors = game.payoffs(&[Action::Defect; 5]);
+
+        // Assert
+        assert_eq!(cooperators, vec![10; 5]);
+        assert_eq!(defectors, vec![0; 5]);
+    }
+
+    #[test]
+    fn test_from_name_parses_multiplier() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            GameType::from_name("public_goods:4.5"),
+            Some(GameType::PublicGoods(PublicGoodsGame::new(4.5).unwrap()))
+        );
+        assert_eq!(GameType::from_name("pd"), Some(GameType::PrisonersDilemma));
+        assert_eq!(GameType::from_name("public_goods:-1"), None);
+    }
+}
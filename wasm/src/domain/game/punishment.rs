@@ -0,0 +1,120 @@
+use crate::domain::agent::{Action, Agent};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 対戦後の罰。裏切られた側は自分の aggression の確率で、cost を払って相手に fine を科す
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PunishmentConfig {
+    pub cost: i32,
+    pub fine: i32,
+}
+
+impl Default for PunishmentConfig {
+    fn default() -> Self {
+        Self { cost: 1, fine: 4 }
+    }
+}
+
+impl PunishmentConfig {
+    pub fn new(cost: i32, fine: i32) -> Self {
+        Self { cost, fine }
+    }
+
+    // "COST:FINE"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Expected COST:FINE, got {spec}");
+        let (cost, fine) = spec.split_once(':').ok_or_else(invalid)?;
+        Ok(Self::new(
+            cost.parse().map_err(|_| invalid())?,
+            fine.parse().map_err(|_| invalid())?,
+        ))
+    }
+
+    // 初期集団用。攻撃性を一様に引く
+    pub fn assign<R: Rng + ?Sized>(&self, agent: &mut Agent, rng: &mut R) {
+        agent.aggression = rng.gen::<f64>();
+    }
+
+    // mutation_rate の確率で攻撃性を ±0.1 の範囲でずらす
+    pub fn mutate<R: Rng + ?Sized>(&self, agent: &mut Agent, mutation_rate: f64, rng: &mut R) {
+        if rng.gen_bool(mutation_rate.clamp(0.0, 1.0)) {
+            agent.aggression = (agent.aggression + rng.gen_range(-0.1..=0.1)).clamp(0.0, 1.0);
+        }
+    }
+
+    // 直前の対戦（actions は両者の実際の行動）の後に罰を与え、それぞれが罰したかを返す
+    // 判定は相手が裏切ったときだけ乱数を引く。罰は両者の最新の対戦記録にも残す
+    pub fn punish<R: Rng + ?Sized>(
+        &self,
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        (action1, action2): (Action, Action),
+        rng: &mut R,
+    ) -> (bool, bool) {
+        let mut decide = |punisher: &Agent, target_action: Action| {
+            target_action == Action::Defect
+                && punisher.aggression > 0.0
+                && rng.gen::<f64>() < punisher.aggression
+        };
+        let punished1 = decide(agent1, action2);
+        let punished2 = decide(agent2, action1);
+
+        let penalty = |punished_other: bool, punished_by_other: bool| {
+            i32::from(punished_other) * self.cost + i32::from(punished_by_other) * self.fine
+        };
+        agent1.score -= penalty(punished1, punished2);
+        agent2.score -= penalty(punished2, punished1);
+        agent1
+            .history
+            .mark_last_punishment(&agent2.id, punished1, punished2);
+        agent2
+            .history
+            .mark_last_punishment(&agent1.id, punished2, punished1);
+        (punished1, punished2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_aggressive_cooperator_pays_to_fine_defector() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let agent = |strategy| {
+            Agent::new(
+                Position::new(0, 0),
+                strategy,
+                0.0,
+                MovementStrategy::Settler,
+            )
+        };
+        let mut punisher = agent(StrategyType::AllCooperate).with_aggression(1.0);
+        let mut defector = agent(StrategyType::AllDefect).with_aggression(1.0);
+        punisher.add_game_result(defector.id, Action::Cooperate, Action::Defect, 0);
+        defector.add_game_result(punisher.id, Action::Defect, Action::Cooperate, 5);
+
+        // Act
+        let punished = PunishmentConfig::new(1, 4).punish(
+            &mut punisher,
+            &mut defector,
+            (Action::Cooperate, Action::Defect),
+            &mut rng,
+        );
+
+        // Assert: 協力した相手は罰されず、罰は対戦記録に残る
+        assert_eq!(punished, (true, false));
+        assert_eq!((punisher.score, defector.score), (-1, 1));
+        let record = defector.history.records().last().unwrap();
+        assert!(record.punished_by_opponent && !record.punished);
+        assert!(punisher.history.records().last().unwrap().punished);
+        assert_eq!(
+            PunishmentConfig::parse("2:6"),
+            Ok(PunishmentConfig::new(2, 6))
+        );
+    }
+}
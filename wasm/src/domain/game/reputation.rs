@@ -0,0 +1,240 @@
+use crate::domain::agent::Action;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 観察された行動から公開の評判をどう更新するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReputationRule {
+    // イメージスコア：協力で +1、裏切りで -1
+    #[default]
+    ImageScoring,
+    // スタンディング：評判の悪い相手への裏切りは正当とみなして評判を下げない
+    Standing,
+}
+
+impl ReputationRule {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "image_scoring" | "image" => Some(ReputationRule::ImageScoring),
+            "standing" => Some(ReputationRule::Standing),
+            _ => None,
+        }
+    }
+}
+
+// 間接互恵の設定。対戦ごとに各自の行動が observation_probability の確率で観察され、
+// 評判は毎ステップ decay の割合で 0 に近づく
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReputationConfig {
+    pub rule: ReputationRule,
+    pub observation_probability: f64,
+    pub decay: f64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            rule: ReputationRule::ImageScoring,
+            observation_probability: 1.0,
+            decay: 0.0,
+        }
+    }
+}
+
+impl ReputationConfig {
+    pub fn new(rule: ReputationRule) -> Self {
+        Self {
+            rule,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_observation_probability(mut self, probability: f64) -> Self {
+        self.observation_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        self.decay = decay.clamp(0.0, 1.0);
+        self
+    }
+
+    // "RULE" / "RULE:P" / "RULE:P:D"（RULE は image_scoring | standing）
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Expected RULE[:P[:D]] (RULE: image_scoring | standing), got {spec}");
+        let mut parts = spec.split(':');
+        let rule = parts
+            .next()
+            .and_then(ReputationRule::from_name)
+            .ok_or_else(invalid)?;
+        let mut config = Self::new(rule);
+        if let Some(probability) = parts.next() {
+            config =
+                config.with_observation_probability(probability.parse().map_err(|_| invalid())?);
+        }
+        if let Some(decay) = parts.next() {
+            config = config.with_decay(decay.parse().map_err(|_| invalid())?);
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(config)
+    }
+}
+
+// シミュレーション全体で共有する評判。載っていない個体の評判は 0（良い）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReputationBoard {
+    scores: HashMap<Uuid, f64>,
+}
+
+impl ReputationBoard {
+    // 評判の上限と下限（昔の行いがいつまでも効きすぎないように）
+    pub const LIMIT: f64 = 5.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &Uuid) -> f64 {
+        self.scores.get(id).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_good(&self, id: &Uuid) -> bool {
+        self.get(id) >= 0.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    // 1 回の対戦の両者の行動をそれぞれ観察するか決めて評判を更新する
+    // スタンディングでは対戦前の相手の評判で裏切りが正当かを判断する。観察された行動の数を返す
+    pub fn observe_game<R: Rng + ?Sized>(
+        &mut self,
+        config: &ReputationConfig,
+        (id1, action1): (Uuid, Action),
+        (id2, action2): (Uuid, Action),
+        rng: &mut R,
+    ) -> usize {
+        let probability = config.observation_probability.clamp(0.0, 1.0);
+        let deltas = [
+            (id1, Self::delta(config.rule, action1, self.is_good(&id2))),
+            (id2, Self::delta(config.rule, action2, self.is_good(&id1))),
+        ];
+        let mut observed = 0;
+        for (id, delta) in deltas {
+            if probability > 0.0 && rng.gen_bool(probability) {
+                observed += 1;
+                let score = self.scores.entry(id).or_insert(0.0);
+                *score = (*score + delta).clamp(-Self::LIMIT, Self::LIMIT);
+            }
+        }
+        observed
+    }
+
+    fn delta(rule: ReputationRule, action: Action, recipient_is_good: bool) -> f64 {
+        match (rule, action) {
+            (_, Action::Cooperate) => 1.0,
+            (ReputationRule::Standing, Action::Defect) if !recipient_is_good => 0.0,
+            (_, Action::Defect) => -1.0,
+        }
+    }
+
+    // 全員の評判を decay の割合だけ 0 に近づける
+    pub fn decay(&mut self, decay: f64) {
+        if decay <= 0.0 {
+            return;
+        }
+        let factor = 1.0 - decay.clamp(0.0, 1.0);
+        self.scores.retain(|_, score| {
+            *score *= factor;
+            score.abs() > f64::EPSILON
+        });
+    }
+
+    // いなくなった個体の評判を捨てる
+    pub fn retain(&mut self, mut alive: impl FnMut(&Uuid) -> bool) {
+        self.scores.retain(|id, _| alive(id));
+    }
+
+    pub fn clear(&mut self) {
+        self.scores.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_standing_forgives_defection_against_bad_reputation() {
+        // Arrange: b は一度裏切って評判が悪い
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let standing = ReputationConfig::new(ReputationRule::Standing);
+        let image = ReputationConfig::new(ReputationRule::ImageScoring);
+        let mut board = ReputationBoard::new();
+        board.observe_game(
+            &standing,
+            (a, Action::Cooperate),
+            (b, Action::Defect),
+            &mut rng,
+        );
+
+        // Act: a が悪い評判の b を裏切る
+        let mut image_board = board.clone();
+        board.observe_game(
+            &standing,
+            (a, Action::Defect),
+            (b, Action::Defect),
+            &mut rng,
+        );
+        image_board.observe_game(&image, (a, Action::Defect), (b, Action::Defect), &mut rng);
+
+        // Assert: スタンディングでは a の評判は下がらず、イメージスコアでは下がる
+        assert_eq!(board.get(&a), 1.0);
+        assert_eq!(image_board.get(&a), 0.0);
+        assert!(!board.is_good(&b));
+    }
+
+    #[test]
+    fn test_decay_and_unobserved_actions() {
+        // Arrange
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+        let unseen = ReputationConfig::default().with_observation_probability(0.0);
+        let mut board = ReputationBoard::new();
+
+        // Act
+        let observed =
+            board.observe_game(&unseen, (a, Action::Defect), (b, Action::Defect), &mut rng);
+        board.observe_game(
+            &ReputationConfig::default(),
+            (a, Action::Cooperate),
+            (b, Action::Cooperate),
+            &mut rng,
+        );
+        board.decay(0.5);
+
+        // Assert
+        assert_eq!(observed, 0);
+        assert_eq!(board.get(&a), 0.5);
+        assert_eq!(
+            ReputationConfig::parse("standing:0.5:0.1"),
+            Ok(ReputationConfig::new(ReputationRule::Standing)
+                .with_observation_probability(0.5)
+                .with_decay(0.1))
+        );
+        assert!(ReputationConfig::parse("gossip").is_err());
+    }
+}
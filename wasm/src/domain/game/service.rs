@@ -1,18 +1,366 @@
-use super::PayoffMatrix;
-use crate::domain::agent::{Action, Agent};
+use super::{PayoffMatrix, PublicGoodsGame};
+use crate::domain::agent::{
+    Action, Agent, DecisionContext, MovementStrategy, Position, StrategyType,
+};
+use rand::Rng;
+use std::collections::HashMap;
 
 pub struct GameService;
 
 impl GameService {
-    pub fn play_game(agent1: &mut Agent, agent2: &mut Agent) -> (Action, Action) {
-        let action1 = agent1.decide_action(&agent2.id);
-        let action2 = agent2.decide_action(&agent1.id);
+    // 確率的な戦略は rng で判定する（シード付きの乱数を渡せば再現できる）
+    pub fn play_game<R: Rng + ?Sized>(
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        rng: &mut R,
+    ) -> (Action, Action) {
+        Self::play_game_with_noise(agent1, agent2, 0.0, rng)
+    }
+
+    // 震える手：各エージェントの意図した行動が確率 noise で反転する
+    // 反転後の行動で利得を計算し、履歴にも反転後の行動を記録する
+    pub fn play_game_with_noise<R: Rng + ?Sized>(
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        noise: f64,
+        rng: &mut R,
+    ) -> (Action, Action) {
+        Self::play_game_with_payoffs(agent1, agent2, &PayoffMatrix::default(), noise, rng)
+    }
+
+    pub fn play_game_with_payoffs<R: Rng + ?Sized>(
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        payoffs: &PayoffMatrix,
+        noise: f64,
+        rng: &mut R,
+    ) -> (Action, Action) {
+        Self::play_game_with_reputation(
+            agent1,
+            agent2,
+            (payoffs, payoffs),
+            noise,
+            (None, None),
+            rng,
+        )
+    }
+
+    // payoffs は (agent1, agent2) それぞれの利得を決める表（区域ごとに異なりうる）
+    // reputations は公開の評判での (agent1, agent2) の評判。戦略は相手の評判を参照できる
+    pub fn play_game_with_reputation<R: Rng + ?Sized>(
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        payoffs: (&PayoffMatrix, &PayoffMatrix),
+        noise: f64,
+        (reputation1, reputation2): (Option<f64>, Option<f64>),
+        rng: &mut R,
+    ) -> (Action, Action) {
+        let noise = noise.clamp(0.0, 1.0);
+        let intended1 = agent1.decide_action_toward(agent2, reputation2, rng);
+        let intended2 = agent2.decide_action_toward(agent1, reputation1, rng);
+        let mut tremble = |action: Action| {
+            if noise > 0.0 && rng.gen_bool(noise) {
+                action.opposite()
+            } else {
+                action
+            }
+        };
+        let action1 = tremble(intended1);
+        let action2 = tremble(intended2);
+
+        Self::settle(agent1, agent2, payoffs, action1, action2)
+    }
+
+    fn settle(
+        agent1: &mut Agent,
+        agent2: &mut Agent,
+        (payoffs1, payoffs2): (&PayoffMatrix, &PayoffMatrix),
+        action1: Action,
+        action2: Action,
+    ) -> (Action, Action) {
+        let (payoff1, _) = payoffs1.payoffs(action1, action2);
+        let (_, payoff2) = payoffs2.payoffs(action1, action2);
 
-        let (payoff1, payoff2) = PayoffMatrix::calculate(action1, action2);
+        agent1.strategy.record(
+            &DecisionContext::new(&agent1.history, &agent2.id),
+            action1,
+            action2,
+            payoff1,
+        );
+        agent2.strategy.record(
+            &DecisionContext::new(&agent2.history, &agent1.id),
+            action2,
+            action1,
+            payoff2,
+        );
 
         agent1.add_game_result(agent2.id, action1, action2, payoff1);
         agent2.add_game_result(agent1.id, action2, action1, payoff2);
 
         (action1, action2)
     }
+
+    // group[0] を中心とする公共財ゲームを 1 回行い、各メンバーの行動を返す
+    // 条件付き戦略はグループを 1 人の相手（ID は中心エージェント）とみなし、
+    // 自分以外のメンバーの過半数が協力したときに相手が協力したものとして履歴に残す
+    pub fn play_public_goods<R: Rng + ?Sized>(
+        group: &mut [Agent],
+        game: &PublicGoodsGame,
+        noise: f64,
+        rng: &mut R,
+    ) -> Vec<Action> {
+        let Some(group_id) = group.first().map(|agent| agent.id) else {
+            return Vec::new();
+        };
+        let noise = noise.clamp(0.0, 1.0);
+        let intended: Vec<Action> = group
+            .iter()
+            .map(|agent| agent.decide_action(&group_id, rng))
+            .collect();
+        let actions: Vec<Action> = intended
+            .into_iter()
+            .map(|action| {
+                if noise > 0.0 && rng.gen_bool(noise) {
+                    action.opposite()
+                } else {
+                    action
+                }
+            })
+            .collect();
+        let payoffs = game.payoffs(&actions);
+
+        let total_cooperators = actions
+            .iter()
+            .filter(|&&action| action == Action::Cooperate)
+            .count();
+        for ((agent, &action), &payoff) in group.iter_mut().zip(&actions).zip(&payoffs) {
+            let others = actions.len() - 1;
+            let other_cooperators = total_cooperators - usize::from(action == Action::Cooperate);
+            let group_action = if others > 0 && other_cooperators * 2 > others {
+                Action::Cooperate
+            } else {
+                Action::Defect
+            };
+
+            agent.strategy.record(
+                &DecisionContext::new(&agent.history, &group_id),
+                action,
+                group_action,
+                payoff,
+            );
+            agent.add_game_result(group_id, action, group_action, payoff);
+        }
+
+        actions
+    }
+
+    // 各戦略ペアで payoffs の表を使って rounds 回の繰り返しゲームを行い、1ラウンドあたりの平均利得を返す
+    // キー (a, b) の値は戦略 a が戦略 b と対戦したときの a の平均利得
+    // 確率的な戦略の判定に rng を使う（同じシードなら同じ表になる）
+    pub fn strategy_payoff_matrix<R: Rng + ?Sized>(
+        payoffs: &PayoffMatrix,
+        rounds: u32,
+        rng: &mut R,
+    ) -> HashMap<(StrategyType, StrategyType), f64> {
+        let mut matrix = HashMap::new();
+        if rounds == 0 {
+            return matrix;
+        }
+
+        for strategy in StrategyType::ALL {
+            for opponent_strategy in StrategyType::ALL {
+                let mut agent = Agent::new(
+                    Position::new(0, 0),
+                    strategy,
+                    0.0,
+                    MovementStrategy::Settler,
+                );
+                let mut opponent = Agent::new(
+                    Position::new(1, 0),
+                    opponent_strategy,
+                    0.0,
+                    MovementStrategy::Settler,
+                );
+
+                for _ in 0..rounds {
+                    Self::play_game_with_payoffs(&mut agent, &mut opponent, payoffs, 0.0, rng);
+                }
+
+                matrix.insert(
+                    (strategy, opponent_strategy),
+                    agent.score as f64 / rounds as f64,
+                );
+            }
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    fn agent(strategy: StrategyType) -> Agent {
+        Agent::new(
+            Position::new(0, 0),
+            strategy,
+            0.0,
+            MovementStrategy::Settler,
+        )
+    }
+
+    #[test]
+    fn test_full_noise_flips_every_intended_action() {
+        // Arrange
+        let mut cooperator = agent(StrategyType::AllCooperate);
+        let mut defector = agent(StrategyType::AllDefect);
+        let mut rng = rand::thread_rng();
+
+        // Act
+        let actions =
+            GameService::play_game_with_noise(&mut cooperator, &mut defector, 1.0, &mut rng);
+
+        // Assert: 反転後の行動で利得が計算され、履歴にも残る
+        assert_eq!(actions, (Action::Defect, Action::Cooperate));
+        assert_eq!(cooperator.score, 5);
+        assert_eq!(defector.score, 0);
+        assert_eq!(
+            cooperator.history.get_last_my_action(&defector.id),
+            Some(Action::Defect)
+        );
+    }
+
+    #[test]
+    fn test_zero_noise_matches_noiseless_game() {
+        // Arrange
+        let mut a = agent(StrategyType::TitForTat);
+        let mut b = agent(StrategyType::AllDefect);
+        let mut rng = rand::thread_rng();
+
+        // Act
+        let first = GameService::play_game_with_noise(&mut a, &mut b, 0.0, &mut rng);
+        let second = GameService::play_game(&mut a, &mut b, &mut rng);
+
+        // Assert
+        assert_eq!(first, (Action::Cooperate, Action::Defect));
+        assert_eq!(second, (Action::Defect, Action::Defect));
+    }
+
+    #[test]
+    fn test_strategy_payoff_matrix_defector_gets_temptation_against_cooperator() {
+        // Arrange & Act
+        let matrix = GameService::strategy_payoff_matrix(
+            &PayoffMatrix::default(),
+            10,
+            &mut rand::thread_rng(),
+        );
+
+        // Assert
+        assert_eq!(
+            matrix[&(StrategyType::AllDefect, StrategyType::AllCooperate)],
+            5.0
+        );
+        assert_eq!(
+            matrix[&(StrategyType::AllCooperate, StrategyType::AllDefect)],
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_strategy_payoff_matrix_covers_all_pairs() {
+        // Arrange & Act
+        let matrix = GameService::strategy_payoff_matrix(
+            &PayoffMatrix::default(),
+            10,
+            &mut rand::thread_rng(),
+        );
+
+        // Assert
+        assert_eq!(
+            matrix.len(),
+            StrategyType::ALL.len() * StrategyType::ALL.len()
+        );
+        assert_eq!(
+            matrix[&(StrategyType::TitForTat, StrategyType::TitForTat)],
+            3.0
+        );
+        // 初回のみ搾取され、以降は相互裏切り
+        assert_eq!(
+            matrix[&(StrategyType::TitForTat, StrategyType::AllDefect)],
+            0.9
+        );
+    }
+
+    #[test]
+    fn test_strategy_payoff_matrix_is_reproducible_with_seeded_rng() {
+        // Arrange: 寛容なしっぺ返しと脅迫戦略は乱数で手を決める
+        let mut first_rng = ChaCha12Rng::seed_from_u64(9);
+        let mut second_rng = ChaCha12Rng::seed_from_u64(9);
+
+        // Act
+        let first =
+            GameService::strategy_payoff_matrix(&PayoffMatrix::default(), 20, &mut first_rng);
+        let second =
+            GameService::strategy_payoff_matrix(&PayoffMatrix::default(), 20, &mut second_rng);
+
+        // Assert
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_strategy_payoff_matrix_uses_given_payoffs() {
+        // Arrange: R = 2 の囚人のジレンマ（T > R > P > S）
+        let payoffs = PayoffMatrix::new(5, 2, 1, 0);
+
+        // Act
+        let matrix = GameService::strategy_payoff_matrix(&payoffs, 10, &mut rand::thread_rng());
+
+        // Assert: 相互裏切りは P、パブロフは協力し続ける相手に協力を続ける
+        assert_eq!(
+            matrix[&(StrategyType::AllDefect, StrategyType::AllDefect)],
+            1.0
+        );
+        assert_eq!(
+            matrix[&(StrategyType::Pavlov, StrategyType::AllCooperate)],
+            2.0
+        );
+    }
+
+    #[test]
+    fn test_strategy_payoff_matrix_zero_rounds_is_empty() {
+        // Arrange & Act
+        let matrix = GameService::strategy_payoff_matrix(
+            &PayoffMatrix::default(),
+            0,
+            &mut rand::thread_rng(),
+        );
+
+        // Assert
+        assert!(matrix.is_empty());
+    }
+
+    #[test]
+    fn test_public_goods_tit_for_tat_follows_group_majority() {
+        // Arrange: しっぺ返し 1 体と常に裏切る 2 体のグループ
+        let mut group = vec![
+            agent(StrategyType::TitForTat),
+            agent(StrategyType::AllDefect),
+            agent(StrategyType::AllDefect),
+        ];
+        let game = PublicGoodsGame::default();
+        let mut rng = rand::thread_rng();
+
+        // Act
+        let first = GameService::play_public_goods(&mut group, &game, 0.0, &mut rng);
+        let second = GameService::play_public_goods(&mut group, &game, 0.0, &mut rng);
+
+        // Assert: 初回は拠出して損をし、次は過半数に倣って裏切る
+        assert_eq!(first[0], Action::Cooperate);
+        assert_eq!(second[0], Action::Defect);
+        assert_eq!(group[0].score, 0);
+        assert_eq!(group[1].score, 5);
+    }
 }
@@ -0,0 +1,130 @@
+use super::Region;
+use crate::domain::agent::Position;
+use serde::{Deserialize, Serialize};
+
+// グリッドを columns × rows の矩形の部分集団（デーム、島モデルの島）に分割する
+// 対戦と通常の移動はデーム内に限られ、デーム間の移住は migration_rate で起こる
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DemeStructure {
+    pub columns: usize,
+    pub rows: usize,
+    pub migration_rate: f64, // 1ステップあたりの移住確率
+    // 島モデルの定期移住。migration_interval 世代ごとに各島のスコア上位 migrants 体を次の番号の島へ送る（0 で無効）
+    #[serde(default)]
+    pub migration_interval: u32,
+    #[serde(default)]
+    pub migrants: usize,
+}
+
+impl DemeStructure {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Self {
+            columns: columns.max(1),
+            rows: rows.max(1),
+            migration_rate: 0.0,
+            migration_interval: 0,
+            migrants: 0,
+        }
+    }
+
+    pub fn with_migration_rate(mut self, rate: f64) -> Self {
+        self.migration_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_scheduled_migration(mut self, interval: u32, migrants: usize) -> Self {
+        self.migration_interval = interval;
+        self.migrants = migrants;
+        self
+    }
+
+    // generation 世代目を作るときに定期移住を行うか
+    pub fn migration_due(&self, generation: u32) -> bool {
+        self.migration_interval > 0
+            && self.migrants > 0
+            && self.deme_count() > 1
+            && generation > 0
+            && generation.is_multiple_of(self.migration_interval)
+    }
+
+    pub fn deme_count(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    pub fn deme_index(&self, position: &Position, grid_width: usize, grid_height: usize) -> usize {
+        let column = (position.x * self.columns / grid_width.max(1)).min(self.columns - 1);
+        let row = (position.y * self.rows / grid_height.max(1)).min(self.rows - 1);
+        row * self.columns + column
+    }
+
+    pub fn regions(&self, grid_width: usize, grid_height: usize) -> Vec<Region> {
+        let x_bounds = Self::bounds(grid_width, self.columns);
+        let y_bounds = Self::bounds(grid_height, self.rows);
+
+        y_bounds
+            .windows(2)
+            .flat_map(|ys| {
+                x_bounds
+                    .windows(2)
+                    .map(move |xs| Region::new(xs[0], ys[0], xs[1] - xs[0], ys[1] - ys[0]))
+            })
+            .collect()
+    }
+
+    // deme_index と一致する境界（x が区画 c に属する ⇔ c*len <= x*parts < (c+1)*len）
+    fn bounds(length: usize, parts: usize) -> Vec<usize> {
+        (0..=parts)
+            .map(|part| (part * length).div_ceil(parts).min(length))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regions_match_deme_index() {
+        // Arrange
+        let demes = DemeStructure::new(3, 2);
+        let (width, height) = (10, 7);
+
+        // Act
+        let regions = demes.regions(width, height);
+
+        // Assert: 全セルがちょうど1つのデームに属し、その番号が deme_index と一致する
+        assert_eq!(regions.len(), demes.deme_count());
+        assert_eq!(
+            regions.iter().map(Region::area).sum::<usize>(),
+            width * height
+        );
+        for (index, region) in regions.iter().enumerate() {
+            for position in region.positions() {
+                assert_eq!(demes.deme_index(&position, width, height), index);
+            }
+        }
+    }
+
+    #[test]
+    fn test_migration_rate_is_clamped() {
+        // Arrange & Act
+        let demes = DemeStructure::new(2, 2).with_migration_rate(1.5);
+
+        // Assert
+        assert_eq!(demes.migration_rate, 1.0);
+    }
+
+    #[test]
+    fn test_scheduled_migration_is_due_every_interval_with_several_islands() {
+        // Arrange
+        let islands = DemeStructure::new(2, 2).with_scheduled_migration(3, 1);
+        let single = DemeStructure::new(1, 1).with_scheduled_migration(3, 1);
+
+        // Act & Assert
+        assert!(!islands.migration_due(0));
+        assert!(!islands.migration_due(2));
+        assert!(islands.migration_due(3));
+        assert!(islands.migration_due(6));
+        assert!(!single.migration_due(3));
+    }
+}
@@ -1,4 +1,8 @@
+use super::{
+    DemeStructure, NeighborhoodType, NetworkWorld, ResourceConfig, ResourceField, ZoneMap,
+};
 use crate::domain::agent::{Agent, Position};
+use crate::domain::game::PayoffMatrix;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -6,8 +10,19 @@ pub struct Grid {
     width: usize,
     height: usize,
     agents: HashMap<Uuid, Agent>,
-    position_map: HashMap<Position, Uuid>,
+    // 占有グリッド：セル (x, y) の添字 y * width + x にいるエージェント
+    occupancy: Vec<Option<Uuid>>,
     torus_mode: bool,
+    demes: Option<DemeStructure>,
+    neighborhood: NeighborhoodType,
+    // 環境レイヤー（設定時のみ）。世代交代の clear では初期化しない
+    resources: Option<ResourceField>,
+    // 設定時は neighborhood と torus_mode の代わりにグラフの隣接で近傍を決める
+    network: Option<NetworkWorld>,
+    // 利得表・突然変異率が周りと異なる区域（設定時のみ）
+    zones: Option<ZoneMap>,
+    // 通れないセル（添字は occupancy と同じ）。個体は置けず、近傍にも含まれない
+    obstacles: Vec<bool>,
 }
 
 impl Grid {
@@ -16,8 +31,14 @@ impl Grid {
             width,
             height,
             agents: HashMap::new(),
-            position_map: HashMap::new(),
+            occupancy: vec![None; width * height],
             torus_mode: false,
+            demes: None,
+            neighborhood: NeighborhoodType::default(),
+            resources: None,
+            network: None,
+            zones: None,
+            obstacles: vec![false; width * height],
         }
     }
 
@@ -30,6 +51,203 @@ impl Grid {
         self.torus_mode = torus_mode;
     }
 
+    pub fn with_demes(mut self, demes: Option<DemeStructure>) -> Self {
+        self.demes = demes;
+        self
+    }
+
+    pub fn set_demes(&mut self, demes: Option<DemeStructure>) {
+        self.demes = demes;
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: NeighborhoodType) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn set_neighborhood(&mut self, neighborhood: NeighborhoodType) {
+        self.neighborhood = neighborhood;
+    }
+
+    pub fn with_resources(mut self, config: Option<ResourceConfig>) -> Self {
+        self.set_resources(config);
+        self
+    }
+
+    // 資源量は満タンから始め直す
+    pub fn set_resources(&mut self, config: Option<ResourceConfig>) {
+        self.resources = config.map(|config| ResourceField::new(self.width, self.height, config));
+    }
+
+    // 保存しておいた資源量をそのまま戻す
+    pub fn restore_resources(&mut self, field: ResourceField) -> Result<(), String> {
+        if field.levels().len() != self.width * self.height {
+            return Err(format!(
+                "Resource field has {} cells but the grid has {} cells",
+                field.levels().len(),
+                self.width * self.height
+            ));
+        }
+        self.resources = Some(field);
+        Ok(())
+    }
+
+    pub fn with_network(mut self, network: Option<NetworkWorld>) -> Self {
+        self.network = network;
+        self
+    }
+
+    pub fn set_network(&mut self, network: Option<NetworkWorld>) {
+        self.network = network;
+    }
+
+    pub fn with_zones(mut self, zones: Option<ZoneMap>) -> Self {
+        self.zones = zones;
+        self
+    }
+
+    pub fn set_zones(&mut self, zones: Option<ZoneMap>) {
+        self.zones = zones;
+    }
+
+    pub fn zones(&self) -> Option<&ZoneMap> {
+        self.zones.as_ref()
+    }
+
+    // セルの区域が利得表を持っていればそれを、なければ base を返す
+    pub fn payoffs_at(&self, position: &Position, base: &PayoffMatrix) -> PayoffMatrix {
+        self.zones
+            .as_ref()
+            .and_then(|zones| zones.payoffs_at(position))
+            .unwrap_or(*base)
+    }
+
+    pub fn with_obstacles(mut self, positions: &[Position]) -> Self {
+        for position in positions {
+            if let Some(index) = self.cell_index(position) {
+                self.obstacles[index] = true;
+            }
+        }
+        self
+    }
+
+    // 壁を置く・取り除く。壁を置いたセルにいた個体は取り除いて返す
+    pub fn set_obstacle(
+        &mut self,
+        position: &Position,
+        blocked: bool,
+    ) -> Result<Option<Agent>, String> {
+        let index = self.cell_index(position).ok_or_else(|| {
+            format!(
+                "Position ({}, {}) is out of bounds for grid {}x{}",
+                position.x, position.y, self.width, self.height
+            )
+        })?;
+        let removed = match (blocked, self.occupancy[index]) {
+            (true, Some(id)) => self.remove_agent(&id),
+            _ => None,
+        };
+        self.obstacles[index] = blocked;
+        Ok(removed)
+    }
+
+    pub fn is_obstacle(&self, position: &Position) -> bool {
+        self.cell_index(position)
+            .is_some_and(|index| self.obstacles[index])
+    }
+
+    // 行優先（(y, x) 順）で壁のセルを返す
+    pub fn obstacle_positions(&self) -> Vec<Position> {
+        self.obstacles
+            .iter()
+            .enumerate()
+            .filter(|(_, &blocked)| blocked)
+            .map(|(index, _)| Position::new(index % self.width, index / self.width))
+            .collect()
+    }
+
+    // 個体を置けるセルの数（壁を除く）
+    pub fn open_cell_count(&self) -> usize {
+        self.obstacles.iter().filter(|&&blocked| !blocked).count()
+    }
+
+    pub fn network(&self) -> Option<&NetworkWorld> {
+        self.network.as_ref()
+    }
+
+    pub fn network_mut(&mut self) -> Option<&mut NetworkWorld> {
+        self.network.as_mut()
+    }
+
+    // セル (x, y) のネットワーク上のノード番号 y * width + x（盤面の外なら None）
+    pub fn node_of(&self, position: &Position) -> Option<usize> {
+        self.cell_index(position)
+    }
+
+    pub fn resources(&self) -> Option<&ResourceField> {
+        self.resources.as_ref()
+    }
+
+    pub fn resources_mut(&mut self) -> Option<&mut ResourceField> {
+        self.resources.as_mut()
+    }
+
+    pub fn neighborhood(&self) -> NeighborhoodType {
+        self.neighborhood
+    }
+
+    pub fn demes(&self) -> Option<&DemeStructure> {
+        self.demes.as_ref()
+    }
+
+    pub fn deme_of(&self, position: &Position) -> Option<usize> {
+        self.demes
+            .as_ref()
+            .map(|demes| demes.deme_index(position, self.width, self.height))
+    }
+
+    pub fn same_deme(&self, a: &Position, b: &Position) -> bool {
+        self.deme_of(a) == self.deme_of(b)
+    }
+
+    // デームが設定されている場合、境界をまたぐセルは隣接とみなさない
+    pub fn neighbor_positions(&self, position: &Position) -> Vec<Position> {
+        let mut neighbors = match (&self.network, self.cell_index(position)) {
+            (Some(network), Some(node)) => self.node_positions(network.neighbors(node)),
+            (Some(_), None) => Vec::new(),
+            (None, _) => {
+                self.neighborhood
+                    .neighbors(position, self.width, self.height, self.torus_mode)
+            }
+        };
+        if self.demes.is_some() {
+            neighbors.retain(|neighbor| self.same_deme(position, neighbor));
+        }
+        neighbors.retain(|neighbor| !self.is_obstacle(neighbor));
+        neighbors
+    }
+
+    pub fn position_of_node(&self, node: usize) -> Position {
+        Position::new(node % self.width, node / self.width)
+    }
+
+    fn node_positions(&self, nodes: &[usize]) -> Vec<Position> {
+        nodes
+            .iter()
+            .map(|&node| self.position_of_node(node))
+            .collect()
+    }
+
+    fn cell_index(&self, position: &Position) -> Option<usize> {
+        (position.x < self.width && position.y < self.height)
+            .then(|| position.y * self.width + position.x)
+    }
+
+    fn occupant(&self, position: &Position) -> Option<Uuid> {
+        self.cell_index(position)
+            .and_then(|index| self.occupancy[index])
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -47,22 +265,25 @@ impl Grid {
             ));
         }
 
-        if self.position_map.contains_key(&agent.position) {
+        let index = agent.position.y * self.width + agent.position.x;
+        if self.occupancy[index].is_some() {
             return Err("Position already occupied".to_string());
         }
+        if self.obstacles[index] {
+            return Err("Position is blocked by an obstacle".to_string());
+        }
 
-        let id = agent.id;
-        let position = agent.position;
-
-        self.agents.insert(id, agent);
-        self.position_map.insert(position, id);
+        self.occupancy[index] = Some(agent.id);
+        self.agents.insert(agent.id, agent);
 
         Ok(())
     }
 
     pub fn remove_agent(&mut self, id: &Uuid) -> Option<Agent> {
         if let Some(agent) = self.agents.remove(id) {
-            self.position_map.remove(&agent.position);
+            if let Some(index) = self.cell_index(&agent.position) {
+                self.occupancy[index] = None;
+            }
             Some(agent)
         } else {
             None
@@ -78,35 +299,86 @@ impl Grid {
     }
 
     pub fn get_agent_at_position(&self, position: &Position) -> Option<&Agent> {
-        // Validate position bounds
-        if position.x >= self.width || position.y >= self.height {
-            return None;
-        }
-
-        self.position_map
-            .get(position)
-            .and_then(|id| self.agents.get(id))
+        self.occupant(position).and_then(|id| self.agents.get(&id))
     }
 
     pub fn get_neighbors(&self, position: &Position) -> Vec<&Agent> {
-        position
-            .neighbors_with_mode(self.width, self.height, self.torus_mode)
+        self.neighbor_positions(position)
             .iter()
             .filter_map(|pos| self.get_agent_at_position(pos))
             .collect()
     }
 
+    // 近傍設定やデームと関係なく、チェビシェフ距離 radius 以内にいる個体（位置順）
+    // ネットワークがあれば radius 本以内の辺でたどれる個体
+    pub fn agents_within(&self, position: &Position, radius: u32) -> Vec<&Agent> {
+        let mut agents: Vec<&Agent> = self
+            .positions_within(position, radius)
+            .iter()
+            .filter_map(|pos| self.get_agent_at_position(pos))
+            .collect();
+        agents.sort_by_key(|agent| (agent.position.y, agent.position.x));
+        agents
+    }
+
+    // agents_within と同じ範囲の空きセル（壁を除く、位置順）
+    pub fn empty_positions_within(&self, position: &Position, radius: u32) -> Vec<Position> {
+        let mut positions: Vec<Position> = self
+            .positions_within(position, radius)
+            .into_iter()
+            .filter(|pos| self.is_position_free(pos))
+            .collect();
+        positions.sort_by_key(|pos| (pos.y, pos.x));
+        positions
+    }
+
+    // チェビシェフ距離（トーラスなら端をまたいだ短い方）
+    pub fn chebyshev_distance(&self, a: &Position, b: &Position) -> usize {
+        let axis = |p: usize, q: usize, size: usize| {
+            let direct = p.abs_diff(q);
+            if self.torus_mode {
+                direct.min(size - direct)
+            } else {
+                direct
+            }
+        };
+        axis(a.x, b.x, self.width).max(axis(a.y, b.y, self.height))
+    }
+
+    fn positions_within(&self, position: &Position, radius: u32) -> Vec<Position> {
+        match (&self.network, self.cell_index(position)) {
+            (Some(network), Some(node)) => self.node_positions(&network.within(node, radius)),
+            (Some(_), None) => Vec::new(),
+            (None, _) => NeighborhoodType::Radius(radius).neighbors(
+                position,
+                self.width,
+                self.height,
+                self.torus_mode,
+            ),
+        }
+    }
+
     pub fn get_neighbors_mut(&mut self, position: &Position) -> Vec<Uuid> {
-        position
-            .neighbors_with_mode(self.width, self.height, self.torus_mode)
+        self.neighbor_positions(position)
             .iter()
-            .filter_map(|pos| self.position_map.get(pos))
-            .copied()
+            .filter_map(|pos| self.occupant(pos))
             .collect()
     }
 
+    // 壁のセルは空いていても free ではない
     pub fn is_position_free(&self, position: &Position) -> bool {
-        !self.position_map.contains_key(position)
+        self.occupant(position).is_none() && !self.is_obstacle(position)
+    }
+
+    // 行優先（(y, x) 順）で空きセル（壁を除く）を返す
+    pub fn get_empty_positions(&self) -> Vec<Position> {
+        self.occupancy
+            .iter()
+            .zip(&self.obstacles)
+            .enumerate()
+            .filter(|(_, (occupant, &blocked))| occupant.is_none() && !blocked)
+            .map(|(index, _)| Position::new(index % self.width, index / self.width))
+            .collect()
     }
 
     pub fn move_agent(&mut self, id: &Uuid, new_position: Position) -> Result<(), String> {
@@ -119,15 +391,17 @@ impl Grid {
         }
 
         if !self.is_position_free(&new_position) {
-            return Err("Target position is occupied".to_string());
+            return Err("Target position is occupied or blocked".to_string());
         }
 
         if let Some(agent) = self.agents.get_mut(id) {
             let old_position = agent.position;
             agent.move_to(new_position);
 
-            self.position_map.remove(&old_position);
-            self.position_map.insert(new_position, *id);
+            if let Some(index) = self.cell_index(&old_position) {
+                self.occupancy[index] = None;
+            }
+            self.occupancy[new_position.y * self.width + new_position.x] = Some(*id);
 
             Ok(())
         } else {
@@ -136,17 +410,33 @@ impl Grid {
     }
 
     pub fn get_empty_neighbors(&self, position: &Position) -> Vec<Position> {
-        position
-            .neighbors_with_mode(self.width, self.height, self.torus_mode)
+        self.neighbor_positions(position)
             .into_iter()
             .filter(|pos| self.is_position_free(pos))
             .collect()
     }
 
+    pub fn neighbor_cooperation(&self, position: &Position, exclude_id: &Uuid) -> f64 {
+        self.get_neighbors(position)
+            .into_iter()
+            .filter(|agent| &agent.id != exclude_id)
+            .map(|agent| agent.cooperation_rate())
+            .sum()
+    }
+
     pub fn agents(&self) -> &HashMap<Uuid, Agent> {
         &self.agents
     }
 
+    // HashMap の走査順に依存しないよう位置 (y, x) 順に並べる（シード再現性のため）
+    pub fn agents_in_position_order(&self) -> Vec<&Agent> {
+        self.occupancy
+            .iter()
+            .flatten()
+            .filter_map(|id| self.agents.get(id))
+            .collect()
+    }
+
     pub fn agents_mut(&mut self) -> &mut HashMap<Uuid, Agent> {
         &mut self.agents
     }
@@ -157,6 +447,118 @@ impl Grid {
 
     pub fn clear(&mut self) {
         self.agents.clear();
-        self.position_map.clear();
+        self.occupancy.fill(None);
+    }
+
+    // 全個体を取り出して盤面を空にする（確保済みの領域は次の世代に使い回す）
+    pub fn drain_agents(&mut self) -> impl Iterator<Item = Agent> + '_ {
+        self.occupancy.fill(None);
+        self.agents.drain().map(|(_, agent)| agent)
+    }
+
+    // 個体の表・占有・壁と、各個体が持つヒープ領域の概算（資源・ネットワーク・区域は含まない）
+    pub fn heap_bytes(&self) -> usize {
+        self.agents.capacity() * (std::mem::size_of::<(Uuid, Agent)>() + 1)
+            + self.occupancy.capacity() * std::mem::size_of::<Option<Uuid>>()
+            + self.obstacles.capacity()
+            + self.agents.values().map(Agent::heap_bytes).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, StrategyType};
+
+    fn agent_at(x: usize, y: usize) -> Agent {
+        Agent::new(
+            Position::new(x, y),
+            StrategyType::AllCooperate,
+            0.5,
+            MovementStrategy::Explorer,
+        )
+    }
+
+    #[test]
+    fn test_occupancy_follows_add_move_and_remove() {
+        // Arrange
+        let mut grid = Grid::new(3, 2);
+        let first = agent_at(2, 1);
+        let second = agent_at(0, 0);
+        let first_id = first.id;
+        grid.add_agent(first).unwrap();
+        grid.add_agent(second).unwrap();
+
+        // Act
+        grid.move_agent(&first_id, Position::new(1, 0)).unwrap();
+
+        // Assert: 移動元が空き、移動先から引ける。位置順は (y, x) 順
+        assert!(grid.is_position_free(&Position::new(2, 1)));
+        assert_eq!(
+            grid.get_agent_at_position(&Position::new(1, 0))
+                .map(|a| a.id),
+            Some(first_id)
+        );
+        let order: Vec<Position> = grid
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| agent.position)
+            .collect();
+        assert_eq!(order, vec![Position::new(0, 0), Position::new(1, 0)]);
+        assert_eq!(grid.get_empty_positions().len(), 4);
+
+        grid.remove_agent(&first_id);
+        assert!(grid.is_position_free(&Position::new(1, 0)));
+        assert!(grid.get_agent_at_position(&Position::new(5, 5)).is_none());
+    }
+
+    #[test]
+    fn test_obstacles_block_placement_movement_and_neighbors() {
+        // Arrange: 3x3 の中央の列を壁にする
+        let mut grid = Grid::new(3, 3).with_obstacles(&[Position::new(1, 0), Position::new(1, 2)]);
+        let left = agent_at(0, 1);
+        let left_id = left.id;
+        grid.add_agent(left).unwrap();
+        grid.add_agent(agent_at(1, 1)).unwrap();
+        grid.add_agent(agent_at(2, 1)).unwrap();
+
+        // Act: 中央に残っていた個体ごと壁でふさぐ
+        let removed = grid.set_obstacle(&Position::new(1, 1), true).unwrap();
+
+        // Assert
+        assert_eq!(
+            removed.map(|agent| agent.position),
+            Some(Position::new(1, 1))
+        );
+        assert!(grid.get_neighbors(&Position::new(0, 1)).is_empty());
+        assert!(grid
+            .neighbor_positions(&Position::new(0, 1))
+            .iter()
+            .all(|position| position.x == 0));
+        assert!(grid.move_agent(&left_id, Position::new(1, 0)).is_err());
+        assert!(grid.add_agent(agent_at(1, 2)).is_err());
+        assert_eq!(grid.get_empty_positions().len(), 4);
+        assert_eq!(grid.open_cell_count(), 6);
+        assert_eq!(grid.obstacle_positions().len(), 3);
+        assert!(grid.set_obstacle(&Position::new(3, 0), true).is_err());
+    }
+
+    #[test]
+    fn test_drain_agents_empties_the_grid() {
+        // Arrange
+        let mut grid = Grid::new(3, 2);
+        grid.add_agent(agent_at(0, 0)).unwrap();
+        grid.add_agent(agent_at(2, 1)).unwrap();
+        let occupied_bytes = grid.heap_bytes();
+
+        // Act
+        let drained: Vec<Agent> = grid.drain_agents().collect();
+
+        // Assert: 個体の表の確保はそのまま残る
+        assert_eq!(drained.len(), 2);
+        assert_eq!(grid.agent_count(), 0);
+        assert_eq!(grid.get_empty_positions().len(), 6);
+        assert!(grid.heap_bytes() > 0 && grid.heap_bytes() <= occupied_bytes);
+        grid.add_agent(agent_at(0, 0)).unwrap();
     }
 }
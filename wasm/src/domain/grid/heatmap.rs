@@ -0,0 +1,108 @@
+use super::Grid;
+use crate::domain::agent::{Agent, Position};
+use serde::{Deserialize, Serialize};
+
+// セルごとに描画する値
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeatmapField {
+    Cooperation,
+    Score,
+    // エージェントがいれば 1.0、空きセルは 0.0
+    Occupancy,
+    Energy,
+    // 資源レイヤーがなければ全セル NaN
+    Resources,
+    // 区域の番号（どの区域にも入らないセルは NaN）
+    Zone,
+    // 壁のセルは 1.0、それ以外は 0.0
+    Obstacle,
+}
+
+impl HeatmapField {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cooperation" => Some(HeatmapField::Cooperation),
+            "score" => Some(HeatmapField::Score),
+            "occupancy" => Some(HeatmapField::Occupancy),
+            "energy" => Some(HeatmapField::Energy),
+            "resources" => Some(HeatmapField::Resources),
+            "zone" => Some(HeatmapField::Zone),
+            "obstacle" => Some(HeatmapField::Obstacle),
+            _ => None,
+        }
+    }
+
+    // 行優先（添字 y * width + x）の width × height 行列。エージェントの値を描く場合、空きセルは NaN
+    pub fn render(&self, grid: &Grid) -> Vec<f32> {
+        let mut values = Vec::with_capacity(grid.width() * grid.height());
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let position = Position::new(x, y);
+                values.push(self.sample(grid, &position));
+            }
+        }
+        values
+    }
+
+    fn sample(&self, grid: &Grid, position: &Position) -> f32 {
+        let agent = grid.get_agent_at_position(position);
+        let value = |read: fn(&Agent) -> f64| agent.map_or(f32::NAN, |agent| read(agent) as f32);
+        match self {
+            HeatmapField::Cooperation => value(Agent::cooperation_rate),
+            HeatmapField::Score => value(|agent| agent.score as f64),
+            HeatmapField::Energy => value(|agent| agent.energy),
+            HeatmapField::Occupancy => {
+                if agent.is_some() {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            HeatmapField::Resources => grid
+                .resources()
+                .map_or(f32::NAN, |resources| resources.level(position) as f32),
+            HeatmapField::Obstacle => {
+                if grid.is_obstacle(position) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            HeatmapField::Zone => grid
+                .zones()
+                .and_then(|zones| zones.zone_index(position))
+                .map_or(f32::NAN, |index| index as f32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, StrategyType};
+
+    #[test]
+    fn test_render_is_row_major_with_nan_for_empty_cells() {
+        // Arrange
+        let mut grid = Grid::new(3, 2);
+        let mut agent = Agent::new(
+            Position::new(2, 1),
+            StrategyType::TitForTat,
+            0.0,
+            MovementStrategy::Settler,
+        );
+        agent.score = 7;
+        grid.add_agent(agent).unwrap();
+
+        // Act
+        let scores = HeatmapField::Score.render(&grid);
+        let occupancy = HeatmapField::Occupancy.render(&grid);
+
+        // Assert
+        assert_eq!(scores.len(), 6);
+        assert_eq!(scores[5], 7.0);
+        assert!(scores[..5].iter().all(|value| value.is_nan()));
+        assert_eq!(occupancy, vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert!(HeatmapField::Resources.render(&grid)[0].is_nan());
+    }
+}
@@ -1,5 +1,23 @@
+pub mod deme;
 pub mod entity;
+pub mod heatmap;
+pub mod movement;
+pub mod neighborhood;
+pub mod network;
+pub mod placement;
+pub mod region;
+pub mod resource;
 pub mod service;
+pub mod zone;
 
+pub use deme::*;
 pub use entity::*;
+pub use heatmap::*;
+pub use movement::*;
+pub use neighborhood::*;
+pub use network::*;
+pub use placement::*;
+pub use region::*;
+pub use resource::*;
 pub use service::*;
+pub use zone::*;
@@ -0,0 +1,315 @@
+use super::Grid;
+use crate::domain::agent::{Agent, Position};
+use crate::domain::game::PayoffMatrix;
+use serde::{Deserialize, Serialize};
+
+// 移動方針の拡張点。移動するかどうかは個体の mobility（遺伝する形質）の確率で決まり、
+// 方針は移動先だけを選ぶ。random は [0, 1) の一様乱数で、同じ評価の候補から 1 つ選ぶのに使う
+pub trait MovementPolicy: Send + Sync {
+    fn name(&self) -> &str;
+
+    // 移動しないなら None
+    fn choose_target(&self, grid: &Grid, agent: &Agent, random: f64) -> Option<Position>;
+}
+
+pub struct StayPolicy;
+pub struct RandomWalkPolicy;
+pub struct GradientPolicy;
+pub struct FleeDefectorsPolicy;
+
+// 成功駆動の移住：radius 以内の空きセルのうち、周りの個体との対戦の期待利得が今より高いセルへ移る
+pub struct SuccessDrivenPolicy {
+    pub radius: u32,
+    pub payoffs: PayoffMatrix,
+}
+
+impl MovementPolicy for StayPolicy {
+    fn name(&self) -> &str {
+        "stay"
+    }
+
+    fn choose_target(&self, _grid: &Grid, _agent: &Agent, _random: f64) -> Option<Position> {
+        None
+    }
+}
+
+impl MovementPolicy for RandomWalkPolicy {
+    fn name(&self) -> &str {
+        "random_walk"
+    }
+
+    fn choose_target(&self, grid: &Grid, agent: &Agent, random: f64) -> Option<Position> {
+        pick(&grid.get_empty_neighbors(&agent.position), random)
+    }
+}
+
+impl MovementPolicy for GradientPolicy {
+    fn name(&self) -> &str {
+        "gradient"
+    }
+
+    // 周りの個体の平均スコアがもっとも高い隣の空きセルへ（今のセルより高いときだけ）
+    fn choose_target(&self, grid: &Grid, agent: &Agent, random: f64) -> Option<Position> {
+        let mean_score = |position: &Position| {
+            let scores: Vec<f64> = neighbors_except(grid, position, agent)
+                .map(|neighbor| neighbor.score as f64)
+                .collect();
+            if scores.is_empty() {
+                f64::NEG_INFINITY
+            } else {
+                scores.iter().sum::<f64>() / scores.len() as f64
+            }
+        };
+        best_improvement(
+            &grid.get_empty_neighbors(&agent.position),
+            mean_score(&agent.position),
+            mean_score,
+            random,
+        )
+    }
+}
+
+impl MovementPolicy for FleeDefectorsPolicy {
+    fn name(&self) -> &str {
+        "flee_defectors"
+    }
+
+    // 協力率が 5 割未満の隣人がいれば、そうした隣人がもっとも少ない隣の空きセルへ逃げる
+    fn choose_target(&self, grid: &Grid, agent: &Agent, random: f64) -> Option<Position> {
+        let defectors = |position: &Position| {
+            -(neighbors_except(grid, position, agent)
+                .filter(|neighbor| neighbor.cooperation_rate() < 0.5)
+                .count() as f64)
+        };
+        best_improvement(
+            &grid.get_empty_neighbors(&agent.position),
+            defectors(&agent.position),
+            defectors,
+            random,
+        )
+    }
+}
+
+impl MovementPolicy for SuccessDrivenPolicy {
+    fn name(&self) -> &str {
+        "success_driven"
+    }
+
+    fn choose_target(&self, grid: &Grid, agent: &Agent, random: f64) -> Option<Position> {
+        let cooperation = agent.cooperation_rate();
+        let expected = |position: &Position| {
+            neighbors_except(grid, position, agent)
+                .map(|neighbor| self.expected_payoff(cooperation, neighbor.cooperation_rate()))
+                .sum::<f64>()
+        };
+        best_improvement(
+            &grid.empty_positions_within(&agent.position, self.radius),
+            expected(&agent.position),
+            expected,
+            random,
+        )
+    }
+}
+
+impl SuccessDrivenPolicy {
+    // 協力率 mine と theirs の個体が 1 回対戦したときの自分の期待利得
+    fn expected_payoff(&self, mine: f64, theirs: f64) -> f64 {
+        let payoffs = &self.payoffs;
+        mine * theirs * payoffs.reward as f64
+            + mine * (1.0 - theirs) * payoffs.sucker as f64
+            + (1.0 - mine) * theirs * payoffs.temptation as f64
+            + (1.0 - mine) * (1.0 - theirs) * payoffs.punishment as f64
+    }
+}
+
+// position の近傍にいる agent 以外の個体（移動先の候補では自分が近傍に入ることがある）
+fn neighbors_except<'a>(
+    grid: &'a Grid,
+    position: &Position,
+    agent: &'a Agent,
+) -> impl Iterator<Item = &'a Agent> {
+    grid.get_neighbors(position)
+        .into_iter()
+        .filter(move |neighbor| neighbor.id != agent.id)
+}
+
+fn pick(candidates: &[Position], random: f64) -> Option<Position> {
+    if candidates.is_empty() {
+        return None;
+    }
+    let index = ((random * candidates.len() as f64) as usize).min(candidates.len() - 1);
+    Some(candidates[index])
+}
+
+// 評価が current より高い候補のうち最高のもの（同点は random で選ぶ）
+fn best_improvement(
+    candidates: &[Position],
+    current: f64,
+    value: impl Fn(&Position) -> f64,
+    random: f64,
+) -> Option<Position> {
+    let scored: Vec<(Position, f64)> = candidates
+        .iter()
+        .map(|position| (*position, value(position)))
+        .collect();
+    let best = scored
+        .iter()
+        .map(|&(_, score)| score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if best <= current {
+        return None;
+    }
+    let ties: Vec<Position> = scored
+        .into_iter()
+        .filter(|&(_, score)| score == best)
+        .map(|(position, _)| position)
+        .collect();
+    pick(&ties, random)
+}
+
+// 対戦の後、戦略の更新（模倣や世代交代）の前に行う移住
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MigrationModel {
+    #[default]
+    None,
+    // Helbing & Yu (2009) の成功駆動の移住：range 以内の空きセルで試しに対戦した利得を比べ、
+    // 今のセルより高い最良のセル（同点なら近い方）へ移る
+    SuccessDriven(u32),
+}
+
+impl MigrationModel {
+    // 論文の標準設定 M = 5（11x11 の範囲）
+    pub const DEFAULT_RANGE: u32 = 5;
+
+    // "none" / "success_driven" / "success_driven:R"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(MigrationModel::None),
+            "success_driven" => Some(MigrationModel::SuccessDriven(Self::DEFAULT_RANGE)),
+            _ => name
+                .strip_prefix("success_driven:")
+                .and_then(|range| range.parse().ok())
+                .map(MigrationModel::SuccessDriven),
+        }
+    }
+}
+
+// 設定で選ぶ移動方針
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementPolicyKind {
+    Stay,
+    RandomWalk,
+    Gradient,
+    FleeDefectors,
+    SuccessDriven { radius: u32 },
+}
+
+impl MovementPolicyKind {
+    // "stay" / "random_walk" / "gradient" / "flee_defectors" / "success_driven"（半径 2） / "success_driven:R"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stay" => Some(MovementPolicyKind::Stay),
+            "random_walk" => Some(MovementPolicyKind::RandomWalk),
+            "gradient" => Some(MovementPolicyKind::Gradient),
+            "flee_defectors" => Some(MovementPolicyKind::FleeDefectors),
+            "success_driven" => Some(MovementPolicyKind::SuccessDriven { radius: 2 }),
+            _ => name
+                .strip_prefix("success_driven:")
+                .and_then(|radius| radius.parse().ok())
+                .map(|radius| MovementPolicyKind::SuccessDriven { radius }),
+        }
+    }
+
+    // payoffs は成功駆動の移住が期待利得の計算に使う利得表
+    pub fn policy(&self, payoffs: PayoffMatrix) -> Box<dyn MovementPolicy> {
+        match *self {
+            MovementPolicyKind::Stay => Box::new(StayPolicy),
+            MovementPolicyKind::RandomWalk => Box::new(RandomWalkPolicy),
+            MovementPolicyKind::Gradient => Box::new(GradientPolicy),
+            MovementPolicyKind::FleeDefectors => Box::new(FleeDefectorsPolicy),
+            MovementPolicyKind::SuccessDriven { radius } => {
+                Box::new(SuccessDrivenPolicy { radius, payoffs })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, MovementStrategy, StrategyType};
+    use uuid::Uuid;
+
+    fn agent_at(x: usize, y: usize, action: Action, score: i32) -> Agent {
+        let mut agent = Agent::new(
+            Position::new(x, y),
+            StrategyType::AllCooperate,
+            1.0,
+            MovementStrategy::Explorer,
+        );
+        agent.add_game_result(Uuid::new_v4(), action, Action::Cooperate, 0);
+        agent.score = score;
+        agent
+    }
+
+    // 5x1 の一列：(0,0) に動く個体、(1,0) は空き、(2,0) に隣人、(4,0) に離れた個体
+    fn corridor(mover: Agent, near: Agent, far: Agent) -> Grid {
+        let mut grid = Grid::new(5, 1);
+        for agent in [mover, near, far] {
+            grid.add_agent(agent).unwrap();
+        }
+        grid
+    }
+
+    #[test]
+    fn test_gradient_and_flee_move_only_when_the_target_is_better() {
+        // Arrange
+        let mover = agent_at(0, 0, Action::Cooperate, 0);
+        let rich = corridor(
+            mover.clone(),
+            agent_at(2, 0, Action::Cooperate, 9),
+            agent_at(4, 0, Action::Cooperate, 0),
+        );
+        let hostile = corridor(
+            mover.clone(),
+            agent_at(2, 0, Action::Defect, 0),
+            agent_at(4, 0, Action::Defect, 0),
+        );
+
+        // Act
+        let gradient = GradientPolicy.choose_target(&rich, &mover, 0.0);
+        let flee = FleeDefectorsPolicy.choose_target(&hostile, &mover, 0.0);
+
+        // Assert: 今のセルには隣人がいないので、高スコアの隣人のいるセルへ動く
+        // 裏切り者から逃げる方針は、今のセルに裏切り者がいなければ動かない
+        assert_eq!(gradient, Some(Position::new(1, 0)));
+        assert_eq!(flee, None);
+        assert_eq!(StayPolicy.choose_target(&rich, &mover, 0.5), None);
+        assert_eq!(
+            RandomWalkPolicy.choose_target(&rich, &mover, 0.5),
+            Some(Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_success_driven_migrates_next_to_cooperators_within_radius() {
+        // Arrange: 裏切り者 (2,0) の隣から、半径 2 以内で協力者 (4,0) の隣の (3,0) へ移れる
+        let mover = agent_at(1, 0, Action::Defect, 0);
+        let mut grid = Grid::new(5, 1);
+        grid.add_agent(mover.clone()).unwrap();
+        grid.add_agent(agent_at(0, 0, Action::Defect, 0)).unwrap();
+        grid.add_agent(agent_at(4, 0, Action::Cooperate, 0))
+            .unwrap();
+        let policy = MovementPolicyKind::from_name("success_driven:2")
+            .unwrap()
+            .policy(PayoffMatrix::prisoners_dilemma());
+
+        // Act
+        let target = policy.choose_target(&grid, &mover, 0.0);
+
+        // Assert
+        assert_eq!(target, Some(Position::new(3, 0)));
+        assert_eq!(policy.name(), "success_driven");
+        assert_eq!(MovementPolicyKind::from_name("teleport"), None);
+    }
+}
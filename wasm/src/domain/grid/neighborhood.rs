@@ -0,0 +1,175 @@
+use crate::domain::agent::Position;
+use serde::{Deserialize, Serialize};
+
+// 対戦・移動で隣接とみなすセルの取り方
+// Moore は 8 近傍、VonNeumann は上下左右の 4 近傍、Radius(r) はチェビシェフ距離 r 以内
+// Hex は (x, y) を軸座標 (q, r) として扱う六角格子で、隣接は 6 セル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NeighborhoodType {
+    #[default]
+    Moore,
+    VonNeumann,
+    Radius(u32),
+    Hex,
+}
+
+impl NeighborhoodType {
+    // "moore" / "von_neumann" / "hex" / "radius:N"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "moore" => Some(NeighborhoodType::Moore),
+            "von_neumann" => Some(NeighborhoodType::VonNeumann),
+            "hex" => Some(NeighborhoodType::Hex),
+            _ => name
+                .strip_prefix("radius:")
+                .and_then(|radius| radius.parse().ok())
+                .map(NeighborhoodType::Radius),
+        }
+    }
+
+    pub fn offsets(&self) -> Vec<(i32, i32)> {
+        match self {
+            NeighborhoodType::Moore => Self::square_offsets(1),
+            NeighborhoodType::VonNeumann => vec![(-1, 0), (0, -1), (0, 1), (1, 0)],
+            NeighborhoodType::Radius(radius) => Self::square_offsets(*radius as i32),
+            NeighborhoodType::Hex => vec![(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)],
+        }
+    }
+
+    fn square_offsets(radius: i32) -> Vec<(i32, i32)> {
+        (-radius..=radius)
+            .flat_map(|dx| (-radius..=radius).map(move |dy| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .collect()
+    }
+
+    pub fn neighbors(
+        &self,
+        position: &Position,
+        grid_width: usize,
+        grid_height: usize,
+        torus_mode: bool,
+    ) -> Vec<Position> {
+        let (width, height) = (grid_width as i32, grid_height as i32);
+        let offsets = self.offsets();
+        let mut neighbors = Vec::with_capacity(offsets.len());
+
+        for (dx, dy) in offsets {
+            let x = position.x as i32 + dx;
+            let y = position.y as i32 + dy;
+            let neighbor = if torus_mode {
+                // トーラス平面モード：端をループ（六角格子では平行四辺形の盤面として巡回）
+                Position::new(x.rem_euclid(width) as usize, y.rem_euclid(height) as usize)
+            } else if (0..width).contains(&x) && (0..height).contains(&y) {
+                Position::new(x as usize, y as usize)
+            } else {
+                continue;
+            };
+
+            // 小さい盤面のトーラスでは同じセルや自分自身に巡回し得るので除く
+            if neighbor != *position && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_interior_cell_has_six_axial_neighbors() {
+        // Arrange
+        let position = Position::new(5, 5);
+
+        // Act
+        let neighbors = NeighborhoodType::Hex.neighbors(&position, 10, 10, false);
+
+        // Assert
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&Position::new(6, 4)));
+        assert!(neighbors.contains(&Position::new(4, 6)));
+        assert!(!neighbors.contains(&Position::new(6, 6)));
+        assert!(!neighbors.contains(&Position::new(4, 4)));
+    }
+
+    #[test]
+    fn test_hex_neighborhood_is_symmetric_and_wraps_on_torus() {
+        // Arrange
+        let (width, height) = (6, 5);
+
+        // Act & Assert: a が b の隣なら b も a の隣
+        for y in 0..height {
+            for x in 0..width {
+                let position = Position::new(x, y);
+                let neighbors = NeighborhoodType::Hex.neighbors(&position, width, height, true);
+                assert_eq!(neighbors.len(), 6);
+                for neighbor in neighbors {
+                    assert!(NeighborhoodType::Hex
+                        .neighbors(&neighbor, width, height, true)
+                        .contains(&position));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_von_neumann_and_radius_neighbor_counts() {
+        // Arrange
+        let center = Position::new(5, 5);
+
+        // Act & Assert
+        assert_eq!(
+            NeighborhoodType::VonNeumann
+                .neighbors(&center, 11, 11, false)
+                .len(),
+            4
+        );
+        assert_eq!(
+            NeighborhoodType::Radius(2)
+                .neighbors(&center, 11, 11, false)
+                .len(),
+            24
+        );
+        assert_eq!(
+            NeighborhoodType::Radius(1).neighbors(&center, 11, 11, false),
+            NeighborhoodType::Moore.neighbors(&center, 11, 11, false)
+        );
+        // 3x3 のトーラスでは半径 2 でも自分以外の 8 セルに収まる
+        assert_eq!(
+            NeighborhoodType::Radius(2)
+                .neighbors(&Position::new(1, 1), 3, 3, true)
+                .len(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_from_name_parses_radius() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            NeighborhoodType::from_name("von_neumann"),
+            Some(NeighborhoodType::VonNeumann)
+        );
+        assert_eq!(
+            NeighborhoodType::from_name("radius:3"),
+            Some(NeighborhoodType::Radius(3))
+        );
+        assert_eq!(NeighborhoodType::from_name("radius:x"), None);
+    }
+
+    #[test]
+    fn test_moore_matches_position_neighbors() {
+        // Arrange
+        let position = Position::new(0, 3);
+
+        // Act & Assert
+        assert_eq!(
+            NeighborhoodType::Moore.neighbors(&position, 10, 10, false),
+            position.neighbors(10, 10)
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// 無向グラフの隣接リスト。ノード i はセル (i % width, i / width) に対応し、
+// 設定するとグリッドの近傍の代わりにグラフ上の隣接ノードと対戦・移動する
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NetworkWorld {
+    // 各ノードの隣接ノード（昇順、自己ループ・重複なし）
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl NetworkWorld {
+    pub fn new(node_count: usize) -> Self {
+        Self {
+            adjacency: vec![Vec::new(); node_count],
+        }
+    }
+
+    // 範囲外のノードや自己ループを含む辺はエラー。重複した辺は 1 本にまとめる
+    pub fn from_edges(node_count: usize, edges: &[(usize, usize)]) -> Result<Self, String> {
+        let mut network = Self::new(node_count);
+        for &(a, b) in edges {
+            if a >= node_count || b >= node_count {
+                return Err(format!(
+                    "Edge ({a}, {b}) refers to a node outside 0..{node_count}"
+                ));
+            }
+            if a == b {
+                return Err(format!("Edge ({a}, {b}) is a self-loop"));
+            }
+            network.add_edge(a, b);
+        }
+        Ok(network)
+    }
+
+    // 各ノード対を probability で結ぶ
+    pub fn erdos_renyi(node_count: usize, probability: f64, rng: &mut impl Rng) -> Self {
+        let mut network = Self::new(node_count);
+        for a in 0..node_count {
+            for b in (a + 1)..node_count {
+                if rng.gen::<f64>() < probability {
+                    network.add_edge(a, b);
+                }
+            }
+        }
+        network
+    }
+
+    // 各ノードを左右 degree / 2 個ずつと結んだ環から、各辺の片端を rewire_probability でつなぎ替える
+    pub fn watts_strogatz(
+        node_count: usize,
+        degree: usize,
+        rewire_probability: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut network = Self::new(node_count);
+        let half = (degree / 2).min(node_count.saturating_sub(1) / 2);
+        for a in 0..node_count {
+            for offset in 1..=half {
+                network.add_edge(a, (a + offset) % node_count);
+            }
+        }
+
+        for offset in 1..=half {
+            for a in 0..node_count {
+                let b = (a + offset) % node_count;
+                if rng.gen::<f64>() >= rewire_probability
+                    || network.degree(a) >= node_count - 1
+                    || !network.has_edge(a, b)
+                {
+                    continue;
+                }
+                let target = loop {
+                    let candidate = rng.gen_range(0..node_count);
+                    if candidate != a && !network.has_edge(a, candidate) {
+                        break candidate;
+                    }
+                };
+                network.remove_edge(a, b);
+                network.add_edge(a, target);
+            }
+        }
+        network
+    }
+
+    // links + 1 ノードの完全グラフから始め、新しいノードを次数に比例した確率で links 個の既存ノードと結ぶ
+    pub fn barabasi_albert(node_count: usize, links: usize, rng: &mut impl Rng) -> Self {
+        let mut network = Self::new(node_count);
+        let seed_nodes = (links + 1).min(node_count);
+        // 辺の端点を並べたもの。ここから一様に引くと次数に比例した選択になる
+        let mut endpoints = Vec::new();
+        for a in 0..seed_nodes {
+            for b in (a + 1)..seed_nodes {
+                network.add_edge(a, b);
+                endpoints.extend([a, b]);
+            }
+        }
+
+        for node in seed_nodes..node_count {
+            let mut targets: Vec<usize> = Vec::with_capacity(links);
+            while targets.len() < links {
+                let target = endpoints[rng.gen_range(0..endpoints.len())];
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+            for target in targets {
+                network.add_edge(node, target);
+                endpoints.extend([node, target]);
+            }
+        }
+        network
+    }
+
+    // 新しく結んだときだけ true
+    pub fn add_edge(&mut self, a: usize, b: usize) -> bool {
+        if a == b || self.has_edge(a, b) {
+            return false;
+        }
+        Self::insert_sorted(&mut self.adjacency[a], b);
+        Self::insert_sorted(&mut self.adjacency[b], a);
+        true
+    }
+
+    // 辺があって外したときだけ true
+    pub fn remove_edge(&mut self, a: usize, b: usize) -> bool {
+        if !self.has_edge(a, b) {
+            return false;
+        }
+        self.adjacency[a].retain(|&node| node != b);
+        self.adjacency[b].retain(|&node| node != a);
+        true
+    }
+
+    fn insert_sorted(neighbors: &mut Vec<usize>, node: usize) {
+        if let Err(index) = neighbors.binary_search(&node) {
+            neighbors.insert(index, node);
+        }
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.adjacency
+            .get(a)
+            .is_some_and(|neighbors| neighbors.binary_search(&b).is_ok())
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        self.adjacency.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn degree(&self, node: usize) -> usize {
+        self.neighbors(node).len()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    // node と from の辺を切り、node の隣接ノードの隣接ノード（2 本先で、まだつながっていないもの）の 1 つと結び直す
+    // 候補がなければ切らずに None（辺の数は変わらない）
+    pub fn rewire(&mut self, node: usize, from: usize, rng: &mut impl Rng) -> Option<usize> {
+        if !self.has_edge(node, from) {
+            return None;
+        }
+        let mut candidates: Vec<usize> = self
+            .neighbors(node)
+            .iter()
+            .flat_map(|&neighbor| self.neighbors(neighbor).iter().copied())
+            .filter(|&candidate| candidate != node && !self.has_edge(node, candidate))
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        if candidates.is_empty() {
+            return None;
+        }
+        let target = candidates[rng.gen_range(0..candidates.len())];
+        self.remove_edge(node, from);
+        self.add_edge(node, target);
+        Some(target)
+    }
+
+    // 添字が次数、値がその次数のノード数
+    pub fn degree_distribution(&self) -> Vec<usize> {
+        let max_degree = (0..self.node_count())
+            .map(|node| self.degree(node))
+            .max()
+            .unwrap_or(0);
+        let mut distribution = vec![0; max_degree + 1];
+        for node in 0..self.node_count() {
+            distribution[self.degree(node)] += 1;
+        }
+        distribution
+    }
+
+    // 辺の両端の次数の相関（Newman の次数相関）。次数がすべて同じなど定まらないときは 0
+    pub fn degree_assortativity(&self) -> f64 {
+        let pairs: Vec<(f64, f64)> = self
+            .edges()
+            .into_iter()
+            .map(|(a, b)| (self.degree(a) as f64, self.degree(b) as f64))
+            .collect();
+        edge_correlation(&pairs)
+    }
+
+    // a < b の組を (a, b) の昇順で返す
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(a, neighbors)| {
+                neighbors
+                    .iter()
+                    .filter(move |&&b| a < b)
+                    .map(move |&b| (a, b))
+            })
+            .collect()
+    }
+
+    // node から hops 本以内の辺でたどれるノード（node 自身を除く、昇順）
+    pub fn within(&self, node: usize, hops: u32) -> Vec<usize> {
+        let mut distance = vec![u32::MAX; self.node_count()];
+        let mut queue = VecDeque::new();
+        if node < self.node_count() {
+            distance[node] = 0;
+            queue.push_back(node);
+        }
+        while let Some(current) = queue.pop_front() {
+            if distance[current] == hops {
+                continue;
+            }
+            for &next in self.neighbors(current) {
+                if distance[next] == u32::MAX {
+                    distance[next] = distance[current] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        (0..self.node_count())
+            .filter(|&other| other != node && distance[other] != u32::MAX)
+            .collect()
+    }
+}
+
+// 無向の辺の両端の値の相関。向きを区別しないよう (x, y) と (y, x) の両方を数える
+pub fn edge_correlation(pairs: &[(f64, f64)]) -> f64 {
+    if pairs.is_empty() {
+        return 0.0;
+    }
+    let count = (pairs.len() * 2) as f64;
+    let mean = pairs.iter().map(|(x, y)| x + y).sum::<f64>() / count;
+    let variance = pairs
+        .iter()
+        .map(|(x, y)| (x - mean).powi(2) + (y - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    if variance <= f64::EPSILON {
+        return 0.0;
+    }
+    let covariance = pairs
+        .iter()
+        .map(|(x, y)| 2.0 * (x - mean) * (y - mean))
+        .sum::<f64>()
+        / count;
+    covariance / variance
+}
+
+// ネットワークの作り方
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NetworkTopology {
+    // 各ノード対を probability で結ぶランダムグラフ
+    ErdosRenyi {
+        probability: f64,
+    },
+    // 平均次数 degree（偶数）の環を rewire_probability でつなぎ替えたスモールワールド
+    WattsStrogatz {
+        degree: usize,
+        rewire_probability: f64,
+    },
+    // 新しいノードが links 本の辺で次数の大きいノードにつながりやすいスケールフリー
+    BarabasiAlbert {
+        links: usize,
+    },
+    // 読み込んだ辺のリスト（ノード番号は 0 始まり）
+    EdgeList(Vec<(usize, usize)>),
+}
+
+impl NetworkTopology {
+    // "erdos_renyi:P" / "watts_strogatz:K:B" / "barabasi_albert:M"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!("Expected erdos_renyi:P, watts_strogatz:K:B or barabasi_albert:M, got {spec}")
+        };
+        let parts: Vec<&str> = spec.split(':').collect();
+        match parts[..] {
+            ["erdos_renyi", probability] => Ok(NetworkTopology::ErdosRenyi {
+                probability: probability.parse().map_err(|_| invalid())?,
+            }),
+            ["watts_strogatz", degree, rewire_probability] => Ok(NetworkTopology::WattsStrogatz {
+                degree: degree.parse().map_err(|_| invalid())?,
+                rewire_probability: rewire_probability.parse().map_err(|_| invalid())?,
+            }),
+            ["barabasi_albert", links] => Ok(NetworkTopology::BarabasiAlbert {
+                links: links.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NetworkTopology::ErdosRenyi { .. } => "erdos_renyi",
+            NetworkTopology::WattsStrogatz { .. } => "watts_strogatz",
+            NetworkTopology::BarabasiAlbert { .. } => "barabasi_albert",
+            NetworkTopology::EdgeList(_) => "edge_list",
+        }
+    }
+}
+
+// グラフ上で対戦させる設定。生成器は seed から作るので、同じ設定なら同じグラフになる
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    pub topology: NetworkTopology,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl NetworkConfig {
+    pub fn new(topology: NetworkTopology) -> Self {
+        Self { topology, seed: 0 }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // node_count 個（= セル数）のノードで作れるか
+    pub fn validate(&self, node_count: usize) -> Result<(), String> {
+        match &self.topology {
+            NetworkTopology::ErdosRenyi { probability } => {
+                if !(0.0..=1.0).contains(probability) {
+                    return Err(format!(
+                        "edge probability must be between 0 and 1, got {probability}"
+                    ));
+                }
+            }
+            NetworkTopology::WattsStrogatz {
+                degree,
+                rewire_probability,
+            } => {
+                if *degree < 2 || degree % 2 != 0 || *degree >= node_count {
+                    return Err(format!(
+                        "degree must be an even number from 2 to {}, got {degree}",
+                        node_count.saturating_sub(1)
+                    ));
+                }
+                if !(0.0..=1.0).contains(rewire_probability) {
+                    return Err(format!(
+                        "rewire probability must be between 0 and 1, got {rewire_probability}"
+                    ));
+                }
+            }
+            NetworkTopology::BarabasiAlbert { links } => {
+                if *links == 0 || *links >= node_count {
+                    return Err(format!(
+                        "links must be between 1 and {}, got {links}",
+                        node_count.saturating_sub(1)
+                    ));
+                }
+            }
+            NetworkTopology::EdgeList(edges) => {
+                NetworkWorld::from_edges(node_count, edges)?;
+            }
+        }
+        Ok(())
+    }
+
+    // validate に通らない設定では、範囲外の辺を捨てた（生成器は引数を丸めた）グラフになる
+    pub fn build(&self, node_count: usize) -> NetworkWorld {
+        let mut rng = ChaCha12Rng::seed_from_u64(self.seed);
+        match &self.topology {
+            NetworkTopology::ErdosRenyi { probability } => {
+                NetworkWorld::erdos_renyi(node_count, *probability, &mut rng)
+            }
+            NetworkTopology::WattsStrogatz {
+                degree,
+                rewire_probability,
+            } => NetworkWorld::watts_strogatz(node_count, *degree, *rewire_probability, &mut rng),
+            NetworkTopology::BarabasiAlbert { links } => NetworkWorld::barabasi_albert(
+                node_count,
+                (*links).clamp(1, node_count.saturating_sub(1).max(1)),
+                &mut rng,
+            ),
+            NetworkTopology::EdgeList(edges) => {
+                let mut network = NetworkWorld::new(node_count);
+                for &(a, b) in edges {
+                    if a < node_count && b < node_count {
+                        network.add_edge(a, b);
+                    }
+                }
+                network
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators_build_expected_degrees() {
+        // Arrange
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+
+        // Act
+        let complete = NetworkWorld::erdos_renyi(10, 1.0, &mut rng);
+        let ring = NetworkWorld::watts_strogatz(20, 4, 0.0, &mut rng);
+        let small_world = NetworkWorld::watts_strogatz(20, 4, 0.3, &mut rng);
+        let scale_free = NetworkWorld::barabasi_albert(50, 2, &mut rng);
+
+        // Assert: つなぎ替えても辺の数は変わらず、BA は最初の完全グラフ 3 本 + 2 本ずつ
+        assert_eq!(complete.edge_count(), 45);
+        assert!((0..20).all(|node| ring.degree(node) == 4));
+        assert!(ring.has_edge(0, 19) && ring.has_edge(0, 2));
+        assert_eq!(small_world.edge_count(), 40);
+        assert_eq!(scale_free.edge_count(), 3 + 47 * 2);
+        assert!((0..50).all(|node| scale_free.degree(node) >= 2));
+    }
+
+    #[test]
+    fn test_from_edges_rejects_invalid_edges_and_within_counts_hops() {
+        // Arrange
+        let path = NetworkWorld::from_edges(4, &[(0, 1), (1, 2), (2, 3), (1, 0)]).unwrap();
+
+        // Act & Assert
+        assert_eq!(path.edge_count(), 3);
+        assert_eq!(path.edges(), vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(path.within(0, 1), vec![1]);
+        assert_eq!(path.within(0, 2), vec![1, 2]);
+        assert!(NetworkWorld::from_edges(4, &[(0, 4)]).is_err());
+        assert!(NetworkWorld::from_edges(4, &[(2, 2)]).is_err());
+    }
+
+    #[test]
+    fn test_rewire_moves_link_to_neighbor_of_neighbor_and_star_is_disassortative() {
+        // Arrange: 0-1-2-3 の道と、中心 0 の星
+        let mut path = NetworkWorld::from_edges(4, &[(0, 1), (1, 2), (2, 3)]).unwrap();
+        let star = NetworkWorld::from_edges(5, &[(0, 1), (0, 2), (0, 3), (0, 4)]).unwrap();
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+
+        // Act
+        let target = path.rewire(1, 0, &mut rng);
+        let isolated = path.rewire(0, 1, &mut rng);
+
+        // Assert: 1 の 2 本先は 3 だけ。辺の数は変わらない
+        assert_eq!(target, Some(3));
+        assert_eq!(path.edges(), vec![(1, 2), (1, 3), (2, 3)]);
+        assert_eq!(isolated, None);
+        assert_eq!(star.degree_distribution(), vec![0, 4, 0, 0, 1]);
+        assert!((star.degree_assortativity() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_config_parses_specs_and_builds_same_graph_for_same_seed() {
+        // Arrange
+        let topology = NetworkTopology::parse("watts_strogatz:4:0.2").unwrap();
+        let config = NetworkConfig::new(topology).with_seed(3);
+
+        // Act & Assert
+        assert_eq!(config.build(30), config.build(30));
+        assert!(config.validate(30).is_ok());
+        assert!(config.validate(4).is_err());
+        assert_eq!(
+            NetworkTopology::parse("barabasi_albert:3"),
+            Ok(NetworkTopology::BarabasiAlbert { links: 3 })
+        );
+        assert!(NetworkTopology::parse("erdos_renyi").is_err());
+        assert!(
+            NetworkConfig::new(NetworkTopology::ErdosRenyi { probability: 1.5 })
+                .validate(10)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use super::Grid;
+use crate::domain::agent::Position;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// 初期配置の空間パターン
+// positions は「埋める順」に並べて返すので、系統を順に割り当てると空間的にまとまる
+// （CenterBlob は中心から、Ring は円周に沿って、Clustered はブロックごと、Checkerboard は同じ色から）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlacementPattern {
+    #[default]
+    Uniform,
+    Clustered {
+        block_size: usize,
+    },
+    Ring,
+    CenterBlob,
+    Checkerboard,
+}
+
+impl PlacementPattern {
+    // "uniform" / "clustered:N" / "ring" / "center_blob" / "checkerboard"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "uniform" => Some(PlacementPattern::Uniform),
+            "ring" => Some(PlacementPattern::Ring),
+            "center_blob" => Some(PlacementPattern::CenterBlob),
+            "checkerboard" => Some(PlacementPattern::Checkerboard),
+            _ => name
+                .strip_prefix("clustered:")
+                .and_then(|size| size.parse().ok())
+                .filter(|&block_size| block_size > 0)
+                .map(|block_size| PlacementPattern::Clustered { block_size }),
+        }
+    }
+
+    // 空きセルから agent_count 個を選ぶ。同順位のセルはランダムに並ぶ
+    pub fn positions<R: Rng + ?Sized>(
+        &self,
+        grid: &Grid,
+        agent_count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Position>, String> {
+        let mut candidates = grid.get_empty_positions();
+        if agent_count > candidates.len() {
+            return Err(format!(
+                "Cannot place {agent_count} agents in {} free cells",
+                candidates.len()
+            ));
+        }
+        candidates.shuffle(rng);
+
+        // 中心からの距離は 2 倍した座標で計算して整数・半整数の中心を同じに扱う
+        let (width, height) = (grid.width() as f64, grid.height() as f64);
+        let offset = |position: &Position| {
+            (
+                (2 * position.x + 1) as f64 - width,
+                (2 * position.y + 1) as f64 - height,
+            )
+        };
+        let distance = |position: &Position| {
+            let (dx, dy) = offset(position);
+            dx.hypot(dy)
+        };
+
+        match *self {
+            PlacementPattern::Uniform => {}
+            PlacementPattern::Clustered { block_size } => {
+                let block_size = block_size.max(1);
+                let columns = grid.width().div_ceil(block_size);
+                let rows = grid.height().div_ceil(block_size);
+                let mut block_rank: Vec<usize> = (0..columns * rows).collect();
+                block_rank.shuffle(rng);
+                candidates.sort_by_key(|position| {
+                    block_rank[(position.y / block_size) * columns + position.x / block_size]
+                });
+            }
+            PlacementPattern::Ring => {
+                let radius = width.min(height) * 2.0 / 3.0;
+                candidates.sort_by(|a, b| {
+                    (distance(a) - radius)
+                        .abs()
+                        .total_cmp(&(distance(b) - radius).abs())
+                });
+                candidates.truncate(agent_count);
+                candidates.sort_by(|a, b| {
+                    let angle = |position: &Position| {
+                        let (dx, dy) = offset(position);
+                        dy.atan2(dx)
+                    };
+                    angle(a).total_cmp(&angle(b))
+                });
+            }
+            PlacementPattern::CenterBlob => {
+                candidates.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+            }
+            PlacementPattern::Checkerboard => {
+                candidates.sort_by_key(|position| (position.x + position.y) % 2);
+            }
+        }
+
+        candidates.truncate(agent_count);
+        Ok(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    fn place(pattern: PlacementPattern, agent_count: usize) -> Vec<Position> {
+        let grid = Grid::new(10, 10);
+        let mut rng = ChaCha12Rng::seed_from_u64(11);
+        pattern.positions(&grid, agent_count, &mut rng).unwrap()
+    }
+
+    #[test]
+    fn test_center_blob_fills_cells_nearest_the_center() {
+        // Arrange & Act
+        let positions = place(PlacementPattern::CenterBlob, 4);
+
+        // Assert: 10×10 の中心 4 セル
+        let mut sorted = positions.clone();
+        sorted.sort_by_key(|position| (position.y, position.x));
+        assert_eq!(
+            sorted,
+            vec![
+                Position::new(4, 4),
+                Position::new(5, 4),
+                Position::new(4, 5),
+                Position::new(5, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checkerboard_uses_one_color_first() {
+        // Arrange & Act
+        let positions = place(PlacementPattern::Checkerboard, 50);
+
+        // Assert
+        assert!(positions
+            .iter()
+            .all(|position| (position.x + position.y) % 2 == 0));
+    }
+
+    #[test]
+    fn test_clustered_fills_whole_blocks() {
+        // Arrange & Act: 5×5 ブロックが 4 つ。50 体ならちょうど 2 ブロック
+        let positions = place(PlacementPattern::Clustered { block_size: 5 }, 50);
+
+        // Assert
+        let mut blocks: Vec<(usize, usize)> = positions
+            .iter()
+            .map(|position| (position.x / 5, position.y / 5))
+            .collect();
+        blocks.sort();
+        blocks.dedup();
+        assert_eq!(blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_ring_avoids_the_center_and_rejects_overfull_grid() {
+        // Arrange
+        let grid = Grid::new(10, 10);
+        let mut rng = ChaCha12Rng::seed_from_u64(11);
+
+        // Act
+        let positions = place(PlacementPattern::Ring, 20);
+        let overfull = PlacementPattern::Ring.positions(&grid, 101, &mut rng);
+
+        // Assert
+        assert_eq!(positions.len(), 20);
+        assert!(!positions.contains(&Position::new(4, 4)));
+        assert!(!positions.contains(&Position::new(5, 5)));
+        assert!(overfull.is_err());
+    }
+
+    #[test]
+    fn test_from_name_parses_block_size() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            PlacementPattern::from_name("clustered:4"),
+            Some(PlacementPattern::Clustered { block_size: 4 })
+        );
+        assert_eq!(PlacementPattern::from_name("clustered:0"), None);
+        assert_eq!(
+            PlacementPattern::from_name("center_blob"),
+            Some(PlacementPattern::CenterBlob)
+        );
+    }
+}
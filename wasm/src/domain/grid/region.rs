@@ -0,0 +1,60 @@
+use crate::domain::agent::Position;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        position.x >= self.x
+            && position.x < self.x + self.width
+            && position.y >= self.y
+            && position.y < self.y + self.height
+    }
+
+    pub fn positions(&self) -> Vec<Position> {
+        (self.y..self.y + self.height)
+            .flat_map(|y| (self.x..self.x + self.width).map(move |x| Position::new(x, y)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_contains_and_positions() {
+        // Arrange
+        let region = Region::new(2, 3, 2, 2);
+
+        // Act
+        let positions = region.positions();
+
+        // Assert
+        assert_eq!(region.area(), 4);
+        assert_eq!(positions.len(), 4);
+        assert!(positions.iter().all(|p| region.contains(p)));
+        assert!(region.contains(&Position::new(3, 4)));
+        assert!(!region.contains(&Position::new(4, 4)));
+        assert!(!region.contains(&Position::new(1, 3)));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::domain::agent::Position;
+use serde::{Deserialize, Serialize};
+
+// セルごとの資源量の設定
+// 対戦のたびにエージェントは自分のセルから consumption を取り、得られた割合だけ利得が減る
+// 各ステップの終わりに全セルが regeneration_rate ずつ capacity まで回復する
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResourceConfig {
+    pub capacity: f64,
+    pub regeneration_rate: f64,
+    pub consumption: f64,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1.0,
+            regeneration_rate: 0.1,
+            consumption: 0.02,
+        }
+    }
+}
+
+impl ResourceConfig {
+    pub fn new(capacity: f64, regeneration_rate: f64, consumption: f64) -> Result<Self, String> {
+        if !(capacity > 0.0 && capacity.is_finite()) {
+            return Err(format!(
+                "Resource capacity must be positive, got {capacity}"
+            ));
+        }
+        if !(regeneration_rate >= 0.0 && consumption >= 0.0) {
+            return Err(format!(
+                "Resource regeneration ({regeneration_rate}) and consumption ({consumption}) must be non-negative"
+            ));
+        }
+        Ok(Self {
+            capacity,
+            regeneration_rate,
+            consumption,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceField {
+    width: usize,
+    config: ResourceConfig,
+    // セル (x, y) の資源量（添字 y * width + x）
+    levels: Vec<f64>,
+}
+
+impl ResourceField {
+    // 全セルが満タンの状態から始める
+    pub fn new(width: usize, height: usize, config: ResourceConfig) -> Self {
+        Self {
+            width,
+            config,
+            levels: vec![config.capacity; width * height],
+        }
+    }
+
+    pub fn config(&self) -> &ResourceConfig {
+        &self.config
+    }
+
+    // 行優先の資源量（ヒートマップ描画用）
+    pub fn levels(&self) -> &[f64] {
+        &self.levels
+    }
+
+    pub fn level(&self, position: &Position) -> f64 {
+        self.levels
+            .get(position.y * self.width + position.x)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    // 1 回の対戦分の資源を取り、必要量のうち得られた割合（0.0〜1.0）を返す
+    pub fn harvest(&mut self, position: &Position) -> f64 {
+        let demand = self.config.consumption;
+        if demand <= 0.0 {
+            return 1.0;
+        }
+        let Some(level) = self.levels.get_mut(position.y * self.width + position.x) else {
+            return 0.0;
+        };
+        let taken = demand.min(*level);
+        *level -= taken;
+        taken / demand
+    }
+
+    pub fn regenerate(&mut self) {
+        let ResourceConfig {
+            capacity,
+            regeneration_rate,
+            ..
+        } = self.config;
+        for level in &mut self.levels {
+            *level = (*level + regeneration_rate).min(capacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harvest_depletes_and_regenerates() {
+        // Arrange: 容量 0.05 のセルから 0.02 ずつ取る
+        let config = ResourceConfig::new(0.05, 0.03, 0.02).unwrap();
+        let mut field = ResourceField::new(2, 2, config);
+        let position = Position::new(1, 0);
+
+        // Act
+        let harvests: Vec<f64> = (0..3).map(|_| field.harvest(&position)).collect();
+        let depleted = field.level(&position);
+        field.regenerate();
+
+        // Assert: 3 回目は残り 0.01 しか取れない
+        assert_eq!(harvests[..2], [1.0, 1.0]);
+        assert!((harvests[2] - 0.5).abs() < 1e-9);
+        assert_eq!(depleted, 0.0);
+        assert!((field.level(&position) - 0.03).abs() < 1e-9);
+        assert_eq!(field.level(&Position::new(0, 0)), 0.05);
+    }
+
+    #[test]
+    fn test_config_rejects_non_positive_capacity() {
+        // Arrange & Act & Assert
+        assert!(ResourceConfig::new(0.0, 0.1, 0.1).is_err());
+        assert!(ResourceConfig::new(1.0, -0.1, 0.1).is_err());
+    }
+}
@@ -1,16 +1,122 @@
-use super::Grid;
-use crate::domain::agent::{Agent, Position};
+use super::{Grid, MovementPolicy};
+use crate::domain::agent::{Agent, Genotype, Position, StrategyType};
+use crate::domain::game::PayoffMatrix;
+use rand::seq::SliceRandom;
 use rand::Rng;
+use uuid::Uuid;
 
 pub struct GridService;
 
 impl GridService {
-    pub fn initialize_random_agents(grid: &mut Grid, agent_count: usize) -> Result<(), String> {
-        if agent_count > grid.width() * grid.height() {
+    pub fn initialize_random_agents<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        agent_count: usize,
+        rng: &mut R,
+    ) -> Result<(), String> {
+        Self::place_agents(grid, agent_count, rng, |position, _, rng| {
+            Agent::random(position, rng)
+        })
+    }
+
+    pub fn initialize_with_strategies<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        strategies: &[StrategyType],
+        rng: &mut R,
+    ) -> Result<(), String> {
+        Self::place_agents(grid, strategies.len(), rng, |position, index, rng| {
+            let mut agent = Agent::random(position, rng);
+            agent.strategy = strategies[index];
+            agent
+        })
+    }
+
+    // 遺伝子型を順に繰り返し、左上から行優先で詰めて配置する（乱数を使わない）
+    pub fn initialize_from_genotypes(
+        grid: &mut Grid,
+        agent_count: usize,
+        genotypes: &[Genotype],
+    ) -> Result<(), String> {
+        if genotypes.is_empty() {
+            return Err("At least one genotype is required".to_string());
+        }
+        if agent_count > grid.open_cell_count() {
+            return Err("Too many agents for grid size".to_string());
+        }
+
+        let positions = grid
+            .get_empty_positions()
+            .into_iter()
+            .take(agent_count)
+            .collect::<Vec<_>>();
+        if positions.len() < agent_count {
+            return Err(format!(
+                "Could only place {} out of {agent_count} agents",
+                positions.len()
+            ));
+        }
+
+        for (index, position) in positions.into_iter().enumerate() {
+            grid.add_agent(genotypes[index % genotypes.len()].spawn(position))?;
+        }
+
+        Ok(())
+    }
+
+    // 読み込んだ個体を元の位置に置き、盤面の外・壁・先に置いた個体と重なるものはランダムな空きセルへ移す
+    // 元の位置の順（同じ位置は ID 順）に処理するので、同じ乱数列なら同じ配置になる。戻り値は移した数
+    pub fn place_imported_agents<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        mut agents: Vec<Agent>,
+        rng: &mut R,
+    ) -> Result<usize, String> {
+        if agents.len() > grid.open_cell_count() {
+            return Err(format!(
+                "Cannot place {} imported agents on {} open cells",
+                agents.len(),
+                grid.open_cell_count()
+            ));
+        }
+
+        agents.sort_by_key(|agent| (agent.position.y, agent.position.x, agent.id));
+        let mut displaced = Vec::new();
+        for agent in agents {
+            let position = agent.position;
+            let fits = position.x < grid.width()
+                && position.y < grid.height()
+                && !grid.is_obstacle(&position)
+                && grid.get_agent_at_position(&position).is_none();
+            if fits {
+                grid.add_agent(agent)?;
+            } else {
+                displaced.push(agent);
+            }
+        }
+
+        let mut empty_positions = grid.get_empty_positions();
+        empty_positions.shuffle(rng);
+        let relocated = displaced.len();
+        for (mut agent, position) in displaced.into_iter().zip(empty_positions) {
+            agent.position = position;
+            grid.add_agent(agent)?;
+        }
+        Ok(relocated)
+    }
+
+    // create_agent(位置, 何体目か, rng) で作ったエージェントをランダムな空きセルに置く
+    pub fn place_agents<R, F>(
+        grid: &mut Grid,
+        agent_count: usize,
+        rng: &mut R,
+        mut create_agent: F,
+    ) -> Result<(), String>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(Position, usize, &mut R) -> Agent,
+    {
+        if agent_count > grid.open_cell_count() {
             return Err("Too many agents for grid size".to_string());
         }
 
-        let mut rng = rand::thread_rng();
         let mut placed_agents = 0;
         let max_attempts = agent_count * 10;
         let mut attempts = 0;
@@ -21,7 +127,7 @@ impl GridService {
             let position = Position::new(x, y);
 
             if grid.is_position_free(&position) {
-                let agent = Agent::random(position);
+                let agent = create_agent(position, placed_agents, rng);
                 if let Ok(()) = grid.add_agent(agent) {
                     placed_agents += 1;
                 }
@@ -39,15 +145,19 @@ impl GridService {
         Ok(())
     }
 
-    pub fn process_movements(grid: &mut Grid, torus_mode: bool) {
+    pub fn process_movements<R: Rng + ?Sized>(grid: &mut Grid, rng: &mut R) {
         let mut movements = Vec::new();
 
-        for agent in grid.agents().values() {
+        for agent in grid.agents_in_position_order() {
+            if agent.movement_strategy.is_deterministic() {
+                if let Some(target_position) = Self::select_deterministic_target(grid, agent) {
+                    movements.push((agent.id, target_position));
+                }
+                continue;
+            }
+
             // 隣接エージェントの情報を収集
-            let neighbor_positions =
-                agent
-                    .position
-                    .neighbors_with_mode(grid.width(), grid.height(), torus_mode);
+            let neighbor_positions = grid.neighbor_positions(&agent.position);
             let mut neighbor_agents = Vec::new();
             let mut neighbor_strategies = Vec::new();
 
@@ -58,10 +168,9 @@ impl GridService {
                 }
             }
 
-            if agent.should_move_with_neighbors(&neighbor_agents, &neighbor_strategies) {
+            if agent.should_move_with_neighbors(&neighbor_agents, &neighbor_strategies, rng) {
                 let empty_neighbors = grid.get_empty_neighbors(&agent.position);
                 if !empty_neighbors.is_empty() {
-                    let mut rng = rand::thread_rng();
                     let target_position = empty_neighbors[rng.gen_range(0..empty_neighbors.len())];
                     movements.push((agent.id, target_position));
                 }
@@ -72,4 +181,363 @@ impl GridService {
             let _ = grid.move_agent(&agent_id, new_position);
         }
     }
+
+    // 設定の移動方針で動かす。各エージェントは自分の mobility の確率で移動を試み、
+    // 移動先は方針が選ぶ。mobility は遺伝するので、移動のしやすさも進化する
+    pub fn process_movements_with<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        policy: &dyn MovementPolicy,
+        rng: &mut R,
+    ) {
+        let mut movements = Vec::new();
+
+        for agent in grid.agents_in_position_order() {
+            if agent.mobility <= 0.0 || rng.gen::<f64>() >= agent.mobility {
+                continue;
+            }
+            if let Some(target_position) = policy.choose_target(grid, agent, rng.gen()) {
+                movements.push((agent.id, target_position));
+            }
+        }
+
+        // 先に動いた個体が移動先を埋めていれば、その移動は取りやめになる
+        for (agent_id, new_position) in movements {
+            let _ = grid.move_agent(&agent_id, new_position);
+        }
+    }
+
+    // 各エージェントが migration_rate の確率で別デームのランダムな空きセルへ移住する
+    pub fn process_migrations<R: Rng + ?Sized>(grid: &mut Grid, rng: &mut R) {
+        let Some(demes) = grid.demes().copied() else {
+            return;
+        };
+        if demes.migration_rate <= 0.0 || demes.deme_count() < 2 {
+            return;
+        }
+
+        let mut empty_positions = grid.get_empty_positions();
+        let migrants: Vec<(Uuid, Position)> = grid
+            .agents_in_position_order()
+            .into_iter()
+            .filter(|_| rng.gen_bool(demes.migration_rate))
+            .map(|agent| (agent.id, agent.position))
+            .collect();
+
+        for (agent_id, position) in migrants {
+            let candidates: Vec<usize> = empty_positions
+                .iter()
+                .enumerate()
+                .filter(|(_, candidate)| !grid.same_deme(&position, candidate))
+                .map(|(index, _)| index)
+                .collect();
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let index = candidates[rng.gen_range(0..candidates.len())];
+            if grid.move_agent(&agent_id, empty_positions[index]).is_ok() {
+                empty_positions.swap_remove(index);
+                empty_positions.push(position);
+            }
+        }
+    }
+
+    // Helbing & Yu の成功駆動の移住。ランダムな順に 1 体ずつ、range 以内の各空きセルに移ったとして
+    // そこの近傍と 1 回ずつ試しに対戦した利得を比べ、今のセルより高い最良のセルへすぐに移る
+    // 同点なら近いセル、さらに同点なら行優先で先のセル。移った個体の数を返す
+    pub fn process_success_driven_migration<R: Rng + ?Sized>(
+        grid: &mut Grid,
+        range: u32,
+        payoffs: &PayoffMatrix,
+        rng: &mut R,
+    ) -> usize {
+        let mut order: Vec<Uuid> = grid
+            .agents_in_position_order()
+            .iter()
+            .map(|agent| agent.id)
+            .collect();
+        order.shuffle(rng);
+
+        let mut moved = 0;
+        for agent_id in order {
+            let Some(agent) = grid.get_agent(&agent_id) else {
+                continue;
+            };
+            let origin = agent.position;
+            let current = Self::test_payoff(grid, agent, &origin, payoffs, rng);
+            let mut best: Option<(Position, i32, usize)> = None;
+            for site in grid.empty_positions_within(&origin, range) {
+                let payoff = Self::test_payoff(grid, agent, &site, payoffs, rng);
+                if payoff <= current {
+                    continue;
+                }
+                let distance = grid.chebyshev_distance(&origin, &site);
+                let better = match best {
+                    None => true,
+                    Some((_, best_payoff, best_distance)) => {
+                        payoff > best_payoff || (payoff == best_payoff && distance < best_distance)
+                    }
+                };
+                if better {
+                    best = Some((site, payoff, distance));
+                }
+            }
+            if let Some((site, _, _)) = best {
+                if grid.move_agent(&agent_id, site).is_ok() {
+                    moved += 1;
+                }
+            }
+        }
+        moved
+    }
+
+    // agent が site にいたとして、そこの近傍（自分を除く）と 1 回ずつ対戦したときの利得の合計
+    // 利得は site の区域の表に従う。対戦の記録は残さない
+    fn test_payoff<R: Rng + ?Sized>(
+        grid: &Grid,
+        agent: &Agent,
+        site: &Position,
+        payoffs: &PayoffMatrix,
+        rng: &mut R,
+    ) -> i32 {
+        let matrix = grid.payoffs_at(site, payoffs);
+        grid.get_neighbors(site)
+            .into_iter()
+            .filter(|neighbor| neighbor.id != agent.id)
+            .map(|neighbor| {
+                let mine = agent.decide_action_toward(neighbor, None, rng);
+                let theirs = neighbor.decide_action_toward(agent, None, rng);
+                matrix.payoffs(mine, theirs).0
+            })
+            .sum()
+    }
+
+    // 協力的な隣人が最も多い空きセルを選ぶ（同点は y, x の小さい順）
+    // 現在地より良いセルがなければ移動しない
+    pub fn select_deterministic_target(grid: &Grid, agent: &Agent) -> Option<Position> {
+        let current_cooperation = grid.neighbor_cooperation(&agent.position, &agent.id);
+        let mut best: Option<(Position, f64)> = None;
+
+        for candidate in grid.get_empty_neighbors(&agent.position) {
+            let cooperation = grid.neighbor_cooperation(&candidate, &agent.id);
+            let is_better = match best {
+                None => true,
+                Some((best_position, best_cooperation)) => {
+                    cooperation > best_cooperation
+                        || (cooperation == best_cooperation
+                            && (candidate.y, candidate.x) < (best_position.y, best_position.x))
+                }
+            };
+
+            if is_better {
+                best = Some((candidate, cooperation));
+            }
+        }
+
+        best.filter(|&(_, cooperation)| cooperation > current_cooperation)
+            .map(|(position, _)| position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, MovementStrategy};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_initialize_from_genotypes_tiles_in_row_major_order() {
+        // Arrange
+        let mut grid = Grid::new(5, 5);
+        let cooperator = Genotype::new(StrategyType::AllCooperate, MovementStrategy::Settler, 0.1);
+        let defector = Genotype::new(StrategyType::AllDefect, MovementStrategy::Explorer, 0.9);
+
+        // Act
+        GridService::initialize_from_genotypes(
+            &mut grid,
+            10,
+            &[cooperator.clone(), defector.clone()],
+        )
+        .unwrap();
+
+        // Assert: 行優先で交互に並び、5/5 に分かれる
+        let mut agents: Vec<&Agent> = grid.agents().values().collect();
+        agents.sort_by_key(|agent| (agent.position.y, agent.position.x));
+        let genotypes: Vec<Genotype> = agents.iter().map(|agent| Genotype::of(agent)).collect();
+        let expected: Vec<Genotype> = (0..10)
+            .map(|i| {
+                if i % 2 == 0 {
+                    cooperator.clone()
+                } else {
+                    defector.clone()
+                }
+            })
+            .collect();
+        assert_eq!(genotypes, expected);
+        assert_eq!(agents[9].position, Position::new(4, 1));
+    }
+
+    fn build_grid() -> (Grid, Agent) {
+        let mut grid = Grid::new(10, 10);
+        let seeker = Agent::new(
+            Position::new(5, 5),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Seeker,
+        );
+        let opponent_id = uuid::Uuid::nil();
+
+        // (6, 5) の周囲に協力的なエージェントを配置
+        for position in [
+            Position::new(7, 4),
+            Position::new(7, 5),
+            Position::new(7, 6),
+        ] {
+            let mut cooperator = Agent::new(
+                position,
+                StrategyType::AllCooperate,
+                0.5,
+                MovementStrategy::Settler,
+            );
+            cooperator.add_game_result(opponent_id, Action::Cooperate, Action::Cooperate, 3);
+            grid.add_agent(cooperator).unwrap();
+        }
+
+        // 反対側に非協力的なエージェントを配置
+        let mut defector = Agent::new(
+            Position::new(3, 5),
+            StrategyType::AllDefect,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        defector.add_game_result(opponent_id, Action::Defect, Action::Defect, 1);
+        grid.add_agent(defector).unwrap();
+
+        grid.add_agent(seeker.clone()).unwrap();
+        (grid, seeker)
+    }
+
+    #[test]
+    fn test_place_imported_agents_keeps_valid_positions_and_relocates_the_rest() {
+        // Arrange: 3x3 の盤面に、そのまま置ける個体・重なる個体・盤面の外の個体
+        let mut grid = Grid::new(3, 3);
+        let agent = |x: usize, y: usize| {
+            Agent::new(
+                Position::new(x, y),
+                StrategyType::TitForTat,
+                0.5,
+                MovementStrategy::Settler,
+            )
+        };
+        let imported = vec![agent(1, 1), agent(1, 1), agent(7, 0)];
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+
+        // Act
+        let relocated = GridService::place_imported_agents(&mut grid, imported, &mut rng).unwrap();
+
+        // Assert
+        assert_eq!(relocated, 2);
+        assert_eq!(grid.agent_count(), 3);
+        assert!(grid
+            .agents()
+            .values()
+            .all(|agent| agent.position.x < 3 && agent.position.y < 3));
+        assert!(GridService::place_imported_agents(
+            &mut Grid::new(1, 1),
+            vec![agent(0, 0), agent(0, 0)],
+            &mut rng
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_select_deterministic_target_moves_toward_cooperators() {
+        // Arrange
+        let (grid, seeker) = build_grid();
+
+        // Act
+        let target = GridService::select_deterministic_target(&grid, &seeker);
+
+        // Assert: 3体の協力者すべてに隣接するセルが選ばれる
+        assert_eq!(target, Some(Position::new(6, 5)));
+    }
+
+    #[test]
+    fn test_select_deterministic_target_is_stable_across_identical_states() {
+        // Arrange
+        let (first_grid, first_seeker) = build_grid();
+        let (second_grid, second_seeker) = build_grid();
+
+        // Act
+        let first = GridService::select_deterministic_target(&first_grid, &first_seeker);
+        let repeated = GridService::select_deterministic_target(&first_grid, &first_seeker);
+        let second = GridService::select_deterministic_target(&second_grid, &second_seeker);
+
+        // Assert
+        assert!(first.is_some());
+        assert_eq!(first, repeated);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_success_driven_migration_moves_to_the_nearest_best_site() {
+        // Arrange: 11x1 の一列。右端の裏切り者 (10,0) の隣にいる協力者 (9,0) から、
+        // 協力者の塊 (3..=5, 0) の両隣 (2,0)・(6,0) がどちらも範囲内にある
+        let mut grid = Grid::new(11, 1);
+        let agent = |x, strategy| {
+            Agent::new(
+                Position::new(x, 0),
+                strategy,
+                0.0,
+                MovementStrategy::Settler,
+            )
+        };
+        let mover = agent(9, StrategyType::AllCooperate);
+        for other in [
+            mover.clone(),
+            agent(10, StrategyType::AllDefect),
+            agent(3, StrategyType::AllCooperate),
+            agent(4, StrategyType::AllCooperate),
+            agent(5, StrategyType::AllCooperate),
+        ] {
+            grid.add_agent(other).unwrap();
+        }
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+
+        // Act
+        let moved = GridService::process_success_driven_migration(
+            &mut grid,
+            7,
+            &PayoffMatrix::prisoners_dilemma(),
+            &mut rng,
+        );
+
+        // Assert: 同じ利得 R の 2 セルのうち近い (6,0) へ移る
+        // 順番によっては、取り残された裏切り者も後を追って (7,0) へ移る
+        assert_eq!(
+            grid.get_agent(&mover.id).unwrap().position,
+            Position::new(6, 0)
+        );
+        assert!(moved >= 1);
+    }
+
+    #[test]
+    fn test_select_deterministic_target_stays_without_better_cell() {
+        // Arrange
+        let mut grid = Grid::new(10, 10);
+        let seeker = Agent::new(
+            Position::new(5, 5),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Seeker,
+        );
+        grid.add_agent(seeker.clone()).unwrap();
+
+        // Act
+        let target = GridService::select_deterministic_target(&grid, &seeker);
+
+        // Assert
+        assert_eq!(target, None);
+    }
 }
@@ -0,0 +1,118 @@
+use super::Region;
+use crate::domain::agent::Position;
+use crate::domain::game::PayoffMatrix;
+use serde::{Deserialize, Serialize};
+
+// 利得表や突然変異率が周りと異なる長方形の区域（例：裏切りが得な「厳しい」区域）
+// 設定しなかった項目は全体の値を使う
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    pub region: Region,
+    #[serde(default)]
+    pub payoffs: Option<PayoffMatrix>,
+    #[serde(default)]
+    pub mutation_rate: Option<f64>,
+}
+
+impl Zone {
+    pub fn new(name: &str, region: Region) -> Self {
+        Self {
+            name: name.to_string(),
+            region,
+            payoffs: None,
+            mutation_rate: None,
+        }
+    }
+
+    pub fn with_payoffs(mut self, payoffs: PayoffMatrix) -> Self {
+        self.payoffs = Some(payoffs);
+        self
+    }
+
+    pub fn with_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = Some(rate);
+        self
+    }
+}
+
+// 区域の一覧。重なったセルでは先に定義した区域が優先される
+// JSON では区域の配列 [{ name, region: { x, y, width, height }, payoffs, mutation_rate }] になる
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ZoneMap {
+    pub zones: Vec<Zone>,
+}
+
+impl ZoneMap {
+    pub fn new(zones: Vec<Zone>) -> Self {
+        Self { zones }
+    }
+
+    // position を含む最初の区域の番号
+    pub fn zone_index(&self, position: &Position) -> Option<usize> {
+        self.zones
+            .iter()
+            .position(|zone| zone.region.contains(position))
+    }
+
+    pub fn zone_at(&self, position: &Position) -> Option<&Zone> {
+        self.zone_index(position).map(|index| &self.zones[index])
+    }
+
+    pub fn payoffs_at(&self, position: &Position) -> Option<PayoffMatrix> {
+        self.zone_at(position).and_then(|zone| zone.payoffs)
+    }
+
+    pub fn mutation_rate_at(&self, position: &Position) -> Option<f64> {
+        self.zone_at(position).and_then(|zone| zone.mutation_rate)
+    }
+
+    // 空の区域や盤面からはみ出した区域がないか
+    pub fn validate(&self, width: usize, height: usize) -> Result<(), String> {
+        for zone in &self.zones {
+            let region = &zone.region;
+            if region.area() == 0 {
+                return Err(format!("zone {} is empty", zone.name));
+            }
+            if region.x + region.width > width || region.y + region.height > height {
+                return Err(format!(
+                    "zone {} ({}x{} at {},{}) lies outside the {width}x{height} grid",
+                    zone.name, region.width, region.height, region.x, region.y
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_matching_zone_wins() {
+        // Arrange: 左の 2x2 が厳しい区域。左上の 1 セルに重ねた区域は先に定義した harsh に隠れる
+        let harsh =
+            Zone::new("harsh", Region::new(0, 0, 2, 2)).with_payoffs(PayoffMatrix::new(8, 3, 1, 0));
+        let hot = Zone::new("hot", Region::new(0, 0, 1, 1)).with_mutation_rate(0.5);
+        let zones = ZoneMap::new(vec![harsh, hot]);
+
+        // Act
+        let indices: Vec<Option<usize>> = [(0, 0), (1, 1), (2, 0)]
+            .iter()
+            .map(|&(x, y)| zones.zone_index(&Position::new(x, y)))
+            .collect();
+
+        // Assert
+        assert_eq!(indices, vec![Some(0), Some(0), None]);
+        assert_eq!(
+            zones.payoffs_at(&Position::new(1, 1)),
+            Some(PayoffMatrix::new(8, 3, 1, 0))
+        );
+        assert_eq!(zones.mutation_rate_at(&Position::new(0, 0)), None);
+        assert_eq!(zones.payoffs_at(&Position::new(2, 0)), None);
+        assert!(zones.validate(3, 2).is_ok());
+        assert!(zones.validate(1, 2).is_err());
+    }
+}
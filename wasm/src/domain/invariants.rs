@@ -0,0 +1,318 @@
+use super::agent::{Action, Agent, Position};
+use super::game::PayoffMatrix;
+use super::grid::Grid;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 不変条件の違反 1 件（rule は破られた規則の名前、デバッグ表示や UI での絞り込みに使う）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantViolation {
+    pub rule: String,
+    pub message: String,
+}
+
+impl InvariantViolation {
+    pub fn new(rule: &str, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.to_string(),
+            message: message.into(),
+        }
+    }
+
+    // 全件を 1 つのエラーメッセージにまとめる
+    pub fn join(violations: &[InvariantViolation]) -> String {
+        violations
+            .iter()
+            .map(InvariantViolation::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.message)
+    }
+}
+
+// シミュレーションの途中でいつでも成り立つべき規則
+pub struct WorldInvariants;
+
+impl WorldInvariants {
+    // 確率として使う形質が [0, 1] に収まっているか
+    pub fn check_agent(agent: &Agent) -> Vec<InvariantViolation> {
+        let mut traits = vec![
+            ("mobility", agent.mobility),
+            ("in_group_bias", agent.in_group_bias),
+            ("aggression", agent.aggression),
+            ("cooperation_rate", agent.cooperation_rate()),
+        ];
+        if let Some(genome) = &agent.memory_genome {
+            traits.push(("memory_genome.initial", genome.initial()));
+            traits.extend(
+                genome
+                    .table()
+                    .iter()
+                    .map(|probability| ("memory_genome.table", *probability)),
+            );
+        }
+
+        traits
+            .into_iter()
+            .filter(|(_, value)| !(0.0..=1.0).contains(value))
+            .map(|(name, value)| {
+                InvariantViolation::new(
+                    "trait_range",
+                    format!("agent {} has {name} = {value} outside [0, 1]", agent.id),
+                )
+            })
+            .collect()
+    }
+
+    // 個体数が空きセル数以下で、各個体がグリッド内の壁でないセルにいて、占有配列と位置が一致するか
+    pub fn check_grid(grid: &Grid) -> Vec<InvariantViolation> {
+        let mut violations = Vec::new();
+        let capacity = grid.open_cell_count();
+        if grid.agent_count() > capacity {
+            violations.push(InvariantViolation::new(
+                "population_capacity",
+                format!(
+                    "{} agents exceed the {capacity} open cells",
+                    grid.agent_count()
+                ),
+            ));
+        }
+
+        for agent in grid.agents().values() {
+            let position = agent.position;
+            if position.x >= grid.width() || position.y >= grid.height() {
+                violations.push(InvariantViolation::new(
+                    "agent_position",
+                    format!(
+                        "agent {} is at ({}, {}) outside the {}x{} grid",
+                        agent.id,
+                        position.x,
+                        position.y,
+                        grid.width(),
+                        grid.height()
+                    ),
+                ));
+                continue;
+            }
+            if grid.is_obstacle(&position) {
+                violations.push(InvariantViolation::new(
+                    "agent_position",
+                    format!(
+                        "agent {} is on the obstacle at ({}, {})",
+                        agent.id, position.x, position.y
+                    ),
+                ));
+            }
+            if grid.get_agent_at_position(&position).map(|other| other.id) != Some(agent.id) {
+                violations.push(InvariantViolation::new(
+                    "occupancy",
+                    format!(
+                        "cell ({}, {}) does not point to agent {} standing on it",
+                        position.x, position.y, agent.id
+                    ),
+                ));
+            }
+        }
+
+        // 占有配列から引ける個体の数が個体の表と同じなら、どこにもいない個体を指すセルはない
+        let occupied = (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get_agent_at_position(&Position::new(x, y)).is_some())
+            .count();
+        if occupied != grid.agent_count() {
+            violations.push(InvariantViolation::new(
+                "occupancy",
+                format!(
+                    "{occupied} occupied cells for {} agents",
+                    grid.agent_count()
+                ),
+            ));
+        }
+        violations
+    }
+
+    // 利得表が対称か（自分と相手を入れ替えると利得も入れ替わる）
+    pub fn check_payoffs(payoffs: &PayoffMatrix) -> Vec<InvariantViolation> {
+        let actions = [Action::Cooperate, Action::Defect];
+        let mut violations = Vec::new();
+        for my_action in actions {
+            for opponent_action in actions {
+                let (mine, theirs) = payoffs.payoffs(my_action, opponent_action);
+                let swapped = payoffs.payoffs(opponent_action, my_action);
+                if swapped != (theirs, mine) {
+                    violations.push(InvariantViolation::new(
+                        "payoff_symmetry",
+                        format!(
+                            "payoffs for ({my_action:?}, {opponent_action:?}) are ({mine}, {theirs}) but swapped give {swapped:?}"
+                        ),
+                    ));
+                }
+            }
+        }
+        violations
+    }
+
+    pub fn check_world(grid: &Grid, payoffs: &PayoffMatrix) -> Vec<InvariantViolation> {
+        let mut violations = Self::check_grid(grid);
+        for agent in grid.agents_in_position_order() {
+            violations.extend(Self::check_agent(agent));
+        }
+        violations.extend(Self::check_payoffs(payoffs));
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MemoryGenome, MovementStrategy, StrategyType};
+    use crate::domain::grid::GridService;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use uuid::Uuid;
+
+    fn action(cooperate: bool) -> Action {
+        if cooperate {
+            Action::Cooperate
+        } else {
+            Action::Defect
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_new_agents_keep_traits_in_unit_interval(
+            mobility in -10.0f64..10.0,
+            x in 0usize..20,
+            y in 0usize..20,
+        ) {
+            // Arrange
+            let agent = Agent::new(
+                Position::new(x, y),
+                StrategyType::TitForTat,
+                mobility,
+                MovementStrategy::Explorer,
+            );
+
+            // Act
+            let violations = WorldInvariants::check_agent(&agent);
+
+            // Assert
+            prop_assert!(violations.is_empty(), "{}", InvariantViolation::join(&violations));
+        }
+
+        #[test]
+        fn prop_grid_occupancy_follows_adds_moves_and_removes(
+            seed in any::<u64>(),
+            agent_count in 0usize..30,
+            moves in prop::collection::vec((0usize..6, 0usize..6), 0..20),
+        ) {
+            // Arrange: 6x6 に個体を置き、ランダムな移動と取り除きを繰り返す
+            let mut grid = Grid::new(6, 6);
+            let mut rng = ChaCha12Rng::seed_from_u64(seed);
+            GridService::initialize_random_agents(&mut grid, agent_count, &mut rng).unwrap();
+
+            // Act
+            for (i, (x, y)) in moves.into_iter().enumerate() {
+                let ids: Vec<Uuid> =
+                    grid.agents_in_position_order().iter().map(|a| a.id).collect();
+                let Some(id) = ids.get(i % ids.len().max(1)) else {
+                    break;
+                };
+                if i % 5 == 4 {
+                    grid.remove_agent(id);
+                } else {
+                    let _ = grid.move_agent(id, Position::new(x, y));
+                }
+            }
+
+            // Assert
+            let violations = WorldInvariants::check_grid(&grid);
+            prop_assert!(violations.is_empty(), "{}", InvariantViolation::join(&violations));
+            prop_assert!(grid.agent_count() <= grid.open_cell_count());
+        }
+
+        #[test]
+        fn prop_payoffs_are_symmetric(
+            temptation in -100i32..100,
+            reward in -100i32..100,
+            punishment in -100i32..100,
+            sucker in -100i32..100,
+            mine in any::<bool>(),
+            theirs in any::<bool>(),
+        ) {
+            // Arrange
+            let payoffs = PayoffMatrix::new(temptation, reward, punishment, sucker);
+
+            // Act
+            let (a, b) = payoffs.payoffs(action(mine), action(theirs));
+            let (c, d) = payoffs.payoffs(action(theirs), action(mine));
+
+            // Assert
+            prop_assert_eq!((a, b), (d, c));
+            prop_assert!(WorldInvariants::check_payoffs(&payoffs).is_empty());
+        }
+
+        #[test]
+        fn prop_strategy_genes_decide_deterministically(
+            strategy in 0usize..StrategyType::ALL.len(),
+            rounds in prop::collection::vec((any::<bool>(), any::<bool>()), 0..8),
+            table in prop::collection::vec(0.0f64..=1.0, 4),
+            use_genome in any::<bool>(),
+            seed in any::<u64>(),
+        ) {
+            // Arrange: 同じ遺伝子と同じ履歴の個体
+            let mut agent = Agent::new(
+                Position::new(0, 0),
+                StrategyType::ALL[strategy],
+                0.5,
+                MovementStrategy::Explorer,
+            );
+            if use_genome {
+                let genome = MemoryGenome::new(1, table[0], table).unwrap();
+                agent = agent.with_memory_genome(genome);
+            }
+            let opponent = Uuid::new_v4();
+            for (my_move, opponent_move) in rounds {
+                agent.history.add_game(opponent, action(my_move), action(opponent_move), 0);
+            }
+            let twin = agent.clone();
+
+            // Act
+            let first = agent.decide_action(&opponent, &mut ChaCha12Rng::seed_from_u64(seed));
+            let second = twin.decide_action(&opponent, &mut ChaCha12Rng::seed_from_u64(seed));
+
+            // Assert: 同じ乱数列なら行動も同じ
+            prop_assert_eq!(first, second);
+            prop_assert!(WorldInvariants::check_agent(&agent).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_check_world_reports_broken_traits() {
+        // Arrange
+        let mut grid = Grid::new(2, 2);
+        let mut agent = Agent::new(
+            Position::new(1, 1),
+            StrategyType::AllCooperate,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        agent.aggression = 1.5;
+        grid.add_agent(agent).unwrap();
+
+        // Act
+        let violations = WorldInvariants::check_world(&grid, &PayoffMatrix::default());
+
+        // Assert
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "trait_range");
+        assert!(violations[0].message.contains("aggression"));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod agent;
 pub mod game;
 pub mod grid;
+pub mod invariants;
@@ -1 +1,3 @@
+pub mod persistence;
+pub mod render;
 pub mod wasm_bindings;
@@ -0,0 +1,357 @@
+use super::{SerializationError, SerializationService};
+use crate::application::simulation::SimulationStatistics;
+use crate::domain::agent::{Agent, MovementStrategy};
+use arrow_array::{
+    ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use std::io::Write;
+use std::sync::Arc;
+
+// 列指向の書き出し形式。どちらも pandas / polars でそのまま読める（read_feather / read_parquet）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    // Arrow IPC のファイル形式（Feather v2）
+    Arrow,
+    Parquet,
+}
+
+impl ColumnarFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "arrow" | "ipc" | "feather" => Some(Self::Arrow),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+
+    // 拡張子が .parquet なら Parquet、それ以外は Arrow IPC
+    pub fn from_path(path: &str) -> Self {
+        if path.to_ascii_lowercase().ends_with(".parquet") {
+            Self::Parquet
+        } else {
+            Self::Arrow
+        }
+    }
+}
+
+enum Sink<W: Write + Send> {
+    Arrow(arrow_ipc::writer::FileWriter<W>),
+    Parquet(ArrowWriter<W>),
+}
+
+// 同じスキーマのバッチを 1 つずつ書き足す（世代ごとの個体を溜めずに書き出すため）
+pub struct ColumnarWriter<W: Write + Send> {
+    sink: Sink<W>,
+    schema: SchemaRef,
+    rows: usize,
+}
+
+impl<W: Write + Send> ColumnarWriter<W> {
+    pub fn new(
+        writer: W,
+        schema: SchemaRef,
+        format: ColumnarFormat,
+    ) -> Result<Self, SerializationError> {
+        let sink = match format {
+            ColumnarFormat::Arrow => {
+                Sink::Arrow(arrow_ipc::writer::FileWriter::try_new(writer, &schema)?)
+            }
+            ColumnarFormat::Parquet => {
+                Sink::Parquet(ArrowWriter::try_new(writer, schema.clone(), None)?)
+            }
+        };
+        Ok(Self {
+            sink,
+            schema,
+            rows: 0,
+        })
+    }
+
+    // 個体のスナップショット用（ColumnarExporter::agents_schema）
+    pub fn for_agents(writer: W, format: ColumnarFormat) -> Result<Self, SerializationError> {
+        Self::new(writer, ColumnarExporter::agents_schema(), format)
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), SerializationError> {
+        if batch.schema() != self.schema {
+            return Err(SerializationError::SchemaMismatch);
+        }
+        match &mut self.sink {
+            Sink::Arrow(writer) => writer.write(batch)?,
+            Sink::Parquet(writer) => writer.write(batch)?,
+        }
+        self.rows += batch.num_rows();
+        Ok(())
+    }
+
+    // generation 世代目の個体を書き足す
+    pub fn write_agents(
+        &mut self,
+        generation: u32,
+        agents: &[Agent],
+    ) -> Result<(), SerializationError> {
+        self.write(&ColumnarExporter::agents_batch(generation, agents)?)
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+
+    // フッタを書いて writer を返す
+    pub fn finish(self) -> Result<W, SerializationError> {
+        match self.sink {
+            Sink::Arrow(writer) => Ok(writer.into_inner()?),
+            Sink::Parquet(writer) => Ok(writer.into_inner()?),
+        }
+    }
+}
+
+// 個体と統計の履歴を Arrow の RecordBatch にする。CSV と違って小数を丸めない
+pub struct ColumnarExporter;
+
+impl ColumnarExporter {
+    pub fn agents_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("generation", DataType::UInt32, false),
+            Field::new("id", DataType::Utf8, false),
+            Field::new("x", DataType::UInt32, false),
+            Field::new("y", DataType::UInt32, false),
+            Field::new("strategy", DataType::Utf8, false),
+            Field::new("movement_strategy", DataType::Utf8, false),
+            Field::new("mobility", DataType::Float64, false),
+            Field::new("score", DataType::Int32, false),
+            Field::new("normalized_score", DataType::Float64, false),
+            Field::new("cooperation_rate", DataType::Float64, false),
+            Field::new("energy", DataType::Float64, false),
+            Field::new("age", DataType::UInt32, false),
+            Field::new("tag", DataType::UInt8, false),
+            Field::new("in_group_bias", DataType::Float64, false),
+            Field::new("aggression", DataType::Float64, false),
+        ]))
+    }
+
+    pub fn agents_batch(
+        generation: u32,
+        agents: &[Agent],
+    ) -> Result<RecordBatch, SerializationError> {
+        let floats = |value: fn(&Agent) -> f64| -> ArrayRef {
+            Arc::new(Float64Array::from_iter_values(agents.iter().map(value)))
+        };
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from(vec![generation; agents.len()])),
+            Arc::new(StringArray::from_iter_values(
+                agents.iter().map(|a| a.id.to_string()),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                agents.iter().map(|a| a.position.x as u32),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                agents.iter().map(|a| a.position.y as u32),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                agents.iter().map(|a| format!("{:?}", a.strategy)),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                agents.iter().map(|a| a.movement_strategy.to_string()),
+            )),
+            floats(|a| a.mobility),
+            Arc::new(Int32Array::from_iter_values(agents.iter().map(|a| a.score))),
+            floats(|a| a.normalized_score),
+            floats(Agent::cooperation_rate),
+            floats(|a| a.energy),
+            Arc::new(UInt32Array::from_iter_values(agents.iter().map(|a| a.age))),
+            Arc::new(UInt8Array::from_iter_values(agents.iter().map(|a| a.tag))),
+            floats(|a| a.in_group_bias),
+            floats(|a| a.aggression),
+        ];
+        Ok(RecordBatch::try_new(Self::agents_schema(), columns)?)
+    }
+
+    // 列は stats_history_to_csv と同じ（戦略と移動戦略ごとの個体数を含む）
+    pub fn stats_history_batch<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics> + Copy,
+    ) -> Result<RecordBatch, SerializationError> {
+        let strategies = SerializationService::census_strategies(history);
+        let mut fields = vec![
+            Field::new("generation", DataType::UInt32, false),
+            Field::new("total_agents", DataType::UInt64, false),
+        ];
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from_iter_values(
+                history.into_iter().map(|s| s.generation),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                history.into_iter().map(|s| s.total_agents as u64),
+            )),
+        ];
+        for strategy in &strategies {
+            fields.push(Field::new(format!("{strategy:?}"), DataType::UInt64, false));
+            columns.push(Arc::new(UInt64Array::from_iter_values(
+                history
+                    .into_iter()
+                    .map(|s| *s.strategy_counts.get(strategy).unwrap_or(&0) as u64),
+            )));
+        }
+        for movement in MovementStrategy::ALL {
+            let name = movement.to_string();
+            columns.push(Arc::new(UInt64Array::from_iter_values(
+                history
+                    .into_iter()
+                    .map(|s| *s.movement_strategy_counts.get(&name).unwrap_or(&0) as u64),
+            )));
+            fields.push(Field::new(name, DataType::UInt64, false));
+        }
+
+        let float_column = |value: fn(&SimulationStatistics) -> f64| -> ArrayRef {
+            Arc::new(Float64Array::from_iter_values(
+                history.into_iter().map(value),
+            ))
+        };
+        let count_column = |value: fn(&SimulationStatistics) -> usize| -> ArrayRef {
+            Arc::new(UInt64Array::from_iter_values(
+                history.into_iter().map(|s| value(s) as u64),
+            ))
+        };
+        let rest = [
+            (
+                "average_cooperation_rate",
+                float_column(|s| s.average_cooperation_rate),
+            ),
+            ("average_mobility", float_column(|s| s.average_mobility)),
+            ("average_score", float_column(|s| s.average_score)),
+            ("average_fitness", float_column(|s| s.average_fitness)),
+            ("mutation_rate", float_column(|s| s.mutation_rate)),
+            ("births", count_column(|s| s.births)),
+            ("deaths", count_column(|s| s.deaths)),
+            ("punishments", count_column(|s| s.punishments)),
+            (
+                "punishment_frequency",
+                float_column(|s| s.punishment_frequency),
+            ),
+            ("niche_count", count_column(|s| s.niche_count)),
+            ("shannon_index", float_column(|s| s.shannon_index)),
+            ("simpson_index", float_column(|s| s.simpson_index)),
+        ];
+        for (name, column) in rest {
+            fields.push(Field::new(name, column.data_type().clone(), false));
+            columns.push(column);
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    pub fn batch_to_bytes(
+        batch: &RecordBatch,
+        format: ColumnarFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        let mut writer = ColumnarWriter::new(Vec::new(), batch.schema(), format)?;
+        writer.write(batch)?;
+        writer.finish()
+    }
+
+    pub fn agents_to_columnar(
+        generation: u32,
+        agents: &[Agent],
+        format: ColumnarFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        Self::batch_to_bytes(&Self::agents_batch(generation, agents)?, format)
+    }
+
+    pub fn stats_history_to_columnar<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics> + Copy,
+        format: ColumnarFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        Self::batch_to_bytes(&Self::stats_history_batch(history)?, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Position, StrategyType};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, UInt32Type};
+    use std::io::Cursor;
+
+    fn agents() -> Vec<Agent> {
+        vec![
+            Agent::new(
+                Position::new(1, 2),
+                StrategyType::TitForTat,
+                0.123456789,
+                MovementStrategy::Explorer,
+            ),
+            Agent::new(
+                Position::new(3, 4),
+                StrategyType::AllDefect,
+                0.5,
+                MovementStrategy::Settler,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_arrow_writer_appends_generations_without_rounding() {
+        // Arrange
+        let agents = agents();
+        let mut writer = ColumnarWriter::for_agents(Vec::new(), ColumnarFormat::Arrow).unwrap();
+
+        // Act
+        writer.write_agents(1, &agents).unwrap();
+        writer.write_agents(2, &agents).unwrap();
+        let rows = writer.row_count();
+        let bytes = writer.finish().unwrap();
+
+        // Assert: 読み戻すと 2 世代分の行があり、移動性は丸められていない
+        assert_eq!(rows, 4);
+        let reader = arrow_ipc::reader::FileReader::try_new(Cursor::new(bytes), None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        assert_eq!(batches.len(), 2);
+        let second = &batches[1];
+        assert_eq!(second.column(0).as_primitive::<UInt32Type>().value(0), 2);
+        assert_eq!(second.column(4).as_string::<i32>().value(1), "AllDefect");
+        assert_eq!(
+            second.column(6).as_primitive::<Float64Type>().value(0),
+            0.123456789
+        );
+    }
+
+    #[test]
+    fn test_stats_history_to_parquet_and_schema_checks() {
+        // Arrange
+        let mut stats = SimulationStatistics::new();
+        stats.generation = 7;
+        stats.total_agents = 2;
+        stats.strategy_counts.insert(StrategyType::TitForTat, 2);
+
+        // Act
+        let batch = ColumnarExporter::stats_history_batch(&[stats]).unwrap();
+        let parquet =
+            ColumnarExporter::stats_history_to_columnar(&[], ColumnarFormat::Parquet).unwrap();
+        let mismatch = ColumnarWriter::for_agents(Vec::new(), ColumnarFormat::Parquet)
+            .unwrap()
+            .write(&batch);
+
+        // Assert: 列の並びは CSV と同じ
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "generation");
+        assert!(schema.field_with_name("TitForTat").is_ok());
+        assert_eq!(schema.fields().last().unwrap().name(), "simpson_index");
+        assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+        assert!(matches!(mismatch, Err(SerializationError::SchemaMismatch)));
+        assert_eq!(
+            ColumnarFormat::from_path("run.PARQUET"),
+            ColumnarFormat::Parquet
+        );
+        assert_eq!(
+            ColumnarFormat::from_name("feather"),
+            Some(ColumnarFormat::Arrow)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::error::Error;
+use thiserror::Error;
+
+// 符号化・復号の失敗。元の serde_json / rmp_serde のエラーは source に残す
+#[derive(Debug, Error)]
+pub enum SerializationError {
+    #[error("Failed to process JSON")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to encode MessagePack")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("Failed to decode MessagePack")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+    #[error("Missing binary header")]
+    MissingBinaryHeader,
+    #[error("Unsupported binary format version: {0}")]
+    UnsupportedBinaryVersion(u8),
+    #[cfg(feature = "arrow")]
+    #[error("Failed to encode Arrow")]
+    Arrow(#[from] arrow_schema::ArrowError),
+    #[cfg(feature = "arrow")]
+    #[error("Failed to encode Parquet")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[cfg(feature = "arrow")]
+    #[error("Record batch does not match the writer's schema")]
+    SchemaMismatch,
+}
+
+// スキーマの版の読み取り・書き換えの失敗
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("Expected a JSON object")]
+    NotAnObject,
+    #[error("Invalid schema version: {0}")]
+    InvalidVersion(serde_json::Value),
+    #[error("Unsupported schema version {version} (this build reads up to {current})")]
+    UnsupportedVersion { version: u32, current: u32 },
+}
+
+// スナップショット・プリセットの保存と読み込みの失敗
+#[derive(Debug, Error)]
+pub enum PersistenceError {
+    #[error("Unable to detect persistence format")]
+    UnknownFormat,
+    #[error(transparent)]
+    Serialization(#[from] SerializationError),
+    #[error("Unsupported document")]
+    Migration(#[from] MigrationError),
+    #[error("Invalid edge on line {line}: {content:?}")]
+    InvalidEdge { line: usize, content: String },
+    #[error("Invalid preset name: {0:?}")]
+    InvalidPresetName(String),
+    #[error("Failed to {action} {path}")]
+    Io {
+        action: &'static str,
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    // localStorage は原因を返さないので理由だけを持つ
+    #[error("{0}")]
+    Storage(&'static str),
+}
+
+impl From<serde_json::Error> for PersistenceError {
+    fn from(error: serde_json::Error) -> Self {
+        PersistenceError::Serialization(error.into())
+    }
+}
+
+// 原因をたどって "外側: 内側: ..." の 1 行にする（CLI の表示や文字列のエラーへの変換に使う）
+pub fn error_chain(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+// アプリケーション層と CLI はエラーを文字列で受け渡すので、原因ごと文字列にする
+impl From<SerializationError> for String {
+    fn from(error: SerializationError) -> Self {
+        error_chain(&error)
+    }
+}
+
+impl From<PersistenceError> for String {
+    fn from(error: PersistenceError) -> Self {
+        error_chain(&error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_chain_keeps_serde_json_source() {
+        // Arrange
+        let json_error = serde_json::from_str::<u32>("oops").unwrap_err();
+        let detail = json_error.to_string();
+
+        // Act
+        let error = PersistenceError::from(json_error);
+        let has_source = error.source().is_some();
+        let message = String::from(error);
+
+        // Assert: serde_json → SerializationError → PersistenceError（transparent）の順に連なる
+        assert!(has_source);
+        assert_eq!(message, format!("Failed to process JSON: {detail}"));
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+// バイナリ形式の先頭に付与するヘッダ
+pub const BINARY_MAGIC: &[u8; 4] = b"PD2D";
+pub const BINARY_FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PersistenceFormat {
+    Json,
+    MessagePack,
+}
+
+impl PersistenceFormat {
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(BINARY_MAGIC) {
+            return Some(PersistenceFormat::MessagePack);
+        }
+
+        // JSON は先頭の空白を除いて `{` か `[` で始まる
+        match bytes.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            Some(b'{') | Some(b'[') => Some(PersistenceFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_json() {
+        // Arrange & Act & Assert
+        assert_eq!(
+            PersistenceFormat::detect(b"[]"),
+            Some(PersistenceFormat::Json)
+        );
+        assert_eq!(
+            PersistenceFormat::detect(b"  \n{\"a\":1}"),
+            Some(PersistenceFormat::Json)
+        );
+    }
+
+    #[test]
+    fn test_detect_message_pack_by_header() {
+        // Arrange
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.push(BINARY_FORMAT_VERSION);
+
+        // Act & Assert
+        assert_eq!(
+            PersistenceFormat::detect(&bytes),
+            Some(PersistenceFormat::MessagePack)
+        );
+    }
+
+    #[test]
+    fn test_detect_unknown_format() {
+        // Arrange & Act & Assert
+        assert_eq!(PersistenceFormat::detect(b"x,y,strategy"), None);
+        assert_eq!(PersistenceFormat::detect(b""), None);
+    }
+}
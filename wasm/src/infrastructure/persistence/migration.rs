@@ -0,0 +1,108 @@
+use super::MigrationError;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+// 1 版上げる変換。steps[i] は版 i + 1 の文書を版 i + 2 に書き換える
+pub type MigrationStep = fn(&mut Map<String, Value>);
+
+pub const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+// 本体を読まずに版だけを取り出す（MessagePack の文書は Value に変換できないため）
+#[derive(Debug, Deserialize)]
+pub struct SchemaProbe {
+    #[serde(default)]
+    pub schema_version: Option<u32>,
+}
+
+pub struct SchemaMigration;
+
+impl SchemaMigration {
+    // 旧版の文書を current 版まで順に書き換える。新しすぎる版は読めないのでエラー
+    // schema_version がない文書は版 1 とみなす
+    pub fn upgrade(
+        mut value: Value,
+        current: u32,
+        steps: &[MigrationStep],
+    ) -> Result<Value, MigrationError> {
+        let document = value.as_object_mut().ok_or(MigrationError::NotAnObject)?;
+        let version = Self::version_of(document)?;
+        Self::check_supported(version, current)?;
+
+        for (index, step) in steps.iter().enumerate() {
+            let from = index as u32 + 1;
+            if from >= version && from < current {
+                step(document);
+                document.insert(SCHEMA_VERSION_FIELD.to_string(), Value::from(from + 1));
+            }
+        }
+        document.insert(SCHEMA_VERSION_FIELD.to_string(), Value::from(current));
+        Ok(value)
+    }
+
+    pub fn check_supported(version: u32, current: u32) -> Result<(), MigrationError> {
+        if version > current {
+            return Err(MigrationError::UnsupportedVersion { version, current });
+        }
+        Ok(())
+    }
+
+    fn version_of(document: &Map<String, Value>) -> Result<u32, MigrationError> {
+        match document.get(SCHEMA_VERSION_FIELD) {
+            None => Ok(1),
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .filter(|version| *version >= 1)
+                .ok_or_else(|| MigrationError::InvalidVersion(version.clone())),
+        }
+    }
+
+    // フィールド名の変更（旧名がなければ何もしない）
+    pub fn rename(document: &mut Map<String, Value>, from: &str, to: &str) {
+        if let Some(value) = document.remove(from) {
+            document.insert(to.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_a_to_b(document: &mut Map<String, Value>) {
+        SchemaMigration::rename(document, "a", "b");
+    }
+
+    fn double_b(document: &mut Map<String, Value>) {
+        let b = document["b"].as_i64().unwrap();
+        document.insert("b".to_string(), json!(b * 2));
+    }
+
+    #[test]
+    fn test_upgrade_applies_only_steps_after_document_version() {
+        // Arrange
+        let steps: [MigrationStep; 2] = [rename_a_to_b, double_b];
+
+        // Act
+        let from_v1 = SchemaMigration::upgrade(json!({ "a": 3 }), 3, &steps).unwrap();
+        let from_v2 =
+            SchemaMigration::upgrade(json!({ "schema_version": 2, "b": 3 }), 3, &steps).unwrap();
+
+        // Assert
+        assert_eq!(from_v1, json!({ "schema_version": 3, "b": 6 }));
+        assert_eq!(from_v2, json!({ "schema_version": 3, "b": 6 }));
+    }
+
+    #[test]
+    fn test_upgrade_rejects_newer_versions() {
+        // Arrange & Act
+        let result = SchemaMigration::upgrade(json!({ "schema_version": 4 }), 3, &[]);
+
+        // Assert
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported schema version 4"));
+    }
+}
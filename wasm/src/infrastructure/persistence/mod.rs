@@ -0,0 +1,21 @@
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod error;
+pub mod format;
+pub mod migration;
+pub mod preset;
+pub mod repository;
+pub mod serialization;
+pub mod service;
+pub mod snapshot;
+
+#[cfg(feature = "arrow")]
+pub use columnar::*;
+pub use error::*;
+pub use format::*;
+pub use migration::*;
+pub use preset::*;
+pub use repository::*;
+pub use serialization::*;
+pub use service::*;
+pub use snapshot::*;
@@ -0,0 +1,137 @@
+use super::{MigrationStep, PersistenceError, SchemaMigration};
+use crate::application::simulation::{SimulationConfig, SimulationService};
+use serde::{Deserialize, Serialize};
+
+pub const PRESET_VERSION: u32 = 1;
+
+// 版を上げたらここに書き換えを足す
+pub const PRESET_MIGRATIONS: [MigrationStep; 0] = [];
+
+// 同じ条件でシミュレーションを作り直せる設定一式（状態は含まない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationPreset {
+    pub schema_version: u32,
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub agent_count: usize,
+    pub config: SimulationConfig,
+}
+
+impl SimulationPreset {
+    pub fn new(
+        name: &str,
+        width: usize,
+        height: usize,
+        agent_count: usize,
+        config: SimulationConfig,
+    ) -> Result<Self, PersistenceError> {
+        Self::validate_name(name)?;
+        Ok(Self {
+            schema_version: PRESET_VERSION,
+            name: name.to_string(),
+            width,
+            height,
+            agent_count,
+            config,
+        })
+    }
+
+    pub fn from_simulation(
+        name: &str,
+        simulation: &SimulationService,
+        agent_count: usize,
+    ) -> Result<Self, PersistenceError> {
+        let (width, height) = simulation.get_grid_size();
+        Self::new(
+            name,
+            width,
+            height,
+            agent_count,
+            simulation.get_config().clone(),
+        )
+    }
+
+    pub fn build(&self) -> Result<SimulationService, String> {
+        SimulationService::with_config(
+            self.width,
+            self.height,
+            self.agent_count,
+            self.config.clone(),
+        )
+    }
+
+    pub fn to_json(&self) -> Result<String, PersistenceError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    // 旧版のプリセットは現在の版へ書き換えてから読む
+    pub fn from_json(json: &str) -> Result<Self, PersistenceError> {
+        let document: serde_json::Value = serde_json::from_str(json)?;
+        let document = SchemaMigration::upgrade(document, PRESET_VERSION, &PRESET_MIGRATIONS)?;
+        let preset: Self = serde_json::from_value(document)?;
+        Self::validate_name(&preset.name)?;
+        Ok(preset)
+    }
+
+    // ファイル名や localStorage のキーにそのまま使うため、英数字と - _ . 空白に限る
+    pub fn validate_name(name: &str) -> Result<(), PersistenceError> {
+        let valid = !name.trim().is_empty()
+            && name.len() <= 64
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '));
+        if valid {
+            Ok(())
+        } else {
+            Err(PersistenceError::InvalidPresetName(name.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::persistence::SCHEMA_VERSION_FIELD;
+
+    #[test]
+    fn test_preset_json_round_trip_rebuilds_simulation() {
+        // Arrange
+        let config = SimulationConfig::default()
+            .with_seed(5)
+            .with_mutation_rate(0.2);
+        let preset = SimulationPreset::new("high mutation", 12, 8, 30, config).unwrap();
+
+        // Act
+        let restored = SimulationPreset::from_json(&preset.to_json().unwrap()).unwrap();
+        let simulation = restored.build().unwrap();
+
+        // Assert
+        assert_eq!(restored.schema_version, PRESET_VERSION);
+        assert_eq!(simulation.get_grid_size(), (12, 8));
+        assert_eq!(simulation.get_agent_count(), 30);
+        assert_eq!(simulation.get_config().mutation_rate, 0.2);
+    }
+
+    #[test]
+    fn test_preset_without_schema_version_is_read_as_version_one() {
+        // Arrange
+        let mut document = serde_json::to_value(
+            SimulationPreset::new("legacy", 5, 5, 5, SimulationConfig::default()).unwrap(),
+        )
+        .unwrap();
+        document
+            .as_object_mut()
+            .unwrap()
+            .remove(SCHEMA_VERSION_FIELD);
+
+        // Act
+        let preset = SimulationPreset::from_json(&document.to_string()).unwrap();
+
+        // Assert
+        assert_eq!(preset.schema_version, PRESET_VERSION);
+        assert!(SimulationPreset::validate_name("../etc").is_err());
+        assert!(SimulationPreset::validate_name("").is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use super::{PersistenceError, SimulationPreset};
+use std::collections::BTreeMap;
+
+// プリセットの保存先（ブラウザは localStorage、ネイティブはディレクトリ、テストはメモリ）
+pub trait PresetRepository {
+    // 同名のプリセットは上書きする
+    fn save(&mut self, preset: &SimulationPreset) -> Result<(), PersistenceError>;
+    fn load(&self, name: &str) -> Result<Option<SimulationPreset>, PersistenceError>;
+    // 名前順
+    fn list(&self) -> Result<Vec<String>, PersistenceError>;
+    // 削除したかどうかを返す
+    fn delete(&mut self, name: &str) -> Result<bool, PersistenceError>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryPresetRepository {
+    presets: BTreeMap<String, String>,
+}
+
+impl InMemoryPresetRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PresetRepository for InMemoryPresetRepository {
+    fn save(&mut self, preset: &SimulationPreset) -> Result<(), PersistenceError> {
+        self.presets.insert(preset.name.clone(), preset.to_json()?);
+        Ok(())
+    }
+
+    fn load(&self, name: &str) -> Result<Option<SimulationPreset>, PersistenceError> {
+        self.presets
+            .get(name)
+            .map(|json| SimulationPreset::from_json(json))
+            .transpose()
+    }
+
+    fn list(&self) -> Result<Vec<String>, PersistenceError> {
+        Ok(self.presets.keys().cloned().collect())
+    }
+
+    fn delete(&mut self, name: &str) -> Result<bool, PersistenceError> {
+        Ok(self.presets.remove(name).is_some())
+    }
+}
+
+// <dir>/<name>.json に 1 ファイルずつ保存する
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FilePresetRepository {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FilePresetRepository {
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, name: &str) -> Result<std::path::PathBuf, PersistenceError> {
+        SimulationPreset::validate_name(name)?;
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PresetRepository for FilePresetRepository {
+    fn save(&mut self, preset: &SimulationPreset) -> Result<(), PersistenceError> {
+        let path = self.path(&preset.name)?;
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|source| io_error("create", &self.dir, source))?;
+        std::fs::write(&path, preset.to_json()?).map_err(|source| io_error("write", &path, source))
+    }
+
+    fn load(&self, name: &str) -> Result<Option<SimulationPreset>, PersistenceError> {
+        let path = self.path(name)?;
+        match std::fs::read_to_string(&path) {
+            Ok(json) => SimulationPreset::from_json(&json).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(io_error("read", &path, source)),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<String>, PersistenceError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(io_error("read", &self.dir, source)),
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                file_name.strip_suffix(".json").map(str::to_string)
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn delete(&mut self, name: &str) -> Result<bool, PersistenceError> {
+        let path = self.path(name)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(source) => Err(io_error("delete", &path, source)),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn io_error(
+    action: &'static str,
+    path: &std::path::Path,
+    source: std::io::Error,
+) -> PersistenceError {
+    PersistenceError::Io {
+        action,
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+// window.localStorage のキー "<prefix><name>" に JSON で保存する（ブラウザ上でのみ動く）
+#[derive(Debug, Clone)]
+pub struct LocalStoragePresetRepository {
+    prefix: String,
+}
+
+impl Default for LocalStoragePresetRepository {
+    fn default() -> Self {
+        Self {
+            prefix: "pd2d.preset.".to_string(),
+        }
+    }
+}
+
+impl LocalStoragePresetRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn storage(&self) -> Result<web_sys::Storage, PersistenceError> {
+        web_sys::window()
+            .ok_or(PersistenceError::Storage("No window available"))?
+            .local_storage()
+            .map_err(|_| PersistenceError::Storage("localStorage is not accessible"))?
+            .ok_or(PersistenceError::Storage("localStorage is not available"))
+    }
+
+    fn key(&self, name: &str) -> Result<String, PersistenceError> {
+        SimulationPreset::validate_name(name)?;
+        Ok(format!("{}{name}", self.prefix))
+    }
+}
+
+impl PresetRepository for LocalStoragePresetRepository {
+    fn save(&mut self, preset: &SimulationPreset) -> Result<(), PersistenceError> {
+        self.storage()?
+            .set_item(&self.key(&preset.name)?, &preset.to_json()?)
+            .map_err(|_| {
+                PersistenceError::Storage("Failed to write localStorage (quota exceeded?)")
+            })
+    }
+
+    fn load(&self, name: &str) -> Result<Option<SimulationPreset>, PersistenceError> {
+        self.storage()?
+            .get_item(&self.key(name)?)
+            .map_err(|_| PersistenceError::Storage("Failed to read localStorage"))?
+            .map(|json| SimulationPreset::from_json(&json))
+            .transpose()
+    }
+
+    fn list(&self) -> Result<Vec<String>, PersistenceError> {
+        let storage = self.storage()?;
+        let length = storage
+            .length()
+            .map_err(|_| PersistenceError::Storage("Failed to read localStorage"))?;
+        let mut names: Vec<String> = (0..length)
+            .filter_map(|index| storage.key(index).ok().flatten())
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn delete(&mut self, name: &str) -> Result<bool, PersistenceError> {
+        let existed = self.load(name)?.is_some();
+        self.storage()?
+            .remove_item(&self.key(name)?)
+            .map_err(|_| PersistenceError::Storage("Failed to write localStorage"))?;
+        Ok(existed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::simulation::SimulationConfig;
+
+    fn exercise(repository: &mut dyn PresetRepository) {
+        // Arrange
+        let config = SimulationConfig::default().with_seed(1);
+        let alpha = SimulationPreset::new("alpha", 10, 10, 20, config.clone()).unwrap();
+        let beta = SimulationPreset::new("beta", 20, 20, 50, config).unwrap();
+
+        // Act
+        repository.save(&beta).unwrap();
+        repository.save(&alpha).unwrap();
+        let listed = repository.list().unwrap();
+        let loaded = repository.load("beta").unwrap().unwrap();
+        let deleted = repository.delete("alpha").unwrap();
+
+        // Assert
+        assert_eq!(listed, vec!["alpha", "beta"]);
+        assert_eq!(loaded.agent_count, 50);
+        assert!(deleted);
+        assert!(!repository.delete("alpha").unwrap());
+        assert!(repository.load("alpha").unwrap().is_none());
+        assert_eq!(repository.list().unwrap(), vec!["beta"]);
+    }
+
+    #[test]
+    fn test_in_memory_repository_crud() {
+        exercise(&mut InMemoryPresetRepository::new());
+    }
+
+    #[test]
+    fn test_file_repository_crud() {
+        let dir = std::env::temp_dir().join(format!("pd2d-presets-{}", uuid::Uuid::new_v4()));
+        exercise(&mut FilePresetRepository::new(&dir));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1613 @@
+use super::{PersistenceFormat, PersistenceService, SerializationError};
+use crate::application::analysis::{
+    AggregatedResult, ComparisonMetric, ExperimentRow, InteractionNetwork, NetworkMetrics,
+    ResultComparison, SpatialMetrics, Summary,
+};
+use crate::application::evolution::{Objective, ParetoFront};
+use crate::application::simulation::{
+    DetailedStatistics, GenerationRecord, PerformanceMetrics, PerformanceReport,
+    SimulationStatistics, TrajectoryPoint,
+};
+use crate::domain::agent::{Agent, MovementStrategy, StrategyType};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub precision: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self { precision: 4 } // 小数点以下4桁
+    }
+}
+
+impl ExportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn format_float(&self, value: f64) -> String {
+        format!("{value:.prec$}", prec = self.precision)
+    }
+
+    pub fn round_float(&self, value: f64) -> f64 {
+        let factor = 10f64.powi(self.precision.min(15) as i32);
+        (value * factor).round() / factor
+    }
+}
+
+// 書かれたバイト列を chunk_size 前後の行単位の断片にまとめて sink に渡す（WASM で巨大な文字列を作らずに CSV を渡すため）
+// sink が false を返すと以降の書き込みは失敗し、is_cancelled() が true になる
+pub struct ChunkedWriter<F: FnMut(&str) -> bool> {
+    sink: F,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    chunks: usize,
+    cancelled: bool,
+}
+
+impl<F: FnMut(&str) -> bool> ChunkedWriter<F> {
+    pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+    pub fn new(chunk_size: usize, sink: F) -> Self {
+        let chunk_size = chunk_size.max(1);
+        Self {
+            sink,
+            chunk_size,
+            buffer: Vec::with_capacity(chunk_size),
+            chunks: 0,
+            cancelled: false,
+        }
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    // 残りを渡して、渡した断片の数を返す
+    pub fn finish(mut self) -> io::Result<usize> {
+        let rest = std::mem::take(&mut self.buffer);
+        self.emit(&rest)?;
+        Ok(self.chunks)
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.cancelled {
+            return Err(io::Error::other("export was cancelled"));
+        }
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let text = std::str::from_utf8(bytes).map_err(io::Error::other)?;
+        self.chunks += 1;
+        if !(self.sink)(text) {
+            self.cancelled = true;
+            return Err(io::Error::other("export was cancelled"));
+        }
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&str) -> bool> Write for ChunkedWriter<F> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        if self.cancelled {
+            return Err(io::Error::other("export was cancelled"));
+        }
+        self.buffer.extend_from_slice(bytes);
+        // 行の途中（と UTF-8 の文字の途中）で切らないよう、最後の改行までを渡す
+        if self.buffer.len() >= self.chunk_size {
+            if let Some(end) = self.buffer.iter().rposition(|&byte| byte == b'\n') {
+                let rest = self.buffer.split_off(end + 1);
+                let chunk = std::mem::replace(&mut self.buffer, rest);
+                self.emit(&chunk)?;
+            }
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct SerializationService;
+
+impl SerializationService {
+    pub fn agents_to_csv(agents: &[Agent], options: &ExportOptions) -> String {
+        let mut bytes = Vec::new();
+        Self::write_agents_csv(&mut bytes, agents, options).expect("writing to a Vec cannot fail");
+        String::from_utf8(bytes).expect("CSV is valid UTF-8")
+    }
+
+    // agents_to_csv と同じ CSV を 1 行ずつ writer に書く（全体を String に溜めない）
+    pub fn write_agents_csv<'a, W: Write + ?Sized>(
+        writer: &mut W,
+        agents: impl IntoIterator<Item = &'a Agent>,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        writer.write_all(b"id,x,y,strategy,movement_strategy,mobility,score,cooperation_rate\n")?;
+
+        let mut line = String::new();
+        for agent in agents {
+            line.clear();
+            let _ = writeln!(
+                line,
+                "{},{},{},{:?},{},{},{},{}",
+                agent.id,
+                agent.position.x,
+                agent.position.y,
+                agent.strategy,
+                agent.movement_strategy,
+                options.format_float(agent.mobility),
+                agent.score,
+                options.format_float(agent.cooperation_rate()),
+            );
+            writer.write_all(line.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    // 全個体の対戦履歴を 1 行 1 記録で書く（1 回の対戦は両者の履歴に 1 行ずつ現れる）
+    pub fn write_battle_log_csv<'a, W: Write + ?Sized>(
+        writer: &mut W,
+        agents: impl IntoIterator<Item = &'a Agent>,
+    ) -> io::Result<()> {
+        writer.write_all(
+            b"agent_id,opponent_id,round,age,my_action,opponent_action,payoff,punished,punished_by_opponent\n",
+        )?;
+
+        let mut line = String::new();
+        for agent in agents {
+            for record in agent.history.records() {
+                line.clear();
+                let _ = writeln!(
+                    line,
+                    "{},{},{},{},{:?},{:?},{},{},{}",
+                    agent.id,
+                    record.opponent_id,
+                    record.round,
+                    record.age,
+                    record.my_action,
+                    record.opponent_action,
+                    record.payoff,
+                    record.punished,
+                    record.punished_by_opponent,
+                );
+                writer.write_all(line.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn battle_log_to_csv<'a>(agents: impl IntoIterator<Item = &'a Agent>) -> String {
+        let mut bytes = Vec::new();
+        Self::write_battle_log_csv(&mut bytes, agents).expect("writing to a Vec cannot fail");
+        String::from_utf8(bytes).expect("CSV is valid UTF-8")
+    }
+
+    // [{ agent_id, records: [GameRecord] }]
+    pub fn battle_log_to_json<'a>(
+        agents: impl IntoIterator<Item = &'a Agent>,
+    ) -> Result<String, SerializationError> {
+        let log: Vec<Value> = agents
+            .into_iter()
+            .map(|agent| {
+                json!({
+                    "agent_id": agent.id,
+                    "records": agent.history.records().collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string(&log)?)
+    }
+
+    // "PD2D" + 版数 1 バイトのヘッダ付き MessagePack（JSON より小さく、版数で将来の形式変更を判別できる）
+    pub fn agents_to_binary(agents: &[Agent]) -> Result<Vec<u8>, SerializationError> {
+        PersistenceService::export_agents(agents, PersistenceFormat::MessagePack)
+    }
+
+    pub fn agents_from_binary(bytes: &[u8]) -> Result<Vec<Agent>, SerializationError> {
+        PersistenceService::import_agents(bytes, PersistenceFormat::MessagePack)
+    }
+
+    pub fn stats_history_to_csv<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics> + Copy,
+        options: &ExportOptions,
+    ) -> String {
+        let mut bytes = Vec::new();
+        Self::write_stats_history_csv(&mut bytes, history, options)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(bytes).expect("CSV is valid UTF-8")
+    }
+
+    // stats_history_to_csv と同じ CSV を 1 行ずつ writer に書く
+    pub fn write_stats_history_csv<'a, W: Write + ?Sized>(
+        writer: &mut W,
+        history: impl IntoIterator<Item = &'a SimulationStatistics> + Copy,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let strategies = Self::census_strategies(history);
+        let mut columns = vec!["generation".to_string(), "total_agents".to_string()];
+        columns.extend(strategies.iter().map(|s| format!("{s:?}")));
+        columns.extend(MovementStrategy::ALL.iter().map(|s| s.to_string()));
+        columns.extend([
+            "average_cooperation_rate".to_string(),
+            "average_mobility".to_string(),
+            "average_score".to_string(),
+            "average_fitness".to_string(),
+            "mutation_rate".to_string(),
+            "births".to_string(),
+            "deaths".to_string(),
+            "punishments".to_string(),
+            "punishment_frequency".to_string(),
+            "niche_count".to_string(),
+            "shannon_index".to_string(),
+            "simpson_index".to_string(),
+        ]);
+
+        writeln!(writer, "{}", columns.join(","))?;
+
+        for stats in history {
+            let mut row = vec![stats.generation.to_string(), stats.total_agents.to_string()];
+            row.extend(
+                strategies
+                    .iter()
+                    .map(|s| stats.strategy_counts.get(s).unwrap_or(&0).to_string()),
+            );
+            row.extend(MovementStrategy::ALL.iter().map(|s| {
+                stats
+                    .movement_strategy_counts
+                    .get(&s.to_string())
+                    .unwrap_or(&0)
+                    .to_string()
+            }));
+            row.extend([
+                options.format_float(stats.average_cooperation_rate),
+                options.format_float(stats.average_mobility),
+                options.format_float(stats.average_score),
+                options.format_float(stats.average_fitness),
+                options.format_float(stats.mutation_rate),
+                stats.births.to_string(),
+                stats.deaths.to_string(),
+                stats.punishments.to_string(),
+                options.format_float(stats.punishment_frequency),
+                stats.niche_count.to_string(),
+                options.format_float(stats.shannon_index),
+                options.format_float(stats.simpson_index),
+            ]);
+
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn stats_history_to_json<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics> + Copy,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let strategies = Self::census_strategies(history);
+        let records: Vec<Value> = history
+            .into_iter()
+            .map(|stats| Self::stats_to_json_value(stats, &strategies, options))
+            .collect();
+
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    // 1 世代の統計を 1 行の JSON にして改行を付ける（実行中に世代ごとに書き足せる JSON Lines）
+    pub fn write_stats_json_line<W: Write + ?Sized>(
+        writer: &mut W,
+        stats: &SimulationStatistics,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let strategies = Self::census_strategies(std::slice::from_ref(stats));
+        serde_json::to_writer(
+            &mut *writer,
+            &Self::stats_to_json_value(stats, &strategies, options),
+        )?;
+        writer.write_all(b"\n")
+    }
+
+    pub fn stats_history_to_jsonl<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics>,
+        options: &ExportOptions,
+    ) -> String {
+        let mut bytes = Vec::new();
+        for stats in history {
+            Self::write_stats_json_line(&mut bytes, stats, options)
+                .expect("writing to a Vec cannot fail");
+        }
+        String::from_utf8(bytes).expect("JSON is valid UTF-8")
+    }
+
+    // 1 行 1 体（PersistenceService の JSON と同じ個体の表現）
+    pub fn write_agents_jsonl<'a, W: Write + ?Sized>(
+        writer: &mut W,
+        agents: impl IntoIterator<Item = &'a Agent>,
+    ) -> io::Result<()> {
+        for agent in agents {
+            serde_json::to_writer(&mut *writer, agent)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    // JSON の配列を 1 要素 1 行に並べ直す（配列でなければそれだけを 1 行にする）
+    pub fn json_to_lines(json: &str) -> Result<String, SerializationError> {
+        let value: Value = serde_json::from_str(json)?;
+        let items = match value {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        let mut lines = String::new();
+        for item in items {
+            lines.push_str(&serde_json::to_string(&item)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    fn stats_to_json_value(
+        stats: &SimulationStatistics,
+        strategies: &[StrategyType],
+        options: &ExportOptions,
+    ) -> Value {
+        let strategy_counts: serde_json::Map<String, Value> = strategies
+            .iter()
+            .map(|s| {
+                (
+                    format!("{s:?}"),
+                    json!(stats.strategy_counts.get(s).unwrap_or(&0)),
+                )
+            })
+            .collect();
+        let islands: Vec<Value> = stats
+            .island_statistics
+            .iter()
+            .map(|island| {
+                json!({
+                    "island": island.island,
+                    "total_agents": island.total_agents,
+                    "average_cooperation_rate": options.round_float(island.average_cooperation_rate),
+                    "average_score": options.round_float(island.average_score),
+                })
+            })
+            .collect();
+        let tags: Vec<Value> = stats
+            .tag_statistics
+            .iter()
+            .map(|tag| {
+                json!({
+                    "tag": tag.tag,
+                    "total_agents": tag.total_agents,
+                    "average_cooperation_rate": options.round_float(tag.average_cooperation_rate),
+                    "average_in_group_bias": options.round_float(tag.average_in_group_bias),
+                })
+            })
+            .collect();
+
+        json!({
+            "generation": stats.generation,
+            "total_agents": stats.total_agents,
+            "strategy_counts": strategy_counts,
+            "movement_strategy_counts": stats.movement_strategy_counts,
+            "average_cooperation_rate": options.round_float(stats.average_cooperation_rate),
+            "average_mobility": options.round_float(stats.average_mobility),
+            "average_score": options.round_float(stats.average_score),
+            "average_fitness": options.round_float(stats.average_fitness),
+            "mutation_rate": options.round_float(stats.mutation_rate),
+            "births": stats.births,
+            "deaths": stats.deaths,
+            "punishments": stats.punishments,
+            "punishment_frequency": options.round_float(stats.punishment_frequency),
+            "niche_count": stats.niche_count,
+            "shannon_index": options.round_float(stats.shannon_index),
+            "simpson_index": options.round_float(stats.simpson_index),
+            "island_statistics": islands,
+            "tag_statistics": tags,
+        })
+    }
+
+    // 1 行がグリッドの 1 行。NaN（空きセル）は空欄にする
+    pub fn heatmap_to_csv(values: &[f32], width: usize, options: &ExportOptions) -> String {
+        let mut csv = String::new();
+        for row in values.chunks(width.max(1)) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|value| {
+                    if value.is_nan() {
+                        String::new()
+                    } else {
+                        options.format_float(*value as f64)
+                    }
+                })
+                .collect();
+            csv.push_str(&cells.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // クラスタサイズ分布は JSON でのみ出力する
+    pub fn spatial_history_to_csv<'a>(
+        history: impl IntoIterator<Item = &'a SpatialMetrics>,
+        options: &ExportOptions,
+    ) -> String {
+        let mut csv = String::from(
+            "generation,cooperator_count,cluster_count,largest_cluster,mean_cluster_size,morans_i\n",
+        );
+        for metrics in history {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                metrics.generation,
+                metrics.cooperator_count,
+                metrics.cluster_count,
+                metrics.largest_cluster,
+                options.format_float(metrics.mean_cluster_size),
+                options.format_float(metrics.morans_i),
+            ));
+        }
+        csv
+    }
+
+    pub fn spatial_history_to_json<'a>(
+        history: impl IntoIterator<Item = &'a SpatialMetrics>,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let records: Vec<Value> = history
+            .into_iter()
+            .map(|metrics| {
+                json!({
+                    "generation": metrics.generation,
+                    "cooperator_count": metrics.cooperator_count,
+                    "cluster_count": metrics.cluster_count,
+                    "largest_cluster": metrics.largest_cluster,
+                    "mean_cluster_size": options.round_float(metrics.mean_cluster_size),
+                    "cluster_size_distribution": metrics.cluster_size_distribution,
+                    "morans_i": options.round_float(metrics.morans_i),
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    // 次数分布は JSON でのみ出力する
+    pub fn network_history_to_csv<'a>(
+        history: impl IntoIterator<Item = &'a NetworkMetrics>,
+        options: &ExportOptions,
+    ) -> String {
+        let mut csv = String::from(
+            "generation,edge_count,mean_degree,degree_assortativity,cooperation_assortativity,rewired_links\n",
+        );
+        for metrics in history {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                metrics.generation,
+                metrics.edge_count,
+                options.format_float(metrics.mean_degree),
+                options.format_float(metrics.degree_assortativity),
+                options.format_float(metrics.cooperation_assortativity),
+                metrics.rewired_links,
+            ));
+        }
+        csv
+    }
+
+    // 1 行 1 辺（source から target への向きのある辺）。Gephi の辺の表としてそのまま読み込める
+    pub fn interaction_network_to_csv(
+        network: &InteractionNetwork,
+        options: &ExportOptions,
+    ) -> String {
+        let mut csv = String::from(
+            "source,target,games,cooperation_rate,mutual_cooperation_rate,average_payoff\n",
+        );
+        for edge in &network.edges {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                edge.source,
+                edge.target,
+                edge.games,
+                options.format_float(edge.cooperation_rate),
+                options.format_float(edge.mutual_cooperation_rate),
+                options.format_float(edge.average_payoff),
+            );
+        }
+        csv
+    }
+
+    // { nodes: [...], edges: [...] }
+    pub fn interaction_network_to_json(
+        network: &InteractionNetwork,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let nodes: Vec<Value> = network
+            .nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "id": node.id,
+                    "x": node.x,
+                    "y": node.y,
+                    "strategy": node.strategy,
+                    "cooperation_rate": options.round_float(node.cooperation_rate),
+                    "score": node.score,
+                })
+            })
+            .collect();
+        let edges: Vec<Value> = network
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "source": edge.source,
+                    "target": edge.target,
+                    "games": edge.games,
+                    "cooperation_rate": options.round_float(edge.cooperation_rate),
+                    "mutual_cooperation_rate": options.round_float(edge.mutual_cooperation_rate),
+                    "average_payoff": options.round_float(edge.average_payoff),
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string(
+            &json!({ "nodes": nodes, "edges": edges }),
+        )?)
+    }
+
+    // 有向グラフの GraphML。辺の weight は対戦数
+    pub fn interaction_network_to_graphml(
+        network: &InteractionNetwork,
+        options: &ExportOptions,
+    ) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        );
+        for (id, domain, name, kind) in [
+            ("x", "node", "x", "int"),
+            ("y", "node", "y", "int"),
+            ("strategy", "node", "strategy", "string"),
+            (
+                "node_cooperation_rate",
+                "node",
+                "cooperation_rate",
+                "double",
+            ),
+            ("score", "node", "score", "int"),
+            ("weight", "edge", "weight", "int"),
+            ("cooperation_rate", "edge", "cooperation_rate", "double"),
+            (
+                "mutual_cooperation_rate",
+                "edge",
+                "mutual_cooperation_rate",
+                "double",
+            ),
+            ("average_payoff", "edge", "average_payoff", "double"),
+        ] {
+            let _ = writeln!(
+                xml,
+                "<key id=\"{id}\" for=\"{domain}\" attr.name=\"{name}\" attr.type=\"{kind}\"/>"
+            );
+        }
+        xml.push_str("<graph id=\"interactions\" edgedefault=\"directed\">\n");
+        for node in &network.nodes {
+            let _ = writeln!(
+                xml,
+                "<node id=\"{}\"><data key=\"x\">{}</data><data key=\"y\">{}</data>\
+                 <data key=\"strategy\">{:?}</data><data key=\"node_cooperation_rate\">{}</data>\
+                 <data key=\"score\">{}</data></node>",
+                node.id,
+                node.x,
+                node.y,
+                node.strategy,
+                options.format_float(node.cooperation_rate),
+                node.score,
+            );
+        }
+        for edge in &network.edges {
+            let _ = writeln!(
+                xml,
+                "<edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data>\
+                 <data key=\"cooperation_rate\">{}</data><data key=\"mutual_cooperation_rate\">{}</data>\
+                 <data key=\"average_payoff\">{}</data></edge>",
+                edge.source,
+                edge.target,
+                edge.games,
+                options.format_float(edge.cooperation_rate),
+                options.format_float(edge.mutual_cooperation_rate),
+                options.format_float(edge.average_payoff),
+            );
+        }
+        xml.push_str("</graph>\n</graphml>\n");
+        xml
+    }
+
+    pub fn network_history_to_json<'a>(
+        history: impl IntoIterator<Item = &'a NetworkMetrics>,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let records: Vec<Value> = history
+            .into_iter()
+            .map(|metrics| {
+                json!({
+                    "generation": metrics.generation,
+                    "edge_count": metrics.edge_count,
+                    "mean_degree": options.round_float(metrics.mean_degree),
+                    "degree_distribution": metrics.degree_distribution,
+                    "degree_assortativity": options.round_float(metrics.degree_assortativity),
+                    "cooperation_assortativity": options.round_float(metrics.cooperation_assortativity),
+                    "rewired_links": metrics.rewired_links,
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    // 1 行 1 世代。処理時間はミリ秒
+    pub fn generation_records_to_csv(
+        records: &[GenerationRecord],
+        options: &ExportOptions,
+    ) -> String {
+        let mut csv = String::from(
+            "generation,population,cooperation_rate,fitness_min,fitness_max,fitness_mean,fitness_sd,strategy_entropy,strategy_richness,genetic_diversity,phenotypic_diversity,spatial_diversity,behavioral_diversity,steps_ms,battles_ms,movement_ms,stats_ms,evolution_ms,selection_ms,crossover_ms,mutation_ms,total_ms,memory_mb\n",
+        );
+        for record in records {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+                record.generation,
+                record.population,
+                options.format_float(record.average_cooperation_rate),
+                options.format_float(record.fitness.min),
+                options.format_float(record.fitness.max),
+                options.format_float(record.fitness.mean),
+                options.format_float(record.fitness.std_dev),
+                options.format_float(record.diversity.strategy_entropy),
+                record.diversity.strategy_richness,
+                options.format_float(record.diversity.genetic_diversity),
+                options.format_float(record.diversity.phenotypic_diversity),
+                options.format_float(record.diversity.spatial_diversity),
+                options.format_float(record.diversity.behavioral_diversity),
+                options.format_float(record.performance.steps_ms),
+                options.format_float(record.performance.battles_ms),
+                options.format_float(record.performance.movement_ms),
+                options.format_float(record.performance.stats_ms),
+                options.format_float(record.performance.evolution_ms),
+                options.format_float(record.performance.selection_ms),
+                options.format_float(record.performance.crossover_ms),
+                options.format_float(record.performance.mutation_ms),
+                options.format_float(record.performance.total_ms),
+                options.format_float(record.memory_usage_mb),
+            ));
+        }
+        csv
+    }
+
+    pub fn generation_records_to_json(
+        records: &[GenerationRecord],
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let records: Vec<Value> = records
+            .iter()
+            .map(|record| {
+                json!({
+                    "generation": record.generation,
+                    "population": record.population,
+                    "cooperation_rate": options.round_float(record.average_cooperation_rate),
+                    "fitness": {
+                        "min": options.round_float(record.fitness.min),
+                        "max": options.round_float(record.fitness.max),
+                        "mean": options.round_float(record.fitness.mean),
+                        "std_dev": options.round_float(record.fitness.std_dev),
+                    },
+                    "diversity": {
+                        "strategy_entropy": options.round_float(record.diversity.strategy_entropy),
+                        "strategy_richness": record.diversity.strategy_richness,
+                        "genetic_diversity": options.round_float(record.diversity.genetic_diversity),
+                        "phenotypic_diversity": options.round_float(record.diversity.phenotypic_diversity),
+                        "spatial_diversity": options.round_float(record.diversity.spatial_diversity),
+                        "behavioral_diversity": options.round_float(record.diversity.behavioral_diversity),
+                    },
+                    "performance": Self::performance_to_json(&record.performance, options),
+                    "memory_mb": options.round_float(record.memory_usage_mb),
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    pub fn performance_report_to_json(
+        report: &PerformanceReport,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        Ok(serde_json::to_string(&json!({
+            "generations": report.generations,
+            "total": Self::performance_to_json(&report.total, options),
+            "average": Self::performance_to_json(&report.average, options),
+            "latest": Self::performance_to_json(&report.latest, options),
+        }))?)
+    }
+
+    fn performance_to_json(metrics: &PerformanceMetrics, options: &ExportOptions) -> Value {
+        json!({
+            "steps_ms": options.round_float(metrics.steps_ms),
+            "battles_ms": options.round_float(metrics.battles_ms),
+            "movement_ms": options.round_float(metrics.movement_ms),
+            "stats_ms": options.round_float(metrics.stats_ms),
+            "evolution_ms": options.round_float(metrics.evolution_ms),
+            "selection_ms": options.round_float(metrics.selection_ms),
+            "crossover_ms": options.round_float(metrics.crossover_ms),
+            "mutation_ms": options.round_float(metrics.mutation_ms),
+            "total_ms": options.round_float(metrics.total_ms),
+        })
+    }
+
+    // 1 行が前線上の 1 体。目的の列は履歴中に現れたものを Objective::ALL の順に並べ、その世代で使っていない目的は空欄
+    pub fn pareto_history_to_csv<'a>(
+        history: impl IntoIterator<Item = &'a ParetoFront> + Copy,
+        options: &ExportOptions,
+    ) -> String {
+        let objectives: Vec<Objective> = Objective::ALL
+            .into_iter()
+            .filter(|objective| {
+                history
+                    .into_iter()
+                    .any(|front| front.objectives.contains(objective))
+            })
+            .collect();
+        let mut csv = String::from("generation,agent_id");
+        for objective in &objectives {
+            csv.push(',');
+            csv.push_str(objective.name());
+        }
+        csv.push('\n');
+
+        for front in history {
+            for member in &front.members {
+                let mut row = vec![front.generation.to_string(), member.id.to_string()];
+                row.extend(objectives.iter().map(|objective| {
+                    front
+                        .objectives
+                        .iter()
+                        .position(|used| used == objective)
+                        .map(|index| options.format_float(member.values[index]))
+                        .unwrap_or_default()
+                }));
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+
+    pub fn pareto_front_to_json(
+        front: &ParetoFront,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        Ok(serde_json::to_string(&Self::pareto_front_value(
+            front, options,
+        ))?)
+    }
+
+    pub fn pareto_history_to_json<'a>(
+        history: impl IntoIterator<Item = &'a ParetoFront>,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let records: Vec<Value> = history
+            .into_iter()
+            .map(|front| Self::pareto_front_value(front, options))
+            .collect();
+
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    // 追跡した個体の軌跡。1 行が 1 個体の 1 世代（個体 ID 順、世代順）
+    pub fn trajectories_to_csv(
+        trajectories: &BTreeMap<Uuid, Vec<TrajectoryPoint>>,
+        options: &ExportOptions,
+    ) -> String {
+        let mut csv =
+            String::from("agent_id,generation,x,y,strategy,score,energy,cooperation_rate\n");
+        for (id, trajectory) in trajectories {
+            for point in trajectory {
+                csv.push_str(&format!(
+                    "{},{},{},{},{:?},{},{},{}\n",
+                    id,
+                    point.generation,
+                    point.position.x,
+                    point.position.y,
+                    point.strategy,
+                    point.score,
+                    options.format_float(point.energy),
+                    options.format_float(point.cooperation_rate),
+                ));
+            }
+        }
+        csv
+    }
+
+    pub fn trajectory_to_json(
+        trajectory: &[TrajectoryPoint],
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        Ok(serde_json::to_string(&Self::trajectory_value(
+            trajectory, options,
+        ))?)
+    }
+
+    // { 個体 ID: [軌跡] }
+    pub fn trajectories_to_json(
+        trajectories: &BTreeMap<Uuid, Vec<TrajectoryPoint>>,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let records: serde_json::Map<String, Value> = trajectories
+            .iter()
+            .map(|(id, trajectory)| (id.to_string(), Self::trajectory_value(trajectory, options)))
+            .collect();
+        Ok(serde_json::to_string(&records)?)
+    }
+
+    fn trajectory_value(trajectory: &[TrajectoryPoint], options: &ExportOptions) -> Value {
+        trajectory
+            .iter()
+            .map(|point| {
+                json!({
+                    "generation": point.generation,
+                    "x": point.position.x,
+                    "y": point.position.y,
+                    "strategy": point.strategy,
+                    "score": point.score,
+                    "energy": options.round_float(point.energy),
+                    "cooperation_rate": options.round_float(point.cooperation_rate),
+                })
+            })
+            .collect()
+    }
+
+    // { generation, members: [{ id, values: { 目的名: 値 } }] }
+    fn pareto_front_value(front: &ParetoFront, options: &ExportOptions) -> Value {
+        let members: Vec<Value> = front
+            .members
+            .iter()
+            .map(|member| {
+                let values: serde_json::Map<String, Value> = front
+                    .objectives
+                    .iter()
+                    .zip(&member.values)
+                    .map(|(objective, value)| {
+                        (
+                            objective.name().to_string(),
+                            json!(options.round_float(*value)),
+                        )
+                    })
+                    .collect();
+                json!({ "id": member.id, "values": values })
+            })
+            .collect();
+        json!({ "generation": front.generation, "members": members })
+    }
+
+    // 1 行 1 組み合わせの整然データ。パラメータの列は最初の行の並びに従う
+    pub fn experiment_to_csv(rows: &[ExperimentRow], options: &ExportOptions) -> String {
+        let mut header: Vec<String> = rows
+            .first()
+            .map(|row| {
+                row.parameters
+                    .iter()
+                    .map(|(parameter, _)| parameter.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        header.push("replicates".to_string());
+        header.extend(Self::summary_columns("cooperation"));
+        header.extend(Self::summary_columns("score"));
+        let mut csv = header.join(",");
+        csv.push('\n');
+
+        for row in rows {
+            let mut fields: Vec<String> = row
+                .parameters
+                .iter()
+                .map(|(_, value)| options.format_float(*value))
+                .collect();
+            fields.push(row.cooperation.count.to_string());
+            fields.push(Self::summary_to_csv(&row.cooperation, options));
+            fields.push(Self::summary_to_csv(&row.score, options));
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn aggregated_to_csv(result: &AggregatedResult, options: &ExportOptions) -> String {
+        let mut header = vec!["generation".to_string(), "replicates".to_string()];
+        header.extend(Self::summary_columns("cooperation"));
+        header.extend(Self::summary_columns("fitness"));
+        let mut csv = header.join(",");
+        csv.push('\n');
+
+        for summary in &result.generations {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                summary.generation,
+                summary.cooperation.count,
+                Self::summary_to_csv(&summary.cooperation, options),
+                Self::summary_to_csv(&summary.fitness, options),
+            ));
+        }
+        csv
+    }
+
+    pub fn aggregated_to_json(
+        result: &AggregatedResult,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let generations: Vec<Value> = result
+            .generations
+            .iter()
+            .map(|generation| {
+                json!({
+                    "generation": generation.generation,
+                    "cooperation": Self::summary_to_json(&generation.cooperation, options),
+                    "fitness": Self::summary_to_json(&generation.fitness, options),
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string(&json!({
+            "replicates": result.replicates,
+            "seeds": result.seeds,
+            "generations": generations,
+        }))?)
+    }
+
+    // 1 行 1 世代。指標ごとに baseline・candidate・差（candidate - baseline）の列が並ぶ
+    pub fn comparison_to_csv(comparison: &ResultComparison, options: &ExportOptions) -> String {
+        let mut header = vec!["generation".to_string()];
+        for metric in ComparisonMetric::ALL {
+            for column in ["baseline", "candidate", "delta"] {
+                header.push(format!("{}_{column}", metric.name()));
+            }
+        }
+        let mut csv = header.join(",");
+        csv.push('\n');
+
+        for generation in &comparison.generations {
+            let mut fields = vec![generation.generation.to_string()];
+            for metric in ComparisonMetric::ALL {
+                let delta = generation.metric(metric);
+                fields.extend(
+                    [delta.baseline, delta.candidate, delta.delta]
+                        .map(|value| options.format_float(value)),
+                );
+            }
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // 世代ごとの差に加えて、最終分布の要約・効果量・検定（実行したときだけ）を含む
+    pub fn comparison_to_json(
+        comparison: &ResultComparison,
+        options: &ExportOptions,
+    ) -> Result<String, SerializationError> {
+        let generations: Vec<Value> = comparison
+            .generations
+            .iter()
+            .map(|generation| {
+                let mut row = json!({ "generation": generation.generation });
+                for metric in ComparisonMetric::ALL {
+                    let delta = generation.metric(metric);
+                    row[metric.name()] = json!({
+                        "baseline": options.round_float(delta.baseline),
+                        "candidate": options.round_float(delta.candidate),
+                        "delta": options.round_float(delta.delta),
+                    });
+                }
+                row
+            })
+            .collect();
+        let metrics: Vec<Value> = comparison
+            .metrics
+            .iter()
+            .map(|metric| {
+                let tests = metric.tests.map(|tests| {
+                    json!({
+                        "welch_t": options.round_float(tests.welch_t),
+                        "welch_df": options.round_float(tests.welch_df),
+                        "welch_p_value": options.round_float(tests.welch_p_value),
+                        "mann_whitney_u": options.round_float(tests.mann_whitney_u),
+                        "mann_whitney_p_value": options.round_float(tests.mann_whitney_p_value),
+                    })
+                });
+                json!({
+                    "metric": metric.metric.name(),
+                    "baseline": Self::summary_to_json(&metric.baseline, options),
+                    "candidate": Self::summary_to_json(&metric.candidate, options),
+                    "mean_difference": options.round_float(metric.mean_difference),
+                    "cohens_d": options.round_float(metric.cohens_d),
+                    "cliffs_delta": options.round_float(metric.cliffs_delta),
+                    "tests": tests,
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string(&json!({
+            "window": comparison.window,
+            "generations": generations,
+            "metrics": metrics,
+        }))?)
+    }
+
+    fn summary_to_json(summary: &Summary, options: &ExportOptions) -> Value {
+        json!({
+            "count": summary.count,
+            "mean": options.round_float(summary.mean),
+            "std_dev": options.round_float(summary.std_dev),
+            "ci_low": options.round_float(summary.ci_low),
+            "ci_high": options.round_float(summary.ci_high),
+        })
+    }
+
+    fn summary_columns(metric: &str) -> Vec<String> {
+        ["mean", "sd", "ci_low", "ci_high"]
+            .iter()
+            .map(|statistic| format!("{metric}_{statistic}"))
+            .collect()
+    }
+
+    fn summary_to_csv(summary: &Summary, options: &ExportOptions) -> String {
+        [
+            summary.mean,
+            summary.std_dev,
+            summary.ci_low,
+            summary.ci_high,
+        ]
+        .map(|value| options.format_float(value))
+        .join(",")
+    }
+
+    // 組み込み戦略に、履歴中に一度でも現れた利用者定義の戦略を ID 順で加えた列
+    pub(crate) fn census_strategies<'a>(
+        history: impl IntoIterator<Item = &'a SimulationStatistics>,
+    ) -> Vec<StrategyType> {
+        let mut strategies = StrategyType::ALL.to_vec();
+        for stats in history {
+            for strategy in stats.strategy_counts.keys() {
+                if !strategies.contains(strategy) {
+                    strategies.push(*strategy);
+                }
+            }
+        }
+        strategies.sort_by_key(|strategy| strategy.id());
+        strategies
+    }
+
+    // ビンの度数は整数なので丸めずにそのまま出す
+    pub fn histograms_to_json<'a>(
+        history: impl IntoIterator<Item = &'a DetailedStatistics>,
+    ) -> Result<String, SerializationError> {
+        let history: Vec<&DetailedStatistics> = history.into_iter().collect();
+        Ok(serde_json::to_string(&history)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::analysis::{
+        GenerationSummary, ResultComparisonService, SweepParameter,
+    };
+    use crate::application::evolution::ParetoMember;
+    use crate::application::simulation::SimulationResult;
+    use crate::domain::agent::{Action, Position};
+    use std::collections::HashMap;
+
+    fn decimals(field: &str) -> usize {
+        field.split('.').nth(1).map(|d| d.len()).unwrap_or(0)
+    }
+
+    #[test]
+    fn test_agents_to_csv_uses_configured_precision() {
+        // Arrange
+        let agent = Agent::new(
+            Position::new(1, 2),
+            StrategyType::TitForTat,
+            1.0 / 3.0,
+            MovementStrategy::Explorer,
+        );
+        let options = ExportOptions::new().with_precision(3);
+
+        // Act
+        let csv = SerializationService::agents_to_csv(&[agent], &options);
+
+        // Assert
+        let row: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+        assert_eq!(row[5], "0.333");
+        assert_eq!(row[7], "0.500");
+        assert_eq!(decimals(row[5]), 3);
+        assert_eq!(decimals(row[7]), 3);
+    }
+
+    #[test]
+    fn test_chunked_writer_splits_csv_on_line_boundaries() {
+        // Arrange
+        let agents: Vec<Agent> = (0..50)
+            .map(|i| {
+                Agent::new(
+                    Position::new(i, 0),
+                    StrategyType::TitForTat,
+                    0.5,
+                    MovementStrategy::Explorer,
+                )
+            })
+            .collect();
+        let options = ExportOptions::default();
+        let mut chunks = Vec::new();
+        let mut writer = ChunkedWriter::new(256, |chunk: &str| {
+            chunks.push(chunk.to_string());
+            true
+        });
+
+        // Act
+        SerializationService::write_agents_csv(&mut writer, &agents, &options).unwrap();
+        let count = writer.finish().unwrap();
+
+        // Assert: つなげると agents_to_csv と同じで、どの断片も行の終わりで切れている
+        assert_eq!(count, chunks.len());
+        assert!(count > 1);
+        assert!(chunks.iter().all(|chunk| chunk.ends_with('\n')));
+        assert_eq!(
+            chunks.concat(),
+            SerializationService::agents_to_csv(&agents, &options)
+        );
+    }
+
+    #[test]
+    fn test_chunked_writer_stops_when_sink_declines() {
+        // Arrange
+        let history = vec![SimulationStatistics::new(); 100];
+        let mut received = 0;
+        let mut writer = ChunkedWriter::new(64, |_: &str| {
+            received += 1;
+            false
+        });
+
+        // Act
+        let result = SerializationService::write_stats_history_csv(
+            &mut writer,
+            &history,
+            &ExportOptions::default(),
+        );
+
+        // Assert
+        assert!(result.is_err());
+        assert!(writer.is_cancelled());
+        assert_eq!(writer.chunk_count(), 1);
+        drop(writer);
+        assert_eq!(received, 1);
+    }
+
+    #[test]
+    fn test_battle_log_lists_every_record_of_every_agent() {
+        // Arrange
+        let mut first = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Explorer,
+        );
+        let mut second = Agent::new(
+            Position::new(1, 0),
+            StrategyType::AllDefect,
+            0.5,
+            MovementStrategy::Explorer,
+        );
+        for _ in 0..2 {
+            first
+                .history
+                .add_game(second.id, Action::Cooperate, Action::Defect, 0);
+            second
+                .history
+                .add_game(first.id, Action::Defect, Action::Cooperate, 5);
+        }
+        let agents = [first, second];
+
+        // Act
+        let csv = SerializationService::battle_log_to_csv(&agents);
+        let json = SerializationService::battle_log_to_json(&agents).unwrap();
+
+        // Assert: 2 体 × 2 戦が両者の側から並ぶ
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("agent_id,opponent_id,round"));
+        assert_eq!(
+            lines[2],
+            format!(
+                "{},{},1,0,Cooperate,Defect,0,false,false",
+                agents[0].id, agents[1].id
+            )
+        );
+        let log: Vec<Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(log[1]["agent_id"], agents[1].id.to_string());
+        assert_eq!(log[1]["records"].as_array().unwrap().len(), 2);
+        assert_eq!(log[1]["records"][0]["payoff"], 5);
+    }
+
+    #[test]
+    fn test_interaction_network_formats_share_nodes_and_edges() {
+        // Arrange: 2 体が 1 戦ずつ記録している
+        let mut grid = crate::domain::grid::Grid::new(2, 1);
+        let mut first = Agent::new(
+            Position::new(0, 0),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        let mut second = Agent::new(
+            Position::new(1, 0),
+            StrategyType::AllDefect,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        first.add_game_result(second.id, Action::Cooperate, Action::Defect, 0);
+        second.add_game_result(first.id, Action::Defect, Action::Cooperate, 5);
+        let (first_id, second_id) = (first.id, second.id);
+        grid.add_agent(first).unwrap();
+        grid.add_agent(second).unwrap();
+        let network = InteractionNetwork::from_grid(&grid);
+        let options = ExportOptions::new().with_precision(2);
+
+        // Act
+        let csv = SerializationService::interaction_network_to_csv(&network, &options);
+        let json = SerializationService::interaction_network_to_json(&network, &options).unwrap();
+        let graphml = SerializationService::interaction_network_to_graphml(&network, &options);
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], format!("{first_id},{second_id},1,1.00,0.00,0.00"));
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(value["edges"][1]["source"], second_id.to_string());
+        assert_eq!(value["edges"][1]["average_payoff"], 5.0);
+        assert!(graphml.contains("edgedefault=\"directed\""));
+        assert_eq!(graphml.matches("<node ").count(), 2);
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+        assert!(graphml.contains("<data key=\"strategy\">AllDefect</data>"));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+    }
+
+    #[test]
+    fn test_stats_history_to_csv_uses_configured_precision() {
+        // Arrange
+        let mut strategy_counts = HashMap::new();
+        strategy_counts.insert(StrategyType::AllDefect, 2);
+        let stats = SimulationStatistics {
+            generation: 3,
+            total_agents: 2,
+            strategy_counts,
+            movement_strategy_counts: HashMap::new(),
+            average_cooperation_rate: 2.0 / 3.0,
+            average_mobility: 0.1,
+            average_score: 12.345678,
+            average_fitness: 12.345678,
+            mutation_rate: 0.05,
+            island_statistics: Vec::new(),
+            tag_statistics: Vec::new(),
+            births: 0,
+            deaths: 0,
+            punishments: 0,
+            punishment_frequency: 0.0,
+            niche_count: 0,
+            shannon_index: 0.0,
+            simpson_index: 0.0,
+        };
+        let options = ExportOptions::new().with_precision(3);
+
+        // Act
+        let csv = SerializationService::stats_history_to_csv(&[stats], &options);
+
+        // Assert
+        let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+        let row: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+        assert_eq!(header.len(), row.len());
+        assert_eq!(row[0], "3");
+        assert_eq!(row[3], "2");
+        assert_eq!(
+            &row[row.len() - 12..],
+            &[
+                "0.667", "0.100", "12.346", "12.346", "0.050", "0", "0", "0", "0.000", "0",
+                "0.000", "0.000"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_history_export_adds_custom_strategy_columns() {
+        // Arrange: 2 世代目にだけ利用者定義の戦略がいる
+        let first = SimulationStatistics::new();
+        let second = SimulationStatistics {
+            generation: 1,
+            strategy_counts: HashMap::from([(StrategyType::Custom(0), 5)]),
+            ..SimulationStatistics::new()
+        };
+        let history = [first, second];
+
+        // Act
+        let csv = SerializationService::stats_history_to_csv(&history, &ExportOptions::new());
+        let json =
+            SerializationService::stats_history_to_json(&history, &ExportOptions::new()).unwrap();
+
+        // Assert
+        let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+        let column = header.iter().position(|c| *c == "Custom(0)").unwrap();
+        let rows: Vec<Vec<&str>> = csv
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').collect())
+            .collect();
+        assert_eq!((rows[0][column], rows[1][column]), ("0", "5"));
+        assert!(json.contains("\"Custom(0)\":5"));
+    }
+
+    #[test]
+    fn test_agents_binary_round_trip_is_smaller_than_json() {
+        // Arrange
+        let agents: Vec<Agent> = (0..20)
+            .map(|i| {
+                Agent::new(
+                    Position::new(i, 0),
+                    StrategyType::Pavlov,
+                    0.25,
+                    MovementStrategy::Social,
+                )
+            })
+            .collect();
+
+        // Act
+        let bytes = SerializationService::agents_to_binary(&agents).unwrap();
+        let restored = SerializationService::agents_from_binary(&bytes).unwrap();
+
+        // Assert
+        let json = PersistenceService::export_agents(&agents, PersistenceFormat::Json).unwrap();
+        assert!(bytes.starts_with(b"PD2D"));
+        assert!(bytes.len() < json.len());
+        assert_eq!(restored.len(), 20);
+        assert_eq!(restored[3].id, agents[3].id);
+        assert!(SerializationService::agents_from_binary(&json).is_err());
+    }
+
+    #[test]
+    fn test_heatmap_to_csv_writes_one_line_per_row() {
+        // Arrange
+        let values = [0.5, f32::NAN, 1.0, 0.25];
+
+        // Act
+        let csv = SerializationService::heatmap_to_csv(
+            &values,
+            2,
+            &ExportOptions::new().with_precision(2),
+        );
+
+        // Assert
+        assert_eq!(csv, "0.50,\n1.00,0.25\n");
+    }
+
+    #[test]
+    fn test_stats_history_to_jsonl_writes_one_object_per_generation() {
+        // Arrange
+        let history: Vec<SimulationStatistics> = (1..=3)
+            .map(|generation| SimulationStatistics {
+                generation,
+                average_score: 1.0 / 3.0,
+                ..SimulationStatistics::new()
+            })
+            .collect();
+        let options = ExportOptions::default();
+
+        // Act
+        let jsonl = SerializationService::stats_history_to_jsonl(&history, &options);
+        let regrouped = SerializationService::json_to_lines(
+            &SerializationService::stats_history_to_json(&history, &options).unwrap(),
+        )
+        .unwrap();
+
+        // Assert: 各行が独立した JSON で、配列の JSON を並べ直したものと同じ
+        let lines: Vec<Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2]["generation"], 3);
+        assert_eq!(lines[0]["average_score"], 0.3333);
+        assert_eq!(jsonl, regrouped);
+    }
+
+    #[test]
+    fn test_stats_history_to_json_rounds_floats() {
+        // Arrange
+        let stats = SimulationStatistics {
+            average_cooperation_rate: 2.0 / 3.0,
+            ..SimulationStatistics::new()
+        };
+        let options = ExportOptions::new().with_precision(3);
+
+        // Act
+        let json = SerializationService::stats_history_to_json(&[stats], &options).unwrap();
+
+        // Assert
+        assert!(json.contains("\"average_cooperation_rate\":0.667"));
+    }
+
+    #[test]
+    fn test_experiment_to_csv_writes_one_tidy_row_per_combination() {
+        // Arrange
+        let row = ExperimentRow {
+            parameters: vec![(SweepParameter::MutationRate, 0.1)],
+            cooperation: Summary::of(&[0.4, 0.6]),
+            score: Summary::of(&[10.0]),
+        };
+
+        // Act
+        let csv = SerializationService::experiment_to_csv(
+            &[row],
+            &ExportOptions::new().with_precision(2),
+        );
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "mutation_rate,replicates,cooperation_mean,cooperation_sd,cooperation_ci_low,cooperation_ci_high,score_mean,score_sd,score_ci_low,score_ci_high"
+        );
+        assert!(lines[1].starts_with("0.10,2,0.50,0.14,"));
+        assert!(lines[1].ends_with(",10.00,0.00,10.00,10.00"));
+    }
+
+    #[test]
+    fn test_aggregated_result_exports_one_row_per_generation() {
+        // Arrange
+        let result = AggregatedResult {
+            replicates: 2,
+            seeds: vec![0, 1],
+            generations: vec![GenerationSummary {
+                generation: 1,
+                cooperation: Summary::of(&[0.25, 0.75]),
+                fitness: Summary::of(&[4.0, 6.0]),
+            }],
+        };
+        let options = ExportOptions::new().with_precision(1);
+
+        // Act
+        let csv = SerializationService::aggregated_to_csv(&result, &options);
+        let json = SerializationService::aggregated_to_json(&result, &options).unwrap();
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("generation,replicates,cooperation_mean"));
+        assert!(lines[0].ends_with("fitness_ci_high"));
+        assert!(lines[1].starts_with("1,2,0.5,0.4,"));
+        assert!(json.contains("\"replicates\":2"));
+        assert!(json.contains("\"mean\":5.0"));
+    }
+
+    #[test]
+    fn test_result_comparison_exports_deltas_and_tests() {
+        // Arrange
+        let result = |rates: &[f64]| {
+            SimulationResult::new(
+                rates
+                    .iter()
+                    .enumerate()
+                    .map(|(generation, rate)| SimulationStatistics {
+                        generation: generation as u32,
+                        average_cooperation_rate: *rate,
+                        ..SimulationStatistics::new()
+                    })
+                    .collect(),
+            )
+        };
+        let comparison = ResultComparisonService::new()
+            .with_significance_tests(true)
+            .compare(&result(&[0.2, 0.4, 0.6]), &result(&[0.5, 0.5, 0.9]));
+        let options = ExportOptions::new().with_precision(2);
+
+        // Act
+        let csv = SerializationService::comparison_to_csv(&comparison, &options);
+        let json = SerializationService::comparison_to_json(&comparison, &options).unwrap();
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("generation,cooperation_baseline,cooperation_candidate"));
+        assert!(lines[0].ends_with("diversity_delta"));
+        assert!(lines[1].starts_with("0,0.20,0.50,0.30,"));
+        assert!(json.contains("\"metric\":\"cooperation\""));
+        assert!(json.contains("\"mean_difference\":0.23"));
+        assert!(json.contains("\"welch_p_value\""));
+    }
+
+    #[test]
+    fn test_trajectories_export_one_row_per_agent_generation() {
+        // Arrange
+        let id = Uuid::nil();
+        let point = |generation, score| TrajectoryPoint {
+            generation,
+            position: Position::new(2, 3),
+            strategy: StrategyType::Pavlov,
+            score,
+            energy: 1.25,
+            cooperation_rate: 0.5,
+        };
+        let trajectories = BTreeMap::from([(id, vec![point(0, 10), point(1, 14)])]);
+        let options = ExportOptions::new().with_precision(1);
+
+        // Act
+        let csv = SerializationService::trajectories_to_csv(&trajectories, &options);
+        let json = SerializationService::trajectories_to_json(&trajectories, &options).unwrap();
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], format!("{id},1,2,3,Pavlov,14,1.2,0.5"));
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[id.to_string()][1]["score"], 14);
+    }
+
+    #[test]
+    fn test_pareto_history_to_csv_leaves_unused_objectives_blank() {
+        // Arrange: 2 世代目から目的を増やした
+        let id = Uuid::nil();
+        let history = vec![
+            ParetoFront {
+                generation: 0,
+                objectives: vec![Objective::Score],
+                members: vec![ParetoMember {
+                    id,
+                    values: vec![12.0],
+                }],
+            },
+            ParetoFront {
+                generation: 1,
+                objectives: vec![Objective::Cooperation, Objective::Score],
+                members: vec![ParetoMember {
+                    id,
+                    values: vec![0.5, 8.0],
+                }],
+            },
+        ];
+
+        // Act
+        let csv = SerializationService::pareto_history_to_csv(
+            &history,
+            &ExportOptions::new().with_precision(1),
+        );
+
+        // Assert
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "generation,agent_id,score,cooperation");
+        assert_eq!(lines[1], format!("0,{id},12.0,"));
+        assert_eq!(lines[2], format!("1,{id},8.0,0.5"));
+    }
+}
@@ -0,0 +1,293 @@
+use super::{
+    PersistenceError, PersistenceFormat, SchemaMigration, SchemaProbe, SerializationError,
+    SimulationSnapshot, BINARY_FORMAT_VERSION, BINARY_MAGIC, SNAPSHOT_MIGRATIONS, SNAPSHOT_VERSION,
+};
+use crate::domain::agent::{Agent, Position};
+use crate::domain::grid::NetworkWorld;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Debug, Clone)]
+pub struct ImportedData {
+    pub format: PersistenceFormat,
+    pub agents: Vec<Agent>,
+}
+
+pub struct PersistenceService;
+
+impl PersistenceService {
+    pub fn export_agents(
+        agents: &[Agent],
+        format: PersistenceFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        Self::encode(agents, format)
+    }
+
+    pub fn import_agents(
+        bytes: &[u8],
+        format: PersistenceFormat,
+    ) -> Result<Vec<Agent>, SerializationError> {
+        Self::decode(bytes, format)
+    }
+
+    pub fn import_auto(bytes: &[u8]) -> Result<ImportedData, PersistenceError> {
+        let format = PersistenceFormat::detect(bytes).ok_or(PersistenceError::UnknownFormat)?;
+        let agents = Self::import_agents(bytes, format)?;
+
+        Ok(ImportedData { format, agents })
+    }
+
+    pub fn export_snapshot(
+        snapshot: &SimulationSnapshot,
+        format: PersistenceFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        Self::encode(snapshot, format)
+    }
+
+    // 形式は自動判別し、旧版のスナップショットは現在の版へ書き換えてから読む（新しすぎる版は拒否する）
+    pub fn import_snapshot(bytes: &[u8]) -> Result<SimulationSnapshot, PersistenceError> {
+        let format = PersistenceFormat::detect(bytes).ok_or(PersistenceError::UnknownFormat)?;
+        match format {
+            PersistenceFormat::Json => {
+                let document: serde_json::Value = Self::decode(bytes, format)?;
+                let document =
+                    SchemaMigration::upgrade(document, SNAPSHOT_VERSION, &SNAPSHOT_MIGRATIONS)?;
+                Ok(serde_json::from_value(document)?)
+            }
+            // 書き換えはできないので版だけ確かめ、追加されたフィールドは serde の既定値に任せる
+            PersistenceFormat::MessagePack => {
+                let probe: SchemaProbe = Self::decode(bytes, format)?;
+                SchemaMigration::check_supported(
+                    probe.schema_version.unwrap_or(1),
+                    SNAPSHOT_VERSION,
+                )?;
+                let mut snapshot: SimulationSnapshot = Self::decode(bytes, format)?;
+                snapshot.schema_version = SNAPSHOT_VERSION;
+                Ok(snapshot)
+            }
+        }
+    }
+
+    // 1 行 1 辺の "a b" / "a,b"（ノード番号は 0 始まり）。空行と # 以降は読み飛ばす
+    pub fn import_edge_list(text: &str) -> Result<Vec<(usize, usize)>, PersistenceError> {
+        let mut edges = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+            let invalid = || PersistenceError::InvalidEdge {
+                line: index + 1,
+                content: content.to_string(),
+            };
+            let nodes: Vec<&str> = content
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .collect();
+            match nodes[..] {
+                [a, b] => edges.push((
+                    a.parse().map_err(|_| invalid())?,
+                    b.parse().map_err(|_| invalid())?,
+                )),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(edges)
+    }
+
+    pub fn export_edge_list(network: &NetworkWorld) -> String {
+        network
+            .edges()
+            .iter()
+            .map(|(a, b)| format!("{a} {b}\n"))
+            .collect()
+    }
+
+    // 1 行がグリッドの 1 行（y = 0 が先頭）で、'#' のセルが壁。それ以外の文字は通れるセル
+    pub fn import_obstacle_map(text: &str) -> Vec<Position> {
+        text.lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, cell)| cell == '#')
+                    .map(move |(x, _)| Position::new(x, y))
+            })
+            .collect()
+    }
+
+    pub fn export_obstacle_map(width: usize, height: usize, obstacles: &[Position]) -> String {
+        let mut rows = vec![vec!['.'; width]; height];
+        for position in obstacles {
+            if let Some(cell) = rows
+                .get_mut(position.y)
+                .and_then(|row| row.get_mut(position.x))
+            {
+                *cell = '#';
+            }
+        }
+        rows.into_iter()
+            .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+            .collect()
+    }
+
+    fn encode<T: Serialize + ?Sized>(
+        value: &T,
+        format: PersistenceFormat,
+    ) -> Result<Vec<u8>, SerializationError> {
+        match format {
+            PersistenceFormat::Json => Ok(serde_json::to_vec(value)?),
+            PersistenceFormat::MessagePack => {
+                let payload = rmp_serde::to_vec_named(value)?;
+
+                let mut bytes = Vec::with_capacity(BINARY_MAGIC.len() + 1 + payload.len());
+                bytes.extend_from_slice(BINARY_MAGIC);
+                bytes.push(BINARY_FORMAT_VERSION);
+                bytes.extend_from_slice(&payload);
+                Ok(bytes)
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(
+        bytes: &[u8],
+        format: PersistenceFormat,
+    ) -> Result<T, SerializationError> {
+        match format {
+            PersistenceFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            PersistenceFormat::MessagePack => {
+                let header_len = BINARY_MAGIC.len() + 1;
+                if bytes.len() < header_len || !bytes.starts_with(BINARY_MAGIC) {
+                    return Err(SerializationError::MissingBinaryHeader);
+                }
+
+                let version = bytes[BINARY_MAGIC.len()];
+                if version != BINARY_FORMAT_VERSION {
+                    return Err(SerializationError::UnsupportedBinaryVersion(version));
+                }
+
+                Ok(rmp_serde::from_slice(&bytes[header_len..])?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, MovementStrategy, Position, StrategyType};
+
+    fn sample_population() -> Vec<Agent> {
+        let mut cooperator = Agent::new(
+            Position::new(1, 2),
+            StrategyType::TitForTat,
+            0.4,
+            MovementStrategy::Settler,
+        );
+        let defector = Agent::new(
+            Position::new(3, 4),
+            StrategyType::AllDefect,
+            0.8,
+            MovementStrategy::Explorer,
+        );
+        cooperator.add_game_result(defector.id, Action::Cooperate, Action::Defect, 0);
+
+        vec![cooperator, defector]
+    }
+
+    fn summarize(agents: &[Agent]) -> Vec<(uuid::Uuid, Position, StrategyType, i32, f64)> {
+        agents
+            .iter()
+            .map(|agent| {
+                (
+                    agent.id,
+                    agent.position,
+                    agent.strategy,
+                    agent.score,
+                    agent.cooperation_rate(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_auto_reads_both_formats() {
+        // Arrange
+        let population = sample_population();
+        let json = PersistenceService::export_agents(&population, PersistenceFormat::Json).unwrap();
+        let binary =
+            PersistenceService::export_agents(&population, PersistenceFormat::MessagePack).unwrap();
+
+        // Act
+        let from_json = PersistenceService::import_auto(&json).unwrap();
+        let from_binary = PersistenceService::import_auto(&binary).unwrap();
+
+        // Assert
+        assert_eq!(from_json.format, PersistenceFormat::Json);
+        assert_eq!(from_binary.format, PersistenceFormat::MessagePack);
+        assert_eq!(summarize(&from_json.agents), summarize(&population));
+        assert_eq!(summarize(&from_binary.agents), summarize(&population));
+    }
+
+    #[test]
+    fn test_import_auto_rejects_unknown_format() {
+        // Arrange
+        let bytes = b"id,x,y\n";
+
+        // Act
+        let result = PersistenceService::import_auto(bytes);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_edge_list_round_trips_and_reports_bad_line() {
+        // Arrange
+        let text = "# path\n0 1\n1,2\n\n2 3 # tail\n";
+
+        // Act
+        let edges = PersistenceService::import_edge_list(text).unwrap();
+        let network = NetworkWorld::from_edges(4, &edges).unwrap();
+        let error = PersistenceService::import_edge_list("0 1\n1 x\n").unwrap_err();
+
+        // Assert
+        assert_eq!(edges, vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(
+            PersistenceService::export_edge_list(&network),
+            "0 1\n1 2\n2 3\n"
+        );
+        assert_eq!(error.to_string(), "Invalid edge on line 2: \"1 x\"");
+    }
+
+    #[test]
+    fn test_obstacle_map_round_trips() {
+        // Arrange: 真ん中に出入り口のある縦の壁
+        let text = "..#..\n.....\n..#..\n";
+
+        // Act
+        let obstacles = PersistenceService::import_obstacle_map(text);
+
+        // Assert
+        assert_eq!(obstacles, vec![Position::new(2, 0), Position::new(2, 2)]);
+        assert_eq!(
+            PersistenceService::export_obstacle_map(5, 3, &obstacles),
+            text
+        );
+    }
+
+    #[test]
+    fn test_import_agents_rejects_unsupported_binary_version() {
+        // Arrange
+        let mut bytes =
+            PersistenceService::export_agents(&sample_population(), PersistenceFormat::MessagePack)
+                .unwrap();
+        bytes[BINARY_MAGIC.len()] = BINARY_FORMAT_VERSION + 1;
+
+        // Act
+        let result = PersistenceService::import_auto(&bytes);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use super::{MigrationStep, SchemaMigration, SCHEMA_VERSION_FIELD};
+use crate::application::analysis::{NetworkMetrics, SpatialMetrics};
+use crate::application::evolution::ParetoFront;
+use crate::application::simulation::{
+    AgentTracker, DetailedStatistics, EvolutionTracker, ExtinctionEvent, SimulationConfig,
+    SimulationStatistics,
+};
+use crate::domain::agent::Agent;
+use crate::domain::game::ReputationBoard;
+use crate::domain::grid::{NetworkWorld, ResourceField};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+// 2: version を schema_version に改名
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+pub const SNAPSHOT_MIGRATIONS: [MigrationStep; 1] = [snapshot_v1_to_v2];
+
+fn snapshot_v1_to_v2(document: &mut serde_json::Map<String, serde_json::Value>) {
+    SchemaMigration::rename(document, "version", SCHEMA_VERSION_FIELD);
+}
+
+// 実行途中のシミュレーションを再開できるだけの状態一式
+// エージェントは対戦履歴ごと保存し、乱数生成器も内部状態ごと保存する
+// 保存しないのは今の世代の処理時間の途中経過と打ち切りのフラグだけ（復元後は 0 と未要求から始める）
+// 後から足した項目は、古い版のスナップショットでは構築直後の値（実効の突然変異率は設定値）になる
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    // 版 1 の MessagePack は書き換えずに読むため旧名も受け付ける
+    #[serde(alias = "version")]
+    pub schema_version: u32,
+    pub width: usize,
+    pub height: usize,
+    pub generation: u32,
+    pub turn: u32,
+    pub turns_per_generation: u32,
+    pub config: SimulationConfig,
+    pub agents: Vec<Agent>,
+    pub generation_history: Vec<SimulationStatistics>,
+    pub diagnostics: Vec<String>,
+    pub rng: ChaCha12Rng,
+    // つなぎ替えで設定から作り直せなくなったネットワーク（ネットワークがなければ None）
+    #[serde(default)]
+    pub network: Option<NetworkWorld>,
+    // 公開の評判（評判を共有しないときは空）
+    #[serde(default)]
+    pub reputation_board: ReputationBoard,
+    // 保持方針で捨てた対戦記録の累計（各個体の対戦履歴そのものは agents に入っている）
+    #[serde(default)]
+    pub evicted_battle_records: u64,
+    // 適応的突然変異で決めた実効の突然変異率
+    #[serde(default)]
+    pub mutation_rate: Option<f64>,
+    // 世代交代で作る子の数
+    #[serde(default)]
+    pub population_target: Option<usize>,
+    #[serde(default)]
+    pub step_history: Vec<SimulationStatistics>,
+    #[serde(default)]
+    pub detailed_history: Vec<DetailedStatistics>,
+    #[serde(default)]
+    pub spatial_history: Vec<SpatialMetrics>,
+    #[serde(default)]
+    pub pareto_history: Vec<ParetoFront>,
+    #[serde(default)]
+    pub network_history: Vec<NetworkMetrics>,
+    #[serde(default)]
+    pub tracker: Option<AgentTracker>,
+    #[serde(default)]
+    pub evolution_tracker: Option<EvolutionTracker>,
+    // 前の世代の区切りからの出生・死亡・罰・罰の機会・つなぎ替えの数
+    #[serde(default)]
+    pub births: usize,
+    #[serde(default)]
+    pub deaths: usize,
+    #[serde(default)]
+    pub punishments: usize,
+    #[serde(default)]
+    pub punishment_opportunities: usize,
+    #[serde(default)]
+    pub rewired_links: usize,
+    // 直前の世代交代で数えたニッチの数
+    #[serde(default)]
+    pub niche_count: usize,
+    #[serde(default)]
+    pub extinction_events: Vec<ExtinctionEvent>,
+    // 全滅時の方針が Stop で止まっていたか
+    #[serde(default)]
+    pub stopped: bool,
+    // 各セルの資源量（資源を設定していなければ None）
+    #[serde(default)]
+    pub resources: Option<ResourceField>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::evolution::AdaptiveMutation;
+    use crate::application::simulation::{
+        BattleHistoryRetention, SimulationService, TrackingTarget,
+    };
+    use crate::domain::game::PunishmentConfig;
+    use crate::domain::grid::ResourceConfig;
+    use crate::infrastructure::persistence::{
+        PersistenceFormat, PersistenceService, SerializationService,
+    };
+
+    fn positions_and_scores(simulation: &SimulationService) -> Vec<(usize, usize, i32)> {
+        let mut state: Vec<(usize, usize, i32)> = simulation
+            .get_agents()
+            .iter()
+            .map(|agent| (agent.position.x, agent.position.y, agent.score))
+            .collect();
+        state.sort();
+        state
+    }
+
+    #[test]
+    fn test_restored_snapshot_continues_identically() {
+        for format in [PersistenceFormat::Json, PersistenceFormat::MessagePack] {
+            // Arrange
+            let config = SimulationConfig::default().with_seed(3);
+            let mut original = SimulationService::with_config(10, 10, 40, config).unwrap();
+            for _ in 0..150 {
+                original.step();
+            }
+            let bytes =
+                PersistenceService::export_snapshot(&original.save_snapshot(), format).unwrap();
+
+            // Act
+            let snapshot = PersistenceService::import_snapshot(&bytes).unwrap();
+            let mut restored = SimulationService::restore_snapshot(snapshot).unwrap();
+            for _ in 0..60 {
+                original.step();
+                restored.step();
+            }
+
+            // Assert: 乱数状態と対戦履歴まで戻っていれば以降の展開も一致する
+            assert_eq!(restored.get_generation(), original.get_generation());
+            assert_eq!(restored.get_turn(), original.get_turn());
+            assert_eq!(
+                restored.get_generation_history().len(),
+                original.get_generation_history().len()
+            );
+            assert_eq!(
+                positions_and_scores(&restored),
+                positions_and_scores(&original)
+            );
+        }
+    }
+
+    #[test]
+    fn test_save_restore_then_steps_equal_uninterrupted_run() {
+        // Arrange: 実効の突然変異率・罰・資源・追跡・分布と空間の履歴がすべて動く設定で世代の途中まで進める
+        let config = SimulationConfig::default()
+            .with_seed(7)
+            .with_adaptive_mutation(AdaptiveMutation::Diversity {
+                threshold: 1.0,
+                boost: 2.0,
+            })
+            .with_punishment(PunishmentConfig::new(1, 4))
+            .with_resources(ResourceConfig::new(1.0, 0.1, 0.2).unwrap())
+            .with_histograms(4)
+            .with_spatial_metrics(true)
+            .with_agent_tracking(TrackingTarget::TopK(2));
+        let mut original = SimulationService::with_config(8, 8, 30, config).unwrap();
+        original.set_turns_per_generation(5);
+        for _ in 0..13 {
+            original.step();
+        }
+        let bytes = PersistenceService::export_snapshot(
+            &original.save_snapshot(),
+            PersistenceFormat::MessagePack,
+        )
+        .unwrap();
+
+        // Act: 保存した側と復元した側を同じだけ進める
+        let snapshot = PersistenceService::import_snapshot(&bytes).unwrap();
+        let mut restored = SimulationService::restore_snapshot(snapshot).unwrap();
+        for _ in 0..12 {
+            original.step();
+            restored.step();
+        }
+
+        // Assert: 処理時間を除いて、保存される状態がすべて一致する
+        let state = |simulation: &SimulationService| {
+            let mut state = serde_json::to_value(simulation.save_snapshot()).unwrap();
+            state.as_object_mut().unwrap().remove("evolution_tracker");
+            state
+        };
+        let records = |simulation: &SimulationService| {
+            simulation
+                .get_evolution_records()
+                .iter()
+                .map(|record| (record.generation, record.population, record.fitness))
+                .collect::<Vec<_>>()
+        };
+        assert!(original.get_statistics().mutation_rate > 0.01);
+        assert!(!original.get_detailed_history().is_empty());
+        assert!(!original.get_spatial_history().is_empty());
+        assert!(original.get_agent_tracker().is_some());
+        assert_eq!(state(&restored), state(&original));
+        assert_eq!(records(&restored), records(&original));
+    }
+
+    #[test]
+    fn test_snapshot_keeps_full_battle_log() {
+        for format in [PersistenceFormat::Json, PersistenceFormat::MessagePack] {
+            // Arrange: 保持方針を変えて、記録の番号と捨てた件数が既定と違う状態にする
+            let config = SimulationConfig::default()
+                .with_seed(5)
+                .with_battle_history_retention(BattleHistoryRetention::PerAgent(25));
+            let mut original = SimulationService::with_config(8, 8, 30, config).unwrap();
+            for _ in 0..40 {
+                original.step();
+            }
+            let bytes =
+                PersistenceService::export_snapshot(&original.save_snapshot(), format).unwrap();
+
+            // Act
+            let snapshot = PersistenceService::import_snapshot(&bytes).unwrap();
+            let restored = SimulationService::restore_snapshot(snapshot).unwrap();
+
+            // Assert
+            let log = |simulation: &SimulationService| {
+                SerializationService::battle_log_to_csv(
+                    simulation.get_grid().agents_in_position_order(),
+                )
+            };
+            assert!(log(&original).lines().count() > 1);
+            assert_eq!(log(&restored), log(&original));
+            assert_eq!(
+                restored.get_battle_history_usage(),
+                original.get_battle_history_usage()
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_snapshot_version() {
+        // Arrange
+        let simulation = SimulationService::new(5, 5, 5).unwrap();
+        let mut snapshot = simulation.save_snapshot();
+        snapshot.schema_version = SNAPSHOT_VERSION + 1;
+        let bytes =
+            PersistenceService::export_snapshot(&snapshot, PersistenceFormat::Json).unwrap();
+
+        // Act
+        let result = PersistenceService::import_snapshot(&bytes);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_import_upgrades_version_one_snapshot() {
+        // Arrange: 旧版は schema_version ではなく version を持っていた
+        let simulation = SimulationService::new(5, 5, 5).unwrap();
+        let bytes = PersistenceService::export_snapshot(
+            &simulation.save_snapshot(),
+            PersistenceFormat::Json,
+        )
+        .unwrap();
+        let mut document: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let object = document.as_object_mut().unwrap();
+        object.remove(SCHEMA_VERSION_FIELD);
+        object.insert("version".to_string(), serde_json::json!(1));
+        let legacy = serde_json::to_vec(&document).unwrap();
+
+        // Act
+        let snapshot = PersistenceService::import_snapshot(&legacy).unwrap();
+
+        // Assert
+        assert_eq!(snapshot.schema_version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.agents.len(), 5);
+    }
+}
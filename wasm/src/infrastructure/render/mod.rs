@@ -0,0 +1,9 @@
+pub mod palette;
+#[cfg(feature = "render")]
+pub mod service;
+pub mod svg;
+
+pub use palette::*;
+#[cfg(feature = "render")]
+pub use service::*;
+pub use svg::*;
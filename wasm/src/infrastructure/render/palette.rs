@@ -0,0 +1,65 @@
+use crate::domain::agent::{Agent, StrategyType};
+use serde::{Deserialize, Serialize};
+
+// セルを塗り分ける基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    // 協力率 0（赤）〜 1（緑）のグラデーション
+    #[default]
+    Cooperation,
+    // 戦略ごとの色（Web フロントエンドの STRATEGY_COLORS と同じ）
+    Strategy,
+}
+
+impl ColorMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cooperation" => Some(ColorMode::Cooperation),
+            "strategy" => Some(ColorMode::Strategy),
+            _ => None,
+        }
+    }
+
+    pub fn color_of(&self, agent: &Agent) -> [u8; 3] {
+        match self {
+            ColorMode::Cooperation => cooperation_color(agent.cooperation_rate()),
+            ColorMode::Strategy => strategy_color(agent.strategy),
+        }
+    }
+}
+
+// 協力率 0 は DEFECTOR、1 は COOPERATOR で、その間は線形に補間する
+pub fn cooperation_color(rate: f64) -> [u8; 3] {
+    let t = rate.clamp(0.0, 1.0);
+    let channel = |i: usize| {
+        (DEFECTOR[i] as f64 + (COOPERATOR[i] as f64 - DEFECTOR[i] as f64) * t).round() as u8
+    };
+    [channel(0), channel(1), channel(2)]
+}
+
+pub fn strategy_color(strategy: StrategyType) -> [u8; 3] {
+    match strategy {
+        StrategyType::Custom(_) => OTHER,
+        builtin => STRATEGY_COLORS[builtin.id() as usize],
+    }
+}
+
+// Web フロントエンドの canvas と同じ色（RGB）
+pub const BACKGROUND: [u8; 3] = [0xf8, 0xf9, 0xfa];
+pub const OBSTACLE: [u8; 3] = [0x37, 0x41, 0x51];
+// 利用者定義の戦略
+pub const OTHER: [u8; 3] = [0x6b, 0x72, 0x80];
+pub const STRATEGY_COLORS: [[u8; 3]; 9] = [
+    [0x22, 0xc5, 0x5e],
+    [0xef, 0x44, 0x44],
+    [0x3b, 0x82, 0xf6],
+    [0xf5, 0x9e, 0x0b],
+    [0x7c, 0x3a, 0xed],
+    [0x06, 0xb6, 0xd4],
+    [0x63, 0x66, 0xf1],
+    [0xbe, 0x12, 0x3c],
+    [0x84, 0xcc, 0x16],
+];
+pub const DEFECTOR: [u8; 3] = [0xef, 0x44, 0x44];
+pub const COOPERATOR: [u8; 3] = [0x22, 0xc5, 0x5e];
@@ -0,0 +1,304 @@
+use super::{cooperation_color, ColorMode, BACKGROUND, OBSTACLE, OTHER, STRATEGY_COLORS};
+use crate::domain::agent::{Position, StrategyType};
+use crate::domain::grid::Grid;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub color_mode: ColorMode,
+    // 1 セルの一辺のピクセル数
+    pub cell_size: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            color_mode: ColorMode::Cooperation,
+            cell_size: 4,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+}
+
+// パレットの番号で塗った画像（PNG は PLTE 付きのインデックスカラー、GIF はグローバルパレットで書き出す）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+pub struct RenderService;
+
+impl RenderService {
+    // 協力率のグラデーションの段数
+    pub const COOPERATION_LEVELS: usize = 32;
+    // 背景・壁・利用者定義の戦略の 3 色の後に、戦略またはグラデーションの色が並ぶ
+    const FIRST_AGENT_COLOR: u8 = 3;
+    const MAX_DIMENSION: usize = u16::MAX as usize;
+
+    // RGB を 3 バイトずつ並べたパレット
+    pub fn palette(color_mode: ColorMode) -> Vec<u8> {
+        let mut palette = vec![BACKGROUND, OBSTACLE, OTHER];
+        match color_mode {
+            ColorMode::Strategy => palette.extend(STRATEGY_COLORS),
+            ColorMode::Cooperation => palette.extend((0..Self::COOPERATION_LEVELS).map(|level| {
+                cooperation_color(level as f64 / (Self::COOPERATION_LEVELS - 1) as f64)
+            })),
+        }
+        palette.into_iter().flatten().collect()
+    }
+
+    pub fn rasterize(grid: &Grid, options: &RenderOptions) -> Raster {
+        let cell = options.cell_size.max(1);
+        let width = grid.width() * cell;
+        let height = grid.height() * cell;
+        let mut pixels = vec![0; width * height];
+
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let position = Position::new(x, y);
+                let color = if grid.is_obstacle(&position) {
+                    1
+                } else {
+                    match grid.get_agent_at_position(&position) {
+                        None => 0,
+                        Some(agent) => match options.color_mode {
+                            ColorMode::Strategy => match agent.strategy {
+                                StrategyType::Custom(_) => 2,
+                                strategy => Self::FIRST_AGENT_COLOR + strategy.id(),
+                            },
+                            ColorMode::Cooperation => {
+                                let level = (agent.cooperation_rate().clamp(0.0, 1.0)
+                                    * (Self::COOPERATION_LEVELS - 1) as f64)
+                                    .round() as u8;
+                                Self::FIRST_AGENT_COLOR + level
+                            }
+                        },
+                    }
+                };
+                for row in y * cell..(y + 1) * cell {
+                    pixels[row * width + x * cell..row * width + (x + 1) * cell].fill(color);
+                }
+            }
+        }
+
+        Raster {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn grid_to_png(grid: &Grid, options: &RenderOptions) -> Result<Vec<u8>, String> {
+        Self::raster_to_png(&Self::rasterize(grid, options), options.color_mode)
+    }
+
+    pub fn raster_to_png(raster: &Raster, color_mode: ColorMode) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, raster.width as u32, raster.height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(Self::palette(color_mode));
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+        writer
+            .write_image_data(&raster.pixels)
+            .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+        writer
+            .finish()
+            .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+        Ok(bytes)
+    }
+
+    // GIF の寸法は 16 ビットに収まる必要がある
+    fn gif_dimensions(width: usize, height: usize) -> Result<(u16, u16), String> {
+        if width == 0 || height == 0 || width > Self::MAX_DIMENSION || height > Self::MAX_DIMENSION
+        {
+            return Err(format!(
+                "GIF frames must be between 1 and {} pixels per side, got {width}x{height}",
+                Self::MAX_DIMENSION
+            ));
+        }
+        Ok((width as u16, height as u16))
+    }
+}
+
+// 1 世代ごとにグリッドを描いてアニメーション GIF に書き足す（フレームは溜めずにすぐ書き出す）
+pub struct GifRecorder<W: Write> {
+    encoder: gif::Encoder<W>,
+    options: RenderOptions,
+    width: usize,
+    height: usize,
+    // 1 フレームの表示時間（1/100 秒）
+    delay: u16,
+    frames: usize,
+}
+
+impl<W: Write> GifRecorder<W> {
+    pub const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+
+    // grid_width × grid_height のグリッドを描く。無限にループする
+    pub fn new(
+        writer: W,
+        grid_width: usize,
+        grid_height: usize,
+        options: RenderOptions,
+    ) -> Result<Self, String> {
+        let cell = options.cell_size.max(1);
+        let (width, height) = RenderService::gif_dimensions(grid_width * cell, grid_height * cell)?;
+        let mut encoder = gif::Encoder::new(
+            writer,
+            width,
+            height,
+            &RenderService::palette(options.color_mode),
+        )
+        .map_err(|e| format!("Failed to encode GIF: {e}"))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| format!("Failed to encode GIF: {e}"))?;
+        Ok(Self {
+            encoder,
+            options,
+            width: width as usize,
+            height: height as usize,
+            delay: (Self::DEFAULT_FRAME_DELAY_MS / 10) as u16,
+            frames: 0,
+        })
+    }
+
+    pub fn with_frame_delay_ms(mut self, delay_ms: u32) -> Self {
+        self.delay = (delay_ms / 10).clamp(1, u16::MAX as u32) as u16;
+        self
+    }
+
+    pub fn record(&mut self, grid: &Grid) -> Result<(), String> {
+        let raster = RenderService::rasterize(grid, &self.options);
+        if (raster.width, raster.height) != (self.width, self.height) {
+            return Err(format!(
+                "Frame is {}x{} pixels but the GIF is {}x{}",
+                raster.width, raster.height, self.width, self.height
+            ));
+        }
+        let mut frame = gif::Frame::from_indexed_pixels(
+            self.width as u16,
+            self.height as u16,
+            raster.pixels,
+            None,
+        );
+        frame.delay = self.delay;
+        self.encoder
+            .write_frame(&frame)
+            .map_err(|e| format!("Failed to encode GIF: {e}"))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    // 終端を書いて writer を返す
+    pub fn finish(self) -> Result<W, String> {
+        self.encoder
+            .into_inner()
+            .map_err(|e| format!("Failed to encode GIF: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, Agent, MovementStrategy};
+    use crate::infrastructure::render::COOPERATOR;
+    use uuid::Uuid;
+
+    fn grid_with_two_agents() -> Grid {
+        let mut grid = Grid::new(3, 2).with_obstacles(&[Position::new(2, 1)]);
+        let mut cooperator = Agent::new(
+            Position::new(0, 0),
+            StrategyType::AllCooperate,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        cooperator
+            .history
+            .add_game(Uuid::new_v4(), Action::Cooperate, Action::Cooperate, 3);
+        grid.add_agent(cooperator).unwrap();
+        grid.add_agent(Agent::new(
+            Position::new(1, 1),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Settler,
+        ))
+        .unwrap();
+        grid
+    }
+
+    #[test]
+    fn test_rasterize_colors_cells_by_mode() {
+        // Arrange
+        let grid = grid_with_two_agents();
+        let options = RenderOptions::new().with_cell_size(2);
+
+        // Act
+        let cooperation = RenderService::rasterize(&grid, &options);
+        let strategy =
+            RenderService::rasterize(&grid, &options.with_color_mode(ColorMode::Strategy));
+
+        // Assert: 6x4 ピクセル。左上の 2x2 が協力者、右下の 2x2 が壁
+        assert_eq!((cooperation.width, cooperation.height), (6, 4));
+        let at = |raster: &Raster, x: usize, y: usize| raster.pixels[y * raster.width + x];
+        let top_color = 3 + (RenderService::COOPERATION_LEVELS - 1) as u8;
+        assert_eq!(at(&cooperation, 1, 1), top_color);
+        assert_eq!(at(&cooperation, 2, 0), 0);
+        assert_eq!(at(&cooperation, 5, 3), 1);
+        // 対戦していない個体は協力率 0.5
+        assert_eq!(at(&cooperation, 2, 2), 3 + 16);
+        assert_eq!(at(&strategy, 0, 0), 3);
+        assert_eq!(at(&strategy, 3, 3), 3 + StrategyType::TitForTat.id());
+        let palette = RenderService::palette(ColorMode::Cooperation);
+        assert_eq!(palette.len(), (3 + RenderService::COOPERATION_LEVELS) * 3);
+        assert_eq!(&palette[top_color as usize * 3..][..3], &COOPERATOR);
+    }
+
+    #[test]
+    fn test_png_and_gif_encoding() {
+        // Arrange
+        let grid = grid_with_two_agents();
+        let options = RenderOptions::new().with_color_mode(ColorMode::Strategy);
+
+        // Act
+        let png = RenderService::grid_to_png(&grid, &options).unwrap();
+        let mut recorder = GifRecorder::new(Vec::new(), 3, 2, options).unwrap();
+        recorder.record(&grid).unwrap();
+        recorder.record(&grid).unwrap();
+        let frames = recorder.frame_count();
+        let gif = recorder.finish().unwrap();
+
+        // Assert
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(frames, 2);
+        assert!(GifRecorder::new(Vec::new(), 3, 2, options)
+            .unwrap()
+            .record(&Grid::new(4, 2))
+            .is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use super::{ColorMode, BACKGROUND, OBSTACLE};
+use crate::application::analysis::SpatialAnalysisService;
+use crate::domain::agent::Position;
+use crate::domain::grid::Grid;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    pub color_mode: ColorMode,
+    // 1 セルの一辺（SVG の単位）
+    pub cell_size: usize,
+    // 協力者のクラスタ（SpatialAnalysisService::cooperator_clusters）の外周を線で重ねる
+    pub cluster_outlines: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            color_mode: ColorMode::Cooperation,
+            cell_size: 10,
+            cluster_outlines: false,
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    pub fn with_cluster_outlines(mut self, cluster_outlines: bool) -> Self {
+        self.cluster_outlines = cluster_outlines;
+        self
+    }
+}
+
+const OUTLINE: &str = "#111827";
+
+fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+// 論文にそのまま貼れるよう、外部の資源を参照しない 1 枚の SVG にする
+pub struct SvgRenderer;
+
+impl SvgRenderer {
+    pub fn render(grid: &Grid, options: &SvgOptions) -> String {
+        let cell = options.cell_size.max(1);
+        let width = grid.width() * cell;
+        let height = grid.height() * cell;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{width}\" height=\"{height}\" fill=\"{}\"/>",
+            hex(BACKGROUND)
+        );
+
+        svg.push_str("<g shape-rendering=\"crispEdges\">\n");
+        for position in grid.obstacle_positions() {
+            Self::push_cell(&mut svg, position, cell, OBSTACLE);
+        }
+        for agent in grid.agents_in_position_order() {
+            Self::push_cell(
+                &mut svg,
+                agent.position,
+                cell,
+                options.color_mode.color_of(agent),
+            );
+        }
+        svg.push_str("</g>\n");
+
+        if options.cluster_outlines {
+            let _ = writeln!(
+                svg,
+                "<g fill=\"none\" stroke=\"{OUTLINE}\" stroke-width=\"{}\" stroke-linecap=\"square\">",
+                (cell as f64 / 5.0).max(1.0)
+            );
+            let clusters = SpatialAnalysisService::cooperator_clusters(
+                grid,
+                SpatialAnalysisService::COOPERATOR_THRESHOLD,
+            );
+            for cluster in clusters {
+                let _ = writeln!(svg, "<path d=\"{}\"/>", Self::outline(&cluster, cell));
+            }
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn push_cell(svg: &mut String, position: Position, cell: usize, color: [u8; 3]) {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" fill=\"{}\"/>",
+            position.x * cell,
+            position.y * cell,
+            hex(color)
+        );
+    }
+
+    // クラスタ外のセルと接する辺を 1 本ずつ並べる（トーラスの端をまたぐ部分も盤面の端で閉じる）
+    fn outline(cluster: &[Position], cell: usize) -> String {
+        let members: HashSet<(usize, usize)> = cluster
+            .iter()
+            .map(|position| (position.x, position.y))
+            .collect();
+        let outside = |x: Option<usize>, y: Option<usize>| match (x, y) {
+            (Some(x), Some(y)) => !members.contains(&(x, y)),
+            _ => true,
+        };
+
+        let mut path = String::new();
+        for position in cluster {
+            let (x, y) = (position.x, position.y);
+            let (left, top) = (x * cell, y * cell);
+            if outside(Some(x), y.checked_sub(1)) {
+                let _ = write!(path, "M{left} {top}h{cell}");
+            }
+            if outside(Some(x), Some(y + 1)) {
+                let _ = write!(path, "M{left} {}h{cell}", top + cell);
+            }
+            if outside(x.checked_sub(1), Some(y)) {
+                let _ = write!(path, "M{left} {top}v{cell}");
+            }
+            if outside(Some(x + 1), Some(y)) {
+                let _ = write!(path, "M{} {top}v{cell}", left + cell);
+            }
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{Action, Agent, MovementStrategy, StrategyType};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_svg_draws_agents_obstacles_and_cluster_outlines() {
+        // Arrange: 協力者 2 体が横に並び、右端が壁
+        let mut grid = Grid::new(3, 1).with_obstacles(&[Position::new(2, 0)]);
+        for x in 0..2 {
+            let mut agent = Agent::new(
+                Position::new(x, 0),
+                StrategyType::TitForTat,
+                0.5,
+                MovementStrategy::Settler,
+            );
+            agent
+                .history
+                .add_game(Uuid::new_v4(), Action::Cooperate, Action::Cooperate, 3);
+            grid.add_agent(agent).unwrap();
+        }
+        let options = SvgOptions::new()
+            .with_color_mode(ColorMode::Strategy)
+            .with_cell_size(4);
+
+        // Act
+        let plain = SvgRenderer::render(&grid, &options);
+        let outlined = SvgRenderer::render(&grid, &options.with_cluster_outlines(true));
+
+        // Assert: 背景・壁・2 体の四角形。外周は上下 2 本ずつと左右 1 本ずつ
+        assert!(plain
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"12\" height=\"4\""));
+        assert_eq!(plain.matches("<rect").count(), 4);
+        assert_eq!(plain.matches("fill=\"#3b82f6\"").count(), 2);
+        assert!(plain.contains("fill=\"#374151\""));
+        assert!(!plain.contains("<path"));
+        assert_eq!(outlined.matches("<path").count(), 1);
+        assert!(outlined.contains("d=\"M0 0h4M0 4h4M0 0v4M4 0h4M4 4h4M8 0v4\""));
+        assert!(outlined.trim_end().ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::domain::agent::Agent;
+
+// 1 エージェントあたり [x, y, cooperation_rate, score] を並べる
+pub const AGENT_BUFFER_STRIDE: usize = 4;
+
+// 描画用にエージェントを平坦な f64 配列へ詰める（buffer は再利用して確保を避ける）
+pub fn pack_agents<'a>(agents: impl IntoIterator<Item = &'a Agent>, buffer: &mut Vec<f64>) {
+    buffer.clear();
+    for agent in agents {
+        buffer.extend_from_slice(&[
+            agent.position.x as f64,
+            agent.position.y as f64,
+            agent.cooperation_rate(),
+            agent.score as f64,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::{MovementStrategy, Position, StrategyType};
+
+    #[test]
+    fn test_pack_agents_writes_stride_values_per_agent_and_reuses_buffer() {
+        // Arrange
+        let mut agent = Agent::new(
+            Position::new(3, 7),
+            StrategyType::TitForTat,
+            0.5,
+            MovementStrategy::Settler,
+        );
+        agent.score = 12;
+        let mut buffer = vec![9.0; 32];
+
+        // Act
+        pack_agents([&agent, &agent], &mut buffer);
+
+        // Assert
+        assert_eq!(buffer.len(), 2 * AGENT_BUFFER_STRIDE);
+        assert_eq!(&buffer[..AGENT_BUFFER_STRIDE], &[3.0, 7.0, 0.5, 12.0]);
+        assert!(buffer.capacity() >= 32);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{js_error, WasmErrorPayload, WasmSimulation};
+use crate::application::evolution::{EvolutionStrategyType, MultiObjective};
+use crate::application::simulation::{SimulationBuilder, SimulationConfig};
+use crate::domain::game::PayoffMatrix;
+use crate::domain::grid::{
+    DemeStructure, NeighborhoodType, NetworkConfig, NetworkTopology, ZoneMap,
+};
+use crate::infrastructure::persistence::PersistenceService;
+use wasm_bindgen::prelude::*;
+
+// JS から名前付きで設定を組み立てる。各メソッドは新しいビルダーを返すのでつなげて書ける
+// new WasmConfigBuilder().world(50, 50).population(100).mutation_rate(0.02).build()
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct WasmConfigBuilder {
+    builder: SimulationBuilder,
+}
+
+#[wasm_bindgen]
+impl WasmConfigBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmConfigBuilder {
+        WasmConfigBuilder::default()
+    }
+
+    #[wasm_bindgen]
+    pub fn world(self, width: usize, height: usize) -> WasmConfigBuilder {
+        self.map(|builder| builder.world(width, height))
+    }
+
+    #[wasm_bindgen]
+    pub fn population(self, agent_count: usize) -> WasmConfigBuilder {
+        self.map(|builder| builder.population(agent_count))
+    }
+
+    #[wasm_bindgen]
+    pub fn turns_per_generation(self, turns: u32) -> WasmConfigBuilder {
+        self.map(|builder| builder.turns_per_generation(turns))
+    }
+
+    #[wasm_bindgen]
+    pub fn seed(self, seed: u64) -> WasmConfigBuilder {
+        self.map(|builder| builder.seed(seed))
+    }
+
+    #[wasm_bindgen]
+    pub fn mutation_rate(self, rate: f64) -> WasmConfigBuilder {
+        self.map(|builder| builder.mutation_rate(rate))
+    }
+
+    #[wasm_bindgen]
+    pub fn action_noise(self, noise: f64) -> WasmConfigBuilder {
+        self.map(|builder| builder.configure(|config| config.with_action_noise(noise)))
+    }
+
+    #[wasm_bindgen]
+    pub fn torus(self, enabled: bool) -> WasmConfigBuilder {
+        self.map(|builder| builder.configure(|config| config.with_torus_field(enabled)))
+    }
+
+    #[wasm_bindgen]
+    pub fn neighborhood(self, name: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let neighborhood = NeighborhoodType::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown neighborhood: {name}"
+            )))
+        })?;
+        Ok(self.map(|builder| builder.configure(|config| config.with_neighborhood(neighborhood))))
+    }
+
+    // "erdos_renyi:P" / "watts_strogatz:K:B" / "barabasi_albert:M" のグラフの上で対戦させる
+    #[wasm_bindgen]
+    pub fn network(self, spec: &str, seed: u64) -> Result<WasmConfigBuilder, JsValue> {
+        let topology = NetworkTopology::parse(spec)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        let network = NetworkConfig::new(topology).with_seed(seed);
+        Ok(self.map(|builder| builder.configure(|config| config.with_network(network))))
+    }
+
+    // "score,cooperation,longevity,spatial_influence" の部分集合で Pareto 選択にする
+    #[wasm_bindgen]
+    pub fn objectives(self, objectives: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let multi = MultiObjective::parse(objectives)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        Ok(self.map(|builder| builder.configure(|config| config.with_multi_objective(multi))))
+    }
+
+    // "classic" / "elitist[:N]" / "steady_state" / "island_model[:CxR]" / "adaptive" / "multi_objective"
+    // 方式に必要な設定のうち、ほかのメソッドで指定しなかったものは既定値で補う
+    #[wasm_bindgen]
+    pub fn evolution_strategy(self, name: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let strategy = EvolutionStrategyType::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown evolution strategy: {name}"
+            )))
+        })?;
+        Ok(
+            self.map(|builder| {
+                builder.configure(|config| config.with_evolution_strategy(strategy))
+            }),
+        )
+    }
+
+    #[wasm_bindgen]
+    pub fn islands(
+        self,
+        columns: usize,
+        rows: usize,
+        migration_interval: u32,
+        migrants: usize,
+    ) -> WasmConfigBuilder {
+        let islands = DemeStructure::new(columns, rows)
+            .with_scheduled_migration(migration_interval, migrants);
+        self.map(|builder| builder.configure(|config| config.with_deme_structure(islands)))
+    }
+
+    // [{ name, region: { x, y, width, height }, payoffs?, mutation_rate? }] の JSON で区域ごとの利得表・突然変異率を与える
+    #[wasm_bindgen]
+    pub fn zones(self, json: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let zones: ZoneMap = serde_json::from_str(json)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("zones")))?;
+        Ok(self.map(|builder| builder.configure(|config| config.with_zones(zones))))
+    }
+
+    // 1 行がグリッドの 1 行で、'#' のセルを壁にする
+    #[wasm_bindgen]
+    pub fn obstacle_map(self, map: &str) -> WasmConfigBuilder {
+        let obstacles = PersistenceService::import_obstacle_map(map);
+        self.map(|builder| builder.configure(|config| config.with_obstacles(obstacles)))
+    }
+
+    #[wasm_bindgen]
+    pub fn game_preset(self, name: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let payoffs = PayoffMatrix::preset(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown game preset: {name}"
+            )))
+        })?;
+        let allow = payoffs.validate_prisoners_dilemma().is_err();
+        Ok(self.map(|builder| {
+            builder.configure(|config| {
+                config
+                    .with_payoff_matrix(payoffs)
+                    .with_non_dilemma_payoffs(allow)
+            })
+        }))
+    }
+
+    // 書き出した個体（JSON か export_agents_binary のバイナリ）で始める。個体数は個体の数になる
+    #[wasm_bindgen]
+    pub fn agents(self, bytes: &[u8]) -> Result<WasmConfigBuilder, JsValue> {
+        let imported = PersistenceService::import_auto(bytes)
+            .map_err(|e| js_error(WasmErrorPayload::from(e).with_context("agents")))?;
+        let agents = imported
+            .agents
+            .into_iter()
+            .map(|agent| (agent.id, agent))
+            .collect();
+        Ok(self.map(|builder| builder.with_agents(agents)))
+    }
+
+    // 残りの設定は SimulationConfig の JSON で上書きする（世界の大きさ・個体数は保つ）
+    #[wasm_bindgen]
+    pub fn config_json(self, json: &str) -> Result<WasmConfigBuilder, JsValue> {
+        let config: SimulationConfig = serde_json::from_str(json)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        Ok(self.map(|builder| builder.config(config)))
+    }
+
+    // [{ field, message }] の JSON（問題がなければ "[]"）
+    #[wasm_bindgen]
+    pub fn validate(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.builder.validate()).map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<WasmSimulation, JsValue> {
+        let service = self.builder.build().map_err(js_error)?;
+        Ok(WasmSimulation::from_service(service))
+    }
+}
+
+impl WasmConfigBuilder {
+    fn map(self, update: impl FnOnce(SimulationBuilder) -> SimulationBuilder) -> Self {
+        Self {
+            builder: update(self.builder),
+        }
+    }
+}
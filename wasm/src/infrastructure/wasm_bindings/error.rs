@@ -0,0 +1,138 @@
+use crate::infrastructure::persistence::{error_chain, PersistenceError, SerializationError};
+use serde::Serialize;
+use std::fmt::Display;
+use wasm_bindgen::JsValue;
+
+// JS 側で type を見て分岐できるようにしたエラーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WasmErrorKind {
+    // 不明な名前、範囲外の値、壊れた JSON などの引数の誤り
+    InvalidArgument,
+    NotFound,
+    // 設定や状態が原因でシミュレーションが受け付けなかった
+    Simulation,
+    Serialization,
+    // localStorage などの保存先の失敗
+    Storage,
+}
+
+// WASM の関数が投げるエラー。JS では { message, type, context? } のオブジェクトになる
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WasmErrorPayload {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: WasmErrorKind,
+    // エラーの対象（個体 ID・プリセット名・出力形式など）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl WasmErrorPayload {
+    pub fn new(kind: WasmErrorKind, message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            kind,
+            context: None,
+        }
+    }
+
+    pub fn invalid_argument(message: impl Display) -> Self {
+        Self::new(WasmErrorKind::InvalidArgument, message)
+    }
+
+    pub fn not_found(message: impl Display) -> Self {
+        Self::new(WasmErrorKind::NotFound, message)
+    }
+
+    pub fn serialization(message: impl Display) -> Self {
+        Self::new(WasmErrorKind::Serialization, message)
+    }
+
+    pub fn storage(message: impl Display) -> Self {
+        Self::new(WasmErrorKind::Storage, message)
+    }
+
+    pub fn with_context(mut self, context: impl Display) -> Self {
+        self.context = Some(context.to_string());
+        self
+    }
+}
+
+// アプリケーション層のエラーは文字列なので、シミュレーションが受け付けなかったものとして扱う
+impl From<String> for WasmErrorPayload {
+    fn from(message: String) -> Self {
+        Self::new(WasmErrorKind::Simulation, message)
+    }
+}
+
+impl From<serde_json::Error> for WasmErrorPayload {
+    fn from(error: serde_json::Error) -> Self {
+        Self::serialization(error)
+    }
+}
+
+// 原因までつなげたメッセージにする
+impl From<SerializationError> for WasmErrorPayload {
+    fn from(error: SerializationError) -> Self {
+        Self::serialization(error_chain(&error))
+    }
+}
+
+impl From<PersistenceError> for WasmErrorPayload {
+    fn from(error: PersistenceError) -> Self {
+        let kind = match &error {
+            PersistenceError::UnknownFormat
+            | PersistenceError::InvalidEdge { .. }
+            | PersistenceError::InvalidPresetName(_) => WasmErrorKind::InvalidArgument,
+            PersistenceError::Serialization(_) | PersistenceError::Migration(_) => {
+                WasmErrorKind::Serialization
+            }
+            PersistenceError::Io { .. } | PersistenceError::Storage(_) => WasmErrorKind::Storage,
+        };
+        Self::new(kind, error_chain(&error))
+    }
+}
+
+impl From<WasmErrorPayload> for JsValue {
+    fn from(payload: WasmErrorPayload) -> Self {
+        serde_json::to_string(&payload)
+            .ok()
+            .and_then(|json| js_sys::JSON::parse(&json).ok())
+            .unwrap_or_else(|| JsValue::from_str(&payload.message))
+    }
+}
+
+// map_err にそのまま渡せる形（文字列のエラーは Simulation、永続化のエラーは原因に応じた種類）
+pub(crate) fn js_error(error: impl Into<WasmErrorPayload>) -> JsValue {
+    error.into().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_serializes_message_type_and_context() {
+        // Arrange
+        let not_found = WasmErrorPayload::not_found("Agent not found").with_context("agent-1");
+        let simulation = WasmErrorPayload::from("Invalid configuration".to_string());
+
+        // Act
+        let not_found = serde_json::to_value(&not_found).unwrap();
+        let simulation = serde_json::to_value(&simulation).unwrap();
+
+        // Assert: context は指定したときだけ出力する
+        assert_eq!(
+            not_found,
+            serde_json::json!({
+                "message": "Agent not found",
+                "type": "NotFound",
+                "context": "agent-1"
+            })
+        );
+        assert_eq!(
+            simulation,
+            serde_json::json!({ "message": "Invalid configuration", "type": "Simulation" })
+        );
+    }
+}
@@ -1,7 +1,17 @@
+pub mod buffer;
+pub mod builder;
+pub mod error;
 pub mod simulation;
+pub mod strategy;
+pub mod threads;
 pub mod types;
 pub mod utils;
 
+pub use buffer::*;
+pub use builder::*;
+pub use error::*;
 pub use simulation::*;
+pub use strategy::*;
+pub use threads::*;
 pub use types::*;
 pub use utils::*;
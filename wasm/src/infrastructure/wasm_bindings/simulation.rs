@@ -1,20 +1,199 @@
-use super::{WasmAgent, WasmStatistics};
-use crate::application::simulation::SimulationService;
+use super::{
+    js_error, pack_agents, AgentTraitsEdit, StrategyGenesEdit, WasmAgent, WasmConfigBuilder,
+    WasmErrorPayload, WasmStatistics, AGENT_BUFFER_STRIDE,
+};
+use crate::application::analysis::{ExperimentService, ExperimentSettings};
+use crate::application::evolution::{
+    AdaptiveMutation, CrossoverMethod, FitnessFunction, FitnessSharing, MultiObjective,
+    SelectionMethod,
+};
+use crate::application::simulation::{
+    Archetype, BattleHistoryQuery, BattleHistoryRetention, CancellationToken, ConfigViolation,
+    EnergyConfig, ExtinctionPolicy, InitialPopulationSpec, LifecycleConfig, MortalityCurve,
+    ReproductionModel, SimulationConfig, SimulationService, SteadyStateConfig, TrackingTarget,
+    UpdateRule,
+};
+use crate::domain::agent::{Genotype, MovementStrategy, Position, StrategyType, TagConfig};
+use crate::domain::game::{
+    GameType, PayoffMatrix, PayoffSchedule, PunishmentConfig, ReputationConfig, ReputationRule,
+};
+use crate::domain::grid::{
+    DemeStructure, HeatmapField, MigrationModel, MovementPolicyKind, NeighborhoodType,
+    NetworkConfig, NetworkTopology, PlacementPattern, ResourceConfig, ZoneMap,
+};
+use crate::infrastructure::persistence::{
+    ChunkedWriter, ExportOptions, LocalStoragePresetRepository, PersistenceFormat,
+    PersistenceService, PresetRepository, SerializationService, SimulationPreset,
+};
+use crate::infrastructure::render::{ColorMode, SvgOptions};
+use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub struct WasmSimulation {
     service: SimulationService,
+    agent_buffer: Vec<f64>,
+}
+
+// 実行中の WasmSimulation は借用中で呼び出せないため、コールバック内からはこのハンドルで中断する
+#[wasm_bindgen]
+pub struct WasmCancellationHandle {
+    token: CancellationToken,
+}
+
+#[wasm_bindgen]
+impl WasmCancellationHandle {
+    #[wasm_bindgen]
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+}
+
+impl WasmSimulation {
+    pub(crate) fn from_service(service: SimulationService) -> Self {
+        Self {
+            service,
+            agent_buffer: Vec::new(),
+        }
+    }
+
+    fn parse_agent_id(id: &str) -> Result<Uuid, JsValue> {
+        Uuid::parse_str(id).map_err(|e| {
+            js_error(
+                WasmErrorPayload::invalid_argument(format!("Invalid agent id: {e}"))
+                    .with_context(id),
+            )
+        })
+    }
+
+    // 今の遺伝子型に編集を当て、書き戻す
+    fn edit_genotype<F>(&mut self, id: &str, edit: F) -> Result<(), JsValue>
+    where
+        F: FnOnce(Genotype) -> Result<Genotype, String>,
+    {
+        let id = Self::parse_agent_id(id)?;
+        let current = self
+            .service
+            .get_agent(&id)
+            .map(Genotype::of)
+            .ok_or_else(|| {
+                js_error(WasmErrorPayload::not_found("Agent not found").with_context(id))
+            })?;
+        let genotype =
+            edit(current).map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service
+            .update_agent_genotype(&id, genotype)
+            .map_err(js_error)
+    }
+}
+
+#[wasm_bindgen]
+pub fn agent_buffer_stride() -> usize {
+    AGENT_BUFFER_STRIDE
+}
+
+// SimulationConfig の JSON を実行前に検査し、[{ field, message }] の JSON を返す（問題がなければ "[]"）
+#[wasm_bindgen]
+pub fn validate_config_json(
+    config: &str,
+    width: usize,
+    height: usize,
+    agent_count: usize,
+) -> Result<String, JsValue> {
+    let violations = match serde_json::from_str::<SimulationConfig>(config) {
+        Ok(config) => config.validate_for(width, height, agent_count),
+        Err(e) => vec![ConfigViolation::new("config", e.to_string())],
+    };
+    serde_json::to_string(&violations).map_err(js_error)
+}
+
+// ExperimentSettings の JSON に従ってパラメータ掃引を実行し、組み合わせごとの要約を CSV で返す
+// Web Worker での実行を想定し、1 回の実行ごとに callback(completed, total) を呼ぶ。false を返すと中断する
+#[wasm_bindgen]
+pub fn run_experiment(settings: &str, callback: &js_sys::Function) -> Result<String, JsValue> {
+    let settings: ExperimentSettings = serde_json::from_str(settings).map_err(|e| {
+        js_error(WasmErrorPayload::invalid_argument(format!(
+            "Invalid experiment settings: {e}"
+        )))
+    })?;
+
+    let mut callback_error = None;
+    let rows = ExperimentService::run_with(&settings, |progress| {
+        match callback.call2(
+            &JsValue::NULL,
+            &JsValue::from(progress.completed_runs as u32),
+            &JsValue::from(progress.total_runs as u32),
+        ) {
+            Ok(result) => result.as_bool() != Some(false),
+            Err(error) => {
+                callback_error = Some(error);
+                false
+            }
+        }
+    })
+    .map_err(js_error)?;
+
+    match callback_error {
+        Some(error) => Err(error),
+        None => Ok(SerializationService::experiment_to_csv(
+            &rows,
+            &ExportOptions::default(),
+        )),
+    }
+}
+
+// write で書いた CSV を ChunkedWriter 経由で callback(chunk) に渡す。callback の例外はそのまま返す
+fn export_csv_chunked(
+    chunk_size: usize,
+    callback: &js_sys::Function,
+    write: impl FnOnce(&mut dyn std::io::Write) -> std::io::Result<()>,
+) -> Result<u32, JsValue> {
+    let mut callback_error = None;
+    let mut writer = ChunkedWriter::new(chunk_size, |chunk: &str| {
+        match callback.call1(&JsValue::NULL, &JsValue::from_str(chunk)) {
+            Ok(result) => result.as_bool() != Some(false),
+            Err(error) => {
+                callback_error = Some(error);
+                false
+            }
+        }
+    });
+    let chunks = match write(&mut writer) {
+        Ok(()) => writer.finish(),
+        Err(_) if writer.is_cancelled() => Ok(writer.chunk_count()),
+        Err(error) => Err(error),
+    };
+
+    match callback_error {
+        Some(error) => Err(error),
+        None => chunks
+            .map(|count| count as u32)
+            .map_err(|e| js_error(WasmErrorPayload::serialization(e))),
+    }
 }
 
 #[wasm_bindgen]
 impl WasmSimulation {
     #[wasm_bindgen(constructor)]
     pub fn new(width: usize, height: usize, agent_count: usize) -> Result<WasmSimulation, JsValue> {
-        let service = SimulationService::new(width, height, agent_count)
-            .map_err(|e| JsValue::from_str(&e))?;
+        let service = SimulationService::new(width, height, agent_count).map_err(js_error)?;
+
+        Ok(WasmSimulation::from_service(service))
+    }
+
+    // 同じシードと設定なら同じ世代履歴を再現する
+    #[wasm_bindgen]
+    pub fn with_seed(
+        width: usize,
+        height: usize,
+        agent_count: usize,
+        seed: u64,
+    ) -> Result<WasmSimulation, JsValue> {
+        let config = SimulationConfig::default().with_seed(seed);
+        let service =
+            SimulationService::with_config(width, height, agent_count, config).map_err(js_error)?;
 
-        Ok(WasmSimulation { service })
+        Ok(WasmSimulation::from_service(service))
     }
 
     #[wasm_bindgen]
@@ -23,6 +202,44 @@ impl WasmSimulation {
         WasmStatistics::from(&stats)
     }
 
+    // 各世代の終了後に callback(WasmStatistics) を呼ぶ。callback が false を返すと中断する
+    // 戻り値は完了した世代数
+    #[wasm_bindgen]
+    pub fn run_generations_with_callback(
+        &mut self,
+        generations: u32,
+        callback: &js_sys::Function,
+    ) -> Result<u32, JsValue> {
+        let mut callback_error = None;
+        let completed = self.service.run_generations_with(generations, |stats| {
+            let stats = JsValue::from(WasmStatistics::from(stats));
+            match callback.call1(&JsValue::NULL, &stats) {
+                Ok(result) => result.as_bool() != Some(false),
+                Err(error) => {
+                    callback_error = Some(error);
+                    false
+                }
+            }
+        });
+
+        match callback_error {
+            Some(error) => Err(error),
+            None => Ok(completed),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn cancellation_handle(&self) -> WasmCancellationHandle {
+        WasmCancellationHandle {
+            token: self.service.cancellation_token(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn request_cancel(&self) {
+        self.service.request_cancel();
+    }
+
     #[wasm_bindgen]
     pub fn get_agents(&self) -> Vec<WasmAgent> {
         self.service
@@ -32,12 +249,534 @@ impl WasmSimulation {
             .collect()
     }
 
+    #[wasm_bindgen]
+    pub fn get_agent_count(&self) -> usize {
+        self.service.get_agent_count()
+    }
+
+    // 空きセルに個体を置き、その ID を返す（実行中でもよい）
+    #[wasm_bindgen]
+    pub fn add_agent(
+        &mut self,
+        x: usize,
+        y: usize,
+        strategy_id: u8,
+        movement_strategy_id: u8,
+        mobility: f64,
+    ) -> Result<String, JsValue> {
+        let strategy = StrategyType::from_id(strategy_id).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown strategy id: {strategy_id}"
+            )))
+        })?;
+        let movement_strategy =
+            MovementStrategy::from_id(movement_strategy_id).ok_or_else(|| {
+                js_error(WasmErrorPayload::invalid_argument(format!(
+                    "Unknown movement strategy id: {movement_strategy_id}"
+                )))
+            })?;
+        let genotype = Genotype::new(strategy, movement_strategy, mobility);
+        self.service
+            .add_agent(Position::new(x, y), &genotype)
+            .map(|id| id.to_string())
+            .map_err(js_error)
+    }
+
+    // 該当する個体がいなければ false
+    #[wasm_bindgen]
+    pub fn remove_agent(&mut self, id: &str) -> Result<bool, JsValue> {
+        let id = Self::parse_agent_id(id)?;
+        Ok(self.service.remove_agent(&id).is_some())
+    }
+
+    // {"movement_strategy": ID, "mobility": 0.0-1.0}（省略した項目はそのまま）
+    #[wasm_bindgen]
+    pub fn update_agent_traits(&mut self, id: &str, traits_json: &str) -> Result<(), JsValue> {
+        let edit: AgentTraitsEdit = serde_json::from_str(traits_json).map_err(|e| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Invalid traits: {e}"
+            )))
+        })?;
+        self.edit_genotype(id, |genotype| edit.apply(genotype))
+    }
+
+    // {"strategy": ID} か {"memory": N, "initial": p, "table": [...]}
+    #[wasm_bindgen]
+    pub fn update_agent_strategy(&mut self, id: &str, genes_json: &str) -> Result<(), JsValue> {
+        let edit: StrategyGenesEdit = serde_json::from_str(genes_json).map_err(|e| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Invalid strategy genes: {e}"
+            )))
+        })?;
+        self.edit_genotype(id, |genotype| edit.apply(genotype))
+    }
+
+    // query_json: {"opponent", "from_round", "to_round", "offset", "limit", "aggregate"}（すべて省略可）
+    // aggregate なら相手ごとの対戦数・相互協力率・平均利得を返す
+    #[wasm_bindgen]
+    pub fn get_battle_history(&self, id: &str, query_json: &str) -> Result<String, JsValue> {
+        let id = Self::parse_agent_id(id)?;
+        let query: BattleHistoryQuery = serde_json::from_str(query_json).map_err(|e| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Invalid battle history query: {e}"
+            )))
+        })?;
+        let page = self
+            .service
+            .get_battle_history(&id, &query)
+            .map_err(js_error)?;
+        serde_json::to_string(&page).map_err(js_error)
+    }
+
+    // GlobalBattleStats の JSON（全個体の対戦記録の集計と戦略の組ごとの協力率）
+    #[wasm_bindgen]
+    pub fn get_global_battle_stats(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_global_battle_stats()).map_err(js_error)
+    }
+
+    // 全個体の対戦履歴（位置順、1 回の対戦は両者の側から 1 件ずつ）を "csv" か "json" で返す
+    #[wasm_bindgen]
+    pub fn export_battle_log(&self, format: &str) -> Result<String, JsValue> {
+        let agents = self.service.get_grid().agents_in_position_order();
+        match format {
+            "csv" => Ok(SerializationService::battle_log_to_csv(agents)),
+            "json" => SerializationService::battle_log_to_json(agents).map_err(js_error),
+            _ => Err(js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown export format: {format}"
+            )))),
+        }
+    }
+
+    // 対戦のグラフ（ノードは個体、辺は対戦数と協力率）を "graphml" / "json" / "csv"（辺の表）で返す
+    #[wasm_bindgen]
+    pub fn export_interaction_network(&self, format: &str) -> Result<String, JsValue> {
+        let network = self.service.get_interaction_network();
+        let options = ExportOptions::default();
+        match format {
+            "graphml" => Ok(SerializationService::interaction_network_to_graphml(
+                &network, &options,
+            )),
+            "json" => SerializationService::interaction_network_to_json(&network, &options)
+                .map_err(js_error),
+            "csv" => Ok(SerializationService::interaction_network_to_csv(
+                &network, &options,
+            )),
+            _ => Err(js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown export format: {format}"
+            )))),
+        }
+    }
+
+    // "per_agent:N" / "total:N" / "last_steps:N"（すぐに切り詰める）
+    #[wasm_bindgen]
+    pub fn set_battle_history_retention(&mut self, spec: &str) -> Result<(), JsValue> {
+        let retention = BattleHistoryRetention::parse(spec)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service
+            .set_battle_history_retention(retention)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))
+    }
+
+    // 今回捨てた対戦記録の件数
+    #[wasm_bindgen]
+    pub fn compact_battle_history(&mut self) -> u64 {
+        self.service.compact_battle_history()
+    }
+
+    // {"retained_records", "evicted_records"}
+    #[wasm_bindgen]
+    pub fn get_battle_history_usage(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_battle_history_usage()).map_err(js_error)
+    }
+
+    // クリックしたセルの近傍の個体と、中心の個体との対戦記録を JSON で返す
+    // radius 0 は対戦に使う近傍、1 以上はその距離以内の全個体
+    #[wasm_bindgen]
+    pub fn get_neighbors_of(&self, x: usize, y: usize, radius: u32) -> Result<String, JsValue> {
+        let neighbors = self
+            .service
+            .get_neighbors_of(&Position::new(x, y), radius)
+            .map_err(js_error)?;
+        serde_json::to_string(&neighbors).map_err(js_error)
+    }
+
+    // [x, y, cooperation_rate, score] × エージェント数 を 1 回のコピーで返す
+    #[wasm_bindgen]
+    pub fn get_agent_buffer(&mut self) -> js_sys::Float64Array {
+        self.update_agent_buffer();
+        js_sys::Float64Array::from(&self.agent_buffer[..])
+    }
+
+    // 行優先（y * width + x）のセルごとの値。field は cooperation / score / occupancy / energy / resources / zone
+    // エージェントの値を描く場合、空きセルは NaN
+    #[wasm_bindgen]
+    pub fn get_heatmap(&self, field: &str) -> Result<js_sys::Float32Array, JsValue> {
+        let field = HeatmapField::from_name(field).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown heatmap field: {field}"
+            )))
+        })?;
+        Ok(js_sys::Float32Array::from(
+            &self.service.get_heatmap(field)[..],
+        ))
+    }
+
+    // 行優先（y * width + x）のセルごとの資源量。資源レイヤーがなければ空
+    #[wasm_bindgen]
+    pub fn get_resource_map(&self) -> js_sys::Float64Array {
+        js_sys::Float64Array::from(self.service.get_resource_levels().unwrap_or(&[]))
+    }
+
+    // 資源レイヤーを有効にする（全セル満タンから始める）
+    #[wasm_bindgen]
+    pub fn set_resources(
+        &mut self,
+        capacity: f64,
+        regeneration_rate: f64,
+        consumption: f64,
+    ) -> Result<(), JsValue> {
+        let resources = ResourceConfig::new(capacity, regeneration_rate, consumption)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service.set_resources(Some(resources));
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn disable_resources(&mut self) {
+        self.service.set_resources(None);
+    }
+
+    // コピーなしで描画する場合：更新後に agent_buffer_ptr() と戻り値の長さで
+    // wasm メモリ上に Float64Array のビューを作る（次の更新・メモリ拡張までのみ有効）
+    #[wasm_bindgen]
+    pub fn update_agent_buffer(&mut self) -> usize {
+        pack_agents(
+            self.service.agents_in_position_order(),
+            &mut self.agent_buffer,
+        );
+        self.agent_buffer.len()
+    }
+
+    #[wasm_bindgen]
+    pub fn agent_buffer_ptr(&self) -> *const f64 {
+        self.agent_buffer.as_ptr()
+    }
+
     #[wasm_bindgen]
     pub fn get_statistics(&self) -> WasmStatistics {
         let stats = self.service.get_statistics();
         WasmStatistics::from(&stats)
     }
 
+    // 世代交代直前の統計を世代順に返す（JSON を経由しない型付きオブジェクト）
+    #[wasm_bindgen]
+    pub fn get_generation_history(&self) -> Vec<WasmStatistics> {
+        self.service
+            .get_generation_history()
+            .iter()
+            .map(WasmStatistics::from)
+            .collect()
+    }
+
+    // from..=to 世代の統計（記録済みの範囲だけ）
+    #[wasm_bindgen]
+    pub fn get_history(&self, from_generation: u32, to_generation: u32) -> Vec<WasmStatistics> {
+        self.service
+            .get_history(from_generation, to_generation)
+            .map(WasmStatistics::from)
+            .collect()
+    }
+
+    // 世代履歴全体を "csv" / "json" / "jsonl"（1 行 1 世代）の文字列で返す
+    #[wasm_bindgen]
+    pub fn export_history(&self, format: &str) -> Result<String, JsValue> {
+        let history = self.service.get_generation_history();
+        let options = ExportOptions::default();
+        match format {
+            "csv" => Ok(SerializationService::stats_history_to_csv(
+                history, &options,
+            )),
+            "json" => {
+                SerializationService::stats_history_to_json(history, &options).map_err(js_error)
+            }
+            "jsonl" => Ok(SerializationService::stats_history_to_jsonl(
+                history, &options,
+            )),
+            _ => Err(js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown export format: {format}"
+            )))),
+        }
+    }
+
+    // 世代履歴の CSV を chunk_size バイト前後の行単位の断片に分けて callback(chunk) に渡す
+    // 全体を 1 つの文字列にしないので大きな履歴でもメモリを倍使わない。false を返すと中断し、戻り値は渡した断片の数
+    #[wasm_bindgen]
+    pub fn export_history_csv_chunked(
+        &self,
+        chunk_size: usize,
+        callback: &js_sys::Function,
+    ) -> Result<u32, JsValue> {
+        let history = self.service.get_generation_history();
+        export_csv_chunked(chunk_size, callback, |writer| {
+            SerializationService::write_stats_history_csv(
+                writer,
+                history,
+                &ExportOptions::default(),
+            )
+        })
+    }
+
+    // 個体の CSV を export_history_csv_chunked と同じように断片で渡す
+    #[wasm_bindgen]
+    pub fn export_agents_csv_chunked(
+        &self,
+        chunk_size: usize,
+        callback: &js_sys::Function,
+    ) -> Result<u32, JsValue> {
+        export_csv_chunked(chunk_size, callback, |writer| {
+            self.service
+                .write_agents_csv(writer, &ExportOptions::default())
+        })
+    }
+
+    // 現在の Pareto 前線（JSON、multi_objective が未設定なら null）
+    #[wasm_bindgen]
+    pub fn get_pareto_front(&self) -> Result<String, JsValue> {
+        match self.service.get_pareto_front() {
+            Some(front) => {
+                SerializationService::pareto_front_to_json(&front, &ExportOptions::default())
+                    .map_err(js_error)
+            }
+            None => Ok("null".to_string()),
+        }
+    }
+
+    // 世代ごとの Pareto 前線を "csv" か "json" で返す
+    #[wasm_bindgen]
+    pub fn export_pareto_history(&self, format: &str) -> Result<String, JsValue> {
+        let history = self.service.get_pareto_history();
+        let options = ExportOptions::default();
+        match format {
+            "csv" => Ok(SerializationService::pareto_history_to_csv(
+                history, &options,
+            )),
+            "json" => {
+                SerializationService::pareto_history_to_json(history, &options).map_err(js_error)
+            }
+            _ => Err(js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown export format: {format}"
+            )))),
+        }
+    }
+
+    // "score,cooperation,longevity,spatial_influence" の部分集合。空文字で単一目的の選択に戻す
+    #[wasm_bindgen]
+    pub fn set_objectives(&mut self, objectives: &str) -> Result<(), JsValue> {
+        let multi = match objectives.trim() {
+            "" => None,
+            spec => Some(
+                MultiObjective::parse(spec)
+                    .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?,
+            ),
+        };
+        self.service.set_multi_objective(multi);
+        Ok(())
+    }
+
+    // "top:K"（世代ごとのスコア上位 K 体）/ "ids:ID,ID,..." / "off"。切り替えると記録済みの軌跡は捨てる
+    #[wasm_bindgen]
+    pub fn set_agent_tracking(&mut self, spec: &str) -> Result<(), JsValue> {
+        let target = match spec {
+            "off" => None,
+            _ => Some(
+                TrackingTarget::parse(spec)
+                    .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?,
+            ),
+        };
+        self.service.set_agent_tracking(target);
+        Ok(())
+    }
+
+    // 追跡した個体の世代ごとの状態（JSON 配列、追跡していなければ null）
+    #[wasm_bindgen]
+    pub fn get_agent_trajectory(&self, id: &str) -> Result<String, JsValue> {
+        let id = Self::parse_agent_id(id)?;
+        match self.service.get_agent_trajectory(&id) {
+            Some(trajectory) => {
+                SerializationService::trajectory_to_json(trajectory, &ExportOptions::default())
+                    .map_err(js_error)
+            }
+            None => Ok("null".to_string()),
+        }
+    }
+
+    // 追跡した全個体の軌跡を "csv" か "json" で返す
+    #[wasm_bindgen]
+    pub fn export_trajectories(&self, format: &str) -> Result<String, JsValue> {
+        let empty = Default::default();
+        let trajectories = self
+            .service
+            .get_agent_tracker()
+            .map_or(&empty, |tracker| tracker.trajectories());
+        let options = ExportOptions::default();
+        match format {
+            "csv" => Ok(SerializationService::trajectories_to_csv(
+                trajectories,
+                &options,
+            )),
+            "json" => {
+                SerializationService::trajectories_to_json(trajectories, &options).map_err(js_error)
+            }
+            _ => Err(js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown export format: {format}"
+            )))),
+        }
+    }
+
+    // 履歴に残す世代数の上限。0 で無制限
+    #[wasm_bindgen]
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.service
+            .set_history_capacity(Some(capacity).filter(|capacity| *capacity > 0));
+    }
+
+    // 世代ごとの記録（適応度・多様性・処理時間）に残す世代数（1 以上）
+    #[wasm_bindgen]
+    pub fn set_evolution_history_size(&mut self, size: usize) -> Result<(), JsValue> {
+        self.service
+            .set_evolution_history_size(size)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("size")))
+    }
+
+    // 記録中の世代の要約（JSON、EvolutionSummary）
+    #[wasm_bindgen]
+    pub fn get_evolution_summary(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_evolution_summary()).map_err(js_error)
+    }
+
+    // 記録中の世代の処理時間の合計・平均・直近（JSON、ミリ秒）。対戦・移動・統計・世代交代（選択・交叉・突然変異）の内訳
+    #[wasm_bindgen]
+    pub fn get_performance_report(&self) -> Result<String, JsValue> {
+        SerializationService::performance_report_to_json(
+            &self.service.get_performance_report(),
+            &ExportOptions::default(),
+        )
+        .map_err(js_error)
+    }
+
+    // 不変条件の違反の一覧（JSON の [{ rule, message }]、空なら問題なし）。デバッグ用
+    #[wasm_bindgen]
+    pub fn validate_world(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.validate_world()).map_err(js_error)
+    }
+
+    // メモリ使用量（JSON、MB）。estimated_mb は個体と履歴の概算、linear_memory_mb は線形メモリ全体
+    #[wasm_bindgen]
+    pub fn get_memory_usage(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_memory_usage()).map_err(js_error)
+    }
+
+    // 世代ごとの適応度・多様性・処理時間の CSV（1 行 1 世代）
+    #[wasm_bindgen]
+    pub fn export_metrics_csv(&self) -> String {
+        self.service.export_metrics_csv()
+    }
+
+    // 現在のグリッドの SVG（color_mode は cooperation | strategy、cluster_outlines で協力者クラスタの外周を重ねる）
+    #[wasm_bindgen]
+    pub fn export_svg(&self, color_mode: &str, cluster_outlines: bool) -> Result<String, JsValue> {
+        let color_mode = ColorMode::from_name(color_mode).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown color mode: {color_mode}"
+            )))
+        })?;
+        let options = SvgOptions::new()
+            .with_color_mode(color_mode)
+            .with_cluster_outlines(cluster_outlines);
+        Ok(self.service.export_svg(&options))
+    }
+
+    // 現在の個体の形質ヒストグラム（JSON）
+    #[wasm_bindgen]
+    pub fn get_detailed_statistics(&self, bins: usize) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_detailed_statistics(bins)).map_err(js_error)
+    }
+
+    // set_histogram_bins で有効にした後、世代ごとに記録されたヒストグラムの配列（JSON）
+    #[wasm_bindgen]
+    pub fn get_histogram_history(&self) -> Result<String, JsValue> {
+        SerializationService::histograms_to_json(self.service.get_detailed_history())
+            .map_err(js_error)
+    }
+
+    // 協力者クラスタと Moran's I（JSON）
+    #[wasm_bindgen]
+    pub fn get_spatial_metrics(&self) -> Result<String, JsValue> {
+        SerializationService::spatial_history_to_json(
+            &[self.service.get_spatial_metrics()],
+            &ExportOptions::default(),
+        )
+        .map_err(js_error)
+    }
+
+    // set_spatial_metrics(true) の後、世代ごとに記録された指標の配列（JSON）
+    #[wasm_bindgen]
+    pub fn get_spatial_history(&self) -> Result<String, JsValue> {
+        SerializationService::spatial_history_to_json(
+            self.service.get_spatial_history(),
+            &ExportOptions::default(),
+        )
+        .map_err(js_error)
+    }
+
+    // 次数分布・次数相関・協力率の相関・つなぎ替えた辺の数（JSON、ネットワークがなければ "[]"）
+    #[wasm_bindgen]
+    pub fn get_network_metrics(&self) -> Result<String, JsValue> {
+        let metrics: Vec<_> = self.service.get_network_metrics().into_iter().collect();
+        SerializationService::network_history_to_json(&metrics, &ExportOptions::default())
+            .map_err(js_error)
+    }
+
+    // ネットワーク上で実行しているあいだ世代ごとに記録された指標の配列（JSON）
+    #[wasm_bindgen]
+    pub fn get_network_history(&self) -> Result<String, JsValue> {
+        SerializationService::network_history_to_json(
+            self.service.get_network_history(),
+            &ExportOptions::default(),
+        )
+        .map_err(js_error)
+    }
+
+    // 直前に裏切った隣接個体との辺を切って結び直す確率（0 で無効、ネットワークが必要）
+    #[wasm_bindgen]
+    pub fn set_rewiring_probability(&mut self, probability: f64) -> Result<(), JsValue> {
+        self.service
+            .set_rewiring_probability(probability)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))
+    }
+
+    #[wasm_bindgen]
+    pub fn set_spatial_metrics(&mut self, enabled: bool) {
+        self.service.set_spatial_metrics(enabled);
+    }
+
+    // 0 で記録を止める
+    #[wasm_bindgen]
+    pub fn set_histogram_bins(&mut self, bins: usize) {
+        self.service
+            .set_histogram_bins(Some(bins).filter(|bins| *bins > 0));
+    }
+
+    // 定常状態更新のときだけ記録される、ステップごとの統計
+    #[wasm_bindgen]
+    pub fn get_step_history(&self) -> Vec<WasmStatistics> {
+        self.service
+            .get_step_history()
+            .iter()
+            .map(WasmStatistics::from)
+            .collect()
+    }
+
     #[wasm_bindgen]
     pub fn get_grid_width(&self) -> usize {
         self.service.get_grid_size().0
@@ -58,11 +797,19 @@ impl WasmSimulation {
         self.service.get_turn()
     }
 
+    #[wasm_bindgen]
+    pub fn get_diagnostics(&self) -> Vec<String> {
+        self.service.get_diagnostics().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_diagnostics(&mut self) {
+        self.service.clear_diagnostics();
+    }
+
     #[wasm_bindgen]
     pub fn reset(&mut self, agent_count: usize) -> Result<(), JsValue> {
-        self.service
-            .reset(agent_count)
-            .map_err(|e| JsValue::from_str(&e))
+        self.service.reset(agent_count).map_err(js_error)
     }
 
     #[wasm_bindgen]
@@ -79,4 +826,648 @@ impl WasmSimulation {
     pub fn set_torus_field(&mut self, enabled: bool) {
         self.service.set_torus_field(enabled);
     }
+
+    #[wasm_bindgen]
+    pub fn set_deme_structure(&mut self, columns: usize, rows: usize, migration_rate: f64) {
+        let demes = DemeStructure::new(columns, rows).with_migration_rate(migration_rate);
+        self.service.set_deme_structure(Some(demes));
+    }
+
+    // columns × rows の島に分け、migration_interval 世代ごとに各島の上位 migrants 体を次の島へ送る
+    #[wasm_bindgen]
+    pub fn set_island_model(
+        &mut self,
+        columns: usize,
+        rows: usize,
+        migration_interval: u32,
+        migrants: usize,
+    ) {
+        let islands = DemeStructure::new(columns, rows)
+            .with_scheduled_migration(migration_interval, migrants);
+        self.service.set_deme_structure(Some(islands));
+    }
+
+    // 島ごとの個体数・平均協力率・平均スコア（JSON 配列、島がなければ "[]"）
+    #[wasm_bindgen]
+    pub fn get_island_statistics(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_statistics().island_statistics).map_err(js_error)
+    }
+
+    // タグごとの個体数・平均協力率・平均の内集団びいき（JSON 配列、タグがなければ "[]"）
+    #[wasm_bindgen]
+    pub fn get_tag_statistics(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.get_statistics().tag_statistics).map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_deme_structure(&mut self) {
+        self.service.set_deme_structure(None);
+    }
+
+    // "moore" / "von_neumann" / "radius:N" / "hex"（六角格子 6 近傍）
+    #[wasm_bindgen]
+    pub fn set_neighborhood(&mut self, name: &str) -> Result<(), JsValue> {
+        let neighborhood = NeighborhoodType::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown neighborhood: {name}"
+            )))
+        })?;
+        self.service.set_neighborhood(neighborhood);
+        Ok(())
+    }
+
+    // セルをノードとするグラフの上で対戦させる（すぐに反映）
+    // spec は "erdos_renyi:P" / "watts_strogatz:K:B" / "barabasi_albert:M"、同じ seed なら同じグラフ
+    #[wasm_bindgen]
+    pub fn set_network(&mut self, spec: &str, seed: u64) -> Result<(), JsValue> {
+        let topology = NetworkTopology::parse(spec)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.apply_network(NetworkConfig::new(topology).with_seed(seed))
+    }
+
+    // 1 行 1 辺 "a b" の辺リストを読み込む（ノード i はセル (i % width, i / width)）
+    #[wasm_bindgen]
+    pub fn import_network_edges(&mut self, text: &str) -> Result<(), JsValue> {
+        let edges = PersistenceService::import_edge_list(text).map_err(js_error)?;
+        self.apply_network(NetworkConfig::new(NetworkTopology::EdgeList(edges)))
+    }
+
+    fn apply_network(&mut self, network: NetworkConfig) -> Result<(), JsValue> {
+        self.service
+            .set_network(Some(network))
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("network")))
+    }
+
+    // 1 行 1 辺のテキスト（ネットワークがなければ空文字列）
+    #[wasm_bindgen]
+    pub fn export_network_edges(&self) -> String {
+        self.service
+            .get_network()
+            .map(PersistenceService::export_edge_list)
+            .unwrap_or_default()
+    }
+
+    // 各ノードの次数（ノード順、ネットワークがなければ空配列）
+    #[wasm_bindgen]
+    pub fn get_network_degrees(&self) -> Vec<u32> {
+        self.service
+            .get_network()
+            .map(|network| {
+                (0..network.node_count())
+                    .map(|node| network.degree(node) as u32)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // グリッドの近傍に戻す
+    #[wasm_bindgen]
+    pub fn clear_network(&mut self) {
+        self.service
+            .set_network(None)
+            .expect("clearing the network is always valid");
+    }
+
+    // 戦略 ID ごとの初期割合（register_custom_strategy で得た ID も使える）。次の reset から反映
+    // 空配列を渡すと全形質ランダムに戻る
+    #[wasm_bindgen]
+    pub fn set_initial_strategy_mix(
+        &mut self,
+        strategy_ids: Vec<u8>,
+        proportions: Vec<f64>,
+    ) -> Result<(), JsValue> {
+        if strategy_ids.len() != proportions.len() {
+            return Err(js_error(WasmErrorPayload::invalid_argument(
+                "strategy_ids and proportions must have the same length",
+            )));
+        }
+        if strategy_ids.is_empty() {
+            self.service.set_initial_population(None);
+            return Ok(());
+        }
+
+        let mut spec = InitialPopulationSpec::new();
+        for (id, proportion) in strategy_ids.into_iter().zip(proportions) {
+            let strategy = StrategyType::from_id(id).ok_or_else(|| {
+                js_error(WasmErrorPayload::invalid_argument(format!(
+                    "Unknown strategy id: {id}"
+                )))
+            })?;
+            spec = spec.with_archetype(Archetype::strategy(strategy, proportion));
+        }
+        spec.validate()
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service.set_initial_population(Some(spec));
+        Ok(())
+    }
+
+    // "uniform" / "clustered:N" / "ring" / "center_blob" / "checkerboard"（次の reset から反映）
+    #[wasm_bindgen]
+    pub fn set_placement_pattern(&mut self, name: &str) -> Result<(), JsValue> {
+        let placement = PlacementPattern::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown placement pattern: {name}"
+            )))
+        })?;
+        self.service.set_placement(placement);
+        Ok(())
+    }
+
+    // 0 で組み込み戦略、1 / 2 で memory-one / memory-two の行動表を進化させる（次の reset から反映）
+    #[wasm_bindgen]
+    pub fn set_memory_genome(&mut self, memory: usize) {
+        self.service
+            .set_memory_genome((memory > 0).then_some(memory));
+    }
+
+    // 対戦の行動を観察して公開の評判を更新する（すぐに反映）。rule は "image_scoring" / "standing"
+    // 各自の行動は observation_probability の確率で観察され、評判は毎ステップ decay の割合で 0 に近づく
+    #[wasm_bindgen]
+    pub fn set_reputation(
+        &mut self,
+        rule: &str,
+        observation_probability: f64,
+        decay: f64,
+    ) -> Result<(), JsValue> {
+        let rule = ReputationRule::from_name(rule).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown reputation rule: {rule}"
+            )))
+        })?;
+        let reputation = ReputationConfig {
+            rule,
+            observation_probability,
+            decay,
+        };
+        self.service
+            .set_reputation(Some(reputation))
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("reputation")))
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_reputation(&mut self) -> Result<(), JsValue> {
+        self.service.set_reputation(None).map_err(js_error)
+    }
+
+    // 公開の評判（まだ観察されていない個体は 0）
+    #[wasm_bindgen]
+    pub fn get_reputation(&self, id: &str) -> Result<f64, JsValue> {
+        let id = Self::parse_agent_id(id)?;
+        Ok(self.service.get_reputation_board().get(&id))
+    }
+
+    // 対戦後の罰。裏切られた個体が攻撃性の確率で cost を払い、相手に fine を科す（攻撃性は次の reset で配る）
+    #[wasm_bindgen]
+    pub fn set_punishment(&mut self, cost: i32, fine: i32) -> Result<(), JsValue> {
+        self.service
+            .set_punishment(Some(PunishmentConfig::new(cost, fine)))
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("punishment")))
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_punishment(&mut self) -> Result<(), JsValue> {
+        self.service.set_punishment(None).map_err(js_error)
+    }
+
+    // 遺伝するタグの種類数と、子ごとにタグを引き直す確率。0 種類でタグなし（次の reset から反映）
+    #[wasm_bindgen]
+    pub fn set_tags(&mut self, tag_count: u8, mutation_rate: f64) -> Result<(), JsValue> {
+        let tags = (tag_count > 0).then_some(TagConfig {
+            tag_count,
+            mutation_rate,
+        });
+        self.service
+            .set_tags(tags)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("tags")))
+    }
+
+    // "prisoners_dilemma" / "snowdrift" / "hawk_dove" / "stag_hunt"（すぐに反映）
+    #[wasm_bindgen]
+    pub fn set_game_preset(&mut self, name: &str) -> Result<(), JsValue> {
+        self.service.set_game_preset(name).map_err(js_error)
+    }
+
+    // T > R > P > S を満たさない表は allow_non_dilemma が true のときだけ受け付ける（警告は診断情報に残る）
+    #[wasm_bindgen]
+    pub fn set_payoff_matrix(
+        &mut self,
+        temptation: i32,
+        reward: i32,
+        punishment: i32,
+        sucker: i32,
+        allow_non_dilemma: bool,
+    ) -> Result<(), JsValue> {
+        self.service
+            .set_payoff_matrix(
+                PayoffMatrix::new(temptation, reward, punishment, sucker),
+                allow_non_dilemma,
+            )
+            .map_err(|e| {
+                js_error(WasmErrorPayload::invalid_argument(e).with_context("payoff_matrix"))
+            })
+    }
+
+    // set_payoff_matrix で設定した基本の利得表（JSON: { temptation, reward, punishment, sucker, allow_non_dilemma }）
+    // 利得の予定を設定している場合、今の世代で使われている表は get_current_payoffs で読む
+    #[wasm_bindgen]
+    pub fn get_payoff_matrix(&self) -> Result<String, JsValue> {
+        let config = self.service.get_config();
+        let payoffs = config.payoff_matrix;
+        serde_json::to_string(&serde_json::json!({
+            "temptation": payoffs.temptation,
+            "reward": payoffs.reward,
+            "punishment": payoffs.punishment,
+            "sucker": payoffs.sucker,
+            "allow_non_dilemma": config.allow_non_dilemma_payoffs,
+        }))
+        .map_err(js_error)
+    }
+
+    // セル (x, y) に壁を置く・取り除く。壁を置いたセルにいた個体は取り除かれる
+    #[wasm_bindgen]
+    pub fn set_obstacle(&mut self, x: usize, y: usize, blocked: bool) -> Result<(), JsValue> {
+        self.service
+            .set_obstacle(Position::new(x, y), blocked)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("obstacle")))
+    }
+
+    // 壁のセルの JSON 配列 [{ x, y }]（行優先）
+    #[wasm_bindgen]
+    pub fn get_obstacles(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self.service.get_obstacles()).map_err(js_error)
+    }
+
+    // 全個体に共通の移動方針（次のステップから反映）。各個体は自分の mobility の確率で動く
+    // "stay" / "random_walk" / "gradient" / "flee_defectors" / "success_driven" / "success_driven:R"
+    #[wasm_bindgen]
+    pub fn set_movement_policy(&mut self, name: &str) -> Result<(), JsValue> {
+        let policy = MovementPolicyKind::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown movement policy: {name}"
+            )))
+        })?;
+        self.service.set_movement_policy(Some(policy)).map_err(|e| {
+            js_error(WasmErrorPayload::invalid_argument(e).with_context("movement_policy"))
+        })
+    }
+
+    // 個体ごとの movement_strategy に戻す
+    #[wasm_bindgen]
+    pub fn clear_movement_policy(&mut self) -> Result<(), JsValue> {
+        self.service.set_movement_policy(None).map_err(js_error)
+    }
+
+    // 対戦の後、戦略の更新の前の移住（次のステップから反映）
+    // "none" / "success_driven"（範囲 5） / "success_driven:R"
+    #[wasm_bindgen]
+    pub fn set_migration_model(&mut self, name: &str) -> Result<(), JsValue> {
+        let model = MigrationModel::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown migration model: {name}"
+            )))
+        })?;
+        self.service.set_migration_model(model).map_err(|e| {
+            js_error(WasmErrorPayload::invalid_argument(e).with_context("migration_model"))
+        })
+    }
+
+    // 区域の JSON（WasmConfigBuilder.zones と同じ形）。区域の地図は get_heatmap("zone") で描ける
+    #[wasm_bindgen]
+    pub fn set_zones(&mut self, json: &str) -> Result<(), JsValue> {
+        serde_json::from_str::<ZoneMap>(json)
+            .map_err(|e| e.to_string())
+            .and_then(|zones| self.service.set_zones(Some(zones)))
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("zones")))
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_zones(&mut self) -> Result<(), JsValue> {
+        self.service.set_zones(None).map_err(js_error)
+    }
+
+    // 区域の定義の JSON 配列（区域がなければ "[]"）。get_heatmap("zone") の値はこの配列の添字
+    #[wasm_bindgen]
+    pub fn get_zones(&self) -> Result<String, JsValue> {
+        let zones = self.service.get_config().zones.clone().unwrap_or_default();
+        serde_json::to_string(&zones).map_err(js_error)
+    }
+
+    // "500=snowdrift" や "1000~8,3,1,0;1500=pd" のように世代ごとの利得表の変化を予定する（すぐに反映）
+    #[wasm_bindgen]
+    pub fn set_payoff_schedule(&mut self, spec: &str) -> Result<(), JsValue> {
+        PayoffSchedule::parse(spec)
+            .and_then(|schedule| self.service.set_payoff_schedule(Some(schedule)))
+            .map_err(|e| {
+                js_error(WasmErrorPayload::invalid_argument(e).with_context("payoff_schedule"))
+            })
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_payoff_schedule(&mut self) -> Result<(), JsValue> {
+        self.service.set_payoff_schedule(None).map_err(js_error)
+    }
+
+    // 今の世代で使われている利得表（JSON: { temptation, reward, punishment, sucker }）
+    #[wasm_bindgen]
+    pub fn get_current_payoffs(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.service.current_payoffs()).map_err(js_error)
+    }
+
+    // エネルギーが尽きると死亡し、閾値を超えると近傍に子を産む（世代ごとの一斉交代はしない）
+    #[wasm_bindgen]
+    pub fn set_energy_model(
+        &mut self,
+        initial_energy: f64,
+        payoff_to_energy: f64,
+        step_cost: f64,
+        move_cost: f64,
+        reproduction_threshold: f64,
+    ) -> Result<(), JsValue> {
+        let energy = EnergyConfig {
+            initial_energy,
+            payoff_to_energy,
+            step_cost,
+            move_cost,
+            reproduction_threshold,
+        };
+        energy
+            .validate()
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service
+            .set_reproduction(ReproductionModel::Energy(energy))
+            .map_err(js_error)
+    }
+
+    // 寿命の規則（すぐに反映）。max_age 0、mortality ""、下限 NaN は未設定として扱う
+    // mortality は "constant:P" / "gompertz:BASE:GROWTH"
+    #[wasm_bindgen]
+    pub fn set_lifecycle(
+        &mut self,
+        max_age: u32,
+        mortality: &str,
+        min_score: f64,
+        min_energy: f64,
+    ) -> Result<(), JsValue> {
+        let lifecycle = LifecycleConfig {
+            max_age: (max_age > 0).then_some(max_age),
+            mortality: match mortality.trim() {
+                "" => None,
+                spec => Some(
+                    MortalityCurve::parse(spec)
+                        .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?,
+                ),
+            },
+            min_score: (!min_score.is_nan()).then_some(min_score as i32),
+            min_energy: (!min_energy.is_nan()).then_some(min_energy),
+        };
+        self.service
+            .set_lifecycle(Some(lifecycle))
+            .map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn disable_lifecycle(&mut self) {
+        self.service
+            .set_lifecycle(None)
+            .expect("disabling the lifecycle is always valid");
+    }
+
+    // "stop" / "continue" / "reseed_random:N" / "reseed_elite:N"（すぐに反映）
+    #[wasm_bindgen]
+    pub fn set_extinction_policy(&mut self, spec: &str) -> Result<(), JsValue> {
+        let policy = ExtinctionPolicy::parse(spec)
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?;
+        self.service.set_extinction_policy(policy).map_err(js_error)
+    }
+
+    // JSON: [{ generation, turn, action, reseeded }]
+    #[wasm_bindgen]
+    pub fn get_extinction_events(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self.service.get_extinction_events()).map_err(js_error)
+    }
+
+    // 全滅時の方針 "stop" で止まっていれば true（reset で再開）
+    #[wasm_bindgen]
+    pub fn is_stopped(&self) -> bool {
+        self.service.is_stopped()
+    }
+
+    // rule は "imitation" / "death_birth" / "fermi" / "fermi:K"。毎ステップ updates_per_step 体を近傍との比較で更新する
+    #[wasm_bindgen]
+    pub fn set_steady_state_model(
+        &mut self,
+        rule: &str,
+        updates_per_step: usize,
+    ) -> Result<(), JsValue> {
+        let rule = UpdateRule::from_name(rule).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown update rule: {rule}"
+            )))
+        })?;
+        self.service
+            .set_reproduction(ReproductionModel::SteadyState(SteadyStateConfig {
+                rule,
+                updates_per_step,
+            }))
+            .map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_generational_model(&mut self) {
+        // 世代交代型はエネルギーを使わないので失敗しない
+        let _ = self
+            .service
+            .set_reproduction(ReproductionModel::Generational);
+    }
+
+    // "pd" / "public_goods" / "public_goods:R"（R は増幅率）
+    #[wasm_bindgen]
+    pub fn set_game_type(&mut self, name: &str) -> Result<(), JsValue> {
+        let game_type = GameType::from_name(name).ok_or_else(|| {
+            js_error(WasmErrorPayload::invalid_argument(format!(
+                "Unknown game type: {name}"
+            )))
+        })?;
+        self.service.set_game_type(game_type);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_mutation_rate(&mut self, rate: f64) {
+        self.service.set_mutation_rate(rate);
+    }
+
+    // "diversity:THRESHOLD:BOOST" / "stagnation:GENERATIONS:BOOST" / "off"
+    #[wasm_bindgen]
+    pub fn set_adaptive_mutation(&mut self, spec: &str) -> Result<(), JsValue> {
+        let adaptive = match spec {
+            "off" => None,
+            _ => Some(
+                AdaptiveMutation::parse(spec)
+                    .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e)))?,
+            ),
+        };
+        self.service.set_adaptive_mutation(adaptive);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_action_noise(&mut self, noise: f64) {
+        self.service.set_action_noise(noise);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_score_normalization(&mut self, enabled: bool) {
+        self.service.set_score_normalization(enabled);
+    }
+
+    // 親選択の方式（次の世代交代から反映）。"roulette" / "complexity-penalty" / "normalized" / "sus"
+    // / "boltzmann" / "boltzmann:T:RATE[:MIN]"（初期温度・世代ごとの冷却率・下限温度）
+    #[wasm_bindgen]
+    pub fn set_selection(&mut self, spec: &str) -> Result<(), JsValue> {
+        SelectionMethod::parse(spec)
+            .and_then(|method| self.service.set_selection(method))
+            .map_err(|e| js_error(WasmErrorPayload::invalid_argument(e).with_context("selection")))
+    }
+
+    // 適応度共有（次の世代交代から反映）。"RADIUS" / "RADIUS:ALPHA"（形質の距離は 0〜1）
+    // ニッチの数は統計の niche_count に入る
+    #[wasm_bindgen]
+    pub fn set_fitness_sharing(&mut self, spec: &str) -> Result<(), JsValue> {
+        FitnessSharing::parse(spec)
+            .and_then(|sharing| self.service.set_fitness_sharing(Some(sharing)))
+            .map_err(|e| {
+                js_error(WasmErrorPayload::invalid_argument(e).with_context("fitness_sharing"))
+            })
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_fitness_sharing(&mut self) -> Result<(), JsValue> {
+        self.service.set_fitness_sharing(None).map_err(js_error)
+    }
+
+    // 移動性・内集団びいき・攻撃性の交叉（次の世代交代・繁殖から反映）
+    // "average" / "sbx" / "sbx:ETA"（分布指数） / "blx" / "blx:ALPHA"
+    #[wasm_bindgen]
+    pub fn set_crossover_method(&mut self, spec: &str) -> Result<(), JsValue> {
+        CrossoverMethod::parse(spec)
+            .and_then(|method| self.service.set_crossover_method(method))
+            .map_err(|e| {
+                js_error(WasmErrorPayload::invalid_argument(e).with_context("crossover_method"))
+            })
+    }
+
+    // 親選択・エリート・平均適応度に使う適応度（次の世代交代から反映）
+    // "raw" / "per_battle" / "energy:W" / "age:RATE" / "weighted:SCORE,COOPERATION,AGGRESSION,MOBILITY"
+    #[wasm_bindgen]
+    pub fn set_fitness_function(&mut self, spec: &str) -> Result<(), JsValue> {
+        FitnessFunction::parse(spec)
+            .and_then(|fitness| self.service.set_fitness_function(Some(fitness)))
+            .map_err(|e| {
+                js_error(WasmErrorPayload::invalid_argument(e).with_context("fitness_function"))
+            })
+    }
+
+    // 重み付き適応度・スコア正規化の設定から決める適応度に戻す
+    #[wasm_bindgen]
+    pub fn clear_fitness_function(&mut self) -> Result<(), JsValue> {
+        self.service.set_fitness_function(None).map_err(js_error)
+    }
+
+    // localStorage に保存できる JSON 文字列としてスナップショットを返す
+    #[wasm_bindgen]
+    pub fn save_snapshot(&self) -> Result<String, JsValue> {
+        let bytes = PersistenceService::export_snapshot(
+            &self.service.save_snapshot(),
+            PersistenceFormat::Json,
+        )
+        .map_err(js_error)?;
+
+        String::from_utf8(bytes).map_err(|e| js_error(WasmErrorPayload::serialization(e)))
+    }
+
+    // 現在の設定を localStorage に名前付きで保存する（同名は上書き）
+    #[wasm_bindgen]
+    pub fn save_preset(&self, name: &str, agent_count: usize) -> Result<(), JsValue> {
+        let preset = SimulationPreset::from_simulation(name, &self.service, agent_count)
+            .map_err(js_error)?;
+        LocalStoragePresetRepository::new()
+            .save(&preset)
+            .map_err(|e| js_error(WasmErrorPayload::from(e).with_context(name)))
+    }
+
+    // SimulationConfig の JSON と書き出した個体（JSON かバイナリ）から始める（移植実験・続きの進化）
+    // 盤面の外や重なったセルの個体は空きセルへ移し、その件数を get_diagnostics に残す
+    #[wasm_bindgen]
+    pub fn initialize_with_agents(
+        width: usize,
+        height: usize,
+        config: &str,
+        agents: &[u8],
+    ) -> Result<WasmSimulation, JsValue> {
+        WasmConfigBuilder::new()
+            .world(width, height)
+            .config_json(config)?
+            .agents(agents)?
+            .build()
+    }
+
+    // 保存したプリセットから新しいシミュレーションを作る
+    #[wasm_bindgen]
+    pub fn load_preset(name: &str) -> Result<WasmSimulation, JsValue> {
+        let preset = LocalStoragePresetRepository::new()
+            .load(name)
+            .map_err(|e| js_error(WasmErrorPayload::from(e).with_context(name)))?
+            .ok_or_else(|| {
+                js_error(WasmErrorPayload::not_found("Preset not found").with_context(name))
+            })?;
+        let service = preset.build().map_err(js_error)?;
+
+        Ok(WasmSimulation::from_service(service))
+    }
+
+    #[wasm_bindgen]
+    pub fn list_presets() -> Result<Vec<String>, JsValue> {
+        LocalStoragePresetRepository::new().list().map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn delete_preset(name: &str) -> Result<bool, JsValue> {
+        LocalStoragePresetRepository::new()
+            .delete(name)
+            .map_err(|e| js_error(WasmErrorPayload::from(e).with_context(name)))
+    }
+
+    // JSON より小さいバイナリ（ヘッダ付き MessagePack）のスナップショット
+    #[wasm_bindgen]
+    pub fn save_snapshot_binary(&self) -> Result<Vec<u8>, JsValue> {
+        PersistenceService::export_snapshot(
+            &self.service.save_snapshot(),
+            PersistenceFormat::MessagePack,
+        )
+        .map_err(js_error)
+    }
+
+    // JSON とバイナリのどちらのスナップショットも受け付ける
+    #[wasm_bindgen]
+    pub fn restore_snapshot_binary(bytes: &[u8]) -> Result<WasmSimulation, JsValue> {
+        let snapshot = PersistenceService::import_snapshot(bytes).map_err(js_error)?;
+        let service = SimulationService::restore_snapshot(snapshot).map_err(js_error)?;
+
+        Ok(WasmSimulation::from_service(service))
+    }
+
+    #[wasm_bindgen]
+    pub fn export_agents_binary(&self) -> Result<Vec<u8>, JsValue> {
+        SerializationService::agents_to_binary(&self.service.get_agents()).map_err(js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn restore_snapshot(snapshot: &str) -> Result<WasmSimulation, JsValue> {
+        let snapshot =
+            PersistenceService::import_snapshot(snapshot.as_bytes()).map_err(js_error)?;
+        let service = SimulationService::restore_snapshot(snapshot).map_err(js_error)?;
+
+        Ok(WasmSimulation::from_service(service))
+    }
 }
@@ -0,0 +1,64 @@
+use super::js_error;
+use crate::domain::agent::{
+    Action, DecisionContext, DecisionStrategy, StrategyRegistry, StrategyType,
+};
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+// JS の関数で行動を決める戦略
+// decide(lastOpponentAction, lastMyAction, lastPayoff) が true を返すと協力、それ以外は裏切り
+// 行動は 0 = 協力 / 1 = 裏切り、初回は undefined
+struct JsDecisionStrategy {
+    name: String,
+    decide: js_sys::Function,
+}
+
+// wasm32 はシングルスレッドで、JS の関数が別スレッドから呼ばれることはない
+unsafe impl Send for JsDecisionStrategy {}
+unsafe impl Sync for JsDecisionStrategy {}
+
+impl DecisionStrategy for JsDecisionStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    // 例外を投げた場合は協力として扱う
+    fn decide(&self, context: &DecisionContext) -> Action {
+        let action = |action: Option<Action>| match action {
+            Some(Action::Cooperate) => JsValue::from(0),
+            Some(Action::Defect) => JsValue::from(1),
+            None => JsValue::UNDEFINED,
+        };
+        let payoff = context
+            .last_payoff()
+            .map_or(JsValue::UNDEFINED, JsValue::from);
+
+        match self.decide.call3(
+            &JsValue::NULL,
+            &action(context.last_opponent_action()),
+            &action(context.last_my_action()),
+            &payoff,
+        ) {
+            Ok(result) if result.as_bool() == Some(true) => Action::Cooperate,
+            Ok(_) => Action::Defect,
+            Err(_) => Action::Cooperate,
+        }
+    }
+}
+
+// 戦略 ID（WasmAgent.strategy と同じ値）を返す。同じ名前なら置き換える
+#[wasm_bindgen]
+pub fn register_custom_strategy(name: &str, decide: js_sys::Function) -> Result<u8, JsValue> {
+    let strategy = StrategyRegistry::register(Arc::new(JsDecisionStrategy {
+        name: name.to_string(),
+        decide,
+    }))
+    .map_err(js_error)?;
+
+    Ok(strategy.id())
+}
+
+#[wasm_bindgen]
+pub fn strategy_name(strategy_id: u8) -> Option<String> {
+    StrategyType::from_id(strategy_id).map(|strategy| strategy.name())
+}
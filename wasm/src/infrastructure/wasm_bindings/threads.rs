@@ -0,0 +1,69 @@
+use wasm_bindgen::prelude::*;
+
+// このページでスレッドを使えるか（wasm-threads 付きのビルドで、crossOriginIsolated かつ SharedArrayBuffer がある）
+// false なら init_thread_pool を呼ばずにそのまま使う（対戦と適応度の評価は 1 スレッドで進む）
+#[wasm_bindgen]
+pub fn threads_supported() -> bool {
+    if !cfg!(feature = "wasm-threads") {
+        return false;
+    }
+    let global = js_sys::global();
+    let isolated = js_sys::Reflect::get(&global, &JsValue::from_str("crossOriginIsolated"))
+        .map(|value| value.as_bool() == Some(true))
+        .unwrap_or(false);
+    let shared_memory =
+        js_sys::Reflect::has(&global, &JsValue::from_str("SharedArrayBuffer")).unwrap_or(false);
+    isolated && shared_memory
+}
+
+// 対戦と適応度の評価に使うスレッド数（スレッドプールがなければ 1）
+#[wasm_bindgen]
+pub fn thread_count() -> usize {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        1
+    }
+}
+
+// num_threads 個のワーカーでスレッドプールを作り、スレッド数を返す
+// spawn_worker(ptr) はスレッドごとに呼ばれるので、同じモジュールと共有メモリで初期化した Web Worker から
+// run_pool_worker(ptr) を呼ぶ。プールの起動を待つため、メインスレッドではなく Web Worker 内から呼ぶこと
+// 失敗したときはエラーを投げ、以降も 1 スレッドのまま動く
+#[cfg(feature = "wasm-threads")]
+#[wasm_bindgen]
+pub fn init_thread_pool(
+    num_threads: usize,
+    spawn_worker: &js_sys::Function,
+) -> Result<usize, JsValue> {
+    use super::js_error;
+
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .spawn_handler(|thread| {
+            let ptr = Box::into_raw(Box::new(thread)) as usize;
+            spawn_worker
+                .call1(&JsValue::NULL, &JsValue::from(ptr))
+                .map(|_| ())
+                .map_err(|_| {
+                    // ワーカーに渡せなかったスレッドは回収する
+                    drop(unsafe { Box::from_raw(ptr as *mut rayon::ThreadBuilder) });
+                    std::io::Error::other("Failed to spawn a worker")
+                })
+        })
+        .build_global()
+        .map_err(|e| js_error(e.to_string()))?;
+    Ok(rayon::current_num_threads())
+}
+
+// spawn_worker に渡されたスレッドをワーカー上で実行する（プールが終わるまで戻らない）
+#[cfg(feature = "wasm-threads")]
+#[wasm_bindgen]
+pub fn run_pool_worker(ptr: usize) {
+    // ptr は init_thread_pool が Box::into_raw で渡したもので、1 度だけ受け取る
+    let thread = unsafe { Box::from_raw(ptr as *mut rayon::ThreadBuilder) };
+    thread.run();
+}
@@ -1,5 +1,5 @@
 use crate::application::simulation::SimulationStatistics;
-use crate::domain::agent::{Agent, MovementStrategy, StrategyType};
+use crate::domain::agent::{Agent, Genotype, MemoryGenome, MovementStrategy, StrategyType};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -14,6 +14,10 @@ pub struct WasmAgent {
     mobility: f64,
     score: i32,
     cooperation_rate: f64,
+    energy: f64,
+    tag: u8,
+    in_group_bias: f64,
+    aggression: f64,
 }
 
 impl From<&Agent> for WasmAgent {
@@ -22,23 +26,15 @@ impl From<&Agent> for WasmAgent {
             id: agent.id.to_string(),
             x: agent.position.x,
             y: agent.position.y,
-            strategy: match agent.strategy {
-                StrategyType::AllCooperate => 0,
-                StrategyType::AllDefect => 1,
-                StrategyType::TitForTat => 2,
-                StrategyType::Pavlov => 3,
-            },
-            movement_strategy: match agent.movement_strategy {
-                MovementStrategy::Explorer => 0,
-                MovementStrategy::Settler => 1,
-                MovementStrategy::Adaptive => 2,
-                MovementStrategy::Opportunist => 3,
-                MovementStrategy::Social => 4,
-                MovementStrategy::Antisocial => 5,
-            },
+            strategy: agent.strategy.id(),
+            movement_strategy: agent.movement_strategy.id(),
             mobility: agent.mobility,
             score: agent.score,
             cooperation_rate: agent.cooperation_rate(),
+            energy: agent.energy,
+            tag: agent.tag,
+            in_group_bias: agent.in_group_bias,
+            aggression: agent.aggression,
         }
     }
 }
@@ -84,6 +80,26 @@ impl WasmAgent {
     pub fn cooperation_rate(&self) -> f64 {
         self.cooperation_rate
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn in_group_bias(&self) -> f64 {
+        self.in_group_bias
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn aggression(&self) -> f64 {
+        self.aggression
+    }
 }
 
 #[wasm_bindgen]
@@ -95,19 +111,38 @@ pub struct WasmStatistics {
     all_defect_count: usize,
     tit_for_tat_count: usize,
     pavlov_count: usize,
+    grim_trigger_count: usize,
+    generous_tit_for_tat_count: usize,
+    tit_for_two_tats_count: usize,
+    extortion_count: usize,
+    reputation_based_count: usize,
     explorer_count: usize,
     settler_count: usize,
     adaptive_count: usize,
     opportunist_count: usize,
     social_count: usize,
     antisocial_count: usize,
+    seeker_count: usize,
+    // 戦略 ID と個体数の並列配列（利用者定義の戦略を含む国勢調査）
+    strategy_ids: Vec<u8>,
+    strategy_counts: Vec<u32>,
     average_cooperation_rate: f64,
     average_mobility: f64,
     average_score: f64,
+    average_fitness: f64,
+    mutation_rate: f64,
+    births: usize,
+    deaths: usize,
+    punishments: usize,
+    punishment_frequency: f64,
+    niche_count: usize,
+    shannon_index: f64,
+    simpson_index: f64,
 }
 
 impl From<&SimulationStatistics> for WasmStatistics {
     fn from(stats: &SimulationStatistics) -> Self {
+        let census = stats.strategy_census();
         Self {
             generation: stats.generation,
             total_agents: stats.total_agents,
@@ -127,6 +162,26 @@ impl From<&SimulationStatistics> for WasmStatistics {
                 .strategy_counts
                 .get(&StrategyType::Pavlov)
                 .unwrap_or(&0),
+            grim_trigger_count: *stats
+                .strategy_counts
+                .get(&StrategyType::GrimTrigger)
+                .unwrap_or(&0),
+            generous_tit_for_tat_count: *stats
+                .strategy_counts
+                .get(&StrategyType::GenerousTitForTat)
+                .unwrap_or(&0),
+            tit_for_two_tats_count: *stats
+                .strategy_counts
+                .get(&StrategyType::TitForTwoTats)
+                .unwrap_or(&0),
+            extortion_count: *stats
+                .strategy_counts
+                .get(&StrategyType::Extortion)
+                .unwrap_or(&0),
+            reputation_based_count: *stats
+                .strategy_counts
+                .get(&StrategyType::ReputationBased)
+                .unwrap_or(&0),
             explorer_count: *stats.movement_strategy_counts.get("Explorer").unwrap_or(&0),
             settler_count: *stats.movement_strategy_counts.get("Settler").unwrap_or(&0),
             adaptive_count: *stats.movement_strategy_counts.get("Adaptive").unwrap_or(&0),
@@ -139,9 +194,21 @@ impl From<&SimulationStatistics> for WasmStatistics {
                 .movement_strategy_counts
                 .get("Antisocial")
                 .unwrap_or(&0),
+            seeker_count: *stats.movement_strategy_counts.get("Seeker").unwrap_or(&0),
             average_cooperation_rate: stats.average_cooperation_rate,
+            strategy_ids: census.iter().map(|(strategy, _)| strategy.id()).collect(),
+            strategy_counts: census.iter().map(|(_, count)| *count as u32).collect(),
             average_mobility: stats.average_mobility,
             average_score: stats.average_score,
+            average_fitness: stats.average_fitness,
+            mutation_rate: stats.mutation_rate,
+            births: stats.births,
+            deaths: stats.deaths,
+            punishments: stats.punishments,
+            punishment_frequency: stats.punishment_frequency,
+            niche_count: stats.niche_count,
+            shannon_index: stats.shannon_index,
+            simpson_index: stats.simpson_index,
         }
     }
 }
@@ -178,6 +245,31 @@ impl WasmStatistics {
         self.pavlov_count
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn grim_trigger_count(&self) -> usize {
+        self.grim_trigger_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn generous_tit_for_tat_count(&self) -> usize {
+        self.generous_tit_for_tat_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tit_for_two_tats_count(&self) -> usize {
+        self.tit_for_two_tats_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn extortion_count(&self) -> usize {
+        self.extortion_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reputation_based_count(&self) -> usize {
+        self.reputation_based_count
+    }
+
     #[wasm_bindgen(getter)]
     pub fn explorer_count(&self) -> usize {
         self.explorer_count
@@ -208,6 +300,21 @@ impl WasmStatistics {
         self.antisocial_count
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn seeker_count(&self) -> usize {
+        self.seeker_count
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn strategy_ids(&self) -> Vec<u8> {
+        self.strategy_ids.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn strategy_counts(&self) -> Vec<u32> {
+        self.strategy_counts.clone()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn average_cooperation_rate(&self) -> f64 {
         self.average_cooperation_rate
@@ -222,17 +329,145 @@ impl WasmStatistics {
     pub fn average_score(&self) -> f64 {
         self.average_score
     }
+
+    // 設定の適応度の平均（スコアそのものを適応度にしていれば average_score と同じ）
+    #[wasm_bindgen(getter)]
+    pub fn average_fitness(&self) -> f64 {
+        self.average_fitness
+    }
+
+    // この世代を生んだ実効の突然変異率（適応的突然変異では世代ごとに変わる）
+    #[wasm_bindgen(getter)]
+    pub fn mutation_rate(&self) -> f64 {
+        self.mutation_rate
+    }
+
+    // 前の世代の区切りからの出生数・死亡数
+    #[wasm_bindgen(getter)]
+    pub fn births(&self) -> usize {
+        self.births
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn deaths(&self) -> usize {
+        self.deaths
+    }
+
+    // 前の世代の区切りからの対戦後の罰の回数と、裏切られた回数に対する割合
+    #[wasm_bindgen(getter)]
+    pub fn punishments(&self) -> usize {
+        self.punishments
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn punishment_frequency(&self) -> f64 {
+        self.punishment_frequency
+    }
+
+    // 適応度共有を設定したときのニッチの数（設定しなければ 0）
+    #[wasm_bindgen(getter)]
+    pub fn niche_count(&self) -> usize {
+        self.niche_count
+    }
+
+    // 戦略の個体数のシャノンエントロピー（ナット）とジニ・シンプソン指数（1 - Σp²）
+    #[wasm_bindgen(getter)]
+    pub fn shannon_index(&self) -> f64 {
+        self.shannon_index
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn simpson_index(&self) -> f64 {
+        self.simpson_index
+    }
+}
+
+// インスペクタからの形質の編集（update_agent_traits の JSON）。省略した項目は今の値を保つ
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AgentTraitsEdit {
+    movement_strategy: Option<u8>,
+    mobility: Option<f64>,
+}
+
+impl AgentTraitsEdit {
+    pub(crate) fn apply(self, genotype: Genotype) -> Result<Genotype, String> {
+        let movement_strategy = match self.movement_strategy {
+            Some(id) => MovementStrategy::from_id(id)
+                .ok_or_else(|| format!("Unknown movement strategy id: {id}"))?,
+            None => genotype.movement_strategy,
+        };
+        let mobility = self.mobility.unwrap_or(genotype.mobility);
+        genotype.with_movement(movement_strategy, mobility)
+    }
+}
+
+// 戦略遺伝子の編集（update_agent_strategy の JSON）
+// {"strategy": 戦略 ID} か memory-N の行動表 {"memory": N, "initial": p, "table": [...]}
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum StrategyGenesEdit {
+    Builtin {
+        strategy: u8,
+    },
+    Memory {
+        memory: usize,
+        initial: f64,
+        table: Vec<f64>,
+    },
+}
+
+impl StrategyGenesEdit {
+    pub(crate) fn apply(self, genotype: Genotype) -> Result<Genotype, String> {
+        match self {
+            StrategyGenesEdit::Builtin { strategy } => StrategyType::from_id(strategy)
+                .map(|strategy| genotype.with_strategy(strategy))
+                .ok_or_else(|| format!("Unknown strategy id: {strategy}")),
+            StrategyGenesEdit::Memory {
+                memory,
+                initial,
+                table,
+            } => Ok(genotype.with_memory_genome(MemoryGenome::new(memory, initial, table)?)),
+        }
+    }
 }
 
 #[wasm_bindgen]
 pub fn movement_strategy_name(strategy_id: u8) -> String {
-    match strategy_id {
-        0 => "Explorer".to_string(),
-        1 => "Settler".to_string(),
-        2 => "Adaptive".to_string(),
-        3 => "Opportunist".to_string(),
-        4 => "Social".to_string(),
-        5 => "Antisocial".to_string(),
-        _ => "Unknown".to_string(),
+    MovementStrategy::from_id(strategy_id)
+        .map(|strategy| strategy.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edits_parse_json_through_domain_constructors() {
+        // Arrange
+        let genotype = Genotype::new(StrategyType::AllCooperate, MovementStrategy::Settler, 0.2);
+        let parse_traits = |json| serde_json::from_str::<AgentTraitsEdit>(json).unwrap();
+        let parse_genes = |json| serde_json::from_str::<StrategyGenesEdit>(json).unwrap();
+
+        // Act
+        let moved = parse_traits(r#"{"mobility": 0.8}"#).apply(genotype.clone());
+        let too_mobile = parse_traits(r#"{"mobility": 2.0}"#).apply(genotype.clone());
+        let unknown_field = serde_json::from_str::<AgentTraitsEdit>(r#"{"score": 10}"#);
+        let builtin = parse_genes(r#"{"strategy": 1}"#).apply(genotype.clone());
+        let memory = parse_genes(r#"{"memory": 1, "initial": 0.0, "table": [0, 0, 0, 0]}"#)
+            .apply(genotype.clone());
+        let short_table =
+            parse_genes(r#"{"memory": 1, "initial": 1.0, "table": [1, 0]}"#).apply(genotype);
+
+        // Assert
+        let moved = moved.unwrap();
+        assert_eq!(moved.mobility, 0.8);
+        assert_eq!(moved.movement_strategy, MovementStrategy::Settler);
+        assert!(too_mobile.is_err());
+        assert!(unknown_field.is_err());
+        assert_eq!(builtin.unwrap().strategy, StrategyType::from_id(1).unwrap());
+        assert_eq!(memory.unwrap().strategy, StrategyType::AllDefect);
+        assert!(short_table.is_err());
     }
 }
@@ -2,4 +2,9 @@ pub mod application;
 pub mod domain;
 pub mod infrastructure;
 
+// ドメインモデルの正規の定義。CLI・WASM バインディング・永続化はこれらを変換するだけのアダプタ
+pub use application::evolution::{MultiObjective, SelectionMethod, WeightedFitness};
+pub use application::simulation::{SimulationConfig, SimulationService, SimulationStatistics};
+pub use domain::agent::{Agent, Genotype, MovementStrategy, Position, StrategyType};
+
 pub use infrastructure::wasm_bindings::*;
@@ -49,6 +49,36 @@ export const StatisticsPanel: React.FC<StatisticsPanelProps> = ({
       type: 2,
     },
     { color: STRATEGY_COLORS[3], count: statistics.pavlov_count, name: STRATEGY_NAMES[3], type: 3 },
+    {
+      color: STRATEGY_COLORS[4],
+      count: statistics.grim_trigger_count,
+      name: STRATEGY_NAMES[4],
+      type: 4,
+    },
+    {
+      color: STRATEGY_COLORS[5],
+      count: statistics.generous_tit_for_tat_count,
+      name: STRATEGY_NAMES[5],
+      type: 5,
+    },
+    {
+      color: STRATEGY_COLORS[6],
+      count: statistics.tit_for_two_tats_count,
+      name: STRATEGY_NAMES[6],
+      type: 6,
+    },
+    {
+      color: STRATEGY_COLORS[7],
+      count: statistics.extortion_count,
+      name: STRATEGY_NAMES[7],
+      type: 7,
+    },
+    {
+      color: STRATEGY_COLORS[8],
+      count: statistics.reputation_based_count,
+      name: STRATEGY_NAMES[8],
+      type: 8,
+    },
   ];
 
   const movementStrategyData = [
@@ -88,6 +118,12 @@ export const StatisticsPanel: React.FC<StatisticsPanelProps> = ({
       name: MOVEMENT_STRATEGY_NAMES[5],
       type: 5,
     },
+    {
+      color: MOVEMENT_STRATEGY_COLORS[6],
+      count: statistics.seeker_count,
+      name: MOVEMENT_STRATEGY_NAMES[6],
+      type: 6,
+    },
   ];
 
   const formatPercentage = (value: number) => (value * 100).toFixed(1);
@@ -85,6 +85,8 @@ const convertStatsToPlainObject = (wasmStats: WasmStatistics) => {
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       pavlov_count: wasmStats.pavlov_count,
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
+      seeker_count: wasmStats.seeker_count,
+      // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       settler_count: wasmStats.settler_count,
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       social_count: wasmStats.social_count,
@@ -131,6 +133,8 @@ const convertStatsToPlainObject = (wasmStats: WasmStatistics) => {
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       pavlov_count: 0,
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
+      seeker_count: 0,
+      // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       settler_count: 0,
       // biome-ignore lint/style/useNamingConvention: WASM properties use snake_case
       social_count: 0,
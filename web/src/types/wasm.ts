@@ -9,6 +9,11 @@ export interface WasmAgent {
   readonly score: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly cooperation_rate: number;
+  readonly energy: number;
+  readonly tag: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly in_group_bias: number;
+  readonly aggression: number;
 }
 
 export interface WasmStatistics {
@@ -24,6 +29,16 @@ export interface WasmStatistics {
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly pavlov_count: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly grim_trigger_count: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly generous_tit_for_tat_count: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly tit_for_two_tats_count: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly extortion_count: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly reputation_based_count: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly explorer_count: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly settler_count: number;
@@ -36,11 +51,48 @@ export interface WasmStatistics {
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly antisocial_count: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly seeker_count: number;
+  // Parallel arrays: strategy id and agent count, including user-defined strategies
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly strategy_ids: Uint8Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly strategy_counts: Uint32Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly average_cooperation_rate: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly average_mobility: number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   readonly average_score: number;
+  // Mean of the configured fitness function (equals average_score for raw score)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly average_fitness: number;
+  // Effective mutation rate that produced this generation
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly mutation_rate: number;
+  // Births and deaths since the previous generation boundary
+  readonly births: number;
+  readonly deaths: number;
+  // Post-game punishments since the previous generation boundary, and their share of defected-on games
+  readonly punishments: number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly punishment_frequency: number;
+  // Niches found with the fitness-sharing radius (0 unless fitness sharing is set)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly niche_count: number;
+  // Shannon entropy (nats) of the strategy counts
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly shannon_index: number;
+  // Gini-Simpson index of the strategy counts (1 - sum of squared shares)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  readonly simpson_index: number;
+}
+
+// Thrown by every fallible WASM method (plain object, not an Error instance)
+export interface WasmErrorPayload {
+  readonly message: string;
+  readonly type: 'InvalidArgument' | 'NotFound' | 'Simulation' | 'Serialization' | 'Storage';
+  // Subject of the error when there is one (agent id, preset name, ...)
+  readonly context?: string;
 }
 
 export interface WasmSimulation {
@@ -48,8 +100,200 @@ export interface WasmSimulation {
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   get_agents(): WasmAgent[];
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_agent_count(): number;
+  // Places an agent on an empty cell mid-run and returns its id
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  add_agent(x: number, y: number, strategyId: number, movementStrategyId: number, mobility: number): string;
+  // Returns false when no agent has the id
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  remove_agent(id: string): boolean;
+  // maxAge 0, mortality "" and NaN thresholds leave that rule unset
+  // mortality: "constant:P" | "gompertz:BASE:GROWTH"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_lifecycle(maxAge: number, mortality: string, minScore: number, minEnergy: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  disable_lifecycle(): void;
+  // "stop" | "continue" | "reseed_random:N" | "reseed_elite:N" (default continue)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_extinction_policy(spec: string): void;
+  // JSON: [{ generation, turn, action, reseeded }]; action is "Stop" | "Continue" | { ReseedRandom: n } | { ReseedFromElite: n }
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_extinction_events(): string;
+  // True once the "stop" policy has halted the run; reset() resumes
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  is_stopped(): boolean;
+  // "per_agent:N" | "total:N" | "last_steps:N" (default per_agent:10); trims immediately
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_battle_history_retention(spec: string): void;
+  // Trims battle histories now and returns the number of evicted records
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  compact_battle_history(): bigint;
+  // JSON { retained_records, evicted_records }
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_battle_history_usage(): string;
+  // queryJson: { opponent?, from_round?, to_round?, offset?, limit?, aggregate? }
+  // JSON: { mode: "records", total, records: [{ round, opponent_id, my_action, opponent_action, payoff, punished,
+  //          punished_by_opponent }] }
+  // or { mode: "summaries", total, summaries: [{ opponent_id, games, mutual_cooperation_rate, average_payoff }] }
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_battle_history(id: string, queryJson: string): string;
+  // Every agent's retained battle records; format: "csv" | "json" ([{ agent_id, records }])
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_battle_log(format: string): string;
+  // JSON: { total_records, total_games, cooperation_frequency, mutual_cooperation_rate, mutual_defection_rate, average_payoff,
+  //   payoff_distribution: [{ payoff, count }], strategy_pairs: [{ strategy, opponent_strategy, records, cooperation_rate, mutual_cooperation_rate, average_payoff }] }
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_global_battle_stats(): string;
+  // Who-played-whom graph from retained battle records; format: "graphml" | "json" ({ nodes, edges }) | "csv" (edge list)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_interaction_network(format: string): string;
+  // JSON: [{ id, position, strategy, score, cooperation_rate, rounds: [[focal, neighbor]], last_payoff }]
+  // radius 0 uses the game neighborhood, radius >= 1 every agent within that Chebyshev distance
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_neighbors_of(x: number, y: number, radius: number): string;
+  // traitsJson: {"movement_strategy": id, "mobility": 0-1}; omitted fields keep their value
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  update_agent_traits(id: string, traitsJson: string): void;
+  // genesJson: {"strategy": id} or {"memory": n, "initial": p, "table": [...]}
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  update_agent_strategy(id: string, genesJson: string): void;
+  // [x, y, cooperation_rate, score] per agent (agent_buffer_stride() values each)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_agent_buffer(): Float64Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  update_agent_buffer(): number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  agent_buffer_ptr(): number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   get_statistics(): WasmStatistics;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_generation_history(): WasmStatistics[];
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_step_history(): WasmStatistics[];
+  // Statistics for generations from..=to that are still in the history buffer
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_history(from: number, to: number): WasmStatistics[];
+  // format: "csv" | "json" | "jsonl" (one generation per line)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_history(format: string): string;
+  // Streams the history CSV to callback in line-aligned chunks of about chunkSize bytes; return false to stop. Returns the chunk count
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_history_csv_chunked(chunkSize: number, callback: (chunk: string) => boolean | undefined): number;
+  // Streams the agents CSV the same way
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_agents_csv_chunked(chunkSize: number, callback: (chunk: string) => boolean | undefined): number;
+  // Maximum generations kept in each history buffer; 0 keeps everything
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_history_capacity(capacity: number): void;
+  // Generations kept in the per-generation fitness/diversity/timing records (at least 1, default 1000)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_evolution_history_size(size: number): void;
+  // JSON: EvolutionSummary (best fitness, fitness improvement, final fitness/diversity, average ms per generation)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_evolution_summary(): string;
+  // JSON: { generations, total, average, latest } with steps/battles/movement/stats/evolution/selection/crossover/mutation/total ms
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_performance_report(): string;
+  // JSON: [{ rule, message }] invariant violations (traits, occupancy, capacity, payoff symmetry); empty when consistent
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  validate_world(): string;
+  // JSON: { estimated_mb, linear_memory_mb } (linear memory is the whole WASM heap)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_memory_usage(): string;
+  // CSV: one row per recorded generation (fitness min/max/mean/sd, strategy entropy, genetic/phenotypic/spatial/behavioral diversity, timings, memory)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_metrics_csv(): string;
+  // SVG of the current grid; colorMode: 'cooperation' | 'strategy', clusterOutlines overlays cooperator cluster borders
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_svg(colorMode: string, clusterOutlines: boolean): string;
+  // JSON: DetailedStatistics (histograms of cooperation_rate / mobility / score, strategy_counts)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_detailed_statistics(bins: number): string;
+  // JSON: DetailedStatistics[] recorded once per generation while histogram bins are set
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_histogram_history(): string;
+  // JSON: cooperator cluster counts, cluster-size distribution and Moran's I of cooperation rate
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_spatial_metrics(): string;
+  // JSON array recorded once per generation while spatial metrics are enabled
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_spatial_history(): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_spatial_metrics(enabled: boolean): void;
+  // 0 disables recording
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_histogram_bins(bins: number): void;
+  // Comma-separated subset of score,cooperation,longevity,spatial_influence; "" restores single-objective selection
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_objectives(objectives: string): void;
+  // JSON { generation, members: [{ id, values: { objective: value } }] }, or "null" without objectives
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_pareto_front(): string;
+  // "top:K" | "ids:ID,ID,..." | "off"; switching discards recorded trajectories
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_agent_tracking(spec: string): void;
+  // JSON [{ generation, x, y, strategy, score, energy, cooperation_rate }], or "null" when the agent is not tracked
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_agent_trajectory(id: string): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_trajectories(format: "csv" | "json"): string;
+  // "csv" (generation, agent_id, one column per objective) | "json"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_pareto_history(format: string): string;
+  // "diversity:THRESHOLD:BOOST" | "stagnation:GENERATIONS:BOOST" | "off"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_adaptive_mutation(spec: string): void;
+  // Every migrationInterval generations each island sends its top `migrants` agents to the next island
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_island_model(columns: number, rows: number, migrationInterval: number, migrants: number): void;
+  // JSON: [{ island, total_agents, average_cooperation_rate, average_score }] ("[]" without islands)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_island_statistics(): string;
+  // Inherited tags 0..tagCount with a per-child resampling probability; 0 tags disables them (applied on reset)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_tags(tagCount: number, mutationRate: number): void;
+  // JSON: [{ tag, total_agents, average_cooperation_rate, average_in_group_bias }] ("[]" without tags)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_tag_statistics(): string;
+  // After each game a defected-on agent pays cost to fine the defector with probability equal to its aggression
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_punishment(cost: number, fine: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_punishment(): void;
+  // Public reputation board for indirect reciprocity (applied immediately); rule is "image_scoring" | "standing"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_reputation(rule: string, observationProbability: number, decay: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_reputation(): void;
+  // Public reputation of the agent (0 until one of its actions is observed)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_reputation(id: string): number;
+  // Play on a graph whose node i is cell (i % width, i / width) instead of the grid neighborhood
+  // "erdos_renyi:P" | "watts_strogatz:K:B" | "barabasi_albert:M"; the same seed builds the same graph
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_network(spec: string, seed: bigint): void;
+  // One "a b" edge per line; blank lines and # comments are skipped
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  import_network_edges(text: string): void;
+  // One "a b" edge per line ("" without a network)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_network_edges(): string;
+  // Degree of each node in node order (empty without a network)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_network_degrees(): Uint32Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_network(): void;
+  // Probability of cutting a link to a neighbor who defected and relinking to a neighbor's neighbor (needs a network)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_rewiring_probability(probability: number): void;
+  // JSON: [{ generation, edge_count, mean_degree, degree_distribution, degree_assortativity,
+  //          cooperation_assortativity, rewired_links }] ("[]" without a network)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_network_metrics(): string;
+  // Same shape as get_network_metrics, one entry per generation
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_network_history(): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   get_grid_width(): number;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   get_grid_height(): number;
@@ -64,19 +308,214 @@ export interface WasmSimulation {
   set_strategy_complexity_penalty_rate(rate: number): void;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   set_torus_field(enabled: boolean): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_placement_pattern(name: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_memory_genome(memory: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_game_type(name: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_game_preset(name: string): void;
+  // Row-major width x height values; field is cooperation | score | occupancy | energy | resources | zone | obstacle
+  // (NaN for empty cells; zone gives the index into get_zones(), NaN outside every zone; obstacle is 1 on walls)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_heatmap(field: string): Float32Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_resource_map(): Float64Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_resources(capacity: number, regenerationRate: number, consumption: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  disable_resources(): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_energy_model(
+    initialEnergy: number,
+    payoffToEnergy: number,
+    stepCost: number,
+    moveCost: number,
+    reproductionThreshold: number,
+  ): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_generational_model(): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_steady_state_model(rule: string, updatesPerStep: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_payoff_matrix(
+    temptation: number,
+    reward: number,
+    punishment: number,
+    sucker: number,
+    allowNonDilemma: boolean,
+  ): void;
+  // JSON: { temptation, reward, punishment, sucker, allow_non_dilemma } as set by set_payoff_matrix (see get_current_payoffs for schedules)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_payoff_matrix(): string;
+  // Schedule payoff changes by generation, e.g. "500=snowdrift" or "1000~8,3,1,0;1500=pd" (= switches, ~ ramps linearly)
+  // Place (blocked = true) or remove a wall; an agent on a newly blocked cell is removed
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_obstacle(x: number, y: number, blocked: boolean): void;
+  // JSON [{ x, y }] of the wall cells in row-major order
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_obstacles(): string;
+  // Move every agent by one policy, with probability equal to its mobility:
+  // "stay" | "random_walk" | "gradient" | "flee_defectors" | "success_driven" | "success_driven:R"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_movement_policy(name: string): void;
+  // Go back to each agent's own movement strategy
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_movement_policy(): void;
+  // Fitness used for parent selection, elites and average_fitness:
+  // "raw" | "per_battle" | "energy:W" | "age:RATE" | "weighted:SCORE,COOPERATION,AGGRESSION,MOBILITY"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_fitness_function(spec: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_fitness_function(): void;
+  // Parent selection: "roulette" | "complexity-penalty" | "normalized" | "sus" | "boltzmann" |
+  // "boltzmann:T:RATE[:MIN]" (initial temperature, per-generation cooling rate, temperature floor)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_selection(spec: string): void;
+  // Crossover of mobility, in-group bias and aggression: "average" | "sbx" | "sbx:ETA" | "blx" | "blx:ALPHA"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_crossover_method(spec: string): void;
+  // Fitness sharing before parent selection: "RADIUS" | "RADIUS:ALPHA" (trait distance 0-1)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_fitness_sharing(spec: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_fitness_sharing(): void;
+  // Migration before each update: "none" | "success_driven" (range 5) | "success_driven:R"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_migration_model(name: string): void;
+  // Zones with their own payoffs / mutation rate, as JSON [{ name, region: { x, y, width, height }, payoffs?, mutation_rate? }]
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_zones(json: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_zones(): void;
+  // JSON array of the zone definitions ("[]" without zones)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_zones(): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_payoff_schedule(spec: string): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  clear_payoff_schedule(): void;
+  // JSON: { temptation, reward, punishment, sucker } in effect for the current generation
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  get_current_payoffs(): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  set_initial_strategy_mix(strategyIds: Uint8Array, proportions: Float64Array): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  run_generations_with_callback(
+    generations: number,
+    callback: (stats: WasmStatistics) => boolean | undefined
+  ): number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  cancellation_handle(): WasmCancellationHandle;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  request_cancel(): void;
+  // "PD2D" + version byte + MessagePack; restore with WasmSimulation.restore_snapshot_binary
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  save_preset(name: string, agentCount: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  save_snapshot_binary(): Uint8Array;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  export_agents_binary(): Uint8Array;
+  free(): void;
+}
+
+export interface WasmCancellationHandle {
+  cancel(): void;
   free(): void;
 }
 
+// Each method returns a new builder, so calls can be chained
+export interface WasmConfigBuilder {
+  world(width: number, height: number): WasmConfigBuilder;
+  population(agentCount: number): WasmConfigBuilder;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  turns_per_generation(turns: number): WasmConfigBuilder;
+  seed(seed: bigint): WasmConfigBuilder;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  mutation_rate(rate: number): WasmConfigBuilder;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  action_noise(noise: number): WasmConfigBuilder;
+  torus(enabled: boolean): WasmConfigBuilder;
+  neighborhood(name: string): WasmConfigBuilder;
+  // "erdos_renyi:P" | "watts_strogatz:K:B" | "barabasi_albert:M"; the same seed builds the same graph
+  network(spec: string, seed: bigint): WasmConfigBuilder;
+  islands(columns: number, rows: number, migrationInterval: number, migrants: number): WasmConfigBuilder;
+  // Comma-separated subset of score,cooperation,longevity,spatial_influence
+  objectives(objectives: string): WasmConfigBuilder;
+  // "classic" | "elitist[:N]" | "steady_state" | "island_model[:CxR]" | "adaptive" | "multi_objective"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  evolution_strategy(name: string): WasmConfigBuilder;
+  // JSON [{ name, region: { x, y, width, height }, payoffs?, mutation_rate? }]
+  zones(json: string): WasmConfigBuilder;
+  // One line per grid row; '#' marks a wall
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  obstacle_map(map: string): WasmConfigBuilder;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  game_preset(name: string): WasmConfigBuilder;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  config_json(json: string): WasmConfigBuilder;
+  // Start from exported agents (JSON bytes or export_agents_binary output); the population size follows them
+  agents(bytes: Uint8Array): WasmConfigBuilder;
+  // JSON [{ field, message }]
+  validate(): string;
+  build(): WasmSimulation;
+  free(): void;
+}
+
+export interface WasmConfigBuilderConstructor {
+  new (): WasmConfigBuilder;
+}
+
 export interface WasmSimulationConstructor {
   new (width: number, height: number, agentCount: number): WasmSimulation;
+  // Presets are stored in localStorage under "pd2d.preset.<name>"
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  load_preset(name: string): WasmSimulation;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  list_presets(): string[];
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  delete_preset(name: string): boolean;
+  // SimulationConfig JSON plus exported agents (JSON bytes or binary); agents outside the grid are moved
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  initialize_with_agents(width: number, height: number, config: string, agents: Uint8Array): WasmSimulation;
 }
 
 export interface WasmModule {
   // biome-ignore lint/style/useNamingConvention: WASM binding class name from Rust
   WasmSimulation: WasmSimulationConstructor;
+  // biome-ignore lint/style/useNamingConvention: WASM binding class name from Rust
+  WasmConfigBuilder: WasmConfigBuilderConstructor;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   movement_strategy_name(strategyId: number): string;
   // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  agent_buffer_stride(): number;
+  // Returns JSON [{ field, message }] ("[]" when the config is valid)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  validate_config_json(config: string, width: number, height: number, agentCount: number): string;
+  // Runs a parameter sweep from ExperimentSettings JSON and returns the summary CSV;
+  // return false from onProgress to stop early
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  run_experiment(settings: string, onProgress: (completed: number, total: number) => boolean | undefined): string;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  register_custom_strategy(
+    name: string,
+    // 0 = cooperate, 1 = defect, undefined on the first round; return true to cooperate
+    decide: (lastOpponentAction?: number, lastMyAction?: number, lastPayoff?: number) => boolean
+  ): number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  strategy_name(strategyId: number): string | undefined;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  threads_supported(): boolean;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  thread_count(): number;
+  // Only exported from builds with the wasm-threads feature; call from a Web Worker.
+  // spawnWorker(ptr) must start a worker sharing this module and memory that calls run_pool_worker(ptr)
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  init_thread_pool?(numThreads: number, spawnWorker: (ptr: number) => void): number;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
+  run_pool_worker?(ptr: number): void;
+  // biome-ignore lint/style/useNamingConvention: WASM binding uses snake_case from Rust
   set_panic_hook(): void;
   greet(name: string): void;
 }
@@ -87,9 +526,19 @@ export const StrategyType = {
   // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
   AllDefect: 1,
   // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
+  Extortion: 7,
+  // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
+  GenerousTitForTat: 5,
+  // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
+  GrimTrigger: 4,
+  // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
   Pavlov: 3,
   // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
+  ReputationBased: 8,
+  // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
   TitForTat: 2,
+  // biome-ignore lint/style/useNamingConvention: Strategy names match Rust enum variants
+  TitForTwoTats: 6,
 } as const;
 
 export type StrategyType = (typeof StrategyType)[keyof typeof StrategyType];
@@ -99,6 +548,11 @@ export const STRATEGY_NAMES = {
   [StrategyType.AllDefect]: '常に裏切り',
   [StrategyType.TitForTat]: 'しっぺ返し',
   [StrategyType.Pavlov]: 'パブロフ',
+  [StrategyType.GrimTrigger]: 'トリガー',
+  [StrategyType.GenerousTitForTat]: '寛容なしっぺ返し',
+  [StrategyType.TitForTwoTats]: '二度目でしっぺ返し',
+  [StrategyType.Extortion]: '搾取（ZD）',
+  [StrategyType.ReputationBased]: '評判',
 } as const;
 
 export const STRATEGY_COLORS = {
@@ -106,6 +560,11 @@ export const STRATEGY_COLORS = {
   [StrategyType.AllDefect]: '#ef4444', // red
   [StrategyType.TitForTat]: '#3b82f6', // blue
   [StrategyType.Pavlov]: '#f59e0b', // amber
+  [StrategyType.GrimTrigger]: '#7c3aed', // violet
+  [StrategyType.GenerousTitForTat]: '#06b6d4', // cyan
+  [StrategyType.TitForTwoTats]: '#6366f1', // indigo
+  [StrategyType.Extortion]: '#be123c', // rose
+  [StrategyType.ReputationBased]: '#84cc16', // lime
 } as const;
 
 export const MovementStrategyType = {
@@ -118,6 +577,8 @@ export const MovementStrategyType = {
   // biome-ignore lint/style/useNamingConvention: Movement strategy names match Rust enum variants
   Opportunist: 3,
   // biome-ignore lint/style/useNamingConvention: Movement strategy names match Rust enum variants
+  Seeker: 6,
+  // biome-ignore lint/style/useNamingConvention: Movement strategy names match Rust enum variants
   Settler: 1,
   // biome-ignore lint/style/useNamingConvention: Movement strategy names match Rust enum variants
   Social: 4,
@@ -132,6 +593,7 @@ export const MOVEMENT_STRATEGY_NAMES = {
   [MovementStrategyType.Opportunist]: '機会主義者',
   [MovementStrategyType.Social]: '社交的',
   [MovementStrategyType.Antisocial]: '非社交的',
+  [MovementStrategyType.Seeker]: '探索者',
 } as const;
 
 export const MOVEMENT_STRATEGY_COLORS = {
@@ -141,4 +603,5 @@ export const MOVEMENT_STRATEGY_COLORS = {
   [MovementStrategyType.Opportunist]: '#f97316', // orange
   [MovementStrategyType.Social]: '#ec4899', // pink
   [MovementStrategyType.Antisocial]: '#6b7280', // gray
+  [MovementStrategyType.Seeker]: '#eab308', // yellow
 } as const;